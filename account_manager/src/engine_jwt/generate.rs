@@ -0,0 +1,70 @@
+use crate::common::ensure_dir_exists;
+use account_utils::create_with_600_perms;
+use clap::{App, Arg, ArgMatches};
+use execution_layer::auth::{JwtKey, JWT_SECRET_LENGTH};
+use rand::Rng;
+use std::fs;
+use std::path::PathBuf;
+
+pub const CMD: &str = "generate";
+pub const FORCE_FLAG: &str = "force";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Generates a new random secret and writes it to the secret file, readable only by \
+             the current user.",
+        )
+        .arg(
+            Arg::with_name(FORCE_FLAG)
+                .long(FORCE_FLAG)
+                .help(
+                    "Replace the secret if the secret file already exists. A running beacon \
+                     node uses the new secret from its next request to the execution engine.",
+                )
+                .takes_value(false),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches, secret_file: PathBuf) -> Result<(), String> {
+    let rotate = secret_file.exists();
+
+    if rotate && !matches.is_present(FORCE_FLAG) {
+        return Err(format!(
+            "{:?} already exists. Use --{} to replace it.",
+            secret_file, FORCE_FLAG
+        ));
+    }
+
+    if let Some(parent) = secret_file.parent() {
+        ensure_dir_exists(parent)?;
+    }
+
+    let mut bytes = [0; JWT_SECRET_LENGTH];
+    rand::thread_rng().fill(&mut bytes);
+    let secret =
+        JwtKey::from_slice(&bytes).map_err(|e| format!("Unable to create secret: {:?}", e))?;
+
+    // Write the secret to a temporary file which is then moved over the secret file, so that a
+    // running beacon node never reads a partially written secret.
+    let temp_file = secret_file.with_extension("tmp");
+    create_with_600_perms(&temp_file, secret.hex_string().as_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", temp_file, e))?;
+    fs::rename(&temp_file, &secret_file).map_err(|e| {
+        format!(
+            "Unable to move {:?} to {:?}: {:?}",
+            temp_file, secret_file, e
+        )
+    })?;
+
+    println!("Wrote a new secret to {:?}", secret_file);
+
+    if rotate {
+        println!();
+        println!("The previous secret has been replaced. A running beacon node will use the new");
+        println!("secret for its next request. The execution engine must also be given the new");
+        println!("secret, otherwise it will reject the beacon node's requests.");
+    }
+
+    Ok(())
+}
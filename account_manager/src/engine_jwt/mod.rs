@@ -0,0 +1,68 @@
+pub mod generate;
+pub mod show;
+
+use clap::{App, Arg, ArgMatches};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+pub const CMD: &str = "engine-jwt";
+pub const SECRET_FILE_FLAG: &str = "secret-file";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Manage the secret which authenticates the beacon node to an execution engine. \
+             Supply the secret file to the beacon node with --execution-jwt.",
+        )
+        .arg(
+            Arg::with_name(SECRET_FILE_FLAG)
+                .long(SECRET_FILE_FLAG)
+                .value_name("SECRET_FILE")
+                .help(
+                    "The file containing the hex-encoded secret. \
+                     Defaults to ~/.lighthouse/engine/jwt.hex",
+                )
+                .takes_value(true),
+        )
+        .subcommand(generate::cli_app())
+        .subcommand(show::cli_app())
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let secret_file = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        SECRET_FILE_FLAG,
+        PathBuf::new()
+            .join(".lighthouse")
+            .join("engine")
+            .join("jwt.hex"),
+    )?;
+
+    match matches.subcommand() {
+        (generate::CMD, Some(matches)) => generate::cli_run(matches, secret_file),
+        (show::CMD, Some(_)) => show::cli_run(secret_file),
+        (unknown, _) => Err(format!(
+            "{} does not have a {} command. See --help",
+            CMD, unknown
+        )),
+    }
+}
+
+/// Returns an error if the file at `path` is accessible by users other than its owner.
+pub fn ensure_owner_only_perms(path: &Path) -> Result<(), String> {
+    let mode = fs::metadata(path)
+        .map_err(|e| format!("Unable to get {:?} metadata: {}", path, e))?
+        .permissions()
+        .mode();
+
+    if mode & 0o077 != 0 {
+        return Err(format!(
+            "{:?} has permissions {:o}, it must only be accessible by its owner (e.g., chmod 600)",
+            path,
+            mode & 0o777
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use super::ensure_owner_only_perms;
+use clap::App;
+use execution_layer::auth::JwtKey;
+use std::path::PathBuf;
+
+pub const CMD: &str = "show";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD).about(
+        "Prints the secret, for configuring an execution engine. Fails if the secret file is \
+         accessible by users other than its owner.",
+    )
+}
+
+pub fn cli_run(secret_file: PathBuf) -> Result<(), String> {
+    ensure_owner_only_perms(&secret_file)?;
+
+    let secret = JwtKey::load(&secret_file)
+        .map_err(|e| format!("Unable to load secret from {:?}: {:?}", secret_file, e))?;
+
+    println!("{}", secret.hex_string());
+
+    Ok(())
+}
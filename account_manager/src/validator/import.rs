@@ -9,10 +9,12 @@ use account_utils::{
     ZeroizeString,
 };
 use clap::{App, Arg, ArgMatches};
+use slashing_protection::{DatabaseBackend, SlashingProtection};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread::sleep;
 use std::time::Duration;
+use types::PublicKey;
 
 pub const CMD: &str = "import";
 pub const KEYSTORE_FLAG: &str = "keystore";
@@ -128,6 +130,7 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
     // Skip keystores that already exist, but exit early if any operation fails.
     // Reuses the same password for all keystores if the `REUSE_PASSWORD_FLAG` flag is set.
     let mut num_imported_keystores = 0;
+    let mut imported_pubkeys = vec![];
     let mut previous_password: Option<ZeroizeString> = None;
     for src_keystore in &keystore_paths {
         let keystore = Keystore::from_json_file(src_keystore)
@@ -205,6 +208,7 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
 
         eprintln!("Successfully imported keystore.");
         num_imported_keystores += 1;
+        imported_pubkeys.extend(keystore.public_key());
 
         let validator_def =
             ValidatorDefinition::new_keystore_with_password(&dest_keystore, password_opt)
@@ -218,6 +222,19 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
         eprintln!("Successfully updated {}.", CONFIG_FILENAME);
     }
 
+    // The validator client also registers all validators when it starts, so failing to register
+    // them here is not fatal.
+    if !imported_pubkeys.is_empty() {
+        eprintln!("");
+        if let Err(e) = register_for_slashing_protection(&validator_dir, &imported_pubkeys) {
+            eprintln!(
+                "WARNING: Unable to register the imported validators for slashing protection: {}. \
+                 They will be registered when the validator client starts.",
+                e
+            );
+        }
+    }
+
     eprintln!("");
     eprintln!(
         "Successfully imported {} validators ({} skipped).",
@@ -229,3 +246,26 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Registers `pubkeys` with the slashing protection database in `validator_dir`, creating a
+/// database of the default backend if none exists.
+fn register_for_slashing_protection(
+    validator_dir: &Path,
+    pubkeys: &[PublicKey],
+) -> Result<(), String> {
+    let backend = DatabaseBackend::detect(validator_dir)?.unwrap_or_default();
+    let slashing_db = SlashingProtection::open_or_create(backend, validator_dir)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", backend.path(validator_dir), e))?;
+
+    let pubkeys = pubkeys.iter().collect::<Vec<_>>();
+    slashing_db
+        .register_validators_batch(&pubkeys, |done, total| {
+            eprintln!(
+                "Registered {}/{} validators for slashing protection.",
+                done, total
+            )
+        })
+        .map_err(|e| format!("{:?}", e))?;
+
+    Ok(())
+}
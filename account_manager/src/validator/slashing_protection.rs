@@ -0,0 +1,183 @@
+use crate::VALIDATOR_DIR_FLAG;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use slashing_protection::migration::migrate_sqlite_to_lmdb;
+use slashing_protection::{DatabaseBackend, Interchange, SlashingProtection};
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
+use types::{EthSpec, Hash256};
+
+pub const CMD: &str = "slashing-protection";
+pub const EXPORT_MINIMAL_CMD: &str = "export-minimal";
+pub const IMPORT_MINIMAL_CMD: &str = "import-minimal";
+pub const MIGRATE_TO_LMDB_CMD: &str = "migrate-to-lmdb";
+
+pub const FILE_ARG: &str = "FILE";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Exports and imports the slashing protection database of a validator client, which \
+            must not be running.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path containing the slashing protection database. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .subcommand(
+            App::new(EXPORT_MINIMAL_CMD)
+                .about(
+                    "Writes the latest block slot and attestation epochs signed by each \
+                    validator to a minimal interchange file.",
+                )
+                .arg(
+                    Arg::with_name(FILE_ARG)
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("The path to write the interchange file to. Must not exist.")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new(IMPORT_MINIMAL_CMD)
+                .about(
+                    "Imports a minimal interchange file. Signing is refused for any block or \
+                    attestation at or below the imported slots and epochs.",
+                )
+                .arg(
+                    Arg::with_name(FILE_ARG)
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("The path of the interchange file to import.")
+                        .required(true),
+                ),
+        )
+        .subcommand(App::new(MIGRATE_TO_LMDB_CMD).about(
+            "Copies the complete signing history of the SQLite slashing protection database \
+            to a new LMDB database, for use with `--slashing-protection-backend lmdb`. The \
+            SQLite database is renamed so that it can no longer be used.",
+        ))
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let data_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let backend = DatabaseBackend::detect(&data_dir)?;
+
+    match matches.subcommand() {
+        (EXPORT_MINIMAL_CMD, Some(matches)) => {
+            let export_path: PathBuf = clap_utils::parse_required(matches, FILE_ARG)?;
+            let genesis_validators_root = genesis_validators_root(&env)?;
+
+            let backend = backend.ok_or_else(|| {
+                format!("No slashing protection database found in {:?}", data_dir)
+            })?;
+            let slashing_db = SlashingProtection::open(backend, &data_dir).map_err(|e| {
+                format!(
+                    "Unable to open slashing protection database at {:?}: {:?}",
+                    backend.path(&data_dir),
+                    e
+                )
+            })?;
+            let interchange = slashing_db
+                .export_minimal_interchange(genesis_validators_root)
+                .map_err(|e| format!("Unable to export slashing protection data: {:?}", e))?;
+
+            let file = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&export_path)
+                .map_err(|e| format!("Unable to create {:?}: {:?}", export_path, e))?;
+            interchange
+                .write_to(file)
+                .map_err(|e| format!("Unable to write {:?}: {:?}", export_path, e))?;
+
+            eprintln!(
+                "Exported {} validators to {:?}",
+                interchange.data.len(),
+                export_path
+            );
+        }
+        (IMPORT_MINIMAL_CMD, Some(matches)) => {
+            let import_path: PathBuf = clap_utils::parse_required(matches, FILE_ARG)?;
+            let genesis_validators_root = genesis_validators_root(&env)?;
+
+            let file = File::open(&import_path)
+                .map_err(|e| format!("Unable to open {:?}: {:?}", import_path, e))?;
+            let interchange = Interchange::from_json_reader(file)
+                .map_err(|e| format!("Unable to parse {:?}: {:?}", import_path, e))?;
+
+            let backend = backend.unwrap_or_default();
+            let slashing_db =
+                SlashingProtection::open_or_create(backend, &data_dir).map_err(|e| {
+                    format!(
+                        "Unable to open or create slashing protection database at {:?}: {:?}",
+                        backend.path(&data_dir),
+                        e
+                    )
+                })?;
+            let num_imported = slashing_db
+                .import_minimal_interchange(&interchange, genesis_validators_root)
+                .map_err(|e| format!("Unable to import slashing protection data: {:?}", e))?;
+
+            eprintln!(
+                "Imported watermarks for {} validators from {:?}",
+                num_imported, import_path
+            );
+        }
+        (MIGRATE_TO_LMDB_CMD, Some(_)) => {
+            if backend != Some(DatabaseBackend::Sqlite) {
+                return Err(format!(
+                    "No SQLite slashing protection database found in {:?}",
+                    data_dir
+                ));
+            }
+
+            let summary = migrate_sqlite_to_lmdb(&data_dir)?;
+
+            eprintln!(
+                "Migrated {} validators, {} signed blocks and {} signed attestations to {:?}",
+                summary.validators,
+                summary.signed_blocks,
+                summary.signed_attestations,
+                DatabaseBackend::Lmdb.path(&data_dir)
+            );
+            eprintln!(
+                "The SQLite database was moved to {:?}. Start the validator client with \
+                --slashing-protection-backend lmdb",
+                summary.migrated_sqlite_path
+            );
+        }
+        (unknown, _) => {
+            return Err(format!(
+                "{} does not have a {} command. See --help",
+                CMD, unknown
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the genesis validators root of the testnet, which identifies the network in
+/// interchange files.
+fn genesis_validators_root<T: EthSpec>(env: &Environment<T>) -> Result<Hash256, String> {
+    env.testnet
+        .as_ref()
+        .and_then(|testnet| testnet.genesis_state.as_ref())
+        .map(|genesis_state| genesis_state.genesis_validators_root)
+        .ok_or_else(|| {
+            "Unable to determine the genesis validators root, the genesis state of the \
+            testnet is unknown"
+                .to_string()
+        })
+}
@@ -412,6 +412,11 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
     pub fn attestation(&self) -> &Attestation<T::EthSpec> {
         &self.signed_aggregate.message.aggregate
     }
+
+    /// Returns the `SignedAggregateAndProof` which was verified.
+    pub fn signed_aggregate(&self) -> &SignedAggregateAndProof<T::EthSpec> {
+        &self.signed_aggregate
+    }
 }
 
 impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
@@ -502,6 +507,12 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
             });
         }
 
+        // Store the attestation itself, so that an identical re-submission can be recognised.
+        chain
+            .observed_unaggregated_attestations
+            .observe_attestation(&attestation, None)
+            .map_err(BeaconChainError::from)?;
+
         Ok(Self {
             attestation,
             indexed_attestation,
@@ -2,15 +2,22 @@ use crate::attestation_verification::{
     Error as AttestationError, SignatureVerifiedAttestation, VerifiedAggregatedAttestation,
     VerifiedUnaggregatedAttestation,
 };
+use crate::block_times_cache::{BlockImportStage, BlockTimesCache};
 use crate::block_verification::{
     check_block_is_finalized_descendant, check_block_relevancy, get_block_root,
     signature_verify_chain_segment, BlockError, FullyVerifiedBlock, GossipVerifiedBlock,
     IntoFullyVerifiedBlock,
 };
+use crate::cache_stats::CachesInfo;
 use crate::chain_config::ChainConfig;
+use crate::chain_hooks::{BlockImport, ChainHooks, Finalization, HeadChange};
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::events::{EventHandler, EventKind};
+use crate::execution_payload::{
+    get_execution_payload_for_production, update_execution_engine_forkchoice,
+};
+use crate::gossip_observations::{GossipMessage, GossipObservation, GossipObservations};
 use crate::head_tracker::HeadTracker;
 use crate::metrics;
 use crate::migrate::Migrate;
@@ -21,12 +28,14 @@ use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::recent_proposals::{Proposal, RecentProposals};
 use crate::shuffling_cache::ShufflingCache;
 use crate::snapshot_cache::SnapshotCache;
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::BeaconForkChoiceStore;
 use crate::BeaconSnapshot;
+use execution_layer::ExecutionLayer;
 use fork_choice::ForkChoice;
 use itertools::process_results;
 use operation_pool::{OperationPool, PersistedOperationPool};
@@ -44,6 +53,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::prelude::*;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use store::iter::{BlockRootsIterator, ParentRootBlockIterator, StateRootsIterator};
@@ -137,6 +147,16 @@ pub enum StateSkipConfig {
     WithoutStateRoots,
 }
 
+/// Defines whether the randao reveal supplied for block production is verified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProduceBlockVerification {
+    /// Verify the randao reveal against the public key of the block proposer.
+    VerifyRandao,
+    /// Don't verify the randao reveal. The produced block will not be valid unless it is later
+    /// given a valid reveal, which is only useful for testing.
+    NoVerification,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct HeadInfo {
     pub slot: Slot,
@@ -182,6 +202,11 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub naive_aggregation_pool: RwLock<NaiveAggregationPool<T::EthSpec>>,
     /// Contains a store of attestations which have been observed by the beacon chain.
     pub observed_attestations: ObservedAttestations<T::EthSpec>,
+    /// Contains a store of the unaggregated attestations which have passed gossip verification.
+    ///
+    /// Used to tell a re-submission of an attestation apart from a different attestation by the
+    /// same validator.
+    pub observed_unaggregated_attestations: ObservedAttestations<T::EthSpec>,
     /// Maintains a record of which validators have been seen to attest in recent epochs.
     pub observed_attesters: ObservedAttesters<T::EthSpec>,
     /// Maintains a record of which validators have been seen to create `SignedAggregateAndProofs`
@@ -197,6 +222,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub observed_attester_slashings: ObservedOperations<AttesterSlashing<T::EthSpec>, T::EthSpec>,
     /// Provides information from the Ethereum 1 (PoW) chain.
     pub eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
+    /// A connection to the execution engine, used to verify execution payloads after the merge.
+    pub execution_layer: Option<ExecutionLayer>,
     /// Stores a "snapshot" of the chain at the time the head-of-the-chain block was received.
     pub(crate) canonical_head: TimeoutRwLock<BeaconSnapshot<T::EthSpec>>,
     /// The root of the genesis block.
@@ -212,10 +239,20 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     >,
     /// A handler for events generated by the beacon chain.
     pub event_handler: T::EventHandler,
+    /// The consumers of changes to the head and finalized checkpoint.
+    pub hooks: ChainHooks,
     /// Used to track the heads of the beacon chain.
     pub(crate) head_tracker: Arc<HeadTracker>,
     /// A cache dedicated to block processing.
     pub(crate) snapshot_cache: TimeoutRwLock<SnapshotCache<T::EthSpec>>,
+    /// Records the times at which recent blocks reached each stage of import.
+    pub block_times_cache: RwLock<BlockTimesCache>,
+    /// Records the times at which recent blocks and aggregates were received on gossip.
+    pub gossip_observations: RwLock<GossipObservations>,
+    /// Records the proposer and graffiti of recently imported blocks.
+    pub recent_proposals: RwLock<RecentProposals>,
+    /// The node shuts down once this slot has ended, if set.
+    pub shutdown_slot: RwLock<Option<Slot>>,
     /// Caches the shuffling for a given epoch and state root.
     pub(crate) shuffling_cache: TimeoutRwLock<ShufflingCache>,
     /// Caches a map of `validator_index -> validator_pubkey`.
@@ -224,8 +261,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub disabled_forks: Vec<String>,
     /// Logging to CLI, etc.
     pub(crate) log: Logger,
-    /// Arbitrary bytes included in the blocks.
-    pub(crate) graffiti: Graffiti,
+    /// Arbitrary bytes included in the blocks, unless the validator provides its own.
+    pub(crate) graffiti: RwLock<Graffiti>,
 }
 
 type BeaconBlockAndState<T> = (BeaconBlock<T>, BeaconState<T>);
@@ -494,6 +531,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.with_head(|head| Ok(head.clone_with_only_committee_caches()))
     }
 
+    /// Returns `true` if the head block was imported without its execution payload being verified
+    /// by the execution engine, and the payload has not been verified since.
+    ///
+    /// Duties should not be performed on an optimistic head, since the head may be invalid.
+    pub fn is_optimistic_head(&self) -> Result<bool, Error> {
+        let head_block_root = self.head_info()?.block_root;
+        self.fork_choice
+            .read()
+            .is_optimistic_block(&head_block_root)
+            .ok_or_else(|| Error::MissingBeaconBlock(head_block_root))
+    }
+
     /// Apply a function to the canonical head without cloning it.
     pub fn with_head<U>(
         &self,
@@ -506,6 +555,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         f(&head_lock)
     }
 
+    /// Apply a function to the canonical head state without cloning it.
+    ///
+    /// The head is read-locked whilst `f` runs, so `f` should avoid slow operations such as
+    /// database reads. Clone the state in `f` if it needs to be held for any length of time.
+    pub fn with_head_state<U>(
+        &self,
+        f: impl FnOnce(&BeaconState<T::EthSpec>) -> Result<U, Error>,
+    ) -> Result<U, Error> {
+        self.with_head(|head| f(&head.beacon_state))
+    }
+
     /// Returns info representing the head block and state.
     ///
     /// A summarized version of `Self::head` that involves less cloning.
@@ -774,6 +834,56 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(Into::into)
     }
 
+    /// Records that a local validator will aggregate the attestations of the committee at `slot`
+    /// and `committee_index`.
+    ///
+    /// The naive aggregation pool retains the attestations of such committees for longer, so that
+    /// `Self::get_aggregated_attestation` can provide them to the aggregator.
+    pub fn register_aggregator_duty(&self, slot: Slot, committee_index: CommitteeIndex) {
+        self.naive_aggregation_pool
+            .write()
+            .register_aggregator(slot, committee_index);
+    }
+
+    /// Returns the number of committees at `slot` on the canonical chain, or `None` if the
+    /// shuffling of the epoch of `slot` is known to neither the shuffling cache nor the head state.
+    ///
+    /// Valid committee indices at `slot` are those less than the returned count.
+    pub fn committee_count_at_slot(&self, slot: Slot) -> Result<Option<u64>, Error> {
+        let epoch = slot.epoch(T::EthSpec::slots_per_epoch());
+
+        let head = self
+            .canonical_head
+            .try_read_for(HEAD_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+        let state = &head.beacon_state;
+
+        // The shuffling cache is keyed by the root of the block at the start of the epoch, which
+        // is the head block if the head is not later than that slot.
+        let epoch_start_slot = epoch.start_slot(T::EthSpec::slots_per_epoch());
+        let target_root = if state.slot <= epoch_start_slot {
+            Some(head.beacon_block_root)
+        } else {
+            state.get_block_root(epoch_start_slot).ok().copied()
+        };
+
+        if let Some(target_root) = target_root {
+            let mut shuffling_cache = self
+                .shuffling_cache
+                .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::AttestationCacheLockTimeout)?;
+
+            if let Some(committee_cache) = shuffling_cache.get(epoch, target_root) {
+                return Ok(Some(committee_cache.committees_per_slot()));
+            }
+        }
+
+        Ok(RelativeEpoch::from_epoch(state.current_epoch(), epoch)
+            .ok()
+            .and_then(|relative_epoch| state.committee_cache(relative_epoch).ok())
+            .map(|committee_cache| committee_cache.committees_per_slot()))
+    }
+
     /// Produce an unaggregated `Attestation` that is valid for the given `slot` and `index`.
     ///
     /// The produced `Attestation` will not be valid until it has been signed by exactly one
@@ -1134,6 +1244,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Accept some proposer slashing and queue it for inclusion in an appropriate block.
     pub fn import_proposer_slashing(&self, proposer_slashing: SigVerifiedOp<ProposerSlashing>) {
+        let _ = self
+            .event_handler
+            .register(EventKind::BeaconProposerSlashingImported {
+                proposer_slashing: Box::new(proposer_slashing.clone().into_inner()),
+            });
+
         if self.eth1_chain.is_some() {
             self.op_pool.insert_proposer_slashing(proposer_slashing)
         }
@@ -1157,6 +1273,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         attester_slashing: SigVerifiedOp<AttesterSlashing<T::EthSpec>>,
     ) -> Result<(), Error> {
+        let _ = self
+            .event_handler
+            .register(EventKind::BeaconAttesterSlashingImported {
+                attester_slashing: Box::new(attester_slashing.clone().into_inner()),
+            });
+
         if self.eth1_chain.is_some() {
             self.op_pool
                 .insert_attester_slashing(attester_slashing, self.head_info()?.fork)
@@ -1397,6 +1519,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 // Increment the Prometheus counter for block processing successes.
                 metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
 
+                self.notify_block_import(block_root, &block);
+
                 let _ = self.event_handler.register(EventKind::BeaconBlockImported {
                     block_root,
                     block: Box::new(block),
@@ -1451,6 +1575,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let block_root = fully_verified_block.block_root;
         let state = fully_verified_block.state;
         let parent_block = fully_verified_block.parent_block;
+        let payload_verification_status = fully_verified_block.payload_verification_status;
         let current_slot = self.slot()?;
         let mut ops = fully_verified_block.intermediate_states;
 
@@ -1519,7 +1644,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let _fork_choice_block_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_BLOCK_TIMES);
             fork_choice
-                .on_block(current_slot, block, block_root, &state)
+                .on_block(
+                    current_slot,
+                    block,
+                    block_root,
+                    &state,
+                    payload_verification_status,
+                )
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
         }
 
@@ -1547,6 +1678,33 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             block.body.attestations.len() as f64,
         );
 
+        // Remove blocks which conflict with finality once the fork choice DAG grows too large.
+        //
+        // Blocks which descend from the finalized block are never removed, so this does not bound
+        // the size of the DAG whilst finality is not advancing.
+        if let Some(max_nodes) = self.config.fork_choice_max_nodes {
+            if fork_choice.proto_array().len() > max_nodes {
+                match fork_choice.prune_all() {
+                    Ok(0) => {}
+                    Ok(removed) => {
+                        metrics::inc_counter(&metrics::FORK_CHOICE_FORCED_PRUNES);
+                        warn!(
+                        self.log,
+                        "Forced fork choice pruning";
+                        "max_nodes" => max_nodes,
+                        "remaining_nodes" => fork_choice.proto_array().len(),
+                            "removed_nodes" => removed,
+                        )
+                    }
+                    Err(e) => error!(
+                        self.log,
+                        "Failed to prune fork choice";
+                        "error" => format!("{:?}", e),
+                    ),
+                }
+            }
+        }
+
         let db_write_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_DB_WRITE);
 
         // Store all the states between the parent block state and this block's slot, the block and state.
@@ -1564,6 +1722,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let parent_root = block.parent_root;
         let slot = block.slot;
 
+        self.record_block_import_stage(block_root, slot, BlockImportStage::Imported, None);
+
         self.snapshot_cache
             .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
             .map(|mut snapshot_cache| {
@@ -1603,13 +1763,70 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         validator_graffiti: Option<Graffiti>,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
-        let state = self
+        self.produce_block_with_verification(
+            randao_reveal,
+            slot,
+            validator_graffiti,
+            ProduceBlockVerification::VerifyRandao,
+        )
+    }
+
+    /// Produce a new block at the given `slot`, verifying the `randao_reveal` as specified by
+    /// `verification`.
+    ///
+    /// See `Self::produce_block` for more detail.
+    pub fn produce_block_with_verification(
+        &self,
+        randao_reveal: Signature,
+        slot: Slot,
+        validator_graffiti: Option<Graffiti>,
+        verification: ProduceBlockVerification,
+    ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
+        let mut state = self
             .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
             .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
 
+        if verification == ProduceBlockVerification::VerifyRandao {
+            // Advance the state here so `produce_block_on_state` does not have to repeat it.
+            while state.slot < slot {
+                per_slot_processing(&mut state, None, &self.spec)?;
+            }
+
+            self.verify_randao_reveal(&mut state, &randao_reveal)?;
+        }
+
         self.produce_block_on_state(state, slot, randao_reveal, validator_graffiti)
     }
 
+    /// Verifies that `randao_reveal` was signed by the proposer at the slot of `state`.
+    fn verify_randao_reveal(
+        &self,
+        state: &mut BeaconState<T::EthSpec>,
+        randao_reveal: &Signature,
+    ) -> Result<(), BlockProductionError> {
+        state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
+
+        let epoch = state.current_epoch();
+        let proposer_index = state.get_beacon_proposer_index(state.slot, &self.spec)?;
+        let pubkey = self
+            .validator_pubkey(proposer_index)
+            .map_err(BlockProductionError::BeaconChainError)?
+            .ok_or_else(|| BlockProductionError::UnknownProposerPubkey(proposer_index))?;
+
+        let domain = self.spec.get_domain(
+            epoch,
+            Domain::Randao,
+            &state.fork,
+            state.genesis_validators_root,
+        );
+
+        if randao_reveal.verify(&pubkey, epoch.signing_root(domain)) {
+            Ok(())
+        } else {
+            Err(BlockProductionError::InvalidRandaoReveal)
+        }
+    }
+
     /// Produce a block for some `slot` upon the given `state`.
     ///
     /// Typically the `self.produce_block()` function should be used, instead of calling this
@@ -1658,6 +1875,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .deposits_for_block_inclusion(&state, &eth1_data, &self.spec)?
             .into();
 
+        // The block body has no field for an execution payload until the merge, so the payload
+        // (always `None` for now) is not yet included.
+        let _execution_payload = get_execution_payload_for_production(self, &state)?;
+
         // Map from attestation head block root to shuffling compatibility.
         // Used to memoize the `attestation_shuffling_is_compatible` function.
         let mut shuffling_filter_cache = HashMap::new();
@@ -1691,10 +1912,33 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        let packing_timer =
+            metrics::start_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_PACKING_TIMES);
+        let deadline = self
+            .config
+            .attestation_packing_deadline
+            .map(|budget| Instant::now() + budget);
+        let (attestations, deadline_reached) = self
+            .op_pool
+            .get_attestations_with_deadline(&state, attestation_filter, deadline, &self.spec)
+            .map_err(BlockProductionError::OpPoolError)?;
+        metrics::stop_timer(packing_timer);
+
+        if deadline_reached {
+            metrics::inc_counter(&metrics::BLOCK_PRODUCTION_ATTESTATION_PACKING_DEADLINE_REACHED);
+            warn!(
+                self.log,
+                "Attestation packing deadline reached";
+                "msg" => "the block may contain fewer attestations than possible",
+                "attestations" => attestations.len(),
+                "slot" => state.slot,
+            );
+        }
+
         // Override the beacon node's graffiti with graffiti from the validator, if present.
         let graffiti = match validator_graffiti {
             Some(graffiti) => graffiti,
-            None => self.graffiti,
+            None => *self.graffiti.read(),
         };
 
         let mut block = SignedBeaconBlock {
@@ -1709,11 +1953,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     graffiti,
                     proposer_slashings: proposer_slashings.into(),
                     attester_slashings: attester_slashings.into(),
-                    attestations: self
-                        .op_pool
-                        .get_attestations(&state, attestation_filter, &self.spec)
-                        .map_err(BlockProductionError::OpPoolError)?
-                        .into(),
+                    attestations: attestations.into(),
                     deposits,
                     voluntary_exits: self.op_pool.get_voluntary_exits(&state, &self.spec).into(),
                 },
@@ -1823,6 +2063,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .map(|root| *root)
                 .unwrap_or_else(|_| Hash256::random());
 
+        // The number of slots between the previous head and the latest slot at which the previous
+        // chain and the new chain agree.
+        let reorg_distance = if is_reorg {
+            let new_root_at_slot = |slot: Slot| {
+                if slot >= new_head.beacon_state.slot {
+                    Ok(beacon_block_root)
+                } else {
+                    new_head.beacon_state.get_block_root(slot).map(|root| *root)
+                }
+            };
+
+            self.with_head(|old_head| {
+                Ok((0..current_head.slot.as_u64())
+                    .rev()
+                    .map(Slot::new)
+                    .find(|slot| {
+                        match (
+                            old_head.beacon_state.get_block_root(*slot),
+                            new_root_at_slot(*slot),
+                        ) {
+                            (Ok(old_root), Ok(new_root)) => *old_root == new_root,
+                            // Beyond the reach of either state, assume the chains agree.
+                            _ => true,
+                        }
+                    })
+                    .map(|slot| (current_head.slot - slot).as_u64()))
+            })?
+        } else {
+            None
+        };
+
         if is_reorg {
             metrics::inc_counter(&metrics::FORK_CHOICE_REORG_COUNT);
             warn!(
@@ -1830,6 +2101,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "Beacon chain re-org";
                 "previous_head" => format!("{}", current_head.block_root),
                 "previous_slot" => current_head.slot,
+                "distance" => format!("{:?}", reorg_distance),
                 "new_head_parent" => format!("{}", new_head.beacon_block.parent_root()),
                 "new_head" => format!("{}", beacon_block_root),
                 "new_slot" => new_head.beacon_block.slot()
@@ -1876,27 +2148,40 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
-        if current_head.slot.epoch(T::EthSpec::slots_per_epoch())
+        let is_new_epoch = current_head.slot.epoch(T::EthSpec::slots_per_epoch())
             < new_head
                 .beacon_state
                 .slot
-                .epoch(T::EthSpec::slots_per_epoch())
-            || is_reorg
-        {
+                .epoch(T::EthSpec::slots_per_epoch());
+
+        if is_new_epoch || is_reorg {
             self.persist_head_and_fork_choice()?;
         }
 
         let update_head_timer = metrics::start_timer(&metrics::UPDATE_HEAD_TIMES);
 
+        let new_head_slot = new_head.beacon_block.slot();
+        let new_head_state_root = new_head.beacon_state_root;
+
         // Update the snapshot that stores the head of the chain at the time it received the
         // block.
-        *self
-            .canonical_head
-            .try_write_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)? = new_head;
+        let old_head = std::mem::replace(
+            &mut *self
+                .canonical_head
+                .try_write_for(HEAD_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::CanonicalHeadLockTimeout)?,
+            new_head,
+        );
 
         metrics::stop_timer(update_head_timer);
 
+        self.record_block_import_stage(
+            beacon_block_root,
+            new_head_slot,
+            BlockImportStage::Head,
+            None,
+        );
+
         self.snapshot_cache
             .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
             .map(|mut snapshot_cache| {
@@ -1919,12 +2204,36 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             )?;
         }
 
+        update_execution_engine_forkchoice(self, beacon_block_root, new_finalized_checkpoint.root)?;
+
+        self.hooks.notify_head_change(HeadChange {
+            slot: new_head_slot,
+            block_root: beacon_block_root,
+            state_root: new_head_state_root,
+            previous_block_root: current_head.block_root,
+            reorg: is_reorg,
+            reorg_distance,
+        });
+
         let _ = self.event_handler.register(EventKind::BeaconHeadChanged {
             reorg: is_reorg,
+            reorg_distance,
             previous_head_beacon_block_root: current_head.block_root,
             current_head_beacon_block_root: beacon_block_root,
         });
 
+        // Balances only change materially at epoch boundaries. The balances of a re-orged chain
+        // are not comparable.
+        if is_new_epoch && !is_reorg {
+            if let Some(head) = self.canonical_head.try_read_for(HEAD_LOCK_TIMEOUT) {
+                let _ = self.event_handler.epoch_balances(
+                    head.beacon_state.current_epoch(),
+                    &old_head.beacon_state.balances,
+                    &head.beacon_state.balances,
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -1938,6 +2247,194 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Records that the block with `block_root` reached `stage` of import at `timestamp`, or at
+    /// the present time if `timestamp` is `None`.
+    ///
+    /// The delay since the preceding stage (or, for `BlockImportStage::Observed`, since the start
+    /// of `slot`) is added to the stage's histogram. Only the first time a block reaches each
+    /// stage is recorded.
+    pub fn record_block_import_stage(
+        &self,
+        block_root: Hash256,
+        slot: Slot,
+        stage: BlockImportStage,
+        timestamp: Option<Duration>,
+    ) {
+        let timestamp = match timestamp.or_else(|| self.slot_clock.now_duration()) {
+            Some(timestamp) => timestamp,
+            None => return,
+        };
+
+        let mut block_times_cache = self.block_times_cache.write();
+
+        if !block_times_cache.set_time(block_root, slot, stage, timestamp) {
+            return;
+        }
+
+        let since = match stage {
+            BlockImportStage::Observed => self.slot_clock.start_of(slot),
+            _ => block_times_cache
+                .get(&block_root)
+                .and_then(|block_times| block_times.timestamps.preceding(stage)),
+        };
+
+        drop(block_times_cache);
+
+        if let Some(delay) = since.and_then(|since| timestamp.checked_sub(since)) {
+            let delay = delay.as_secs_f64();
+            match stage {
+                BlockImportStage::Observed => {
+                    metrics::observe(&metrics::BLOCK_OBSERVED_DELAY, delay)
+                }
+                BlockImportStage::SignatureVerified => {
+                    metrics::observe(&metrics::BLOCK_SIGNATURE_VERIFIED_DELAY, delay)
+                }
+                BlockImportStage::StateTransition => {
+                    metrics::observe(&metrics::BLOCK_STATE_TRANSITION_DELAY, delay)
+                }
+                BlockImportStage::Imported => {
+                    metrics::observe(&metrics::BLOCK_IMPORTED_DELAY, delay)
+                }
+                BlockImportStage::Head => metrics::observe(&metrics::BLOCK_HEAD_DELAY, delay),
+            }
+        }
+    }
+
+    /// Returns the slot after which the node should shut down, if that slot has ended.
+    pub fn ended_shutdown_slot(&self) -> Option<Slot> {
+        let shutdown_slot = (*self.shutdown_slot.read())?;
+        let current_slot = self.slot().ok()?;
+
+        if current_slot > shutdown_slot {
+            Some(shutdown_slot)
+        } else {
+            None
+        }
+    }
+
+    /// Records the proposal of the imported block with `block_root`, and notifies the consumers
+    /// of imported blocks.
+    fn notify_block_import(&self, block_root: Hash256, block: &SignedBeaconBlock<T::EthSpec>) {
+        let slot = block.slot();
+        let proposer_index = block.message.proposer_index;
+        let graffiti = block.message.body.graffiti;
+
+        self.recent_proposals.write().record(Proposal {
+            slot,
+            proposer_index,
+            graffiti,
+        });
+
+        let timestamps = self
+            .block_times_cache
+            .read()
+            .get(&block_root)
+            .map(|block_times| block_times.timestamps);
+        let slot_start = self.slot_clock.start_of(slot);
+        let delay = |stage| {
+            let timestamp = timestamps?.get(stage)?;
+            timestamp.checked_sub(slot_start?)
+        };
+
+        self.hooks.notify_block(BlockImport {
+            slot,
+            block_root,
+            proposer_index,
+            graffiti,
+            observed_delay: delay(BlockImportStage::Observed),
+            imported_delay: delay(BlockImportStage::Imported),
+        });
+    }
+
+    /// Records that `message`, from `slot`, was received on gossip at `seen_timestamp`, or at the
+    /// present time if `seen_timestamp` is `None`.
+    pub fn record_gossip_observation(
+        &self,
+        slot: Slot,
+        message: GossipMessage,
+        seen_timestamp: Option<Duration>,
+    ) {
+        if let Some(seen_timestamp) = seen_timestamp.or_else(|| self.slot_clock.now_duration()) {
+            self.gossip_observations.write().observe(GossipObservation {
+                slot,
+                message,
+                seen_timestamp,
+            });
+        }
+    }
+
+    /// Persists the head and fork choice, then writes a consistent copy of the database to `dir`
+    /// without interrupting the chain. Returns the number of entries copied from the hot and
+    /// cold DBs, respectively.
+    ///
+    /// See `HotColdDB::backup`.
+    pub fn backup_database(&self, dir: &Path) -> Result<(usize, usize), Error> {
+        self.persist_head_and_fork_choice()?;
+        self.store.backup(dir).map_err(Into::into)
+    }
+
+    /// Removes every block from fork choice which does not descend from the finalized block,
+    /// regardless of the usual pruning threshold. Returns the number of blocks removed.
+    pub fn prune_fork_choice(&self) -> Result<usize, Error> {
+        let removed = self.fork_choice.write().prune_all()?;
+
+        info!(
+            self.log,
+            "Pruned fork choice";
+            "removed_nodes" => removed,
+        );
+
+        Ok(removed)
+    }
+
+    /// Returns the size and lookup statistics of each of the in-memory caches.
+    pub fn caches_info(&self) -> Result<CachesInfo, Error> {
+        Ok(CachesInfo {
+            snapshot_cache: self
+                .snapshot_cache
+                .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::SnapshotCacheLockTimeout)?
+                .info(),
+            shuffling_cache: self
+                .shuffling_cache
+                .try_read_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::AttestationCacheLockTimeout)?
+                .info(),
+            validator_pubkey_cache: self
+                .validator_pubkey_cache
+                .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::ValidatorPubkeyCacheLockTimeout)?
+                .info(),
+        })
+    }
+
+    /// Removes every snapshot other than the head's from the snapshot cache.
+    ///
+    /// Intended for debugging memory usage. Block processing is slower until the cache refills.
+    pub fn clear_snapshot_cache(&self) -> Result<(), Error> {
+        self.snapshot_cache
+            .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::SnapshotCacheLockTimeout)?
+            .clear();
+
+        info!(self.log, "Cleared snapshot cache");
+        Ok(())
+    }
+
+    /// Removes every committee cache from the shuffling cache.
+    ///
+    /// Intended for debugging memory usage. Attestation verification is slower until the cache
+    /// refills.
+    pub fn clear_shuffling_cache(&self) -> Result<(), Error> {
+        self.shuffling_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::AttestationCacheLockTimeout)?
+            .clear();
+
+        info!(self.log, "Cleared shuffling cache");
+        Ok(())
+    }
+
     /// Called after `self` has had a new block finalized.
     ///
     /// Performs pruning and finality-based optimizations.
@@ -1984,6 +2481,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             new_finalized_checkpoint,
         )?;
 
+        self.hooks.notify_finalization(Finalization {
+            epoch: new_finalized_checkpoint.epoch,
+            root: new_finalized_checkpoint.root,
+        });
+
         let _ = self.event_handler.register(EventKind::BeaconFinalization {
             epoch: new_finalized_checkpoint.epoch,
             root: new_finalized_checkpoint.root,
@@ -2170,9 +2672,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.dump_as_dot(&mut file);
     }
 
-    // Should be used in tests only
-    pub fn set_graffiti(&mut self, graffiti: Graffiti) {
-        self.graffiti = graffiti;
+    /// Returns the graffiti included in produced blocks which the validator provides none for.
+    pub fn graffiti(&self) -> Graffiti {
+        *self.graffiti.read()
+    }
+
+    /// Sets the graffiti included in produced blocks which the validator provides none for.
+    pub fn set_graffiti(&self, graffiti: Graffiti) {
+        *self.graffiti.write() = graffiti;
     }
 }
 
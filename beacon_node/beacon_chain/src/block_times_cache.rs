@@ -0,0 +1,237 @@
+//! Provides the `BlockTimesCache` which records the times at which recent blocks reached each
+//! stage of import.
+//!
+//! This is useful for diagnosing late blocks, since it shows whether a block arrived late or was
+//! slow to import (and in which stage).
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+/// The number of blocks for which import times are retained.
+pub const DEFAULT_BLOCK_TIMES_CACHE_SIZE: usize = 64;
+
+/// A stage of block import, listed in the order in which they are reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockImportStage {
+    /// The block was received from the network.
+    Observed,
+    /// All of the signatures in the block were verified.
+    SignatureVerified,
+    /// The block was applied to its parent state.
+    StateTransition,
+    /// The block was added to fork choice and written to the database.
+    Imported,
+    /// The block became the head of the chain.
+    Head,
+}
+
+impl BlockImportStage {
+    /// All stages, in the order in which they are reached.
+    pub const ALL: [BlockImportStage; 5] = [
+        BlockImportStage::Observed,
+        BlockImportStage::SignatureVerified,
+        BlockImportStage::StateTransition,
+        BlockImportStage::Imported,
+        BlockImportStage::Head,
+    ];
+}
+
+/// The times at which a block reached each stage of import, as durations since the UNIX epoch.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BlockTimestamps {
+    pub observed: Option<Duration>,
+    pub signature_verified: Option<Duration>,
+    pub state_transition: Option<Duration>,
+    pub imported: Option<Duration>,
+    pub head: Option<Duration>,
+}
+
+impl BlockTimestamps {
+    /// Returns the time at which `stage` was reached, if it has been.
+    pub fn get(&self, stage: BlockImportStage) -> Option<Duration> {
+        match stage {
+            BlockImportStage::Observed => self.observed,
+            BlockImportStage::SignatureVerified => self.signature_verified,
+            BlockImportStage::StateTransition => self.state_transition,
+            BlockImportStage::Imported => self.imported,
+            BlockImportStage::Head => self.head,
+        }
+    }
+
+    /// Returns the time at which the latest stage before `stage` was reached, if any was.
+    ///
+    /// Not every block passes through every stage (e.g., blocks from sync are not observed on
+    /// gossip), so the preceding stage may be more than one stage prior.
+    pub fn preceding(&self, stage: BlockImportStage) -> Option<Duration> {
+        BlockImportStage::ALL
+            .iter()
+            .take_while(|other| **other != stage)
+            .filter_map(|other| self.get(*other))
+            .last()
+    }
+
+    fn get_mut(&mut self, stage: BlockImportStage) -> &mut Option<Duration> {
+        match stage {
+            BlockImportStage::Observed => &mut self.observed,
+            BlockImportStage::SignatureVerified => &mut self.signature_verified,
+            BlockImportStage::StateTransition => &mut self.state_transition,
+            BlockImportStage::Imported => &mut self.imported,
+            BlockImportStage::Head => &mut self.head,
+        }
+    }
+}
+
+/// The import times of a single block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockTimes {
+    pub slot: Slot,
+    pub timestamps: BlockTimestamps,
+}
+
+/// Records the import times of the most recently seen blocks.
+pub struct BlockTimesCache {
+    capacity: usize,
+    times: HashMap<Hash256, BlockTimes>,
+    /// The roots in `times`, oldest first.
+    order: VecDeque<Hash256>,
+}
+
+impl Default for BlockTimesCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_TIMES_CACHE_SIZE)
+    }
+}
+
+impl BlockTimesCache {
+    /// Instantiates a new cache which retains the times of up to `capacity` blocks.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            times: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records that the block with `block_root` reached `stage` at `timestamp`.
+    ///
+    /// Only the first time each stage is reached is recorded. Returns `true` if `timestamp` was
+    /// recorded.
+    pub fn set_time(
+        &mut self,
+        block_root: Hash256,
+        slot: Slot,
+        stage: BlockImportStage,
+        timestamp: Duration,
+    ) -> bool {
+        if !self.times.contains_key(&block_root) {
+            if self.order.len() >= self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.times.remove(&oldest);
+                }
+            }
+
+            self.order.push_back(block_root);
+            self.times.insert(
+                block_root,
+                BlockTimes {
+                    slot,
+                    timestamps: BlockTimestamps::default(),
+                },
+            );
+        }
+
+        match self
+            .times
+            .get_mut(&block_root)
+            .map(|times| times.timestamps.get_mut(stage))
+        {
+            Some(time) if time.is_none() => {
+                *time = Some(timestamp);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the times for the block with `block_root`, if it is known.
+    pub fn get(&self, block_root: &Hash256) -> Option<&BlockTimes> {
+        self.times.get(block_root)
+    }
+
+    /// Iterates through the times of all known blocks, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (&Hash256, &BlockTimes)> {
+        let times = &self.times;
+        self.order
+            .iter()
+            .filter_map(move |root| times.get(root).map(|block_times| (root, block_times)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    #[test]
+    fn records_first_time_only() {
+        let mut cache = BlockTimesCache::new(4);
+        let slot = Slot::new(1);
+
+        assert!(cache.set_time(
+            root(1),
+            slot,
+            BlockImportStage::Observed,
+            Duration::from_millis(10)
+        ));
+        assert!(!cache.set_time(
+            root(1),
+            slot,
+            BlockImportStage::Observed,
+            Duration::from_millis(20)
+        ));
+        assert!(cache.set_time(
+            root(1),
+            slot,
+            BlockImportStage::Imported,
+            Duration::from_millis(30)
+        ));
+
+        let times = cache.get(&root(1)).expect("should have times");
+        assert_eq!(times.slot, slot);
+        assert_eq!(times.timestamps.observed, Some(Duration::from_millis(10)));
+        assert_eq!(times.timestamps.imported, Some(Duration::from_millis(30)));
+        assert_eq!(
+            times.timestamps.preceding(BlockImportStage::Head),
+            Some(Duration::from_millis(30))
+        );
+        assert_eq!(
+            times.timestamps.preceding(BlockImportStage::Imported),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(times.timestamps.preceding(BlockImportStage::Observed), None);
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let mut cache = BlockTimesCache::new(2);
+
+        for i in 0..3 {
+            cache.set_time(
+                root(i),
+                Slot::new(i),
+                BlockImportStage::Head,
+                Duration::from_secs(i),
+            );
+        }
+
+        assert!(cache.get(&root(0)).is_none());
+        assert_eq!(
+            cache.iter().map(|(root, _)| *root).collect::<Vec<_>>(),
+            vec![root(1), root(2)]
+        );
+    }
+}
@@ -40,6 +40,8 @@
 //!            END
 //!
 //! ```
+use crate::block_times_cache::BlockImportStage;
+use crate::execution_payload::notify_new_payload;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
     beacon_chain::{
@@ -48,7 +50,8 @@ use crate::{
     },
     metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BeaconSnapshot,
 };
-use fork_choice::{ForkChoice, ForkChoiceStore};
+use execution_layer::PayloadStatus;
+use fork_choice::{ForkChoice, ForkChoiceStore, PayloadVerificationStatus};
 use parking_lot::RwLockReadGuard;
 use slog::{error, Logger};
 use slot_clock::SlotClock;
@@ -200,6 +203,23 @@ pub enum BlockError<T: EthSpec> {
     ///
     /// The block is invalid and the peer is faulty.
     PerBlockProcessingError(BlockProcessingError),
+    /// The execution engine found the block's execution payload to be invalid.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block is invalid and the peer is faulty.
+    ExecutionPayloadInvalid(PayloadStatus),
+    /// The execution engine has not validated the block's execution payload and the block is too
+    /// recent to be imported optimistically.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block is not necessarily invalid. It may be imported once the execution engine has
+    /// synced or more slots have passed.
+    OptimisticImportNotPermitted {
+        block_slot: Slot,
+        current_slot: Slot,
+    },
     /// There was an error whilst processing the block. It is not necessarily invalid.
     ///
     /// ## Peer scoring
@@ -357,6 +377,7 @@ pub struct FullyVerifiedBlock<'a, T: BeaconChainTypes> {
     pub state: BeaconState<T::EthSpec>,
     pub parent_block: SignedBeaconBlock<T::EthSpec>,
     pub intermediate_states: Vec<StoreOp<'a, T::EthSpec>>,
+    pub payload_verification_status: PayloadVerificationStatus,
 }
 
 /// Implemented on types that can be converted into a `FullyVerifiedBlock`.
@@ -539,6 +560,13 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         signature_verifier.include_all_signatures(&block, Some(block_root))?;
 
         if signature_verifier.verify().is_ok() {
+            chain.record_block_import_stage(
+                block_root,
+                block.slot(),
+                BlockImportStage::SignatureVerified,
+                None,
+            );
+
             Ok(Self {
                 block,
                 block_root,
@@ -571,6 +599,13 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         signature_verifier.include_all_signatures_except_proposal(&block)?;
 
         if signature_verifier.verify().is_ok() {
+            chain.record_block_import_stage(
+                from.block_root,
+                block.slot(),
+                BlockImportStage::SignatureVerified,
+                None,
+            );
+
             Ok(Self {
                 block,
                 block_root: from.block_root,
@@ -762,6 +797,13 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
 
         metrics::stop_timer(core_timer);
 
+        /*
+         * Verify the block's execution payload (if any) with the execution engine.
+         */
+
+        let payload_verification_status =
+            notify_new_payload(chain, &parent.beacon_block.message, &block.message)?;
+
         /*
          * Calculate the state root of the newly modified state
          */
@@ -789,12 +831,20 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
             });
         }
 
+        chain.record_block_import_stage(
+            block_root,
+            block.slot(),
+            BlockImportStage::StateTransition,
+            None,
+        );
+
         Ok(Self {
             block,
             block_root,
             state,
             parent_block: parent.beacon_block,
             intermediate_states,
+            payload_verification_status,
         })
     }
 }
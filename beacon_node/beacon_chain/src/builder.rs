@@ -17,6 +17,7 @@ use crate::{
     Eth1ChainBackend, EventHandler,
 };
 use eth1::Config as Eth1Config;
+use execution_layer::ExecutionLayer;
 use fork_choice::ForkChoice;
 use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::RwLock;
@@ -26,7 +27,7 @@ use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use store::{HotColdDB, ItemStore};
+use store::{Error as StoreError, HotColdDB, ItemStore};
 use types::{
     BeaconBlock, BeaconState, ChainSpec, EthSpec, Graffiti, Hash256, Signature, SignedBeaconBlock,
     Slot,
@@ -104,6 +105,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     genesis_block_root: Option<Hash256>,
     op_pool: Option<OperationPool<T::EthSpec>>,
     eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
+    execution_layer: Option<ExecutionLayer>,
     event_handler: Option<T::EventHandler>,
     slot_clock: Option<T::SlotClock>,
     head_tracker: Option<HeadTracker>,
@@ -151,6 +153,7 @@ where
             genesis_block_root: None,
             op_pool: None,
             eth1_chain: None,
+            execution_layer: None,
             event_handler: None,
             slot_clock: None,
             head_tracker: None,
@@ -183,6 +186,15 @@ where
         self
     }
 
+    /// Sets the maximum number of blocks in the fork choice DAG before blocks which do not
+    /// descend from the finalized block are forcibly pruned.
+    ///
+    /// Set to `None` for no limit.
+    pub fn fork_choice_max_nodes(mut self, n: Option<usize>) -> Self {
+        self.chain_config.fork_choice_max_nodes = n;
+        self
+    }
+
     /// Sets the store (database).
     ///
     /// Should generally be called early in the build chain.
@@ -390,6 +402,12 @@ where
         self
     }
 
+    /// Sets the connection to the execution engine.
+    pub fn execution_layer(mut self, execution_layer: Option<ExecutionLayer>) -> Self {
+        self.execution_layer = execution_layer;
+        self
+    }
+
     /// Sets the `BeaconChain` event handler backend.
     ///
     /// For example, provide `WebSocketSender` as a `handler`.
@@ -460,6 +478,7 @@ where
 
         // If this beacon chain is being loaded from disk, use the stored head. Otherwise, just use
         // the finalized checkpoint (which is probably genesis).
+        let resuming_from_db = self.canonical_head.is_some();
         let mut canonical_head = if let Some(head) = self.canonical_head {
             head
         } else {
@@ -487,15 +506,42 @@ where
 
         let persisted_fork_choice = store
             .get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY))
-            .map_err(|e| format!("DB error when reading persisted fork choice: {:?}", e))?;
+            .map_err(|e| match e {
+                StoreError::SszDecodeError(e) => format!(
+                    "Unable to decode persisted fork choice: {:?}. The database may have been \
+                     written by an incompatible version of Lighthouse. Try purging the beacon \
+                     chain database.",
+                    e
+                ),
+                e => format!("DB error when reading persisted fork choice: {:?}", e),
+            })?;
 
         let fork_choice = if let Some(persisted) = persisted_fork_choice {
-            let fc_store =
-                BeaconForkChoiceStore::from_persisted(persisted.fork_choice_store, store.clone())
-                    .map_err(|e| format!("Unable to load ForkChoiceStore: {:?}", e))?;
+            let fork_choice =
+                restore_fork_choice(persisted, &store, &canonical_head).map_err(|e| {
+                    format!(
+                        "Persisted fork choice is inconsistent with the database: {}. Try \
+                         purging the beacon chain database.",
+                        e
+                    )
+                })?;
+
+            info!(
+                log,
+                "Restored fork choice from disk";
+                "nodes" => fork_choice.proto_array().len(),
+                "justified_epoch" => fork_choice.fc_store().justified_checkpoint().epoch,
+                "finalized_epoch" => fork_choice.fc_store().finalized_checkpoint().epoch,
+            );
 
-            ForkChoice::from_persisted(persisted.fork_choice, fc_store)
-                .map_err(|e| format!("Unable to parse persisted fork choice from disk: {:?}", e))?
+            fork_choice
+        } else if resuming_from_db {
+            // Fork choice must not be rebuilt from the head of a resumed chain, since the head is
+            // not a valid anchor for it (e.g., it is not justified).
+            return Err(
+                "No persisted fork choice found in store. Try purging the beacon chain database."
+                    .to_string(),
+            );
         } else {
             let genesis = &canonical_head;
 
@@ -505,6 +551,8 @@ where
                 .map_err(|e| format!("Unable to build initialize ForkChoice: {:?}", e))?
         };
 
+        let shutdown_slot = self.chain_config.shutdown_after_slot;
+
         let beacon_chain = BeaconChain {
             spec: self.spec,
             config: self.chain_config,
@@ -521,6 +569,8 @@ where
             // TODO: allow for persisting and loading the pool from disk.
             observed_attestations: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
+            observed_unaggregated_attestations: <_>::default(),
+            // TODO: allow for persisting and loading the pool from disk.
             observed_attesters: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
             observed_aggregators: <_>::default(),
@@ -531,6 +581,7 @@ where
             observed_proposer_slashings: <_>::default(),
             observed_attester_slashings: <_>::default(),
             eth1_chain: self.eth1_chain,
+            execution_layer: self.execution_layer,
             genesis_validators_root: canonical_head.beacon_state.genesis_validators_root,
             canonical_head: TimeoutRwLock::new(canonical_head.clone()),
             genesis_block_root: self
@@ -540,16 +591,21 @@ where
             event_handler: self
                 .event_handler
                 .ok_or_else(|| "Cannot build without an event handler".to_string())?,
+            hooks: <_>::default(),
             head_tracker: Arc::new(self.head_tracker.unwrap_or_default()),
             snapshot_cache: TimeoutRwLock::new(SnapshotCache::new(
                 DEFAULT_SNAPSHOT_CACHE_SIZE,
                 canonical_head,
             )),
+            block_times_cache: <_>::default(),
+            gossip_observations: <_>::default(),
+            recent_proposals: <_>::default(),
+            shutdown_slot: RwLock::new(shutdown_slot),
             shuffling_cache: TimeoutRwLock::new(ShufflingCache::new()),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             disabled_forks: self.disabled_forks,
             log: log.clone(),
-            graffiti: self.graffiti,
+            graffiti: RwLock::new(self.graffiti),
         };
 
         let head = beacon_chain
@@ -677,6 +733,63 @@ where
     }
 }
 
+/// Restores fork choice from `persisted`, checking that it is consistent with the `canonical_head`
+/// and the blocks in `store`.
+#[allow(clippy::type_complexity)]
+fn restore_fork_choice<E, Hot, Cold>(
+    persisted: PersistedForkChoice,
+    store: &Arc<HotColdDB<E, Hot, Cold>>,
+    canonical_head: &BeaconSnapshot<E>,
+) -> Result<ForkChoice<BeaconForkChoiceStore<E, Hot, Cold>, E>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let fc_store =
+        BeaconForkChoiceStore::from_persisted(persisted.fork_choice_store, store.clone())
+            .map_err(|e| format!("Unable to load ForkChoiceStore: {:?}", e))?;
+
+    let fork_choice = ForkChoice::from_persisted(persisted.fork_choice, fc_store)
+        .map_err(|e| format!("Unable to parse persisted fork choice from disk: {:?}", e))?;
+
+    if !fork_choice.contains_block(&canonical_head.beacon_block_root) {
+        return Err(format!(
+            "Head block {:?} is not known to fork choice",
+            canonical_head.beacon_block_root
+        ));
+    }
+
+    let justified_checkpoint = *fork_choice.fc_store().justified_checkpoint();
+    let finalized_checkpoint = *fork_choice.fc_store().finalized_checkpoint();
+
+    for (name, checkpoint) in &[
+        ("justified", justified_checkpoint),
+        ("finalized", finalized_checkpoint),
+    ] {
+        if !fork_choice.contains_block(&checkpoint.root) {
+            return Err(format!(
+                "The {} block {:?} is not known to fork choice",
+                name, checkpoint.root
+            ));
+        }
+
+        let block_in_db = store
+            .get_block(&checkpoint.root)
+            .map_err(|e| format!("DB error when reading {} block: {:?}", name, e))?
+            .is_some();
+
+        if !block_in_db {
+            return Err(format!(
+                "The {} block {:?} is not in the database",
+                name, checkpoint.root
+            ));
+        }
+    }
+
+    Ok(fork_choice)
+}
+
 fn genesis_block<T: EthSpec>(
     genesis_state: &mut BeaconState<T>,
     spec: &ChainSpec,
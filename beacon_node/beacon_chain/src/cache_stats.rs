@@ -0,0 +1,90 @@
+//! Counts the hits and misses of the in-memory caches of the `BeaconChain`, so that their
+//! effectiveness can be inspected via the HTTP API.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The number of lookups of a cache which found (hits) and did not find (misses) an entry.
+///
+/// Atomic so that lookups via a shared reference (e.g., under a read lock) can be counted.
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheStats {
+    /// Counts the result of a lookup, returning it unchanged.
+    pub fn record<T>(&self, result: Option<T>) -> Option<T> {
+        let counter = if result.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+/// The size and lookup statistics of a cache.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheInfo {
+    /// The number of entries in the cache.
+    pub len: usize,
+    /// The maximum number of entries in the cache, if it is bounded.
+    pub capacity: Option<usize>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheInfo {
+    pub fn new(len: usize, capacity: Option<usize>, stats: &CacheStats) -> Self {
+        Self {
+            len,
+            capacity,
+            hits: stats.hits(),
+            misses: stats.misses(),
+        }
+    }
+}
+
+/// The `CacheInfo` of each of the `BeaconChain` caches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachesInfo {
+    pub snapshot_cache: CacheInfo,
+    pub shuffling_cache: CacheInfo,
+    pub validator_pubkey_cache: CacheInfo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let stats = CacheStats::default();
+
+        assert_eq!(stats.record(Some(1)), Some(1));
+        assert_eq!(stats.record(Some(2)), Some(2));
+        assert_eq!(stats.record::<u8>(None), None);
+
+        assert_eq!(stats.hits(), 2);
+        assert_eq!(stats.misses(), 1);
+        assert_eq!(
+            CacheInfo::new(3, Some(4), &stats),
+            CacheInfo {
+                len: 3,
+                capacity: Some(4),
+                hits: 2,
+                misses: 1
+            }
+        );
+    }
+}
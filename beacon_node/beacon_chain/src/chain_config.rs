@@ -1,8 +1,21 @@
 use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+use types::Slot;
 
 /// There is a 693 block skip in the current canonical Medalla chain, we use 700 to be safe.
 pub const DEFAULT_IMPORT_BLOCK_MAX_SKIP_SLOTS: u64 = 700;
 
+/// Allows for a little over two days of blocks without finality.
+pub const DEFAULT_FORK_CHOICE_MAX_NODES: usize = 16_384;
+
+/// Defined here:
+///
+/// https://github.com/ethereum/consensus-specs/blob/v1.1.10/sync/optimistic.md#constants
+pub const DEFAULT_SAFE_SLOTS_TO_IMPORT_OPTIMISTICALLY: u64 = 128;
+
+/// Leaves most of the first third of a slot to sign and propagate the block.
+pub const DEFAULT_ATTESTATION_PACKING_DEADLINE: Duration = Duration::from_millis(500);
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ChainConfig {
     /// Maximum number of slots to skip when importing a consensus message (e.g., block,
@@ -10,12 +23,36 @@ pub struct ChainConfig {
     ///
     /// If `None`, there is no limit.
     pub import_max_skip_slots: Option<u64>,
+    /// Maximum number of blocks in the fork choice DAG. When exceeded, all blocks which do not
+    /// descend from the finalized block are pruned.
+    ///
+    /// This is not a bound on the size of the DAG: blocks which descend from the finalized block
+    /// are always retained, so the DAG continues to grow whilst finality is not advancing.
+    ///
+    /// If `None`, there is no limit.
+    pub fork_choice_max_nodes: Option<usize>,
+    /// The number of slots which must have passed since a block's slot before the block may be
+    /// imported without the execution engine having validated its payload, unless its parent
+    /// contains an execution payload.
+    pub safe_slots_to_import_optimistically: u64,
+    /// The node shuts down once this slot has ended, if set. May be changed at runtime via
+    /// `BeaconChain::shutdown_slot`.
+    pub shutdown_after_slot: Option<Slot>,
+    /// The time allowed for selecting the attestations of a block during block production. Once
+    /// exceeded, the block is produced with the best attestations found so far.
+    ///
+    /// If `None`, there is no limit.
+    pub attestation_packing_deadline: Option<Duration>,
 }
 
 impl Default for ChainConfig {
     fn default() -> Self {
         Self {
             import_max_skip_slots: Some(DEFAULT_IMPORT_BLOCK_MAX_SKIP_SLOTS),
+            fork_choice_max_nodes: Some(DEFAULT_FORK_CHOICE_MAX_NODES),
+            safe_slots_to_import_optimistically: DEFAULT_SAFE_SLOTS_TO_IMPORT_OPTIMISTICALLY,
+            shutdown_after_slot: None,
+            attestation_packing_deadline: Some(DEFAULT_ATTESTATION_PACKING_DEADLINE),
         }
     }
 }
@@ -0,0 +1,242 @@
+//! Allows consumers outside of this crate (e.g., indexers, relays or the HTTP API) to be notified
+//! of imported blocks and changes to the head and finalized checkpoint of the `BeaconChain`,
+//! without polling.
+//!
+//! Each consumer is given the receiving end of a bounded channel. Notifications are never allowed
+//! to block the `BeaconChain`: if a consumer's channel is full the notification is dropped for
+//! that consumer (and counted in the `beacon_chain_hook_notifications_dropped_total` metric).
+//! Consumers are removed once their receiver is dropped.
+
+use crate::metrics;
+use environment::TaskExecutor;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use types::{Epoch, Graffiti, Hash256, Slot};
+
+/// A block was imported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockImport {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: u64,
+    pub graffiti: Graffiti,
+    /// The time after the start of the slot at which the block was received on gossip, if it was.
+    pub observed_delay: Option<Duration>,
+    /// The time after the start of the slot at which the block was imported.
+    pub imported_delay: Option<Duration>,
+}
+
+/// The head of the chain changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadChange {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub state_root: Hash256,
+    pub previous_block_root: Hash256,
+    /// `true` if the new head does not descend from the previous head.
+    pub reorg: bool,
+    /// The number of slots of the previous chain which were re-orged, if known.
+    pub reorg_distance: Option<u64>,
+}
+
+/// The finalized checkpoint of the chain changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Finalization {
+    pub epoch: Epoch,
+    pub root: Hash256,
+}
+
+/// The consumers of `BlockImport`, `HeadChange` and `Finalization` notifications.
+#[derive(Default)]
+pub struct ChainHooks {
+    block_senders: Mutex<Vec<Sender<BlockImport>>>,
+    head_senders: Mutex<Vec<Sender<HeadChange>>>,
+    finalization_senders: Mutex<Vec<Sender<Finalization>>>,
+}
+
+impl ChainHooks {
+    /// Returns a receiver of each subsequently imported block.
+    ///
+    /// At most `capacity` (or one, if `capacity` is zero) unreceived notifications are kept,
+    /// further notifications are dropped.
+    pub fn subscribe_block(&self, capacity: usize) -> Receiver<BlockImport> {
+        subscribe(&self.block_senders, capacity)
+    }
+
+    /// Returns a receiver of each subsequent change of head.
+    ///
+    /// At most `capacity` (or one, if `capacity` is zero) unreceived notifications are kept,
+    /// further notifications are dropped.
+    pub fn subscribe_head(&self, capacity: usize) -> Receiver<HeadChange> {
+        subscribe(&self.head_senders, capacity)
+    }
+
+    /// Returns a receiver of each subsequent change of finalized checkpoint.
+    ///
+    /// At most `capacity` (or one, if `capacity` is zero) unreceived notifications are kept,
+    /// further notifications are dropped.
+    pub fn subscribe_finalization(&self, capacity: usize) -> Receiver<Finalization> {
+        subscribe(&self.finalization_senders, capacity)
+    }
+
+    /// Spawns a task on `executor` which calls `callback` with each subsequently imported block.
+    ///
+    /// The next notification is not received until the future returned by `callback` completes.
+    pub fn on_block<F, R>(&self, executor: &TaskExecutor, capacity: usize, callback: F)
+    where
+        F: FnMut(BlockImport) -> R + Send + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        spawn_callback(
+            executor,
+            self.subscribe_block(capacity),
+            callback,
+            "block_hook",
+        )
+    }
+
+    /// Spawns a task on `executor` which calls `callback` with each subsequent change of head.
+    ///
+    /// The next notification is not received until the future returned by `callback` completes.
+    pub fn on_head_change<F, R>(&self, executor: &TaskExecutor, capacity: usize, callback: F)
+    where
+        F: FnMut(HeadChange) -> R + Send + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        spawn_callback(
+            executor,
+            self.subscribe_head(capacity),
+            callback,
+            "head_change_hook",
+        )
+    }
+
+    /// Spawns a task on `executor` which calls `callback` with each subsequent change of finalized
+    /// checkpoint.
+    ///
+    /// The next notification is not received until the future returned by `callback` completes.
+    pub fn on_finalization<F, R>(&self, executor: &TaskExecutor, capacity: usize, callback: F)
+    where
+        F: FnMut(Finalization) -> R + Send + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        spawn_callback(
+            executor,
+            self.subscribe_finalization(capacity),
+            callback,
+            "finalization_hook",
+        )
+    }
+
+    pub(crate) fn notify_block(&self, block_import: BlockImport) {
+        notify(&self.block_senders, block_import)
+    }
+
+    pub(crate) fn notify_head_change(&self, head_change: HeadChange) {
+        notify(&self.head_senders, head_change)
+    }
+
+    pub(crate) fn notify_finalization(&self, finalization: Finalization) {
+        notify(&self.finalization_senders, finalization)
+    }
+}
+
+fn subscribe<T>(senders: &Mutex<Vec<Sender<T>>>, capacity: usize) -> Receiver<T> {
+    // `mpsc::channel` panics if the capacity is zero.
+    let (sender, receiver) = mpsc::channel(std::cmp::max(capacity, 1));
+    senders.lock().push(sender);
+    receiver
+}
+
+fn spawn_callback<T, F, R>(
+    executor: &TaskExecutor,
+    mut receiver: Receiver<T>,
+    mut callback: F,
+    name: &'static str,
+) where
+    T: Send + 'static,
+    F: FnMut(T) -> R + Send + 'static,
+    R: Future<Output = ()> + Send + 'static,
+{
+    executor.spawn(
+        async move {
+            while let Some(notification) = receiver.recv().await {
+                callback(notification).await;
+            }
+        },
+        name,
+    )
+}
+
+/// Sends `notification` to each consumer which has room for it, removing consumers which have
+/// dropped their receiver.
+fn notify<T: Clone>(senders: &Mutex<Vec<Sender<T>>>, notification: T) {
+    let mut senders = senders.lock();
+    *senders = senders
+        .drain(..)
+        .filter_map(|mut sender| match sender.try_send(notification.clone()) {
+            Ok(()) => Some(sender),
+            Err(TrySendError::Full(_)) => {
+                metrics::inc_counter(&metrics::HOOK_NOTIFICATIONS_DROPPED);
+                Some(sender)
+            }
+            Err(TrySendError::Closed(_)) => None,
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finalization(epoch: u64) -> Finalization {
+        Finalization {
+            epoch: Epoch::new(epoch),
+            root: Hash256::from_low_u64_be(epoch),
+        }
+    }
+
+    #[test]
+    fn drops_when_full_and_removes_closed() {
+        let hooks = ChainHooks::default();
+
+        let mut receiver = hooks.subscribe_finalization(2);
+        let closed = hooks.subscribe_finalization(2);
+        drop(closed);
+
+        for epoch in 1..=3 {
+            hooks.notify_finalization(finalization(epoch));
+        }
+
+        assert_eq!(
+            hooks.finalization_senders.lock().len(),
+            1,
+            "should remove the closed consumer"
+        );
+        assert_eq!(receiver.try_recv().ok(), Some(finalization(1)));
+        assert_eq!(receiver.try_recv().ok(), Some(finalization(2)));
+        assert!(
+            receiver.try_recv().is_err(),
+            "should drop the notification sent while full"
+        );
+
+        hooks.notify_finalization(finalization(4));
+        assert_eq!(receiver.try_recv().ok(), Some(finalization(4)));
+    }
+
+    #[test]
+    fn zero_capacity_keeps_one_notification() {
+        let hooks = ChainHooks::default();
+
+        let mut receiver = hooks.subscribe_finalization(0);
+
+        for epoch in 1..=2 {
+            hooks.notify_finalization(finalization(epoch));
+        }
+
+        assert_eq!(receiver.try_recv().ok(), Some(finalization(1)));
+        assert!(receiver.try_recv().is_err());
+    }
+}
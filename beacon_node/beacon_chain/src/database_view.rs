@@ -0,0 +1,133 @@
+//! Provides read-only access to the database of a beacon node which is not running.
+//!
+//! Unlike a `BeaconChain`, a `DatabaseView` requires no slot clock, eth1 backend or network, so it
+//! is suitable for analysis tools (e.g., `lighthouse db`) which only need to query the blocks and
+//! states that the beacon node has stored.
+
+use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
+use crate::persisted_beacon_chain::PersistedBeaconChain;
+use slog::Logger;
+use std::path::Path;
+use std::sync::Arc;
+use store::{HotColdDB, ItemStore, LevelDB, RawEntryIter, StoreConfig};
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// A read-only view of the blocks and states in a beacon node database.
+pub struct DatabaseView<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    head_block_root: Hash256,
+    genesis_block_root: Hash256,
+}
+
+impl<E: EthSpec> DatabaseView<E, LevelDB<E>, LevelDB<E>> {
+    /// Opens the existing on-disk database with the given paths to the hot and cold DBs.
+    ///
+    /// Returns an error if there is no database at either path, or if it does not contain a
+    /// persisted beacon chain.
+    pub fn open(
+        hot_path: &Path,
+        cold_path: &Path,
+        config: StoreConfig,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let store = HotColdDB::open_existing(hot_path, cold_path, config, spec, log)
+            .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+        Self::from_store(Arc::new(store))
+    }
+}
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> DatabaseView<E, Hot, Cold> {
+    /// Instantiates a view of `store`, which must contain a persisted beacon chain.
+    pub fn from_store(store: Arc<HotColdDB<E, Hot, Cold>>) -> Result<Self, String> {
+        let chain = store
+            .get_item::<PersistedBeaconChain>(&Hash256::from_slice(&BEACON_CHAIN_DB_KEY))
+            .map_err(|e| format!("DB error when reading persisted beacon chain: {:?}", e))?
+            .ok_or_else(|| "No persisted beacon chain found in store".to_string())?;
+
+        Ok(Self {
+            store,
+            head_block_root: chain.canonical_head_block_root,
+            genesis_block_root: chain.genesis_block_root,
+        })
+    }
+
+    /// Returns the root of the canonical head block when the beacon node last persisted its head.
+    pub fn head_block_root(&self) -> Hash256 {
+        self.head_block_root
+    }
+
+    /// Returns the root of the genesis block.
+    pub fn genesis_block_root(&self) -> Hash256 {
+        self.genesis_block_root
+    }
+
+    /// Returns the slot at which the database is split between the hot and cold DBs. States
+    /// prior to this slot are finalized.
+    pub fn split_slot(&self) -> Slot {
+        self.store.get_split_slot()
+    }
+
+    /// Iterate through every raw key and value in the hot DB. See `KeyValueStore::iter_raw`.
+    pub fn iter_hot_raw(&self) -> RawEntryIter<'_> {
+        self.store.iter_hot_raw()
+    }
+
+    /// Iterate through every raw key and value in the cold DB. See `KeyValueStore::iter_raw`.
+    pub fn iter_cold_raw(&self) -> RawEntryIter<'_> {
+        self.store.iter_cold_raw()
+    }
+
+    /// Copy the hot and cold DBs into new databases in `dir`, returning the number of entries
+    /// copied from each. See `HotColdDB::backup`.
+    pub fn backup(&self, dir: &Path) -> Result<(usize, usize), String> {
+        self.store
+            .backup(dir)
+            .map_err(|e| format!("Unable to back up database: {:?}", e))
+    }
+
+    /// Returns the block with `block_root`, if it is known.
+    pub fn get_block(&self, block_root: &Hash256) -> Result<Option<SignedBeaconBlock<E>>, String> {
+        self.store
+            .get_block(block_root)
+            .map_err(|e| format!("DB error when reading block {:?}: {:?}", block_root, e))
+    }
+
+    /// Returns the state with `state_root`, if it is known.
+    ///
+    /// Providing the `slot` of the state allows it to be found more efficiently.
+    pub fn get_state(
+        &self,
+        state_root: &Hash256,
+        slot: Option<Slot>,
+    ) -> Result<Option<BeaconState<E>>, String> {
+        self.store
+            .get_state(state_root, slot)
+            .map_err(|e| format!("DB error when reading state {:?}: {:?}", state_root, e))
+    }
+
+    /// Returns the finalized state at `slot`, if it is known.
+    pub fn get_finalized_state_by_slot(
+        &self,
+        slot: Slot,
+    ) -> Result<Option<BeaconState<E>>, String> {
+        self.store
+            .get_finalized_state_by_slot(slot)
+            .map_err(|e| format!("DB error when reading state at slot {}: {:?}", slot, e))
+    }
+
+    /// Returns the canonical head block.
+    pub fn head_block(&self) -> Result<SignedBeaconBlock<E>, String> {
+        self.get_block(&self.head_block_root)?
+            .ok_or_else(|| "Head block not found in store".to_string())
+    }
+
+    /// Returns the state of the canonical head block.
+    pub fn head_state(&self) -> Result<BeaconState<E>, String> {
+        let head_block = self.head_block()?;
+
+        self.get_state(&head_block.state_root(), Some(head_block.slot()))?
+            .ok_or_else(|| "Head state not found in store".to_string())
+    }
+}
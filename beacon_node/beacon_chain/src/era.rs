@@ -0,0 +1,326 @@
+//! Reads and writes era files, which archive the finalized blocks of a period of
+//! `SLOTS_PER_HISTORICAL_ROOT` slots along with a canonical state.
+//!
+//! Era files allow the history of the chain to be distributed out-of-band, so that a new node can
+//! import it from disk rather than downloading it from the network.
+//!
+//! An era file is a sequence of e2store entries: a version entry, the blocks of the era in
+//! order of increasing slot, then the state at the first slot of the next era. Blocks and states
+//! are SSZ-encoded, then snappy-compressed (framed format). Era `n` contains the blocks from slots
+//! `[(n - 1) * SLOTS_PER_HISTORICAL_ROOT, n * SLOTS_PER_HISTORICAL_ROOT)` and the state at slot
+//! `n * SLOTS_PER_HISTORICAL_ROOT`, whose `block_roots` commit to exactly those blocks.
+//!
+//! The slot indices which allow random access to the entries of an era file are not written, so
+//! files must be read in full.
+
+use crate::{BeaconChain, BeaconChainTypes, ChainSegmentResult};
+use snap::read::FrameDecoder;
+use snap::write::FrameEncoder;
+use ssz::{Decode, Encode};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use types::{BeaconState, EthSpec, SignedBeaconBlock, Slot};
+
+pub const ERA_FILE_EXTENSION: &str = "era";
+
+/// The length of the header of each e2store entry: a 2 byte type, a 4 byte little-endian length
+/// and 2 reserved bytes.
+const ENTRY_HEADER_LEN: usize = 8;
+
+const VERSION: [u8; 2] = [0x65, 0x32];
+const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+
+/// The blocks of an era, and the state at the start of the next era.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Era<E: EthSpec> {
+    pub blocks: Vec<SignedBeaconBlock<E>>,
+    pub state: BeaconState<E>,
+}
+
+impl<E: EthSpec> Era<E> {
+    /// Returns the number of the era, i.e., the slot of the state divided by
+    /// `SLOTS_PER_HISTORICAL_ROOT`.
+    pub fn number(&self) -> u64 {
+        self.state.slot.as_u64() / E::slots_per_historical_root() as u64
+    }
+
+    /// Returns the first slot of era `number`, i.e., the slot of the state of era `number - 1`.
+    pub fn start_slot(number: u64) -> Slot {
+        Slot::new(number.saturating_sub(1) * E::slots_per_historical_root() as u64)
+    }
+
+    /// Returns the file name of the era, e.g. `00012-4b363db9.era`, where the hex string is a
+    /// prefix of the state root.
+    pub fn file_name(&self) -> String {
+        let state_root = self.state.canonical_root();
+        format!(
+            "{:05}-{}.{}",
+            self.number(),
+            state_root.as_bytes()[..4]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>(),
+            ERA_FILE_EXTENSION
+        )
+    }
+
+    /// Returns an error if a block is not committed to by the `block_roots` of the state, or if
+    /// the state is not at the start of an era.
+    pub fn verify(&self) -> Result<(), String> {
+        if self.state.slot % E::slots_per_historical_root() as u64 != 0 {
+            return Err(format!(
+                "State at slot {} is not at the start of an era",
+                self.state.slot
+            ));
+        }
+
+        let start_slot = Self::start_slot(self.number());
+        for block in &self.blocks {
+            if block.slot() < start_slot {
+                return Err(format!(
+                    "Block at slot {} is prior to era {}",
+                    block.slot(),
+                    self.number()
+                ));
+            }
+
+            let expected_root = self.state.get_block_root(block.slot()).map_err(|e| {
+                format!("Block at slot {} is outside the era: {:?}", block.slot(), e)
+            })?;
+            if block.canonical_root() != *expected_root {
+                return Err(format!(
+                    "Block at slot {} does not match the block roots of the state",
+                    block.slot()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encodes the era as e2store entries.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = vec![];
+        write_entry(&mut bytes, VERSION, &[])?;
+        for block in &self.blocks {
+            write_entry(
+                &mut bytes,
+                COMPRESSED_SIGNED_BEACON_BLOCK,
+                &compress(&block.as_ssz_bytes())?,
+            )?;
+        }
+        write_entry(
+            &mut bytes,
+            COMPRESSED_BEACON_STATE,
+            &compress(&self.state.as_ssz_bytes())?,
+        )?;
+        Ok(bytes)
+    }
+
+    /// Decodes an era from e2store entries. Entries of unknown types are ignored.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, String> {
+        let (entry_type, _) = read_entry(&mut bytes)?;
+        if entry_type != VERSION {
+            return Err("Era does not begin with a version entry".to_string());
+        }
+
+        let mut blocks = vec![];
+        let mut state = None;
+        while !bytes.is_empty() {
+            let (entry_type, data) = read_entry(&mut bytes)?;
+            match entry_type {
+                COMPRESSED_SIGNED_BEACON_BLOCK if state.is_none() => {
+                    let block = SignedBeaconBlock::from_ssz_bytes(&decompress(data)?)
+                        .map_err(|e| format!("Unable to decode block: {:?}", e))?;
+                    blocks.push(block);
+                }
+                COMPRESSED_BEACON_STATE if state.is_none() => {
+                    let decoded = BeaconState::from_ssz_bytes(&decompress(data)?)
+                        .map_err(|e| format!("Unable to decode state: {:?}", e))?;
+                    state = Some(decoded);
+                }
+                COMPRESSED_SIGNED_BEACON_BLOCK | COMPRESSED_BEACON_STATE => {
+                    return Err("Era contains entries after its state".to_string())
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            blocks,
+            state: state.ok_or_else(|| "Era does not contain a state".to_string())?,
+        })
+    }
+
+    /// Writes the era to a new file in `dir`, returning its path.
+    ///
+    /// Existing files are never overwritten.
+    pub fn write(&self, dir: &Path) -> Result<PathBuf, String> {
+        let path = dir.join(self.file_name());
+        if path.exists() {
+            return Err(format!("Refusing to overwrite {:?}", path));
+        }
+
+        fs::create_dir_all(dir).map_err(|e| format!("Unable to create {:?}: {:?}", dir, e))?;
+        fs::write(&path, self.to_bytes()?)
+            .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))?;
+
+        Ok(path)
+    }
+
+    /// Reads the era in the file at `path`.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+        Self::from_bytes(&bytes).map_err(|e| format!("Invalid era file {:?}: {}", path, e))
+    }
+}
+
+/// Returns the paths of the era files in `dir`, in order of increasing era.
+pub fn era_file_paths(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths = fs::read_dir(dir)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?;
+    paths.retain(|path| {
+        path.extension()
+            .map_or(false, |ext| ext == ERA_FILE_EXTENSION)
+    });
+    // The era number is zero-padded, so the file names sort by era.
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Imports the blocks of each era file in `dir` into `chain`, in order of increasing era,
+/// returning the number of blocks imported.
+///
+/// Each block is fully verified, as if it were downloaded from the network. Blocks which are
+/// already known are skipped, so an import may be resumed.
+pub fn import_era_files<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    dir: &Path,
+) -> Result<usize, String> {
+    let mut total_imported = 0;
+
+    for path in era_file_paths(dir)? {
+        let era = Era::<T::EthSpec>::read(&path)?;
+        era.verify()
+            .map_err(|e| format!("Invalid era file {:?}: {}", path, e))?;
+        let number = era.number();
+
+        let imported_blocks = match chain.process_chain_segment(era.blocks) {
+            ChainSegmentResult::Successful { imported_blocks } => imported_blocks,
+            ChainSegmentResult::Failed {
+                imported_blocks,
+                error,
+            } => {
+                return Err(format!(
+                    "Unable to import era {} after {} blocks: {:?}",
+                    number,
+                    total_imported + imported_blocks,
+                    error
+                ))
+            }
+        };
+        total_imported += imported_blocks;
+
+        if imported_blocks > 0 {
+            chain
+                .fork_choice()
+                .map_err(|e| format!("Unable to run fork choice: {:?}", e))?;
+        }
+
+        info!(
+            chain.log,
+            "Imported era file";
+            "era" => number,
+            "imported_blocks" => imported_blocks,
+        );
+    }
+
+    Ok(total_imported)
+}
+
+fn write_entry(bytes: &mut Vec<u8>, entry_type: [u8; 2], data: &[u8]) -> Result<(), String> {
+    if data.len() > u32::max_value() as usize {
+        return Err(format!("Entry of {} bytes is too large", data.len()));
+    }
+
+    bytes.extend_from_slice(&entry_type);
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&[0, 0]);
+    bytes.extend_from_slice(data);
+    Ok(())
+}
+
+/// Reads the entry at the start of `bytes`, advancing `bytes` past it.
+fn read_entry<'a>(bytes: &mut &'a [u8]) -> Result<([u8; 2], &'a [u8]), String> {
+    if bytes.len() < ENTRY_HEADER_LEN {
+        return Err("Truncated entry header".to_string());
+    }
+
+    let entry_type = [bytes[0], bytes[1]];
+    let mut len = [0; 4];
+    len.copy_from_slice(&bytes[2..6]);
+    let len = u32::from_le_bytes(len) as usize;
+
+    let end = ENTRY_HEADER_LEN
+        .checked_add(len)
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| format!("Truncated entry of {} bytes", len))?;
+    let data = &bytes[ENTRY_HEADER_LEN..end];
+    *bytes = &bytes[end..];
+
+    Ok((entry_type, data))
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = FrameEncoder::new(vec![]);
+    encoder
+        .write_all(data)
+        .map_err(|e| format!("Unable to compress: {:?}", e))?;
+    encoder
+        .into_inner()
+        .map_err(|e| format!("Unable to compress: {:?}", e))
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+    FrameDecoder::new(data)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Unable to decompress: {:?}", e))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_round_trip() {
+        let mut bytes = vec![];
+        write_entry(&mut bytes, VERSION, &[]).unwrap();
+        write_entry(&mut bytes, COMPRESSED_BEACON_STATE, &[1, 2, 3]).unwrap();
+
+        let mut remaining = &bytes[..];
+        assert_eq!(read_entry(&mut remaining).unwrap(), (VERSION, &[][..]));
+        assert_eq!(
+            read_entry(&mut remaining).unwrap(),
+            (COMPRESSED_BEACON_STATE, &[1, 2, 3][..])
+        );
+        assert!(remaining.is_empty());
+
+        let mut truncated = &bytes[..bytes.len() - 1];
+        read_entry(&mut truncated).unwrap();
+        assert!(read_entry(&mut truncated).is_err());
+    }
+
+    #[test]
+    fn compression_round_trip() {
+        let data = vec![42; 1024];
+        assert_eq!(decompress(&compress(&data).unwrap()).unwrap(), data);
+    }
+}
@@ -68,6 +68,7 @@ pub enum BeaconChainError {
     SszTypesError(SszTypesError),
     CanonicalHeadLockTimeout,
     AttestationCacheLockTimeout,
+    SnapshotCacheLockTimeout,
     ValidatorPubkeyCacheLockTimeout,
     IncorrectStateForAttestation(RelativeEpochError),
     InvalidValidatorPubkeyBytes(bls::Error),
@@ -83,6 +84,7 @@ pub enum BeaconChainError {
     ObservedBlockProducersError(ObservedBlockProducersError),
     PruningError(PruningError),
     ArithError(ArithError),
+    ExecutionLayerError(execution_layer::Error),
 }
 
 easy_from_to!(SlotProcessingError, BeaconChainError);
@@ -113,6 +115,11 @@ pub enum BlockProductionError {
     /// The `BeaconChain` was explicitly configured _without_ a connection to eth1, therefore it
     /// cannot produce blocks.
     NoEth1ChainConnection,
+    /// The randao reveal was not signed by the proposer of the block.
+    InvalidRandaoReveal,
+    /// The public key of the proposer with the given index is not known.
+    UnknownProposerPubkey(usize),
+    BeaconChainError(BeaconChainError),
 }
 
 easy_from_to!(BlockProcessingError, BlockProductionError);
@@ -1,6 +1,6 @@
 use crate::metrics;
 use environment::TaskExecutor;
-use eth1::{Config as Eth1Config, Eth1Block, Service as HttpService};
+use eth1::{Config as Eth1Config, DepositTreeSnapshot, Eth1Block, Service as HttpService};
 use eth2_hashing::hash;
 use slog::{debug, error, trace, Logger};
 use ssz::{Decode, Encode};
@@ -143,6 +143,16 @@ where
         }
     }
 
+    /// Returns a snapshot of the deposit tree containing the first `deposit_count` deposits, if
+    /// they are known.
+    pub fn deposit_snapshot(&self, deposit_count: u64) -> Option<DepositTreeSnapshot> {
+        if self.use_dummy_backend {
+            None
+        } else {
+            self.backend.deposit_snapshot(deposit_count)
+        }
+    }
+
     /// Instantiate `Eth1Chain` from a persisted `SszEth1`.
     ///
     /// The `Eth1Chain` will have the same caches as the persisted `SszEth1`.
@@ -195,6 +205,12 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
         spec: &ChainSpec,
     ) -> Result<Vec<Deposit>, Error>;
 
+    /// Returns a snapshot of the deposit tree containing the first `deposit_count` deposits, if
+    /// they are known.
+    fn deposit_snapshot(&self, _deposit_count: u64) -> Option<DepositTreeSnapshot> {
+        None
+    }
+
     /// Encode the `Eth1ChainBackend` instance to bytes.
     fn as_bytes(&self) -> Vec<u8>;
 
@@ -405,6 +421,15 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         self.core.as_bytes()
     }
 
+    fn deposit_snapshot(&self, deposit_count: u64) -> Option<DepositTreeSnapshot> {
+        self.core
+            .deposits()
+            .read()
+            .cache
+            .get_snapshot(deposit_count)
+            .ok()
+    }
+
     /// Recover the cached backend from encoded bytes.
     fn from_bytes(
         bytes: &[u8],
@@ -599,6 +624,7 @@ mod test {
             DepositLog {
                 deposit_data,
                 block_number: i,
+                block_hash: Hash256::from_low_u64_be(i),
                 index: i,
                 signature_is_valid: true,
             }
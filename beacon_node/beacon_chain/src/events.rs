@@ -1,14 +1,28 @@
-use bus::Bus;
-use parking_lot::Mutex;
 use serde_derive::{Deserialize, Serialize};
-use slog::{error, Logger};
+use slog::{warn, Logger};
 use std::marker::PhantomData;
-use std::sync::Arc;
-use types::{Attestation, Epoch, EthSpec, Hash256, SignedBeaconBlock, SignedBeaconBlockHash};
+use types::{
+    Attestation, AttesterSlashing, Epoch, EthSpec, Hash256, ProposerSlashing, SignedBeaconBlock,
+};
+pub use webhook_notifier::{WebhookEvent, WebhookSender};
 pub use websocket_server::WebSocketSender;
 
 pub trait EventHandler<T: EthSpec>: Sized + Send + Sync {
     fn register(&self, kind: EventKind<T>) -> Result<(), String>;
+
+    /// Called when the head advances to a new `epoch`, with the validator balances of the
+    /// `previous` and `current` head states.
+    ///
+    /// The balances are not sent as an `EventKind`, since they are too large to serialize for
+    /// every subscriber.
+    fn epoch_balances(
+        &self,
+        _epoch: Epoch,
+        _previous: &[u64],
+        _current: &[u64],
+    ) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 pub struct NullEventHandler<T: EthSpec>(PhantomData<T>);
@@ -22,80 +36,106 @@ impl<T: EthSpec> EventHandler<T> for WebSocketSender<T> {
     }
 }
 
-pub struct ServerSentEvents<T: EthSpec> {
-    // Bus<> is itself Sync + Send.  We use Mutex<> here only because of the surrounding code does
-    // not enforce mutability statically (i.e. relies on interior mutability).
-    head_changed_queue: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
-    log: Logger,
-    _phantom: PhantomData<T>,
-}
-
-impl<T: EthSpec> ServerSentEvents<T> {
-    pub fn new(log: Logger) -> (Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>) {
-        let bus = Bus::new(T::slots_per_epoch() as usize);
-        let mutex = Mutex::new(bus);
-        let arc = Arc::new(mutex);
-        let this = Self {
-            head_changed_queue: arc.clone(),
-            log,
-            _phantom: PhantomData,
-        };
-        (this, arc)
-    }
-}
-
-impl<T: EthSpec> EventHandler<T> for ServerSentEvents<T> {
+impl<T: EthSpec> EventHandler<T> for WebhookSender {
     fn register(&self, kind: EventKind<T>) -> Result<(), String> {
         match kind {
             EventKind::BeaconHeadChanged {
+                reorg_distance: Some(distance),
+                previous_head_beacon_block_root,
                 current_head_beacon_block_root,
                 ..
-            } => {
-                let mut guard = self.head_changed_queue.lock();
-                if guard
-                    .try_broadcast(current_head_beacon_block_root.into())
-                    .is_err()
-                {
-                    error!(
-                        self.log,
-                        "Head change streaming queue full";
-                        "dropped_change" => format!("{}", current_head_beacon_block_root),
-                    );
-                }
+            } => self.reorg(
+                distance,
+                previous_head_beacon_block_root,
+                current_head_beacon_block_root,
+            ),
+            EventKind::BeaconFinalization { epoch, .. } => {
+                self.finalized(epoch);
                 Ok(())
             }
+            EventKind::BeaconProposerSlashingImported { proposer_slashing } => {
+                self.send(WebhookEvent::ProposerSlashing {
+                    proposer_index: proposer_slashing.signed_header_1.message.proposer_index,
+                })
+            }
+            EventKind::BeaconAttesterSlashingImported { attester_slashing } => {
+                let indices_2 = &attester_slashing.attestation_2.attesting_indices;
+                let attester_indices = attester_slashing
+                    .attestation_1
+                    .attesting_indices
+                    .iter()
+                    .filter(|index| indices_2.iter().any(|other| other == *index))
+                    .copied()
+                    .collect();
+
+                self.send(WebhookEvent::AttesterSlashing { attester_indices })
+            }
             _ => Ok(()),
         }
     }
+
+    fn epoch_balances(
+        &self,
+        epoch: Epoch,
+        previous: &[u64],
+        current: &[u64],
+    ) -> Result<(), String> {
+        self.balances(epoch, previous, current)
+    }
 }
 
-// An event handler that pushes events to both the websockets handler and the SSE handler.
-// Named after the unix `tee` command.  Meant as a temporary solution before ditching WebSockets
-// completely once SSE functions well enough.
+// An event handler that pushes events to the websockets handler and the webhook handler. Named
+// after the unix `tee` command. Meant as a temporary solution before ditching WebSockets completely
+// in favour of the `ChainHooks`.
 pub struct TeeEventHandler<E: EthSpec> {
     websockets_handler: WebSocketSender<E>,
-    sse_handler: ServerSentEvents<E>,
+    webhook_handler: WebhookSender,
+    log: Logger,
 }
 
 impl<E: EthSpec> TeeEventHandler<E> {
-    #[allow(clippy::type_complexity)]
     pub fn new(
-        log: Logger,
         websockets_handler: WebSocketSender<E>,
-    ) -> Result<(Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>), String> {
-        let (sse_handler, bus) = ServerSentEvents::new(log);
-        let result = Self {
+        webhook_handler: WebhookSender,
+        log: Logger,
+    ) -> Self {
+        Self {
             websockets_handler,
-            sse_handler,
-        };
-        Ok((result, bus))
+            webhook_handler,
+            log,
+        }
     }
 }
 
 impl<E: EthSpec> EventHandler<E> for TeeEventHandler<E> {
     fn register(&self, kind: EventKind<E>) -> Result<(), String> {
-        self.websockets_handler.register(kind.clone())?;
-        self.sse_handler.register(kind)?;
+        // Webhooks are an optional extra, so a failure to queue one is logged rather than
+        // returned, and never stops the event reaching the other handlers.
+        if let Err(e) = self.webhook_handler.register(kind.clone()) {
+            warn!(
+                self.log,
+                "Unable to queue webhook event";
+                "error" => e
+            );
+        }
+
+        self.websockets_handler.register(kind)
+    }
+
+    fn epoch_balances(
+        &self,
+        epoch: Epoch,
+        previous: &[u64],
+        current: &[u64],
+    ) -> Result<(), String> {
+        if let Err(e) = self.webhook_handler.balances(epoch, previous, current) {
+            warn!(
+                self.log,
+                "Unable to queue webhook event";
+                "error" => e
+            );
+        }
+
         Ok(())
     }
 }
@@ -122,6 +162,8 @@ impl<T: EthSpec> Default for NullEventHandler<T> {
 pub enum EventKind<T: EthSpec> {
     BeaconHeadChanged {
         reorg: bool,
+        /// The number of slots of the previous chain which were re-orged, if known.
+        reorg_distance: Option<u64>,
         current_head_beacon_block_root: Hash256,
         previous_head_beacon_block_root: Hash256,
     },
@@ -144,4 +186,10 @@ pub enum EventKind<T: EthSpec> {
         reason: String,
         attestation: Box<Attestation<T>>,
     },
+    BeaconProposerSlashingImported {
+        proposer_slashing: Box<ProposerSlashing>,
+    },
+    BeaconAttesterSlashingImported {
+        attester_slashing: Box<AttesterSlashing<T>>,
+    },
 }
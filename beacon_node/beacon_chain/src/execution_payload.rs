@@ -0,0 +1,162 @@
+//! The points at which block production, block import and fork choice interact with the
+//! execution engine.
+//!
+//! No beacon block contains an execution payload until the merge fork is introduced, so none of
+//! these functions contact the execution engine yet. They exist so that the payload handling can
+//! be filled in without restructuring the callers.
+
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, BlockProductionError};
+use execution_layer::{ExecutionPayload, PayloadStatusKind};
+use fork_choice::PayloadVerificationStatus;
+use slog::{debug, warn};
+use types::{BeaconBlock, BeaconState, EthSpec, Hash256};
+
+/// Returns the execution payload included in `block`, if any.
+///
+/// Always `None`, since blocks do not yet contain execution payloads.
+pub fn get_execution_payload<E: EthSpec>(_block: &BeaconBlock<E>) -> Option<&ExecutionPayload> {
+    None
+}
+
+/// Verifies the execution payload of `block` (whose parent is `parent`) with the execution
+/// engine, if the block has one.
+///
+/// Payloads which the execution engine has not yet validated (e.g., because it is syncing) are
+/// accepted if the block may be imported optimistically. Such blocks are marked as optimistic in
+/// fork choice.
+pub fn notify_new_payload<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    parent: &BeaconBlock<T::EthSpec>,
+    block: &BeaconBlock<T::EthSpec>,
+) -> Result<PayloadVerificationStatus, BlockError<T::EthSpec>> {
+    let payload = match get_execution_payload(block) {
+        Some(payload) => payload,
+        None => return Ok(PayloadVerificationStatus::Irrelevant),
+    };
+
+    let status = match &chain.execution_layer {
+        Some(execution_layer) => Some(
+            execution_layer
+                .block_on(|execution_layer| execution_layer.notify_new_payload(payload))
+                .map_err(BeaconChainError::ExecutionLayerError)?,
+        ),
+        // Without an execution engine the payload can only be imported optimistically.
+        None => None,
+    };
+
+    match status {
+        Some(status) if status.status == PayloadStatusKind::Valid => {
+            Ok(PayloadVerificationStatus::Verified)
+        }
+        Some(status)
+            if status.status == PayloadStatusKind::Invalid
+                || status.status == PayloadStatusKind::InvalidBlockHash =>
+        {
+            Err(BlockError::ExecutionPayloadInvalid(status))
+        }
+        _ => {
+            check_optimistic_import_permitted(chain, parent, block)?;
+            Ok(PayloadVerificationStatus::NotVerified)
+        }
+    }
+}
+
+/// Returns an error unless `block` may be imported before its execution payload has been
+/// validated.
+///
+/// ## Specification
+///
+/// Equivalent to:
+///
+/// https://github.com/ethereum/consensus-specs/blob/v1.1.10/sync/optimistic.md#helpers
+fn check_optimistic_import_permitted<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    parent: &BeaconBlock<T::EthSpec>,
+    block: &BeaconBlock<T::EthSpec>,
+) -> Result<(), BlockError<T::EthSpec>> {
+    if get_execution_payload(parent).is_some() {
+        return Ok(());
+    }
+
+    let current_slot = chain.slot()?;
+    if block.slot + chain.config.safe_slots_to_import_optimistically <= current_slot {
+        Ok(())
+    } else {
+        Err(BlockError::OptimisticImportNotPermitted {
+            block_slot: block.slot,
+            current_slot,
+        })
+    }
+}
+
+/// Returns the execution payload to include in a block produced on `state`, if any.
+///
+/// Always `None`, since blocks cannot yet contain execution payloads.
+pub fn get_execution_payload_for_production<T: BeaconChainTypes>(
+    _chain: &BeaconChain<T>,
+    _state: &BeaconState<T::EthSpec>,
+) -> Result<Option<ExecutionPayload>, BlockProductionError> {
+    Ok(None)
+}
+
+/// Informs the execution engine of the execution blocks of the new head and finalized beacon
+/// blocks, if the head block contains an execution payload.
+///
+/// The update is sent from a separate task, so that fork choice is not delayed by the execution
+/// engine. Failures are logged.
+pub fn update_execution_engine_forkchoice<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    head_block_root: Hash256,
+    finalized_block_root: Hash256,
+) -> Result<(), BeaconChainError> {
+    let execution_layer = match &chain.execution_layer {
+        Some(execution_layer) => execution_layer.clone(),
+        None => return Ok(()),
+    };
+
+    let head_block_hash = match execution_block_hash(chain, head_block_root)? {
+        Some(hash) => hash,
+        // The head is before the merge, there is nothing to tell the execution engine.
+        None => return Ok(()),
+    };
+    let finalized_block_hash =
+        execution_block_hash(chain, finalized_block_root)?.unwrap_or_else(Hash256::zero);
+
+    let executor = execution_layer.executor().clone();
+    let log = chain.log.clone();
+    executor.spawn(
+        async move {
+            match execution_layer
+                .notify_forkchoice_updated(head_block_hash, finalized_block_hash, None)
+                .await
+            {
+                Ok(response) => debug!(
+                    log,
+                    "Execution engine forkchoice updated";
+                    "status" => format!("{:?}", response.payload_status.status),
+                    "head_block_hash" => format!("{:?}", head_block_hash),
+                ),
+                Err(e) => warn!(
+                    log,
+                    "Failed to update execution engine forkchoice";
+                    "error" => format!("{:?}", e),
+                    "head_block_hash" => format!("{:?}", head_block_hash),
+                ),
+            }
+        },
+        "execution_forkchoice_updated",
+    );
+
+    Ok(())
+}
+
+/// Returns the hash of the execution block included in the beacon block with `block_root`, if
+/// any.
+fn execution_block_hash<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block_root: Hash256,
+) -> Result<Option<Hash256>, BeaconChainError> {
+    Ok(chain
+        .get_block(&block_root)?
+        .and_then(|block| get_execution_payload(&block.message).map(|payload| payload.block_hash)))
+}
@@ -0,0 +1,146 @@
+//! Provides the `GossipObservations` ring buffer, which records the time at which recent blocks
+//! and aggregates were received on gossip.
+//!
+//! This allows the propagation latency of messages across the network to be studied without
+//! capturing packets. Messages are only recorded once they pass gossip verification, which rejects
+//! repeated messages, so only the first time each message is seen is recorded.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+/// The number of gossip messages for which observations are retained.
+///
+/// Enough for all the blocks and aggregates of a few epochs on a network with many validators.
+pub const DEFAULT_GOSSIP_OBSERVATIONS_SIZE: usize = 16_384;
+
+/// A message received on gossip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GossipMessage {
+    /// A `SignedBeaconBlock`.
+    Block {
+        block_root: Hash256,
+        proposer_index: u64,
+    },
+    /// A `SignedAggregateAndProof`.
+    Aggregate {
+        /// The root of the aggregate's `AttestationData`.
+        attestation_data_root: Hash256,
+        aggregator_index: u64,
+    },
+}
+
+/// The time at which a message was received on gossip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GossipObservation {
+    pub slot: Slot,
+    pub message: GossipMessage,
+    /// The time at which the message was received, as a duration since the UNIX epoch.
+    pub seen_timestamp: Duration,
+}
+
+/// Records the observations of the most recently received gossip messages.
+pub struct GossipObservations {
+    capacity: usize,
+    /// Oldest first.
+    observations: VecDeque<GossipObservation>,
+}
+
+impl Default for GossipObservations {
+    fn default() -> Self {
+        Self::new(DEFAULT_GOSSIP_OBSERVATIONS_SIZE)
+    }
+}
+
+impl GossipObservations {
+    /// Instantiates a new buffer which retains up to `capacity` observations.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            observations: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records `observation`, evicting the oldest observation if the buffer is full.
+    pub fn observe(&mut self, observation: GossipObservation) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.observations.len() >= self.capacity {
+            self.observations.pop_front();
+        }
+
+        self.observations.push_back(observation);
+    }
+
+    /// Iterates through all known observations, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &GossipObservation> {
+        self.observations.iter()
+    }
+
+    /// Iterates through the known observations of messages from `slot`, oldest first.
+    pub fn iter_slot(&self, slot: Slot) -> impl Iterator<Item = &GossipObservation> {
+        self.iter()
+            .filter(move |observation| observation.slot == slot)
+    }
+
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(i: u64) -> GossipObservation {
+        GossipObservation {
+            slot: Slot::new(i),
+            message: GossipMessage::Block {
+                block_root: Hash256::from_low_u64_be(i),
+                proposer_index: i,
+            },
+            seen_timestamp: Duration::from_secs(i),
+        }
+    }
+
+    fn aggregate(i: u64) -> GossipObservation {
+        GossipObservation {
+            slot: Slot::new(i),
+            message: GossipMessage::Aggregate {
+                attestation_data_root: Hash256::from_low_u64_be(i),
+                aggregator_index: i,
+            },
+            seen_timestamp: Duration::from_secs(i),
+        }
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let mut observations = GossipObservations::new(3);
+
+        for i in 0..2 {
+            observations.observe(block(i));
+            observations.observe(aggregate(i));
+        }
+
+        assert_eq!(observations.len(), 3);
+        assert_eq!(
+            observations.iter().copied().collect::<Vec<_>>(),
+            vec![aggregate(0), block(1), aggregate(1)]
+        );
+        assert_eq!(
+            observations
+                .iter_slot(Slot::new(1))
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![block(1), aggregate(1)]
+        );
+        assert_eq!(observations.iter_slot(Slot::new(2)).count(), 0);
+    }
+}
@@ -10,12 +10,19 @@ pub mod attestation_verification;
 mod beacon_chain;
 mod beacon_fork_choice_store;
 mod beacon_snapshot;
+pub mod block_times_cache;
 mod block_verification;
 pub mod builder;
+pub mod cache_stats;
 pub mod chain_config;
+pub mod chain_hooks;
+mod database_view;
+pub mod era;
 mod errors;
 pub mod eth1_chain;
 pub mod events;
+mod execution_payload;
+pub mod gossip_observations;
 mod head_tracker;
 mod metrics;
 pub mod migrate;
@@ -25,7 +32,8 @@ mod observed_attesters;
 mod observed_block_producers;
 pub mod observed_operations;
 mod persisted_beacon_chain;
-mod persisted_fork_choice;
+pub mod persisted_fork_choice;
+pub mod recent_proposals;
 mod shuffling_cache;
 mod snapshot_cache;
 pub mod test_utils;
@@ -34,10 +42,11 @@ mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult,
-    ForkChoiceError, StateSkipConfig,
+    ForkChoiceError, ProduceBlockVerification, StateSkipConfig,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
 pub use self::chain_config::ChainConfig;
+pub use self::database_view::DatabaseView;
 pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use attestation_verification::Error as AttestationError;
 pub use beacon_fork_choice_store::{BeaconForkChoiceStore, Error as ForkChoiceStoreError};
@@ -54,6 +54,30 @@ lazy_static! {
         "Time spent hashing and remembering all the attestations in the block"
     );
 
+    /*
+     * Block Import Timings
+     */
+    pub static ref BLOCK_OBSERVED_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_observed_slot_start_delay_seconds",
+        "Time between the start of the block's slot and the block being received from the network"
+    );
+    pub static ref BLOCK_SIGNATURE_VERIFIED_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_signature_verified_delay_seconds",
+        "Time between the block being received and its signatures being verified"
+    );
+    pub static ref BLOCK_STATE_TRANSITION_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_state_transition_delay_seconds",
+        "Time between the block's signatures being verified and the state transition completing"
+    );
+    pub static ref BLOCK_IMPORTED_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_imported_delay_seconds",
+        "Time between the state transition completing and the block being imported to fork choice and the database"
+    );
+    pub static ref BLOCK_HEAD_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_head_delay_seconds",
+        "Time between the block being imported and it becoming the head of the chain"
+    );
+
     /*
      * Block Production
      */
@@ -67,6 +91,14 @@ lazy_static! {
     );
     pub static ref BLOCK_PRODUCTION_TIMES: Result<Histogram> =
         try_create_histogram("beacon_block_production_seconds", "Full runtime of block production");
+    pub static ref BLOCK_PRODUCTION_ATTESTATION_PACKING_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_block_production_attestation_packing_seconds",
+        "Time taken to select the attestations for a block"
+    );
+    pub static ref BLOCK_PRODUCTION_ATTESTATION_PACKING_DEADLINE_REACHED: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_attestation_packing_deadline_reached_total",
+        "Count of blocks produced with fewer attestations because packing reached its deadline"
+    );
 
     /*
      * Block Statistics
@@ -218,6 +250,10 @@ lazy_static! {
         "beacon_fork_choice_reorg_total",
         "Count of occasions fork choice has switched to a different chain"
     );
+    pub static ref HOOK_NOTIFICATIONS_DROPPED: Result<IntCounter> = try_create_int_counter(
+        "beacon_chain_hook_notifications_dropped_total",
+        "Count of head and finalization notifications dropped because a consumer was full"
+    );
     pub static ref FORK_CHOICE_TIMES: Result<Histogram> =
         try_create_histogram("beacon_fork_choice_seconds", "Full runtime of fork choice");
     pub static ref FORK_CHOICE_FIND_HEAD_TIMES: Result<Histogram> =
@@ -230,6 +266,18 @@ lazy_static! {
         "beacon_fork_choice_process_attestation_seconds",
         "Time taken to add an attestation to fork choice"
     );
+    pub static ref FORK_CHOICE_NODES: Result<IntGauge> = try_create_int_gauge(
+        "beacon_fork_choice_nodes",
+        "Number of blocks in the fork choice DAG"
+    );
+    pub static ref FORK_CHOICE_SIZE_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "beacon_fork_choice_size_bytes",
+        "Estimated memory used by the fork choice DAG and votes"
+    );
+    pub static ref FORK_CHOICE_FORCED_PRUNES: Result<IntCounter> = try_create_int_counter(
+        "beacon_fork_choice_forced_prunes_total",
+        "Count of occasions where the fork choice DAG was pruned for exceeding its maximum size"
+    );
     pub static ref BALANCES_CACHE_HITS: Result<IntCounter> =
         try_create_int_counter("beacon_balances_cache_hits_total", "Count of times balances cache fulfils request");
     pub static ref BALANCES_CACHE_MISSES: Result<IntCounter> =
@@ -341,6 +389,16 @@ pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
         scrape_attestation_observation(slot, beacon_chain);
     }
 
+    {
+        let fork_choice = beacon_chain.fork_choice.read();
+        let proto_array = fork_choice.proto_array();
+        set_gauge_by_usize(&FORK_CHOICE_NODES, proto_array.len());
+        set_gauge_by_usize(
+            &FORK_CHOICE_SIZE_BYTES,
+            proto_array.approximate_size_bytes(),
+        );
+    }
+
     set_gauge_by_usize(
         &OP_POOL_NUM_ATTESTATIONS,
         beacon_chain.op_pool.num_attestations(),
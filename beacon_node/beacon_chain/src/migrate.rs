@@ -62,6 +62,11 @@ pub trait Migrate<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>:
         new_finalized_checkpoint: Checkpoint,
         log: &Logger,
     ) -> Result<(), BeaconChainError> {
+        // Abandoned forks are kept in archive mode.
+        if !store.get_config().prune_abandoned_forks {
+            return Ok(());
+        }
+
         // There will never be any blocks to prune if there is only a single head in the chain.
         if head_tracker.heads().len() == 1 {
             return Ok(());
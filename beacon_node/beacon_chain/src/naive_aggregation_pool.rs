@@ -1,6 +1,6 @@
 use crate::metrics;
-use std::collections::HashMap;
-use types::{Attestation, AttestationData, EthSpec, Slot};
+use std::collections::{HashMap, HashSet};
+use types::{Attestation, AttestationData, CommitteeIndex, EthSpec, Slot};
 
 /// The number of slots that will be stored in the pool.
 ///
@@ -9,6 +9,13 @@ use types::{Attestation, AttestationData, EthSpec, Slot};
 /// will be refused.
 const SLOTS_RETAINED: usize = 3;
 
+/// The number of slots that attestations will be stored for committees with a declared
+/// aggregator (see `NaiveAggregationPool::register_aggregator`).
+///
+/// Aggregators produce their aggregate two-thirds of the way through the slot, this provides
+/// additional time for a slow validator client to do so.
+const AGGREGATOR_SLOTS_RETAINED: usize = 8;
+
 /// The maximum number of distinct `AttestationData` that will be stored in each slot.
 ///
 /// This is a DoS protection measure.
@@ -127,6 +134,11 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
     pub fn len(&self) -> usize {
         self.map.len()
     }
+
+    /// Removes all attestations for which `f(committee_index)` is `false`.
+    pub fn retain_committees<F: FnMut(CommitteeIndex) -> bool>(&mut self, mut f: F) {
+        self.map.retain(|data, _attestation| f(data.index))
+    }
 }
 
 /// A pool of `Attestation` that is specially designed to store "unaggregated" attestations from
@@ -150,9 +162,14 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
 /// `current_slot - SLOTS_RETAINED` will be removed and any future attestation with a slot lower
 /// than that will also be refused. Pruning is done automatically based upon the attestations it
 /// receives and it can be triggered manually.
+///
+/// The attestations of committees with a declared aggregator are retained for
+/// `AGGREGATOR_SLOTS_RETAINED` slots instead, so that the aggregator can still obtain them.
 pub struct NaiveAggregationPool<E: EthSpec> {
     lowest_permissible_slot: Slot,
     maps: HashMap<Slot, AggregatedAttestationMap<E>>,
+    /// The `(slot, committee_index)` of each committee with a declared aggregator.
+    aggregator_duties: HashSet<(Slot, CommitteeIndex)>,
 }
 
 impl<E: EthSpec> Default for NaiveAggregationPool<E> {
@@ -160,6 +177,7 @@ impl<E: EthSpec> Default for NaiveAggregationPool<E> {
         Self {
             lowest_permissible_slot: Slot::new(0),
             maps: HashMap::new(),
+            aggregator_duties: HashSet::new(),
         }
     }
 }
@@ -233,47 +251,94 @@ impl<E: EthSpec> NaiveAggregationPool<E> {
         self.maps.iter().map(|(_slot, map)| map.iter()).flatten()
     }
 
+    /// Returns the lowest slot of the attestations which are accepted by the pool.
+    pub fn lowest_permissible_slot(&self) -> Slot {
+        self.lowest_permissible_slot
+    }
+
+    /// Returns `true` if an aggregator has been declared for the committee at `slot` and
+    /// `committee_index`.
+    pub fn has_aggregator(&self, slot: Slot, committee_index: CommitteeIndex) -> bool {
+        self.aggregator_duties.contains(&(slot, committee_index))
+    }
+
+    /// Records that an aggregator has been declared for the committee at `slot` and
+    /// `committee_index`, so that its attestations are retained for `AGGREGATOR_SLOTS_RETAINED`
+    /// slots.
+    pub fn register_aggregator(&mut self, slot: Slot, committee_index: CommitteeIndex) {
+        self.aggregator_duties.insert((slot, committee_index));
+    }
+
     /// Removes any attestations with a slot lower than `current_slot` and bars any future
     /// attestations with a slot lower than `current_slot - SLOTS_RETAINED`.
+    ///
+    /// Attestations of committees with a declared aggregator are only removed once their slot is
+    /// lower than `current_slot - AGGREGATOR_SLOTS_RETAINED`.
     pub fn prune(&mut self, current_slot: Slot) {
         let _timer = metrics::start_timer(&metrics::ATTESTATION_PROCESSING_AGG_POOL_PRUNE);
 
         // Taking advantage of saturating subtraction on `Slot`.
         let lowest_permissible_slot = current_slot - Slot::from(SLOTS_RETAINED);
+        let lowest_aggregator_slot = current_slot - Slot::from(AGGREGATOR_SLOTS_RETAINED);
 
         // No need to prune if the lowest permissible slot has not changed and the queue length is
         // less than the maximum
         if self.lowest_permissible_slot == lowest_permissible_slot
-            && self.maps.len() <= SLOTS_RETAINED
+            && self.num_recent_slots() <= SLOTS_RETAINED
         {
             return;
         }
 
         self.lowest_permissible_slot = lowest_permissible_slot;
 
-        // Remove any maps that are definitely expired.
-        self.maps
-            .retain(|slot, _map| *slot >= lowest_permissible_slot);
+        self.aggregator_duties
+            .retain(|(slot, _committee_index)| *slot >= lowest_aggregator_slot);
+
+        // Remove any maps that are definitely expired, only keeping the attestations of
+        // committees with an aggregator from the older maps.
+        let aggregator_duties = &self.aggregator_duties;
+        self.maps.retain(|slot, map| {
+            if *slot >= lowest_permissible_slot {
+                true
+            } else if *slot >= lowest_aggregator_slot {
+                map.retain_committees(|committee_index| {
+                    aggregator_duties.contains(&(*slot, committee_index))
+                });
+                map.len() > 0
+            } else {
+                false
+            }
+        });
 
-        // If we have too many maps, remove the lowest amount to ensure we only have
+        // If we have too many recent maps, remove the lowest amount to ensure we only have
         // `SLOTS_RETAINED` left.
-        if self.maps.len() > SLOTS_RETAINED {
+        if self.num_recent_slots() > SLOTS_RETAINED {
             let mut slots = self
                 .maps
                 .iter()
                 .map(|(slot, _map)| *slot)
+                .filter(|slot| *slot >= lowest_permissible_slot)
                 .collect::<Vec<_>>();
             // Sort is generally pretty slow, however `SLOTS_RETAINED` is quite low so it should be
             // negligible.
             slots.sort_unstable();
             slots
                 .into_iter()
-                .take(self.maps.len().saturating_sub(SLOTS_RETAINED))
+                .take(self.num_recent_slots().saturating_sub(SLOTS_RETAINED))
                 .for_each(|slot| {
                     self.maps.remove(&slot);
                 })
         }
     }
+
+    /// Returns the number of maps with a slot that is not lower than
+    /// `self.lowest_permissible_slot`.
+    fn num_recent_slots(&self) -> usize {
+        self.maps
+            .keys()
+            .filter(|slot| **slot >= self.lowest_permissible_slot)
+            .count()
+    }
 }
 
 #[cfg(test)]
@@ -463,6 +528,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn aggregator_pruning() {
+        let mut base = get_attestation(Slot::new(0));
+        sign(&mut base, 0, Hash256::random());
+
+        let mut pool = NaiveAggregationPool::default();
+
+        // Two committees at slot 0, only one of which has an aggregator.
+        let mut with_aggregator = base.clone();
+        with_aggregator.data.index = 1;
+        let mut without_aggregator = base.clone();
+        without_aggregator.data.index = 2;
+
+        pool.register_aggregator(Slot::new(0), with_aggregator.data.index);
+        pool.insert(&with_aggregator)
+            .expect("should accept attestation with aggregator");
+        pool.insert(&without_aggregator)
+            .expect("should accept attestation without aggregator");
+
+        pool.prune(Slot::from(SLOTS_RETAINED + 1));
+
+        assert_eq!(
+            pool.get(&with_aggregator.data),
+            Ok(Some(with_aggregator.clone())),
+            "attestation with aggregator should be retained"
+        );
+        assert_eq!(
+            pool.get(&without_aggregator.data),
+            Ok(None),
+            "attestation without aggregator should be pruned"
+        );
+
+        // Attestations for recent slots should not be affected by the retained map.
+        for i in SLOTS_RETAINED + 1..=AGGREGATOR_SLOTS_RETAINED {
+            let mut a = base.clone();
+            a.data.slot = Slot::from(i);
+            pool.insert(&a).expect("should accept new attestation");
+        }
+        assert_eq!(
+            pool.num_recent_slots(),
+            SLOTS_RETAINED,
+            "the pool should have SLOTS_RETAINED recent slots"
+        );
+        assert_eq!(
+            pool.get(&with_aggregator.data),
+            Ok(Some(with_aggregator.clone())),
+            "attestation with aggregator should still be retained"
+        );
+
+        pool.prune(Slot::from(AGGREGATOR_SLOTS_RETAINED + 1));
+
+        assert_eq!(
+            pool.get(&with_aggregator.data),
+            Ok(None),
+            "attestation with aggregator should eventually be pruned"
+        );
+        assert!(
+            pool.aggregator_duties.is_empty(),
+            "aggregator duty should be pruned"
+        );
+    }
+
     #[test]
     fn max_attestations() {
         let mut base = get_attestation(Slot::new(0));
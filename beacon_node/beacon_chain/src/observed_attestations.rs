@@ -163,6 +163,11 @@ impl<E: EthSpec> ObservedAttestations<E> {
             .and_then(|set| set.is_known(a, root))
     }
 
+    /// Returns the number of observed attestations in `self`, across all slots.
+    pub fn observed_count(&self) -> usize {
+        self.sets.read().iter().map(SlotHashSet::len).sum()
+    }
+
     /// The maximum number of slots that attestations are stored for.
     fn max_capacity(&self) -> u64 {
         // We add `2` in order to account for one slot either side of the range due to
@@ -1,9 +1,28 @@
 use crate::beacon_fork_choice_store::PersistedForkChoiceStore as ForkChoiceStore;
 use fork_choice::PersistedForkChoice as ForkChoice;
-use ssz::{Decode, Encode};
+use proto_array::ProtoArrayForkChoice;
+use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 use store::{DBColumn, Error, StoreItem};
 
+/// The version of the on-disk encoding of `PersistedForkChoice`.
+///
+/// Must be incremented whenever the encoding of `PersistedForkChoice` (or any of its fields)
+/// changes, alongside a migration from the previous encoding in `Self::from_store_bytes`.
+///
+/// ## Versions
+///
+/// - 0: Unversioned SSZ.
+/// - 1: As for version 0, prefixed with the version.
+/// - 2: Adds `ProtoNode::is_optimistic`.
+pub const FORK_CHOICE_SCHEMA_VERSION: u8 = 2;
+
+/// The first bytes of fork choice persisted before it was versioned (schema version 0).
+///
+/// These encodings are unprefixed SSZ, which begins with the 4-byte offset of the variable-length
+/// `fork_choice` field. That offset is always 8, so it cannot be mistaken for a schema version.
+const LEGACY_SSZ_PREFIX: [u8; 4] = [8, 0, 0, 0];
+
 #[derive(Encode, Decode)]
 pub struct PersistedForkChoice {
     pub fork_choice: ForkChoice,
@@ -15,11 +34,58 @@ impl StoreItem for PersistedForkChoice {
         DBColumn::ForkChoice
     }
 
+    /// Encodes `self` as SSZ, prefixed with `FORK_CHOICE_SCHEMA_VERSION`.
     fn as_store_bytes(&self) -> Vec<u8> {
-        self.as_ssz_bytes()
+        let mut bytes = vec![FORK_CHOICE_SCHEMA_VERSION];
+        bytes.append(&mut self.as_ssz_bytes());
+        bytes
     }
 
+    /// Decodes `bytes` written with any schema version, migrating them to the current version.
     fn from_store_bytes(bytes: &[u8]) -> std::result::Result<Self, Error> {
-        Self::from_ssz_bytes(bytes).map_err(Into::into)
+        if bytes.starts_with(&LEGACY_SSZ_PREFIX) {
+            return Self::from_ssz_bytes(bytes)?.migrate_from_v1();
+        }
+
+        match bytes.split_first() {
+            Some((&FORK_CHOICE_SCHEMA_VERSION, ssz_bytes)) => {
+                Self::from_ssz_bytes(ssz_bytes).map_err(Into::into)
+            }
+            Some((1, ssz_bytes)) => Self::from_ssz_bytes(ssz_bytes)?.migrate_from_v1(),
+            Some((version, _)) => Err(DecodeError::BytesInvalid(format!(
+                "Unsupported fork choice schema version {}, expected {}",
+                version, FORK_CHOICE_SCHEMA_VERSION
+            ))
+            .into()),
+            None => Err(DecodeError::InvalidByteLength {
+                len: 0,
+                expected: 1,
+            }
+            .into()),
+        }
+    }
+}
+
+impl PersistedForkChoice {
+    /// Converts fork choice decoded from schema version 0 or 1, whose blocks do not record whether
+    /// they were imported optimistically. No block was imported optimistically by those versions.
+    fn migrate_from_v1(mut self) -> Result<Self, Error> {
+        self.fork_choice.proto_array_bytes =
+            ProtoArrayForkChoice::from_bytes_v1(&self.fork_choice.proto_array_bytes)
+                .map_err(DecodeError::BytesInvalid)?
+                .as_bytes();
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_schema_versions() {
+        assert!(PersistedForkChoice::from_store_bytes(&[]).is_err());
+        assert!(PersistedForkChoice::from_store_bytes(&[FORK_CHOICE_SCHEMA_VERSION + 1]).is_err());
+        assert!(PersistedForkChoice::from_store_bytes(&LEGACY_SSZ_PREFIX).is_err());
     }
 }
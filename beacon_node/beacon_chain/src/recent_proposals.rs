@@ -0,0 +1,177 @@
+//! Provides `RecentProposals`, which records the graffiti of recently imported blocks so that the
+//! diversity of the clients used by block proposers can be estimated.
+//!
+//! Clients are identified by the graffiti which they include in blocks by default. Many validators
+//! set their own graffiti, so the client of many blocks cannot be identified.
+
+use std::collections::VecDeque;
+use types::{Graffiti, Slot};
+
+/// The number of blocks for which proposals are retained.
+pub const DEFAULT_RECENT_PROPOSALS_SIZE: usize = 1024;
+
+/// The proposer and graffiti of an imported block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Proposal {
+    pub slot: Slot,
+    pub proposer_index: u64,
+    pub graffiti: Graffiti,
+}
+
+/// Records the proposals of the most recently imported blocks.
+pub struct RecentProposals {
+    capacity: usize,
+    /// Oldest first.
+    proposals: VecDeque<Proposal>,
+}
+
+impl Default for RecentProposals {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_PROPOSALS_SIZE)
+    }
+}
+
+impl RecentProposals {
+    /// Instantiates a new buffer which retains up to `capacity` proposals.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            proposals: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records `proposal`, evicting the oldest proposal if the buffer is full.
+    pub fn record(&mut self, proposal: Proposal) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.proposals.len() >= self.capacity {
+            self.proposals.pop_front();
+        }
+
+        self.proposals.push_back(proposal);
+    }
+
+    /// Iterates through all known proposals, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Proposal> {
+        self.proposals.iter()
+    }
+}
+
+/// A consensus client which may be identified by its default graffiti.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ClientKind {
+    Lighthouse,
+    Lodestar,
+    Nimbus,
+    Prysm,
+    Teku,
+    /// The graffiti does not identify a client.
+    Unknown,
+}
+
+impl ClientKind {
+    /// All kinds, in order.
+    pub const ALL: [ClientKind; 6] = [
+        ClientKind::Lighthouse,
+        ClientKind::Lodestar,
+        ClientKind::Nimbus,
+        ClientKind::Prysm,
+        ClientKind::Teku,
+        ClientKind::Unknown,
+    ];
+
+    /// Returns the client whose name appears in `graffiti` (ignoring case), if any.
+    pub fn from_graffiti(graffiti: &Graffiti) -> Self {
+        let graffiti = graffiti_to_string(graffiti).to_lowercase();
+
+        if graffiti.contains("lighthouse") {
+            ClientKind::Lighthouse
+        } else if graffiti.contains("lodestar") {
+            ClientKind::Lodestar
+        } else if graffiti.contains("nimbus") {
+            ClientKind::Nimbus
+        } else if graffiti.contains("prysm") || graffiti.contains("prylabs") {
+            ClientKind::Prysm
+        } else if graffiti.contains("teku") {
+            ClientKind::Teku
+        } else {
+            ClientKind::Unknown
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ClientKind::Lighthouse => "lighthouse",
+            ClientKind::Lodestar => "lodestar",
+            ClientKind::Nimbus => "nimbus",
+            ClientKind::Prysm => "prysm",
+            ClientKind::Teku => "teku",
+            ClientKind::Unknown => "unknown",
+        }
+    }
+}
+
+/// Returns `graffiti` as a string, replacing invalid UTF-8 and removing trailing null bytes.
+pub fn graffiti_to_string(graffiti: &Graffiti) -> String {
+    String::from_utf8_lossy(graffiti)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graffiti(s: &str) -> Graffiti {
+        let mut graffiti = Graffiti::default();
+        graffiti[..s.len()].copy_from_slice(s.as_bytes());
+        graffiti
+    }
+
+    #[test]
+    fn client_from_graffiti() {
+        assert_eq!(
+            graffiti_to_string(&graffiti("Lighthouse/v0.2.9")),
+            "Lighthouse/v0.2.9"
+        );
+        assert_eq!(
+            ClientKind::from_graffiti(&graffiti("Lighthouse/v0.2.9")),
+            ClientKind::Lighthouse
+        );
+        assert_eq!(
+            ClientKind::from_graffiti(&graffiti("prylabs")),
+            ClientKind::Prysm
+        );
+        assert_eq!(
+            ClientKind::from_graffiti(&graffiti("hello world")),
+            ClientKind::Unknown
+        );
+        assert_eq!(
+            ClientKind::from_graffiti(&Graffiti::default()),
+            ClientKind::Unknown
+        );
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let mut proposals = RecentProposals::new(2);
+
+        for i in 0..3 {
+            proposals.record(Proposal {
+                slot: Slot::new(i),
+                proposer_index: i,
+                graffiti: Graffiti::default(),
+            });
+        }
+
+        assert_eq!(
+            proposals
+                .iter()
+                .map(|proposal| proposal.proposer_index)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+}
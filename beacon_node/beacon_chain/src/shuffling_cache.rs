@@ -1,3 +1,4 @@
+use crate::cache_stats::{CacheInfo, CacheStats};
 use crate::metrics;
 use lru::LruCache;
 use types::{beacon_state::CommitteeCache, Epoch, Hash256};
@@ -15,12 +16,14 @@ const CACHE_SIZE: usize = 16;
 /// a find/replace error.
 pub struct ShufflingCache {
     cache: LruCache<(Epoch, Hash256), CommitteeCache>,
+    stats: CacheStats,
 }
 
 impl ShufflingCache {
     pub fn new() -> Self {
         Self {
             cache: LruCache::new(CACHE_SIZE),
+            stats: CacheStats::default(),
         }
     }
 
@@ -33,7 +36,7 @@ impl ShufflingCache {
             metrics::inc_counter(&metrics::SHUFFLING_CACHE_MISSES);
         }
 
-        opt
+        self.stats.record(opt)
     }
 
     pub fn insert(&mut self, epoch: Epoch, root: Hash256, committee_cache: &CommitteeCache) {
@@ -43,4 +46,12 @@ impl ShufflingCache {
             self.cache.put(key, committee_cache.clone());
         }
     }
+
+    pub fn clear(&mut self) {
+        self.cache.clear()
+    }
+
+    pub fn info(&self) -> CacheInfo {
+        CacheInfo::new(self.cache.len(), Some(self.cache.cap()), &self.stats)
+    }
 }
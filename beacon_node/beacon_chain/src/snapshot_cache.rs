@@ -1,3 +1,4 @@
+use crate::cache_stats::{CacheInfo, CacheStats};
 use crate::BeaconSnapshot;
 use std::cmp;
 use types::{Epoch, EthSpec, Hash256};
@@ -21,6 +22,7 @@ pub struct SnapshotCache<T: EthSpec> {
     max_len: usize,
     head_block_root: Hash256,
     snapshots: Vec<BeaconSnapshot<T>>,
+    stats: CacheStats,
 }
 
 impl<T: EthSpec> SnapshotCache<T> {
@@ -32,6 +34,7 @@ impl<T: EthSpec> SnapshotCache<T> {
             max_len: cmp::max(max_len, 1),
             head_block_root: head.beacon_block_root,
             snapshots: vec![head],
+            stats: CacheStats::default(),
         }
     }
 
@@ -63,18 +66,24 @@ impl<T: EthSpec> SnapshotCache<T> {
 
     /// If there is a snapshot with `block_root`, remove and return it.
     pub fn try_remove(&mut self, block_root: Hash256) -> Option<BeaconSnapshot<T>> {
-        self.snapshots
+        let position = self
+            .snapshots
             .iter()
-            .position(|snapshot| snapshot.beacon_block_root == block_root)
+            .position(|snapshot| snapshot.beacon_block_root == block_root);
+        self.stats
+            .record(position)
             .map(|i| self.snapshots.remove(i))
     }
 
     /// If there is a snapshot with `block_root`, clone it (with only the committee caches) and
     /// return the clone.
     pub fn get_cloned(&self, block_root: Hash256) -> Option<BeaconSnapshot<T>> {
-        self.snapshots
-            .iter()
-            .find(|snapshot| snapshot.beacon_block_root == block_root)
+        self.stats
+            .record(
+                self.snapshots
+                    .iter()
+                    .find(|snapshot| snapshot.beacon_block_root == block_root),
+            )
             .map(|snapshot| snapshot.clone_with_only_committee_caches())
     }
 
@@ -92,6 +101,17 @@ impl<T: EthSpec> SnapshotCache<T> {
     pub fn update_head(&mut self, head_block_root: Hash256) {
         self.head_block_root = head_block_root
     }
+
+    /// Removes all snapshots other than that of the head.
+    pub fn clear(&mut self) {
+        let head_block_root = self.head_block_root;
+        self.snapshots
+            .retain(|snapshot| snapshot.beacon_block_root == head_block_root)
+    }
+
+    pub fn info(&self) -> CacheInfo {
+        CacheInfo::new(self.snapshots.len(), Some(self.max_len), &self.stats)
+    }
 }
 
 #[cfg(test)]
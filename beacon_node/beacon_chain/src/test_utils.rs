@@ -10,7 +10,7 @@ use crate::{
     events::NullEventHandler,
     BeaconChain, BeaconChainTypes, StateSkipConfig,
 };
-use genesis::interop_genesis_state;
+use genesis::{interop_genesis_state, interop_genesis_state_with_deposits, InteropDeposit};
 use rand::rngs::StdRng;
 use rand::Rng;
 use rand_core::SeedableRng;
@@ -118,6 +118,21 @@ type HarnessAttestations<E> = Vec<(
 
 impl<E: EthSpec> BeaconChainHarness<BlockingMigratorEphemeralHarnessType<E>> {
     pub fn new(eth_spec_instance: E, validators_keypairs: Vec<Keypair>) -> Self {
+        let deposits = vec![
+            InteropDeposit::max_effective_balance(&E::default_spec());
+            validators_keypairs.len()
+        ];
+        Self::new_with_deposits(eth_spec_instance, validators_keypairs, deposits)
+    }
+
+    /// Instantiate a new harness where the genesis deposit of `validators_keypairs[i]` is
+    /// `deposits[i]`, allowing validators to start with custom balances or withdrawal
+    /// credentials.
+    pub fn new_with_deposits(
+        eth_spec_instance: E,
+        validators_keypairs: Vec<Keypair>,
+        deposits: Vec<InteropDeposit>,
+    ) -> Self {
         let data_dir = tempdir().unwrap();
         let mut spec = E::default_spec();
 
@@ -142,8 +157,13 @@ impl<E: EthSpec> BeaconChainHarness<BlockingMigratorEphemeralHarnessType<E>> {
             .store_migrator(BlockingMigrator::new(store, log.clone()))
             .data_dir(data_dir.path().to_path_buf())
             .genesis_state(
-                interop_genesis_state::<E>(&validators_keypairs, HARNESS_GENESIS_TIME, &spec)
-                    .unwrap(),
+                interop_genesis_state_with_deposits::<E>(
+                    &validators_keypairs,
+                    &deposits,
+                    HARNESS_GENESIS_TIME,
+                    &spec,
+                )
+                .unwrap(),
             )
             .unwrap()
             .dummy_eth1_backend()
@@ -279,6 +299,18 @@ impl<E: EthSpec> BeaconChainHarness<BlockingMigratorDiskHarnessType<E>> {
         validators_keypairs: Vec<Keypair>,
         data_dir: TempDir,
     ) -> Self {
+        Self::try_resume_from_disk_store(eth_spec_instance, store, validators_keypairs, data_dir)
+            .expect("should resume beacon chain from db")
+    }
+
+    /// Instantiate a new harness from the chain persisted in `store`, returning an error if the
+    /// chain cannot be resumed.
+    pub fn try_resume_from_disk_store(
+        eth_spec_instance: E,
+        store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+        validators_keypairs: Vec<Keypair>,
+        data_dir: TempDir,
+    ) -> Result<Self, String> {
         let spec = E::default_spec();
 
         let log = NullLoggerBuilder.build().expect("logger should build");
@@ -293,23 +325,21 @@ impl<E: EthSpec> BeaconChainHarness<BlockingMigratorDiskHarnessType<E>> {
                 log.clone(),
             ))
             .data_dir(data_dir.path().to_path_buf())
-            .resume_from_db()
-            .expect("should resume beacon chain from db")
+            .resume_from_db()?
             .dummy_eth1_backend()
             .expect("should build dummy backend")
             .null_event_handler()
             .testing_slot_clock(Duration::from_secs(1))
             .expect("should configure testing slot clock")
-            .build()
-            .expect("should build");
+            .build()?;
 
-        Self {
+        Ok(Self {
             spec: chain.spec.clone(),
             chain,
             validators_keypairs,
             data_dir,
             rng: make_rng(),
-        }
+        })
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::cache_stats::{CacheInfo, CacheStats};
 use crate::errors::BeaconChainError;
 use ssz::{Decode, DecodeError, Encode};
 use std::collections::HashMap;
@@ -22,6 +23,7 @@ pub struct ValidatorPubkeyCache {
     pubkeys: Vec<PublicKey>,
     indices: HashMap<PublicKeyBytes, usize>,
     persitence_file: ValidatorPubkeyCacheFile,
+    stats: CacheStats,
 }
 
 impl ValidatorPubkeyCache {
@@ -50,6 +52,7 @@ impl ValidatorPubkeyCache {
             persitence_file: ValidatorPubkeyCacheFile::create(persistence_path)?,
             pubkeys: vec![],
             indices: HashMap::new(),
+            stats: CacheStats::default(),
         };
 
         cache.import_new_pubkeys(state)?;
@@ -108,18 +111,23 @@ impl ValidatorPubkeyCache {
 
     /// Get the public key for a validator with index `i`.
     pub fn get(&self, i: usize) -> Option<&PublicKey> {
-        self.pubkeys.get(i)
+        self.stats.record(self.pubkeys.get(i))
     }
 
     /// Get the index of a validator with `pubkey`.
     pub fn get_index(&self, pubkey: &PublicKeyBytes) -> Option<usize> {
-        self.indices.get(pubkey).copied()
+        self.stats.record(self.indices.get(pubkey).copied())
     }
 
     /// Returns the number of validators in the cache.
     pub fn len(&self) -> usize {
         self.indices.len()
     }
+
+    /// The cache holds every known validator, so it is unbounded.
+    pub fn info(&self) -> CacheInfo {
+        CacheInfo::new(self.len(), None, &self.stats)
+    }
 }
 
 /// Allows for maintaining an on-disk copy of the `ValidatorPubkeyCache`. The file is raw SSZ bytes
@@ -215,6 +223,7 @@ impl ValidatorPubkeyCacheFile {
             pubkeys,
             indices,
             persitence_file: self,
+            stats: CacheStats::default(),
         })
     }
 }
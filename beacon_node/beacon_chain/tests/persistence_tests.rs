@@ -4,14 +4,20 @@
 extern crate lazy_static;
 
 use beacon_chain::{
-    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy},
-    BeaconChain, BeaconChainTypes,
+    persisted_fork_choice::PersistedForkChoice,
+    test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, BlockingMigratorDiskHarnessType,
+        FORK_CHOICE_DB_KEY,
+    },
+    BeaconChain, BeaconChainTypes, DatabaseView,
 };
+use proto_array::ProtoArrayForkChoice;
 use sloggers::{null::NullLoggerBuilder, Build};
+use ssz::{Decode, Encode};
 use std::sync::Arc;
-use store::{HotColdDB, LevelDB, StoreConfig};
+use store::{DBColumn, Error as StoreError, HotColdDB, LevelDB, StoreConfig, StoreItem};
 use tempfile::{tempdir, TempDir};
-use types::{EthSpec, Keypair, MinimalEthSpec};
+use types::{EthSpec, Hash256, Keypair, MinimalEthSpec};
 
 type E = MinimalEthSpec;
 
@@ -137,6 +143,188 @@ fn finalizes_after_resuming_from_db() {
     );
 }
 
+#[test]
+fn database_view_reads_persisted_head() {
+    let validator_count = 16;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let mut harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+
+    let head = harness.chain.head().expect("should read head");
+    let view = DatabaseView::from_store(store).expect("should open database view");
+
+    assert_eq!(view.head_block_root(), head.beacon_block_root);
+    assert_eq!(view.genesis_block_root(), harness.chain.genesis_block_root);
+    assert_eq!(
+        view.head_block().expect("should read head block"),
+        head.beacon_block
+    );
+    assert_eq!(
+        view.head_state()
+            .expect("should read head state")
+            .canonical_root(),
+        head.beacon_state_root
+    );
+}
+
+/// The raw bytes of the persisted fork choice, allowing them to be rewritten.
+struct ForkChoiceBytes(Vec<u8>);
+
+impl StoreItem for ForkChoiceBytes {
+    fn db_column() -> DBColumn {
+        DBColumn::ForkChoice
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self(bytes.to_vec()))
+    }
+}
+
+/// Persists a chain in a database at `db_path`, replaces the persisted fork choice with the result
+/// of `rewrite` and then attempts to resume the chain.
+///
+/// Returns the original chain and the result of resuming it.
+#[allow(clippy::type_complexity)]
+fn resume_with_fork_choice_bytes<F>(
+    db_path: &TempDir,
+    rewrite: F,
+) -> (
+    BeaconChain<BlockingMigratorDiskHarnessType<E>>,
+    Result<BeaconChainHarness<BlockingMigratorDiskHarnessType<E>>, String>,
+)
+where
+    F: FnOnce(Vec<u8>) -> Vec<u8>,
+{
+    let store = get_store(db_path);
+
+    let mut harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[..].to_vec(),
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize * 2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+
+    let key = Hash256::from_slice(&FORK_CHOICE_DB_KEY);
+    let ForkChoiceBytes(bytes) = store
+        .get_item(&key)
+        .expect("should read fork choice")
+        .expect("should have persisted fork choice");
+    store
+        .put_item(&key, &ForkChoiceBytes(rewrite(bytes)))
+        .expect("should rewrite fork choice");
+
+    let data_dir = harness.data_dir;
+    let original_chain = harness.chain;
+
+    let resumed = BeaconChainHarness::try_resume_from_disk_store(
+        MinimalEthSpec,
+        store,
+        KEYPAIRS[..].to_vec(),
+        data_dir,
+    );
+
+    (original_chain, resumed)
+}
+
+#[test]
+fn resumes_from_current_fork_choice_encoding() {
+    let db_path = tempdir().unwrap();
+
+    let (original_chain, resumed) = resume_with_fork_choice_bytes(&db_path, |bytes| bytes);
+    let resumed_harness = resumed.expect("should resume from current encoding");
+
+    assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
+}
+
+/// Re-encodes persisted fork choice `bytes` as they were before `ProtoNode::is_optimistic` was
+/// added, without a version prefix (i.e., schema version 0).
+fn unversioned_v1_fork_choice_bytes(bytes: Vec<u8>) -> Vec<u8> {
+    let mut persisted =
+        PersistedForkChoice::from_ssz_bytes(&bytes[1..]).expect("should decode fork choice");
+    persisted.fork_choice.proto_array_bytes =
+        ProtoArrayForkChoice::from_bytes(&persisted.fork_choice.proto_array_bytes)
+            .expect("should decode proto array")
+            .as_bytes_v1();
+    persisted.as_ssz_bytes()
+}
+
+#[test]
+fn resumes_from_unversioned_fork_choice_encoding() {
+    let db_path = tempdir().unwrap();
+
+    let (original_chain, resumed) =
+        resume_with_fork_choice_bytes(&db_path, unversioned_v1_fork_choice_bytes);
+    let resumed_harness = resumed.expect("should resume from schema version 0");
+
+    assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
+}
+
+#[test]
+fn resumes_from_v1_fork_choice_encoding() {
+    let db_path = tempdir().unwrap();
+
+    let (original_chain, resumed) = resume_with_fork_choice_bytes(&db_path, |bytes| {
+        let mut v1_bytes = vec![1];
+        v1_bytes.append(&mut unversioned_v1_fork_choice_bytes(bytes));
+        v1_bytes
+    });
+    let resumed_harness = resumed.expect("should resume from schema version 1");
+
+    assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
+}
+
+#[test]
+fn refuses_to_resume_from_undecodable_fork_choice() {
+    let db_path = tempdir().unwrap();
+
+    let (_, resumed) =
+        resume_with_fork_choice_bytes(&db_path, |bytes| bytes[..bytes.len() / 2].to_vec());
+
+    match resumed {
+        Err(e) => assert!(
+            e.contains("Unable to decode persisted fork choice"),
+            "unexpected error: {}",
+            e
+        ),
+        Ok(_) => panic!("should not resume from an undecodable fork choice"),
+    }
+}
+
 /// Checks that two chains are the same, for the purpose of this tests.
 ///
 /// Several fields that are hard/impossible to check are ignored (e.g., the store).
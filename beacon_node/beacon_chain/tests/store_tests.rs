@@ -9,10 +9,11 @@ extern crate slog_term;
 
 use crate::slog::Drain;
 use beacon_chain::attestation_verification::Error as AttnError;
+use beacon_chain::era::{import_era_files, Era};
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, BlockingMigratorDiskHarnessType,
 };
-use beacon_chain::BeaconSnapshot;
+use beacon_chain::{BeaconSnapshot, StateSkipConfig};
 use maplit::hashset;
 use rand::Rng;
 use std::collections::HashMap;
@@ -88,6 +89,100 @@ fn full_participation_no_skips() {
     check_iterators(&harness);
 }
 
+#[test]
+fn finalized_state_by_slot() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let split_slot = store.get_split_slot();
+    assert_ne!(split_slot, 0);
+
+    for slot in (0..=split_slot.as_u64()).step_by(E::slots_per_epoch() as usize) {
+        let slot = Slot::new(slot);
+        let state = store
+            .get_finalized_state_by_slot(slot)
+            .expect("should load finalized state")
+            .expect("finalized state should exist");
+        let expected = harness
+            .chain
+            .state_at_slot(slot, StateSkipConfig::WithStateRoots)
+            .expect("should get state from chain");
+
+        assert_eq!(state.slot, slot);
+        assert_eq!(state.canonical_root(), expected.canonical_root());
+    }
+
+    assert!(store
+        .get_finalized_state_by_slot(split_slot + 1)
+        .expect("should not error beyond the split")
+        .is_none());
+}
+
+#[test]
+fn era_export_and_import() {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let num_blocks_produced = slots_per_era + E::slots_per_epoch() * 4;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let state = store
+        .get_finalized_state_by_slot(Slot::new(slots_per_era))
+        .expect("should load finalized state")
+        .expect("era state should be finalized");
+
+    let mut blocks: Vec<SignedBeaconBlock<E>> = vec![];
+    for slot in 0..slots_per_era {
+        let block_root = *state.get_block_root(Slot::new(slot)).unwrap();
+        if blocks.last().map(|block| block.canonical_root()) != Some(block_root) {
+            blocks.push(store.get_block(&block_root).unwrap().unwrap());
+        }
+    }
+
+    let era = Era { blocks, state };
+    era.verify().expect("era should be valid");
+    assert_eq!(era.number(), 1);
+
+    let era_dir = tempdir().unwrap();
+    let path = era.write(era_dir.path()).expect("should write era");
+    assert_eq!(Era::<E>::read(&path).expect("should read era"), era);
+    assert!(era.write(era_dir.path()).is_err(), "should not overwrite");
+
+    // Import the era into a new chain.
+    let new_db_path = tempdir().unwrap();
+    let new_harness = get_harness(get_store(&new_db_path), LOW_VALIDATOR_COUNT);
+    new_harness.chain.slot_clock.set_slot(slots_per_era);
+
+    let imported = import_era_files(&new_harness.chain, era_dir.path()).expect("should import");
+
+    // The genesis block is already known.
+    assert_eq!(imported, era.blocks.len() - 1);
+    assert_eq!(
+        new_harness.chain.head_info().unwrap().block_root,
+        era.blocks.last().unwrap().canonical_root()
+    );
+
+    // Importing again is a no-op.
+    assert_eq!(
+        import_era_files(&new_harness.chain, era_dir.path()).expect("should import again"),
+        0
+    );
+}
+
 #[test]
 fn randomised_skips() {
     let num_slots = E::slots_per_epoch() * 5;
@@ -337,6 +337,152 @@ fn does_not_finalize_without_attestation() {
     );
 }
 
+/// Returns the number of times fork choice has been force-pruned by this process.
+fn forced_fork_choice_prunes() -> f64 {
+    lighthouse_metrics::gather()
+        .iter()
+        .find(|family| family.get_name() == "beacon_fork_choice_forced_prunes_total")
+        .map_or(0.0, |family| {
+            family.get_metric()[0].get_counter().get_value()
+        })
+}
+
+#[test]
+fn forced_fork_choice_pruning() {
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch();
+    let max_nodes = slots_per_epoch as usize;
+
+    let mut harness = get_harness(VALIDATOR_COUNT);
+    harness.chain.config.fork_choice_max_nodes = Some(max_nodes);
+    let prunes_at_start = forced_fork_choice_prunes();
+
+    // Without finality every block descends from the finalized block, so nothing can be pruned
+    // and the limit is exceeded without any forced prunes being recorded.
+    let blocks_without_finality = slots_per_epoch * 2;
+    harness.extend_chain(
+        blocks_without_finality as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+
+    assert_eq!(
+        harness.chain.fork_choice.read().proto_array().len(),
+        blocks_without_finality as usize + 1,
+        "should retain all blocks whilst not finalizing"
+    );
+    assert_eq!(
+        forced_fork_choice_prunes(),
+        prunes_at_start,
+        "should not record a prune which removed nothing"
+    );
+
+    // Once finality advances, blocks prior to the finalized block are removed.
+    let blocks_with_finality = slots_per_epoch * 5;
+    harness.extend_chain(
+        blocks_with_finality as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    assert!(
+        harness
+            .chain
+            .head_info()
+            .unwrap()
+            .finalized_checkpoint
+            .epoch
+            > 0,
+        "should finalize"
+    );
+    assert!(
+        forced_fork_choice_prunes() > prunes_at_start,
+        "should record forced prunes"
+    );
+    assert!(
+        harness.chain.fork_choice.read().proto_array().len()
+            < (blocks_without_finality + blocks_with_finality) as usize,
+        "should remove blocks prior to the finalized block"
+    );
+}
+
+#[test]
+fn records_block_import_times() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch();
+
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head = harness.chain.head_info().expect("should get head");
+    let block_times_cache = harness.chain.block_times_cache.read();
+    let block_times = block_times_cache
+        .get(&head.block_root)
+        .expect("should have times for the head block");
+    let timestamps = block_times.timestamps;
+
+    assert_eq!(block_times.slot, head.slot, "should record the block slot");
+    assert_eq!(
+        timestamps.observed, None,
+        "blocks not from gossip should not be observed"
+    );
+    assert!(timestamps.signature_verified.is_some());
+    assert!(timestamps.state_transition.is_some());
+    assert!(timestamps.imported.is_some());
+    assert!(timestamps.head.is_some());
+    assert!(
+        timestamps.signature_verified <= timestamps.state_transition
+            && timestamps.state_transition <= timestamps.imported
+            && timestamps.imported <= timestamps.head,
+        "stages should be recorded in order"
+    );
+    assert_eq!(
+        block_times_cache.iter().count(),
+        num_blocks_produced as usize,
+        "should have times for every block"
+    );
+}
+
+#[test]
+fn notifies_head_and_finalization_hooks() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
+
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    let capacity = num_blocks_produced as usize;
+    let mut head_changes = harness.chain.hooks.subscribe_head(capacity);
+    let mut finalizations = harness.chain.hooks.subscribe_finalization(capacity);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head = harness.chain.head_info().expect("should get head");
+
+    let mut last_head_change = None;
+    while let Ok(head_change) = head_changes.try_recv() {
+        assert!(!head_change.reorg, "should not re-org");
+        last_head_change = Some(head_change);
+    }
+    let last_head_change = last_head_change.expect("should notify head changes");
+    assert_eq!(last_head_change.slot, head.slot);
+    assert_eq!(last_head_change.block_root, head.block_root);
+    assert_eq!(last_head_change.state_root, head.state_root);
+
+    let mut last_finalization = None;
+    while let Ok(finalization) = finalizations.try_recv() {
+        last_finalization = Some(finalization);
+    }
+    let last_finalization = last_finalization.expect("should notify finalization");
+    assert_eq!(last_finalization.epoch, head.finalized_checkpoint.epoch);
+    assert_eq!(last_finalization.root, head.finalized_checkpoint.root);
+}
+
 #[test]
 fn roundtrip_operation_pool() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
@@ -4,21 +4,22 @@ use crate::Client;
 use beacon_chain::events::TeeEventHandler;
 use beacon_chain::{
     builder::{BeaconChainBuilder, Witness},
+    era::import_era_files,
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     migrate::{BackgroundMigrator, Migrate},
     slot_clock::{SlotClock, SystemTimeSlotClock},
     store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, EventHandler,
 };
-use bus::Bus;
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
+use execution_layer::ExecutionLayer;
 use genesis::{interop_genesis_state, Eth1GenesisService};
-use network::{NetworkConfig, NetworkMessage, NetworkService};
-use parking_lot::Mutex;
-use slog::info;
+use network::{GossipValidator, NetworkConfig, NetworkMessage, NetworkService};
+use rest_api::RequestLimiter;
+use slog::{info, warn};
 use ssz::Decode;
 use std::net::SocketAddr;
 use std::path::Path;
@@ -26,10 +27,8 @@ use std::sync::Arc;
 use std::time::Duration;
 use timer::spawn_timer;
 use tokio::sync::mpsc::UnboundedSender;
-use types::{
-    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
-    SignedBeaconBlockHash,
-};
+use types::{test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec};
+use webhook_notifier::{Config as WebhookConfig, WebhookSender};
 use websocket_server::{Config as WebSocketConfig, WebSocketSender};
 
 /// Interval between polling the eth1 node for genesis information.
@@ -61,7 +60,9 @@ pub struct ClientBuilder<T: BeaconChainTypes> {
     event_handler: Option<T::EventHandler>,
     network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     network_send: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
+    gossip_validator: Option<Arc<dyn GossipValidator<T::EthSpec>>>,
     http_listen_addr: Option<SocketAddr>,
+    http_request_limiter: Option<Arc<RequestLimiter>>,
     websocket_listen_addr: Option<SocketAddr>,
     eth_spec_instance: T::EthSpec,
 }
@@ -103,7 +104,9 @@ where
             event_handler: None,
             network_globals: None,
             network_send: None,
+            gossip_validator: None,
             http_listen_addr: None,
+            http_request_limiter: None,
             websocket_listen_addr: None,
             eth_spec_instance,
         }
@@ -148,6 +151,25 @@ where
         let spec = chain_spec
             .ok_or_else(|| "beacon_chain_start_method requires a chain spec".to_string())?;
 
+        let execution_layer = config
+            .execution_layer
+            .as_ref()
+            .map(|execution_config| {
+                let context = context.service_context("exec".into());
+                info!(
+                    context.log(),
+                    "Using execution engine";
+                    "endpoint" => &execution_config.endpoint,
+                    "authenticated" => execution_config.jwt_secret.is_some(),
+                );
+                ExecutionLayer::from_config(
+                    execution_config,
+                    context.executor.clone(),
+                    context.log().clone(),
+                )
+            })
+            .transpose()?;
+
         let builder = BeaconChainBuilder::new(eth_spec_instance)
             .logger(context.log().clone())
             .store(store)
@@ -156,7 +178,8 @@ where
             .custom_spec(spec.clone())
             .chain_config(chain_config)
             .disabled_forks(disabled_forks)
-            .graffiti(graffiti);
+            .graffiti(graffiti)
+            .execution_layer(execution_layer);
 
         let chain_exists = builder
             .store_contains_beacon_chain()
@@ -234,6 +257,18 @@ where
         Ok(self)
     }
 
+    /// Specifies a `GossipValidator` which applies additional validation to gossip blocks and
+    /// attestations.
+    ///
+    /// Must be called before `Self::network`.
+    pub fn gossip_validator(
+        mut self,
+        gossip_validator: Arc<dyn GossipValidator<TEthSpec>>,
+    ) -> Self {
+        self.gossip_validator = Some(gossip_validator);
+        self
+    }
+
     /// Starts the networking stack.
     pub async fn network(mut self, config: &NetworkConfig) -> Result<Self, String> {
         let beacon_chain = self
@@ -246,10 +281,14 @@ where
             .ok_or_else(|| "network requires a runtime_context")?
             .clone();
 
-        let (network_globals, network_send) =
-            NetworkService::start(beacon_chain, config, context.executor)
-                .await
-                .map_err(|e| format!("Failed to start network: {:?}", e))?;
+        let (network_globals, network_send) = NetworkService::start(
+            beacon_chain,
+            config,
+            self.gossip_validator.clone(),
+            context.executor,
+        )
+        .await
+        .map_err(|e| format!("Failed to start network: {:?}", e))?;
 
         self.network_globals = Some(network_globals);
         self.network_send = Some(network_send);
@@ -257,6 +296,32 @@ where
         Ok(self)
     }
 
+    /// Imports the blocks of each era file in `dir` into the beacon chain.
+    ///
+    /// Must be called before `Self::network`, so that the blocks are not also downloaded from
+    /// peers.
+    pub fn import_era_files(self, dir: &Path) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "import_era_files requires a beacon chain")?;
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "import_era_files requires a runtime_context")?;
+
+        let imported_blocks = import_era_files(&beacon_chain, dir)?;
+
+        info!(
+            context.log(),
+            "Imported era files";
+            "dir" => format!("{:?}", dir),
+            "imported_blocks" => imported_blocks,
+        );
+
+        Ok(self)
+    }
+
     /// Immediately starts the timer service.
     fn timer(self) -> Result<Self, String> {
         let context = self
@@ -285,7 +350,6 @@ where
         mut self,
         client_config: &ClientConfig,
         eth2_config: &Eth2Config,
-        events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
     ) -> Result<Self, String> {
         let beacon_chain = self
             .beacon_chain
@@ -308,8 +372,23 @@ where
         let network_info = rest_api::NetworkInfo {
             network_globals,
             network_chan: network_send,
+            boot_enrs: client_config
+                .network
+                .boot_nodes_enr
+                .iter()
+                .map(|enr| enr.to_base64())
+                .collect(),
+            deposit_contract: rest_api::DepositContract {
+                chain_id: client_config.eth1.chain_id.as_u64(),
+                address: client_config.eth1.deposit_contract_address.clone(),
+                deploy_block: client_config.eth1.deposit_contract_deploy_block,
+            },
         };
 
+        let request_limiter = Arc::new(RequestLimiter::new(
+            client_config.rest_api.max_concurrent_heavy_requests,
+        ));
+
         let listening_addr = rest_api::start_server(
             context.executor,
             &client_config.rest_api,
@@ -322,11 +401,12 @@ where
                 .create_freezer_db_path()
                 .map_err(|_| "unable to read freezer DB dir")?,
             eth2_config.clone(),
-            events,
+            request_limiter.clone(),
         )
         .map_err(|e| format!("Failed to start HTTP API: {:?}", e))?;
 
         self.http_listen_addr = Some(listening_addr);
+        self.http_request_limiter = Some(request_limiter);
 
         Ok(self)
     }
@@ -383,7 +463,9 @@ where
         Client {
             beacon_chain: self.beacon_chain,
             network_globals: self.network_globals,
+            network_send: self.network_send,
             http_listen_addr: self.http_listen_addr,
+            http_request_limiter: self.http_request_limiter,
             websocket_listen_addr: self.websocket_listen_addr,
         }
     }
@@ -456,19 +538,19 @@ where
     THotStore: ItemStore<TEthSpec> + 'static,
     TColdStore: ItemStore<TEthSpec> + 'static,
 {
-    #[allow(clippy::type_complexity)]
-    /// Specifies that the `BeaconChain` should publish events using the WebSocket server.
+    /// Specifies that the `BeaconChain` should publish events using the WebSocket server and the
+    /// webhook notifier.
     pub fn tee_event_handler(
         mut self,
         config: WebSocketConfig,
-    ) -> Result<(Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>), String> {
+        webhook_config: WebhookConfig,
+    ) -> Result<Self, String> {
         let context = self
             .runtime_context
             .as_ref()
             .ok_or_else(|| "tee_event_handler requires a runtime_context")?
             .service_context("ws".into());
 
-        let log = context.log().clone();
         let (sender, listening_addr): (WebSocketSender<TEthSpec>, Option<_>) = if config.enabled {
             let (sender, listening_addr) =
                 websocket_server::start_server(context.executor, &config)?;
@@ -477,10 +559,33 @@ where
             (WebSocketSender::dummy(), None)
         };
 
+        let webhook_sender = if webhook_config.enabled {
+            let spec = self
+                .chain_spec
+                .as_ref()
+                .ok_or_else(|| "webhook notifier requires a chain spec".to_string())?;
+            let seconds_per_epoch = spec.milliseconds_per_slot * TEthSpec::slots_per_epoch() / 1000;
+            let context = self
+                .runtime_context
+                .as_ref()
+                .ok_or_else(|| "webhook notifier requires a runtime_context")?
+                .service_context("webhook".into());
+
+            webhook_notifier::start_notifier(context.executor, &webhook_config, seconds_per_epoch)?
+        } else {
+            WebhookSender::dummy()
+        };
+
+        let log = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "tee_event_handler requires a runtime_context")?
+            .log()
+            .clone();
+
         self.websocket_listen_addr = listening_addr;
-        let (tee_event_handler, bus) = TeeEventHandler::new(log, sender)?;
-        self.event_handler = Some(tee_event_handler);
-        Ok((self, bus))
+        self.event_handler = Some(TeeEventHandler::new(sender, webhook_sender, log));
+        Ok(self)
     }
 }
 
@@ -626,22 +731,29 @@ where
         } else {
             beacon_chain_builder
                 .get_persisted_eth1_backend()?
-                .map(|persisted| {
+                .and_then(|persisted| {
+                    // The cache can always be rebuilt from the eth1 node, so a cache persisted in an
+                    // incompatible format is discarded rather than preventing start-up.
                     Eth1Chain::from_ssz_container(
                         &persisted,
                         config.clone(),
                         &context.log().clone(),
                         spec.clone(),
                     )
-                    .map(|chain| chain.into_backend())
+                    .map_err(|e| {
+                        warn!(
+                            context.log(),
+                            "Discarding persisted eth1 cache";
+                            "error" => e,
+                            "info" => "the cache will be rebuilt from the eth1 node"
+                        )
+                    })
+                    .ok()
                 })
+                .map(|chain| chain.into_backend())
                 .unwrap_or_else(|| {
-                    Ok(CachingEth1Backend::new(
-                        config,
-                        context.log().clone(),
-                        spec.clone(),
-                    ))
-                })?
+                    CachingEth1Backend::new(config, context.log().clone(), spec.clone())
+                })
         };
 
         self.eth1_service = None;
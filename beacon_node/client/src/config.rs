@@ -66,7 +66,12 @@ pub struct Config {
     pub rest_api: rest_api::Config,
     pub chain: beacon_chain::ChainConfig,
     pub websocket_server: websocket_server::Config,
+    pub webhook: webhook_notifier::Config,
     pub eth1: eth1::Config,
+    /// The connection to the execution engine, if any.
+    pub execution_layer: Option<execution_layer::Config>,
+    /// A directory of era files whose blocks are imported when the node starts.
+    pub import_era_dir: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -82,10 +87,13 @@ impl Default for Config {
             chain: <_>::default(),
             rest_api: <_>::default(),
             websocket_server: <_>::default(),
+            webhook: <_>::default(),
             spec_constants: TESTNET_SPEC_CONSTANTS.into(),
             dummy_eth1_backend: false,
             sync_eth1_chain: false,
             eth1: <_>::default(),
+            execution_layer: None,
+            import_era_dir: None,
             disabled_forks: Vec::new(),
             graffiti: Graffiti::default(),
         }
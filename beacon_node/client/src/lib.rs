@@ -9,8 +9,11 @@ pub mod error;
 
 use beacon_chain::BeaconChain;
 use eth2_libp2p::{Enr, Multiaddr, NetworkGlobals};
+use network::NetworkMessage;
+use rest_api::RequestLimiter;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
 
 pub use beacon_chain::{BeaconChainTypes, Eth1ChainBackend};
 pub use builder::ClientBuilder;
@@ -23,7 +26,9 @@ pub use eth2_config::Eth2Config;
 pub struct Client<T: BeaconChainTypes> {
     beacon_chain: Option<Arc<BeaconChain<T>>>,
     network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
+    network_send: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     http_listen_addr: Option<SocketAddr>,
+    http_request_limiter: Option<Arc<RequestLimiter>>,
     websocket_listen_addr: Option<SocketAddr>,
 }
 
@@ -33,11 +38,21 @@ impl<T: BeaconChainTypes> Client<T> {
         self.beacon_chain.clone()
     }
 
+    /// Returns a channel to the client's network service, if it was started.
+    pub fn network_send(&self) -> Option<UnboundedSender<NetworkMessage<T::EthSpec>>> {
+        self.network_send.clone()
+    }
+
     /// Returns the address of the client's HTTP API server, if it was started.
     pub fn http_listen_addr(&self) -> Option<SocketAddr> {
         self.http_listen_addr
     }
 
+    /// Returns the limiter of the heavy requests to the client's HTTP API server, if it was started.
+    pub fn http_request_limiter(&self) -> Option<Arc<RequestLimiter>> {
+        self.http_request_limiter.clone()
+    }
+
     /// Returns the address of the client's WebSocket API server, if it was started.
     pub fn websocket_listen_addr(&self) -> Option<SocketAddr> {
         self.websocket_listen_addr
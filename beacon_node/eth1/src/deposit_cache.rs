@@ -1,4 +1,6 @@
 use crate::DepositLog;
+use merkle_proof::MerkleTree;
+use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
 use std::cmp::Ordering;
@@ -73,6 +75,25 @@ impl SszDepositCache {
     }
 }
 
+/// A compact representation of the first `deposit_count` deposits of the deposit contract tree.
+///
+/// Holds only the roots of the subtrees which are full, rather than every deposit, so that another
+/// node may resume following the deposit contract from `eth1_block_number` without downloading all
+/// of the prior deposit logs.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct DepositTreeSnapshot {
+    /// The roots of the full subtrees which together contain the first `deposit_count` leaves,
+    /// ordered from the leftmost (largest) subtree.
+    pub finalized: Vec<Hash256>,
+    /// The root of the deposit tree, including the mixed-in deposit count.
+    pub deposit_root: Hash256,
+    pub deposit_count: u64,
+    /// The hash of the eth1 block which included the last deposit.
+    pub eth1_block_hash: Hash256,
+    /// The number of the eth1 block which included the last deposit.
+    pub eth1_block_number: u64,
+}
+
 /// Mirrors the merkle tree of deposits in the eth1 deposit contract.
 ///
 /// Provides `Deposit` objects with merkle proofs included.
@@ -127,6 +148,13 @@ impl DepositCache {
         self.logs.last().map(|log| log.block_number)
     }
 
+    /// Returns the block number and block hash for the most recent deposit in the cache.
+    pub fn latest_block(&self) -> Option<(u64, Hash256)> {
+        self.logs
+            .last()
+            .map(|log| (log.block_number, log.block_hash))
+    }
+
     /// Returns an iterator over all the logs in `self`.
     pub fn iter(&self) -> impl Iterator<Item = &DepositLog> {
         self.logs.iter()
@@ -172,6 +200,74 @@ impl DepositCache {
         }
     }
 
+    /// Removes all logs from blocks with a number greater than or equal to `block_number`,
+    /// returning the number of logs removed.
+    ///
+    /// Used to discard the deposits of blocks which are no longer in the canonical eth1 chain.
+    pub fn remove_logs_from_block(&mut self, block_number: u64) -> usize {
+        let retained = self
+            .logs
+            .iter()
+            .take_while(|log| log.block_number < block_number)
+            .count();
+        let removed = self.logs.len() - retained;
+
+        if removed > 0 {
+            self.logs.truncate(retained);
+            self.leaves.truncate(retained);
+            // `deposit_roots` also includes the zero root.
+            self.deposit_roots.truncate(retained + 1);
+            // The incremental tree cannot remove leaves, so it is rebuilt.
+            self.deposit_tree = DepositDataTree::create(&self.leaves, retained, DEPOSIT_TREE_DEPTH);
+        }
+
+        removed
+    }
+
+    /// Returns a snapshot of the tree containing the first `deposit_count` deposits.
+    ///
+    /// ## Errors
+    ///
+    /// - If there are fewer than `deposit_count` deposits in the cache.
+    pub fn get_snapshot(&self, deposit_count: u64) -> Result<DepositTreeSnapshot, Error> {
+        let leaves = self.leaves.get(0..deposit_count as usize).ok_or_else(|| {
+            Error::InsufficientDeposits {
+                requested: deposit_count,
+                known_deposits: self.logs.len(),
+            }
+        })?;
+        let deposit_root = *self
+            .deposit_roots
+            .get(deposit_count as usize)
+            .ok_or_else(|| Error::InternalError("Unable to get known deposit root".into()))?;
+
+        // Each set bit of the deposit count corresponds to a full subtree of that size.
+        let mut finalized = vec![];
+        let mut start = 0;
+        for depth in (0..DEPOSIT_TREE_DEPTH).rev() {
+            let size = 1 << depth;
+            if deposit_count as usize & size != 0 {
+                let subtree = MerkleTree::create(&leaves[start..start + size], depth);
+                finalized.push(subtree.hash());
+                start += size;
+            }
+        }
+
+        let (eth1_block_number, eth1_block_hash) = deposit_count
+            .checked_sub(1)
+            .and_then(|index| self.logs.get(index as usize))
+            .map(|log| (log.block_number, log.block_hash))
+            .unwrap_or_else(|| (self.deposit_contract_deploy_block, Hash256::zero()));
+
+        Ok(DepositTreeSnapshot {
+            finalized,
+            deposit_root,
+            deposit_count,
+            eth1_block_hash,
+            eth1_block_number,
+        })
+    }
+
     /// Returns a list of `Deposit` objects, within the given deposit index `range`.
     ///
     /// The `deposit_count` is used to generate the proofs for the `Deposits`. For example, if we
@@ -302,6 +398,7 @@ pub mod tests {
 
         let log = Log {
             block_number: 42,
+            block_hash: Hash256::zero(),
             data: EXAMPLE_LOG.to_vec(),
         };
         DepositLog::from_log(&log, &spec).expect("should decode log")
@@ -422,6 +519,79 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn remove_logs_from_block() {
+        let n = 8;
+        let mut tree = DepositCache::default();
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i / 2;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+
+        let root_at_block_1 = tree.get_deposit_root_from_cache(1);
+
+        assert_eq!(tree.remove_logs_from_block(4), 0, "no logs from block 4");
+        assert_eq!(tree.remove_logs_from_block(2), 4);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.latest_block_number(), Some(1));
+        assert_eq!(tree.get_deposit_root_from_cache(3), root_at_block_1);
+
+        // Logs from the new canonical chain can be inserted in place of those removed.
+        let mut log = example_log();
+        log.index = 4;
+        log.block_number = 2;
+        log.block_hash = Hash256::repeat_byte(2);
+        tree.insert_log(log).expect("should add log after removal");
+        assert_eq!(tree.latest_block(), Some((2, Hash256::repeat_byte(2))));
+
+        let (root, _) = tree
+            .get_deposits(0, 5, 5, TREE_DEPTH)
+            .expect("should get deposits");
+        assert_eq!(tree.get_deposit_root_from_cache(2), Some(root));
+    }
+
+    #[test]
+    fn get_snapshot() {
+        let n = 13;
+        let mut tree = DepositCache::default();
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+
+        for deposit_count in 0..=n {
+            let snapshot = tree
+                .get_snapshot(deposit_count)
+                .expect("should get snapshot");
+            let (root, _) = tree
+                .get_deposits(0, 0, deposit_count, TREE_DEPTH)
+                .expect("should get deposit root");
+
+            assert_eq!(snapshot.deposit_root, root);
+            assert_eq!(snapshot.deposit_count, deposit_count);
+            assert_eq!(
+                snapshot.finalized.len(),
+                deposit_count.count_ones() as usize,
+                "one subtree per set bit of the deposit count"
+            );
+        }
+
+        // 13 = 8 + 4 + 1, so the last subtree is the 13th leaf.
+        let snapshot = tree.get_snapshot(n).expect("should get snapshot");
+        assert_eq!(snapshot.finalized[2], tree.leaves[12]);
+        assert_eq!(snapshot.eth1_block_number, n - 1);
+
+        assert!(tree.get_snapshot(n + 1).is_err());
+    }
+
     #[test]
     fn get_deposit_invalid() {
         let n = 16;
@@ -25,6 +25,8 @@ pub struct DepositLog {
     pub deposit_data: DepositData,
     /// The block number of the log that included this `DepositData`.
     pub block_number: u64,
+    /// The hash of the block that included this `DepositData`, used to detect eth1 re-orgs.
+    pub block_hash: Hash256,
     /// The index included with the deposit log.
     pub index: u64,
     /// True if the signature is valid.
@@ -69,6 +71,7 @@ impl DepositLog {
         Ok(DepositLog {
             deposit_data,
             block_number: log.block_number,
+            block_hash: log.block_hash,
             index: u64::from_ssz_bytes(index).map_err(|e| format!("Invalid index ssz: {:?}", e))?,
             signature_is_valid,
         })
@@ -111,6 +114,7 @@ pub mod tests {
     fn can_parse_example_log() {
         let log = Log {
             block_number: 42,
+            block_hash: Hash256::zero(),
             data: EXAMPLE_LOG.to_vec(),
         };
         DepositLog::from_log(&log, &MainnetEthSpec::default_spec()).expect("should decode log");
@@ -39,6 +39,17 @@ pub enum Eth1NetworkId {
     Custom(u64),
 }
 
+impl Eth1NetworkId {
+    /// Returns the numeric network id, as returned by `net_version`.
+    pub fn as_u64(&self) -> u64 {
+        match self {
+            Eth1NetworkId::Mainnet => 1,
+            Eth1NetworkId::Goerli => 5,
+            Eth1NetworkId::Custom(network_id) => *network_id,
+        }
+    }
+}
+
 impl FromStr for Eth1NetworkId {
     type Err = String;
 
@@ -259,6 +270,7 @@ async fn call(
 #[derive(Debug, PartialEq, Clone)]
 pub struct Log {
     pub(crate) block_number: u64,
+    pub(crate) block_hash: Hash256,
     pub(crate) data: Vec<u8>,
 }
 
@@ -295,6 +307,17 @@ pub async fn get_deposit_logs_in_range(
                 .as_str()
                 .ok_or_else(|| "Block number was not string")?;
 
+            let block_hash = hex_to_bytes(
+                value
+                    .get("blockHash")
+                    .ok_or_else(|| "No block hash field in log")?
+                    .as_str()
+                    .ok_or_else(|| "Block hash was not string")?,
+            )?;
+            if block_hash.len() != 32 {
+                return Err(format!("Block hash was not 32 bytes: {:?}", block_hash));
+            }
+
             let data = value
                 .get("data")
                 .ok_or_else(|| "No block number field in log")?
@@ -303,6 +326,7 @@ pub async fn get_deposit_logs_in_range(
 
             Ok(Log {
                 block_number: hex_to_u64_be(&block_number)?,
+                block_hash: Hash256::from_slice(&block_hash),
                 data: hex_to_bytes(data)?,
             })
         })
@@ -16,4 +16,6 @@ lazy_static! {
         try_create_int_gauge("eth1_deposit_cache_len", "Number of deposits in the eth1 cache");
     pub static ref HIGHEST_PROCESSED_DEPOSIT_BLOCK: Result<IntGauge> =
         try_create_int_gauge("eth1_highest_processed_deposit_block", "Number of the last block checked for deposits");
+    pub static ref DEPOSITS_REMOVED_BY_REORG_TOTAL: Result<IntCounter> =
+        try_create_int_counter("eth1_deposits_removed_by_reorg_total", "Count of cached deposits removed because their block was re-orged out of the eth1 chain");
 }
@@ -11,7 +11,7 @@ use crate::{
 use futures::{future::TryFutureExt, stream, stream::TryStreamExt, StreamExt};
 use parking_lot::{RwLock, RwLockReadGuard};
 use serde::{Deserialize, Serialize};
-use slog::{debug, error, info, trace, Logger};
+use slog::{debug, error, info, trace, warn, Logger};
 use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -20,6 +20,8 @@ use types::ChainSpec;
 
 /// Indicates the default eth1 network we use for the deposit contract.
 pub const DEFAULT_NETWORK_ID: Eth1NetworkId = Eth1NetworkId::Goerli;
+/// Indicates the default eth1 chain id we use for the deposit contract.
+pub const DEFAULT_CHAIN_ID: Eth1NetworkId = Eth1NetworkId::Goerli;
 
 const STANDARD_TIMEOUT_MILLIS: u64 = 15_000;
 
@@ -83,6 +85,10 @@ pub struct Config {
     pub deposit_contract_address: String,
     /// The eth1 network id where the deposit contract is deployed (Goerli/Mainnet).
     pub network_id: Eth1NetworkId,
+    /// The eth1 chain id (EIP-155) where the deposit contract is deployed.
+    ///
+    /// This is distinct from `network_id`; some networks use different values for each.
+    pub chain_id: Eth1NetworkId,
     /// Defines the first block that the `DepositCache` will start searching for deposit logs.
     ///
     /// Setting too high can result in missed logs. Setting too low will result in unnecessary
@@ -113,6 +119,7 @@ impl Default for Config {
             endpoint: "http://localhost:8545".into(),
             deposit_contract_address: "0x0000000000000000000000000000000000000000".into(),
             network_id: DEFAULT_NETWORK_ID,
+            chain_id: DEFAULT_CHAIN_ID,
             deposit_contract_deploy_block: 1,
             lowest_cached_block_number: 1,
             follow_distance: 128,
@@ -413,6 +420,8 @@ impl Service {
     ///
     /// Emits logs for debugging and errors.
     pub async fn update_deposit_cache(&self) -> Result<DepositCacheUpdateOutcome, Error> {
+        self.remove_reorged_deposits().await?;
+
         let endpoint = self.config().endpoint.clone();
         let follow_distance = self.config().follow_distance;
         let deposit_contract_address = self.config().deposit_contract_address.clone();
@@ -536,6 +545,55 @@ impl Service {
         Ok(DepositCacheUpdateOutcome { logs_imported })
     }
 
+    /// Checks that the block which included the most recent deposit in the cache is still in the
+    /// canonical eth1 chain, removing the deposits of each block which is not so that they are
+    /// downloaded again from the canonical chain.
+    ///
+    /// Returns the number of deposits removed.
+    async fn remove_reorged_deposits(&self) -> Result<usize, Error> {
+        let endpoint = self.config().endpoint.clone();
+        let mut removed = 0;
+
+        loop {
+            let latest_block = self.deposits().read().cache.latest_block();
+            let (block_number, block_hash) = match latest_block {
+                Some(latest_block) => latest_block,
+                None => break,
+            };
+
+            let block = get_block(
+                &endpoint,
+                block_number,
+                Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+            )
+            .map_err(Error::BlockDownloadFailed)
+            .await?;
+
+            if block.hash == block_hash {
+                break;
+            }
+
+            let mut deposits = self.deposits().write();
+            removed += deposits.cache.remove_logs_from_block(block_number);
+            deposits.last_processed_block = Some(block_number.saturating_sub(1));
+        }
+
+        if removed > 0 {
+            let deposits = self.deposits().read();
+            warn!(
+                self.log,
+                "Removed deposits from re-orged eth1 blocks";
+                "removed" => removed,
+                "latest_block" => deposits.cache.latest_block_number(),
+                "total" => deposits.cache.len(),
+            );
+            metrics::inc_counter_by(&metrics::DEPOSITS_REMOVED_BY_REORG_TOTAL, removed as i64);
+            metrics::set_gauge(&metrics::DEPOSIT_CACHE_LEN, deposits.cache.len() as i64);
+        }
+
+        Ok(removed)
+    }
+
     /// Contacts the remote eth1 node and attempts to import all blocks up to the configured
     /// follow-distance block.
     ///
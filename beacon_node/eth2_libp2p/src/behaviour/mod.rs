@@ -2,7 +2,9 @@ use crate::peer_manager::{score::PeerAction, PeerManager, PeerManagerEvent};
 use crate::rpc::*;
 use crate::types::{EnrBitfield, GossipEncoding, GossipKind, GossipTopic, SubnetDiscovery};
 use crate::Eth2Enr;
-use crate::{error, metrics, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash};
+use crate::{
+    error, metrics, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash, TrustedPeer,
+};
 use futures::prelude::*;
 use handler::{BehaviourHandler, BehaviourHandlerIn, BehaviourHandlerOut, DelegateIn, DelegateOut};
 use libp2p::{
@@ -30,6 +32,7 @@ use std::path::PathBuf;
 use std::{
     collections::VecDeque,
     marker::PhantomData,
+    net::Ipv4Addr,
     sync::Arc,
     task::{Context, Poll},
 };
@@ -351,6 +354,26 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.peer_manager.discovery_mut().add_enr(enr);
     }
 
+    /// Changes the target number of peers.
+    pub fn set_target_peers(&mut self, target_peers: usize) {
+        self.peer_manager.set_target_peers(target_peers);
+    }
+
+    /// Adds a trusted peer, to which a connection is always maintained.
+    pub fn add_trusted_peer(&mut self, peer: &TrustedPeer) {
+        self.peer_manager.add_trusted_peer(peer);
+    }
+
+    /// Removes a trusted peer, returning false if it was not trusted.
+    pub fn remove_trusted_peer(&mut self, peer_id: &PeerId) -> bool {
+        self.peer_manager.remove_trusted_peer(peer_id)
+    }
+
+    /// Returns the ENR of a peer, if it is known to the discovery mechanism.
+    pub fn enr_of_peer(&mut self, peer_id: &PeerId) -> Option<Enr> {
+        self.peer_manager.discovery_mut().enr_of_peer(peer_id)
+    }
+
     /// Updates a subnet value to the ENR bitfield.
     ///
     /// The `value` is `true` if a subnet is being added and false otherwise.
@@ -366,6 +389,22 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.update_metadata();
     }
 
+    /// Updates the IPv4 address and ports of the local ENR with those mapped on a NAT gateway.
+    pub fn update_enr_nat_mapping(
+        &mut self,
+        external_address: Option<Ipv4Addr>,
+        tcp_port: Option<u16>,
+        udp_port: Option<u16>,
+    ) {
+        if let Err(e) = self.peer_manager.discovery_mut().update_enr_socket(
+            external_address,
+            tcp_port,
+            udp_port,
+        ) {
+            warn!(self.log, "Could not update ENR with NAT mapping"; "error" => e);
+        }
+    }
+
     /// Attempts to discover new peers for a given subnet. The `min_ttl` gives the time at which we
     /// would like to retain the peers for.
     pub fn discover_subnet_peers(&mut self, subnet_subscriptions: Vec<SubnetDiscovery>) {
@@ -807,6 +846,7 @@ impl<TSpec: EthSpec> NetworkBehaviour for Behaviour<TSpec> {
             // If the peer is banned, send goodbye with reason banned.
             Some(GoodbyeReason::Banned)
         } else if self.peer_manager.peer_limit_reached()
+            && !self.peer_manager.is_trusted(peer_id)
             && self
                 .network_globals
                 .peers
@@ -815,7 +855,7 @@ impl<TSpec: EthSpec> NetworkBehaviour for Behaviour<TSpec> {
                 .map_or(true, |i| !i.has_future_duty())
         {
             // If we are at our peer limit and we don't need the peer for a future validator
-            // duty, send goodbye with reason TooManyPeers
+            // duty (nor is it trusted), send goodbye with reason TooManyPeers
             Some(GoodbyeReason::TooManyPeers)
         } else {
             None
@@ -1,12 +1,15 @@
+use crate::peer_manager::score::ScoreParams;
 use crate::types::GossipKind;
-use crate::Enr;
+use crate::{Enr, TrustedPeer};
 use discv5::{Discv5Config, Discv5ConfigBuilder};
+use libp2p::core::multiaddr::Protocol;
 use libp2p::gossipsub::{
     GossipsubConfig, GossipsubConfigBuilder, GossipsubMessage, MessageId, ValidationMode,
 };
 use libp2p::Multiaddr;
 use serde_derive::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::net::Ipv6Addr;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -38,9 +41,29 @@ pub struct Config {
     /// The tcp port to broadcast to peers in order to reach back for libp2p services.
     pub enr_tcp_port: Option<u16>,
 
+    /// An additional IPv6 address for libp2p to listen on, when `listen_address` is IPv4. This
+    /// allows dual-stack operation. Discovery only listens on `listen_address`.
+    pub listen_address6: Option<Ipv6Addr>,
+
+    /// The TCP port that libp2p listens on at `listen_address6`. Defaults to `libp2p_port`.
+    pub libp2p_port6: Option<u16>,
+
+    /// The IPv6 address to broadcast to peers when dual-stack. None indicates that no address has
+    /// been set in the CLI args.
+    pub enr_address6: Option<Ipv6Addr>,
+
+    /// The tcp6 port to broadcast to peers when dual-stack. Defaults to the listening port.
+    pub enr_tcp6_port: Option<u16>,
+
     /// Target number of connected peers.
     pub target_peers: usize,
 
+    /// Maximum number of peers to persist across restarts, to be dialed on startup.
+    pub max_persisted_peers: usize,
+
+    /// Persisted peers which have not been connected for longer than this are forgotten.
+    pub persisted_peer_expiry: Duration,
+
     /// Gossipsub configuration parameters.
     #[serde(skip)]
     pub gs_config: GossipsubConfig,
@@ -58,14 +81,66 @@ pub struct Config {
     /// List of libp2p nodes to initially connect to.
     pub libp2p_nodes: Vec<Multiaddr>,
 
+    /// List of peers to which connections are always maintained, and which are exempt from
+    /// score-based disconnects.
+    pub trusted_peers: Vec<TrustedPeer>,
+
     /// Client version
     pub client_version: String,
 
     /// Disables the discovery protocol from starting.
     pub disable_discovery: bool,
 
+    /// Attempt to map the TCP and UDP ports on the local gateway with UPnP or NAT-PMP, and
+    /// advertise the external address in the ENR.
+    pub nat_enabled: bool,
+
+    /// Do not listen for inbound connections, relying solely on connections dialed by this node.
+    /// No address is advertised in the ENR.
+    pub disable_inbound: bool,
+
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
+
+    /// Peer scoring parameters.
+    pub score_params: ScoreParams,
+}
+
+impl Config {
+    /// Returns the addresses on which libp2p listens: `listen_address`, and `listen_address6` when
+    /// dual-stack. None are returned if inbound connections are disabled.
+    pub fn listen_multiaddrs(&self) -> Vec<Multiaddr> {
+        if self.disable_inbound {
+            return vec![];
+        }
+
+        let mut primary = Multiaddr::from(self.listen_address);
+        primary.push(Protocol::Tcp(self.libp2p_port));
+        let mut multiaddrs = vec![primary];
+
+        if let Some(address6) = self.dual_stack_address6() {
+            let mut secondary = Multiaddr::from(address6);
+            secondary.push(Protocol::Tcp(self.libp2p_port6.unwrap_or(self.libp2p_port)));
+            multiaddrs.push(secondary);
+        }
+        multiaddrs
+    }
+
+    /// Returns `listen_address6` if the node is dual-stack, i.e., `listen_address` is IPv4.
+    pub fn dual_stack_address6(&self) -> Option<Ipv6Addr> {
+        self.listen_address6
+            .filter(|_| self.listen_address.is_ipv4())
+    }
+
+    /// Returns true if libp2p listens on an IPv4 address.
+    pub fn listens_ipv4(&self) -> bool {
+        self.listen_address.is_ipv4()
+    }
+
+    /// Returns true if libp2p listens on an IPv6 address.
+    pub fn listens_ipv6(&self) -> bool {
+        self.listen_address.is_ipv6() || self.dual_stack_address6().is_some()
+    }
 }
 
 impl Default for Config {
@@ -137,15 +212,25 @@ impl Default for Config {
             enr_address: None,
             enr_udp_port: None,
             enr_tcp_port: None,
+            listen_address6: None,
+            libp2p_port6: None,
+            enr_address6: None,
+            enr_tcp6_port: None,
             target_peers: 50,
+            max_persisted_peers: 100,
+            persisted_peer_expiry: Duration::from_secs(72 * 60 * 60),
             gs_config,
             discv5_config,
             boot_nodes_enr: vec![],
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
+            trusted_peers: vec![],
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
+            nat_enabled: false,
+            disable_inbound: false,
             topics,
+            score_params: ScoreParams::default(),
         }
     }
 }
@@ -21,6 +21,10 @@ use types::{EnrForkId, EthSpec};
 pub const ETH2_ENR_KEY: &str = "eth2";
 /// The ENR field specifying the subnet bitfield.
 pub const BITFIELD_ENR_KEY: &str = "attnets";
+/// The ENR field specifying the IPv6 TCP port.
+pub const TCP6_ENR_KEY: &str = "tcp6";
+/// The ENR field specifying the IPv6 UDP port.
+pub const UDP6_ENR_KEY: &str = "udp6";
 
 /// Extension trait for ENR's within Eth2.
 pub trait Eth2Enr {
@@ -122,19 +126,51 @@ pub fn build_or_load_enr<T: EthSpec>(
 
 pub fn create_enr_builder_from_config<T: EnrKey>(config: &NetworkConfig) -> EnrBuilder<T> {
     let mut builder = EnrBuilder::new("v4");
+    // a node which does not accept inbound connections advertises no address
+    if config.disable_inbound {
+        return builder;
+    }
+    // an IPv6 address is advertised in the `ip6` field, with its ports in `udp6` and `tcp6`
+    let is_ipv6 = config
+        .enr_address
+        .map_or(config.listen_address.is_ipv6(), |address| address.is_ipv6());
     if let Some(enr_address) = config.enr_address {
         builder.ip(enr_address);
     }
-    if let Some(udp_port) = config.enr_udp_port {
-        builder.udp(udp_port);
-    }
     // we always give it our listening tcp port
     // TODO: Add uPnP support to map udp and tcp ports
     let tcp_port = config.enr_tcp_port.unwrap_or_else(|| config.libp2p_port);
-    builder.tcp(tcp_port).tcp(config.libp2p_port);
+    if is_ipv6 {
+        if let Some(udp_port) = config.enr_udp_port {
+            add_port(&mut builder, UDP6_ENR_KEY, udp_port);
+        }
+        add_port(&mut builder, TCP6_ENR_KEY, tcp_port);
+    } else {
+        if let Some(udp_port) = config.enr_udp_port {
+            builder.udp(udp_port);
+        }
+        builder.tcp(tcp_port).tcp(config.libp2p_port);
+    }
+
+    // a dual-stack node also advertises the IPv6 address that libp2p listens on
+    if config.dual_stack_address6().is_some() && !is_ipv6 {
+        if let Some(enr_address6) = config.enr_address6 {
+            builder.ip(enr_address6.into());
+        }
+        let tcp6_port = config
+            .enr_tcp6_port
+            .or(config.libp2p_port6)
+            .unwrap_or(config.libp2p_port);
+        add_port(&mut builder, TCP6_ENR_KEY, tcp6_port);
+    }
     builder
 }
 
+/// Sets a port field which `EnrBuilder` has no method for, encoded as `EnrBuilder::tcp` does.
+fn add_port<T: EnrKey>(builder: &mut EnrBuilder<T>, key: &str, port: u16) {
+    builder.add_value(key.into(), port.to_be_bytes().to_vec());
+}
+
 /// Builds a lighthouse ENR given a `NetworkConfig`.
 pub fn build_enr<T: EthSpec>(
     enr_key: &CombinedKey,
@@ -167,6 +203,10 @@ fn compare_enr(local_enr: &Enr, disk_enr: &Enr) -> bool {
         && local_enr.get(ETH2_ENR_KEY) == disk_enr.get(ETH2_ENR_KEY)
         // take preference over disk udp port if one is not specified
         && (local_enr.udp().is_none() || local_enr.udp() == disk_enr.udp())
+        // likewise for the IPv6 fields
+        && (local_enr.ip6().is_none() || local_enr.ip6() == disk_enr.ip6())
+        && local_enr.tcp6() == disk_enr.tcp6()
+        && (local_enr.udp6().is_none() || local_enr.udp6() == disk_enr.udp6())
         // we need the BITFIELD_ENR_KEY key to match, otherwise we use a new ENR. This will likely only
         // be true for non-validating nodes
         && local_enr.get(BITFIELD_ENR_KEY) == disk_enr.get(BITFIELD_ENR_KEY)
@@ -189,3 +229,68 @@ pub fn save_enr_to_disk(dir: &Path, enr: &Enr, log: &slog::Logger) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    fn build(config: &NetworkConfig) -> Enr {
+        let key = CombinedKey::generate_secp256k1();
+        create_enr_builder_from_config(config).build(&key).unwrap()
+    }
+
+    #[test]
+    fn ipv6_enr() {
+        let address = "2001:db8::1".parse::<IpAddr>().unwrap();
+        let mut config = NetworkConfig::default();
+        config.listen_address = address;
+        config.enr_address = Some(address);
+        config.enr_udp_port = Some(9001);
+
+        let enr = build(&config);
+        assert_eq!(enr.ip(), None);
+        assert_eq!(enr.tcp(), None);
+        assert_eq!(enr.ip6(), Some("2001:db8::1".parse::<Ipv6Addr>().unwrap()));
+        assert_eq!(enr.tcp6(), Some(config.libp2p_port));
+        assert_eq!(enr.udp6(), Some(9001));
+    }
+
+    #[test]
+    fn dual_stack_enr() {
+        let mut config = NetworkConfig::default();
+        config.enr_address = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        config.enr_udp_port = Some(9000);
+        config.listen_address6 = Some(Ipv6Addr::UNSPECIFIED);
+        config.libp2p_port6 = Some(9002);
+        config.enr_address6 = Some("2001:db8::1".parse().unwrap());
+
+        let enr = build(&config);
+        assert_eq!(enr.ip(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(enr.tcp(), Some(config.libp2p_port));
+        assert_eq!(enr.udp(), Some(9000));
+        assert_eq!(enr.ip6(), Some("2001:db8::1".parse::<Ipv6Addr>().unwrap()));
+        assert_eq!(enr.tcp6(), Some(9002));
+        assert_eq!(enr.udp6(), None);
+
+        // A node which only listens on IPv4 advertises no IPv6 fields.
+        config.listen_address6 = None;
+        let enr = build(&config);
+        assert_eq!(enr.ip6(), None);
+        assert_eq!(enr.tcp6(), None);
+    }
+
+    #[test]
+    fn outbound_only_enr() {
+        let mut config = NetworkConfig::default();
+        config.enr_address = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        config.enr_udp_port = Some(9000);
+        config.disable_inbound = true;
+
+        let enr = build(&config);
+        assert_eq!(enr.ip(), None);
+        assert_eq!(enr.tcp(), None);
+        assert_eq!(enr.udp(), None);
+        assert!(config.listen_multiaddrs().is_empty());
+    }
+}
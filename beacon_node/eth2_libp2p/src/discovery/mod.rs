@@ -20,7 +20,7 @@ use ssz::{Decode, Encode};
 use ssz_types::BitVector;
 use std::{
     collections::{HashMap, VecDeque},
-    net::SocketAddr,
+    net::{Ipv4Addr, SocketAddr},
     path::Path,
     pin::Pin,
     sync::Arc,
@@ -157,6 +157,11 @@ pub struct Discovery<TSpec: EthSpec> {
     /// always false.
     started: bool,
 
+    /// Whether libp2p listens on IPv4 and IPv6 respectively. Only peers reachable over one of
+    /// these are returned by queries.
+    listens_ipv4: bool,
+    listens_ipv6: bool,
+
     /// Logger for the discovery behaviour.
     log: slog::Logger,
 }
@@ -178,9 +183,16 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
         let local_enr = network_globals.local_enr.read().clone();
 
-        info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> format!("{}",local_enr.node_id()), "ip" => format!("{:?}", local_enr.ip()), "udp"=> format!("{:?}", local_enr.udp()), "tcp" => format!("{:?}", local_enr.tcp()));
+        info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> format!("{}",local_enr.node_id()), "ip" => format!("{:?}", local_enr.ip()), "udp"=> format!("{:?}", local_enr.udp()), "tcp" => format!("{:?}", local_enr.tcp()), "ip6" => format!("{:?}", local_enr.ip6()), "udp6"=> format!("{:?}", local_enr.udp6()), "tcp6" => format!("{:?}", local_enr.tcp6()));
 
-        let listen_socket = SocketAddr::new(config.listen_address, config.discovery_port);
+        // without inbound connections, queries are sent from an ephemeral port which is not
+        // advertised
+        let discovery_port = if config.disable_inbound {
+            0
+        } else {
+            config.discovery_port
+        };
+        let listen_socket = SocketAddr::new(config.listen_address, discovery_port);
 
         // convert the keypair into an ENR key
         let enr_key: CombinedKey = CombinedKey::from_libp2p(&local_key)?;
@@ -277,6 +289,8 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
             discv5,
             event_stream,
             started: !config.disable_discovery,
+            listens_ipv4: config.listens_ipv4(),
+            listens_ipv6: config.listens_ipv6(),
             log,
             enr_dir,
         })
@@ -436,6 +450,38 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         enr::save_enr_to_disk(Path::new(&self.enr_dir), &self.local_enr(), &self.log);
     }
 
+    /// Updates the IPv4 address and ports of the local ENR, e.g., with those mapped on a NAT
+    /// gateway.
+    pub fn update_enr_socket(
+        &mut self,
+        ip: Option<Ipv4Addr>,
+        tcp_port: Option<u16>,
+        udp_port: Option<u16>,
+    ) -> Result<(), String> {
+        if let Some(ip) = ip {
+            self.discv5
+                .enr_insert("ip", ip.octets().to_vec())
+                .map_err(|e| format!("{:?}", e))?;
+        }
+        if let Some(tcp_port) = tcp_port {
+            self.discv5
+                .enr_insert("tcp", tcp_port.to_be_bytes().to_vec())
+                .map_err(|e| format!("{:?}", e))?;
+        }
+        if let Some(udp_port) = udp_port {
+            self.discv5
+                .enr_insert("udp", udp_port.to_be_bytes().to_vec())
+                .map_err(|e| format!("{:?}", e))?;
+        }
+
+        // replace the global version
+        *self.network_globals.local_enr.write() = self.discv5.local_enr();
+
+        // persist modified enr to disk
+        enr::save_enr_to_disk(Path::new(&self.enr_dir), &self.local_enr(), &self.log);
+        Ok(())
+    }
+
     /* Internal Functions */
 
     /// Adds a subnet query if one doesn't exist. If a subnet query already exists, this
@@ -638,9 +684,17 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         // predicate for finding nodes with a matching fork
         let eth2_fork_predicate = move |enr: &Enr| enr.eth2() == Ok(enr_fork_id.clone());
 
+        // predicate for finding nodes which share an IP version with us, so we can dial them
+        let (listens_ipv4, listens_ipv6) = (self.listens_ipv4, self.listens_ipv6);
+        let reachable_predicate = move |enr: &Enr| {
+            (listens_ipv4 && enr.ip().is_some() && enr.tcp().is_some())
+                || (listens_ipv6 && enr.ip6().is_some() && enr.tcp6().is_some())
+        };
+
         // General predicate
-        let predicate: Box<dyn Fn(&Enr) -> bool + Send> =
-            Box::new(move |enr: &Enr| eth2_fork_predicate(enr) && additional_predicate(enr));
+        let predicate: Box<dyn Fn(&Enr) -> bool + Send> = Box::new(move |enr: &Enr| {
+            eth2_fork_predicate(enr) && reachable_predicate(enr) && additional_predicate(enr)
+        });
 
         // Build the future
         let query_future = self
@@ -4,13 +4,13 @@ pub use self::peerdb::*;
 use crate::discovery::{subnet_predicate, Discovery, DiscoveryEvent, TARGET_SUBNET_PEERS};
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
 use crate::{error, metrics};
-use crate::{EnrExt, NetworkConfig, NetworkGlobals, PeerId, SubnetDiscovery};
+use crate::{EnrExt, NetworkConfig, NetworkGlobals, PeerId, SubnetDiscovery, TrustedPeer};
 use futures::prelude::*;
 use futures::Stream;
 use hashset_delay::HashSetDelay;
 use libp2p::core::multiaddr::Protocol as MProtocol;
 use libp2p::identify::IdentifyInfo;
-use slog::{crit, debug, error};
+use slog::{crit, debug, error, info};
 use smallvec::SmallVec;
 use std::{
     net::SocketAddr,
@@ -31,7 +31,7 @@ pub(crate) mod score;
 
 pub use peer_info::{PeerConnectionStatus::*, PeerInfo};
 pub use peer_sync_status::{PeerSyncStatus, SyncInfo};
-use score::{PeerAction, ScoreState};
+use score::{PeerAction, ScoreParams, ScoreState};
 use std::collections::HashMap;
 /// The time in seconds between re-status's peers.
 const STATUS_INTERVAL: u64 = 300;
@@ -46,6 +46,8 @@ const HEARTBEAT_INTERVAL: u64 = 30;
 /// A fraction of `PeerManager::target_peers` that we allow to connect to us in excess of
 /// `PeerManager::target_peers`. For clarity, if `PeerManager::target_peers` is 50 and
 /// PEER_EXCESS_FACTOR = 0.1 we allow 10% more nodes, i.e 55.
+///
+/// No excess is allowed when inbound connections are disabled, since every peer is dialed.
 const PEER_EXCESS_FACTOR: f32 = 0.1;
 
 /// The main struct that handles peer's reputation and connection status.
@@ -62,6 +64,10 @@ pub struct PeerManager<TSpec: EthSpec> {
     target_peers: usize,
     /// The maximum number of peers we allow (exceptions for subnet peers)
     max_peers: usize,
+    /// The fraction of `target_peers` which may connect in excess of it.
+    peer_excess_factor: f32,
+    /// The parameters which determine how peers are scored.
+    score_params: ScoreParams,
     /// The discovery service.
     discovery: Discovery<TSpec>,
     /// The heartbeat interval to perform routine maintenance.
@@ -70,6 +76,11 @@ pub struct PeerManager<TSpec: EthSpec> {
     log: slog::Logger,
 }
 
+/// Returns the maximum number of peers allowed to connect for `target_peers`.
+fn max_peers(target_peers: usize, peer_excess_factor: f32) -> usize {
+    (target_peers as f32 * (1.0 + peer_excess_factor)).ceil() as usize
+}
+
 /// The events that the `PeerManager` outputs (requests).
 pub enum PeerManagerEvent {
     /// Dial a PeerId.
@@ -102,13 +113,29 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
         let heartbeat = tokio::time::interval(tokio::time::Duration::from_secs(HEARTBEAT_INTERVAL));
 
+        // trusted peers are dialed in the first heartbeat
+        {
+            let mut peerdb = network_globals.peers.write();
+            for peer in &config.trusted_peers {
+                peerdb.add_trusted_peer(&peer.peer_id(), peer.multiaddrs());
+            }
+        }
+
+        let peer_excess_factor = if config.disable_inbound {
+            0.0
+        } else {
+            PEER_EXCESS_FACTOR
+        };
+
         Ok(PeerManager {
             network_globals,
             events: SmallVec::new(),
             ping_peers: HashSetDelay::new(Duration::from_secs(PING_INTERVAL)),
             status_peers: HashSetDelay::new(Duration::from_secs(STATUS_INTERVAL)),
             target_peers: config.target_peers,
-            max_peers: (config.target_peers as f32 * (1.0 + PEER_EXCESS_FACTOR)).ceil() as usize,
+            max_peers: max_peers(config.target_peers, peer_excess_factor),
+            peer_excess_factor,
+            score_params: config.score_params,
             discovery,
             heartbeat,
             log: log.clone(),
@@ -125,17 +152,61 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.connect_peer(peer_id, ConnectingType::Dialing)
     }
 
+    /// Changes the target number of peers.
+    ///
+    /// Peers in excess of the new target are disconnected on the next heartbeat.
+    pub fn set_target_peers(&mut self, target_peers: usize) {
+        info!(self.log, "Updated target peers"; "target_peers" => target_peers);
+        self.target_peers = target_peers;
+        self.max_peers = max_peers(target_peers, self.peer_excess_factor);
+    }
+
+    /// Adds a trusted peer, dialing it if it isn't connected.
+    ///
+    /// A connection to a trusted peer is always maintained, and it is neither scored nor banned.
+    pub fn add_trusted_peer(&mut self, peer: &TrustedPeer) {
+        let peer_id = peer.peer_id();
+        info!(self.log, "Adding trusted peer"; "peer_id" => peer_id.to_string(), "peer" => peer.to_string());
+        let is_connected_or_dialing = {
+            let mut peerdb = self.network_globals.peers.write();
+            peerdb.add_trusted_peer(&peer_id, peer.multiaddrs());
+            peerdb.is_connected_or_dialing(&peer_id)
+        };
+        if !is_connected_or_dialing {
+            self.dial_peer(&peer_id);
+        }
+    }
+
+    /// Removes a trusted peer, returning false if it was not trusted. The connection to the peer is
+    /// kept, subject to the usual scoring.
+    pub fn remove_trusted_peer(&mut self, peer_id: &PeerId) -> bool {
+        let removed = self
+            .network_globals
+            .peers
+            .write()
+            .remove_trusted_peer(peer_id);
+        if removed {
+            info!(self.log, "Removed trusted peer"; "peer_id" => peer_id.to_string());
+        }
+        removed
+    }
+
     /// The application layer wants to disconnect from a peer for a particular reason.
     ///
-    /// All instant disconnections are fatal and we ban the associated peer.
+    /// All instant disconnections are fatal and we ban the associated peer, unless it is trusted.
     ///
     /// This will send a goodbye and disconnect the peer if it is connected or dialing.
     pub fn goodbye_peer(&mut self, peer_id: &PeerId, reason: GoodbyeReason) {
         // get the peer info
-        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+        let mut peerdb = self.network_globals.peers.write();
+        let is_trusted = peerdb.is_trusted(peer_id);
+        if let Some(info) = peerdb.peer_info_mut(peer_id) {
             debug!(self.log, "Sending goodbye to peer"; "peer_id" => peer_id.to_string(), "reason" => reason.to_string(), "score" => info.score.to_string());
             // Goodbye's are fatal
-            info.score.apply_peer_action(PeerAction::Fatal);
+            if !is_trusted {
+                info.score
+                    .apply_peer_action(PeerAction::Fatal, &self.score_params);
+            }
             if info.connection_status.is_connected_or_dialing() {
                 self.events
                     .push(PeerManagerEvent::DisconnectPeer(peer_id.clone(), reason));
@@ -145,8 +216,13 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
     /// Reports a peer for some action.
     ///
-    /// If the peer doesn't exist, log a warning and insert defaults.
+    /// If the peer doesn't exist, log a warning and insert defaults. Trusted peers are not scored.
     pub fn report_peer(&mut self, peer_id: &PeerId, action: PeerAction) {
+        if self.network_globals.peers.read().is_trusted(peer_id) {
+            debug!(self.log, "Ignoring report of trusted peer"; "peer_id" => peer_id.to_string(), "action" => action.to_string());
+            return;
+        }
+
         // TODO: Remove duplicate code  - This is duplicated in the update_peer_scores()
         // function.
 
@@ -155,10 +231,10 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         let mut unban_peer = None;
 
         if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
-            let previous_state = info.score.state();
-            info.score.apply_peer_action(action);
-            if previous_state != info.score.state() {
-                match info.score.state() {
+            let previous_state = info.score.state(&self.score_params);
+            info.score.apply_peer_action(action, &self.score_params);
+            if previous_state != info.score.state(&self.score_params) {
+                match info.score.state(&self.score_params) {
                     ScoreState::Banned => {
                         debug!(self.log, "Peer has been banned"; "peer_id" => peer_id.to_string(), "score" => info.score.to_string());
                         ban_peer = Some(peer_id.clone());
@@ -341,6 +417,12 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.network_globals.connected_or_dialing_peers() >= self.max_peers
     }
 
+    /// Reports if a peer is trusted, in which case connections are accepted regardless of the
+    /// peer limit.
+    pub fn is_trusted(&self, peer_id: &PeerId) -> bool {
+        self.network_globals.peers.read().is_trusted(peer_id)
+    }
+
     /// Updates `PeerInfo` with `identify` information.
     pub fn identify(&mut self, peer_id: &PeerId, info: &IdentifyInfo) {
         if let Some(peer_info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
@@ -538,10 +620,16 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     .is_none()
             });
 
+            out_list
+                .extend_from_slice(self.network_globals.peers.read().trusted_addresses(peer_id));
             out_list
         } else {
-            // PeerId is not known
-            Vec::new()
+            // PeerId is not known to discovery, but may be trusted
+            self.network_globals
+                .peers
+                .read()
+                .trusted_addresses(peer_id)
+                .to_vec()
         }
     }
 
@@ -689,9 +777,9 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         let mut to_unban_peers = Vec::new();
 
         for (peer_id, info) in pdb.peers_mut() {
-            let previous_state = info.score.state();
+            let previous_state = info.score.state(&self.score_params);
             // Update scores
-            info.score.update();
+            info.score.update(&self.score_params);
 
             /* TODO: Implement logic about connection lifetimes
             match info.connection_status {
@@ -746,8 +834,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             */
 
             // handle score transitions
-            if previous_state != info.score.state() {
-                match info.score.state() {
+            if previous_state != info.score.state(&self.score_params) {
+                match info.score.state(&self.score_params) {
                     ScoreState::Banned => {
                         debug!(self.log, "Peer has been banned"; "peer_id" => peer_id.to_string(), "score" => info.score.to_string());
                         to_ban_peers.push(peer_id.clone());
@@ -805,23 +893,36 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             self.discovery.discover_peers();
         }
 
+        // Maintain connections to trusted peers.
+        let trusted_to_dial = {
+            let peerdb = self.network_globals.peers.read();
+            peerdb
+                .trusted_peer_ids()
+                .filter(|peer_id| !peerdb.is_connected_or_dialing(peer_id))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        for peer_id in trusted_to_dial {
+            debug!(self.log, "Dialing trusted peer"; "peer_id" => peer_id.to_string());
+            self.dial_peer(&peer_id);
+        }
+
         // Updates peer's scores.
         self.update_peer_scores();
 
         let connected_peer_count = self.network_globals.connected_peers();
         if connected_peer_count > self.target_peers {
-            //remove excess peers with the worst scores, but keep subnet peers
-            for (peer_id, _) in self
-                .network_globals
-                .peers
-                .read()
+            let score_params = self.score_params;
+            //remove excess peers with the worst scores, but keep subnet and trusted peers
+            let peerdb = self.network_globals.peers.read();
+            for (peer_id, _) in peerdb
                 .worst_connected_peers()
                 .iter()
-                .filter(|(_, info)| !info.has_future_duty())
+                .filter(|(peer_id, info)| !info.has_future_duty() && !peerdb.is_trusted(peer_id))
                 .take(connected_peer_count - self.target_peers)
                 //we only need to disconnect peers with healthy scores, since the others got already
                 //disconnected in update_peer_scores
-                .filter(|(_, info)| info.score.state() == ScoreState::Healthy)
+                .filter(|(_, info)| info.score.state(&score_params) == ScoreState::Healthy)
             {
                 self.events.push(PeerManagerEvent::DisconnectPeer(
                     (*peer_id).clone(),
@@ -1,9 +1,9 @@
 use super::peer_info::{PeerConnectionStatus, PeerInfo};
 use super::peer_sync_status::PeerSyncStatus;
-use super::score::{Score, ScoreState};
+use super::score::{Score, ScoreParams, ScoreState};
 use crate::multiaddr::Protocol;
 use crate::rpc::methods::MetaData;
-use crate::PeerId;
+use crate::{Multiaddr, PeerId};
 use rand::seq::SliceRandom;
 use slog::{crit, debug, trace, warn};
 use std::collections::HashMap;
@@ -27,6 +27,11 @@ pub struct PeerDB<TSpec: EthSpec> {
     disconnected_peers: usize,
     /// Counts banned peers in total and per ip
     banned_peers_count: BannedPeersCount,
+    /// Peers to which connections are always maintained, and the addresses on which they are
+    /// dialed. These are never banned.
+    trusted_peers: HashMap<PeerId, Vec<Multiaddr>>,
+    /// The parameters which determine the state of a peer given its score.
+    score_params: ScoreParams,
     /// PeerDB's logger
     log: slog::Logger,
 }
@@ -86,17 +91,24 @@ impl BannedPeersCount {
 }
 
 impl<TSpec: EthSpec> PeerDB<TSpec> {
-    pub fn new(log: &slog::Logger) -> Self {
+    pub fn new(score_params: ScoreParams, log: &slog::Logger) -> Self {
         Self {
             log: log.clone(),
             disconnected_peers: 0,
             banned_peers_count: BannedPeersCount::new(),
+            trusted_peers: HashMap::new(),
+            score_params,
             peers: HashMap::new(),
         }
     }
 
     /* Getters */
 
+    /// The parameters which determine how peers are scored.
+    pub fn score_params(&self) -> &ScoreParams {
+        &self.score_params
+    }
+
     /// Gives the score of a peer, or default score if it is unknown.
     pub fn score(&self, peer_id: &PeerId) -> Score {
         self.peers
@@ -156,10 +168,30 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         }
     }
 
-    /// Returns true if the Peer is banned.
+    /// Returns true if the peer is trusted.
+    pub fn is_trusted(&self, peer_id: &PeerId) -> bool {
+        self.trusted_peers.contains_key(peer_id)
+    }
+
+    /// Gives the ids of all trusted peers, whether or not they are connected.
+    pub fn trusted_peer_ids(&self) -> impl Iterator<Item = &PeerId> {
+        self.trusted_peers.keys()
+    }
+
+    /// Returns the addresses on which a trusted peer is dialed, or none if it is not trusted.
+    pub fn trusted_addresses(&self, peer_id: &PeerId) -> &[Multiaddr] {
+        self.trusted_peers
+            .get(peer_id)
+            .map_or(&[][..], Vec::as_slice)
+    }
+
+    /// Returns true if the Peer is banned. Trusted peers are never banned.
     pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        if self.is_trusted(peer_id) {
+            return false;
+        }
         if let Some(peer) = self.peers.get(peer_id) {
-            match peer.score.state() {
+            match peer.score.state(&self.score_params) {
                 ScoreState::Banned => true,
                 _ => self.ip_is_banned(peer),
             }
@@ -180,8 +212,11 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
 
     /// Returns true if the Peer is either banned or in the disconnected state.
     pub fn is_banned_or_disconnected(&self, peer_id: &PeerId) -> bool {
+        if self.is_trusted(peer_id) {
+            return false;
+        }
         if let Some(peer) = self.peers.get(peer_id) {
-            match peer.score.state() {
+            match peer.score.state(&self.score_params) {
                 ScoreState::Banned | ScoreState::Disconnected => true,
                 _ => self.ip_is_banned(peer),
             }
@@ -392,8 +427,30 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         }
     }
 
-    /// Marks a peer as banned.
+    /// Adds a trusted peer, which may be dialed on `addresses`. If the peer is known, its score
+    /// is reset and it is unbanned.
+    pub fn add_trusted_peer(&mut self, peer_id: &PeerId, addresses: Vec<Multiaddr>) {
+        self.trusted_peers.insert(peer_id.clone(), addresses);
+        if let Some(info) = self.peers.get_mut(peer_id) {
+            info.score = Score::default();
+            if info.connection_status.is_banned() {
+                self.unban(peer_id);
+            }
+        }
+    }
+
+    /// Removes a trusted peer, returning false if it was not trusted. The peer is subject to
+    /// scoring and bans from now on.
+    pub fn remove_trusted_peer(&mut self, peer_id: &PeerId) -> bool {
+        self.trusted_peers.remove(peer_id).is_some()
+    }
+
+    /// Marks a peer as banned. Trusted peers are not banned.
     pub fn ban(&mut self, peer_id: &PeerId) {
+        if self.is_trusted(peer_id) {
+            debug!(self.log, "Not banning trusted peer"; "peer_id" => peer_id.to_string());
+            return;
+        }
         let log_ref = &self.log;
         let info = self.peers.entry(peer_id.clone()).or_insert_with(|| {
             warn!(log_ref, "Banning unknown peer";
@@ -549,7 +606,7 @@ mod tests {
 
     fn get_db() -> PeerDB<M> {
         let log = build_log(slog::Level::Debug, false);
-        PeerDB::new(&log)
+        PeerDB::new(ScoreParams::default(), &log)
     }
 
     #[test]
@@ -938,4 +995,31 @@ mod tests {
         assert!(pdb.is_banned(&p1));
         assert!(!pdb.is_banned(&p2));
     }
+
+    #[test]
+    fn test_trusted_peers_not_banned() {
+        let mut pdb = get_db();
+
+        let trusted = PeerId::random();
+        pdb.connect_ingoing(&trusted);
+        add_score(&mut pdb, &trusted, -100.0);
+        pdb.ban(&trusted);
+        assert!(pdb.is_banned(&trusted));
+
+        // Trusting a banned peer unbans it and resets its score.
+        pdb.add_trusted_peer(&trusted, vec![]);
+        assert!(pdb.is_trusted(&trusted));
+        assert!(!pdb.is_banned(&trusted));
+        assert!(!pdb.is_banned_or_disconnected(&trusted));
+        assert_eq!(pdb.score(&trusted), Score::default());
+
+        pdb.ban(&trusted);
+        assert!(!pdb.is_banned(&trusted));
+        assert_eq!(pdb.banned_peers().count(), 0);
+
+        assert!(pdb.remove_trusted_peer(&trusted));
+        assert!(!pdb.remove_trusted_peer(&trusted));
+        pdb.ban(&trusted);
+        assert_eq!(pdb.banned_peers().count(), 1);
+    }
 }
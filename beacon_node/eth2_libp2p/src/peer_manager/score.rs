@@ -5,13 +5,9 @@
 //! As the logic develops this documentation will advance.
 //!
 //! The scoring algorithms are currently experimental.
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
-lazy_static! {
-    static ref HALFLIFE_DECAY: f64 = -(2.0f64.ln()) / SCORE_HALFLIFE;
-}
-
 /// The default score for new peers.
 pub(crate) const DEFAULT_SCORE: f64 = 0.0;
 /// The minimum reputation before a peer is disconnected.
@@ -27,6 +23,74 @@ const SCORE_HALFLIFE: f64 = 600.0;
 /// The number of seconds we ban a peer for before their score begins to decay.
 const BANNED_BEFORE_DECAY: u64 = 1800;
 
+/// The parameters which determine how peers are scored.
+///
+/// The defaults are the values Lighthouse uses. They may be overridden (e.g., for research) with a
+/// TOML file, in which any omitted parameter takes its default value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreParams {
+    /// The score at or below which a peer is disconnected.
+    pub min_score_before_disconnect: f64,
+    /// The score at or below which a peer is banned.
+    pub min_score_before_ban: f64,
+    /// The number of seconds it takes for a score to decay to half its value.
+    pub score_halflife: f64,
+    /// The number of seconds a peer is banned for before its score begins to decay.
+    pub banned_before_decay: u64,
+    /// The penalty applied for a `PeerAction::LowToleranceError`.
+    pub low_tolerance_penalty: f64,
+    /// The penalty applied for a `PeerAction::MidToleranceError`.
+    pub mid_tolerance_penalty: f64,
+    /// The penalty applied for a `PeerAction::HighToleranceError`.
+    pub high_tolerance_penalty: f64,
+}
+
+impl Default for ScoreParams {
+    fn default() -> Self {
+        Self {
+            min_score_before_disconnect: MIN_SCORE_BEFORE_DISCONNECT,
+            min_score_before_ban: MIN_SCORE_BEFORE_BAN,
+            score_halflife: SCORE_HALFLIFE,
+            banned_before_decay: BANNED_BEFORE_DECAY,
+            low_tolerance_penalty: 10.0,
+            mid_tolerance_penalty: 5.0,
+            high_tolerance_penalty: 1.0,
+        }
+    }
+}
+
+impl ScoreParams {
+    /// Returns an error if the parameters would not produce sensible scoring.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_SCORE < self.min_score_before_ban
+            && self.min_score_before_ban < self.min_score_before_disconnect
+            && self.min_score_before_disconnect < DEFAULT_SCORE)
+        {
+            return Err(format!(
+                "Scores must satisfy {} < min_score_before_ban < min_score_before_disconnect < {}",
+                MIN_SCORE, DEFAULT_SCORE
+            ));
+        }
+
+        if !(self.score_halflife > 0.0 && self.score_halflife.is_finite()) {
+            return Err("score_halflife must be a positive number of seconds".to_string());
+        }
+
+        for (name, penalty) in &[
+            ("low_tolerance_penalty", self.low_tolerance_penalty),
+            ("mid_tolerance_penalty", self.mid_tolerance_penalty),
+            ("high_tolerance_penalty", self.high_tolerance_penalty),
+        ] {
+            if !(*penalty >= 0.0 && penalty.is_finite()) {
+                return Err(format!("{} must be a non-negative number", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// A collection of actions a peer can perform which will adjust its score.
 /// Each variant has an associated score change.
 // To easily assess the behaviour of scores changes the number of variants should stay low, and
@@ -151,21 +215,21 @@ impl Score {
     }
 
     /// Modifies the score based on a peer's action.
-    pub fn apply_peer_action(&mut self, peer_action: PeerAction) {
+    pub fn apply_peer_action(&mut self, peer_action: PeerAction, params: &ScoreParams) {
         match peer_action {
             PeerAction::Fatal => self.score = MIN_SCORE, // The worst possible score
-            PeerAction::LowToleranceError => self.add(-10.0),
-            PeerAction::MidToleranceError => self.add(-5.0),
-            PeerAction::HighToleranceError => self.add(-1.0),
+            PeerAction::LowToleranceError => self.add(-params.low_tolerance_penalty),
+            PeerAction::MidToleranceError => self.add(-params.mid_tolerance_penalty),
+            PeerAction::HighToleranceError => self.add(-params.high_tolerance_penalty),
             PeerAction::_ValidMessage => self.add(0.1),
         }
     }
 
     /// Returns the expected state of the peer given it's score.
-    pub(crate) fn state(&self) -> ScoreState {
+    pub(crate) fn state(&self, params: &ScoreParams) -> ScoreState {
         match self.score {
-            x if x <= MIN_SCORE_BEFORE_BAN => ScoreState::Banned,
-            x if x <= MIN_SCORE_BEFORE_DISCONNECT => ScoreState::Disconnected,
+            x if x <= params.min_score_before_ban => ScoreState::Banned,
+            x if x <= params.min_score_before_disconnect => ScoreState::Disconnected,
             _ => ScoreState::Healthy,
         }
     }
@@ -185,18 +249,18 @@ impl Score {
 
     /// Applies time-based logic such as decay rates to the score.
     /// This function should be called periodically.
-    pub fn update(&mut self) {
+    pub fn update(&mut self, params: &ScoreParams) {
         // Apply decay logic
         //
         // There is two distinct decay processes. One for banned peers and one for all others. If
         // the score is below the banning threshold and the duration since it was last update is
         // shorter than the banning threshold, we do nothing.
         let now = Instant::now();
-        if self.score <= MIN_SCORE_BEFORE_BAN
+        if self.score <= params.min_score_before_ban
             && now
                 .checked_duration_since(self.last_updated)
                 .map(|d| d.as_secs())
-                <= Some(BANNED_BEFORE_DECAY)
+                <= Some(params.banned_before_decay)
         {
             // The peer is banned and still within the ban timeout. Do not update it's score.
             // Update last_updated so that the decay begins correctly when ready.
@@ -211,7 +275,8 @@ impl Score {
             .map(|d| d.as_secs())
         {
             // e^(-ln(2)/HL*t)
-            let decay_factor = (*HALFLIFE_DECAY * secs_since_update as f64).exp();
+            let halflife_decay = -(2.0f64.ln()) / params.score_halflife;
+            let decay_factor = (halflife_decay * secs_since_update as f64).exp();
             self.score *= decay_factor;
             self.last_updated = now;
         }
@@ -250,4 +315,45 @@ mod tests {
         score.add(change);
         assert_eq!(score.score(), DEFAULT_SCORE + change);
     }
+
+    #[test]
+    fn test_custom_params() {
+        let params = ScoreParams {
+            min_score_before_disconnect: -5.0,
+            min_score_before_ban: -8.0,
+            low_tolerance_penalty: 4.0,
+            ..ScoreParams::default()
+        };
+        assert_eq!(params.validate(), Ok(()));
+
+        let mut score = Score::default();
+        score.apply_peer_action(PeerAction::LowToleranceError, &params);
+        assert_eq!(score.score(), -4.0);
+        assert_eq!(score.state(&params), ScoreState::Healthy);
+
+        score.apply_peer_action(PeerAction::LowToleranceError, &params);
+        assert_eq!(score.state(&params), ScoreState::Banned);
+        assert_eq!(score.state(&ScoreParams::default()), ScoreState::Healthy);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let params = ScoreParams {
+            min_score_before_ban: -10.0,
+            ..ScoreParams::default()
+        };
+        assert!(params.validate().is_err(), "ban threshold above disconnect");
+
+        let params = ScoreParams {
+            score_halflife: 0.0,
+            ..ScoreParams::default()
+        };
+        assert!(params.validate().is_err(), "zero halflife");
+
+        let params = ScoreParams {
+            mid_tolerance_penalty: -1.0,
+            ..ScoreParams::default()
+        };
+        assert!(params.validate().is_err(), "negative penalty");
+    }
 }
@@ -75,6 +75,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
             enr.clone(),
             config.libp2p_port,
             config.discovery_port,
+            config.score_params,
             &log,
         ));
 
@@ -84,7 +85,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
         } else {
             config.discovery_port.to_string()
         };
-        debug!(log, "Attempting to open listening ports"; "address" => format!("{}", config.listen_address), "tcp_port" => config.libp2p_port, "udp_port" => discovery_string);
+        debug!(log, "Attempting to open listening ports"; "address" => format!("{}", config.listen_address), "tcp_port" => config.libp2p_port, "udp_port" => discovery_string, "address6" => format!("{:?}", config.dual_stack_address6()));
 
         let mut swarm = {
             // Set up the transport - tcp/ws with noise and mplex
@@ -111,29 +112,32 @@ impl<TSpec: EthSpec> Service<TSpec> {
                 .build()
         };
 
-        // listen on the specified address
-        let listen_multiaddr = {
-            let mut m = Multiaddr::from(config.listen_address);
-            m.push(Protocol::Tcp(config.libp2p_port));
-            m
-        };
+        if config.disable_inbound {
+            info!(
+                log,
+                "Inbound connections disabled, not listening on any address"
+            );
+        }
 
-        match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
-            Ok(_) => {
-                let mut log_address = listen_multiaddr;
-                log_address.push(Protocol::P2p(local_peer_id.clone().into()));
-                info!(log, "Listening established"; "address" => format!("{}", log_address));
-            }
-            Err(err) => {
-                crit!(
-                    log,
-                    "Unable to listen on libp2p address";
-                    "error" => format!("{:?}", err),
-                    "listen_multiaddr" => format!("{}", listen_multiaddr),
-                );
-                return Err("Libp2p was unable to listen on the given listen address.".into());
-            }
-        };
+        // listen on the specified addresses, both IPv4 and IPv6 if dual-stack
+        for listen_multiaddr in config.listen_multiaddrs() {
+            match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
+                Ok(_) => {
+                    let mut log_address = listen_multiaddr;
+                    log_address.push(Protocol::P2p(local_peer_id.clone().into()));
+                    info!(log, "Listening established"; "address" => format!("{}", log_address));
+                }
+                Err(err) => {
+                    crit!(
+                        log,
+                        "Unable to listen on libp2p address";
+                        "error" => format!("{:?}", err),
+                        "listen_multiaddr" => format!("{}", listen_multiaddr),
+                    );
+                    return Err("Libp2p was unable to listen on the given listen address.".into());
+                }
+            };
+        }
 
         // helper closure for dialing peers
         let mut dial_addr = |mut multiaddr: Multiaddr| {
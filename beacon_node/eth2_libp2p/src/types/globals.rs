@@ -1,6 +1,6 @@
 //! A collection of variables that are accessible outside of the network thread itself.
-use crate::peer_manager::PeerDB;
-use crate::types::SyncState;
+use crate::peer_manager::{score::ScoreParams, PeerDB};
+use crate::types::{NatStatus, SyncState};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, GossipTopic, Multiaddr, PeerId};
@@ -26,19 +26,28 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub gossipsub_subscriptions: RwLock<HashSet<GossipTopic>>,
     /// The current sync status of the node.
     pub sync_state: RwLock<SyncState>,
+    /// The status of the port mappings on the local NAT gateway.
+    pub nat_status: RwLock<NatStatus>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
-    pub fn new(enr: Enr, tcp_port: u16, udp_port: u16, log: &slog::Logger) -> Self {
+    pub fn new(
+        enr: Enr,
+        tcp_port: u16,
+        udp_port: u16,
+        score_params: ScoreParams,
+        log: &slog::Logger,
+    ) -> Self {
         NetworkGlobals {
             local_enr: RwLock::new(enr.clone()),
             peer_id: RwLock::new(enr.peer_id()),
             listen_multiaddrs: RwLock::new(Vec::new()),
             listen_port_tcp: AtomicU16::new(tcp_port),
             listen_port_udp: AtomicU16::new(udp_port),
-            peers: RwLock::new(PeerDB::new(log)),
+            peers: RwLock::new(PeerDB::new(score_params, log)),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
+            nat_status: RwLock::new(NatStatus::default()),
         }
     }
 
@@ -68,6 +77,11 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.listen_port_udp.load(Ordering::Relaxed)
     }
 
+    /// Returns the status of the port mappings on the local NAT gateway.
+    pub fn nat_status(&self) -> NatStatus {
+        self.nat_status.read().clone()
+    }
+
     /// Returns the number of libp2p connected peers.
     pub fn connected_peers(&self) -> usize {
         self.peers.read().connected_peer_ids().count()
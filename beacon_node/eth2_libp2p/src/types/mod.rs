@@ -1,9 +1,11 @@
 pub mod error;
 mod globals;
+mod nat_status;
 mod pubsub;
 mod subnet;
 mod sync_state;
 mod topics;
+mod trusted_peer;
 
 use types::{BitVector, EthSpec};
 
@@ -13,7 +15,9 @@ pub type EnrBitfield<T: EthSpec> = BitVector<T::SubnetBitfieldLength>;
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
 pub use globals::NetworkGlobals;
+pub use nat_status::{NatMethod, NatState, NatStatus};
 pub use pubsub::PubsubMessage;
 pub use subnet::SubnetDiscovery;
 pub use sync_state::SyncState;
 pub use topics::{GossipEncoding, GossipKind, GossipTopic};
+pub use trusted_peer::TrustedPeer;
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// The progress of mapping the node's ports on the local NAT gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NatState {
+    /// NAT traversal is not enabled.
+    Disabled,
+    /// The gateway is being searched for, or the ports are being mapped.
+    Pending,
+    /// The ports have been mapped on the gateway.
+    Established,
+    /// No gateway was found, or it refused to map the ports.
+    Failed,
+}
+
+/// The protocol used to map ports on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NatMethod {
+    Upnp,
+    NatPmp,
+}
+
+/// The status of the port mappings on the local NAT gateway.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NatStatus {
+    pub state: NatState,
+    /// The protocol with which the ports were mapped.
+    pub method: Option<NatMethod>,
+    /// The external address of the gateway.
+    pub external_address: Option<Ipv4Addr>,
+    /// The external TCP port mapped to the libp2p port.
+    pub tcp_port: Option<u16>,
+    /// The external UDP port mapped to the discovery port.
+    pub udp_port: Option<u16>,
+    /// The reason the ports could not be mapped.
+    pub error: Option<String>,
+}
+
+impl Default for NatStatus {
+    fn default() -> Self {
+        NatStatus {
+            state: NatState::Disabled,
+            method: None,
+            external_address: None,
+            tcp_port: None,
+            udp_port: None,
+            error: None,
+        }
+    }
+}
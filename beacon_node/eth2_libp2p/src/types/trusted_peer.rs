@@ -0,0 +1,148 @@
+use crate::{Enr, EnrExt, PeerId};
+use libp2p::core::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// A peer to which a connection is always maintained, and which is exempt from score-based
+/// disconnects and bans.
+///
+/// A trusted peer is given either by its ENR or by a multiaddr ending in `/p2p/<peer-id>`. It is
+/// serialized as a string, which is validated by `FromStr` when deserialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TrustedPeer {
+    Enr(Enr),
+    Multiaddr(Multiaddr),
+}
+
+impl TrustedPeer {
+    /// The `PeerId` of the peer.
+    pub fn peer_id(&self) -> PeerId {
+        match self {
+            TrustedPeer::Enr(enr) => enr.peer_id(),
+            TrustedPeer::Multiaddr(multiaddr) => {
+                peer_id_of_multiaddr(multiaddr).expect("checked when parsed")
+            }
+        }
+    }
+
+    /// The TCP multiaddrs on which the peer may be dialed, without the `/p2p` suffix.
+    pub fn multiaddrs(&self) -> Vec<Multiaddr> {
+        match self {
+            TrustedPeer::Enr(enr) => {
+                let mut multiaddrs = enr.multiaddr();
+                multiaddrs.retain(|addr| addr.iter().all(|p| !matches!(p, Protocol::Udp(_))));
+                multiaddrs
+            }
+            TrustedPeer::Multiaddr(multiaddr) => {
+                let mut multiaddr = multiaddr.clone();
+                multiaddr.pop();
+                vec![multiaddr]
+            }
+        }
+    }
+}
+
+impl FromStr for TrustedPeer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(enr) = s.parse() {
+            return Ok(TrustedPeer::Enr(enr));
+        }
+
+        let multiaddr: Multiaddr = s
+            .parse()
+            .map_err(|_| format!("Not valid as ENR nor Multiaddr: {}", s))?;
+        if peer_id_of_multiaddr(&multiaddr).is_none() {
+            return Err(format!("Multiaddr must end with /p2p/<peer-id>: {}", s));
+        }
+        Ok(TrustedPeer::Multiaddr(multiaddr))
+    }
+}
+
+impl TryFrom<String> for TrustedPeer {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TrustedPeer> for String {
+    fn from(peer: TrustedPeer) -> String {
+        peer.to_string()
+    }
+}
+
+impl fmt::Display for TrustedPeer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrustedPeer::Enr(enr) => write!(f, "{}", enr.to_base64()),
+            TrustedPeer::Multiaddr(multiaddr) => write!(f, "{}", multiaddr),
+        }
+    }
+}
+
+/// Returns the `PeerId` of the `/p2p` protocol which ends `multiaddr`, if any.
+fn peer_id_of_multiaddr(multiaddr: &Multiaddr) -> Option<PeerId> {
+    match multiaddr.iter().last() {
+        Some(Protocol::P2p(multihash)) => PeerId::from_multihash(multihash).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use discv5::enr::{CombinedKey, EnrBuilder};
+
+    #[test]
+    fn parse_trusted_peers() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = EnrBuilder::new("v4")
+            .ip("10.0.0.1".parse().unwrap())
+            .tcp(9000)
+            .udp(9000)
+            .build(&key)
+            .unwrap();
+        let peer = enr.to_base64().parse::<TrustedPeer>().unwrap();
+        assert_eq!(peer.peer_id(), enr.peer_id());
+        assert_eq!(
+            peer.multiaddrs(),
+            vec!["/ip4/10.0.0.1/tcp/9000".parse::<Multiaddr>().unwrap()]
+        );
+
+        let peer_id = PeerId::random();
+        let peer = format!("/ip4/10.0.0.2/tcp/9000/p2p/{}", peer_id)
+            .parse::<TrustedPeer>()
+            .unwrap();
+        assert_eq!(peer.peer_id(), peer_id);
+        assert_eq!(
+            peer.multiaddrs(),
+            vec!["/ip4/10.0.0.2/tcp/9000".parse::<Multiaddr>().unwrap()]
+        );
+
+        assert!("/ip4/10.0.0.2/tcp/9000".parse::<TrustedPeer>().is_err());
+        assert!("not a peer".parse::<TrustedPeer>().is_err());
+    }
+
+    #[test]
+    fn serde_validates_trusted_peers() {
+        let peer = format!("/ip4/10.0.0.2/tcp/9000/p2p/{}", PeerId::random())
+            .parse::<TrustedPeer>()
+            .unwrap();
+        let json = serde_json::to_string(&peer).unwrap();
+        assert_eq!(json, format!("\"{}\"", peer));
+        assert_eq!(serde_json::from_str::<TrustedPeer>(&json).unwrap(), peer);
+
+        assert!(serde_json::from_str::<TrustedPeer>("\"/ip4/10.0.0.2/tcp/9000\"").is_err());
+        assert!(
+            serde_json::from_str::<TrustedPeer>("{\"Multiaddr\": \"/ip4/10.0.0.2/tcp/9000\"}")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Authentication of the engine API, as a JSON Web Token (JWT) signed with HMAC-SHA256 using a
+//! secret shared with the execution engine.
+//!
+//! The only claim is the issued-at time (`iat`), which the execution engine requires to be
+//! within a few seconds of its own clock. A fresh token is therefore generated for each request.
+//!
+//! A secret loaded from a file is reloaded whenever the file is modified, so that the secret may
+//! be rotated without restarting the beacon node.
+
+use hmac::{Hmac, Mac, NewMac};
+use parking_lot::Mutex;
+use serde::Serialize;
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of bytes in the secret shared with the execution engine.
+pub const JWT_SECRET_LENGTH: usize = 32;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The secret was not `JWT_SECRET_LENGTH` bytes.
+    InvalidSecretLength(usize),
+    /// The secret was not valid hex.
+    InvalidSecretHex(String),
+    /// The secret file could not be read.
+    UnableToReadSecret(String),
+    /// The modification time of the secret file could not be read.
+    UnableToReadSecretMetadata(String),
+    /// The system clock is before the unix epoch.
+    SystemTimeBeforeEpoch,
+    InvalidHmacKey,
+    Serde(String),
+}
+
+/// The secret shared with the execution engine.
+#[derive(Clone)]
+pub struct JwtKey([u8; JWT_SECRET_LENGTH]);
+
+impl JwtKey {
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != JWT_SECRET_LENGTH {
+            return Err(Error::InvalidSecretLength(bytes.len()));
+        }
+
+        let mut key = [0; JWT_SECRET_LENGTH];
+        key.copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+
+    /// Parses a hex-encoded secret, with or without a `0x` prefix. Surrounding whitespace is
+    /// ignored.
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        let bytes = hex::decode(hex.trim().trim_start_matches("0x"))
+            .map_err(|e| Error::InvalidSecretHex(format!("{:?}", e)))?;
+        Self::from_slice(&bytes)
+    }
+
+    /// Reads a hex-encoded secret from the file at `path`.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let hex = fs::read_to_string(path)
+            .map_err(|e| Error::UnableToReadSecret(format!("{:?}: {:?}", path, e)))?;
+        Self::from_hex(&hex)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the secret as `0x`-prefixed hex, the format in which it is stored in files.
+    pub fn hex_string(&self) -> String {
+        format!("0x{}", hex::encode(self.0))
+    }
+}
+
+/// The file from which a secret was loaded, and the modification time of the file at that time.
+struct SecretFile {
+    path: PathBuf,
+    modified: SystemTime,
+}
+
+fn modified_time(path: &Path) -> Result<SystemTime, Error> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| Error::UnableToReadSecretMetadata(format!("{:?}: {:?}", path, e)))
+}
+
+#[derive(Serialize)]
+struct Header {
+    alg: &'static str,
+    typ: &'static str,
+}
+
+#[derive(Serialize)]
+struct Claims {
+    /// The time the token was issued, in seconds since the unix epoch.
+    iat: u64,
+}
+
+/// Generates the bearer tokens which authenticate requests to the execution engine.
+pub struct Auth {
+    key: Mutex<JwtKey>,
+    secret_file: Option<Mutex<SecretFile>>,
+}
+
+impl Auth {
+    pub fn new(key: JwtKey) -> Self {
+        Self {
+            key: Mutex::new(key),
+            secret_file: None,
+        }
+    }
+
+    /// Loads the secret from the file at `path`, reloading it whenever the file is modified.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let modified = modified_time(path)?;
+        let key = JwtKey::load(path)?;
+
+        Ok(Self {
+            key: Mutex::new(key),
+            secret_file: Some(Mutex::new(SecretFile {
+                path: path.to_path_buf(),
+                modified,
+            })),
+        })
+    }
+
+    /// Returns a token issued at the current time.
+    pub fn generate_token(&self) -> Result<String, Error> {
+        self.reload_if_modified()?;
+
+        let iat = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| Error::SystemTimeBeforeEpoch)?
+            .as_secs();
+        self.generate_token_at(iat)
+    }
+
+    /// Reloads the secret if it was loaded from a file which has since been modified.
+    ///
+    /// The previous secret is kept if the file cannot be read, so that the reload is retried by
+    /// the next request.
+    fn reload_if_modified(&self) -> Result<(), Error> {
+        let mut secret_file = match &self.secret_file {
+            Some(secret_file) => secret_file.lock(),
+            None => return Ok(()),
+        };
+
+        let modified = modified_time(&secret_file.path)?;
+        if modified != secret_file.modified {
+            *self.key.lock() = JwtKey::load(&secret_file.path)?;
+            secret_file.modified = modified;
+        }
+
+        Ok(())
+    }
+
+    fn generate_token_at(&self, iat: u64) -> Result<String, Error> {
+        let header = Header {
+            alg: "HS256",
+            typ: "JWT",
+        };
+        let claims = Claims { iat };
+
+        let message = format!(
+            "{}.{}",
+            base64_url(&serde_json::to_vec(&header).map_err(|e| Error::Serde(e.to_string()))?),
+            base64_url(&serde_json::to_vec(&claims).map_err(|e| Error::Serde(e.to_string()))?)
+        );
+
+        let mut mac =
+            Hmac::<Sha256>::new_varkey(&self.key.lock().0).map_err(|_| Error::InvalidHmacKey)?;
+        mac.update(message.as_bytes());
+        let signature = mac.finalize().into_bytes();
+
+        Ok(format!("{}.{}", message, base64_url(&signature)))
+    }
+}
+
+fn base64_url(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_matches_known_encoding() {
+        let key = JwtKey::from_slice(&(0..32).collect::<Vec<u8>>()).unwrap();
+        let token = Auth::new(key).generate_token_at(1_650_000_000).unwrap();
+
+        assert_eq!(
+            token,
+            "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJpYXQiOjE2NTAwMDAwMDB9.\
+             bTOEUCa-8iDiduwDXY9icsYVwK0up6iCRLVCgfMXjwk"
+        );
+    }
+
+    #[test]
+    fn secret_file_is_reloaded_when_modified() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        let first = JwtKey::from_slice(&[1; JWT_SECRET_LENGTH]).unwrap();
+        let second = JwtKey::from_slice(&[2; JWT_SECRET_LENGTH]).unwrap();
+
+        fs::write(&path, first.hex_string()).unwrap();
+        let auth = Auth::from_file(&path).unwrap();
+        auth.reload_if_modified().unwrap();
+        assert_eq!(auth.key.lock().as_bytes(), first.as_bytes());
+
+        fs::write(&path, second.hex_string()).unwrap();
+        // Ensure the modification time differs, regardless of the filesystem's resolution.
+        auth.secret_file.as_ref().unwrap().lock().modified = UNIX_EPOCH;
+        auth.reload_if_modified().unwrap();
+        assert_eq!(auth.key.lock().as_bytes(), second.as_bytes());
+    }
+
+    #[test]
+    fn secret_parsing() {
+        let hex = "0x000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\n";
+        assert_eq!(
+            JwtKey::from_hex(hex).unwrap().0.to_vec(),
+            (0..32).collect::<Vec<u8>>()
+        );
+        assert_eq!(
+            JwtKey::from_hex("0x0001").err(),
+            Some(Error::InvalidSecretLength(2))
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! The JSON representations of the objects exchanged with the execution engine, as defined by the
+//! `engine_*` methods of the engine API.
+//!
+//! The consensus types do not yet include execution payloads, so they are defined here in their
+//! JSON form only.
+
+use ethereum_types::U256;
+use serde::{Deserialize, Serialize};
+use types::{Address, Hash256};
+
+pub const ENGINE_NEW_PAYLOAD_V1: &str = "engine_newPayloadV1";
+pub const ENGINE_FORKCHOICE_UPDATED_V1: &str = "engine_forkchoiceUpdatedV1";
+
+/// An execution block, as included in a post-merge beacon block.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayload {
+    pub parent_hash: Hash256,
+    pub fee_recipient: Address,
+    pub state_root: Hash256,
+    pub receipts_root: Hash256,
+    #[serde(with = "hex_bytes")]
+    pub logs_bloom: Vec<u8>,
+    pub prev_randao: Hash256,
+    #[serde(with = "quantity")]
+    pub block_number: u64,
+    #[serde(with = "quantity")]
+    pub gas_limit: u64,
+    #[serde(with = "quantity")]
+    pub gas_used: u64,
+    #[serde(with = "quantity")]
+    pub timestamp: u64,
+    #[serde(with = "hex_bytes")]
+    pub extra_data: Vec<u8>,
+    pub base_fee_per_gas: U256,
+    pub block_hash: Hash256,
+    #[serde(with = "hex_bytes_vec")]
+    pub transactions: Vec<Vec<u8>>,
+}
+
+/// The execution blocks which the consensus layer considers to be the head, safe and finalized.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkChoiceState {
+    pub head_block_hash: Hash256,
+    pub safe_block_hash: Hash256,
+    pub finalized_block_hash: Hash256,
+}
+
+/// The attributes of a payload which the execution engine should start building on the head.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadAttributes {
+    #[serde(with = "quantity")]
+    pub timestamp: u64,
+    pub prev_randao: Hash256,
+    pub suggested_fee_recipient: Address,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PayloadStatusKind {
+    Valid,
+    Invalid,
+    Syncing,
+    Accepted,
+    InvalidBlockHash,
+}
+
+/// The result of the execution engine validating a payload.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadStatus {
+    pub status: PayloadStatusKind,
+    pub latest_valid_hash: Option<Hash256>,
+    pub validation_error: Option<String>,
+}
+
+/// Identifies a payload which the execution engine is building.
+pub type PayloadId = [u8; 8];
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkchoiceUpdatedResponse {
+    pub payload_status: PayloadStatus,
+    #[serde(with = "optional_payload_id")]
+    pub payload_id: Option<PayloadId>,
+}
+
+/// Serializes a `u64` as a `0x`-prefixed, big-endian hex quantity without leading zeros.
+mod quantity {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{:x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if !s.starts_with("0x") {
+            return Err(de::Error::custom("missing 0x prefix"));
+        }
+        u64::from_str_radix(&s[2..], 16)
+            .map_err(|e| de::Error::custom(format!("invalid quantity ({:?})", e)))
+    }
+}
+
+/// Serializes bytes as `0x`-prefixed hex.
+mod hex_bytes {
+    use serde::{Deserializer, Serializer};
+    use serde_hex::PrefixedHexVisitor;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&serde_hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        deserializer.deserialize_str(PrefixedHexVisitor)
+    }
+}
+
+/// Serializes a list of byte strings as a list of `0x`-prefixed hex strings.
+mod hex_bytes_vec {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(list: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(list.iter().map(serde_hex::encode))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| {
+                if s.starts_with("0x") {
+                    hex::decode(&s[2..])
+                        .map_err(|e| de::Error::custom(format!("invalid hex ({:?})", e)))
+                } else {
+                    Err(de::Error::custom("missing 0x prefix"))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Serializes an optional payload id as `0x`-prefixed hex, or `null`.
+mod optional_payload_id {
+    use super::PayloadId;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        id: &Option<PayloadId>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match id {
+            Some(id) => serializer.serialize_str(&serde_hex::encode(id)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PayloadId>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => {
+                let bytes = hex::decode(s.trim_start_matches("0x"))
+                    .map_err(|e| de::Error::custom(format!("invalid hex ({:?})", e)))?;
+                if bytes.len() != 8 {
+                    return Err(de::Error::custom("payload id must be 8 bytes"));
+                }
+                let mut id = PayloadId::default();
+                id.copy_from_slice(&bytes);
+                Ok(Some(id))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn forkchoice_updated_response_decoding() {
+        let response: ForkchoiceUpdatedResponse = serde_json::from_value(json!({
+            "payloadStatus": {
+                "status": "VALID",
+                "latestValidHash": format!("{:?}", Hash256::repeat_byte(1)),
+                "validationError": null
+            },
+            "payloadId": "0x0000000000000001"
+        }))
+        .unwrap();
+
+        assert_eq!(response.payload_status.status, PayloadStatusKind::Valid);
+        assert_eq!(
+            response.payload_status.latest_valid_hash,
+            Some(Hash256::repeat_byte(1))
+        );
+        assert_eq!(response.payload_id, Some([0, 0, 0, 0, 0, 0, 0, 1]));
+    }
+
+    #[test]
+    fn payload_encoding_round_trip() {
+        let payload = ExecutionPayload {
+            parent_hash: Hash256::repeat_byte(1),
+            fee_recipient: Address::repeat_byte(2),
+            state_root: Hash256::repeat_byte(3),
+            receipts_root: Hash256::repeat_byte(4),
+            logs_bloom: vec![0; 256],
+            prev_randao: Hash256::repeat_byte(5),
+            block_number: 42,
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            timestamp: 1_650_000_000,
+            extra_data: vec![],
+            base_fee_per_gas: U256::from(7),
+            block_hash: Hash256::repeat_byte(6),
+            transactions: vec![vec![0xde, 0xad]],
+        };
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["blockNumber"], "0x2a");
+        assert_eq!(json["extraData"], "0x");
+        assert_eq!(json["transactions"], json!(["0xdead"]));
+        assert_eq!(
+            serde_json::from_value::<ExecutionPayload>(json).unwrap(),
+            payload
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! A JSON-RPC client for the engine API of an execution engine.
+//!
+//! Like the `eth1` crate, this does not use a web3 library. Requests are sent with `reqwest` and
+//! responses are decoded with `serde`.
+
+use crate::auth::Auth;
+use crate::engine_api::{
+    ExecutionPayload, ForkChoiceState, ForkchoiceUpdatedResponse, PayloadAttributes, PayloadStatus,
+    ENGINE_FORKCHOICE_UPDATED_V1, ENGINE_NEW_PAYLOAD_V1,
+};
+use crate::Error;
+use reqwest::{header::CONTENT_TYPE, Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// The timeout for `engine_newPayloadV1`, which may require the payload to be executed.
+pub const ENGINE_NEW_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(8);
+/// The timeout for `engine_forkchoiceUpdatedV1`.
+pub const ENGINE_FORKCHOICE_UPDATED_TIMEOUT: Duration = Duration::from_secs(8);
+
+pub struct HttpJsonRpc {
+    client: Client,
+    url: String,
+    auth: Option<Auth>,
+}
+
+impl HttpJsonRpc {
+    /// Creates a client for the engine API at `url`, authenticating each request with a JWT if
+    /// `auth` is supplied.
+    pub fn new(url: String, auth: Option<Auth>) -> Result<Self, Error> {
+        Ok(Self {
+            client: Client::builder().build().map_err(Error::Reqwest)?,
+            url,
+            auth,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Sends `payload` to the execution engine to be validated.
+    pub async fn new_payload_v1(&self, payload: &ExecutionPayload) -> Result<PayloadStatus, Error> {
+        self.rpc_request(
+            ENGINE_NEW_PAYLOAD_V1,
+            json!([payload]),
+            ENGINE_NEW_PAYLOAD_TIMEOUT,
+        )
+        .await
+    }
+
+    /// Informs the execution engine of the head, safe and finalized blocks. If
+    /// `payload_attributes` are supplied, the execution engine starts building a payload on the
+    /// head.
+    pub async fn forkchoice_updated_v1(
+        &self,
+        forkchoice_state: ForkChoiceState,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdatedResponse, Error> {
+        self.rpc_request(
+            ENGINE_FORKCHOICE_UPDATED_V1,
+            json!([forkchoice_state, payload_attributes]),
+            ENGINE_FORKCHOICE_UPDATED_TIMEOUT,
+        )
+        .await
+    }
+
+    async fn rpc_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<T, Error> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": 1
+        });
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .timeout(timeout)
+            .header(CONTENT_TYPE, "application/json")
+            .json(&body);
+
+        if let Some(auth) = &self.auth {
+            request = request.bearer_auth(auth.generate_token().map_err(Error::Auth)?);
+        }
+
+        let response = request.send().await.map_err(Error::Reqwest)?;
+        if response.status() != StatusCode::OK {
+            return Err(Error::StatusCode(response.status()));
+        }
+
+        let response: Value = response.json().await.map_err(Error::Reqwest)?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::ServerMessage(error.to_string()));
+        }
+
+        let result = response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| Error::MissingResult(method.to_string()))?;
+        serde_json::from_value(result).map_err(|e| Error::InvalidResponse(e.to_string()))
+    }
+}
@@ -0,0 +1,156 @@
+//! Provides a client for the engine API of an execution engine (e.g., an eth1 node after the
+//! merge), used to validate execution payloads and to keep the execution engine's fork choice in
+//! step with the beacon chain.
+//!
+//! The beacon chain does not yet contain execution payloads, so nothing is sent to the execution
+//! engine until the merge fork is introduced.
+
+use environment::TaskExecutor;
+use serde::{Deserialize, Serialize};
+use slog::{debug, Logger};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use types::Hash256;
+
+pub mod auth;
+pub mod engine_api;
+mod http;
+
+pub use auth::{Auth, JwtKey};
+pub use engine_api::{
+    ExecutionPayload, ForkChoiceState, ForkchoiceUpdatedResponse, PayloadAttributes, PayloadId,
+    PayloadStatus, PayloadStatusKind,
+};
+pub use http::HttpJsonRpc;
+
+#[derive(Debug)]
+pub enum Error {
+    Reqwest(reqwest::Error),
+    Auth(auth::Error),
+    /// The execution engine responded with a status other than 200 OK.
+    StatusCode(reqwest::StatusCode),
+    /// The execution engine returned a JSON-RPC error.
+    ServerMessage(String),
+    /// The response for the given method did not include a result.
+    MissingResult(String),
+    InvalidResponse(String),
+}
+
+/// The configuration of the connection to the execution engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// The URL of the execution engine's engine API.
+    pub endpoint: String,
+    /// The path of the file containing the hex-encoded secret shared with the execution engine.
+    ///
+    /// Requests are not authenticated if this is `None`.
+    pub jwt_secret: Option<PathBuf>,
+}
+
+struct Inner {
+    engine: HttpJsonRpc,
+    executor: TaskExecutor,
+    log: Logger,
+}
+
+/// A connection to an execution engine, which may be cheaply cloned.
+#[derive(Clone)]
+pub struct ExecutionLayer {
+    inner: Arc<Inner>,
+}
+
+impl ExecutionLayer {
+    pub fn from_config(
+        config: &Config,
+        executor: TaskExecutor,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let auth = config
+            .jwt_secret
+            .as_ref()
+            .map(|path| {
+                Auth::from_file(path).map_err(|e| format!("Unable to load JWT secret: {:?}", e))
+            })
+            .transpose()?;
+
+        let engine = HttpJsonRpc::new(config.endpoint.clone(), auth)
+            .map_err(|e| format!("Unable to create engine API client: {:?}", e))?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                engine,
+                executor,
+                log,
+            }),
+        })
+    }
+
+    pub fn endpoint(&self) -> &str {
+        self.inner.engine.url()
+    }
+
+    pub fn executor(&self) -> &TaskExecutor {
+        &self.inner.executor
+    }
+
+    /// Runs the future returned by `generate_future` to completion, blocking the current thread.
+    ///
+    /// Allows the (synchronous) beacon chain to call the execution engine. Must not be called
+    /// from an async task.
+    pub fn block_on<'a, F, T, U>(&'a self, generate_future: F) -> U
+    where
+        F: FnOnce(&'a Self) -> T,
+        T: Future<Output = U>,
+    {
+        self.inner
+            .executor
+            .runtime_handle()
+            .enter(|| futures::executor::block_on(generate_future(self)))
+    }
+
+    /// Sends `payload` to the execution engine to be validated, as part of importing the block
+    /// which contains it.
+    pub async fn notify_new_payload(
+        &self,
+        payload: &ExecutionPayload,
+    ) -> Result<PayloadStatus, Error> {
+        debug!(
+            self.inner.log,
+            "Issuing engine_newPayload";
+            "block_number" => payload.block_number,
+            "block_hash" => format!("{:?}", payload.block_hash),
+        );
+
+        self.inner.engine.new_payload_v1(payload).await
+    }
+
+    /// Informs the execution engine of the execution block of the head and finalized beacon
+    /// blocks. If `payload_attributes` are supplied, the execution engine starts building a
+    /// payload for an upcoming block proposal.
+    pub async fn notify_forkchoice_updated(
+        &self,
+        head_block_hash: Hash256,
+        finalized_block_hash: Hash256,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdatedResponse, Error> {
+        debug!(
+            self.inner.log,
+            "Issuing engine_forkchoiceUpdated";
+            "head_block_hash" => format!("{:?}", head_block_hash),
+            "finalized_block_hash" => format!("{:?}", finalized_block_hash),
+        );
+
+        let forkchoice_state = ForkChoiceState {
+            head_block_hash,
+            // There is no notion of a safe block yet, so the head is used.
+            safe_block_hash: head_block_hash,
+            finalized_block_hash,
+        };
+
+        self.inner
+            .engine
+            .forkchoice_updated_v1(forkchoice_state, payload_attributes)
+            .await
+    }
+}
@@ -5,6 +5,27 @@ use ssz::Encode;
 use state_processing::initialize_beacon_state_from_eth1;
 use types::{BeaconState, ChainSpec, DepositData, EthSpec, Hash256, Keypair, PublicKey, Signature};
 
+/// The deposit made for a single validator in an interop genesis state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteropDeposit {
+    /// The deposit amount, in Gwei.
+    pub amount: u64,
+    /// The withdrawal credentials. If `None`, BLS credentials are derived from the validator's
+    /// public key.
+    pub withdrawal_credentials: Option<Hash256>,
+}
+
+impl InteropDeposit {
+    /// A deposit of `spec.max_effective_balance` with BLS withdrawal credentials, as used by the
+    /// Eth2 interop procedure.
+    pub fn max_effective_balance(spec: &ChainSpec) -> Self {
+        Self {
+            amount: spec.max_effective_balance,
+            withdrawal_credentials: None,
+        }
+    }
+}
+
 /// Builds a genesis state as defined by the Eth2 interop procedure (see below).
 ///
 /// Reference:
@@ -14,9 +35,31 @@ pub fn interop_genesis_state<T: EthSpec>(
     genesis_time: u64,
     spec: &ChainSpec,
 ) -> Result<BeaconState<T>, String> {
+    let deposits = vec![InteropDeposit::max_effective_balance(spec); keypairs.len()];
+    interop_genesis_state_with_deposits(keypairs, &deposits, genesis_time, spec)
+}
+
+/// Builds a genesis state in the same way as `interop_genesis_state`, except that the deposit of
+/// `keypairs[i]` is described by `deposits[i]`.
+///
+/// Validators with a deposit of less than `spec.max_effective_balance` are not activated at
+/// genesis, which is useful for producing pending validators in tests.
+pub fn interop_genesis_state_with_deposits<T: EthSpec>(
+    keypairs: &[Keypair],
+    deposits: &[InteropDeposit],
+    genesis_time: u64,
+    spec: &ChainSpec,
+) -> Result<BeaconState<T>, String> {
+    if keypairs.len() != deposits.len() {
+        return Err(format!(
+            "Keypair count ({}) does not match deposit count ({})",
+            keypairs.len(),
+            deposits.len()
+        ));
+    }
+
     let eth1_block_hash = Hash256::from_slice(&[0x42; 32]);
     let eth1_timestamp = 2_u64.pow(40);
-    let amount = spec.max_effective_balance;
 
     let withdrawal_credentials = |pubkey: &PublicKey| {
         let mut credentials = hash(&pubkey.as_ssz_bytes());
@@ -26,11 +69,14 @@ pub fn interop_genesis_state<T: EthSpec>(
 
     let datas = keypairs
         .into_par_iter()
-        .map(|keypair| {
+        .zip(deposits.into_par_iter())
+        .map(|(keypair, deposit)| {
             let mut data = DepositData {
-                withdrawal_credentials: withdrawal_credentials(&keypair.pk),
+                withdrawal_credentials: deposit
+                    .withdrawal_credentials
+                    .unwrap_or_else(|| withdrawal_credentials(&keypair.pk)),
                 pubkey: keypair.pk.clone().into(),
-                amount,
+                amount: deposit.amount,
                 signature: Signature::empty().into(),
             };
 
@@ -117,4 +163,39 @@ mod test {
             "validator count should be correct"
         );
     }
+
+    #[test]
+    fn interop_state_with_deposits() {
+        let spec = &TestEthSpec::default_spec();
+        let keypairs = generate_deterministic_keypairs(4);
+
+        let credentials = Hash256::repeat_byte(0x01);
+        let pending_amount = spec.max_effective_balance / 2;
+        let mut deposits = vec![InteropDeposit::max_effective_balance(spec); keypairs.len()];
+        deposits[1].withdrawal_credentials = Some(credentials);
+        deposits[2].amount = pending_amount;
+
+        let state =
+            interop_genesis_state_with_deposits::<TestEthSpec>(&keypairs, &deposits, 42, spec)
+                .expect("should build state");
+
+        assert_eq!(state.validators.len(), keypairs.len());
+        assert_eq!(state.validators[1].withdrawal_credentials, credentials);
+        assert_eq!(state.balances[2], pending_amount);
+
+        let genesis_epoch = state.current_epoch();
+        for (i, validator) in state.validators.iter().enumerate() {
+            assert_eq!(
+                validator.is_active_at(genesis_epoch),
+                i != 2,
+                "only validators with a full deposit should be active at genesis"
+            );
+        }
+
+        assert!(
+            interop_genesis_state_with_deposits::<TestEthSpec>(&keypairs, &deposits[1..], 42, spec)
+                .is_err(),
+            "should reject mismatched deposits"
+        );
+    }
 }
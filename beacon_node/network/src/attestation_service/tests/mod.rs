@@ -9,7 +9,8 @@ mod tests {
     };
     use eth2_libp2p::discovery::{build_enr, Keypair};
     use eth2_libp2p::{
-        discovery::CombinedKey, CombinedKeyExt, NetworkConfig, NetworkGlobals, SubnetDiscovery,
+        discovery::CombinedKey, CombinedKeyExt, NetworkConfig, NetworkGlobals, ScoreParams,
+        SubnetDiscovery,
     };
     use futures::Stream;
     use genesis::{generate_deterministic_keypairs, interop_genesis_state};
@@ -102,7 +103,8 @@ mod tests {
         let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
         let enr = build_enr::<MinimalEthSpec>(&enr_key, &config, EnrForkId::default()).unwrap();
 
-        let network_globals: NetworkGlobals<MinimalEthSpec> = NetworkGlobals::new(enr, 0, 0, &log);
+        let network_globals: NetworkGlobals<MinimalEthSpec> =
+            NetworkGlobals::new(enr, 0, 0, ScoreParams::default(), &log);
         AttestationService::new(beacon_chain, Arc::new(network_globals), &log)
     }
 
@@ -35,7 +35,7 @@
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
 
-use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
+use crate::{metrics, service::NetworkMessage, sync::SyncMessage, GossipValidator};
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
 use environment::TaskExecutor;
 use eth2_libp2p::{MessageId, NetworkGlobals, PeerId};
@@ -223,10 +223,13 @@ impl<E: EthSpec> WorkEvent<E> {
     }
 
     /// Create a new `Work` event for some aggregated attestation.
+    ///
+    /// The `seen_timestamp` is the time at which the aggregate was received from the network.
     pub fn aggregated_attestation(
         message_id: MessageId,
         peer_id: PeerId,
         aggregate: SignedAggregateAndProof<E>,
+        seen_timestamp: Option<Duration>,
     ) -> Self {
         Self {
             drop_during_sync: true,
@@ -234,15 +237,19 @@ impl<E: EthSpec> WorkEvent<E> {
                 message_id,
                 peer_id,
                 aggregate: Box::new(aggregate),
+                seen_timestamp,
             },
         }
     }
 
     /// Create a new `Work` event for some block.
+    ///
+    /// The `seen_timestamp` is the time at which the block was received from the network.
     pub fn gossip_beacon_block(
         message_id: MessageId,
         peer_id: PeerId,
         block: Box<SignedBeaconBlock<E>>,
+        seen_timestamp: Option<Duration>,
     ) -> Self {
         Self {
             drop_during_sync: false,
@@ -250,6 +257,7 @@ impl<E: EthSpec> WorkEvent<E> {
                 message_id,
                 peer_id,
                 block,
+                seen_timestamp,
             },
         }
     }
@@ -336,11 +344,13 @@ pub enum Work<E: EthSpec> {
         message_id: MessageId,
         peer_id: PeerId,
         aggregate: Box<SignedAggregateAndProof<E>>,
+        seen_timestamp: Option<Duration>,
     },
     GossipBlock {
         message_id: MessageId,
         peer_id: PeerId,
         block: Box<SignedBeaconBlock<E>>,
+        seen_timestamp: Option<Duration>,
     },
     GossipVoluntaryExit {
         message_id: MessageId,
@@ -411,6 +421,7 @@ pub struct BeaconProcessor<T: BeaconChainTypes> {
     pub network_tx: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
     pub sync_tx: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
     pub network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+    pub gossip_validator: Option<Arc<dyn GossipValidator<T::EthSpec>>>,
     pub executor: TaskExecutor,
     pub max_workers: usize,
     pub current_workers: usize,
@@ -704,6 +715,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
             chain,
             network_tx: self.network_tx.clone(),
             sync_tx: self.sync_tx.clone(),
+            gossip_validator: self.gossip_validator.clone(),
             log: self.log.clone(),
         };
 
@@ -742,7 +754,13 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         message_id,
                         peer_id,
                         aggregate,
-                    } => worker.process_gossip_aggregate(message_id, peer_id, *aggregate),
+                        seen_timestamp,
+                    } => worker.process_gossip_aggregate(
+                        message_id,
+                        peer_id,
+                        *aggregate,
+                        seen_timestamp,
+                    ),
                     /*
                      * Verification for beacon blocks received on gossip.
                      */
@@ -750,7 +768,8 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         message_id,
                         peer_id,
                         block,
-                    } => worker.process_gossip_block(message_id, peer_id, *block),
+                        seen_timestamp,
+                    } => worker.process_gossip_block(message_id, peer_id, *block, seen_timestamp),
                     /*
                      * Voluntary exits received on gossip.
                      */
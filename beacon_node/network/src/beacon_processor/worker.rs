@@ -2,16 +2,19 @@ use super::{
     chain_segment::{handle_chain_segment, ProcessId},
     BlockResultSender,
 };
-use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
+use crate::{metrics, service::NetworkMessage, sync::SyncMessage, GossipValidator};
 use beacon_chain::{
-    attestation_verification::Error as AttnError, observed_operations::ObservationOutcome,
-    BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
+    attestation_verification::Error as AttnError, block_times_cache::BlockImportStage,
+    gossip_observations::GossipMessage, observed_operations::ObservationOutcome, BeaconChain,
+    BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
 };
 use eth2_libp2p::{MessageAcceptance, MessageId, PeerAction, PeerId};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use ssz::Encode;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tree_hash::TreeHash;
 use types::{
     Attestation, AttesterSlashing, Hash256, ProposerSlashing, SignedAggregateAndProof,
     SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
@@ -22,6 +25,7 @@ pub struct Worker<T: BeaconChainTypes> {
     pub chain: Arc<BeaconChain<T>>,
     pub network_tx: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
     pub sync_tx: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
+    pub gossip_validator: Option<Arc<dyn GossipValidator<T::EthSpec>>>,
     pub log: Logger,
 }
 
@@ -60,6 +64,12 @@ impl<T: BeaconChainTypes> Worker<T> {
             }
         };
 
+        if !self.apply_gossip_validator(&message_id, &peer_id, |validator| {
+            validator.validate_attestation(&peer_id, attestation.attestation(), subnet_id)
+        }) {
+            return;
+        }
+
         // Indicate to the `Network` service that this message is valid and can be
         // propagated on the gossip network.
         self.propagate_validation_result(message_id, peer_id.clone(), MessageAcceptance::Accept);
@@ -116,6 +126,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         message_id: MessageId,
         peer_id: PeerId,
         aggregate: SignedAggregateAndProof<T::EthSpec>,
+        seen_timestamp: Option<Duration>,
     ) {
         let beacon_block_root = aggregate.message.aggregate.data.beacon_block_root;
 
@@ -137,6 +148,22 @@ impl<T: BeaconChainTypes> Worker<T> {
             }
         };
 
+        let signed_aggregate = aggregate.signed_aggregate();
+        self.chain.record_gossip_observation(
+            signed_aggregate.message.aggregate.data.slot,
+            GossipMessage::Aggregate {
+                attestation_data_root: signed_aggregate.message.aggregate.data.tree_hash_root(),
+                aggregator_index: signed_aggregate.message.aggregator_index,
+            },
+            seen_timestamp,
+        );
+
+        if !self.apply_gossip_validator(&message_id, &peer_id, |validator| {
+            validator.validate_aggregate(&peer_id, aggregate.signed_aggregate())
+        }) {
+            return;
+        }
+
         // Indicate to the `Network` service that this message is valid and can be
         // propagated on the gossip network.
         self.propagate_validation_result(message_id, peer_id.clone(), MessageAcceptance::Accept);
@@ -189,6 +216,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         message_id: MessageId,
         peer_id: PeerId,
         block: SignedBeaconBlock<T::EthSpec>,
+        seen_timestamp: Option<Duration>,
     ) {
         let verified_block = match self.chain.verify_block_for_gossip(block) {
             Ok(verified_block) => {
@@ -198,6 +226,25 @@ impl<T: BeaconChainTypes> Worker<T> {
                     "slot" => verified_block.block.slot(),
                     "hash" => verified_block.block_root.to_string()
                 );
+                self.chain.record_block_import_stage(
+                    verified_block.block_root,
+                    verified_block.block.slot(),
+                    BlockImportStage::Observed,
+                    seen_timestamp,
+                );
+                self.chain.record_gossip_observation(
+                    verified_block.block.slot(),
+                    GossipMessage::Block {
+                        block_root: verified_block.block_root,
+                        proposer_index: verified_block.block.message.proposer_index,
+                    },
+                    seen_timestamp,
+                );
+                if !self.apply_gossip_validator(&message_id, &peer_id, |validator| {
+                    validator.validate_block(&peer_id, &verified_block.block)
+                }) {
+                    return;
+                }
                 self.propagate_validation_result(
                     message_id,
                     peer_id.clone(),
@@ -214,6 +261,7 @@ impl<T: BeaconChainTypes> Worker<T> {
             | Err(e @ BlockError::BlockIsAlreadyKnown)
             | Err(e @ BlockError::RepeatProposal { .. })
             | Err(e @ BlockError::NotFinalizedDescendant { .. })
+            | Err(e @ BlockError::OptimisticImportNotPermitted { .. })
             | Err(e @ BlockError::BeaconChainError(_)) => {
                 warn!(self.log, "Could not verify block for gossip, ignoring the block";
                             "error" => e.to_string());
@@ -227,6 +275,7 @@ impl<T: BeaconChainTypes> Worker<T> {
             | Err(e @ BlockError::NonLinearSlots)
             | Err(e @ BlockError::UnknownValidator(_))
             | Err(e @ BlockError::PerBlockProcessingError(_))
+            | Err(e @ BlockError::ExecutionPayloadInvalid(_))
             | Err(e @ BlockError::NonLinearParentRoots)
             | Err(e @ BlockError::BlockIsNotLaterThanParent { .. })
             | Err(e @ BlockError::InvalidSignature)
@@ -493,6 +542,42 @@ impl<T: BeaconChainTypes> Worker<T> {
         handle_chain_segment(self.chain, process_id, blocks, self.sync_tx, self.log)
     }
 
+    /// Applies the `GossipValidator` (if any) to a message which has passed gossip verification.
+    ///
+    /// Returns `true` if the message should be propagated and imported. Otherwise, the result of
+    /// the validation is sent to the network and `false` is returned.
+    fn apply_gossip_validator<F>(
+        &self,
+        message_id: &MessageId,
+        peer_id: &PeerId,
+        validate: F,
+    ) -> bool
+    where
+        F: FnOnce(&dyn GossipValidator<T::EthSpec>) -> MessageAcceptance,
+    {
+        let validator = match &self.gossip_validator {
+            Some(validator) => validator,
+            None => return true,
+        };
+
+        match validate(validator.as_ref()) {
+            MessageAcceptance::Accept => true,
+            validation_result => {
+                debug!(
+                    self.log,
+                    "Gossip message failed custom validation";
+                    "peer" => peer_id.to_string()
+                );
+                self.propagate_validation_result(
+                    message_id.clone(),
+                    peer_id.clone(),
+                    validation_result,
+                );
+                false
+            }
+        }
+    }
+
     /// Send a message on `message_tx` that the `message_id` sent by `peer_id` should be propagated on
     /// the gossip network.
     ///
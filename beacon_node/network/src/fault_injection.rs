@@ -0,0 +1,107 @@
+//! Allows the messages received from specific peers to be dropped or delayed before they reach the
+//! router, so that partitions and latency between nodes can be reproduced deterministically in
+//! tests and simulations.
+//!
+//! Only compiled with the `fault_injection` feature. Faults are injected by sending a
+//! `NetworkMessage::InjectFault` to the network service.
+
+use crate::router::RouterMessage;
+use eth2_libp2p::PeerId;
+use std::collections::HashMap;
+use std::time::Duration;
+use types::EthSpec;
+
+/// The kind of messages received from a peer that a fault applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultTarget {
+    /// Gossipsub messages which the peer propagated to us.
+    Gossip,
+    /// RPC requests and responses sent to us by the peer.
+    Rpc,
+}
+
+/// The treatment of messages which are subject to a fault.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    /// The message is discarded. Dropped gossip is not propagated, dropped RPC requests are never
+    /// responded to and dropped RPC responses are never seen by the requester.
+    Drop,
+    /// The message is delivered to the router after the given duration.
+    Delay(Duration),
+}
+
+/// The faults currently injected, keyed by peer and the kind of messages they apply to.
+#[derive(Default)]
+pub struct FaultInjector {
+    faults: HashMap<(PeerId, FaultTarget), Fault>,
+}
+
+impl FaultInjector {
+    /// Applies `fault` to the `target` messages received from `peer_id`, or clears any such fault
+    /// if `fault` is `None`.
+    pub fn set_fault(&mut self, peer_id: PeerId, target: FaultTarget, fault: Option<Fault>) {
+        match fault {
+            Some(fault) => {
+                self.faults.insert((peer_id, target), fault);
+            }
+            None => {
+                self.faults.remove(&(peer_id, target));
+            }
+        }
+    }
+
+    /// Returns the fault applied to the `target` messages received from `peer_id`, if any.
+    pub fn fault(&self, peer_id: &PeerId, target: FaultTarget) -> Option<Fault> {
+        self.faults.get(&(peer_id.clone(), target)).copied()
+    }
+
+    /// Returns the fault applied to `message`, if any.
+    ///
+    /// Only gossip and RPC messages are subject to faults, connection events are always delivered.
+    pub fn fault_for<E: EthSpec>(&self, message: &RouterMessage<E>) -> Option<Fault> {
+        match message {
+            RouterMessage::RPCRequestReceived { peer_id, .. }
+            | RouterMessage::RPCResponseReceived { peer_id, .. } => {
+                self.fault(peer_id, FaultTarget::Rpc)
+            }
+            RouterMessage::PubsubMessage(_, peer_id, _, _) => {
+                self.fault(peer_id, FaultTarget::Gossip)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MinimalEthSpec;
+
+    #[test]
+    fn faults_are_per_peer_and_target() {
+        let mut injector = FaultInjector::default();
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let delay = Fault::Delay(Duration::from_millis(500));
+
+        injector.set_fault(peer_a.clone(), FaultTarget::Gossip, Some(Fault::Drop));
+        injector.set_fault(peer_a.clone(), FaultTarget::Rpc, Some(delay));
+
+        assert_eq!(
+            injector.fault(&peer_a, FaultTarget::Gossip),
+            Some(Fault::Drop)
+        );
+        assert_eq!(injector.fault(&peer_a, FaultTarget::Rpc), Some(delay));
+        assert_eq!(injector.fault(&peer_b, FaultTarget::Gossip), None);
+
+        injector.set_fault(peer_a.clone(), FaultTarget::Gossip, None);
+        assert_eq!(injector.fault(&peer_a, FaultTarget::Gossip), None);
+        assert_eq!(injector.fault(&peer_a, FaultTarget::Rpc), Some(delay));
+
+        assert_eq!(
+            injector.fault_for(&RouterMessage::<MinimalEthSpec>::StatusPeer(peer_a)),
+            None,
+            "connection events are not subject to faults"
+        );
+    }
+}
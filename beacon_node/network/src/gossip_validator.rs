@@ -0,0 +1,46 @@
+//! Provides a hook which allows applications embedding Lighthouse to apply additional validation
+//! to (or simply observe) gossip messages.
+
+use eth2_libp2p::{MessageAcceptance, PeerId};
+use types::{Attestation, EthSpec, SignedAggregateAndProof, SignedBeaconBlock, SubnetId};
+
+/// Additional validation for blocks and attestations received via gossip.
+///
+/// Each method is called only once a message has passed Lighthouse's own gossip verification,
+/// from a blocking worker thread. Its result determines whether the message is propagated and
+/// imported:
+///
+/// - `Accept`: the message is propagated and imported as normal.
+/// - `Ignore`: the message is neither propagated nor imported.
+/// - `Reject`: as for `Ignore`, and gossipsub is told the message is invalid.
+///
+/// All methods default to accepting the message.
+pub trait GossipValidator<E: EthSpec>: Send + Sync {
+    /// Validate a block received on the `beacon_block` topic.
+    fn validate_block(
+        &self,
+        _peer_id: &PeerId,
+        _block: &SignedBeaconBlock<E>,
+    ) -> MessageAcceptance {
+        MessageAcceptance::Accept
+    }
+
+    /// Validate an unaggregated attestation received on the attestation subnet `subnet_id`.
+    fn validate_attestation(
+        &self,
+        _peer_id: &PeerId,
+        _attestation: &Attestation<E>,
+        _subnet_id: SubnetId,
+    ) -> MessageAcceptance {
+        MessageAcceptance::Accept
+    }
+
+    /// Validate an aggregate received on the `beacon_aggregate_and_proof` topic.
+    fn validate_aggregate(
+        &self,
+        _peer_id: &PeerId,
+        _aggregate: &SignedAggregateAndProof<E>,
+    ) -> MessageAcceptance {
+        MessageAcceptance::Accept
+    }
+}
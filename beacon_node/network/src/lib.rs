@@ -3,14 +3,23 @@ extern crate lazy_static;
 
 /// This crate provides the network server for Lighthouse.
 pub mod error;
+pub mod gossip_validator;
 pub mod service;
 
 mod attestation_service;
 mod beacon_processor;
+#[cfg(feature = "fault_injection")]
+mod fault_injection;
 mod metrics;
+mod nat;
 mod persisted_dht;
+mod persisted_peers;
 mod router;
 mod sync;
 
-pub use eth2_libp2p::NetworkConfig;
+pub use eth2_libp2p::{MessageAcceptance, NetworkConfig};
+#[cfg(feature = "fault_injection")]
+pub use fault_injection::{Fault, FaultTarget};
+pub use gossip_validator::GossipValidator;
+pub use persisted_peers::{load_peers, PersistedPeer, PersistedPeers};
 pub use service::{NetworkMessage, NetworkService};
@@ -0,0 +1,477 @@
+//! Maps the node's TCP and UDP ports on the local NAT gateway, so that peers outside the local
+//! network may connect to it.
+//!
+//! UPnP is attempted first, falling back to NAT-PMP. Only IPv4 is supported.
+use crate::{NetworkConfig, NetworkMessage};
+use eth2_libp2p::types::{NatMethod, NatState, NatStatus};
+use eth2_libp2p::NetworkGlobals;
+use slog::{debug, info, warn};
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use types::EthSpec;
+
+/// The description given to UPnP port mappings.
+const UPNP_MAPPING_DESCRIPTION: &str = "lighthouse";
+/// How long to search for a UPnP gateway when removing mappings on shutdown.
+const UPNP_REMOVE_TIMEOUT: Duration = Duration::from_secs(1);
+/// The port on which the gateway listens for NAT-PMP requests.
+const NAT_PMP_PORT: u16 = 5351;
+/// The lifetime (in seconds) requested for NAT-PMP mappings, which are renewed halfway through.
+const NAT_PMP_LIFETIME: u32 = 7200;
+/// The number of times a NAT-PMP request is sent, doubling the timeout each time.
+const NAT_PMP_ATTEMPTS: u32 = 4;
+/// The time to wait for a response to the first NAT-PMP request.
+const NAT_PMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+/// The NAT-PMP opcodes of the supported requests.
+const NAT_PMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+const NAT_PMP_OP_MAP_UDP: u8 = 1;
+const NAT_PMP_OP_MAP_TCP: u8 = 2;
+
+/// Configuration of the NAT traversal task.
+#[derive(Debug, Clone)]
+pub struct NatConfig {
+    /// The local libp2p TCP port.
+    tcp_port: u16,
+    /// The local discovery UDP port, if discovery is enabled.
+    udp_port: Option<u16>,
+    /// Whether the mapped TCP port should be advertised in the ENR.
+    update_enr_tcp_port: bool,
+    /// Whether the external address and mapped UDP port should be advertised in the ENR.
+    update_enr_address: bool,
+}
+
+impl From<&NetworkConfig> for NatConfig {
+    fn from(config: &NetworkConfig) -> Self {
+        NatConfig {
+            tcp_port: config.libp2p_port,
+            udp_port: if config.disable_discovery {
+                None
+            } else {
+                Some(config.discovery_port)
+            },
+            update_enr_tcp_port: config.enr_tcp_port.is_none(),
+            update_enr_address: config.enr_address.is_none(),
+        }
+    }
+}
+
+/// The port mappings established on the gateway.
+#[derive(Debug, Clone, PartialEq)]
+struct Mappings {
+    method: NatMethod,
+    external_address: Ipv4Addr,
+    tcp_port: u16,
+    udp_port: Option<u16>,
+}
+
+impl Mappings {
+    fn status(&self) -> NatStatus {
+        NatStatus {
+            state: NatState::Established,
+            method: Some(self.method),
+            external_address: Some(self.external_address),
+            tcp_port: Some(self.tcp_port),
+            udp_port: self.udp_port,
+            error: None,
+        }
+    }
+}
+
+/// Attempts to map the ports in `config` on the local gateway, recording the outcome in
+/// `network_globals` and sending the external address and ports to the network service to be
+/// advertised in the ENR.
+///
+/// NAT-PMP mappings expire, so are renewed until the task is dropped.
+pub async fn run_nat_task<T: EthSpec>(
+    config: NatConfig,
+    network_globals: Arc<NetworkGlobals<T>>,
+    network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
+    log: slog::Logger,
+) {
+    *network_globals.nat_status.write() = NatStatus {
+        state: NatState::Pending,
+        ..NatStatus::default()
+    };
+
+    info!(log, "Attempting to map ports on the NAT gateway");
+    let mut mappings = {
+        let config = config.clone();
+        let log = log.clone();
+        match blocking(move || establish_mappings(&config, &log)).await {
+            Ok(mappings) => mappings,
+            Err(e) => {
+                warn!(log, "Could not map ports on the NAT gateway"; "error" => &e);
+                set_failed(&network_globals, e);
+                return;
+            }
+        }
+    };
+    publish_mappings(&config, &mappings, &network_globals, &network_send, &log);
+
+    if mappings.method != NatMethod::NatPmp {
+        return;
+    }
+
+    loop {
+        tokio::time::delay_for(Duration::from_secs(u64::from(NAT_PMP_LIFETIME / 2))).await;
+
+        let config_clone = config.clone();
+        match blocking(move || nat_pmp_mappings(&config_clone)).await {
+            Ok(renewed) if renewed == mappings => {
+                debug!(log, "Renewed NAT-PMP port mappings");
+                // A previous renewal may have failed.
+                *network_globals.nat_status.write() = renewed.status();
+            }
+            Ok(renewed) => {
+                mappings = renewed;
+                publish_mappings(&config, &mappings, &network_globals, &network_send, &log);
+            }
+            Err(e) => {
+                warn!(log, "Could not renew NAT-PMP port mappings"; "error" => &e);
+                set_failed(&network_globals, e);
+            }
+        }
+    }
+}
+
+/// Removes the UPnP port mappings described by `status`, if any.
+///
+/// NAT-PMP mappings are left to expire.
+pub fn remove_mappings(status: &NatStatus, log: &slog::Logger) {
+    if status.state != NatState::Established || status.method != Some(NatMethod::Upnp) {
+        return;
+    }
+
+    let options = igd::SearchOptions {
+        timeout: Some(UPNP_REMOVE_TIMEOUT),
+        ..igd::SearchOptions::default()
+    };
+    let gateway = match igd::search_gateway(options) {
+        Ok(gateway) => gateway,
+        Err(e) => {
+            debug!(log, "Could not find UPnP gateway to remove port mappings"; "error" => e.to_string());
+            return;
+        }
+    };
+
+    let mappings = [
+        (igd::PortMappingProtocol::TCP, status.tcp_port),
+        (igd::PortMappingProtocol::UDP, status.udp_port),
+    ];
+    for (protocol, port) in mappings.iter() {
+        if let Some(port) = port {
+            match gateway.remove_port(*protocol, *port) {
+                Ok(()) => {
+                    debug!(log, "Removed UPnP port mapping"; "protocol" => format!("{:?}", protocol), "port" => port)
+                }
+                Err(e) => {
+                    debug!(log, "Could not remove UPnP port mapping"; "protocol" => format!("{:?}", protocol), "port" => port, "error" => e.to_string())
+                }
+            }
+        }
+    }
+}
+
+/// Records the `mappings` in `network_globals`, and sends those which should be advertised to
+/// the network service.
+fn publish_mappings<T: EthSpec>(
+    config: &NatConfig,
+    mappings: &Mappings,
+    network_globals: &NetworkGlobals<T>,
+    network_send: &mpsc::UnboundedSender<NetworkMessage<T>>,
+    log: &slog::Logger,
+) {
+    info!(
+        log,
+        "Mapped ports on the NAT gateway";
+        "method" => format!("{:?}", mappings.method),
+        "external_address" => mappings.external_address.to_string(),
+        "tcp_port" => mappings.tcp_port,
+        "udp_port" => format!("{:?}", mappings.udp_port),
+    );
+    *network_globals.nat_status.write() = mappings.status();
+
+    let message = NetworkMessage::NatMappingEstablished {
+        external_address: Some(mappings.external_address).filter(|_| config.update_enr_address),
+        tcp_port: Some(mappings.tcp_port).filter(|_| config.update_enr_tcp_port),
+        udp_port: mappings.udp_port.filter(|_| config.update_enr_address),
+    };
+    let _ = network_send.send(message).map_err(|_| {
+        debug!(log, "Failed to send NAT mapping to the network service");
+    });
+}
+
+fn set_failed<T: EthSpec>(network_globals: &NetworkGlobals<T>, error: String) {
+    *network_globals.nat_status.write() = NatStatus {
+        state: NatState::Failed,
+        error: Some(error),
+        ..NatStatus::default()
+    };
+}
+
+/// Runs `f` on a thread where blocking is acceptable.
+async fn blocking<F, R>(f: F) -> Result<R, String>
+where
+    F: FnOnce() -> Result<R, String> + Send + 'static,
+    R: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("NAT task failed: {}", e))?
+}
+
+/// Maps the ports with UPnP, or with NAT-PMP if UPnP fails.
+fn establish_mappings(config: &NatConfig, log: &slog::Logger) -> Result<Mappings, String> {
+    let upnp_error = match upnp_mappings(config, log) {
+        Ok(mappings) => return Ok(mappings),
+        Err(e) => e,
+    };
+    debug!(log, "UPnP port mapping failed, trying NAT-PMP"; "error" => &upnp_error);
+    nat_pmp_mappings(config).map_err(|e| format!("UPnP: {}, NAT-PMP: {}", upnp_error, e))
+}
+
+/* UPnP */
+
+fn upnp_mappings(config: &NatConfig, log: &slog::Logger) -> Result<Mappings, String> {
+    let gateway = igd::search_gateway(igd::SearchOptions::default())
+        .map_err(|e| format!("No gateway found: {}", e))?;
+    let local_address = local_address_towards(*gateway.addr.ip())?;
+    let external_address = gateway
+        .get_external_ip()
+        .map_err(|e| format!("Could not get external address: {}", e))?;
+
+    let tcp_port = upnp_add_port(
+        &gateway,
+        igd::PortMappingProtocol::TCP,
+        SocketAddrV4::new(local_address, config.tcp_port),
+        log,
+    )?;
+    let udp_port = config.udp_port.and_then(|port| {
+        upnp_add_port(
+            &gateway,
+            igd::PortMappingProtocol::UDP,
+            SocketAddrV4::new(local_address, port),
+            log,
+        )
+        .map_err(|e| warn!(log, "Could not map the discovery port with UPnP"; "error" => e))
+        .ok()
+    });
+
+    Ok(Mappings {
+        method: NatMethod::Upnp,
+        external_address,
+        tcp_port,
+        udp_port,
+    })
+}
+
+/// Maps the same external port as the port of `local`, replacing any existing mapping of it.
+///
+/// Specific ports are more reliably mapped than those assigned by the gateway. If several nodes
+/// share a gateway, they should use different ports.
+fn upnp_add_port(
+    gateway: &igd::Gateway,
+    protocol: igd::PortMappingProtocol,
+    local: SocketAddrV4,
+    log: &slog::Logger,
+) -> Result<u16, String> {
+    let port = local.port();
+    let add_port = || {
+        gateway
+            .add_port(protocol, port, local, 0, UPNP_MAPPING_DESCRIPTION)
+            .map(|()| port)
+    };
+    match add_port() {
+        Err(igd::AddPortError::PortInUse) => {
+            debug!(log, "UPnP port in use, remapping"; "protocol" => format!("{:?}", protocol), "port" => port);
+            gateway.remove_port(protocol, port).map_err(|e| {
+                format!("Could not remove {:?} mapping of port {}: {}", protocol, port, e)
+            })?;
+            add_port()
+        }
+        result => result,
+    }
+    .map_err(|e| format!("Could not map {:?} port {}: {}", protocol, port, e))
+}
+
+/// Returns the local address from which packets are routed to `gateway`.
+fn local_address_towards(gateway: Ipv4Addr) -> Result<Ipv4Addr, String> {
+    // Connecting a UDP socket selects a route without sending anything.
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| socket.connect((gateway, NAT_PMP_PORT)).map(|()| socket))
+        .map_err(|e| format!("No route to gateway {}: {}", gateway, e))?;
+    match socket.local_addr() {
+        Ok(SocketAddr::V4(address)) => Ok(*address.ip()),
+        Ok(address) => Err(format!("Unexpected local address: {}", address)),
+        Err(e) => Err(format!("Could not get local address: {}", e)),
+    }
+}
+
+/* NAT-PMP (RFC 6886) */
+
+fn nat_pmp_mappings(config: &NatConfig) -> Result<Mappings, String> {
+    let gateway = default_gateway()?;
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| socket.connect((gateway, NAT_PMP_PORT)).map(|()| socket))
+        .map_err(|e| format!("Could not connect to gateway {}: {}", gateway, e))?;
+
+    let response = nat_pmp_request(&socket, &[0, NAT_PMP_OP_EXTERNAL_ADDRESS])?;
+    let external_address = parse_external_address_response(&response)?;
+    let tcp_port = nat_pmp_map_port(&socket, NAT_PMP_OP_MAP_TCP, config.tcp_port)?;
+    let udp_port = config
+        .udp_port
+        .map(|port| nat_pmp_map_port(&socket, NAT_PMP_OP_MAP_UDP, port))
+        .transpose()?;
+
+    Ok(Mappings {
+        method: NatMethod::NatPmp,
+        external_address,
+        tcp_port,
+        udp_port,
+    })
+}
+
+/// Requests a mapping of `port` to the same external port, returning the external port assigned
+/// by the gateway.
+fn nat_pmp_map_port(socket: &UdpSocket, opcode: u8, port: u16) -> Result<u16, String> {
+    let mut request = vec![0, opcode, 0, 0];
+    // The internal port, followed by the suggested external port.
+    request.extend_from_slice(&port.to_be_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    request.extend_from_slice(&NAT_PMP_LIFETIME.to_be_bytes());
+    parse_mapping_response(&nat_pmp_request(socket, &request)?, opcode)
+}
+
+/// Sends `request` to the gateway until a response arrives, doubling the timeout each attempt.
+fn nat_pmp_request(socket: &UdpSocket, request: &[u8]) -> Result<Vec<u8>, String> {
+    let mut timeout = NAT_PMP_INITIAL_TIMEOUT;
+    let mut buf = [0; 16];
+    for _ in 0..NAT_PMP_ATTEMPTS {
+        socket
+            .set_read_timeout(Some(timeout))
+            .and_then(|()| socket.send(request))
+            .map_err(|e| format!("Could not send request: {}", e))?;
+        match socket.recv(&mut buf) {
+            Ok(len) => return Ok(buf[..len].to_vec()),
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                timeout *= 2;
+            }
+            Err(e) => return Err(format!("Could not receive response: {}", e)),
+        }
+    }
+    Err("No response from gateway".into())
+}
+
+/// Checks the header of a response to a request with `opcode`, returning the remainder of the
+/// `len`-byte response.
+fn nat_pmp_response_body(response: &[u8], opcode: u8, len: usize) -> Result<&[u8], String> {
+    if response.len() < len {
+        return Err(format!("Truncated response of {} bytes", response.len()));
+    }
+    if response[0] != 0 || response[1] != 128 + opcode {
+        return Err(format!(
+            "Unexpected response version {} opcode {}",
+            response[0], response[1]
+        ));
+    }
+    let result = u16::from_be_bytes([response[2], response[3]]);
+    if result != 0 {
+        return Err(format!("Request refused with result code {}", result));
+    }
+    // Skip the seconds since the gateway's mapping table was initialised.
+    Ok(&response[8..len])
+}
+
+fn parse_external_address_response(response: &[u8]) -> Result<Ipv4Addr, String> {
+    let body = nat_pmp_response_body(response, NAT_PMP_OP_EXTERNAL_ADDRESS, 12)?;
+    Ok(Ipv4Addr::new(body[0], body[1], body[2], body[3]))
+}
+
+/// Returns the external port of a mapping response.
+fn parse_mapping_response(response: &[u8], opcode: u8) -> Result<u16, String> {
+    let body = nat_pmp_response_body(response, opcode, 16)?;
+    Ok(u16::from_be_bytes([body[2], body[3]]))
+}
+
+/// Returns the IPv4 default gateway, read from the kernel's routing table.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Result<Ipv4Addr, String> {
+    let routes = std::fs::read_to_string("/proc/net/route")
+        .map_err(|e| format!("Could not read routing table: {}", e))?;
+    parse_default_gateway(&routes).ok_or_else(|| "No default gateway".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Result<Ipv4Addr, String> {
+    Err("Finding the default gateway is only supported on Linux".into())
+}
+
+/// Parses the gateway of the default route from the contents of `/proc/net/route`, in which
+/// addresses are hex-encoded in host byte order.
+#[cfg(target_os = "linux")]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        if fields.get(1) != Some(&"00000000") {
+            return None;
+        }
+        let gateway = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nat_pmp_responses() {
+        let external_address = [0, 128, 0, 0, 0, 0, 1, 0, 203, 0, 113, 7];
+        assert_eq!(
+            parse_external_address_response(&external_address),
+            Ok(Ipv4Addr::new(203, 0, 113, 7))
+        );
+        assert!(parse_external_address_response(&external_address[..11]).is_err());
+
+        let mut mapping = vec![0, 130, 0, 0, 0, 0, 1, 0];
+        mapping.extend_from_slice(&9000u16.to_be_bytes());
+        mapping.extend_from_slice(&9001u16.to_be_bytes());
+        mapping.extend_from_slice(&NAT_PMP_LIFETIME.to_be_bytes());
+        assert_eq!(
+            parse_mapping_response(&mapping, NAT_PMP_OP_MAP_TCP),
+            Ok(9001)
+        );
+        assert!(parse_mapping_response(&mapping, NAT_PMP_OP_MAP_UDP).is_err());
+
+        // Result code 2: not authorized.
+        mapping[3] = 2;
+        assert!(parse_mapping_response(&mapping, NAT_PMP_OP_MAP_TCP).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn default_gateway_from_routing_table() {
+        let gateway = Ipv4Addr::new(192, 168, 1, 1);
+        let routes = format!(
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+             eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+             eth0\t00000000\t{:08X}\t0003\t0\t0\t0\t00000000\t0\t0\t0\n",
+            u32::from_ne_bytes(gateway.octets())
+        );
+        assert_eq!(parse_default_gateway(&routes), Some(gateway));
+        assert_eq!(
+            parse_default_gateway(
+                routes
+                    .lines()
+                    .take(2)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .as_str()
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use eth2_libp2p::{Enr, EnrExt, PeerId};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PersistedPeers`.
+pub const PEER_DB_KEY: &str = "PERSISTEDPEERSPERSISTEDPEERSPERS";
+
+/// A peer which was connected in a previous run, and may be dialed on startup before discovery
+/// has found any peers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistedPeer {
+    pub enr: Enr,
+    /// The score of the peer when it was last seen.
+    pub score: f64,
+    /// The time the peer was last seen connected, in seconds since the UNIX epoch.
+    pub last_seen: u64,
+}
+
+impl PersistedPeer {
+    pub fn peer_id(&self) -> PeerId {
+        self.enr.peer_id()
+    }
+
+    /// Orders peers by decreasing score, then by most recently seen.
+    fn priority_cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .partial_cmp(&self.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.last_seen.cmp(&self.last_seen))
+    }
+}
+
+impl rlp::Encodable for PersistedPeer {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(3);
+        s.append(&self.enr);
+        s.append(&self.score.to_bits());
+        s.append(&self.last_seen);
+    }
+}
+
+impl rlp::Decodable for PersistedPeer {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        Ok(Self {
+            enr: rlp.val_at(0)?,
+            score: f64::from_bits(rlp.val_at(1)?),
+            last_seen: rlp.val_at(2)?,
+        })
+    }
+}
+
+/// The peers persisted to disk, in order of dialing priority.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PersistedPeers {
+    pub peers: Vec<PersistedPeer>,
+}
+
+impl PersistedPeers {
+    /// Combine the currently `connected` peers with those persisted previously, which are
+    /// forgotten if `is_excluded` (e.g., they have since been banned) or they have not been seen
+    /// for longer than `expiry`.
+    ///
+    /// Only the `max_peers` with the highest priority are kept.
+    pub fn merge(
+        &self,
+        connected: Vec<PersistedPeer>,
+        is_excluded: impl Fn(&PeerId) -> bool,
+        now: u64,
+        expiry: Duration,
+        max_peers: usize,
+    ) -> Self {
+        let connected_ids = connected
+            .iter()
+            .map(PersistedPeer::peer_id)
+            .collect::<HashSet<_>>();
+
+        let previous = self.peers.iter().filter(|peer| {
+            let peer_id = peer.peer_id();
+            !connected_ids.contains(&peer_id) && !is_excluded(&peer_id)
+        });
+
+        let mut peers = Self {
+            peers: connected.iter().chain(previous).cloned().collect(),
+        };
+        peers.prune(now, expiry, max_peers);
+        peers
+    }
+
+    /// Remove peers which have not been seen for longer than `expiry`, then sort by priority and
+    /// keep only the first `max_peers`.
+    pub fn prune(&mut self, now: u64, expiry: Duration, max_peers: usize) {
+        self.peers
+            .retain(|peer| peer.last_seen.saturating_add(expiry.as_secs()) >= now);
+        self.peers.sort_by(PersistedPeer::priority_cmp);
+        self.peers.truncate(max_peers);
+    }
+}
+
+impl StoreItem for PersistedPeers {
+    fn db_column() -> DBColumn {
+        DBColumn::PeerDb
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        rlp::encode_list(&self.peers)
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let rlp = rlp::Rlp::new(bytes);
+        let peers = rlp
+            .as_list()
+            .map_err(|e| StoreError::RlpError(format!("{}", e)))?;
+        Ok(PersistedPeers { peers })
+    }
+}
+
+/// Load the persisted peers from `store`, or none if they can't be read.
+pub fn load_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> PersistedPeers {
+    let key = Hash256::from_slice(&PEER_DB_KEY.as_bytes());
+    match store.get_item(&key) {
+        Ok(Some(peers)) => peers,
+        _ => PersistedPeers::default(),
+    }
+}
+
+/// Attempt to persist `peers` to `store`.
+pub fn persist_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    peers: &PersistedPeers,
+) -> Result<(), store::Error> {
+    let key = Hash256::from_slice(&PEER_DB_KEY.as_bytes());
+    store.put_item(&key, peers)?;
+    Ok(())
+}
+
+/// The current time in seconds since the UNIX epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2_libp2p::discv5::enr::{CombinedKey, EnrBuilder};
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::{HotColdDB, MemoryStore};
+    use types::{ChainSpec, MinimalEthSpec};
+
+    const EXPIRY: Duration = Duration::from_secs(100);
+
+    fn peer(score: f64, last_seen: u64) -> PersistedPeer {
+        let key = CombinedKey::generate_secp256k1();
+        PersistedPeer {
+            enr: EnrBuilder::new("v4").build(&key).unwrap(),
+            score,
+            last_seen,
+        }
+    }
+
+    #[test]
+    fn test_persisted_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap();
+        let store = Arc::new(store);
+
+        assert_eq!(load_peers(store.clone()), PersistedPeers::default());
+
+        let peers = PersistedPeers {
+            peers: vec![peer(-1.5, 10), peer(0.0, 20)],
+        };
+        persist_peers(store.clone(), &peers).unwrap();
+        assert_eq!(load_peers(store), peers);
+    }
+
+    #[test]
+    fn merge_prioritises_and_expires() {
+        let now = 1_000;
+        let (expired, banned, stale, replaced) = (
+            peer(5.0, now - EXPIRY.as_secs() - 1),
+            peer(5.0, now),
+            peer(1.0, now - 50),
+            peer(1.0, now - 50),
+        );
+        let previous = PersistedPeers {
+            peers: vec![expired, banned.clone(), stale.clone(), replaced.clone()],
+        };
+
+        let reconnected = PersistedPeer {
+            last_seen: now,
+            score: 0.0,
+            ..replaced
+        };
+        let best = peer(2.0, now);
+        let merged = previous.merge(
+            vec![reconnected.clone(), best.clone()],
+            |peer_id| *peer_id == banned.peer_id(),
+            now,
+            EXPIRY,
+            10,
+        );
+        assert_eq!(merged.peers, vec![best.clone(), stale, reconnected]);
+
+        let merged = merged.merge(vec![], |_| false, now, EXPIRY, 1);
+        assert_eq!(merged.peers, vec![best]);
+    }
+}
@@ -9,6 +9,7 @@ pub mod processor;
 
 use crate::error;
 use crate::service::NetworkMessage;
+use crate::GossipValidator;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{
     rpc::{RPCError, RequestId},
@@ -74,6 +75,7 @@ impl<T: BeaconChainTypes> Router<T> {
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
+        gossip_validator: Option<Arc<dyn GossipValidator<T::EthSpec>>>,
         executor: environment::TaskExecutor,
         log: slog::Logger,
     ) -> error::Result<mpsc::UnboundedSender<RouterMessage<T::EthSpec>>> {
@@ -88,6 +90,7 @@ impl<T: BeaconChainTypes> Router<T> {
             beacon_chain,
             network_globals.clone(),
             network_send,
+            gossip_validator,
             &log,
         );
 
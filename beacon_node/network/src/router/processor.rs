@@ -3,6 +3,7 @@ use crate::beacon_processor::{
 };
 use crate::service::NetworkMessage;
 use crate::sync::{PeerSyncInfo, SyncMessage};
+use crate::GossipValidator;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::rpc::*;
 use eth2_libp2p::{
@@ -10,6 +11,7 @@ use eth2_libp2p::{
 };
 use itertools::process_results;
 use slog::{debug, error, o, trace, warn};
+use slot_clock::SlotClock;
 use std::cmp;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -44,6 +46,7 @@ impl<T: BeaconChainTypes> Processor<T> {
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
+        gossip_validator: Option<Arc<dyn GossipValidator<T::EthSpec>>>,
         log: &slog::Logger,
     ) -> Self {
         let sync_logger = log.new(o!("service"=> "sync"));
@@ -65,6 +68,7 @@ impl<T: BeaconChainTypes> Processor<T> {
             network_tx: network_send.clone(),
             sync_tx: sync_send.clone(),
             network_globals,
+            gossip_validator,
             executor,
             max_workers: cmp::max(1, num_cpus::get()),
             current_workers: 0,
@@ -529,9 +533,14 @@ impl<T: BeaconChainTypes> Processor<T> {
         peer_id: PeerId,
         block: Box<SignedBeaconBlock<T::EthSpec>>,
     ) {
+        let seen_timestamp = self.chain.slot_clock.now_duration();
+
         self.beacon_processor_send
             .try_send(BeaconWorkEvent::gossip_beacon_block(
-                message_id, peer_id, block,
+                message_id,
+                peer_id,
+                block,
+                seen_timestamp,
             ))
             .unwrap_or_else(|e| {
                 error!(
@@ -575,9 +584,14 @@ impl<T: BeaconChainTypes> Processor<T> {
         peer_id: PeerId,
         aggregate: SignedAggregateAndProof<T::EthSpec>,
     ) {
+        let seen_timestamp = self.chain.slot_clock.now_duration();
+
         self.beacon_processor_send
             .try_send(BeaconWorkEvent::aggregated_attestation(
-                message_id, peer_id, aggregate,
+                message_id,
+                peer_id,
+                aggregate,
+                seen_timestamp,
             ))
             .unwrap_or_else(|e| {
                 error!(
@@ -1,8 +1,12 @@
+#[cfg(feature = "fault_injection")]
+use crate::fault_injection::{Fault, FaultInjector, FaultTarget};
+use crate::nat::{self, NatConfig};
 use crate::persisted_dht::{load_dht, persist_dht};
+use crate::persisted_peers::{load_peers, persist_peers, unix_now, PersistedPeer, PersistedPeers};
 use crate::router::{Router, RouterMessage};
 use crate::{
     attestation_service::{AttServiceMessage, AttestationService},
-    NetworkConfig,
+    GossipValidator, NetworkConfig,
 };
 use crate::{error, metrics};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
@@ -12,12 +16,13 @@ use eth2_libp2p::{
 };
 use eth2_libp2p::{
     types::GossipKind, BehaviourEvent, GossipTopic, MessageId, NetworkGlobals, PeerId, TopicHash,
+    TrustedPeer,
 };
 use eth2_libp2p::{MessageAcceptance, Service as LibP2PService};
 use futures::prelude::*;
 use rest_types::ValidatorSubscription;
 use slog::{debug, error, info, o, trace, warn};
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, net::Ipv4Addr, sync::Arc, time::Duration};
 use store::HotColdDB;
 use tokio::sync::mpsc;
 use tokio::time::Delay;
@@ -27,6 +32,8 @@ mod tests;
 
 /// The interval (in seconds) that various network metrics will update.
 const METRIC_UPDATE_INTERVAL: u64 = 1;
+/// The interval (in seconds) at which connected peers are persisted to the store.
+const PERSIST_PEERS_INTERVAL: u64 = 300;
 
 /// Types of messages that the network service can receive.
 #[derive(Debug)]
@@ -74,6 +81,27 @@ pub enum NetworkMessage<T: EthSpec> {
         peer_id: PeerId,
         reason: GoodbyeReason,
     },
+    /// Always maintain a connection to a peer, exempting it from score-based disconnects.
+    AddTrustedPeer { peer: TrustedPeer },
+    /// Stop trusting a peer, without disconnecting it.
+    RemoveTrustedPeer { peer_id: PeerId },
+    /// Change the target number of peers.
+    SetTargetPeers { target_peers: usize },
+    /// Ports have been mapped on the NAT gateway. Each of the external address and ports which is
+    /// present should be advertised in the local ENR.
+    NatMappingEstablished {
+        external_address: Option<Ipv4Addr>,
+        tcp_port: Option<u16>,
+        udp_port: Option<u16>,
+    },
+    /// Drops or delays the `target` messages received from a peer, or clears the fault if `fault`
+    /// is `None`.
+    #[cfg(feature = "fault_injection")]
+    InjectFault {
+        peer_id: PeerId,
+        target: FaultTarget,
+        fault: Option<Fault>,
+    },
 }
 
 /// Service that handles communication between internal services and the `eth2_libp2p` network service.
@@ -97,6 +125,20 @@ pub struct NetworkService<T: BeaconChainTypes> {
     next_fork_update: Option<Delay>,
     /// A timer for updating various network metrics.
     metrics_update: tokio::time::Interval,
+    /// Peers connected now or in a previous run, to be dialed on the next startup.
+    persisted_peers: PersistedPeers,
+    /// The maximum number of `persisted_peers`.
+    max_persisted_peers: usize,
+    /// Persisted peers which have not been connected for this long are forgotten.
+    persisted_peer_expiry: Duration,
+    /// A timer for persisting the connected peers.
+    persist_peers_update: tokio::time::Interval,
+    /// The faults applied to messages received from peers.
+    #[cfg(feature = "fault_injection")]
+    fault_injector: FaultInjector,
+    /// Used to deliver delayed messages to the router.
+    #[cfg(feature = "fault_injection")]
+    executor: environment::TaskExecutor,
     /// The logger for the network service.
     log: slog::Logger,
 }
@@ -106,6 +148,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
     pub async fn start(
         beacon_chain: Arc<BeaconChain<T>>,
         config: &NetworkConfig,
+        gossip_validator: Option<Arc<dyn GossipValidator<T::EthSpec>>>,
         executor: environment::TaskExecutor,
     ) -> error::Result<(
         Arc<NetworkGlobals<T::EthSpec>>,
@@ -137,6 +180,48 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             libp2p.swarm.add_enr(enr.clone()); //TODO change?
         }
 
+        // Dial the best peers from previous runs, rather than waiting for discovery to find peers.
+        let mut persisted_peers =
+            load_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone());
+        persisted_peers.prune(
+            unix_now(),
+            config.persisted_peer_expiry,
+            config.max_persisted_peers,
+        );
+        let peers_to_dial = persisted_peers
+            .peers
+            .iter()
+            .take(config.target_peers)
+            .collect::<Vec<_>>();
+        info!(
+            network_log,
+            "Dialing persisted peers"; "peers" => peers_to_dial.len()
+        );
+        for peer in peers_to_dial {
+            libp2p.swarm.add_enr(peer.enr.clone());
+            libp2p.swarm.dial(&peer.peer_id());
+        }
+
+        // map the listening ports on the local gateway
+        if config.nat_enabled {
+            if config.listens_ipv4() {
+                executor.spawn(
+                    nat::run_nat_task(
+                        NatConfig::from(config),
+                        network_globals.clone(),
+                        network_send.clone(),
+                        network_log.new(o!("service" => "nat")),
+                    ),
+                    "nat",
+                );
+            } else {
+                warn!(
+                    network_log,
+                    "NAT traversal is only supported when listening on IPv4"
+                );
+            }
+        }
+
         // launch derived network services
 
         // router task
@@ -144,6 +229,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             beacon_chain.clone(),
             network_globals.clone(),
             network_send.clone(),
+            gossip_validator,
             executor.clone(),
             network_log.clone(),
         )?;
@@ -155,6 +241,13 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         // create a timer for updating network metrics
         let metrics_update = tokio::time::interval(Duration::from_secs(METRIC_UPDATE_INTERVAL));
 
+        // create a timer for persisting connected peers, which skips the immediate first tick
+        let persist_peers_interval = Duration::from_secs(PERSIST_PEERS_INTERVAL);
+        let persist_peers_update = tokio::time::interval_at(
+            tokio::time::Instant::now() + persist_peers_interval,
+            persist_peers_interval,
+        );
+
         // create the network service and spawn the task
         let network_log = network_log.new(o!("service" => "network"));
         let network_service = NetworkService {
@@ -167,6 +260,14 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_globals: network_globals.clone(),
             next_fork_update,
             metrics_update,
+            persisted_peers,
+            max_persisted_peers: config.max_persisted_peers,
+            persisted_peer_expiry: config.persisted_peer_expiry,
+            persist_peers_update,
+            #[cfg(feature = "fault_injection")]
+            fault_injector: FaultInjector::default(),
+            #[cfg(feature = "fault_injection")]
+            executor: executor.clone(),
             log: network_log,
         };
 
@@ -174,6 +275,103 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
         Ok((network_globals, network_send))
     }
+
+    /// Merge the connected peers with a non-negative score into the persisted peers, and write
+    /// them to the store.
+    ///
+    /// Peers are only persisted if their ENR is known, since it is required to dial them.
+    fn update_persisted_peers(&mut self) {
+        let now = unix_now();
+        let connected_scores = self
+            .network_globals
+            .peers
+            .read()
+            .connected_peers()
+            .map(|(peer_id, info)| (peer_id.clone(), info.score.score()))
+            .filter(|(_, score)| *score >= 0.0)
+            .collect::<Vec<_>>();
+        let connected = connected_scores
+            .into_iter()
+            .filter_map(|(peer_id, score)| {
+                Some(PersistedPeer {
+                    enr: self.libp2p.swarm.enr_of_peer(&peer_id)?,
+                    score,
+                    last_seen: now,
+                })
+            })
+            .collect();
+
+        let peer_db = self.network_globals.peers.read();
+        self.persisted_peers = self.persisted_peers.merge(
+            connected,
+            |peer_id| peer_db.is_banned_or_disconnected(peer_id),
+            now,
+            self.persisted_peer_expiry,
+            self.max_persisted_peers,
+        );
+        drop(peer_db);
+
+        debug!(
+            self.log,
+            "Persisting peers to store";
+            "peers" => self.persisted_peers.peers.len(),
+        );
+        if let Err(e) = persist_peers::<T::EthSpec, T::HotStore, T::ColdStore>(
+            self.store.clone(),
+            &self.persisted_peers,
+        ) {
+            error!(
+                self.log,
+                "Failed to persist peers";
+                "error" => format!("{:?}", e)
+            );
+        }
+    }
+
+    /// Sends a gossip or RPC message received from a peer to the router.
+    ///
+    /// With the `fault_injection` feature, the message is instead dropped or delayed if a fault
+    /// has been injected for the peer.
+    fn send_to_router(&mut self, message: RouterMessage<T::EthSpec>) {
+        #[cfg(feature = "fault_injection")]
+        match self.fault_injector.fault_for(&message) {
+            Some(Fault::Drop) => {
+                trace!(
+                    self.log,
+                    "Dropping message due to injected fault";
+                    "message" => format!("{:?}", message)
+                );
+                // Ensure dropped gossip is neither propagated nor retained for validation.
+                if let RouterMessage::PubsubMessage(id, peer_id, _, _) = message {
+                    self.libp2p.swarm.report_message_validation_result(
+                        &peer_id,
+                        id,
+                        MessageAcceptance::Ignore,
+                    );
+                }
+                return;
+            }
+            Some(Fault::Delay(delay)) => {
+                let router_send = self.router_send.clone();
+                let log = self.log.clone();
+                self.executor.spawn(
+                    async move {
+                        tokio::time::delay_for(delay).await;
+                        let _ = router_send.send(message).map_err(|_| {
+                            debug!(log, "Failed to send delayed message to router");
+                        });
+                    },
+                    "network_fault_delay",
+                );
+                return;
+            }
+            None => (),
+        }
+
+        let _ = self.router_send.send(message).map_err(|_| {
+            debug!(self.log, "Failed to send message to router");
+        });
+    }
 }
 
 fn spawn_service<T: BeaconChainTypes>(
@@ -211,6 +409,8 @@ fn spawn_service<T: BeaconChainTypes>(
                             "Saved DHT state";
                         ),
                     }
+                    service.update_persisted_peers();
+                    nat::remove_mappings(&service.network_globals.nat_status(), &service.log);
 
                     info!(service.log, "Network service shutdown");
                     return;
@@ -226,6 +426,9 @@ fn spawn_service<T: BeaconChainTypes>(
                     }
                     update_gossip_metrics::<T::EthSpec>(&service.libp2p.swarm.gs());
                 }
+                _ = service.persist_peers_update.next() => {
+                    service.update_persisted_peers();
+                }
                 // handle a message sent to the network
                 Some(message) = service.network_recv.recv() => {
                     match message {
@@ -272,6 +475,25 @@ fn spawn_service<T: BeaconChainTypes>(
                         }
                         NetworkMessage::ReportPeer { peer_id, action } => service.libp2p.report_peer(&peer_id, action),
                         NetworkMessage::GoodbyePeer { peer_id, reason } => service.libp2p.goodbye_peer(&peer_id, reason),
+                        NetworkMessage::AddTrustedPeer { peer } => service.libp2p.swarm.add_trusted_peer(&peer),
+                        NetworkMessage::RemoveTrustedPeer { peer_id } => {
+                            service.libp2p.swarm.remove_trusted_peer(&peer_id);
+                        }
+                        NetworkMessage::SetTargetPeers { target_peers } => service.libp2p.swarm.set_target_peers(target_peers),
+                        NetworkMessage::NatMappingEstablished { external_address, tcp_port, udp_port } => {
+                            service.libp2p.swarm.update_enr_nat_mapping(external_address, tcp_port, udp_port);
+                        }
+                        #[cfg(feature = "fault_injection")]
+                        NetworkMessage::InjectFault { peer_id, target, fault } => {
+                            debug!(
+                                service.log,
+                                "Injecting network fault";
+                                "peer_id" => peer_id.to_string(),
+                                "target" => format!("{:?}", target),
+                                "fault" => format!("{:?}", fault),
+                            );
+                            service.fault_injector.set_fault(peer_id, target, fault);
+                        }
                         NetworkMessage::Subscribe { subscriptions } => {
                             if let Err(e) = service
                                 .attestation_service
@@ -327,21 +549,10 @@ fn spawn_service<T: BeaconChainTypes>(
                                 });
                             },
                             BehaviourEvent::RequestReceived{peer_id, id, request} => {
-                                let _ = service
-                                    .router_send
-                                    .send(RouterMessage::RPCRequestReceived{peer_id, id, request})
-                                    .map_err(|_| {
-                                        debug!(service.log, "Failed to send RPC to router");
-                                    });
+                                service.send_to_router(RouterMessage::RPCRequestReceived{peer_id, id, request});
                             }
                             BehaviourEvent::ResponseReceived{peer_id, id, response} => {
-                                let _ = service
-                                    .router_send
-                                    .send(RouterMessage::RPCResponseReceived{ peer_id, request_id: id, response })
-                                    .map_err(|_| {
-                                        debug!(service.log, "Failed to send RPC to router");
-                                    });
-
+                                service.send_to_router(RouterMessage::RPCResponseReceived{ peer_id, request_id: id, response });
                             }
                             BehaviourEvent::RPCFailed{id, peer_id, error} => {
                                 let _ = service
@@ -379,21 +590,11 @@ fn spawn_service<T: BeaconChainTypes>(
                                             subnet,
                                             attestation,
                                         );
-                                        let _ = service
-                                            .router_send
-                                            .send(RouterMessage::PubsubMessage(id, source, message, should_process))
-                                            .map_err(|_| {
-                                                debug!(service.log, "Failed to send pubsub message to router");
-                                            });
+                                        service.send_to_router(RouterMessage::PubsubMessage(id, source, message, should_process));
                                     }
                                     _ => {
                                         // all else is sent to the router
-                                        let _ = service
-                                            .router_send
-                                            .send(RouterMessage::PubsubMessage(id, source, message, true))
-                                            .map_err(|_| {
-                                                debug!(service.log, "Failed to send pubsub message to router");
-                                            });
+                                        service.send_to_router(RouterMessage::PubsubMessage(id, source, message, true));
                                     }
                                 }
                             }
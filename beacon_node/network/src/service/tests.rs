@@ -56,7 +56,7 @@ mod tests {
             // Create a new network service which implicitly gets dropped at the
             // end of the block.
 
-            let _ = NetworkService::start(beacon_chain.clone(), &config, executor)
+            let _ = NetworkService::start(beacon_chain.clone(), &config, None, executor)
                 .await
                 .unwrap();
             drop(signal);
@@ -50,6 +50,11 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             .unwrap_or_default()
     }
 
+    /// Returns true if the peer is trusted, in which case it is preferred for requests.
+    pub fn is_trusted(&self, peer_id: &PeerId) -> bool {
+        self.network_globals.peers.read().is_trusted(peer_id)
+    }
+
     pub fn status_peer<U: BeaconChainTypes>(
         &mut self,
         chain: Arc<BeaconChain<U>>,
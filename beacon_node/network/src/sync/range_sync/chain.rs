@@ -667,12 +667,14 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             // chain is unrecoverable, remove it
             ProcessingResult::RemoveChain
         } else {
-            // try to re-process the request using a different peer, if possible
+            // try to re-process the request using a different peer, if possible, preferring
+            // trusted peers
             let current_peer = &batch.current_peer;
             let new_peer = self
                 .peer_pool
                 .iter()
-                .find(|peer| *peer != current_peer)
+                .filter(|peer| *peer != current_peer)
+                .max_by_key(|peer| network.is_trusted(peer))
                 .unwrap_or_else(|| current_peer);
 
             batch.current_peer = new_peer.clone();
@@ -706,7 +708,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     /// to send a request and there are batches to request, false otherwise.
     fn send_range_request(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) -> bool {
         // find the next pending batch and request it from the peer
-        if let Some(peer_id) = self.get_next_peer() {
+        if let Some(peer_id) = self.get_next_peer(network) {
             if let Some(batch) = self.get_next_batch(peer_id) {
                 debug!(self.log, "Requesting batch";
                     "start_slot" => batch.start_slot(),
@@ -721,15 +723,17 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         false
     }
 
-    /// Returns a peer if there exists a peer which does not currently have a pending request.
+    /// Returns a peer if there exists a peer which does not currently have a pending request,
+    /// preferring trusted peers.
     ///
     /// This is used to create the next request.
-    fn get_next_peer(&self) -> Option<PeerId> {
+    fn get_next_peer(&self, network: &SyncNetworkContext<T::EthSpec>) -> Option<PeerId> {
         // TODO: Optimize this by combining with above two functions.
-        // randomize the peers for load balancing
+        // randomize the peers for load balancing, then move trusted peers to the front
         let mut rng = rand::thread_rng();
         let mut peers = self.peer_pool.iter().collect::<Vec<_>>();
         peers.shuffle(&mut rng);
+        peers.sort_by_key(|peer| !network.is_trusted(peer));
         for peer in peers {
             if self.pending_batches.peer_is_idle(peer) {
                 return Some(peer.clone());
@@ -15,13 +15,14 @@ use state_processing::per_block_processing::{
     verify_exit, VerifySignatures,
 };
 use state_processing::SigVerifiedOp;
-use std::collections::{hash_map, HashMap, HashSet};
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::ptr;
+use std::time::Instant;
 use types::{
     typenum::Unsigned, Attestation, AttesterSlashing, BeaconState, BeaconStateError, ChainSpec,
     EthSpec, Fork, ForkVersion, Hash256, ProposerSlashing, RelativeEpoch, SignedVoluntaryExit,
-    Validator,
+    Slot, Validator,
 };
 
 #[derive(Default, Debug)]
@@ -95,6 +96,22 @@ impl<T: EthSpec> OperationPool<T> {
         self.attestations.read().values().map(Vec::len).sum()
     }
 
+    /// Returns the number of distinct `AttestationData` and the total number of attestations in
+    /// the pool, for each slot.
+    pub fn attestation_counts_by_slot(&self) -> BTreeMap<Slot, (usize, usize)> {
+        let mut counts = BTreeMap::new();
+        for attestations in self.attestations.read().values() {
+            // All the attestations in this bucket have the same data.
+            if let Some(attestation) = attestations.first() {
+                let (num_data, num_attestations) =
+                    counts.entry(attestation.data.slot).or_insert((0, 0));
+                *num_data += 1;
+                *num_attestations += attestations.len();
+            }
+        }
+        counts
+    }
+
     /// Get a list of attestations for inclusion in a block.
     ///
     /// The `validity_filter` is a closure that provides extra filtering of the attestations
@@ -107,6 +124,23 @@ impl<T: EthSpec> OperationPool<T> {
         validity_filter: impl FnMut(&&Attestation<T>) -> bool,
         spec: &ChainSpec,
     ) -> Result<Vec<Attestation<T>>, OpPoolError> {
+        self.get_attestations_with_deadline(state, validity_filter, None, spec)
+            .map(|(attestations, _)| attestations)
+    }
+
+    /// As `get_attestations`, but once `deadline` has passed no further attestations are
+    /// considered, and the remaining space is filled without optimising for overlap (see
+    /// `maximum_cover`). This produces a block with fewer (or less valuable)
+    /// attestations rather than delaying it.
+    ///
+    /// Returns `true` alongside the attestations if the deadline was reached.
+    pub fn get_attestations_with_deadline(
+        &self,
+        state: &BeaconState<T>,
+        validity_filter: impl FnMut(&&Attestation<T>) -> bool,
+        deadline: Option<Instant>,
+        spec: &ChainSpec,
+    ) -> Result<(Vec<Attestation<T>>, bool), OpPoolError> {
         // Attestations for the current fork, which may be from the current or previous epoch.
         let prev_epoch = state.previous_epoch();
         let current_epoch = state.current_epoch();
@@ -123,6 +157,7 @@ impl<T: EthSpec> OperationPool<T> {
             spec,
         );
         let reader = self.attestations.read();
+        let mut skipped = false;
         let active_indices = state
             .get_cached_active_validator_indices(RelativeEpoch::Current)
             .map_err(OpPoolError::GetAttestationsTotalBalanceError)?;
@@ -136,6 +171,12 @@ impl<T: EthSpec> OperationPool<T> {
                     || key.domain_bytes_match(&curr_domain_bytes)
             })
             .flat_map(|(_, attestations)| attestations)
+            // Until the deadline...
+            .take_while(|_| {
+                let reached = deadline.map_or(false, |deadline| Instant::now() >= deadline);
+                skipped |= reached;
+                !reached
+            })
             // That are valid...
             .filter(|attestation| {
                 verify_attestation_for_block_inclusion(
@@ -149,10 +190,24 @@ impl<T: EthSpec> OperationPool<T> {
             .filter(validity_filter)
             .flat_map(|att| AttMaxCover::new(att, state, total_active_balance, spec));
 
-        Ok(maximum_cover(
-            valid_attestations,
-            T::MaxAttestations::to_usize(),
-        ))
+        let (attestations, deadline_reached) =
+            maximum_cover(valid_attestations, T::MaxAttestations::to_usize(), deadline);
+
+        Ok((attestations, skipped || deadline_reached))
+    }
+
+    /// Returns all attestations in the pool for which `filter` returns `true`.
+    pub fn get_filtered_attestations(
+        &self,
+        mut filter: impl FnMut(&Attestation<T>) -> bool,
+    ) -> Vec<Attestation<T>> {
+        self.attestations
+            .read()
+            .values()
+            .flatten()
+            .filter(|attestation| filter(attestation))
+            .cloned()
+            .collect()
     }
 
     /// Remove attestations which are too old to be included in a block.
@@ -749,6 +804,55 @@ mod release_tests {
         }
     }
 
+    /// Check that attestations are packed without delay once the deadline has passed.
+    #[test]
+    fn attestation_packing_deadline() {
+        let (ref mut state, ref keypairs, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let op_pool = OperationPool::new();
+
+        let slot = state.slot - 1;
+        let committees = state
+            .get_beacon_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(BeaconCommittee::into_owned)
+            .collect::<Vec<_>>();
+
+        for bc in &committees {
+            let att = signed_attestation(
+                &bc.committee,
+                bc.index,
+                keypairs,
+                0..bc.committee.len(),
+                slot,
+                state,
+                spec,
+                None,
+            );
+            op_pool
+                .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                .unwrap();
+        }
+
+        state.slot += spec.min_attestation_inclusion_delay;
+
+        let far_future = Instant::now() + std::time::Duration::from_secs(3600);
+        let (attestations, deadline_reached) = op_pool
+            .get_attestations_with_deadline(state, |_| true, Some(far_future), spec)
+            .expect("should have attestations");
+        assert!(!deadline_reached);
+        assert_eq!(attestations.len(), committees.len());
+
+        // A deadline which has already passed skips every attestation, rather than delaying the
+        // block.
+        let (attestations, deadline_reached) = op_pool
+            .get_attestations_with_deadline(state, |_| true, Some(Instant::now()), spec)
+            .expect("should pack attestations");
+        assert!(deadline_reached);
+        assert!(attestations.is_empty());
+    }
+
     #[test]
     fn attestation_rewards() {
         let small_step_size = 2;
@@ -1,3 +1,6 @@
+use std::cmp::Reverse;
+use std::time::Instant;
+
 /// Trait for types that we can compute a maximum cover for.
 ///
 /// Terminology:
@@ -40,9 +43,18 @@ impl<T> MaxCoverItem<T> {
 
 /// Compute an approximate maximum cover using a greedy algorithm.
 ///
+/// Once `deadline` has passed, the remaining space is filled with the remaining items of the
+/// highest score, without updating their covering sets for each selection. These items may
+/// overlap, but selecting them takes only `O(n log n)` time. Returns `true` alongside the cover
+/// if the deadline was reached.
+///
 /// * Time complexity: `O(limit * items_iter.len())`
 /// * Space complexity: `O(item_iter.len())`
-pub fn maximum_cover<I, T>(items_iter: I, limit: usize) -> Vec<T::Object>
+pub fn maximum_cover<I, T>(
+    items_iter: I,
+    limit: usize,
+    deadline: Option<Instant>,
+) -> (Vec<T::Object>, bool)
 where
     I: IntoIterator<Item = T>,
     T: MaxCover,
@@ -57,6 +69,19 @@ where
     let mut result = vec![];
 
     for _ in 0..limit {
+        if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            let mut remaining = all_items
+                .into_iter()
+                .filter(|x| x.available && x.item.score() != 0)
+                .map(|x| x.item)
+                .collect::<Vec<_>>();
+            remaining.sort_by_key(|item| Reverse(item.score()));
+
+            let space = limit - result.len();
+            result.extend(remaining.iter().take(space).map(MaxCover::object));
+            return (result, true);
+        }
+
         // Select the item with the maximum score.
         let (best_item, best_cover) = match all_items
             .iter_mut()
@@ -67,7 +92,7 @@ where
                 x.available = false;
                 (x.item.object(), x.item.covering_set().clone())
             }
-            None => return result,
+            None => return (result, false),
         };
 
         // Update the covering sets of the other items, for the inclusion of the selected item.
@@ -80,7 +105,7 @@ where
         result.push(best_item);
     }
 
-    result
+    (result, false)
 }
 
 #[cfg(test)]
@@ -126,14 +151,14 @@ mod test {
 
     #[test]
     fn zero_limit() {
-        let cover = maximum_cover(example_system(), 0);
+        let cover = maximum_cover(example_system(), 0, None).0;
         assert_eq!(cover.len(), 0);
     }
 
     #[test]
     fn one_limit() {
         let sets = example_system();
-        let cover = maximum_cover(sets.clone(), 1);
+        let cover = maximum_cover(sets.clone(), 1, None).0;
         assert_eq!(cover.len(), 1);
         assert_eq!(cover[0], sets[1]);
     }
@@ -143,13 +168,29 @@ mod test {
     fn exclude_zero_score() {
         let sets = example_system();
         for k in 2..10 {
-            let cover = maximum_cover(sets.clone(), k);
+            let cover = maximum_cover(sets.clone(), k, None).0;
             assert_eq!(cover.len(), 2);
             assert_eq!(cover[0], sets[1]);
             assert_eq!(cover[1], sets[0]);
         }
     }
 
+    #[test]
+    fn deadline_reached() {
+        let sets = example_system();
+
+        let (cover, deadline_reached) = maximum_cover(sets.clone(), 2, Some(Instant::now()));
+        assert!(deadline_reached);
+        // Without updating covering sets, the two identical sets of the greatest score are
+        // selected.
+        assert_eq!(cover, vec![sets[1].clone(), sets[2].clone()]);
+
+        let far_future = Instant::now() + std::time::Duration::from_secs(3600);
+        let (cover, deadline_reached) = maximum_cover(sets.clone(), 2, Some(far_future));
+        assert!(!deadline_reached);
+        assert_eq!(cover, maximum_cover(sets, 2, None).0);
+    }
+
     fn quality<T: Eq + Hash>(solution: &[HashSet<T>]) -> usize {
         solution.iter().map(HashSet::len).sum()
     }
@@ -167,7 +208,7 @@ mod test {
             HashSet::from_iter(vec![5, 6, 7, 8]),      // 4, 4*
             HashSet::from_iter(vec![0, 1, 2, 3, 4]),   // 5*
         ];
-        let cover = maximum_cover(sets, 3);
+        let cover = maximum_cover(sets, 3, None).0;
         assert_eq!(quality(&cover), 11);
     }
 
@@ -182,7 +223,7 @@ mod test {
             HashSet::from_iter(vec![1, 5, 6, 8]),
             HashSet::from_iter(vec![1, 7, 11, 19]),
         ];
-        let cover = maximum_cover(sets, 5);
+        let cover = maximum_cover(sets, 5, None).0;
         assert_eq!(quality(&cover), 19);
         assert_eq!(cover.len(), 5);
     }
@@ -0,0 +1,87 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use types::{Attestation, CommitteeIndex, EthSpec, Slot};
+
+/// The unsigned attestations most recently produced for the latest slot, keyed by committee index.
+///
+/// Validators in the same committee request identical attestations, so when producing a fresh
+/// attestation exceeds its deadline the one produced for an earlier request may be served instead.
+pub struct AttestationCache<E: EthSpec> {
+    inner: Mutex<Option<(Slot, HashMap<CommitteeIndex, Attestation<E>>)>>,
+}
+
+impl<E: EthSpec> Default for AttestationCache<E> {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(None),
+        }
+    }
+}
+
+impl<E: EthSpec> AttestationCache<E> {
+    /// Stores `attestation`, discarding any attestations from prior slots.
+    ///
+    /// Attestations from slots prior to the latest stored slot are ignored.
+    pub fn insert(&self, attestation: Attestation<E>) {
+        let slot = attestation.data.slot;
+        let mut inner = self.inner.lock();
+
+        match &mut *inner {
+            Some((cached_slot, attestations)) if *cached_slot == slot => {
+                attestations.insert(attestation.data.index, attestation);
+            }
+            Some((cached_slot, _)) if *cached_slot > slot => (),
+            _ => {
+                let mut attestations = HashMap::new();
+                attestations.insert(attestation.data.index, attestation);
+                *inner = Some((slot, attestations));
+            }
+        }
+    }
+
+    /// Returns the attestation stored for `slot` and `index`, if any.
+    pub fn get(&self, slot: Slot, index: CommitteeIndex) -> Option<Attestation<E>> {
+        self.inner
+            .lock()
+            .as_ref()
+            .filter(|(cached_slot, _)| *cached_slot == slot)
+            .and_then(|(_, attestations)| attestations.get(&index).cloned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::{AggregateSignature, AttestationData, BitList, MainnetEthSpec};
+
+    fn attestation(slot: u64, index: CommitteeIndex) -> Attestation<MainnetEthSpec> {
+        Attestation {
+            aggregation_bits: BitList::with_capacity(4).unwrap(),
+            data: AttestationData {
+                slot: Slot::new(slot),
+                index,
+                ..AttestationData::default()
+            },
+            signature: AggregateSignature::empty(),
+        }
+    }
+
+    #[test]
+    fn only_latest_slot_is_retained() {
+        let cache = AttestationCache::default();
+
+        cache.insert(attestation(1, 0));
+        cache.insert(attestation(1, 1));
+        assert_eq!(cache.get(Slot::new(1), 0), Some(attestation(1, 0)));
+        assert_eq!(cache.get(Slot::new(1), 1), Some(attestation(1, 1)));
+        assert_eq!(cache.get(Slot::new(1), 2), None);
+
+        cache.insert(attestation(2, 0));
+        assert_eq!(cache.get(Slot::new(1), 0), None);
+        assert_eq!(cache.get(Slot::new(2), 0), Some(attestation(2, 0)));
+
+        cache.insert(attestation(1, 1));
+        assert_eq!(cache.get(Slot::new(1), 1), None);
+        assert_eq!(cache.get(Slot::new(2), 0), Some(attestation(2, 0)));
+    }
+}
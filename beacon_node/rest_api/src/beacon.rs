@@ -1,24 +1,30 @@
+use crate::facade::{self, BlockId, StateId};
 use crate::helpers::*;
-use crate::validator::get_state_for_epoch;
 use crate::Context;
-use crate::{ApiError, UrlQuery};
+use crate::{parse_query, ApiError, UrlQuery};
 use beacon_chain::{
-    observed_operations::ObservationOutcome, BeaconChain, BeaconChainTypes, StateSkipConfig,
+    observed_operations::ObservationOutcome, recent_proposals::graffiti_to_string, BeaconChain,
+    BeaconChainTypes, StateSkipConfig,
 };
-use futures::executor::block_on;
 use hyper::body::Bytes;
 use hyper::{Body, Request};
 use rest_types::{
-    BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, StateResponse,
-    ValidatorRequest, ValidatorResponse,
+    BlockEvent, BlockHeaderResponse, BlockResponse, CanonicalHeadResponse, Committee,
+    CommitteesQuery, HeadBeaconBlock, HeadersQuery, Paginated, PoolAttestationsQuery,
+    PoolSubmissionResponse, RandaoMix, RandaoQuery, SlotOrRootQuery, SlotQuery, StateResponse,
+    ValidatorRequest, ValidatorResponse, ValidatorsQuery,
 };
+use serde::Serialize;
 use std::io::Write;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Receiver;
 
 use slog::error;
+use state_processing::VerifyOperation;
 use types::{
-    AttesterSlashing, BeaconState, EthSpec, Hash256, ProposerSlashing, PublicKeyBytes,
-    RelativeEpoch, SignedBeaconBlockHash, Slot,
+    Attestation, AttestationData, AttesterSlashing, BeaconState, EthSpec, Hash256,
+    ProposerSlashing, RelativeEpoch, SignedBeaconBlockHeader, Slot,
 };
 
 /// Returns a summary of the head of the beacon chain.
@@ -26,31 +32,36 @@ pub fn get_head<T: BeaconChainTypes>(
     ctx: Arc<Context<T>>,
 ) -> Result<CanonicalHeadResponse, ApiError> {
     let beacon_chain = &ctx.beacon_chain;
-    let chain_head = beacon_chain.head()?;
-
-    Ok(CanonicalHeadResponse {
-        slot: chain_head.beacon_state.slot,
-        block_root: chain_head.beacon_block_root,
-        state_root: chain_head.beacon_state_root,
-        finalized_slot: chain_head
-            .beacon_state
-            .finalized_checkpoint
-            .epoch
-            .start_slot(T::EthSpec::slots_per_epoch()),
-        finalized_block_root: chain_head.beacon_state.finalized_checkpoint.root,
-        justified_slot: chain_head
-            .beacon_state
-            .current_justified_checkpoint
-            .epoch
-            .start_slot(T::EthSpec::slots_per_epoch()),
-        justified_block_root: chain_head.beacon_state.current_justified_checkpoint.root,
-        previous_justified_slot: chain_head
-            .beacon_state
-            .previous_justified_checkpoint
-            .epoch
-            .start_slot(T::EthSpec::slots_per_epoch()),
-        previous_justified_block_root: chain_head.beacon_state.previous_justified_checkpoint.root,
-    })
+    beacon_chain
+        .with_head(|chain_head| {
+            Ok(CanonicalHeadResponse {
+                slot: chain_head.beacon_state.slot,
+                block_root: chain_head.beacon_block_root,
+                state_root: chain_head.beacon_state_root,
+                finalized_slot: chain_head
+                    .beacon_state
+                    .finalized_checkpoint
+                    .epoch
+                    .start_slot(T::EthSpec::slots_per_epoch()),
+                finalized_block_root: chain_head.beacon_state.finalized_checkpoint.root,
+                justified_slot: chain_head
+                    .beacon_state
+                    .current_justified_checkpoint
+                    .epoch
+                    .start_slot(T::EthSpec::slots_per_epoch()),
+                justified_block_root: chain_head.beacon_state.current_justified_checkpoint.root,
+                previous_justified_slot: chain_head
+                    .beacon_state
+                    .previous_justified_checkpoint
+                    .epoch
+                    .start_slot(T::EthSpec::slots_per_epoch()),
+                previous_justified_block_root: chain_head
+                    .beacon_state
+                    .previous_justified_checkpoint
+                    .root,
+            })
+        })
+        .map_err(Into::into)
 }
 
 /// Return the list of heads of the beacon chain.
@@ -70,36 +81,19 @@ pub fn get_block<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<BlockResponse<T::EthSpec>, ApiError> {
-    let beacon_chain = &ctx.beacon_chain;
-    let query_params = ["root", "slot"];
-    let (key, value) = UrlQuery::from_request(&req)?.first_of(&query_params)?;
-
-    let block_root = match (key.as_ref(), value) {
-        ("slot", value) => {
-            let target = parse_slot(&value)?;
-
-            block_root_at_slot(beacon_chain, target)?.ok_or_else(|| {
-                ApiError::NotFound(format!(
-                    "Unable to find SignedBeaconBlock for slot {:?}",
-                    target
-                ))
-            })?
+    let query: SlotOrRootQuery = parse_query(&req)?;
+
+    let block_id = match (query.slot, query.root) {
+        (Some(slot), None) => BlockId::Slot(slot),
+        (None, Some(root)) => BlockId::Root(root),
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Exactly one of the slot or root query parameters is required".into(),
+            ))
         }
-        ("root", value) => parse_root(&value)?,
-        _ => return Err(ApiError::ServerError("Unexpected query parameter".into())),
     };
 
-    let block = beacon_chain.store.get_block(&block_root)?.ok_or_else(|| {
-        ApiError::NotFound(format!(
-            "Unable to find SignedBeaconBlock for root {:?}",
-            block_root
-        ))
-    })?;
-
-    Ok(BlockResponse {
-        root: block_root,
-        beacon_block: block,
-    })
+    facade::block(&ctx.beacon_chain, block_id)
 }
 
 /// HTTP handler to return a `SignedBeaconBlock` root at a given `slot`.
@@ -107,8 +101,7 @@ pub fn get_block_root<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Hash256, ApiError> {
-    let slot_string = UrlQuery::from_request(&req)?.only_one("slot")?;
-    let target = parse_slot(&slot_string)?;
+    let target = parse_query::<SlotQuery, _>(&req)?.slot;
 
     block_root_at_slot(&ctx.beacon_chain, target)?.ok_or_else(|| {
         ApiError::NotFound(format!(
@@ -118,38 +111,154 @@ pub fn get_block_root<T: BeaconChainTypes>(
     })
 }
 
-fn make_sse_response_chunk(new_head_hash: SignedBeaconBlockHash) -> std::io::Result<Bytes> {
+/// HTTP handler to return the header of the canonical block at the given `slot`, or the head
+/// block if no `slot` is given.
+///
+/// The result is empty if there is no block at `slot` or if it does not match `parent_root`.
+pub fn get_headers<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<Vec<BlockHeaderResponse>, ApiError> {
+    let beacon_chain = &ctx.beacon_chain;
+    let query: HeadersQuery = parse_query(&req)?;
+
+    let block_root = match query.slot {
+        Some(slot) => block_root_at_slot(beacon_chain, slot)?,
+        None => Some(beacon_chain.head_info()?.block_root),
+    };
+
+    let block = match block_root {
+        Some(root) => beacon_chain
+            .store
+            .get_block(&root)?
+            .map(|block| (root, block)),
+        None => None,
+    };
+
+    Ok(block
+        .into_iter()
+        // Skipped slots return the root of the prior block, which is not at `slot`.
+        .filter(|(_, block)| query.slot.map_or(true, |slot| block.slot() == slot))
+        .filter(|(_, block)| {
+            query
+                .parent_root
+                .map_or(true, |parent_root| block.parent_root() == parent_root)
+        })
+        .map(|(root, block)| BlockHeaderResponse {
+            root,
+            canonical: true,
+            header: SignedBeaconBlockHeader {
+                message: block.message.block_header(),
+                signature: block.signature,
+            },
+        })
+        .collect())
+}
+
+fn make_sse_response_chunk(new_head_root: Hash256) -> std::io::Result<Bytes> {
     let mut buffer = Vec::new();
     {
         let mut sse_message = uhttp_sse::SseMessage::new(&mut buffer);
-        let untyped_hash: Hash256 = new_head_hash.into();
-        write!(sse_message.data()?, "{:?}", untyped_hash)?;
+        write!(sse_message.data()?, "{:?}", new_head_root)?;
     }
     let bytes: Bytes = buffer.into();
     Ok(bytes)
 }
 
-pub fn stream_forks<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Body, ApiError> {
-    let mut events = ctx.events.lock().add_rx();
+fn make_sse_event_chunk<D: Serialize>(event: &str, data: &D) -> std::io::Result<Bytes> {
+    let data = serde_json::to_string(data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut buffer = Vec::new();
+    {
+        let mut sse_message = uhttp_sse::SseMessage::new(&mut buffer);
+        write!(sse_message.event()?, "{}", event)?;
+        write!(sse_message.data()?, "{}", data)?;
+    }
+    let bytes: Bytes = buffer.into();
+    Ok(bytes)
+}
+
+/// Spawns a task which sends each notification from `notifications` to the returned body as a
+/// server-sent event, encoded by `to_chunk`.
+fn stream_notifications<T, N, F>(
+    ctx: &Context<T>,
+    mut notifications: Receiver<N>,
+    to_chunk: F,
+    name: &'static str,
+) -> Body
+where
+    T: BeaconChainTypes,
+    N: Send + 'static,
+    F: Fn(N) -> std::io::Result<Bytes> + Send + 'static,
+{
     let (mut sender, body) = Body::channel();
-    std::thread::spawn(move || {
-        while let Ok(new_head_hash) = events.recv() {
-            let chunk = match make_sse_response_chunk(new_head_hash) {
-                Ok(chunk) => chunk,
-                Err(e) => {
-                    error!(ctx.log, "Failed to make SSE chunk"; "error" => e.to_string());
-                    sender.abort();
-                    break;
+    let log = ctx.log.clone();
+    ctx.executor.spawn(
+        async move {
+            while let Some(notification) = notifications.recv().await {
+                let chunk = match to_chunk(notification) {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        error!(log, "Failed to make SSE chunk"; "error" => e.to_string());
+                        sender.abort();
+                        break;
+                    }
+                };
+                match sender.send_data(chunk).await {
+                    Err(e) if e.is_closed() => break,
+                    Err(e) => error!(log, "Couldn't stream piece {:?}", e),
+                    Ok(_) => (),
                 }
-            };
-            match block_on(sender.send_data(chunk)) {
-                Err(e) if e.is_closed() => break,
-                Err(e) => error!(ctx.log, "Couldn't stream piece {:?}", e),
-                Ok(_) => (),
             }
-        }
-    });
-    Ok(body)
+        },
+        name,
+    );
+    body
+}
+
+/// Streams the root of each new head block as a server-sent event.
+///
+/// Head changes are dropped if the client falls more than an epoch behind.
+pub fn stream_forks<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Body, ApiError> {
+    let head_changes = ctx
+        .beacon_chain
+        .hooks
+        .subscribe_head(T::EthSpec::slots_per_epoch() as usize);
+
+    Ok(stream_notifications(
+        &ctx,
+        head_changes,
+        |head_change| make_sse_response_chunk(head_change.block_root),
+        "fork_stream",
+    ))
+}
+
+/// Streams each imported block as a server-sent `block` event, whose data is a JSON `BlockEvent`.
+///
+/// Blocks are dropped if the client falls more than an epoch behind.
+pub fn stream_blocks<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Body, ApiError> {
+    let block_imports = ctx
+        .beacon_chain
+        .hooks
+        .subscribe_block(T::EthSpec::slots_per_epoch() as usize);
+    let as_millis = |delay: Option<Duration>| delay.map(|delay| delay.as_millis() as u64);
+
+    Ok(stream_notifications(
+        &ctx,
+        block_imports,
+        move |block_import| {
+            let event = BlockEvent {
+                slot: block_import.slot,
+                block_root: block_import.block_root,
+                proposer_index: block_import.proposer_index,
+                graffiti: graffiti_to_string(&block_import.graffiti),
+                observed_delay_ms: as_millis(block_import.observed_delay),
+                imported_delay_ms: as_millis(block_import.imported_delay),
+            };
+            make_sse_event_chunk("block", &event)
+        },
+        "block_stream",
+    ))
 }
 
 /// HTTP handler to which accepts a query string of a list of validator pubkeys and maps it to a
@@ -175,7 +284,12 @@ pub fn get_validators<T: BeaconChainTypes>(
         None
     };
 
-    validator_responses_by_pubkey(&ctx.beacon_chain, state_root_opt, validator_pubkeys)
+    facade::validators(
+        &ctx.beacon_chain,
+        &ctx.config,
+        state_root_opt,
+        validator_pubkeys,
+    )
 }
 
 /// HTTP handler to return all validators, each as a `ValidatorResponse`.
@@ -183,21 +297,23 @@ pub fn get_all_validators<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Vec<ValidatorResponse>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
+    let query: ValidatorsQuery = parse_query(&req)?;
 
-    let state_root_opt = if let Some((_key, value)) = query.first_of_opt(&["state_root"]) {
-        Some(parse_root(&value)?)
-    } else {
-        None
-    };
-
-    let mut state = get_state_from_root_opt(&ctx.beacon_chain, state_root_opt)?;
+    let mut state =
+        facade::state_at_root_or_head(&ctx.beacon_chain, &ctx.config, query.state_root)?;
     state.update_pubkey_cache()?;
 
-    state
+    let epoch = state.current_epoch();
+    let far_future_epoch = ctx.beacon_chain.spec.far_future_epoch;
+    let validators = state
         .validators
         .iter()
-        .map(|validator| validator_response_by_pubkey(&state, validator.pubkey.clone()))
+        .filter(|validator| query.includes(validator, epoch, far_future_epoch));
+
+    query
+        .paginate(validators)
+        .into_iter()
+        .map(|validator| facade::validator(&state, validator.pubkey.clone()))
         .collect::<Result<Vec<_>, _>>()
 }
 
@@ -206,22 +322,22 @@ pub fn get_active_validators<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Vec<ValidatorResponse>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
-
-    let state_root_opt = if let Some((_key, value)) = query.first_of_opt(&["state_root"]) {
-        Some(parse_root(&value)?)
-    } else {
-        None
-    };
+    let query: ValidatorsQuery = parse_query(&req)?;
 
-    let mut state = get_state_from_root_opt(&ctx.beacon_chain, state_root_opt)?;
+    let mut state =
+        facade::state_at_root_or_head(&ctx.beacon_chain, &ctx.config, query.state_root)?;
     state.update_pubkey_cache()?;
 
-    state
-        .validators
-        .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()))
-        .map(|validator| validator_response_by_pubkey(&state, validator.pubkey.clone()))
+    let epoch = state.current_epoch();
+    let far_future_epoch = ctx.beacon_chain.spec.far_future_epoch;
+    let active_validators = state.validators.iter().filter(|validator| {
+        validator.is_active_at(epoch) && query.includes(validator, epoch, far_future_epoch)
+    });
+
+    query
+        .paginate(active_validators)
+        .into_iter()
+        .map(|validator| facade::validator(&state, validator.pubkey.clone()))
         .collect::<Result<Vec<_>, _>>()
 }
 
@@ -242,102 +358,63 @@ pub fn post_validators<T: BeaconChainTypes>(
             ))
         })
         .and_then(|bulk_request| {
-            validator_responses_by_pubkey(
+            facade::validators(
                 &ctx.beacon_chain,
+                &ctx.config,
                 bulk_request.state_root,
                 bulk_request.pubkeys,
             )
         })
 }
 
-/// Returns either the state given by `state_root_opt`, or the canonical head state if it is
-/// `None`.
-fn get_state_from_root_opt<T: BeaconChainTypes>(
-    beacon_chain: &BeaconChain<T>,
-    state_root_opt: Option<Hash256>,
-) -> Result<BeaconState<T::EthSpec>, ApiError> {
-    if let Some(state_root) = state_root_opt {
-        beacon_chain
-            .get_state(&state_root, None)
-            .map_err(|e| {
-                ApiError::ServerError(format!(
-                    "Database error when reading state root {}: {:?}",
-                    state_root, e
-                ))
-            })?
-            .ok_or_else(|| ApiError::NotFound(format!("No state exists with root: {}", state_root)))
-    } else {
-        Ok(beacon_chain.head()?.beacon_state)
-    }
-}
-
-/// Maps a vec of `validator_pubkey` to a vec of `ValidatorResponse`, using the state at the given
-/// `state_root`. If `state_root.is_none()`, uses the canonial head state.
-fn validator_responses_by_pubkey<T: BeaconChainTypes>(
-    beacon_chain: &BeaconChain<T>,
-    state_root_opt: Option<Hash256>,
-    validator_pubkeys: Vec<PublicKeyBytes>,
-) -> Result<Vec<ValidatorResponse>, ApiError> {
-    let mut state = get_state_from_root_opt(beacon_chain, state_root_opt)?;
-    state.update_pubkey_cache()?;
-
-    validator_pubkeys
-        .into_iter()
-        .map(|validator_pubkey| validator_response_by_pubkey(&state, validator_pubkey))
-        .collect::<Result<Vec<_>, ApiError>>()
-}
-
-/// Maps a `validator_pubkey` to a `ValidatorResponse`, using the given state.
+/// HTTP handler to return the RANDAO mix of an epoch, using the state at the given `state_root`.
 ///
-/// The provided `state` must have a fully up-to-date pubkey cache.
-fn validator_response_by_pubkey<E: EthSpec>(
-    state: &BeaconState<E>,
-    validator_pubkey: PublicKeyBytes,
-) -> Result<ValidatorResponse, ApiError> {
-    let validator_index_opt = state
-        .get_validator_index(&validator_pubkey)
-        .map_err(|e| ApiError::ServerError(format!("Unable to read pubkey cache: {:?}", e)))?;
-
-    if let Some(validator_index) = validator_index_opt {
-        let balance = state.balances.get(validator_index).ok_or_else(|| {
-            ApiError::ServerError(format!("Invalid balances index: {:?}", validator_index))
-        })?;
+/// Only epochs within `EPOCHS_PER_HISTORICAL_VECTOR` of the state's current epoch, and not after
+/// it, are available.
+pub fn get_randao_mix<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<RandaoMix, ApiError> {
+    let query: RandaoQuery = parse_query(&req)?;
+    let state = facade::state_at_root_or_head(&ctx.beacon_chain, &ctx.config, query.state_root)?;
+    let epoch = query.epoch.unwrap_or_else(|| state.current_epoch());
 
-        let validator = state
-            .validators
-            .get(validator_index)
-            .ok_or_else(|| {
-                ApiError::ServerError(format!("Invalid validator index: {:?}", validator_index))
-            })?
-            .clone();
-
-        Ok(ValidatorResponse {
-            pubkey: validator_pubkey,
-            validator_index: Some(validator_index),
-            balance: Some(*balance),
-            validator: Some(validator),
-        })
-    } else {
-        Ok(ValidatorResponse {
-            pubkey: validator_pubkey,
-            validator_index: None,
-            balance: None,
-            validator: None,
+    state
+        .get_randao_mix(epoch)
+        .map(|randao| RandaoMix { randao: *randao })
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to get the randao mix for epoch {}: {:?}",
+                epoch, e
+            ))
         })
-    }
 }
 
-/// HTTP handler
+/// HTTP handler to return the committees of an epoch, optionally filtered by slot and index.
 pub fn get_committees<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Vec<Committee>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
+    let query: CommitteesQuery = parse_query(&req)?;
+    let epoch = query.epoch;
+
+    if let Some(slot) = query.slot {
+        if slot.epoch(T::EthSpec::slots_per_epoch()) != epoch {
+            return Err(ApiError::BadRequest(format!(
+                "Slot {} is not in epoch {}",
+                slot, epoch
+            )));
+        }
+    }
 
-    let epoch = query.epoch()?;
+    check_state_served(
+        &ctx.beacon_chain,
+        ctx.config.mode,
+        epoch.start_slot(T::EthSpec::slots_per_epoch()),
+    )?;
 
     let mut state =
-        get_state_for_epoch(&ctx.beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+        facade::state_for_epoch(&ctx.beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
 
     let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch).map_err(|e| {
         ApiError::ServerError(format!("Failed to get state suitable for epoch: {:?}", e))
@@ -351,6 +428,8 @@ pub fn get_committees<T: BeaconChainTypes>(
         .get_beacon_committees_at_epoch(relative_epoch)
         .map_err(|e| ApiError::ServerError(format!("Unable to get all committees: {:?}", e)))?
         .into_iter()
+        .filter(|c| query.slot.map_or(true, |slot| c.slot == slot))
+        .filter(|c| query.index.map_or(true, |index| c.index == index))
         .map(|c| Committee {
             slot: c.slot,
             index: c.index,
@@ -359,6 +438,38 @@ pub fn get_committees<T: BeaconChainTypes>(
         .collect::<Vec<_>>())
 }
 
+/// HTTP handler to return the attestations in the operation pool and the naive aggregation pool,
+/// optionally filtered by slot and committee index.
+pub fn get_pool_attestations<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<Vec<Attestation<T::EthSpec>>, ApiError> {
+    let query: PoolAttestationsQuery = parse_query(&req)?;
+
+    let matches_query = |data: &AttestationData| {
+        query.slot.map_or(true, |slot| data.slot == slot)
+            && query
+                .committee_index
+                .map_or(true, |index| data.index == index)
+    };
+
+    let mut attestations = ctx
+        .beacon_chain
+        .op_pool
+        .get_filtered_attestations(|attestation| matches_query(&attestation.data));
+
+    attestations.extend(
+        ctx.beacon_chain
+            .naive_aggregation_pool
+            .read()
+            .iter()
+            .filter(|attestation| matches_query(&attestation.data))
+            .cloned(),
+    );
+
+    Ok(attestations)
+}
+
 /// HTTP handler to return a `BeaconState` at a given `root` or `slot`.
 ///
 /// Will not return a state if the request slot is in the future. Will return states higher than
@@ -367,43 +478,21 @@ pub fn get_state<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<StateResponse<T::EthSpec>, ApiError> {
-    let head_state = ctx.beacon_chain.head()?.beacon_state;
-
-    let (key, value) = match UrlQuery::from_request(&req) {
-        Ok(query) => {
-            // We have *some* parameters, just check them.
-            let query_params = ["root", "slot"];
-            query.first_of(&query_params)?
-        }
-        Err(ApiError::BadRequest(_)) => {
-            // No parameters provided at all, use current slot.
-            (String::from("slot"), head_state.slot.to_string())
-        }
-        Err(e) => {
-            return Err(e);
-        }
-    };
-
-    let (root, state): (Hash256, BeaconState<T::EthSpec>) = match (key.as_ref(), value) {
-        ("slot", value) => state_at_slot(&ctx.beacon_chain, parse_slot(&value)?)?,
-        ("root", value) => {
-            let root = &parse_root(&value)?;
-
-            let state = ctx
-                .beacon_chain
-                .store
-                .get_state(root, None)?
-                .ok_or_else(|| ApiError::NotFound(format!("No state for root: {:?}", root)))?;
-
-            (*root, state)
+    let query: SlotOrRootQuery = parse_query(&req)?;
+
+    let state_id = match (query.slot, query.root) {
+        (Some(slot), None) => StateId::Slot(slot),
+        (None, Some(root)) => StateId::Root(root),
+        // No parameters provided at all, use the head slot.
+        (None, None) => StateId::Head,
+        (Some(_), Some(_)) => {
+            return Err(ApiError::BadRequest(
+                "Only one of the slot or root query parameters may be given".into(),
+            ))
         }
-        _ => return Err(ApiError::ServerError("Unexpected query parameter".into())),
     };
 
-    Ok(StateResponse {
-        root,
-        beacon_state: state,
-    })
+    facade::state(&ctx.beacon_chain, &ctx.config, state_id)
 }
 
 /// HTTP handler to return a `BeaconState` root at a given `slot`.
@@ -414,8 +503,7 @@ pub fn get_state_root<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Hash256, ApiError> {
-    let slot_string = UrlQuery::from_request(&req)?.only_one("slot")?;
-    let slot = parse_slot(&slot_string)?;
+    let slot = parse_query::<SlotQuery, _>(&req)?.slot;
 
     state_root_at_slot(&ctx.beacon_chain, slot, StateSkipConfig::WithStateRoots)
 }
@@ -430,52 +518,63 @@ pub fn get_genesis_state<T: BeaconChainTypes>(
     state_at_slot(&ctx.beacon_chain, Slot::new(0)).map(|(_root, state)| state)
 }
 
+/// HTTP handler to import a `ProposerSlashing` into the operation pool.
+///
+/// A slashing for a validator with a known slashing is accepted if it is valid, with
+/// `already_known` set in the response.
 pub fn proposer_slashing<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
-) -> Result<bool, ApiError> {
-    let body = req.into_body();
+) -> Result<PoolSubmissionResponse, ApiError> {
+    let proposer_slashing =
+        decode_body::<ProposerSlashing>(&req, ctx.config.max_body_size, "ProposerSlashing")?;
+
+    if ctx.beacon_chain.eth1_chain.is_none() {
+        return Err(ApiError::BadRequest(
+            "Cannot insert proposer slashing on node without Eth1 connection.".to_string(),
+        ));
+    }
 
-    serde_json::from_slice::<ProposerSlashing>(&body)
-        .map_err(|e| format!("Unable to parse JSON into ProposerSlashing: {:?}", e))
-        .and_then(move |proposer_slashing| {
-            if ctx.beacon_chain.eth1_chain.is_some() {
-                let obs_outcome = ctx
-                    .beacon_chain
-                    .verify_proposer_slashing_for_gossip(proposer_slashing)
-                    .map_err(|e| format!("Error while verifying proposer slashing: {:?}", e))?;
-                if let ObservationOutcome::New(verified_proposer_slashing) = obs_outcome {
-                    ctx.beacon_chain
-                        .import_proposer_slashing(verified_proposer_slashing);
-                    Ok(())
-                } else {
-                    Err("Proposer slashing for that validator index already known".into())
-                }
-            } else {
-                Err("Cannot insert proposer slashing on node without Eth1 connection.".to_string())
-            }
+    let obs_outcome = ctx
+        .beacon_chain
+        .verify_proposer_slashing_for_gossip(proposer_slashing.clone())
+        .map_err(|e| {
+            ApiError::BadRequest(format!("Error while verifying proposer slashing: {:?}", e))
+        })?;
+    if let ObservationOutcome::New(verified_proposer_slashing) = obs_outcome {
+        ctx.beacon_chain
+            .import_proposer_slashing(verified_proposer_slashing);
+        Ok(PoolSubmissionResponse {
+            already_known: false,
         })
-        .map_err(ApiError::BadRequest)?;
-
-    Ok(true)
+    } else {
+        verify_known_operation(&ctx.beacon_chain, proposer_slashing).map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Proposer slashing for that validator index already known, \
+                 however this slashing is invalid: {}",
+                e
+            ))
+        })?;
+        Ok(PoolSubmissionResponse {
+            already_known: true,
+        })
+    }
 }
 
+/// HTTP handler to import an `AttesterSlashing` into the operation pool.
+///
+/// A slashing which only covers validators with known slashings is accepted if it is
+/// valid, with `already_known` set in the response.
 pub fn attester_slashing<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
-) -> Result<bool, ApiError> {
-    let body = req.into_body();
-    serde_json::from_slice::<AttesterSlashing<T::EthSpec>>(&body)
-        .map_err(|e| {
-            ApiError::BadRequest(format!(
-                "Unable to parse JSON into AttesterSlashing: {:?}",
-                e
-            ))
-        })
-        .and_then(move |attester_slashing| {
+) -> Result<PoolSubmissionResponse, ApiError> {
+    let max_body_size = ctx.config.max_body_size;
+    decode_body::<AttesterSlashing<T::EthSpec>>(&req, max_body_size, "AttesterSlashing").and_then(
+        move |attester_slashing| {
             if ctx.beacon_chain.eth1_chain.is_some() {
                 ctx.beacon_chain
-                    .verify_attester_slashing_for_gossip(attester_slashing)
+                    .verify_attester_slashing_for_gossip(attester_slashing.clone())
                     .map_err(|e| format!("Error while verifying attester slashing: {:?}", e))
                     .and_then(|outcome| {
                         if let ObservationOutcome::New(verified_attester_slashing) = outcome {
@@ -484,8 +583,21 @@ pub fn attester_slashing<T: BeaconChainTypes>(
                                 .map_err(|e| {
                                     format!("Error while importing attester slashing: {:?}", e)
                                 })
+                                .map(|()| PoolSubmissionResponse {
+                                    already_known: false,
+                                })
                         } else {
-                            Err("Attester slashing only covers already slashed indices".to_string())
+                            verify_known_operation(&ctx.beacon_chain, attester_slashing)
+                                .map_err(|e| {
+                                    format!(
+                                        "Attester slashing only covers already slashed indices, \
+                                         however this slashing is invalid: {}",
+                                        e
+                                    )
+                                })
+                                .map(|()| PoolSubmissionResponse {
+                                    already_known: true,
+                                })
                         }
                     })
                     .map_err(ApiError::BadRequest)
@@ -494,7 +606,23 @@ pub fn attester_slashing<T: BeaconChainTypes>(
                     "Cannot insert attester slashing on node without Eth1 connection.".to_string(),
                 ))
             }
-        })?;
+        },
+    )
+}
 
-    Ok(true)
+/// Verifies an operation which `ObservedOperations` reported as `AlreadyKnown` without verifying
+/// it, so that an invalid operation is not accepted as a duplicate of a valid one.
+fn verify_known_operation<T: BeaconChainTypes, Op: VerifyOperation<T::EthSpec>>(
+    beacon_chain: &BeaconChain<T>,
+    op: Op,
+) -> Result<(), String>
+where
+    Op::Error: std::fmt::Debug,
+{
+    let state = beacon_chain
+        .wall_clock_state()
+        .map_err(|e| format!("{:?}", e))?;
+    op.validate(&state, &beacon_chain.spec)
+        .map(|_| ())
+        .map_err(|e| format!("{:?}", e))
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use store::NodeMode;
 
 /// Defines the encoding for the API.
 #[derive(Clone, Serialize, Deserialize, Copy)]
@@ -29,6 +31,11 @@ impl From<&str> for ApiEncodingFormat {
     }
 }
 
+/// The default maximum size of a request body, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 4 * 1_048_576;
+/// The default maximum size of a request body containing a block, in bytes.
+pub const DEFAULT_MAX_BLOCK_BODY_SIZE: usize = 16 * 1_048_576;
+
 /// HTTP REST API Configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -41,6 +48,32 @@ pub struct Config {
     /// If something else than "", a 'Access-Control-Allow-Origin' header will be present in
     /// responses.  Put *, to allow any origin.
     pub allow_origin: String,
+    /// The maximum number of epochs prior to the current epoch for which validator duties will be
+    /// computed from historical states.
+    pub duties_max_lookback_epochs: u64,
+    /// The maximum number of requests to expensive endpoints (e.g., state downloads) which will be
+    /// served concurrently.
+    pub max_concurrent_heavy_requests: usize,
+    /// Permit blocks to be produced without verifying the randao reveal, for testing.
+    pub allow_skip_randao_verification: bool,
+    /// Serve duties and produce blocks and attestations whilst the node is syncing far behind
+    /// the present slot, for testnets.
+    pub allow_unsynced: bool,
+    /// The maximum size of a request body, in bytes. Larger requests are rejected with a 413, and
+    /// compressed bodies which are larger once decompressed with a 400.
+    pub max_body_size: usize,
+    /// As per `max_body_size`, but for requests containing a block.
+    pub max_block_body_size: usize,
+    /// The mode of the node, which determines the historical states which are served.
+    pub mode: NodeMode,
+    /// Glob patterns of the routes which are disabled, e.g. `lighthouse/debug/*`. Requests to
+    /// these routes are rejected with a 403.
+    pub disabled_routes: Vec<String>,
+    /// The directory in which database backups requested via `/advanced/database/backup` are
+    /// written. If `None`, backups cannot be requested.
+    pub database_backup_dir: Option<PathBuf>,
+    /// Permit trusted peers to be added and removed via `/lighthouse/admin/trusted_peers`.
+    pub allow_trusted_peer_updates: bool,
 }
 
 impl Default for Config {
@@ -50,6 +83,78 @@ impl Default for Config {
             listen_address: Ipv4Addr::new(127, 0, 0, 1),
             port: 5052,
             allow_origin: "".to_string(),
+            duties_max_lookback_epochs: 1024,
+            max_concurrent_heavy_requests: 4,
+            allow_skip_randao_verification: false,
+            allow_unsynced: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_block_body_size: DEFAULT_MAX_BLOCK_BODY_SIZE,
+            mode: NodeMode::default(),
+            disabled_routes: vec![],
+            database_backup_dir: None,
+            allow_trusted_peer_updates: false,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the first of the `disabled_routes` patterns which matches `path`, if any.
+    pub fn disabled_route_pattern(&self, path: &str) -> Option<&str> {
+        self.disabled_routes
+            .iter()
+            .find(|pattern| route_matches(pattern, path))
+            .map(String::as_str)
+    }
+}
+
+/// Returns `true` if the route `pattern` matches `path`, ignoring any leading or trailing `/`.
+///
+/// A `*` segment matches any single segment of the path, except as the final segment of the
+/// pattern, where it matches all the remaining segments.
+pub fn route_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_matches('/').split('/').collect::<Vec<_>>();
+    let path = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    for (i, pattern_segment) in pattern.iter().enumerate() {
+        match path.get(i) {
+            Some(_) if *pattern_segment == "*" && i == pattern.len() - 1 => return true,
+            Some(path_segment) if *pattern_segment == "*" || pattern_segment == path_segment => {}
+            _ => return false,
         }
     }
+
+    pattern.len() == path.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_patterns() {
+        assert!(route_matches("beacon/state", "/beacon/state"));
+        assert!(route_matches("/beacon/state/", "/beacon/state"));
+        assert!(!route_matches("beacon/state", "/beacon/state_root"));
+        assert!(!route_matches("beacon/state", "/beacon/state/extra"));
+
+        assert!(route_matches(
+            "lighthouse/*/caches",
+            "/lighthouse/debug/caches"
+        ));
+        assert!(!route_matches(
+            "lighthouse/*/caches",
+            "/lighthouse/debug/caches/drop"
+        ));
+
+        assert!(route_matches(
+            "lighthouse/debug/*",
+            "/lighthouse/debug/caches"
+        ));
+        assert!(route_matches(
+            "lighthouse/debug/*",
+            "/lighthouse/debug/caches/drop"
+        ));
+        assert!(!route_matches("lighthouse/debug/*", "/lighthouse/debug"));
+        assert!(!route_matches("lighthouse/debug/*", "/lighthouse/syncing"));
+    }
 }
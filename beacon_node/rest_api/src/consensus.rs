@@ -1,8 +1,8 @@
 use crate::helpers::*;
-use crate::{ApiError, Context, UrlQuery};
+use crate::{parse_query, ApiError, Context};
 use beacon_chain::BeaconChainTypes;
 use hyper::Request;
-use rest_types::{IndividualVotesRequest, IndividualVotesResponse};
+use rest_types::{EpochQuery, IndividualVotesRequest, IndividualVotesResponse};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use state_processing::per_epoch_processing::{TotalBalances, ValidatorStatuses};
@@ -52,12 +52,11 @@ pub fn get_vote_count<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<VoteCount, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
-
-    let epoch = query.epoch()?;
+    let epoch = parse_query::<EpochQuery, _>(&req)?.epoch;
     // This is the last slot of the given epoch (one prior to the first slot of the next epoch).
     let target_slot = (epoch + 1).start_slot(T::EthSpec::slots_per_epoch()) - 1;
 
+    check_state_served(&ctx.beacon_chain, ctx.config.mode, target_slot)?;
     let (_root, state) = state_at_slot(&ctx.beacon_chain, target_slot)?;
     let spec = &ctx.beacon_chain.spec;
 
@@ -86,6 +85,7 @@ pub fn post_individual_votes<T: BeaconChainTypes>(
             // This is the last slot of the given epoch (one prior to the first slot of the next epoch).
             let target_slot = (epoch + 1).start_slot(T::EthSpec::slots_per_epoch()) - 1;
 
+            check_state_served(&ctx.beacon_chain, ctx.config.mode, target_slot)?;
             let (_root, mut state) = state_at_slot(&ctx.beacon_chain, target_slot)?;
             let spec = &ctx.beacon_chain.spec;
 
@@ -0,0 +1,458 @@
+//! Typed entry points to the core logic behind the HTTP API, independent of `hyper` and the
+//! router.
+//!
+//! The HTTP handlers in this crate only parse requests and delegate to these functions, so
+//! embedders can call them in-process or mount them in a server of their own and get the same
+//! behaviour as the `--http` server, including the restrictions of the node's `--mode` and the
+//! `duties_max_lookback_epochs` limit.
+//!
+//! These functions read from the database and perform state processing, so they block. Async
+//! callers should run them on a blocking thread (e.g., with `tokio::task::spawn_blocking`), as the
+//! router does.
+
+use crate::helpers::{
+    block_root_at_slot, check_state_root_served, check_state_served, state_at_slot,
+};
+use crate::{ApiError, Config};
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, StateSkipConfig};
+use bls::PublicKeyBytes;
+use rest_types::{
+    BlockResponse, ExtraFields, StateResponse, ValidatorDutyBytes, ValidatorResponse,
+};
+use types::{
+    BeaconState, CloneConfig, Epoch, EthSpec, Hash256, RelativeEpoch, SelectionProof, Slot,
+};
+
+/// Identifies a `SignedBeaconBlock` in the canonical chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockId {
+    Head,
+    Slot(Slot),
+    Root(Hash256),
+}
+
+/// Identifies a `BeaconState` in the canonical chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateId {
+    Head,
+    Slot(Slot),
+    Root(Hash256),
+}
+
+/// Selects the validators to return duties for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DutiesSelection {
+    /// Every validator in the state.
+    All,
+    /// Every validator which is active in the epoch of the state.
+    Active,
+    /// The validators with the given public keys, including those which are unknown.
+    Pubkeys(Vec<PublicKeyBytes>),
+}
+
+/// Returns the block identified by `block_id`, along with its root.
+pub fn block<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block_id: BlockId,
+) -> Result<BlockResponse<T::EthSpec>, ApiError> {
+    let block_root = match block_id {
+        BlockId::Head => beacon_chain.head_info()?.block_root,
+        BlockId::Slot(slot) => block_root_at_slot(beacon_chain, slot)?.ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "Unable to find SignedBeaconBlock for slot {:?}",
+                slot
+            ))
+        })?,
+        BlockId::Root(root) => root,
+    };
+
+    let block = beacon_chain.store.get_block(&block_root)?.ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "Unable to find SignedBeaconBlock for root {:?}",
+            block_root
+        ))
+    })?;
+
+    Ok(BlockResponse {
+        root: block_root,
+        beacon_block: block,
+    })
+}
+
+/// Returns the state identified by `state_id`, along with its root.
+///
+/// Will not return a state if the requested slot is in the future. Will return states higher than
+/// the current head by skipping slots.
+pub fn state<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    config: &Config,
+    state_id: StateId,
+) -> Result<StateResponse<T::EthSpec>, ApiError> {
+    let (root, state) = match state_id {
+        StateId::Head => {
+            let head_slot = beacon_chain.head_info()?.slot;
+            state_at_slot(beacon_chain, head_slot)?
+        }
+        StateId::Slot(slot) => {
+            check_state_served(beacon_chain, config.mode, slot)?;
+            state_at_slot(beacon_chain, slot)?
+        }
+        StateId::Root(root) => {
+            check_state_root_served(beacon_chain, config.mode, &root)?;
+            let state = beacon_chain
+                .store
+                .get_state(&root, None)?
+                .ok_or_else(|| ApiError::NotFound(format!("No state for root: {:?}", root)))?;
+
+            (root, state)
+        }
+    };
+
+    Ok(StateResponse {
+        root,
+        beacon_state: state,
+    })
+}
+
+/// Returns either the state given by `state_root_opt`, or the canonical head state if it is
+/// `None`.
+pub fn state_at_root_or_head<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    config: &Config,
+    state_root_opt: Option<Hash256>,
+) -> Result<BeaconState<T::EthSpec>, ApiError> {
+    if let Some(state_root) = state_root_opt {
+        check_state_root_served(beacon_chain, config.mode, &state_root)?;
+
+        beacon_chain
+            .get_state(&state_root, None)
+            .map_err(|e| {
+                ApiError::ServerError(format!(
+                    "Database error when reading state root {}: {:?}",
+                    state_root, e
+                ))
+            })?
+            .ok_or_else(|| ApiError::NotFound(format!("No state exists with root: {}", state_root)))
+    } else {
+        Ok(beacon_chain.head()?.beacon_state)
+    }
+}
+
+/// Maps a vec of `validator_pubkey` to a vec of `ValidatorResponse`, using the state at the given
+/// `state_root`. If `state_root.is_none()`, uses the canonial head state.
+pub fn validators<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    config: &Config,
+    state_root_opt: Option<Hash256>,
+    validator_pubkeys: Vec<PublicKeyBytes>,
+) -> Result<Vec<ValidatorResponse>, ApiError> {
+    let mut state = state_at_root_or_head(beacon_chain, config, state_root_opt)?;
+    state.update_pubkey_cache()?;
+
+    validator_pubkeys
+        .into_iter()
+        .map(|validator_pubkey| validator(&state, validator_pubkey))
+        .collect::<Result<Vec<_>, ApiError>>()
+}
+
+/// Maps a `validator_pubkey` to a `ValidatorResponse`, using the given state.
+///
+/// The provided `state` must have a fully up-to-date pubkey cache.
+pub fn validator<E: EthSpec>(
+    state: &BeaconState<E>,
+    validator_pubkey: PublicKeyBytes,
+) -> Result<ValidatorResponse, ApiError> {
+    let validator_index_opt = state
+        .get_validator_index(&validator_pubkey)
+        .map_err(|e| ApiError::ServerError(format!("Unable to read pubkey cache: {:?}", e)))?;
+
+    if let Some(validator_index) = validator_index_opt {
+        let balance = state.balances.get(validator_index).ok_or_else(|| {
+            ApiError::ServerError(format!("Invalid balances index: {:?}", validator_index))
+        })?;
+
+        let validator = state
+            .validators
+            .get(validator_index)
+            .ok_or_else(|| {
+                ApiError::ServerError(format!("Invalid validator index: {:?}", validator_index))
+            })?
+            .clone();
+
+        Ok(ValidatorResponse {
+            pubkey: validator_pubkey,
+            validator_index: Some(validator_index),
+            balance: Some(*balance),
+            validator: Some(validator),
+            extra: ExtraFields::default(),
+        })
+    } else {
+        Ok(ValidatorResponse {
+            pubkey: validator_pubkey,
+            validator_index: None,
+            balance: None,
+            validator: None,
+            extra: ExtraFields::default(),
+        })
+    }
+}
+
+/// Returns the duties in `epoch` of the validators given by `selection`.
+pub fn validator_duties<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    config: &Config,
+    epoch: Epoch,
+    selection: DutiesSelection,
+) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
+    let state = state_for_duties(beacon_chain, config, epoch)?;
+
+    let validator_pubkeys = match selection {
+        DutiesSelection::All => state
+            .validators
+            .iter()
+            .map(|validator| validator.pubkey.clone())
+            .collect(),
+        DutiesSelection::Active => state
+            .validators
+            .iter()
+            .filter(|validator| validator.is_active_at(state.current_epoch()))
+            .map(|validator| validator.pubkey.clone())
+            .collect(),
+        DutiesSelection::Pubkeys(pubkeys) => pubkeys,
+    };
+
+    duties_from_state(beacon_chain, state, epoch, validator_pubkeys)
+}
+
+/// Returns the state that can be used to determine the duties for some `epoch`, refusing epochs
+/// which are further in the past than the configured maximum lookback.
+pub fn state_for_duties<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    config: &Config,
+    epoch: Epoch,
+) -> Result<BeaconState<T::EthSpec>, ApiError> {
+    let current_epoch = beacon_chain.epoch()?;
+    let max_lookback = config.duties_max_lookback_epochs;
+
+    if epoch < current_epoch.saturating_sub(max_lookback) {
+        return Err(ApiError::BadRequest(format!(
+            "Epoch {} is more than {} epochs prior to the current epoch {}",
+            epoch, max_lookback, current_epoch
+        )));
+    }
+
+    check_state_served(
+        beacon_chain,
+        config.mode,
+        epoch.start_slot(T::EthSpec::slots_per_epoch()),
+    )?;
+
+    state_for_epoch(beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)
+}
+
+/// Returns the state that can be used to determine the duties for some `epoch`.
+///
+/// Past epochs are served from the historical state at the start of that epoch.
+pub fn state_for_epoch<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    epoch: Epoch,
+    config: StateSkipConfig,
+) -> Result<BeaconState<T::EthSpec>, ApiError> {
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let current_epoch = beacon_chain.epoch()?;
+
+    // Only clone the head state if it can serve the request. The pubkey cache is cloned too, so
+    // that it does not need to be rebuilt when looking up validator indices.
+    let head_state_opt = beacon_chain.with_head_state(|head_state| {
+        let head_epoch = head_state.current_epoch();
+
+        if head_epoch == current_epoch && (epoch == current_epoch || epoch == current_epoch + 1) {
+            Ok(Some(head_state.clone_with(CloneConfig {
+                pubkey_cache: true,
+                ..CloneConfig::committee_caches_only()
+            })))
+        } else {
+            Ok(None)
+        }
+    })?;
+
+    if let Some(head_state) = head_state_opt {
+        Ok(head_state)
+    } else {
+        // If epoch is ahead of current epoch, then it should be a "next epoch" request for
+        // attestation duties. So, go to the start slot of the epoch prior to that,
+        // which should be just the next wall-clock epoch.
+        let slot = if epoch > current_epoch {
+            (epoch - 1).start_slot(slots_per_epoch)
+        }
+        // Otherwise, go to the start of the request epoch.
+        else {
+            epoch.start_slot(slots_per_epoch)
+        };
+
+        beacon_chain
+            .state_at_slot(slot, config)
+            .map_err(|e| match e {
+                BeaconChainError::NoStateForSlot(_) if epoch < current_epoch => ApiError::NotFound(
+                    format!("No historical state is available for epoch {}", epoch),
+                ),
+                e => ApiError::ServerError(format!(
+                    "Unable to load state for epoch {}: {:?}",
+                    epoch, e
+                )),
+            })
+    }
+}
+
+/// Returns the duties for some `validator_pubkeys` in some `epoch`.
+///
+/// The `state` must have been obtained from `state_for_epoch`.
+fn duties_from_state<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    mut state: BeaconState<T::EthSpec>,
+    epoch: Epoch,
+    validator_pubkeys: Vec<PublicKeyBytes>,
+) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
+    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)
+        .map_err(|_| ApiError::ServerError(String::from("Loaded state is in the wrong epoch")))?;
+
+    state
+        .build_committee_cache(relative_epoch, &beacon_chain.spec)
+        .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
+    state
+        .update_pubkey_cache()
+        .map_err(|e| ApiError::ServerError(format!("Unable to build pubkey cache: {:?}", e)))?;
+
+    // Get a list of all validators for this epoch.
+    //
+    // Used for quickly determining the slot for a proposer.
+    let (validator_proposers, proposal_dependent_root) = if epoch == state.current_epoch() {
+        (
+            Some(proposers_for_epoch(beacon_chain, &state, epoch)?),
+            None,
+        )
+    } else if epoch == beacon_chain.epoch()? + 1 {
+        // Proposers for the next epoch are only known once the state has been advanced into it,
+        // so they are computed from the current head and may change if the head does.
+        let head_block_root = beacon_chain.head_info()?.block_root;
+        let lookahead_state = beacon_chain
+            .state_at_slot(
+                epoch.start_slot(T::EthSpec::slots_per_epoch()),
+                StateSkipConfig::WithoutStateRoots,
+            )
+            .map_err(|e| {
+                ApiError::ServerError(format!(
+                    "Unable to advance state to epoch {}: {:?}",
+                    epoch, e
+                ))
+            })?;
+
+        (
+            Some(proposers_for_epoch(beacon_chain, &lookahead_state, epoch)?),
+            Some(head_block_root),
+        )
+    } else {
+        (None, None)
+    };
+
+    validator_pubkeys
+        .into_iter()
+        .map(|validator_pubkey| {
+            // The `beacon_chain` can return a validator index that does not exist in all states.
+            // Therefore, we must check to ensure that the validator index is valid for our
+            // `state`.
+            let validator_index = beacon_chain
+                .validator_index(&validator_pubkey)
+                .map_err(|e| {
+                    ApiError::ServerError(format!("Unable to get validator index: {:?}", e))
+                })?
+                .filter(|i| *i < state.validators.len());
+
+            if let Some(validator_index) = validator_index {
+                let duties = state
+                    .get_attestation_duties(validator_index, relative_epoch)
+                    .map_err(|e| {
+                        ApiError::ServerError(format!(
+                            "Unable to obtain attestation duties: {:?}",
+                            e
+                        ))
+                    })?;
+
+                let committee_count_at_slot = duties
+                    .map(|d| state.get_committee_count_at_slot(d.slot))
+                    .transpose()
+                    .map_err(|e| {
+                        ApiError::ServerError(format!(
+                            "Unable to find committee count at slot: {:?}",
+                            e
+                        ))
+                    })?;
+
+                let aggregator_modulo = duties
+                    .map(|duties| SelectionProof::modulo(duties.committee_len, &beacon_chain.spec))
+                    .transpose()
+                    .map_err(|e| {
+                        ApiError::ServerError(format!("Unable to find modulo: {:?}", e))
+                    })?;
+
+                let block_proposal_slots = validator_proposers.as_ref().map(|proposers| {
+                    proposers
+                        .iter()
+                        .filter(|(i, _slot)| validator_index == *i)
+                        .map(|(_i, slot)| *slot)
+                        .collect()
+                });
+
+                Ok(ValidatorDutyBytes {
+                    validator_pubkey,
+                    validator_index: Some(validator_index as u64),
+                    attestation_slot: duties.map(|d| d.slot),
+                    attestation_committee_index: duties.map(|d| d.index),
+                    committee_count_at_slot,
+                    attestation_committee_position: duties.map(|d| d.committee_position),
+                    block_proposal_slots,
+                    block_proposal_dependent_root: proposal_dependent_root,
+                    aggregator_modulo,
+                    extra: ExtraFields::default(),
+                })
+            } else {
+                Ok(ValidatorDutyBytes {
+                    validator_pubkey,
+                    validator_index: None,
+                    attestation_slot: None,
+                    attestation_committee_index: None,
+                    attestation_committee_position: None,
+                    block_proposal_slots: None,
+                    block_proposal_dependent_root: None,
+                    committee_count_at_slot: None,
+                    aggregator_modulo: None,
+                    extra: ExtraFields::default(),
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, ApiError>>()
+}
+
+/// Returns the `(proposer_index, slot)` pairs for every slot of `epoch`.
+///
+/// The `state` must be in `epoch`.
+fn proposers_for_epoch<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    state: &BeaconState<T::EthSpec>,
+    epoch: Epoch,
+) -> Result<Vec<(usize, Slot)>, ApiError> {
+    epoch
+        .slot_iter(T::EthSpec::slots_per_epoch())
+        .map(|slot| {
+            state
+                .get_beacon_proposer_index(slot, &beacon_chain.spec)
+                .map(|i| (i, slot))
+                .map_err(|e| {
+                    ApiError::ServerError(format!(
+                        "Unable to get proposer index for validator: {:?}",
+                        e
+                    ))
+                })
+        })
+        .collect()
+}
@@ -2,60 +2,120 @@ use crate::{ApiError, NetworkChannel};
 use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
 use bls::PublicKeyBytes;
 use eth2_libp2p::PubsubMessage;
+use hyper::{header, Request};
 use itertools::process_results;
 use network::NetworkMessage;
+use rest_types::{SNAPPY_CONTENT_ENCODING, SSZ_CONTENT_TYPE};
+use serde::de::DeserializeOwned;
+use slot_clock::SlotClock;
+use snap::raw::{decompress_len, Decoder};
 use ssz::Decode;
-use store::iter::AncestorIter;
-use types::{
-    BeaconState, CommitteeIndex, Epoch, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock, Slot,
-};
+use std::path::{Component, Path, PathBuf};
+use store::NodeMode;
+use types::{BeaconState, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock, Slot};
 
-/// Parse a slot.
+/// Returns the path of the database backup called `name` within `backup_dir`.
 ///
-/// E.g., `"1234"`
-pub fn parse_slot(string: &str) -> Result<Slot, ApiError> {
-    string
-        .parse::<u64>()
-        .map(Slot::from)
-        .map_err(|e| ApiError::BadRequest(format!("Unable to parse slot: {:?}", e)))
-}
+/// The `name` must be a single path component, so that backups cannot be written outside of
+/// `backup_dir`. Returns an error if the path already exists.
+pub fn database_backup_path(backup_dir: &Path, name: &str) -> Result<PathBuf, ApiError> {
+    let mut components = Path::new(name).components();
 
-/// Parse an epoch.
-///
-/// E.g., `"13"`
-pub fn parse_epoch(string: &str) -> Result<Epoch, ApiError> {
-    string
-        .parse::<u64>()
-        .map(Epoch::from)
-        .map_err(|e| ApiError::BadRequest(format!("Unable to parse epoch: {:?}", e)))
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => {}
+        _ => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid backup name {:?}, it must be a file name without any directories",
+                name
+            )))
+        }
+    }
+
+    let path = backup_dir.join(name);
+
+    // Symbolic links are not followed, so that an existing link is never written through.
+    if path.symlink_metadata().is_ok() {
+        return Err(ApiError::BadRequest(format!(
+            "Backup destination already exists: {:?}",
+            path
+        )));
+    }
+
+    Ok(path)
 }
 
-/// Parse a CommitteeIndex.
+/// The number of slots the head may be behind the present slot whilst the node is syncing, before
+/// the node refuses to serve duties or produce blocks and attestations.
 ///
-/// E.g., `"18"`
-pub fn parse_committee_index(string: &str) -> Result<CommitteeIndex, ApiError> {
-    string
-        .parse::<CommitteeIndex>()
-        .map_err(|e| ApiError::BadRequest(format!("Unable to parse committee index: {:?}", e)))
-}
+/// Matches the tolerance used by the validator client when checking that a node is synced.
+pub const SYNC_TOLERANCE: u64 = 4;
 
-/// Parse an SSZ object from some hex-encoded bytes.
+/// Decodes the body of `req` as JSON or, if its `Content-Type` is `SSZ_CONTENT_TYPE`, as SSZ.
 ///
-/// E.g., A signature is `"0x0000000000000000000000000000000000000000000000000000000000000000"`
-pub fn parse_hex_ssz_bytes<T: Decode>(string: &str) -> Result<T, ApiError> {
-    const PREFIX: &str = "0x";
+/// An SSZ body may be compressed with snappy, as on gossip, if its `Content-Encoding` is
+/// `SNAPPY_CONTENT_ENCODING`. A compressed body is subject to the same `max_body_size` as an
+/// uncompressed one once decompressed, which prevents a small body from exhausting memory. The
+/// `type_name` is used in error messages.
+pub fn decode_body<T: DeserializeOwned + Decode>(
+    req: &Request<Vec<u8>>,
+    max_body_size: usize,
+    type_name: &str,
+) -> Result<T, ApiError> {
+    let header_value = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(str::trim)
+            .unwrap_or("")
+    };
+    let is_ssz = matches!(
+        header_value(header::CONTENT_TYPE),
+        SSZ_CONTENT_TYPE | "application/ssz"
+    );
+    let is_snappy = header_value(header::CONTENT_ENCODING) == SNAPPY_CONTENT_ENCODING;
+    let body = req.body();
 
-    if string.starts_with(PREFIX) {
-        let trimmed = string.trim_start_matches(PREFIX);
-        let bytes = hex::decode(trimmed)
-            .map_err(|e| ApiError::BadRequest(format!("Unable to parse SSZ hex: {:?}", e)))?;
-        T::from_ssz_bytes(&bytes)
-            .map_err(|e| ApiError::BadRequest(format!("Unable to parse SSZ bytes: {:?}", e)))
-    } else {
-        Err(ApiError::BadRequest(
-            "Hex bytes must have a 0x prefix".to_string(),
-        ))
+    if !is_ssz {
+        if is_snappy {
+            return Err(ApiError::BadRequest(
+                "Only SSZ request bodies may be snappy compressed".to_string(),
+            ));
+        }
+
+        return serde_json::from_slice(body).map_err(|e| {
+            ApiError::BadRequest(format!("Unable to parse JSON into {}: {:?}", type_name, e))
+        });
     }
+
+    let decompressed;
+    let ssz_bytes = if is_snappy {
+        match decompress_len(body) {
+            Ok(len) if len > max_body_size => {
+                return Err(ApiError::BadRequest(format!(
+                    "Decompressed body of {} bytes exceeds the maximum of {} bytes",
+                    len, max_body_size
+                )))
+            }
+            Ok(_) => (),
+            Err(e) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid snappy body: {:?}",
+                    e
+                )))
+            }
+        }
+        decompressed = Decoder::new()
+            .decompress_vec(body)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid snappy body: {:?}", e)))?;
+        &decompressed
+    } else {
+        body
+    };
+
+    T::from_ssz_bytes(ssz_bytes).map_err(|e| {
+        ApiError::BadRequest(format!("Unable to decode SSZ into {}: {:?}", type_name, e))
+    })
 }
 
 /// Parse a root from a `0x` prefixed string.
@@ -120,10 +180,20 @@ pub fn state_at_slot<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
     slot: Slot,
 ) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
-    let head = beacon_chain.head()?;
+    // Only clone the head state if it is the one being requested.
+    let head_opt = beacon_chain.with_head(|head| {
+        if head.beacon_state.slot == slot {
+            Ok(Some((
+                head.beacon_state_root,
+                head.beacon_state.clone_with_only_committee_caches(),
+            )))
+        } else {
+            Ok(None)
+        }
+    })?;
 
-    if head.beacon_state.slot == slot {
-        Ok((head.beacon_state_root, head.beacon_state))
+    if let Some(head) = head_opt {
+        Ok(head)
     } else {
         let root = state_root_at_slot(beacon_chain, slot, StateSkipConfig::WithStateRoots)?;
 
@@ -136,6 +206,49 @@ pub fn state_at_slot<T: BeaconChainTypes>(
     }
 }
 
+/// Returns an error if the state at `slot` is a finalized state which is not served in the node's
+/// `--mode`.
+///
+/// In `minimal` mode, finalized states are only served at restore points, since any other state
+/// may require replaying up to `SLOTS_PER_HISTORICAL_ROOT` blocks.
+pub fn check_state_served<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    mode: NodeMode,
+    slot: Slot,
+) -> Result<(), ApiError> {
+    let store = &beacon_chain.store;
+    let slots_per_restore_point = store.get_config().slots_per_restore_point;
+
+    if mode.serves_reconstructed_states()
+        || slot >= store.get_split_slot()
+        || slot % slots_per_restore_point == 0
+    {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "The finalized state at slot {} is not served in {} mode, only those at multiples of \
+             {} slots. Use a node in standard or archive mode.",
+            slot, mode, slots_per_restore_point
+        )))
+    }
+}
+
+/// As per `check_state_served`, for the state with the given `state_root`.
+pub fn check_state_root_served<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    mode: NodeMode,
+    state_root: &Hash256,
+) -> Result<(), ApiError> {
+    if mode.serves_reconstructed_states() {
+        return Ok(());
+    }
+
+    match beacon_chain.store.load_cold_state_slot(state_root)? {
+        Some(slot) => check_state_served(beacon_chain, mode, slot),
+        None => Ok(()),
+    }
+}
+
 /// Returns the root of the `BeaconState` in the canonical chain of `beacon_chain` at the given
 /// `slot`, if possible.
 ///
@@ -146,7 +259,8 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
     slot: Slot,
     config: StateSkipConfig,
 ) -> Result<Hash256, ApiError> {
-    let head_state = &beacon_chain.head()?.beacon_state;
+    let (head_slot, head_state_root) =
+        beacon_chain.with_head(|head| Ok((head.beacon_state.slot, head.beacon_state_root)))?;
     let current_slot = beacon_chain
         .slot()
         .map_err(|_| ApiError::ServerError("Unable to read slot clock".to_string()))?;
@@ -166,25 +280,27 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
             "Requested slot {} is past the current slot {}",
             slot, current_slot
         )))
-    } else if head_state.slot == slot {
+    } else if head_slot == slot {
         // 2. The request slot is the same as the best block (head) slot.
         //
         // The head state root is stored in memory, return a reference.
-        Ok(beacon_chain.head()?.beacon_state_root)
-    } else if head_state.slot > slot {
+        Ok(head_state_root)
+    } else if head_slot > slot {
         // 3. The request slot is prior to the head slot.
         //
-        // Iterate through the state roots on the head state to find the root for that
-        // slot. Once the root is found, load it from the database.
-        process_results(
-            head_state
-                .try_iter_ancestor_roots(beacon_chain.store.clone())
-                .ok_or_else(|| {
-                    ApiError::ServerError("Failed to create roots iterator".to_string())
-                })?,
-            |mut iter| iter.find(|(_, s)| *s == slot).map(|(root, _)| root),
-        )?
-        .ok_or_else(|| ApiError::NotFound(format!("Unable to find state at slot {}", slot)))
+        // Recent state roots are read directly from the head state. Older roots require iterating
+        // through the database, which is done without holding the head lock.
+        let recent_root = beacon_chain
+            .with_head_state(|head_state| Ok(head_state.get_state_root(slot).ok().copied()))?;
+
+        if let Some(root) = recent_root {
+            Ok(root)
+        } else {
+            process_results(beacon_chain.rev_iter_state_roots()?, |mut iter| {
+                iter.find(|(_, s)| *s == slot).map(|(root, _)| root)
+            })?
+            .ok_or_else(|| ApiError::NotFound(format!("Unable to find state at slot {}", slot)))
+        }
     } else {
         // 4. The request slot is later than the head slot.
         //
@@ -211,6 +327,90 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
     }
 }
 
+/// Returns a `ServiceUnavailable` error if the present time is prior to genesis, since there are
+/// no duties to perform until the chain has started.
+///
+/// The error asks the caller to retry once genesis has been reached.
+pub fn check_chain_started<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+) -> Result<(), ApiError> {
+    let slot_clock = &beacon_chain.slot_clock;
+
+    match slot_clock.is_prior_to_genesis() {
+        Some(false) => Ok(()),
+        Some(true) => {
+            let retry_after = slot_clock
+                .duration_to_slot(slot_clock.genesis_slot())
+                .map_or(1, |duration| duration.as_secs() + 1);
+
+            Err(ApiError::ServiceUnavailable(
+                format!(
+                    "The chain has not started, genesis is in {} seconds",
+                    retry_after
+                ),
+                retry_after,
+            ))
+        }
+        None => Err(ApiError::ServerError(
+            "Unable to read slot clock".to_string(),
+        )),
+    }
+}
+
+/// Returns a `ServiceUnavailable` error if the node is syncing and its head is more than
+/// `SYNC_TOLERANCE` slots behind the present slot, since duties computed from such a stale head
+/// are likely to be wrong.
+pub fn check_chain_synced<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    is_syncing: bool,
+) -> Result<(), ApiError> {
+    if !is_syncing {
+        return Ok(());
+    }
+
+    let head_slot = beacon_chain.head_info()?.slot;
+    let current_slot = beacon_chain
+        .slot()
+        .map_err(|_| ApiError::ServerError("Unable to read slot clock".to_string()))?;
+
+    if is_within_sync_tolerance(head_slot, current_slot) {
+        Ok(())
+    } else {
+        Err(ApiError::ServiceUnavailable(
+            format!(
+                "BeaconNodeIsSyncing: head slot {} is {} slots behind the current slot {}",
+                head_slot,
+                current_slot - head_slot,
+                current_slot
+            ),
+            beacon_chain.slot_clock.slot_duration().as_secs(),
+        ))
+    }
+}
+
+/// Returns a `ServiceUnavailable` error if the head block was imported optimistically (i.e., its
+/// execution payload has not been validated), since duties computed from such a head may be for
+/// an invalid chain.
+pub fn check_head_not_optimistic<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+) -> Result<(), ApiError> {
+    if beacon_chain.is_optimistic_head()? {
+        Err(ApiError::ServiceUnavailable(
+            "BeaconNodeIsOptimistic: the execution payload of the head block has not been \
+             validated by the execution engine"
+                .to_string(),
+            beacon_chain.slot_clock.slot_duration().as_secs(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns `true` if `head_slot` is no more than `SYNC_TOLERANCE` slots behind `current_slot`.
+fn is_within_sync_tolerance(head_slot: Slot, current_slot: Slot) -> bool {
+    head_slot + SYNC_TOLERANCE >= current_slot
+}
+
 pub fn publish_beacon_block_to_network<T: BeaconChainTypes + 'static>(
     chan: &NetworkChannel<T::EthSpec>,
     block: SignedBeaconBlock<T::EthSpec>,
@@ -251,10 +451,18 @@ mod test {
     }
 
     #[test]
-    fn parse_slot_works() {
-        assert_eq!(parse_slot("0"), Ok(Slot::new(0)));
-        assert_eq!(parse_slot("42"), Ok(Slot::new(42)));
-        assert_eq!(parse_slot("10000000"), Ok(Slot::new(10_000_000)));
-        assert!(parse_slot("cats").is_err());
+    fn sync_tolerance() {
+        let head_slot = Slot::new(100);
+
+        assert!(is_within_sync_tolerance(head_slot, head_slot));
+        assert!(is_within_sync_tolerance(head_slot, head_slot - 1));
+        assert!(is_within_sync_tolerance(
+            head_slot,
+            head_slot + SYNC_TOLERANCE
+        ));
+        assert!(!is_within_sync_tolerance(
+            head_slot,
+            head_slot + SYNC_TOLERANCE + 1
+        ));
     }
 }
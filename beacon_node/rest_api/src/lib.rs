@@ -3,18 +3,21 @@ extern crate lazy_static;
 mod router;
 extern crate network as client_network;
 
+mod attestation_cache;
 mod beacon;
 pub mod config;
 mod consensus;
+pub mod facade;
 mod helpers;
 mod lighthouse;
 mod metrics;
 mod node;
+mod request_limiter;
 mod url_query;
 mod validator;
 
+use attestation_cache::AttestationCache;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use bus::Bus;
 use client_network::NetworkMessage;
 pub use config::ApiEncodingFormat;
 use eth2_config::Eth2Config;
@@ -30,11 +33,12 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use types::SignedBeaconBlockHash;
-use url_query::UrlQuery;
+use url_query::{parse_query, UrlQuery};
 
 pub use crate::helpers::parse_pubkey_bytes;
 pub use config::Config;
+pub use request_limiter::RequestLimiter;
+pub use rest_types::DepositContract;
 pub use router::Context;
 
 pub type NetworkChannel<T> = mpsc::UnboundedSender<NetworkMessage<T>>;
@@ -42,6 +46,9 @@ pub type NetworkChannel<T> = mpsc::UnboundedSender<NetworkMessage<T>>;
 pub struct NetworkInfo<T: BeaconChainTypes> {
     pub network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     pub network_chan: NetworkChannel<T::EthSpec>,
+    /// The base64 ENRs of the boot nodes the node was configured with.
+    pub boot_enrs: Vec<String>,
+    pub deposit_contract: DepositContract,
 }
 
 // Allowing more than 7 arguments.
@@ -54,7 +61,7 @@ pub fn start_server<T: BeaconChainTypes>(
     db_path: PathBuf,
     freezer_db_path: PathBuf,
     eth2_config: Eth2Config,
-    events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+    request_limiter: Arc<RequestLimiter>,
 ) -> Result<SocketAddr, hyper::Error> {
     let log = executor.log();
     let eth2_config = Arc::new(eth2_config);
@@ -65,11 +72,15 @@ pub fn start_server<T: BeaconChainTypes>(
         beacon_chain,
         network_globals: network_info.network_globals.clone(),
         network_chan: network_info.network_chan,
+        boot_enrs: network_info.boot_enrs,
+        deposit_contract: network_info.deposit_contract,
         eth2_config,
         log: log.clone(),
         db_path,
         freezer_db_path,
-        events,
+        request_limiter,
+        genesis_state_ssz: Mutex::new(None),
+        attestation_cache: AttestationCache::default(),
     });
 
     // Define the function that will build the request handler.
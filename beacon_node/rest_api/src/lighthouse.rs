@@ -1,11 +1,32 @@
 //! This contains a collection of lighthouse specific HTTP endpoints.
 
-use crate::{ApiError, Context};
+use crate::{beacon, facade, parse_query, ApiError, Context};
+use beacon_chain::block_times_cache::{BlockImportStage, BlockTimes};
+use beacon_chain::cache_stats::CacheInfo as ChainCacheInfo;
+use beacon_chain::gossip_observations::{GossipMessage, GossipObservation};
+use beacon_chain::recent_proposals::{graffiti_to_string, ClientKind};
 use beacon_chain::BeaconChainTypes;
-use eth2_libp2p::PeerInfo;
+use eth1::DepositTreeSnapshot;
+use eth2_libp2p::{PeerId, PeerInfo, TrustedPeer};
+use hyper::{header, Request};
+use network::NetworkMessage;
+use rest_types::{
+    AttestationPoolsResponse, AttesterDuty, BatchedDutiesQuery, BlockTimings, BlockTimingsQuery,
+    CacheInfo, CachesResponse, ClientDiversityResponse, ClientProposals, CommitteeAggregation,
+    ConfigReloadResponse, DropCachesRequest, DroppableCache, EpochAttesterDuties,
+    GraffitiProposals, NaiveAggregationPoolInfo, NodeConfigResponse, ObservedAttestationsInfo,
+    ObservedGossipAggregate, ObservedGossipBlock, ObservedGossipQuery, ObservedGossipResponse,
+    OpPoolAttestationsInfo, OpPoolSlotAttestations, RangedBytes, ShutdownAtSlot, TestnetInfo,
+    TrustedPeerInfo, TrustedPeersRequest, WeakSubjectivityPeriodResponse,
+};
 use serde::Serialize;
+use slog::info;
+use slot_clock::SlotClock;
+use ssz::Encode;
+use state_processing::common::compute_weak_subjectivity_period;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use types::EthSpec;
+use types::{BeaconState, EthSpec, Hash256, RelativeEpoch, SelectionProof, Slot};
 
 /// Returns all known peers and corresponding information
 pub fn peers<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Vec<Peer<T::EthSpec>>, ApiError> {
@@ -37,6 +58,671 @@ pub fn connected_peers<T: BeaconChainTypes>(
         .collect())
 }
 
+/// Returns the peers persisted to the database, in the order they will be dialed on startup.
+///
+/// Connected peers are persisted periodically, so recent connections may not be included.
+pub fn peer_db<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<Vec<PersistedPeerInfo>, ApiError> {
+    Ok(network::load_peers(ctx.beacon_chain.store.clone())
+        .peers
+        .into_iter()
+        .map(|peer| PersistedPeerInfo {
+            peer_id: peer.peer_id().to_string(),
+            enr: peer.enr.to_base64(),
+            score: peer.score,
+            last_seen: peer.last_seen,
+        })
+        .collect())
+}
+
+/// Returns the information required to bootstrap a node onto the same network as this one.
+pub fn testnet<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<TestnetInfo, ApiError> {
+    let beacon_chain = &ctx.beacon_chain;
+    let genesis_block = beacon_chain
+        .get_block(&beacon_chain.genesis_block_root)?
+        .ok_or_else(|| ApiError::ServerError("Unable to find genesis block".to_string()))?;
+
+    Ok(TestnetInfo {
+        config_name: ctx.eth2_config.spec_constants.clone(),
+        boot_enrs: ctx.boot_enrs.clone(),
+        genesis_time: beacon_chain.head_info()?.genesis_time,
+        genesis_validators_root: beacon_chain.genesis_validators_root,
+        genesis_state_root: genesis_block.state_root(),
+        deposit_contract: ctx.deposit_contract.clone(),
+    })
+}
+
+/// Returns the mode of the node and the database and API settings it determines.
+pub fn node_config<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> NodeConfigResponse {
+    let store_config = ctx.beacon_chain.store.get_config();
+
+    NodeConfigResponse {
+        mode: ctx.config.mode.to_string(),
+        slots_per_restore_point: store_config.slots_per_restore_point,
+        prune_abandoned_forks: store_config.prune_abandoned_forks,
+        serves_reconstructed_states: ctx.config.mode.serves_reconstructed_states(),
+        duties_max_lookback_epochs: ctx.config.duties_max_lookback_epochs,
+    }
+}
+
+/// Returns the weak subjectivity period of the head state and the latest finalized checkpoint,
+/// which operators may publish for other nodes to sync from.
+pub fn ws_period<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<WeakSubjectivityPeriodResponse, ApiError> {
+    let beacon_chain = &ctx.beacon_chain;
+    let (ws_period, mut checkpoint) = beacon_chain.with_head_state(|state| {
+        let ws_period = compute_weak_subjectivity_period(state, &beacon_chain.spec)?;
+        Ok((ws_period, state.finalized_checkpoint))
+    })?;
+
+    // The finalized checkpoint of the states of the genesis epoch has a zero root.
+    if checkpoint.epoch == 0 {
+        checkpoint.root = beacon_chain.genesis_block_root;
+    }
+
+    let current_epoch = beacon_chain.epoch()?;
+    let safe_until_epoch = checkpoint.epoch + ws_period;
+
+    Ok(WeakSubjectivityPeriodResponse {
+        current_epoch,
+        ws_period: ws_period.as_u64(),
+        checkpoint,
+        safe_until_epoch,
+        is_safe: current_epoch <= safe_until_epoch,
+    })
+}
+
+/// Returns a snapshot of the deposit tree containing the deposits included in the finalized
+/// chain, allowing another node to follow the deposit contract without the prior deposit logs.
+pub fn deposit_snapshot<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<DepositTreeSnapshot, ApiError> {
+    let beacon_chain = &ctx.beacon_chain;
+    let eth1_chain = beacon_chain.eth1_chain.as_ref().ok_or_else(|| {
+        ApiError::NotFound("The beacon node is not following the eth1 chain".to_string())
+    })?;
+
+    let finalized_root = beacon_chain.head_info()?.finalized_checkpoint.root;
+    let finalized_block = beacon_chain.get_block(&finalized_root)?.ok_or_else(|| {
+        ApiError::ServerError(format!("Unable to find finalized block {}", finalized_root))
+    })?;
+    let finalized_state = beacon_chain
+        .get_state(&finalized_block.state_root(), Some(finalized_block.slot()))?
+        .ok_or_else(|| {
+            ApiError::ServerError(format!(
+                "Unable to find finalized state {}",
+                finalized_block.state_root()
+            ))
+        })?;
+
+    eth1_chain
+        .deposit_snapshot(finalized_state.eth1_deposit_index)
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "The first {} deposits are not known",
+                finalized_state.eth1_deposit_index
+            ))
+        })
+}
+
+/// Returns the SSZ encoding of the genesis state, or the range of it specified by the `Range`
+/// header of the request.
+///
+/// The encoding is cached, since clients downloading the (potentially very large) state in ranges
+/// will make many requests.
+pub fn genesis_state<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<RangedBytes, ApiError> {
+    let range_header = req
+        .headers()
+        .get(header::RANGE)
+        .map(|value| {
+            value.to_str().map_err(|e| {
+                ApiError::BadRequest(format!(
+                    "The Range header contains invalid characters: {:?}",
+                    e
+                ))
+            })
+        })
+        .transpose()?;
+
+    let mut cached = ctx.genesis_state_ssz.lock();
+    let bytes = match cached.as_ref() {
+        Some(bytes) => bytes.clone(),
+        None => {
+            let bytes = Arc::new(beacon::get_genesis_state(ctx.clone())?.as_ssz_bytes());
+            *cached = Some(bytes.clone());
+            bytes
+        }
+    };
+    drop(cached);
+
+    RangedBytes::new(bytes, range_header)
+}
+
+/// Returns the attester duties of the validators with the given `indices` during each of the given
+/// `epochs`.
+///
+/// A state can provide the shuffling for the epochs either side of its own, so a single state is
+/// used for as many of the (sorted) `epochs` as possible rather than loading one per epoch.
+pub fn validator_duties<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<Vec<EpochAttesterDuties>, ApiError> {
+    let query = parse_query::<BatchedDutiesQuery, _>(&req)?;
+    let current_epoch = ctx.beacon_chain.epoch()?;
+    let spec = &ctx.beacon_chain.spec;
+
+    let mut epochs = query.epochs;
+    epochs.sort();
+    epochs.dedup();
+
+    if epochs.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one epoch must be supplied".to_string(),
+        ));
+    }
+
+    if let Some(epoch) = epochs.iter().find(|epoch| **epoch > current_epoch + 1) {
+        return Err(ApiError::BadRequest(format!(
+            "Epoch {} is more than one epoch after the current epoch {}",
+            epoch, current_epoch
+        )));
+    }
+
+    let mut state_opt: Option<BeaconState<T::EthSpec>> = None;
+
+    epochs
+        .into_iter()
+        .map(|epoch| -> Result<_, ApiError> {
+            let state = match state_opt.take() {
+                Some(state) if RelativeEpoch::from_epoch(state.current_epoch(), epoch).is_ok() => {
+                    state
+                }
+                _ => facade::state_for_duties(&ctx.beacon_chain, &ctx.config, epoch)?,
+            };
+            let state = state_opt.get_or_insert(state);
+
+            let relative_epoch =
+                RelativeEpoch::from_epoch(state.current_epoch(), epoch).map_err(|_| {
+                    ApiError::ServerError(String::from("Loaded state is in the wrong epoch"))
+                })?;
+            state.build_committee_cache(relative_epoch, spec)?;
+
+            let duties = query
+                .indices
+                .iter()
+                .filter_map(|&validator_index| {
+                    state
+                        .get_attestation_duties(validator_index as usize, relative_epoch)
+                        .transpose()
+                        .map(|result| result.map(|duty| (validator_index, duty)))
+                })
+                .map(|result| -> Result<_, ApiError> {
+                    let (validator_index, duty) = result?;
+
+                    Ok(AttesterDuty {
+                        validator_index,
+                        attestation_slot: duty.slot,
+                        attestation_committee_index: duty.index,
+                        attestation_committee_position: duty.committee_position,
+                        committee_count_at_slot: state.get_committee_count_at_slot(duty.slot)?,
+                        aggregator_modulo: SelectionProof::modulo(duty.committee_len, spec)
+                            .map_err(|e| {
+                                ApiError::ServerError(format!("Unable to find modulo: {:?}", e))
+                            })?,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(EpochAttesterDuties { epoch, duties })
+        })
+        .collect()
+}
+
+/// Returns the times at which recently imported blocks reached each stage of import, oldest
+/// first.
+///
+/// If a `root` is supplied, only the timings of that block are returned.
+pub fn block_timings<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<Vec<BlockTimings>, ApiError> {
+    let query = parse_query::<BlockTimingsQuery, _>(&req)?;
+    let beacon_chain = &ctx.beacon_chain;
+    let block_times_cache = beacon_chain.block_times_cache.read();
+
+    let to_timings = |block_root: Hash256, block_times: &BlockTimes| {
+        let slot_start = beacon_chain.slot_clock.start_of(block_times.slot);
+        let delay_ms = |stage| {
+            let timestamp = block_times.timestamps.get(stage)?;
+            let slot_start = slot_start?;
+            Some(timestamp.as_millis() as i64 - slot_start.as_millis() as i64)
+        };
+
+        BlockTimings {
+            block_root,
+            slot: block_times.slot,
+            observed_delay_ms: delay_ms(BlockImportStage::Observed),
+            signature_verified_delay_ms: delay_ms(BlockImportStage::SignatureVerified),
+            state_transition_delay_ms: delay_ms(BlockImportStage::StateTransition),
+            imported_delay_ms: delay_ms(BlockImportStage::Imported),
+            head_delay_ms: delay_ms(BlockImportStage::Head),
+        }
+    };
+
+    if let Some(root) = query.root {
+        block_times_cache
+            .get(&root)
+            .map(|block_times| vec![to_timings(root, block_times)])
+            .ok_or_else(|| ApiError::NotFound(format!("No timings known for block {:?}", root)))
+    } else {
+        Ok(block_times_cache
+            .iter()
+            .map(|(block_root, block_times)| to_timings(*block_root, block_times))
+            .collect())
+    }
+}
+
+/// Returns the size and hit rate of each of the in-memory caches of the beacon chain.
+pub fn caches<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<CachesResponse, ApiError> {
+    let info = ctx.beacon_chain.caches_info()?;
+
+    let to_response = |info: ChainCacheInfo| {
+        let lookups = info.hits + info.misses;
+        CacheInfo {
+            len: info.len as u64,
+            capacity: info.capacity.map(|capacity| capacity as u64),
+            hits: info.hits,
+            misses: info.misses,
+            hit_rate: if lookups > 0 {
+                Some(info.hits as f64 / lookups as f64)
+            } else {
+                None
+            },
+        }
+    };
+
+    Ok(CachesResponse {
+        snapshot_cache: to_response(info.snapshot_cache),
+        shuffling_cache: to_response(info.shuffling_cache),
+        validator_pubkey_cache: to_response(info.validator_pubkey_cache),
+    })
+}
+
+/// Returns the contents of the naive aggregation pool and the attestations of the operation pool,
+/// along with the sizes of the caches of observed attestations.
+///
+/// Useful for debugging the absence of an aggregate from `/validator/aggregate_attestation`.
+pub fn attestation_pools<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<AttestationPoolsResponse, ApiError> {
+    let beacon_chain = &ctx.beacon_chain;
+
+    let naive_aggregation_pool = {
+        let pool = beacon_chain.naive_aggregation_pool.read();
+
+        let mut num_attestations = 0;
+        let mut committees = BTreeMap::<(Slot, u64), CommitteeAggregation>::new();
+        for attestation in pool.iter() {
+            num_attestations += 1;
+
+            let slot = attestation.data.slot;
+            let index = attestation.data.index;
+            let committee =
+                committees
+                    .entry((slot, index))
+                    .or_insert_with(|| CommitteeAggregation {
+                        slot,
+                        index,
+                        attestation_data: 0,
+                        aggregated_attesters: 0,
+                        committee_size: attestation.aggregation_bits.len() as u64,
+                        completeness: 0.0,
+                        aggregator_registered: pool.has_aggregator(slot, index),
+                    });
+
+            committee.attestation_data += 1;
+            committee.aggregated_attesters = std::cmp::max(
+                committee.aggregated_attesters,
+                attestation.aggregation_bits.num_set_bits() as u64,
+            );
+        }
+
+        let mut slots = committees.keys().map(|(slot, _)| *slot).collect::<Vec<_>>();
+        slots.dedup();
+
+        NaiveAggregationPoolInfo {
+            lowest_permissible_slot: pool.lowest_permissible_slot(),
+            slots,
+            num_attestations,
+            committees: committees
+                .into_iter()
+                .map(|(_, mut committee)| {
+                    if committee.committee_size > 0 {
+                        committee.completeness =
+                            committee.aggregated_attesters as f64 / committee.committee_size as f64;
+                    }
+                    committee
+                })
+                .collect(),
+        }
+    };
+
+    let op_pool = OpPoolAttestationsInfo {
+        num_attestations: beacon_chain.op_pool.num_attestations() as u64,
+        slots: beacon_chain
+            .op_pool
+            .attestation_counts_by_slot()
+            .into_iter()
+            .map(
+                |(slot, (attestation_data, attestations))| OpPoolSlotAttestations {
+                    slot,
+                    attestation_data: attestation_data as u64,
+                    attestations: attestations as u64,
+                },
+            )
+            .collect(),
+    };
+
+    let current_epoch = beacon_chain.epoch()?;
+    let previous_epoch = current_epoch.saturating_sub(1u64);
+    let observed = ObservedAttestationsInfo {
+        aggregates: beacon_chain.observed_attestations.observed_count() as u64,
+        attesters_current_epoch: beacon_chain
+            .observed_attesters
+            .observed_validator_count(current_epoch)
+            .unwrap_or(0) as u64,
+        attesters_previous_epoch: beacon_chain
+            .observed_attesters
+            .observed_validator_count(previous_epoch)
+            .unwrap_or(0) as u64,
+        aggregators_current_epoch: beacon_chain
+            .observed_aggregators
+            .observed_validator_count(current_epoch)
+            .unwrap_or(0) as u64,
+        aggregators_previous_epoch: beacon_chain
+            .observed_aggregators
+            .observed_validator_count(previous_epoch)
+            .unwrap_or(0) as u64,
+    };
+
+    Ok(AttestationPoolsResponse {
+        naive_aggregation_pool,
+        op_pool,
+        observed,
+    })
+}
+
+/// Clears each of the caches listed in the request body, returning the state of the caches
+/// afterwards.
+pub fn drop_caches<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<CachesResponse, ApiError> {
+    let body = req.into_body();
+    let request = serde_json::from_slice::<DropCachesRequest>(&body).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into DropCachesRequest: {:?}",
+            e
+        ))
+    })?;
+
+    for cache in request.caches {
+        match cache {
+            DroppableCache::SnapshotCache => ctx.beacon_chain.clear_snapshot_cache()?,
+            DroppableCache::ShufflingCache => ctx.beacon_chain.clear_shuffling_cache()?,
+        }
+    }
+
+    caches(ctx)
+}
+
+/// Returns the slot after which the node will shut down, if any.
+pub fn shutdown_slot<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> ShutdownAtSlot {
+    ShutdownAtSlot {
+        slot: *ctx.beacon_chain.shutdown_slot.read(),
+    }
+}
+
+/// Schedules the node to shut down once a slot has ended, or cancels the scheduled shutdown.
+///
+/// Slots which have already ended are rejected.
+pub fn shutdown_at_slot<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<ShutdownAtSlot, ApiError> {
+    let body = req.into_body();
+    let request = serde_json::from_slice::<ShutdownAtSlot>(&body).map_err(|e| {
+        ApiError::BadRequest(format!("Unable to parse JSON into ShutdownAtSlot: {:?}", e))
+    })?;
+
+    if let Some(slot) = request.slot {
+        let current_slot = ctx.beacon_chain.slot()?;
+        if slot < current_slot {
+            return Err(ApiError::BadRequest(format!(
+                "Slot {} has already ended, the current slot is {}",
+                slot, current_slot
+            )));
+        }
+    }
+
+    *ctx.beacon_chain.shutdown_slot.write() = request.slot;
+
+    match request.slot {
+        Some(slot) => info!(ctx.log, "Scheduled shutdown"; "shutdown_after_slot" => slot),
+        None => info!(ctx.log, "Cancelled scheduled shutdown"),
+    }
+
+    Ok(shutdown_slot(ctx))
+}
+
+/// Reloads the configuration of the node, as upon `SIGHUP`, applying the settings which can be
+/// changed without a restart.
+pub fn reload_config<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<ConfigReloadResponse, ApiError> {
+    // This handler runs in a blocking task, whilst the reload is performed by the main thread.
+    let reload = futures::executor::block_on(ctx.executor.reload_config())
+        .map_err(|e| ApiError::ServerError(format!("Unable to reload config: {}", e)))?;
+
+    Ok(ConfigReloadResponse {
+        applied: reload.applied,
+        requires_restart: reload.requires_restart,
+    })
+}
+
+/// Returns the trusted peers, to which connections are always maintained.
+pub fn trusted_peers<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Vec<TrustedPeerInfo> {
+    let peers = ctx.network_globals.peers.read();
+    peers
+        .trusted_peer_ids()
+        .map(|peer_id| TrustedPeerInfo {
+            peer_id: peer_id.to_string(),
+            connected: peers.is_connected(peer_id),
+        })
+        .collect()
+}
+
+/// Adds and removes trusted peers, if permitted by `allow_trusted_peer_updates`.
+///
+/// The request is validated before any change is made. Changes are applied by the network
+/// service, so they may not be visible to an immediate GET.
+pub fn update_trusted_peers<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<(), ApiError> {
+    if !ctx.config.allow_trusted_peer_updates {
+        return Err(ApiError::Forbidden(
+            "Trusted peer updates are not enabled on this beacon node, see \
+            --http-allow-trusted-peer-updates"
+                .to_string(),
+        ));
+    }
+
+    let body = req.into_body();
+    let request = serde_json::from_slice::<TrustedPeersRequest>(&body).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into TrustedPeersRequest: {:?}",
+            e
+        ))
+    })?;
+
+    let to_add = request
+        .add
+        .iter()
+        .map(|peer| peer.parse::<TrustedPeer>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ApiError::BadRequest)?;
+    let to_remove = request
+        .remove
+        .iter()
+        .map(|peer_id| {
+            peer_id
+                .parse::<PeerId>()
+                .map_err(|_| ApiError::BadRequest(format!("Invalid peer ID: {}", peer_id)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let messages = to_remove
+        .into_iter()
+        .map(|peer_id| NetworkMessage::RemoveTrustedPeer { peer_id })
+        .chain(
+            to_add
+                .into_iter()
+                .map(|peer| NetworkMessage::AddTrustedPeer { peer }),
+        );
+    for message in messages {
+        ctx.network_chan.send(message).map_err(|e| {
+            ApiError::ServerError(format!(
+                "Unable to send trusted peers to the network: {:?}",
+                e
+            ))
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Returns the times at which recent blocks and aggregates were first received on gossip, in the
+/// order they were received.
+///
+/// If a `slot` is supplied, only the messages from that slot are returned.
+pub fn observed_gossip<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<ObservedGossipResponse, ApiError> {
+    let query = parse_query::<ObservedGossipQuery, _>(&req)?;
+    let beacon_chain = &ctx.beacon_chain;
+    let gossip_observations = beacon_chain.gossip_observations.read();
+
+    let observations: Box<dyn Iterator<Item = &GossipObservation>> = match query.slot {
+        Some(slot) => Box::new(gossip_observations.iter_slot(slot)),
+        None => Box::new(gossip_observations.iter()),
+    };
+
+    let mut response = ObservedGossipResponse::default();
+
+    for observation in observations {
+        let seen_delay_ms = beacon_chain
+            .slot_clock
+            .start_of(observation.slot)
+            .map(|slot_start| {
+                observation.seen_timestamp.as_millis() as i64 - slot_start.as_millis() as i64
+            });
+
+        match observation.message {
+            GossipMessage::Block {
+                block_root,
+                proposer_index,
+            } => response.blocks.push(ObservedGossipBlock {
+                slot: observation.slot,
+                block_root,
+                proposer_index,
+                seen_delay_ms,
+            }),
+            GossipMessage::Aggregate {
+                attestation_data_root,
+                aggregator_index,
+            } => response.aggregates.push(ObservedGossipAggregate {
+                slot: observation.slot,
+                attestation_data_root,
+                aggregator_index,
+                seen_delay_ms,
+            }),
+        }
+    }
+
+    Ok(response)
+}
+
+/// The number of distinct graffiti returned by `client_diversity`.
+pub const CLIENT_DIVERSITY_TOP_GRAFFITI: usize = 16;
+
+/// Returns an estimate of the proportion of recent blocks proposed by each client, as identified
+/// by the graffiti of the blocks, along with the most common graffiti.
+pub fn client_diversity<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<ClientDiversityResponse, ApiError> {
+    let recent_proposals = ctx.beacon_chain.recent_proposals.read();
+
+    let mut clients = HashMap::<ClientKind, u64>::new();
+    let mut graffiti = HashMap::<String, u64>::new();
+    let mut proposals = 0;
+
+    for proposal in recent_proposals.iter() {
+        *clients
+            .entry(ClientKind::from_graffiti(&proposal.graffiti))
+            .or_default() += 1;
+        *graffiti
+            .entry(graffiti_to_string(&proposal.graffiti))
+            .or_default() += 1;
+        proposals += 1;
+    }
+
+    let mut clients = ClientKind::ALL
+        .iter()
+        .map(|client| {
+            let client_proposals = clients.get(client).copied().unwrap_or(0);
+            ClientProposals {
+                client: client.name().to_string(),
+                proposals: client_proposals,
+                proportion: if proposals > 0 {
+                    client_proposals as f64 / proposals as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+    clients.sort_by(|a, b| b.proposals.cmp(&a.proposals));
+
+    let mut graffiti = graffiti
+        .into_iter()
+        .map(|(graffiti, proposals)| GraffitiProposals {
+            graffiti,
+            proposals,
+        })
+        .collect::<Vec<_>>();
+    graffiti.sort_by(|a, b| {
+        b.proposals
+            .cmp(&a.proposals)
+            .then_with(|| a.graffiti.cmp(&b.graffiti))
+    });
+    graffiti.truncate(CLIENT_DIVERSITY_TOP_GRAFFITI);
+
+    Ok(ClientDiversityResponse {
+        start_slot: recent_proposals.iter().map(|proposal| proposal.slot).min(),
+        end_slot: recent_proposals.iter().map(|proposal| proposal.slot).max(),
+        proposals,
+        clients,
+        graffiti,
+    })
+}
+
 /// Information returned by `peers` and `connected_peers`.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -46,3 +732,15 @@ pub struct Peer<T: EthSpec> {
     /// The PeerInfo associated with the peer.
     peer_info: PeerInfo<T>,
 }
+
+/// Information returned by `peer_db`.
+#[derive(Clone, Debug, Serialize)]
+pub struct PersistedPeerInfo {
+    peer_id: String,
+    /// The base64-encoded ENR used to dial the peer.
+    enr: String,
+    /// The score of the peer when it was last seen.
+    score: f64,
+    /// The time the peer was last seen connected, in seconds since the UNIX epoch.
+    last_seen: u64,
+}
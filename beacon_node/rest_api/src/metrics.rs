@@ -29,6 +29,31 @@ lazy_static! {
         "Duration to process HTTP requests",
         &["endpoint"]
     );
+    pub static ref BEACON_HTTP_API_HEAVY_QUEUE_TIMES: Result<HistogramVec> =
+        try_create_histogram_vec(
+            "beacon_http_api_heavy_queue_times",
+            "Time spent waiting for other requests to expensive endpoints to finish",
+            &["endpoint"]
+        );
+    pub static ref BEACON_HTTP_API_HEAVY_REJECTED_TOTAL: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "beacon_http_api_heavy_rejected_total",
+            "Count of HTTP requests to expensive endpoints rejected due to too many concurrent requests",
+            &["endpoint"]
+        );
+    pub static ref BEACON_HTTP_API_SLOT_OFFSET_SECONDS: Result<HistogramVec> =
+        try_create_histogram_vec(
+            "beacon_http_api_slot_offset_seconds",
+            "Time since the start of the current slot at which responses to duty-critical \
+            HTTP requests were served",
+            &["endpoint"]
+        );
+    pub static ref BEACON_HTTP_API_DEGRADED_ATTESTATION_DATA_TOTAL: Result<IntCounter> =
+        try_create_int_counter(
+            "beacon_http_api_degraded_attestation_data_total",
+            "Count of attestations served from the cache because producing a fresh attestation \
+            exceeded its deadline"
+        );
     pub static ref REQUEST_RESPONSE_TIME: Result<Histogram> = try_create_histogram(
         "http_server_request_duration_seconds",
         "Time taken to build a response to a HTTP request"
@@ -2,10 +2,13 @@ use crate::{ApiError, Context};
 use beacon_chain::BeaconChainTypes;
 use eth2_libp2p::types::SyncState;
 use rest_types::{SyncingResponse, SyncingStatus};
+use slot_clock::SlotClock;
 use std::sync::Arc;
 use types::Slot;
 
 /// Returns a syncing status.
+///
+/// Prior to genesis there is nothing to sync, so the node reports that it is not syncing.
 pub fn syncing<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<SyncingResponse, ApiError> {
     let current_slot = ctx
         .beacon_chain
@@ -13,6 +16,25 @@ pub fn syncing<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<SyncingRespo
         .map_err(|e| ApiError::ServerError(format!("Unable to read head slot: {:?}", e)))?
         .slot;
 
+    let is_pre_genesis = ctx
+        .beacon_chain
+        .slot_clock
+        .is_prior_to_genesis()
+        .ok_or_else(|| ApiError::ServerError("Unable to read slot clock".to_string()))?;
+
+    if is_pre_genesis {
+        return Ok(SyncingResponse {
+            is_syncing: false,
+            is_pre_genesis,
+            is_optimistic: false,
+            sync_status: SyncingStatus {
+                starting_slot: current_slot,
+                current_slot,
+                highest_slot: current_slot,
+            },
+        });
+    }
+
     let (starting_slot, highest_slot) = match ctx.network_globals.sync_state() {
         SyncState::SyncingFinalized {
             start_slot,
@@ -34,6 +56,8 @@ pub fn syncing<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<SyncingRespo
 
     Ok(SyncingResponse {
         is_syncing: ctx.network_globals.is_syncing(),
+        is_pre_genesis,
+        is_optimistic: ctx.beacon_chain.is_optimistic_head()?,
         sync_status,
     })
 }
@@ -0,0 +1,217 @@
+use crate::{metrics, ApiError};
+use hyper::Method;
+use parking_lot::Mutex;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// The time a heavy request may wait for another to finish before it is rejected.
+const HEAVY_REQUEST_QUEUE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The number of seconds a rejected client is asked to wait before retrying.
+const HEAVY_REQUEST_RETRY_AFTER_SECS: u64 = 1;
+
+/// Returns `true` if the route loads whole (and possibly historical) states, which is expensive
+/// enough that too many concurrent requests can monopolize the node.
+///
+/// The validator duties endpoints are not limited, so that validator clients are not refused
+/// their duties whilst states are being downloaded.
+pub fn is_heavy_route(method: &Method, path: &str) -> bool {
+    match (method, path) {
+        (&Method::GET, "/beacon/state")
+        | (&Method::GET, "/beacon/state/genesis")
+        | (&Method::GET, "/lighthouse/genesis_state")
+        | (&Method::GET, "/beacon/validators/all")
+        | (&Method::GET, "/beacon/validators/active")
+        | (&Method::POST, "/beacon/validators")
+        | (&Method::GET, "/beacon/committees")
+        | (&Method::GET, "/beacon/randao")
+        | (&Method::GET, "/consensus/global_votes")
+        | (&Method::POST, "/consensus/individual_votes") => true,
+        _ => false,
+    }
+}
+
+/// Limits the number of heavy requests (see `is_heavy_route`) which are served concurrently.
+pub struct RequestLimiter {
+    semaphore: Semaphore,
+    limits: Mutex<Limits>,
+}
+
+struct Limits {
+    /// The maximum number of heavy requests served concurrently, which may be changed whilst the
+    /// node is running.
+    max_concurrent_heavy_requests: usize,
+    /// The number of permits which must be removed from the semaphore to apply a reduced maximum,
+    /// but which are held by requests being served. Each is forgotten when its request finishes.
+    excess_permits: usize,
+}
+
+impl RequestLimiter {
+    pub fn new(max_concurrent_heavy_requests: usize) -> Self {
+        Self {
+            semaphore: Semaphore::new(max_concurrent_heavy_requests),
+            limits: Mutex::new(Limits {
+                max_concurrent_heavy_requests,
+                excess_permits: 0,
+            }),
+        }
+    }
+
+    /// Returns the maximum number of heavy requests served concurrently.
+    pub fn max_concurrent_heavy_requests(&self) -> usize {
+        self.limits.lock().max_concurrent_heavy_requests
+    }
+
+    /// Changes the maximum number of heavy requests served concurrently.
+    ///
+    /// Requests being served when the maximum is reduced are allowed to finish, so the new maximum
+    /// only applies once enough of them have done so.
+    pub fn set_max_concurrent_heavy_requests(&self, max_concurrent_heavy_requests: usize) {
+        let mut limits = self.limits.lock();
+        let current = limits.max_concurrent_heavy_requests;
+
+        if max_concurrent_heavy_requests > current {
+            // Cancel any reduction which has not yet applied before adding permits.
+            let increase = max_concurrent_heavy_requests - current;
+            let cancelled = std::cmp::min(increase, limits.excess_permits);
+            limits.excess_permits -= cancelled;
+            self.semaphore.add_permits(increase - cancelled);
+        } else if max_concurrent_heavy_requests < current {
+            // Permits can't be removed from a semaphore, so the excess are acquired and never
+            // released. Those held by requests are forgotten when the requests finish.
+            limits.excess_permits += current - max_concurrent_heavy_requests;
+            while limits.excess_permits > 0 {
+                match self.semaphore.try_acquire() {
+                    Ok(permit) => {
+                        permit.forget();
+                        limits.excess_permits -= 1;
+                    }
+                    Err(_) => break,
+                }
+            }
+        }
+
+        limits.max_concurrent_heavy_requests = max_concurrent_heavy_requests;
+    }
+
+    /// Waits for a heavy request to be allowed to proceed. The request may proceed for as long as
+    /// the returned permit is held.
+    ///
+    /// Returns a `ServiceUnavailable` error if the request could not proceed within
+    /// `HEAVY_REQUEST_QUEUE_TIMEOUT`.
+    pub async fn acquire(&self, path: &str) -> Result<HeavyRequestPermit<'_>, ApiError> {
+        let timer = metrics::start_timer_vec(&metrics::BEACON_HTTP_API_HEAVY_QUEUE_TIMES, &[path]);
+
+        let result =
+            tokio::time::timeout(HEAVY_REQUEST_QUEUE_TIMEOUT, self.semaphore.acquire()).await;
+
+        metrics::stop_timer(timer);
+
+        result
+            .map(|permit| HeavyRequestPermit {
+                permit: Some(permit),
+                limiter: self,
+            })
+            .map_err(|_| {
+                metrics::inc_counter_vec(&metrics::BEACON_HTTP_API_HEAVY_REJECTED_TOTAL, &[path]);
+                ApiError::ServiceUnavailable(
+                    "Too many concurrent requests for expensive endpoints, try again later"
+                        .to_string(),
+                    HEAVY_REQUEST_RETRY_AFTER_SECS,
+                )
+            })
+    }
+}
+
+/// Permits a heavy request to proceed until dropped.
+pub struct HeavyRequestPermit<'a> {
+    permit: Option<SemaphorePermit<'a>>,
+    limiter: &'a RequestLimiter,
+}
+
+impl<'a> Drop for HeavyRequestPermit<'a> {
+    fn drop(&mut self) {
+        let mut limits = self.limiter.limits.lock();
+        if let Some(permit) = self.permit.take() {
+            // Apply a pending reduction of the maximum by not releasing the permit.
+            if limits.excess_permits > 0 {
+                permit.forget();
+                limits.excess_permits -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn try_acquire(limiter: &RequestLimiter) -> Option<HeavyRequestPermit> {
+        limiter
+            .semaphore
+            .try_acquire()
+            .ok()
+            .map(|permit| HeavyRequestPermit {
+                permit: Some(permit),
+                limiter,
+            })
+    }
+
+    #[test]
+    fn shrink_whilst_serving() {
+        let limiter = RequestLimiter::new(2);
+        let first = try_acquire(&limiter).expect("should acquire first permit");
+        let second = try_acquire(&limiter).expect("should acquire second permit");
+
+        limiter.set_max_concurrent_heavy_requests(1);
+        assert_eq!(limiter.max_concurrent_heavy_requests(), 1);
+
+        drop(first);
+        assert!(
+            try_acquire(&limiter).is_none(),
+            "should not exceed the reduced maximum"
+        );
+
+        drop(second);
+        let third = try_acquire(&limiter).expect("should acquire a permit once below the maximum");
+        assert!(try_acquire(&limiter).is_none());
+        drop(third);
+        assert_eq!(limiter.semaphore.available_permits(), 1);
+    }
+
+    #[test]
+    fn shrink_then_grow_whilst_serving() {
+        let limiter = RequestLimiter::new(4);
+        let permits = (0..4)
+            .map(|_| try_acquire(&limiter).expect("should acquire permit"))
+            .collect::<Vec<_>>();
+
+        // The reduction can't apply until the requests finish, and is then partly cancelled.
+        limiter.set_max_concurrent_heavy_requests(1);
+        limiter.set_max_concurrent_heavy_requests(3);
+        drop(permits);
+
+        assert_eq!(limiter.max_concurrent_heavy_requests(), 3);
+        assert_eq!(limiter.semaphore.available_permits(), 3);
+
+        // Growing beyond the original maximum cancels the reduction entirely.
+        let permits = (0..3)
+            .map(|_| try_acquire(&limiter).expect("should acquire permit"))
+            .collect::<Vec<_>>();
+        limiter.set_max_concurrent_heavy_requests(1);
+        limiter.set_max_concurrent_heavy_requests(5);
+        drop(permits);
+
+        assert_eq!(limiter.semaphore.available_permits(), 5);
+    }
+
+    #[test]
+    fn shrink_whilst_idle() {
+        let limiter = RequestLimiter::new(4);
+        limiter.set_max_concurrent_heavy_requests(2);
+        assert_eq!(limiter.semaphore.available_permits(), 2);
+
+        limiter.set_max_concurrent_heavy_requests(3);
+        assert_eq!(limiter.semaphore.available_permits(), 3);
+    }
+}
@@ -1,8 +1,12 @@
+use crate::attestation_cache::AttestationCache;
+use crate::helpers::{
+    check_chain_started, check_chain_synced, check_head_not_optimistic, database_backup_path,
+};
+use crate::request_limiter::{is_heavy_route, RequestLimiter};
 use crate::{
     beacon, config::Config, consensus, lighthouse, metrics, node, validator, NetworkChannel,
 };
-use beacon_chain::{BeaconChain, BeaconChainTypes};
-use bus::Bus;
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use environment::TaskExecutor;
 use eth2_config::Eth2Config;
 use eth2_libp2p::{NetworkGlobals, PeerId};
@@ -11,12 +15,17 @@ use hyper::{Body, Method, Request, Response};
 use lighthouse_version::version_with_platform;
 use operation_pool::PersistedOperationPool;
 use parking_lot::Mutex;
-use rest_types::{ApiError, Handler, Health};
+use rest_types::{
+    ApiError, DatabaseBackupRequest, DatabaseBackupResponse, DepositContract,
+    ForkChoicePruneResponse, Handler, Health, SpecConfig,
+};
 use slog::debug;
+use slot_clock::SlotClock;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
-use types::{EthSpec, SignedBeaconBlockHash};
+use store::Error as StoreError;
+use types::{EthSpec, Fork, YamlConfig};
 
 pub struct Context<T: BeaconChainTypes> {
     pub executor: TaskExecutor,
@@ -24,11 +33,80 @@ pub struct Context<T: BeaconChainTypes> {
     pub beacon_chain: Arc<BeaconChain<T>>,
     pub network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     pub network_chan: NetworkChannel<T::EthSpec>,
+    pub boot_enrs: Vec<String>,
+    pub deposit_contract: DepositContract,
     pub eth2_config: Arc<Eth2Config>,
     pub log: slog::Logger,
     pub db_path: PathBuf,
     pub freezer_db_path: PathBuf,
-    pub events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+    pub request_limiter: Arc<RequestLimiter>,
+    /// The SSZ encoding of the genesis state, once it has been requested.
+    pub genesis_state_ssz: Mutex<Option<Arc<Vec<u8>>>>,
+    /// Attestations produced for the latest slot, served when producing another is too slow.
+    pub attestation_cache: AttestationCache<T::EthSpec>,
+}
+
+/// Returns `true` if the response to a request for `path` is used to perform a validator duty
+/// within a slot, and so is worth timing relative to the start of the slot.
+fn is_slot_timed_route(method: &Method, path: &str) -> bool {
+    *method == Method::GET
+        && matches!(
+            path,
+            "/validator/attestation" | "/validator/aggregate_attestation" | "/validator/block"
+        )
+}
+
+/// Returns `true` if `path` serves or accepts the duties of validators, which cannot be performed
+/// until the chain has started.
+fn requires_started_chain(path: &str) -> bool {
+    path.starts_with("/validator/") || path == "/lighthouse/validator/duties"
+}
+
+/// Returns `true` if the response to a request for `path` is computed from the head, and so is
+/// likely to be wrong whilst the node is syncing far behind the present slot.
+fn requires_synced_chain(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::GET => matches!(
+            path,
+            "/validator/duties/all"
+                | "/validator/duties/active"
+                | "/validator/block"
+                | "/validator/attestation"
+                | "/validator/aggregate_attestation"
+                | "/lighthouse/validator/duties"
+        ),
+        Method::POST => path == "/validator/duties",
+        _ => false,
+    }
+}
+
+/// Returns the maximum size of the body of a request for `path`, in bytes.
+///
+/// Requests containing a block are permitted to be larger, since blocks may be large on networks
+/// with non-standard configurations.
+fn max_body_size(method: &Method, path: &str, config: &Config) -> usize {
+    if *method == Method::POST && path == "/validator/block" {
+        config.max_block_body_size
+    } else {
+        config.max_body_size
+    }
+}
+
+/// Records how far into the current slot the response to a request for `path` was served.
+fn observe_slot_offset<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>, path: &str) {
+    let slot_clock = &beacon_chain.slot_clock;
+    let offset = slot_clock.now_duration().and_then(|now| {
+        let slot_start = slot_clock.start_of(slot_clock.slot_of(now)?)?;
+        now.checked_sub(slot_start)
+    });
+
+    if let Some(offset) = offset {
+        metrics::observe_vec(
+            &metrics::BEACON_HTTP_API_SLOT_OFFSET_SECONDS,
+            &[path],
+            offset.as_secs_f64(),
+        );
+    }
 }
 
 pub async fn on_http_request<T: BeaconChainTypes>(
@@ -43,11 +121,20 @@ pub async fn on_http_request<T: BeaconChainTypes>(
     let received_instant = Instant::now();
     let log = ctx.log.clone();
     let allow_origin = ctx.config.allow_origin.clone();
+    let slot_timed_chain = if is_slot_timed_route(req.method(), &path) {
+        Some(ctx.beacon_chain.clone())
+    } else {
+        None
+    };
 
     match route(req, ctx).await {
         Ok(mut response) => {
             metrics::inc_counter_vec(&metrics::BEACON_HTTP_API_SUCCESS_TOTAL, &[&path]);
 
+            if let Some(beacon_chain) = slot_timed_chain {
+                observe_slot_offset(&beacon_chain, &path);
+            }
+
             if allow_origin != "" {
                 let headers = response.headers_mut();
                 headers.insert(
@@ -88,7 +175,36 @@ async fn route<T: BeaconChainTypes>(
     let ctx = ctx.clone();
     let method = req.method().clone();
     let executor = ctx.executor.clone();
-    let handler = Handler::new(req, ctx, executor)?;
+
+    if let Some(pattern) = ctx.config.disabled_route_pattern(&path) {
+        return Err(ApiError::Forbidden(format!(
+            "The route {} is disabled on this node (matched --http-disabled-routes {})",
+            path, pattern
+        )));
+    }
+
+    // Hold a permit for expensive routes until the response has been built.
+    let limiter_ctx = ctx.clone();
+    let _permit = if is_heavy_route(&method, &path) {
+        Some(limiter_ctx.request_limiter.acquire(&path).await?)
+    } else {
+        None
+    };
+
+    if requires_started_chain(&path) {
+        check_chain_started(&ctx.beacon_chain)?;
+    }
+
+    if requires_synced_chain(&method, &path) {
+        if !ctx.config.allow_unsynced {
+            check_chain_synced(&ctx.beacon_chain, ctx.network_globals.is_syncing())?;
+        }
+        // Duties are never served from an optimistic head, even if unsynced nodes are allowed.
+        check_head_not_optimistic(&ctx.beacon_chain)?;
+    }
+
+    let body_limit = max_body_size(&method, &path, &ctx.config);
+    let handler = Handler::new(req, ctx, executor)?.max_body_size(body_limit);
 
     match (method, path.as_ref()) {
         (Method::GET, "/node/version") => handler
@@ -152,6 +268,10 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(beacon::get_block_root)
             .await?
             .all_encodings(),
+        (Method::GET, "/beacon/headers") => handler
+            .in_blocking_task(beacon::get_headers)
+            .await?
+            .all_encodings(),
         (Method::GET, "/beacon/fork") => handler
             .in_blocking_task(|_, ctx| Ok(ctx.beacon_chain.head_info()?.fork))
             .await?
@@ -159,6 +279,11 @@ async fn route<T: BeaconChainTypes>(
         (Method::GET, "/beacon/fork/stream") => {
             handler.sse_stream(|_, ctx| beacon::stream_forks(ctx)).await
         }
+        (Method::GET, "/beacon/block/stream") => {
+            handler
+                .sse_stream(|_, ctx| beacon::stream_blocks(ctx))
+                .await
+        }
         (Method::GET, "/beacon/genesis_time") => handler
             .in_blocking_task(|_, ctx| Ok(ctx.beacon_chain.head_info()?.genesis_time))
             .await?
@@ -196,10 +321,18 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(|_, ctx| beacon::get_genesis_state(ctx))
             .await?
             .all_encodings(),
+        (Method::GET, "/beacon/randao") => handler
+            .in_blocking_task(beacon::get_randao_mix)
+            .await?
+            .all_encodings(),
         (Method::GET, "/beacon/committees") => handler
             .in_blocking_task(beacon::get_committees)
             .await?
             .all_encodings(),
+        (Method::GET, "/beacon/pool/attestations") => handler
+            .in_blocking_task(beacon::get_pool_attestations)
+            .await?
+            .all_encodings(),
         (Method::POST, "/beacon/proposer_slashing") => handler
             .allow_body()
             .in_blocking_task(beacon::proposer_slashing)
@@ -279,6 +412,31 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(|_, ctx| Ok(ctx.eth2_config.as_ref().clone()))
             .await?
             .serde_encodings(),
+        (Method::GET, "/spec/config") => handler
+            .in_blocking_task(|_, ctx| {
+                Ok(SpecConfig {
+                    config_name: ctx.eth2_config.spec_constants.clone(),
+                    yaml_config: YamlConfig::from_spec::<T::EthSpec>(&ctx.beacon_chain.spec),
+                })
+            })
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/spec/deposit_contract") => handler
+            .in_core_task(|_, ctx| Ok(ctx.deposit_contract.clone()))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/spec/fork_schedule") => handler
+            .in_core_task(|_, ctx| {
+                // Only phase 0 is supported, so the genesis fork is the only fork.
+                let genesis_fork_version = ctx.beacon_chain.spec.genesis_fork_version;
+                Ok(vec![Fork {
+                    previous_version: genesis_fork_version,
+                    current_version: genesis_fork_version,
+                    epoch: T::EthSpec::genesis_epoch(),
+                }])
+            })
+            .await?
+            .serde_encodings(),
         (Method::GET, "/advanced/fork_choice") => handler
             .in_blocking_task(|_, ctx| {
                 Ok(ctx
@@ -291,6 +449,57 @@ async fn route<T: BeaconChainTypes>(
             })
             .await?
             .serde_encodings(),
+        (Method::POST, "/advanced/fork_choice/prune") => handler
+            .in_blocking_task(|_, ctx| {
+                let removed_nodes = ctx.beacon_chain.prune_fork_choice()?;
+                let remaining_nodes = ctx.beacon_chain.fork_choice.read().proto_array().len();
+
+                Ok(ForkChoicePruneResponse {
+                    removed_nodes: removed_nodes as u64,
+                    remaining_nodes: remaining_nodes as u64,
+                })
+            })
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/advanced/database/backup") => handler
+            .allow_body()
+            .in_blocking_task(|req, ctx| {
+                let backup_dir = ctx.config.database_backup_dir.as_ref().ok_or_else(|| {
+                    ApiError::Forbidden(
+                        "Database backups are disabled on this node (see --database-backup-dir)"
+                            .to_string(),
+                    )
+                })?;
+
+                let request = serde_json::from_slice::<DatabaseBackupRequest>(&req.into_body())
+                    .map_err(|e| {
+                        ApiError::BadRequest(format!(
+                            "Unable to parse JSON into DatabaseBackupRequest: {:?}",
+                            e
+                        ))
+                    })?;
+                let output = database_backup_path(backup_dir, &request.name)?;
+
+                let (hot_entries, cold_entries) = ctx
+                    .beacon_chain
+                    .backup_database(&output)
+                    .map_err(|e| match e {
+                        BeaconChainError::DBError(StoreError::BackupPathExists(path)) => {
+                            ApiError::BadRequest(format!(
+                                "Backup destination already exists: {:?}",
+                                path
+                            ))
+                        }
+                        e => ApiError::ServerError(format!("Unable to back up database: {:?}", e)),
+                    })?;
+
+                Ok(DatabaseBackupResponse {
+                    hot_entries: hot_entries as u64,
+                    cold_entries: cold_entries as u64,
+                })
+            })
+            .await?
+            .serde_encodings(),
         (Method::GET, "/advanced/operation_pool") => handler
             .in_blocking_task(|_, ctx| {
                 Ok(PersistedOperationPool::from_operation_pool(
@@ -315,6 +524,85 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(|_, ctx| lighthouse::connected_peers(ctx))
             .await?
             .serde_encodings(),
+        (Method::GET, "/lighthouse/peer_db") => handler
+            .in_blocking_task(|_, ctx| lighthouse::peer_db(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/network/nat") => handler
+            .in_core_task(|_, ctx| Ok(ctx.network_globals.nat_status()))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/eth1/deposit_snapshot") => handler
+            .in_blocking_task(|_, ctx| lighthouse::deposit_snapshot(ctx))
+            .await?
+            .all_encodings(),
+        (Method::GET, "/lighthouse/genesis_state") => handler
+            .in_blocking_task(lighthouse::genesis_state)
+            .await?
+            .ranged_ssz_encoding(),
+        (Method::GET, "/lighthouse/validator/duties") => handler
+            .in_blocking_task(lighthouse::validator_duties)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/debug/block_timings") => handler
+            .in_blocking_task(lighthouse::block_timings)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/debug/observed_gossip") => handler
+            .in_blocking_task(lighthouse::observed_gossip)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/analysis/client_diversity") => handler
+            .in_blocking_task(|_, ctx| lighthouse::client_diversity(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/debug/attestation_pools") => handler
+            .in_blocking_task(|_, ctx| lighthouse::attestation_pools(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/debug/caches") => handler
+            .in_blocking_task(|_, ctx| lighthouse::caches(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/debug/caches/drop") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::drop_caches)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/admin/shutdown_at_slot") => handler
+            .in_blocking_task(|_, ctx| Ok(lighthouse::shutdown_slot(ctx)))
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/admin/shutdown_at_slot") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::shutdown_at_slot)
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/admin/reload-config") => handler
+            .in_blocking_task(|_, ctx| lighthouse::reload_config(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/admin/trusted_peers") => handler
+            .in_blocking_task(|_, ctx| Ok(lighthouse::trusted_peers(ctx)))
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/admin/trusted_peers") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::update_trusted_peers)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/config") => handler
+            .in_blocking_task(|_, ctx| Ok(lighthouse::node_config(ctx)))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/ws_period") => handler
+            .in_blocking_task(|_, ctx| lighthouse::ws_period(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/testnet") => handler
+            .in_blocking_task(|_, ctx| lighthouse::testnet(ctx))
+            .await?
+            .serde_encodings(),
         _ => Err(ApiError::NotFound(
             "Request path and/or method not found.".to_owned(),
         )),
@@ -1,7 +1,6 @@
-use crate::helpers::{parse_committee_index, parse_epoch, parse_hex_ssz_bytes, parse_slot};
 use crate::ApiError;
 use hyper::Request;
-use types::{AttestationData, CommitteeIndex, Epoch, Signature, Slot};
+use serde::de::DeserializeOwned;
 
 /// Provides handy functions for parsing the query parameters of a URL.
 
@@ -84,36 +83,14 @@ impl<'a> UrlQuery<'a> {
             .collect();
         Ok(queries)
     }
+}
 
-    /// Returns the value of the first occurrence of the `epoch` key.
-    pub fn epoch(self) -> Result<Epoch, ApiError> {
-        self.first_of(&["epoch"])
-            .and_then(|(_key, value)| parse_epoch(&value))
-    }
-
-    /// Returns the value of the first occurrence of the `slot` key.
-    pub fn slot(self) -> Result<Slot, ApiError> {
-        self.first_of(&["slot"])
-            .and_then(|(_key, value)| parse_slot(&value))
-    }
-
-    /// Returns the value of the first occurrence of the `committee_index` key.
-    pub fn committee_index(self) -> Result<CommitteeIndex, ApiError> {
-        self.first_of(&["committee_index"])
-            .and_then(|(_key, value)| parse_committee_index(&value))
-    }
-
-    /// Returns the value of the first occurrence of the `randao_reveal` key.
-    pub fn randao_reveal(self) -> Result<Signature, ApiError> {
-        self.first_of(&["randao_reveal"])
-            .and_then(|(_key, value)| parse_hex_ssz_bytes(&value))
-    }
-
-    /// Returns the value of the first occurrence of the `attestation_data` key.
-    pub fn attestation_data(self) -> Result<AttestationData, ApiError> {
-        self.first_of(&["attestation_data"])
-            .and_then(|(_key, value)| parse_hex_ssz_bytes(&value))
-    }
+/// Parses the query parameters of `req` into a typed query (e.g., `rest_types::EpochQuery`).
+///
+/// The same types are used by `remote_beacon_node` to build the query, keeping both sides in sync.
+pub fn parse_query<Q: DeserializeOwned, B>(req: &Request<B>) -> Result<Q, ApiError> {
+    serde_urlencoded::from_str(req.uri().query().unwrap_or(""))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid query parameters: {}", e)))
 }
 
 #[cfg(test)]
@@ -132,6 +109,124 @@ mod test {
         assert!(get_result("http://cat.io/", "").is_err());
     }
 
+    #[test]
+    fn parse_query_typed() {
+        use rest_types::{
+            AttestationQuery, BatchedDutiesQuery, EpochQuery, SlotOrRootQuery, SlotQuery,
+            ValidatorsQuery,
+        };
+        use types::{Epoch, Hash256, Slot};
+
+        let get_request = |uri: &str| Request::builder().uri(uri).body(()).unwrap();
+
+        assert_eq!(
+            parse_query::<EpochQuery, _>(&get_request("http://cat.io/?epoch=42")),
+            Ok(EpochQuery {
+                epoch: Epoch::new(42)
+            })
+        );
+        assert!(parse_query::<EpochQuery, _>(&get_request("http://cat.io/?epoch=cats")).is_err());
+        assert!(parse_query::<EpochQuery, _>(&get_request("http://cat.io/")).is_err());
+        assert_eq!(
+            parse_query::<AttestationQuery, _>(&get_request(
+                "http://cat.io/?committee_index=3&slot=7"
+            )),
+            Ok(AttestationQuery {
+                slot: Slot::new(7),
+                committee_index: 3,
+            })
+        );
+        assert_eq!(
+            parse_query::<ValidatorsQuery, _>(&get_request("http://cat.io/")),
+            Ok(ValidatorsQuery::default())
+        );
+        assert_eq!(
+            parse_query::<ValidatorsQuery, _>(&get_request("http://cat.io/?offset=10&limit=5")),
+            Ok(ValidatorsQuery {
+                state_root: None,
+                offset: Some(10),
+                limit: Some(5),
+            })
+        );
+        assert_eq!(
+            parse_query::<ValidatorsQuery, _>(&get_request(&format!(
+                "http://cat.io/?state_root=0x{}",
+                "00".repeat(31) + "2a"
+            ))),
+            Ok(ValidatorsQuery {
+                state_root: Some(Hash256::from_low_u64_be(42)),
+                ..ValidatorsQuery::default()
+            })
+        );
+        assert_eq!(
+            parse_query::<SlotQuery, _>(&get_request("http://cat.io/?slot=10000000")),
+            Ok(SlotQuery {
+                slot: Slot::new(10_000_000)
+            })
+        );
+        assert!(parse_query::<SlotQuery, _>(&get_request("http://cat.io/?slot=cats")).is_err());
+        assert_eq!(
+            parse_query::<SlotOrRootQuery, _>(&get_request("http://cat.io/?slot=42")),
+            Ok(SlotOrRootQuery::slot(Slot::new(42)))
+        );
+        assert_eq!(
+            parse_query::<SlotOrRootQuery, _>(&get_request("http://cat.io/")),
+            Ok(SlotOrRootQuery::default())
+        );
+        assert_eq!(
+            parse_query::<BatchedDutiesQuery, _>(&get_request(
+                "http://cat.io/?epochs=5,6,7&indices=1,42"
+            )),
+            Ok(BatchedDutiesQuery {
+                epochs: vec![Epoch::new(5), Epoch::new(6), Epoch::new(7)],
+                indices: vec![1, 42],
+            })
+        );
+        assert_eq!(
+            parse_query::<BatchedDutiesQuery, _>(&get_request("http://cat.io/?epochs=5&indices=")),
+            Ok(BatchedDutiesQuery {
+                epochs: vec![Epoch::new(5)],
+                indices: vec![],
+            })
+        );
+        assert!(parse_query::<BatchedDutiesQuery, _>(&get_request(
+            "http://cat.io/?epochs=5,,6&indices=1"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn parse_query_malformed_identifiers() {
+        use rest_types::{SlotOrRootQuery, SlotQuery};
+
+        let get_error = |uri: &str| -> String {
+            match parse_query::<SlotOrRootQuery, _>(&Request::builder().uri(uri).body(()).unwrap())
+            {
+                Err(ApiError::BadRequest(message)) => message,
+                other => panic!("{} should be a bad request, got {:?}", uri, other),
+            }
+        };
+
+        assert!(get_error("http://cat.io/?slot=head").contains("unknown slot keyword"));
+        assert!(
+            get_error("http://cat.io/?slot=-1").contains("unexpected character '-' at position 0")
+        );
+        assert!(get_error("http://cat.io/?root=42").contains("missing 0x prefix"));
+        assert!(get_error("http://cat.io/?root=0x42")
+            .contains("expected 64 hex characters after the 0x prefix, found 2"));
+        assert!(
+            get_error(&format!("http://cat.io/?root=0x{}zz", "00".repeat(31)))
+                .contains("invalid hex character 'z' at position 64")
+        );
+        assert!(parse_query::<SlotQuery, _>(
+            &Request::builder()
+                .uri("http://cat.io/?slot=0x2a")
+                .body(())
+                .unwrap()
+        )
+        .is_err());
+    }
+
     #[test]
     fn first_of() {
         let url = url::Url::parse("http://lighthouse.io/cats?a=42&b=12&c=100").unwrap();
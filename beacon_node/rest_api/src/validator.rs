@@ -1,22 +1,35 @@
-use crate::helpers::{parse_hex_ssz_bytes, publish_beacon_block_to_network};
-use crate::{ApiError, Context, NetworkChannel, UrlQuery};
+use crate::facade::{self, DutiesSelection};
+use crate::helpers::{decode_body, publish_beacon_block_to_network};
+use crate::{metrics, parse_query, ApiError, Context, NetworkChannel};
 use beacon_chain::{
     attestation_verification::Error as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
-    BlockError, ForkChoiceError, StateSkipConfig,
+    BlockError, BlockProductionError, ForkChoiceError, ProduceBlockVerification,
 };
-use bls::PublicKeyBytes;
 use eth2_libp2p::PubsubMessage;
 use hyper::Request;
 use network::NetworkMessage;
-use rest_types::{ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorSubscription};
+use rest_types::{
+    AggregateAttestationQuery, AttestationQuery, BlockProductionQuery, EpochQuery,
+    PoolSubmissionResponse, ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorSubscription,
+};
 use slog::{error, info, trace, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::Arc;
+use tree_hash::TreeHash;
 use types::beacon_state::EthSpec;
 use types::{
-    Attestation, AttestationData, BeaconBlock, BeaconState, Epoch, RelativeEpoch, SelectionProof,
-    SignedAggregateAndProof, SignedBeaconBlock, SubnetId,
+    Attestation, AttestationData, BeaconBlock, SignedAggregateAndProof, SignedBeaconBlock, SubnetId,
 };
 
+/// The number of epochs the head may be behind the slot of a requested attestation, before
+/// attesting is refused.
+const ATTESTATION_MAX_HEAD_AGE_EPOCHS: u64 = 2;
+
+/// Producing an attestation may take at most `1 / ATTESTATION_DEADLINE_QUOTIENT` of a slot before
+/// a cached attestation is served instead.
+const ATTESTATION_DEADLINE_QUOTIENT: u32 = 6;
+
 /// HTTP Handler to retrieve the duties for a set of validators during a particular epoch. This
 /// method allows for collecting bulk sets of validator duties without risking exceeding the max
 /// URL length with query pairs.
@@ -34,16 +47,22 @@ pub fn post_validator_duties<T: BeaconChainTypes>(
             ))
         })
         .and_then(|bulk_request| {
-            return_validator_duties(
-                &ctx.beacon_chain.clone(),
+            facade::validator_duties(
+                &ctx.beacon_chain,
+                &ctx.config,
                 bulk_request.epoch,
-                bulk_request.pubkeys.into_iter().map(Into::into).collect(),
+                DutiesSelection::Pubkeys(
+                    bulk_request.pubkeys.into_iter().map(Into::into).collect(),
+                ),
             )
         })
 }
 
 /// HTTP Handler to retrieve subscriptions for a set of validators. This allows the node to
 /// organise peer discovery and topic subscription for known validators.
+///
+/// Subscriptions of aggregators are also registered with the beacon chain, so that it retains the
+/// attestations they will aggregate.
 pub fn post_validator_subscriptions<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
@@ -58,6 +77,16 @@ pub fn post_validator_subscriptions<T: BeaconChainTypes>(
             ))
         })
         .and_then(move |subscriptions: Vec<ValidatorSubscription>| {
+            subscriptions
+                .iter()
+                .filter(|subscription| subscription.is_aggregator)
+                .for_each(|subscription| {
+                    ctx.beacon_chain.register_aggregator_duty(
+                        subscription.slot,
+                        subscription.attestation_committee_index,
+                    )
+                });
+
             ctx.network_chan
                 .send(NetworkMessage::Subscribe { subscriptions })
                 .map_err(|e| {
@@ -75,19 +104,9 @@ pub fn get_all_validator_duties<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
-
-    let epoch = query.epoch()?;
-
-    let state = get_state_for_epoch(&ctx.beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+    let epoch = parse_query::<EpochQuery, _>(&req)?.epoch;
 
-    let validator_pubkeys = state
-        .validators
-        .iter()
-        .map(|validator| validator.pubkey.clone())
-        .collect();
-
-    return_validator_duties(&ctx.beacon_chain, epoch, validator_pubkeys)
+    facade::validator_duties(&ctx.beacon_chain, &ctx.config, epoch, DutiesSelection::All)
 }
 
 /// HTTP Handler to retrieve all active validator duties for the given epoch.
@@ -95,167 +114,14 @@ pub fn get_active_validator_duties<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
-
-    let epoch = query.epoch()?;
-
-    let state = get_state_for_epoch(&ctx.beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
-
-    let validator_pubkeys = state
-        .validators
-        .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()))
-        .map(|validator| validator.pubkey.clone())
-        .collect();
-
-    return_validator_duties(&ctx.beacon_chain, epoch, validator_pubkeys)
-}
-
-/// Helper function to return the state that can be used to determine the duties for some `epoch`.
-pub fn get_state_for_epoch<T: BeaconChainTypes>(
-    beacon_chain: &BeaconChain<T>,
-    epoch: Epoch,
-    config: StateSkipConfig,
-) -> Result<BeaconState<T::EthSpec>, ApiError> {
-    let slots_per_epoch = T::EthSpec::slots_per_epoch();
-    let head = beacon_chain.head()?;
-    let current_epoch = beacon_chain.epoch()?;
-    let head_epoch = head.beacon_state.current_epoch();
-
-    if head_epoch == current_epoch && RelativeEpoch::from_epoch(current_epoch, epoch).is_ok() {
-        Ok(head.beacon_state)
-    } else {
-        // If epoch is ahead of current epoch, then it should be a "next epoch" request for
-        // attestation duties. So, go to the start slot of the epoch prior to that,
-        // which should be just the next wall-clock epoch.
-        let slot = if epoch > current_epoch {
-            (epoch - 1).start_slot(slots_per_epoch)
-        }
-        // Otherwise, go to the start of the request epoch.
-        else {
-            epoch.start_slot(slots_per_epoch)
-        };
-
-        beacon_chain.state_at_slot(slot, config).map_err(|e| {
-            ApiError::ServerError(format!("Unable to load state for epoch {}: {:?}", epoch, e))
-        })
-    }
-}
-
-/// Helper function to get the duties for some `validator_pubkeys` in some `epoch`.
-fn return_validator_duties<T: BeaconChainTypes>(
-    beacon_chain: &BeaconChain<T>,
-    epoch: Epoch,
-    validator_pubkeys: Vec<PublicKeyBytes>,
-) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
-    let mut state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
-
-    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)
-        .map_err(|_| ApiError::ServerError(String::from("Loaded state is in the wrong epoch")))?;
-
-    state
-        .build_committee_cache(relative_epoch, &beacon_chain.spec)
-        .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
-    state
-        .update_pubkey_cache()
-        .map_err(|e| ApiError::ServerError(format!("Unable to build pubkey cache: {:?}", e)))?;
-
-    // Get a list of all validators for this epoch.
-    //
-    // Used for quickly determining the slot for a proposer.
-    let validator_proposers = if epoch == state.current_epoch() {
-        Some(
-            epoch
-                .slot_iter(T::EthSpec::slots_per_epoch())
-                .map(|slot| {
-                    state
-                        .get_beacon_proposer_index(slot, &beacon_chain.spec)
-                        .map(|i| (i, slot))
-                        .map_err(|e| {
-                            ApiError::ServerError(format!(
-                                "Unable to get proposer index for validator: {:?}",
-                                e
-                            ))
-                        })
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        )
-    } else {
-        None
-    };
-
-    validator_pubkeys
-        .into_iter()
-        .map(|validator_pubkey| {
-            // The `beacon_chain` can return a validator index that does not exist in all states.
-            // Therefore, we must check to ensure that the validator index is valid for our
-            // `state`.
-            let validator_index = beacon_chain
-                .validator_index(&validator_pubkey)
-                .map_err(|e| {
-                    ApiError::ServerError(format!("Unable to get validator index: {:?}", e))
-                })?
-                .filter(|i| *i < state.validators.len());
-
-            if let Some(validator_index) = validator_index {
-                let duties = state
-                    .get_attestation_duties(validator_index, relative_epoch)
-                    .map_err(|e| {
-                        ApiError::ServerError(format!(
-                            "Unable to obtain attestation duties: {:?}",
-                            e
-                        ))
-                    })?;
-
-                let committee_count_at_slot = duties
-                    .map(|d| state.get_committee_count_at_slot(d.slot))
-                    .transpose()
-                    .map_err(|e| {
-                        ApiError::ServerError(format!(
-                            "Unable to find committee count at slot: {:?}",
-                            e
-                        ))
-                    })?;
-
-                let aggregator_modulo = duties
-                    .map(|duties| SelectionProof::modulo(duties.committee_len, &beacon_chain.spec))
-                    .transpose()
-                    .map_err(|e| {
-                        ApiError::ServerError(format!("Unable to find modulo: {:?}", e))
-                    })?;
-
-                let block_proposal_slots = validator_proposers.as_ref().map(|proposers| {
-                    proposers
-                        .iter()
-                        .filter(|(i, _slot)| validator_index == *i)
-                        .map(|(_i, slot)| *slot)
-                        .collect()
-                });
-
-                Ok(ValidatorDutyBytes {
-                    validator_pubkey,
-                    validator_index: Some(validator_index as u64),
-                    attestation_slot: duties.map(|d| d.slot),
-                    attestation_committee_index: duties.map(|d| d.index),
-                    committee_count_at_slot,
-                    attestation_committee_position: duties.map(|d| d.committee_position),
-                    block_proposal_slots,
-                    aggregator_modulo,
-                })
-            } else {
-                Ok(ValidatorDutyBytes {
-                    validator_pubkey,
-                    validator_index: None,
-                    attestation_slot: None,
-                    attestation_committee_index: None,
-                    attestation_committee_position: None,
-                    block_proposal_slots: None,
-                    committee_count_at_slot: None,
-                    aggregator_modulo: None,
-                })
-            }
-        })
-        .collect::<Result<Vec<_>, ApiError>>()
+    let epoch = parse_query::<EpochQuery, _>(&req)?.epoch;
+
+    facade::validator_duties(
+        &ctx.beacon_chain,
+        &ctx.config,
+        epoch,
+        DutiesSelection::Active,
+    )
 }
 
 /// HTTP Handler to produce a new BeaconBlock from the current state, ready to be signed by a validator.
@@ -263,133 +129,274 @@ pub fn get_new_beacon_block<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<BeaconBlock<T::EthSpec>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
+    let query: BlockProductionQuery = parse_query(&req)?;
+
+    let verification = if query.skip_randao_verification {
+        if !ctx.config.allow_skip_randao_verification {
+            return Err(ApiError::BadRequest(
+                "skip_randao_verification is not enabled on this beacon node, see \
+                --http-allow-skip-randao-verification"
+                    .to_string(),
+            ));
+        }
 
-    let slot = query.slot()?;
-    let randao_reveal = query.randao_reveal()?;
+        if !query.randao_reveal.is_empty() {
+            return Err(ApiError::BadRequest(
+                "randao_reveal must be empty when skip_randao_verification is set".to_string(),
+            ));
+        }
 
-    let validator_graffiti = if let Some((_key, value)) = query.first_of_opt(&["graffiti"]) {
-        Some(parse_hex_ssz_bytes(&value)?)
+        ProduceBlockVerification::NoVerification
     } else {
-        None
+        ProduceBlockVerification::VerifyRandao
     };
 
     let (new_block, _state) = ctx
         .beacon_chain
-        .produce_block(randao_reveal, slot, validator_graffiti)
-        .map_err(|e| {
-            error!(
-                ctx.log,
-                "Error whilst producing block";
-                "error" => format!("{:?}", e)
-            );
+        .produce_block_with_verification(
+            query.randao_reveal,
+            query.slot,
+            query.graffiti,
+            verification,
+        )
+        .map_err(|e| match e {
+            BlockProductionError::InvalidRandaoReveal => {
+                ApiError::BadRequest("randao_reveal is invalid".to_string())
+            }
+            e => {
+                error!(
+                    ctx.log,
+                    "Error whilst producing block";
+                    "error" => format!("{:?}", e)
+                );
 
-            ApiError::ServerError(format!(
-                "Beacon node is not able to produce a block: {:?}",
-                e
-            ))
+                ApiError::ServerError(format!(
+                    "Beacon node is not able to produce a block: {:?}",
+                    e
+                ))
+            }
         })?;
 
     Ok(new_block)
 }
 
 /// HTTP Handler to publish a SignedBeaconBlock, which has been signed by a validator.
+///
+/// Blocks which pass gossip verification are published to the network before they are imported,
+/// so a block which fails import is still broadcast. This is signalled to the caller with a 202.
+/// Blocks which fail gossip verification are not published and a 400 is returned.
+///
+/// Publishing a block again, e.g. when retrying or via another node, is not an error. A block
+/// which has already been imported returns a 200, and a block whose proposal has been seen but not
+/// imported returns a 202.
 pub fn publish_beacon_block<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<(), ApiError> {
-    let body = req.into_body();
+    let block: SignedBeaconBlock<T::EthSpec> =
+        decode_body(&req, ctx.config.max_block_body_size, "SignedBeaconBlock")?;
+    let slot = block.slot();
+
+    let verified_block = match ctx.beacon_chain.verify_block_for_gossip(block.clone()) {
+        Ok(verified_block) => verified_block,
+        Err(BlockError::BlockIsAlreadyKnown) => {
+            info!(
+                ctx.log,
+                "Block from local validator already known";
+                "block_root" => format!("{}", block.canonical_root()),
+                "block_slot" => slot,
+            );
+
+            return Ok(());
+        }
+        Err(BlockError::RepeatProposal { proposer, .. }) => {
+            warn!(
+                ctx.log,
+                "Block from local validator already proposed";
+                "proposer" => proposer,
+                "block_slot" => slot,
+            );
+
+            return Err(ApiError::ProcessingError(format!(
+                "A block from proposer {} at slot {} has already been published",
+                proposer, slot
+            )));
+        }
+        Err(BlockError::BeaconChainError(e)) => {
+            error!(
+                ctx.log,
+                "Error whilst verifying block";
+                "error" => format!("{:?}", e)
+            );
+
+            return Err(ApiError::ServerError(format!(
+                "Error while verifying block: {:?}",
+                e
+            )));
+        }
+        Err(other) => {
+            warn!(
+                ctx.log,
+                "Invalid block from local validator";
+                "outcome" => format!("{:?}", other)
+            );
+
+            return Err(ApiError::BadRequest(format!(
+                "The SignedBeaconBlock is invalid and has not been published: {:?}",
+                other
+            )));
+        }
+    };
+
+    // The block is valid for gossip, publish it before import so that an import failure does
+    // not cost the proposer their block.
+    publish_beacon_block_to_network::<T>(&ctx.network_chan, block)?;
 
-    serde_json::from_slice(&body).map_err(|e| {
-                    ApiError::BadRequest(format!("Unable to parse JSON into SignedBeaconBlock: {:?}", e))
-                })
-            .and_then(move |block: SignedBeaconBlock<T::EthSpec>| {
-                let slot = block.slot();
-                match ctx.beacon_chain.process_block(block.clone()) {
-                    Ok(block_root) => {
-                        // Block was processed, publish via gossipsub
-                        info!(
-                            ctx.log,
-                            "Block from local validator";
-                            "block_root" => format!("{}", block_root),
-                            "block_slot" => slot,
-                        );
-
-                        publish_beacon_block_to_network::<T>(&ctx.network_chan, block)?;
-
-                        // Run the fork choice algorithm and enshrine a new canonical head, if
-                        // found.
-                        //
-                        // The new head may or may not be the block we just received.
-                        if let Err(e) = ctx.beacon_chain.fork_choice() {
-                            error!(
-                                ctx.log,
-                                "Failed to find beacon chain head";
-                                "error" => format!("{:?}", e)
-                            );
-                        } else {
-                            // In the best case, validators should produce blocks that become the
-                            // head.
-                            //
-                            // Potential reasons this may not be the case:
-                            //
-                            // - A quick re-org between block produce and publish.
-                            // - Excessive time between block produce and publish.
-                            // - A validator is using another beacon node to produce blocks and
-                            // submitting them here.
-                            if ctx.beacon_chain.head()?.beacon_block_root != block_root {
-                                warn!(
-                                    ctx.log,
-                                    "Block from validator is not head";
-                                    "desc" => "potential re-org",
-                                );
-
-                            }
-                        }
-
-                        Ok(())
-                    }
-                    Err(BlockError::BeaconChainError(e)) => {
-                        error!(
-                            ctx.log,
-                            "Error whilst processing block";
-                            "error" => format!("{:?}", e)
-                        );
-
-                        Err(ApiError::ServerError(format!(
-                            "Error while processing block: {:?}",
-                            e
-                        )))
-                    }
-                    Err(other) => {
-                        warn!(
-                            ctx.log,
-                            "Invalid block from local validator";
-                            "outcome" => format!("{:?}", other)
-                        );
-
-                        Err(ApiError::ProcessingError(format!(
-                            "The SignedBeaconBlock could not be processed and has not been published: {:?}",
-                            other
-                        )))
-                    }
+    match ctx.beacon_chain.process_block(verified_block) {
+        Ok(block_root) => {
+            info!(
+                ctx.log,
+                "Block from local validator";
+                "block_root" => format!("{}", block_root),
+                "block_slot" => slot,
+            );
+
+            // Run the fork choice algorithm and enshrine a new canonical head, if
+            // found.
+            //
+            // The new head may or may not be the block we just received.
+            if let Err(e) = ctx.beacon_chain.fork_choice() {
+                error!(
+                    ctx.log,
+                    "Failed to find beacon chain head";
+                    "error" => format!("{:?}", e)
+                );
+            } else {
+                // In the best case, validators should produce blocks that become the
+                // head.
+                //
+                // Potential reasons this may not be the case:
+                //
+                // - A quick re-org between block produce and publish.
+                // - Excessive time between block produce and publish.
+                // - A validator is using another beacon node to produce blocks and
+                // submitting them here.
+                if ctx.beacon_chain.head_info()?.block_root != block_root {
+                    warn!(
+                        ctx.log,
+                        "Block from validator is not head";
+                        "desc" => "potential re-org",
+                    );
                 }
-        })
+            }
+
+            Ok(())
+        }
+        Err(e) => {
+            error!(
+                ctx.log,
+                "Published block from local validator failed import";
+                "error" => format!("{:?}", e),
+                "block_slot" => slot,
+            );
+
+            Err(ApiError::ProcessingError(format!(
+                "The SignedBeaconBlock has been published but could not be imported: {:?}",
+                e
+            )))
+        }
+    }
 }
 
 /// HTTP Handler to produce a new Attestation from the current state, ready to be signed by a validator.
+///
+/// If producing the attestation takes longer than a fraction of a slot (e.g., due to a slow state
+/// read), an identical attestation produced for an earlier request is served instead, if one
+/// exists. Attesting is refused if the head is too far behind the requested slot, unless the node
+/// allows unsynced requests.
+///
+/// Committee indices which are out of range at the requested slot are rejected with the number
+/// of committees at that slot, where the shuffling of its epoch is known.
 pub fn get_new_attestation<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Attestation<T::EthSpec>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
+    let query: AttestationQuery = parse_query(&req)?;
+    let slot = query.slot;
+    let index = query.committee_index;
+    let slot_duration = ctx.beacon_chain.slot_clock.slot_duration();
+
+    if !ctx.config.allow_unsynced {
+        let head_slot = ctx.beacon_chain.head_info()?.slot;
+        let max_head_age = ATTESTATION_MAX_HEAD_AGE_EPOCHS * T::EthSpec::slots_per_epoch();
+
+        if head_slot + max_head_age < slot {
+            return Err(ApiError::ServiceUnavailable(
+                format!(
+                    "HeadTooOld: head slot {} is more than {} slots prior to the attestation \
+                    slot {}",
+                    head_slot, max_head_age, slot
+                ),
+                slot_duration.as_secs(),
+            ));
+        }
+    }
+
+    if let Some(committee_count) = ctx.beacon_chain.committee_count_at_slot(slot)? {
+        if index >= committee_count {
+            return Err(ApiError::BadRequest(format!(
+                "InvalidCommitteeIndex: committee index {} is out of range, there are {} \
+                committees at slot {}",
+                index, committee_count, slot
+            )));
+        }
+    }
 
-    let slot = query.slot()?;
-    let index = query.committee_index()?;
+    let (tx, rx) = mpsc::channel();
+    let task_ctx = ctx.clone();
+    ctx.executor.spawn_blocking(
+        move || {
+            let result = task_ctx
+                .beacon_chain
+                .produce_unaggregated_attestation(slot, index);
+
+            if let Ok(attestation) = &result {
+                task_ctx.attestation_cache.insert(attestation.clone());
+            }
+
+            // The receiver is dropped if a cached attestation was served in the meantime.
+            let _ = tx.send(result);
+        },
+        "produce_attestation",
+    );
+
+    let result = match rx.recv_timeout(slot_duration / ATTESTATION_DEADLINE_QUOTIENT) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            if let Some(attestation) = ctx.attestation_cache.get(slot, index) {
+                metrics::inc_counter(&metrics::BEACON_HTTP_API_DEGRADED_ATTESTATION_DATA_TOTAL);
+                warn!(
+                    ctx.log,
+                    "Serving cached attestation";
+                    "reason" => "production exceeded deadline",
+                    "slot" => slot,
+                    "index" => index,
+                );
+                return Ok(attestation);
+            }
 
-    ctx.beacon_chain
-        .produce_unaggregated_attestation(slot, index)
-        .map_err(|e| ApiError::BadRequest(format!("Unable to produce attestation: {:?}", e)))
+            rx.recv().map_err(|_| {
+                ApiError::ServerError("Attestation production task failed".to_string())
+            })?
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            return Err(ApiError::ServerError(
+                "Attestation production task failed".to_string(),
+            ))
+        }
+    };
+
+    result.map_err(|e| ApiError::BadRequest(format!("Unable to produce attestation: {:?}", e)))
 }
 
 /// HTTP Handler to retrieve the aggregate attestation for a slot
@@ -397,9 +404,7 @@ pub fn get_aggregate_attestation<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<Attestation<T::EthSpec>, ApiError> {
-    let query = UrlQuery::from_request(&req)?;
-
-    let attestation_data = query.attestation_data()?;
+    let attestation_data = parse_query::<AggregateAttestationQuery, _>(&req)?.attestation_data;
 
     match ctx
         .beacon_chain
@@ -422,16 +427,9 @@ pub fn get_aggregate_attestation<T: BeaconChainTypes>(
 pub fn publish_attestations<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
-) -> Result<(), ApiError> {
-    let bytes = req.into_body();
-
-    serde_json::from_slice(&bytes)
-        .map_err(|e| {
-            ApiError::BadRequest(format!(
-                "Unable to deserialize JSON into a list of attestations: {:?}",
-                e
-            ))
-        })
+) -> Result<PoolSubmissionResponse, ApiError> {
+    let max_body_size = ctx.config.max_body_size;
+    decode_body(&req, max_body_size, "a list of attestations")
         // Process all of the aggregates _without_ exiting early if one fails.
         .map(
             move |attestations: Vec<(Attestation<T::EthSpec>, SubnetId)>| {
@@ -451,11 +449,22 @@ pub fn publish_attestations<T: BeaconChainTypes>(
                     .collect::<Vec<Result<_, _>>>()
             },
         )
-        // Iterate through all the results and return on the first `Err`.
-        //
-        // Note: this will only provide info about the _first_ failure, not all failures.
-        .and_then(|processing_results| processing_results.into_iter().try_for_each(|result| result))
-        .map(|_| ())
+        .and_then(combine_submission_results)
+}
+
+/// Returns `true` if an attestation identical to `attestation` has already passed gossip
+/// verification.
+///
+/// A different attestation from the same validator in the same epoch is not known: it has not been
+/// published and may conflict with the one that was.
+fn is_known_unaggregated_attestation<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    attestation: &Attestation<T::EthSpec>,
+) -> bool {
+    beacon_chain
+        .observed_unaggregated_attestations
+        .is_known(attestation, attestation.tree_hash_root())
+        .unwrap_or(false)
 }
 
 /// Processes an unaggregrated attestation that was included in a list of attestations with the
@@ -468,20 +477,40 @@ fn process_unaggregated_attestation<T: BeaconChainTypes>(
     subnet_id: SubnetId,
     i: usize,
     log: &Logger,
-) -> Result<(), ApiError> {
+) -> Result<PoolSubmissionResponse, ApiError> {
     let data = &attestation.data.clone();
 
     // Verify that the attestation is valid to included on the gossip network.
-    let verified_attestation = beacon_chain
+    let verified_attestation = match beacon_chain
         .verify_unaggregated_attestation_for_gossip(attestation.clone(), subnet_id)
-        .map_err(|e| {
-            handle_attestation_error(
+    {
+        Ok(verified_attestation) => verified_attestation,
+        Err(AttnError::PriorAttestationKnown {
+            validator_index,
+            epoch,
+        }) if is_known_unaggregated_attestation(beacon_chain, &attestation) => {
+            trace!(
+                log,
+                "Ignored known attn from local validator";
+                "validator_index" => validator_index,
+                "epoch" => epoch,
+            );
+
+            // Exit early with success, this exact attestation has already been published (e.g.,
+            // the validator client is retrying a request).
+            return Ok(PoolSubmissionResponse {
+                already_known: true,
+            });
+        }
+        Err(e) => {
+            return Err(handle_attestation_error(
                 e,
                 &format!("unaggregated attestation {} failed gossip verification", i),
                 data,
                 log,
-            )
-        })?;
+            ))
+        }
+    };
 
     // Publish the attestation to the network
     if let Err(e) = network_chan.send(NetworkMessage::Publish {
@@ -524,23 +553,18 @@ fn process_unaggregated_attestation<T: BeaconChainTypes>(
             )
         })?;
 
-    Ok(())
+    Ok(PoolSubmissionResponse {
+        already_known: false,
+    })
 }
 
 /// HTTP Handler to publish an Attestation, which has been signed by a validator.
 pub fn publish_aggregate_and_proofs<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
-) -> Result<(), ApiError> {
-    let body = req.into_body();
-
-    serde_json::from_slice(&body)
-        .map_err(|e| {
-            ApiError::BadRequest(format!(
-                "Unable to deserialize JSON into a list of SignedAggregateAndProof: {:?}",
-                e
-            ))
-        })
+) -> Result<PoolSubmissionResponse, ApiError> {
+    let max_body_size = ctx.config.max_body_size;
+    decode_body(&req, max_body_size, "a list of SignedAggregateAndProof")
         // Process all of the aggregates _without_ exiting early if one fails.
         .map(
             move |signed_aggregates: Vec<SignedAggregateAndProof<T::EthSpec>>| {
@@ -559,10 +583,7 @@ pub fn publish_aggregate_and_proofs<T: BeaconChainTypes>(
                     .collect::<Vec<Result<_, _>>>()
             },
         )
-        // Iterate through all the results and return on the first `Err`.
-        //
-        // Note: this will only provide info about the _first_ failure, not all failures.
-        .and_then(|processing_results| processing_results.into_iter().try_for_each(|result| result))
+        .and_then(combine_submission_results)
 }
 
 /// Processes an aggregrated attestation that was included in a list of attestations with the index
@@ -574,7 +595,7 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
     signed_aggregate: SignedAggregateAndProof<T::EthSpec>,
     i: usize,
     log: &Logger,
-) -> Result<(), ApiError> {
+) -> Result<PoolSubmissionResponse, ApiError> {
     let data = &signed_aggregate.message.aggregate.data.clone();
 
     // Verify that the attestation is valid to be included on the gossip network.
@@ -596,7 +617,9 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
 
                 // Exit early with success for a known attestation, there's no need to re-process
                 // an aggregate we already know.
-                return Ok(());
+                return Ok(PoolSubmissionResponse {
+                    already_known: true,
+                });
             }
             /*
              * It's worth noting that we don't check for `Error::AggregatorAlreadyKnown` since (at
@@ -656,7 +679,40 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
             )
         })?;
 
-    Ok(())
+    Ok(PoolSubmissionResponse {
+        already_known: false,
+    })
+}
+
+/// Combines the results of processing each object in a list submitted for the operation pool.
+///
+/// If any object could not be processed, returns the error of the first failure with the
+/// description of every failure, one per line. Otherwise, the list is only `already_known` if
+/// every object in it was.
+fn combine_submission_results(
+    processing_results: Vec<Result<PoolSubmissionResponse, ApiError>>,
+) -> Result<PoolSubmissionResponse, ApiError> {
+    let mut already_known = true;
+    let mut failures = vec![];
+
+    for result in processing_results {
+        match result {
+            Ok(response) => already_known &= response.already_known,
+            Err(e) => failures.push(e),
+        }
+    }
+
+    let mut failures = failures.into_iter();
+    match failures.next() {
+        None => Ok(PoolSubmissionResponse { already_known }),
+        Some(first) => Err(first.map_description(|first_desc| {
+            failures.fold(first_desc, |mut desc, failure| {
+                desc.push('\n');
+                desc.push_str(&failure.status_code().1);
+                desc
+            })
+        })),
+    }
 }
 
 /// Common handler for `AttnError` during attestation verification.
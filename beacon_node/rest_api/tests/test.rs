@@ -3,26 +3,41 @@
 #[macro_use]
 extern crate assert_matches;
 
-use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
+use beacon_chain::{
+    gossip_observations::GossipMessage,
+    recent_proposals::{ClientKind, Proposal},
+    slot_clock::SlotClock,
+    BeaconChain, BeaconChainTypes, StateSkipConfig,
+};
+use eth1::http::Eth1NetworkId;
+use eth2_libp2p::PeerId;
 use node_test_rig::{
     environment::{Environment, EnvironmentBuilder},
     testing_client_config, ClientConfig, ClientGenesis, LocalBeaconNode,
 };
+use parking_lot::Mutex;
 use remote_beacon_node::{
-    Committee, HeadBeaconBlock, PersistedOperationPool, PublishStatus, ValidatorResponse,
+    BlockPublishStatus, Committee, DroppableCache, HeadBeaconBlock, HeadersQuery, Method,
+    PersistedOperationPool, PublishStatus, RequestBuilder, RequestObserver, StatusCode, Url,
+    ValidatorResponse, ValidatorStatus,
 };
-use rest_types::ValidatorDutyBytes;
+use rest_api::facade::{self, BlockId, DutiesSelection, StateId};
+use rest_types::{StateResponse, ValidatorDutyBytes};
+use ssz::{Decode, Encode};
 use std::convert::TryInto;
+use std::fs;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use store::{NodeMode, BACKUP_COLD_DB_DIR, BACKUP_HOT_DB_DIR};
+use tempfile::tempdir;
 use types::{
     test_utils::{
         build_double_vote_attester_slashing, build_proposer_slashing,
         generate_deterministic_keypair, AttesterSlashingTestTask, ProposerSlashingTestTask,
     },
-    BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, MinimalEthSpec, PublicKey,
-    RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
-    SubnetId, Validator,
+    AggregateSignature, Attestation, BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec,
+    Graffiti, Hash256, MinimalEthSpec, PublicKey, RelativeEpoch, Signature,
+    SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot, SubnetId, Validator, YamlConfig,
 };
 
 type E = MinimalEthSpec;
@@ -233,6 +248,93 @@ fn validator_produce_attestation() {
         "the signed published attestation should be valid"
     );
 
+    // Try re-publishing the valid attestation, as a validator client may when retrying. Use SSZ
+    // to ensure it is decoded as per JSON.
+    let publish_status = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_attestations_ssz(vec![(attestation.clone(), subnet_id)]),
+        )
+        .expect("should re-publish attestation");
+    assert_eq!(
+        publish_status,
+        PublishStatus::AlreadyKnown,
+        "the re-published attestation should be known"
+    );
+
+    // A different attestation from the same validator in the same epoch has not been published, so
+    // it should be rejected rather than reported as known.
+    let mut conflicting_attestation = attestation.clone();
+    conflicting_attestation.data.source.root = Hash256::from_low_u64_be(42);
+    conflicting_attestation
+        .aggregation_bits
+        .set(
+            duties
+                .attestation_committee_position
+                .expect("should have committee position"),
+            false,
+        )
+        .expect("should un-set attestation bit");
+    conflicting_attestation.signature = AggregateSignature::infinity();
+    conflicting_attestation
+        .sign(
+            &keypair.sk,
+            duties
+                .attestation_committee_position
+                .expect("should have committee position"),
+            &state.fork,
+            state.genesis_validators_root,
+            spec,
+        )
+        .expect("should sign conflicting attestation");
+
+    let publish_status = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_attestations(vec![(conflicting_attestation, subnet_id)]),
+        )
+        .expect("should publish conflicting attestation");
+    assert_matches!(
+        publish_status,
+        PublishStatus::Invalid(_),
+        "the conflicting attestation should be invalid"
+    );
+
+    // The published attestation should be in the pool, and only returned by matching filters.
+    let get_pool_attestations = |env: &mut Environment<E>, slot, committee_index| {
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .beacon()
+                    .get_pool_attestations(slot, committee_index),
+            )
+            .expect("should fetch pool attestations")
+    };
+    let slot = attestation.data.slot;
+    let index = attestation.data.index;
+    for (slot, committee_index) in &[(None, None), (Some(slot), None), (Some(slot), Some(index))] {
+        assert_eq!(
+            get_pool_attestations(&mut env, *slot, *committee_index),
+            vec![attestation.clone()],
+            "pool should contain the published attestation"
+        );
+    }
+    assert!(
+        get_pool_attestations(&mut env, Some(slot + 1), None).is_empty(),
+        "pool should not contain attestations for other slots"
+    );
+    assert!(
+        get_pool_attestations(&mut env, None, Some(index + 1)).is_empty(),
+        "pool should not contain attestations for other committees"
+    );
+
     // Try obtaining an aggregated attestation with a matching attestation data to the previous
     // one.
     let aggregated_attestation = env
@@ -271,6 +373,63 @@ fn validator_produce_attestation() {
     );
 }
 
+#[test]
+fn validator_produce_attestation_invalid_committee_index() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let slot = beacon_chain.slot().expect("should get slot");
+    let committee_count = beacon_chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .get_committee_count_at_slot(slot)
+        .expect("should get committee count");
+
+    assert_eq!(
+        beacon_chain
+            .committee_count_at_slot(slot)
+            .expect("should get committee count from chain"),
+        Some(committee_count),
+        "chain should know the committee count at the current slot"
+    );
+
+    for index in &[committee_count, committee_count + 1, u64::max_value()] {
+        let error = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .validator()
+                    .produce_attestation(slot, *index),
+            )
+            .expect_err("should not produce attestation for out-of-range index");
+
+        assert_matches!(
+            error,
+            remote_beacon_node::Error::DidNotSucceed { status, body, .. }
+                if status == StatusCode::BAD_REQUEST
+                    && body.contains("InvalidCommitteeIndex")
+                    && body.contains(&format!("there are {} committees", committee_count))
+        );
+    }
+
+    env.runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .produce_attestation(slot, committee_count - 1),
+        )
+        .expect("should produce attestation for the last committee");
+}
+
 #[test]
 fn validator_duties() {
     let mut env = build_env();
@@ -310,17 +469,194 @@ fn validator_duties() {
         spec,
     );
 
-    epoch += 4;
+    epoch += 1;
+    let duties = env
+        .runtime()
+        .block_on(remote_node.http.validator().get_duties(epoch, &validators))
+        .expect("should fetch duties from http api");
+
+    // 2. Check the next epoch, where proposers are computed with lookahead.
+    check_duties(
+        duties,
+        epoch,
+        validators.clone(),
+        beacon_chain.clone(),
+        spec,
+    );
+
+    epoch += 3;
     let duties = env
         .runtime()
         .block_on(remote_node.http.validator().get_duties(epoch, &validators))
         .expect("should fetch duties from http api");
 
-    // 2. Check with a long skip forward.
+    // 3. Check with a long skip forward.
     check_duties(duties, epoch, validators, beacon_chain, spec);
+}
+
+#[test]
+fn validator_duties_in_the_past() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+    let current_epoch = Epoch::new(5);
+    let max_lookback = 2;
+
+    // Start the chain half-way through an epoch, so that the current epoch does not change whilst
+    // the test runs.
+    let seconds_per_epoch = spec.milliseconds_per_slot / 1_000 * E::slots_per_epoch();
+    let mut config = testing_client_config();
+    config.rest_api.duties_max_lookback_epochs = max_lookback;
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - current_epoch.as_u64() * seconds_per_epoch
+            - seconds_per_epoch / 2,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    assert_eq!(
+        beacon_chain.epoch().expect("should get epoch"),
+        current_epoch,
+        "should be in the current epoch"
+    );
+
+    let validators = beacon_chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .validators
+        .iter()
+        .map(|v| (&v.pubkey).try_into().expect("pubkey should be valid"))
+        .collect::<Vec<_>>();
+
+    // 1. Check a past epoch within the lookback.
+    let epoch = current_epoch - 1;
+    let duties = env
+        .runtime()
+        .block_on(remote_node.http.validator().get_duties(epoch, &validators))
+        .expect("should fetch duties for a past epoch");
+    check_duties(
+        duties,
+        epoch,
+        validators.clone(),
+        beacon_chain.clone(),
+        spec,
+    );
+
+    // 2. Check the earliest epoch permitted by the lookback.
+    let epoch = current_epoch - max_lookback;
+    let duties = env
+        .runtime()
+        .block_on(remote_node.http.validator().get_duties(epoch, &validators))
+        .expect("should fetch duties at the maximum lookback");
+    check_duties(duties, epoch, validators.clone(), beacon_chain, spec);
+
+    // 3. Check that an epoch beyond the lookback is refused.
+    let epoch = current_epoch - max_lookback - 1;
+    let error = env
+        .runtime()
+        .block_on(remote_node.http.validator().get_duties(epoch, &validators))
+        .expect_err("should not fetch duties beyond the maximum lookback");
+    assert_matches!(
+        error,
+        remote_beacon_node::Error::DidNotSucceed { status, body, .. }
+            if status == StatusCode::BAD_REQUEST
+                && body.contains(&format!("more than {} epochs prior", max_lookback))
+    );
+}
+
+#[test]
+fn batched_validator_duties() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let current_epoch = beacon_chain.epoch().expect("should get epoch");
+    let validators = beacon_chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .validators
+        .iter()
+        .map(|v| (&v.pubkey).try_into().expect("pubkey should be valid"))
+        .collect::<Vec<_>>();
+    let indices = (0..validators.len() as u64).collect::<Vec<_>>();
+
+    let batched = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .get_validator_duties(vec![current_epoch + 1, current_epoch], indices.clone()),
+        )
+        .expect("should fetch batched duties from http api");
+
+    assert_eq!(
+        batched.iter().map(|d| d.epoch).collect::<Vec<_>>(),
+        vec![current_epoch, current_epoch + 1],
+        "epochs should be sorted"
+    );
+
+    for epoch_duties in batched {
+        let duties = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .validator()
+                    .get_duties(epoch_duties.epoch, &validators),
+            )
+            .expect("should fetch duties from http api");
+
+        assert_eq!(
+            epoch_duties.duties.len(),
+            indices.len(),
+            "every validator should have a duty"
+        );
+
+        for (batched_duty, duty) in epoch_duties.duties.iter().zip(duties.iter()) {
+            assert_eq!(Some(batched_duty.validator_index), duty.validator_index);
+            assert_eq!(Some(batched_duty.attestation_slot), duty.attestation_slot);
+            assert_eq!(
+                Some(batched_duty.attestation_committee_index),
+                duty.attestation_committee_index
+            );
+            assert_eq!(
+                Some(batched_duty.attestation_committee_position),
+                duty.attestation_committee_position
+            );
+            assert_eq!(
+                Some(batched_duty.committee_count_at_slot),
+                duty.committee_count_at_slot
+            );
+            assert_eq!(Some(batched_duty.aggregator_modulo), duty.aggregator_modulo);
+        }
+    }
 
-    // TODO: test an epoch in the past. Blocked because the `LocalBeaconNode` cannot produce a
-    // chain, yet.
+    let result = env.runtime().block_on(
+        remote_node
+            .http
+            .lighthouse()
+            .get_validator_duties(vec![current_epoch + 2], indices),
+    );
+    assert!(result.is_err(), "should not serve duties for future epochs");
 }
 
 fn check_duties<T: BeaconChainTypes>(
@@ -336,9 +672,17 @@ fn check_duties<T: BeaconChainTypes>(
         "there should be a duty for each validator"
     );
 
-    // Are the duties from the current epoch of the beacon chain, and thus are proposer indices
-    // known?
-    let proposers_known = epoch == beacon_chain.epoch().unwrap();
+    // Are the duties from a past, the current or the next epoch of the beacon chain, and thus are
+    // proposer indices known?
+    let current_epoch = beacon_chain.epoch().unwrap();
+    let proposers_known = epoch <= current_epoch + 1;
+
+    // Proposers for the next epoch depend upon the current head.
+    let expected_dependent_root = if epoch == current_epoch + 1 {
+        Some(beacon_chain.head_info().unwrap().block_root)
+    } else {
+        None
+    };
 
     let mut state = beacon_chain
         .state_at_slot(
@@ -383,6 +727,11 @@ fn check_duties<T: BeaconChainTypes>(
                 "attestation index should match"
             );
 
+            assert_eq!(
+                duty.block_proposal_dependent_root, expected_dependent_root,
+                "dependent root should match"
+            );
+
             if proposers_known {
                 let block_proposal_slots = duty.block_proposal_slots.as_ref().unwrap();
 
@@ -474,10 +823,8 @@ fn validator_block_post() {
         .block_on(remote_node.http.validator().publish_block(empty_sig_block))
         .expect("should publish block");
     if cfg!(not(feature = "fake_crypto")) {
-        assert!(
-            !publish_status.is_valid(),
-            "the unsigned published block should not be valid"
-        );
+        // The unsigned published block should not be valid.
+        assert_matches!(publish_status, BlockPublishStatus::Invalid(_));
     }
 
     let signed_block = sign_block(beacon_chain.clone(), block, spec);
@@ -485,14 +832,32 @@ fn validator_block_post() {
 
     let publish_status = env
         .runtime()
-        .block_on(remote_node.http.validator().publish_block(signed_block))
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_block(signed_block.clone()),
+        )
         .expect("should publish block");
 
     if cfg!(not(feature = "fake_crypto")) {
         assert_eq!(
             publish_status,
-            PublishStatus::Valid,
-            "the signed published block should be valid"
+            BlockPublishStatus::Imported,
+            "the signed published block should be imported"
+        );
+    }
+
+    // Publishing the block again, e.g. when retrying, should not be flagged as invalid.
+    let publish_status = env
+        .runtime()
+        .block_on(remote_node.http.validator().publish_block(signed_block))
+        .expect("should publish block again");
+    if cfg!(not(feature = "fake_crypto")) {
+        assert_eq!(
+            publish_status,
+            BlockPublishStatus::Imported,
+            "the re-published block should already be imported"
         );
     }
 
@@ -525,12 +890,24 @@ fn validator_block_post() {
 }
 
 #[test]
-fn validator_block_get() {
+fn validator_block_post_ssz() {
     let mut env = build_env();
 
     let spec = &E::default_spec();
 
-    let node = build_node(&mut env, testing_client_config());
+    let two_slots_secs = (spec.milliseconds_per_slot / 1_000) * 2;
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - two_slots_secs,
+    };
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
 
     let beacon_chain = node
@@ -539,7 +916,7 @@ fn validator_block_get() {
         .expect("client should have beacon chain");
 
     let slot = Slot::new(1);
-    let randao_reveal = get_randao_reveal(beacon_chain, slot, spec);
+    let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
 
     let block = env
         .runtime()
@@ -547,26 +924,85 @@ fn validator_block_get() {
             remote_node
                 .http
                 .validator()
-                .produce_block(slot, randao_reveal.clone(), None),
+                .produce_block(slot, randao_reveal, None),
         )
         .expect("should fetch block from http api");
 
-    let (expected_block, _state) = node
-        .client
-        .beacon_chain()
-        .expect("client should have beacon chain")
-        .produce_block(randao_reveal, slot, None)
-        .expect("should produce block");
-
-    assert_eq!(
-        block, expected_block,
-        "the block returned from the API should be as expected"
-    );
-}
+    let signed_block = sign_block(beacon_chain.clone(), block, spec);
+    let block_root = signed_block.canonical_root();
 
-#[test]
-fn validator_block_get_with_graffiti() {
-    let mut env = build_env();
+    let publish_status = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_block_ssz(&signed_block),
+        )
+        .expect("should publish block");
+
+    if cfg!(not(feature = "fake_crypto")) {
+        assert_eq!(
+            publish_status,
+            BlockPublishStatus::Imported,
+            "the signed published block should be imported"
+        );
+    }
+
+    let head = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_head())
+        .expect("should get head");
+
+    assert_eq!(
+        head.block_root, block_root,
+        "the published block should become the head block"
+    );
+}
+
+#[test]
+fn validator_block_get() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let slot = Slot::new(1);
+    let randao_reveal = get_randao_reveal(beacon_chain, slot, spec);
+
+    let block = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .produce_block(slot, randao_reveal.clone(), None),
+        )
+        .expect("should fetch block from http api");
+
+    let (expected_block, _state) = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain")
+        .produce_block(randao_reveal, slot, None)
+        .expect("should produce block");
+
+    assert_eq!(
+        block, expected_block,
+        "the block returned from the API should be as expected"
+    );
+}
+
+#[test]
+fn validator_block_get_with_graffiti() {
+    let mut env = build_env();
 
     let spec = &E::default_spec();
 
@@ -607,6 +1043,82 @@ fn validator_block_get_with_graffiti() {
     );
 }
 
+#[test]
+fn validator_block_get_skip_randao_verification() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let slot = Slot::new(1);
+
+    assert!(
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .validator()
+                    .produce_block_skip_randao_verification(slot, None)
+            )
+            .is_err(),
+        "should not skip randao verification unless enabled"
+    );
+
+    let mut config = testing_client_config();
+    config.rest_api.allow_skip_randao_verification = true;
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let block = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .produce_block_skip_randao_verification(slot, None),
+        )
+        .expect("should fetch block from http api");
+
+    assert_eq!(block.slot, slot);
+    assert!(block.body.randao_reveal.is_empty());
+
+    if cfg!(not(feature = "fake_crypto")) {
+        // A reveal signed by a validator other than the proposer is invalid, and is verified by
+        // default.
+        let proposer_index = beacon_chain
+            .block_proposer(slot)
+            .expect("should get proposer index");
+        let keypair = generate_deterministic_keypair(proposer_index + 1);
+        let epoch = slot.epoch(E::slots_per_epoch());
+        let head = beacon_chain.head().expect("should get head");
+        let domain = spec.get_domain(
+            epoch,
+            Domain::Randao,
+            &head.beacon_state.fork,
+            head.beacon_state.genesis_validators_root,
+        );
+        let invalid_randao_reveal = keypair.sk.sign(epoch.signing_root(domain));
+
+        assert!(
+            env.runtime()
+                .block_on(remote_node.http.validator().produce_block(
+                    slot,
+                    invalid_randao_reveal,
+                    None
+                ))
+                .is_err(),
+            "should not produce a block with an invalid randao reveal"
+        );
+    }
+}
+
 #[test]
 fn beacon_state() {
     let mut env = build_env();
@@ -651,6 +1163,124 @@ fn beacon_state() {
     );
 }
 
+#[test]
+fn facade_matches_http() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let config = rest_api::Config::default();
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let (http_block, http_block_root) = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_block_by_slot(Slot::new(0)))
+        .expect("should fetch block from http api");
+    let block = facade::block(&beacon_chain, BlockId::Slot(Slot::new(0)))
+        .expect("should fetch block from facade");
+
+    assert_eq!(block.root, http_block_root, "block roots should match");
+    assert_eq!(block.beacon_block, http_block, "blocks should match");
+
+    let (http_state, http_state_root) = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_state_by_slot(Slot::new(0)))
+        .expect("should fetch state from http api");
+    let mut state = facade::state(&beacon_chain, &config, StateId::Root(http_state_root))
+        .expect("should fetch state from facade");
+    state.beacon_state.drop_all_caches();
+
+    assert_eq!(state.root, http_state_root, "state roots should match");
+    assert_eq!(state.beacon_state, http_state, "states should match");
+
+    let epoch = Epoch::new(0);
+    let validators = http_state
+        .validators
+        .iter()
+        .map(|v| (&v.pubkey).try_into().expect("pubkey should be valid"))
+        .collect::<Vec<_>>();
+    let http_duties = env
+        .runtime()
+        .block_on(remote_node.http.validator().get_duties(epoch, &validators))
+        .expect("should fetch duties from http api");
+    let duties = facade::validator_duties(&beacon_chain, &config, epoch, DutiesSelection::All)
+        .expect("should fetch duties from facade");
+
+    assert_eq!(duties, http_duties, "duties should match");
+}
+
+#[test]
+fn beacon_state_to_file() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let dir = tempdir().expect("should create temp dir");
+
+    let (state, root) = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_state_by_slot(Slot::new(0)))
+        .expect("should fetch state from http api");
+
+    let state_path = dir.path().join("state.ssz");
+    let written = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .get_state_by_root_to_file(root, &state_path),
+        )
+        .expect("should download state to file");
+    let bytes = fs::read(&state_path).expect("should read state file");
+    assert_eq!(written, bytes.len() as u64);
+    assert_eq!(
+        StateResponse::<E>::from_ssz_bytes(&bytes).expect("should decode state response"),
+        StateResponse {
+            root,
+            beacon_state: state.clone(),
+        },
+        "state file should contain the state response"
+    );
+
+    let genesis_path = dir.path().join("genesis.ssz");
+    env.runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .get_genesis_state_to_file(&genesis_path),
+        )
+        .expect("should download genesis state to file");
+    let bytes = fs::read(&genesis_path).expect("should read genesis state file");
+    assert_eq!(
+        BeaconState::<E>::from_ssz_bytes(&bytes).expect("should decode genesis state"),
+        state,
+        "genesis state file should contain the genesis state"
+    );
+
+    let missing_path = dir.path().join("missing.ssz");
+    assert!(
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .beacon()
+                    .get_state_by_root_to_file(Hash256::zero(), &missing_path),
+            )
+            .is_err(),
+        "should not download unknown state"
+    );
+    assert!(
+        !missing_path.exists(),
+        "should not create a file for an unknown state"
+    );
+}
+
 #[test]
 fn beacon_block() {
     let mut env = build_env();
@@ -695,6 +1325,138 @@ fn beacon_block() {
     );
 }
 
+#[test]
+fn beacon_headers() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let headers = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_headers(HeadersQuery {
+            slot: Some(Slot::new(0)),
+            parent_root: None,
+        }))
+        .expect("should fetch headers from http api");
+
+    let db_block = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain")
+        .block_at_slot(Slot::new(0))
+        .expect("should find block")
+        .expect("block should not be none");
+
+    assert_eq!(headers.len(), 1, "should return the genesis header");
+    assert_eq!(
+        headers[0].root,
+        db_block.canonical_root(),
+        "root from database should match that from the API"
+    );
+    assert_eq!(
+        headers[0].header.message,
+        db_block.message.block_header(),
+        "genesis header from api should match that from the DB"
+    );
+
+    let headers = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_headers(HeadersQuery {
+            slot: Some(Slot::new(0)),
+            parent_root: Some(db_block.canonical_root()),
+        }))
+        .expect("should fetch headers from http api");
+
+    assert!(
+        headers.is_empty(),
+        "genesis block should not have itself as a parent"
+    );
+}
+
+#[test]
+fn beacon_pool_attestations() {
+    let mut env = build_env();
+    let spec = &E::default_spec();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let state = beacon_chain.head().expect("should get head").beacon_state;
+
+    let get_pool_attestations = |env: &mut Environment<E>, slot, committee_index| {
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .beacon()
+                    .get_pool_attestations(slot, committee_index),
+            )
+            .expect("should fetch pool attestations")
+    };
+
+    assert!(
+        get_pool_attestations(&mut env, None, None).is_empty(),
+        "pool should start empty"
+    );
+
+    let slot = beacon_chain.slot().expect("should get slot");
+    let first = env
+        .runtime()
+        .block_on(remote_node.http.validator().produce_attestation(slot, 0))
+        .expect("should fetch attestation from http api");
+    let mut other_committee = first.clone();
+    other_committee.data.index = 1;
+    let mut other_slot = first.clone();
+    other_slot.data.slot = slot + 1;
+
+    for attestation in &[&first, &other_committee, &other_slot] {
+        beacon_chain
+            .op_pool
+            .insert_attestation(
+                (*attestation).clone(),
+                &state.fork,
+                state.genesis_validators_root,
+                spec,
+            )
+            .expect("should insert attestation into op pool");
+    }
+
+    let sorted = |mut attestations: Vec<Attestation<E>>| {
+        attestations.sort_by_key(|attestation| (attestation.data.slot, attestation.data.index));
+        attestations
+    };
+
+    assert_eq!(
+        sorted(get_pool_attestations(&mut env, None, None)),
+        vec![first.clone(), other_committee.clone(), other_slot.clone()],
+        "no filters should return every attestation"
+    );
+    assert_eq!(
+        sorted(get_pool_attestations(&mut env, Some(slot), None)),
+        vec![first, other_committee.clone()],
+        "slot filter should only return attestations for that slot"
+    );
+    assert_eq!(
+        sorted(get_pool_attestations(&mut env, None, Some(1))),
+        vec![other_committee],
+        "committee index filter should only return attestations for that committee"
+    );
+    assert_eq!(
+        get_pool_attestations(&mut env, Some(slot + 1), Some(0)),
+        vec![other_slot],
+        "both filters should only return attestations matching both"
+    );
+    assert!(
+        get_pool_attestations(&mut env, Some(slot + 2), None).is_empty(),
+        "should not return attestations for other slots"
+    );
+}
+
 #[test]
 fn genesis_time() {
     let mut env = build_env();
@@ -746,21 +1508,101 @@ fn genesis_validators_root() {
 }
 
 #[test]
-fn fork() {
+fn pre_genesis() {
     let mut env = build_env();
 
-    let node = build_node(&mut env, testing_client_config());
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3_600,
+    };
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
 
-    let fork = env
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let genesis_slot = beacon_chain.head_info().expect("should get head").slot;
+
+    let syncing = env
         .runtime()
-        .block_on(remote_node.http.beacon().get_fork())
-        .expect("should fetch from http api");
+        .block_on(remote_node.http.node().syncing_status())
+        .expect("should fetch syncing status from http api");
 
-    assert_eq!(
-        node.client
-            .beacon_chain()
-            .expect("should have beacon chain")
+    assert!(syncing.is_pre_genesis, "should report pre-genesis");
+    assert!(!syncing.is_syncing, "should not be syncing before genesis");
+    assert_eq!(syncing.sync_status.current_slot, genesis_slot);
+    assert_eq!(syncing.sync_status.highest_slot, genesis_slot);
+
+    // The genesis state is known, so it is served before genesis.
+    env.runtime()
+        .block_on(remote_node.http.beacon().get_genesis_time())
+        .expect("should fetch genesis time before genesis");
+
+    let validators = beacon_chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .validators
+        .iter()
+        .map(|v| (&v.pubkey).try_into().expect("pubkey should be valid"))
+        .collect::<Vec<_>>();
+
+    let duties_error = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .get_duties(Epoch::new(0), &validators),
+        )
+        .expect_err("should not serve duties before genesis");
+
+    assert_matches!(
+        duties_error,
+        remote_beacon_node::Error::DidNotSucceed { status, body, .. }
+            if status == StatusCode::SERVICE_UNAVAILABLE
+                && body.contains("chain has not started")
+    );
+
+    let block_error = env
+        .runtime()
+        .block_on(remote_node.http.validator().produce_block(
+            Slot::new(1),
+            Signature::empty(),
+            None,
+        ))
+        .expect_err("should not produce a block before genesis");
+
+    assert_matches!(
+        block_error,
+        remote_beacon_node::Error::DidNotSucceed { status, .. }
+            if status == StatusCode::SERVICE_UNAVAILABLE
+    );
+}
+
+#[test]
+fn fork() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let fork = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_fork())
+        .expect("should fetch from http api");
+
+    assert_eq!(
+        node.client
+            .beacon_chain()
+            .expect("should have beacon chain")
             .head()
             .expect("should get head")
             .beacon_state
@@ -795,227 +1637,1171 @@ fn eth2_config() {
 }
 
 #[test]
-fn get_version() {
+fn spec_config() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let config = env
+        .runtime()
+        .block_on(remote_node.http.spec().get_config())
+        .expect("should fetch spec config from http api");
+
+    let spec = &node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain")
+        .spec;
+
+    assert_eq!(config.config_name, "minimal", "should match spec constants");
+    assert_eq!(
+        config.yaml_config,
+        YamlConfig::from_spec::<E>(spec),
+        "should include every constant"
+    );
+}
+
+#[test]
+fn deposit_contract_and_fork_schedule() {
+    let mut env = build_env();
+
+    let mut client_config = testing_client_config();
+    client_config.eth1.network_id = Eth1NetworkId::Custom(1337);
+    client_config.eth1.chain_id = Eth1NetworkId::Custom(42);
+    let node = build_node(&mut env, client_config.clone());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let deposit_contract = env
+        .runtime()
+        .block_on(remote_node.http.spec().get_deposit_contract())
+        .expect("should fetch deposit contract from http api");
+
+    assert_eq!(
+        deposit_contract.address, client_config.eth1.deposit_contract_address,
+        "should match deposit contract address"
+    );
+    assert_eq!(
+        deposit_contract.deploy_block, client_config.eth1.deposit_contract_deploy_block,
+        "should match deploy block"
+    );
+    assert_eq!(
+        deposit_contract.chain_id, 42,
+        "should report the chain id rather than the network id"
+    );
+
+    let fork_schedule = env
+        .runtime()
+        .block_on(remote_node.http.spec().get_fork_schedule())
+        .expect("should fetch fork schedule from http api");
+
+    let genesis_fork = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain")
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .fork;
+
+    assert_eq!(
+        fork_schedule,
+        vec![genesis_fork],
+        "should only have genesis fork"
+    );
+}
+
+#[test]
+fn get_version() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let version = env
+        .runtime()
+        .block_on(remote_node.http.node().get_version())
+        .expect("should fetch version from http api");
+
+    assert_eq!(
+        lighthouse_version::version_with_platform(),
+        version,
+        "result should be as expected"
+    );
+}
+
+/// Records the calls made to a `RequestObserver`.
+#[derive(Default)]
+struct RecordingObserver {
+    calls: Mutex<Vec<String>>,
+}
+
+impl RequestObserver for RecordingObserver {
+    fn on_request(&self, method: &Method, url: &Url, request: RequestBuilder) -> RequestBuilder {
+        self.calls
+            .lock()
+            .push(format!("request {} {}", method, url.path()));
+        request.header("x-trace-id", "42")
+    }
+
+    fn on_response(
+        &self,
+        method: &Method,
+        url: &Url,
+        status: Option<StatusCode>,
+        _duration: Duration,
+    ) {
+        self.calls.lock().push(format!(
+            "response {} {} {:?}",
+            method,
+            url.path(),
+            status.map(|status| status.as_u16())
+        ));
+    }
+
+    fn on_error_body(&self, url: &Url, status: StatusCode, body: &str) {
+        self.calls.lock().push(format!(
+            "error {} {} {}",
+            url.path(),
+            status.as_u16(),
+            !body.is_empty()
+        ));
+    }
+}
+
+#[test]
+fn request_observer() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let observer = Arc::new(RecordingObserver::default());
+    let http = remote_node.http.clone().with_observer(observer.clone());
+
+    env.runtime()
+        .block_on(http.node().get_version())
+        .expect("should fetch version from http api");
+    env.runtime()
+        .block_on(http.beacon().get_block_by_root(Hash256::zero()))
+        .expect_err("should not find unknown block");
+
+    assert_eq!(
+        *observer.calls.lock(),
+        vec![
+            "request GET /node/version".to_string(),
+            "response GET /node/version Some(200)".to_string(),
+            "request GET /beacon/block".to_string(),
+            "response GET /beacon/block Some(404)".to_string(),
+            "error /beacon/block 404 true".to_string(),
+        ],
+        "should observe requests, responses and error bodies"
+    );
+}
+
+#[test]
+fn get_genesis_state_root() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let slot = Slot::new(0);
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_state_root(slot))
+        .expect("should fetch from http api");
+
+    let expected = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain")
+        .rev_iter_state_roots()
+        .expect("should get iter")
+        .map(Result::unwrap)
+        .find(|(_cur_root, cur_slot)| slot == *cur_slot)
+        .map(|(cur_root, _)| cur_root)
+        .expect("chain should have state root at slot");
+
+    assert_eq!(result, expected, "result should be as expected");
+}
+
+#[test]
+fn get_genesis_block_root() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let slot = Slot::new(0);
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_block_root(slot))
+        .expect("should fetch from http api");
+
+    let expected = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain")
+        .rev_iter_block_roots()
+        .expect("should get iter")
+        .map(Result::unwrap)
+        .find(|(_cur_root, cur_slot)| slot == *cur_slot)
+        .map(|(cur_root, _)| cur_root)
+        .expect("chain should have state root at slot");
+
+    assert_eq!(result, expected, "result should be as expected");
+}
+
+#[test]
+fn get_validators() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    let validators = state.validators.iter().take(2).collect::<Vec<_>>();
+    let pubkeys = validators
+        .iter()
+        .map(|v| (&v.pubkey).try_into().expect("should decode pubkey bytes"))
+        .collect();
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_validators(pubkeys, None))
+        .expect("should fetch from http api");
+
+    result
+        .iter()
+        .zip(validators.iter())
+        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+}
+
+#[test]
+fn get_all_validators() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_all_validators(None))
+        .expect("should fetch from http api");
+
+    result
+        .iter()
+        .zip(state.validators.iter())
+        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+}
+
+#[test]
+fn get_validators_by_status() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    let mut get = |statuses: Vec<ValidatorStatus>| {
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .beacon()
+                    .get_validators_by_status(None, statuses),
+            )
+            .expect("should fetch from http api")
+    };
+
+    // All validators of the interop genesis are active and have not initiated an exit.
+    for statuses in vec![
+        vec![ValidatorStatus::Active],
+        vec![ValidatorStatus::ActiveOngoing],
+        vec![ValidatorStatus::Exited, ValidatorStatus::Active],
+    ] {
+        let result = get(statuses);
+        assert_eq!(result.len(), state.validators.len());
+        result
+            .iter()
+            .zip(state.validators.iter())
+            .for_each(|(response, validator)| {
+                compare_validator_response(state, response, validator)
+            });
+    }
+
+    for statuses in vec![
+        vec![ValidatorStatus::Pending],
+        vec![ValidatorStatus::ActiveExiting],
+        vec![ValidatorStatus::ExitedSlashed, ValidatorStatus::Withdrawal],
+    ] {
+        assert!(get(statuses).is_empty());
+    }
+}
+
+#[test]
+fn get_active_validators() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_active_validators(None))
+        .expect("should fetch from http api");
+
+    /*
+     * This test isn't comprehensive because all of the validators in the state are active (i.e.,
+     * there is no one to exclude.
+     *
+     * This should be fixed once we can generate more interesting scenarios with the
+     * `NodeTestRig`.
+     */
+
+    let validators = state
+        .validators
+        .iter()
+        .filter(|validator| validator.is_active_at(state.current_epoch()));
+
+    result
+        .iter()
+        .zip(validators)
+        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+}
+
+#[test]
+fn get_committees() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+
+    let epoch = Epoch::new(0);
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_committees(epoch))
+        .expect("should fetch from http api");
+
+    let expected = chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .get_beacon_committees_at_epoch(RelativeEpoch::Current)
+        .expect("should get committees")
+        .iter()
+        .map(|c| Committee {
+            slot: c.slot,
+            index: c.index,
+            committee: c.committee.to_vec(),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(result, expected, "result should be as expected");
+}
+
+#[test]
+fn get_randao_mix() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = chain.head().expect("should get head").beacon_state;
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_randao_mix(None, None))
+        .expect("should fetch from http api");
+
+    assert_eq!(
+        result,
+        *state
+            .get_randao_mix(state.current_epoch())
+            .expect("should get randao mix"),
+        "result should be as expected"
+    );
+
+    let future_epoch = state.current_epoch() + 1;
+    let result = env.runtime().block_on(
+        remote_node
+            .http
+            .beacon()
+            .get_randao_mix(None, Some(future_epoch)),
+    );
+
+    assert!(result.is_err(), "future epoch should not be available");
+}
+
+#[test]
+fn get_fork_choice() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let fork_choice = env
+        .runtime()
+        .block_on(remote_node.http.advanced().get_fork_choice())
+        .expect("should not error when getting fork choice");
+
+    assert_eq!(
+        fork_choice,
+        *node
+            .client
+            .beacon_chain()
+            .expect("node should have beacon chain")
+            .fork_choice
+            .read()
+            .proto_array()
+            .core_proto_array(),
+        "result should be as expected"
+    );
+}
+
+#[test]
+fn prune_fork_choice() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.advanced().prune_fork_choice())
+        .expect("should not error when pruning fork choice");
+
+    // The node has only the genesis block, which is finalized.
+    assert_eq!(result.removed_nodes, 0, "should not remove any blocks");
+    assert_eq!(result.remaining_nodes, 1, "should retain the genesis block");
+}
+
+#[test]
+fn backup_database() {
+    let mut env = build_env();
+
+    let backup_dir = tempdir().expect("should create temp dir");
+    let mut client_config = testing_client_config();
+    client_config.rest_api.database_backup_dir = Some(backup_dir.path().to_path_buf());
+
+    let node = build_node(&mut env, client_config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let result = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .advanced()
+                .backup_database("backup".to_string()),
+        )
+        .expect("should not error when backing up database");
+
+    let output = backup_dir.path().join("backup");
+    assert!(result.hot_entries > 0, "should copy the hot database");
+    assert!(output.join(BACKUP_HOT_DB_DIR).exists());
+    assert!(output.join(BACKUP_COLD_DB_DIR).exists());
+
+    for name in &[
+        "backup",
+        "../backup-2",
+        "nested/backup",
+        "/tmp/backup",
+        "..",
+    ] {
+        let error = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .advanced()
+                    .backup_database(name.to_string()),
+            )
+            .expect_err("should reject existing or non-file-name paths");
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST), "{}", name);
+    }
+    assert!(!backup_dir.path().join("nested").exists());
+    assert!(!backup_dir.path().join("..").join("backup-2").exists());
+}
+
+#[test]
+fn backup_database_disabled_by_default() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let error = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .advanced()
+                .backup_database("backup".to_string()),
+        )
+        .expect_err("should not back up without a backup directory");
+
+    assert_eq!(error.status(), Some(StatusCode::FORBIDDEN));
+}
+
+#[test]
+fn get_block_timings() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_block_timings(None))
+        .expect("should not error when getting block timings");
+
+    // The node has only the genesis block, which was not imported.
+    assert!(result.is_empty(), "should not have any block timings");
+
+    let result = env.runtime().block_on(
+        remote_node
+            .http
+            .lighthouse()
+            .get_block_timings(Some(Hash256::zero())),
+    );
+
+    assert!(
+        result.is_err(),
+        "should not find timings for an unknown block"
+    );
+}
+
+#[test]
+fn get_and_drop_caches() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_caches())
+        .expect("should not error when getting caches");
+
+    assert_eq!(
+        result.snapshot_cache.len, 1,
+        "should only have the genesis snapshot"
+    );
+    assert_eq!(
+        result.validator_pubkey_cache.len,
+        node.client
+            .beacon_chain()
+            .expect("client should have beacon chain")
+            .head()
+            .expect("should get head")
+            .beacon_state
+            .validators
+            .len() as u64,
+        "should have a pubkey for each validator"
+    );
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().drop_caches(vec![
+            DroppableCache::SnapshotCache,
+            DroppableCache::ShufflingCache,
+        ]))
+        .expect("should not error when dropping caches");
+
+    assert_eq!(
+        result.snapshot_cache.len, 1,
+        "should retain the head snapshot"
+    );
+    assert_eq!(
+        result.shuffling_cache.len, 0,
+        "should clear shuffling cache"
+    );
+}
+
+#[test]
+fn get_attestation_pools() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let slot = beacon_chain.slot().expect("should get slot");
+    let mut attestation = beacon_chain
+        .produce_unaggregated_attestation(slot, 0)
+        .expect("should produce attestation");
+    attestation
+        .aggregation_bits
+        .set(0, true)
+        .expect("should set attestation bit");
+    beacon_chain
+        .naive_aggregation_pool
+        .write()
+        .insert(&attestation)
+        .expect("should insert attestation");
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_attestation_pools())
+        .expect("should not error when getting attestation pools");
+
+    let pool = result.naive_aggregation_pool;
+    assert_eq!(pool.num_attestations, 1);
+    assert_eq!(pool.slots, vec![slot]);
+    assert_eq!(pool.committees.len(), 1, "should have one committee");
+
+    let committee = &pool.committees[0];
+    assert_eq!(committee.slot, slot);
+    assert_eq!(committee.index, 0);
+    assert_eq!(committee.attestation_data, 1);
+    assert_eq!(committee.aggregated_attesters, 1);
+    assert_eq!(
+        committee.committee_size,
+        attestation.aggregation_bits.len() as u64
+    );
+    assert_eq!(
+        committee.completeness,
+        1.0 / attestation.aggregation_bits.len() as f64
+    );
+    assert!(!committee.aggregator_registered);
+
+    assert_eq!(result.op_pool.num_attestations, 0);
+    assert!(result.op_pool.slots.is_empty());
+}
+
+#[test]
+fn get_observed_gossip() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let seen_at = |slot: Slot| {
+        beacon_chain
+            .slot_clock
+            .start_of(slot)
+            .map(|slot_start| slot_start + Duration::from_millis(250))
+    };
+
+    beacon_chain.record_gossip_observation(
+        Slot::new(1),
+        GossipMessage::Block {
+            block_root: Hash256::from_low_u64_be(1),
+            proposer_index: 3,
+        },
+        seen_at(Slot::new(1)),
+    );
+    beacon_chain.record_gossip_observation(
+        Slot::new(2),
+        GossipMessage::Aggregate {
+            attestation_data_root: Hash256::from_low_u64_be(2),
+            aggregator_index: 5,
+        },
+        seen_at(Slot::new(2)),
+    );
+
+    let result = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .get_observed_gossip(Some(Slot::new(1))),
+        )
+        .expect("should not error when getting observed gossip");
+
+    assert_eq!(result.blocks.len(), 1, "should have the block from slot 1");
+    assert!(
+        result.aggregates.is_empty(),
+        "should not have the aggregate from slot 2"
+    );
+    assert_eq!(result.blocks[0].block_root, Hash256::from_low_u64_be(1));
+    assert_eq!(result.blocks[0].proposer_index, 3);
+    assert_eq!(result.blocks[0].seen_delay_ms, Some(250));
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_observed_gossip(None))
+        .expect("should not error when getting observed gossip");
+
+    assert_eq!(result.blocks.len(), 1, "should have one block");
+    assert_eq!(result.aggregates.len(), 1, "should have one aggregate");
+    assert_eq!(result.aggregates[0].aggregator_index, 5);
+    assert_eq!(result.aggregates[0].seen_delay_ms, Some(250));
+}
+
+#[test]
+fn get_client_diversity() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let graffiti = |s: &str| {
+        let mut graffiti = Graffiti::default();
+        graffiti[..s.len()].copy_from_slice(s.as_bytes());
+        graffiti
+    };
+
+    for (i, graffiti) in vec![
+        graffiti("Lighthouse/v0.2.9"),
+        graffiti("Lighthouse/v0.2.9"),
+        graffiti("prylabs"),
+        Graffiti::default(),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        beacon_chain.recent_proposals.write().record(Proposal {
+            slot: Slot::new(i as u64 + 1),
+            proposer_index: i as u64,
+            graffiti,
+        });
+    }
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_client_diversity())
+        .expect("should not error when getting client diversity");
+
+    assert_eq!(result.start_slot, Some(Slot::new(1)));
+    assert_eq!(result.end_slot, Some(Slot::new(4)));
+    assert_eq!(result.proposals, 4);
+    assert_eq!(
+        result.clients.len(),
+        ClientKind::ALL.len(),
+        "should include every client"
+    );
+    assert_eq!(result.clients[0].client, "lighthouse");
+    assert_eq!(result.clients[0].proposals, 2);
+    assert_eq!(result.clients[0].proportion, 0.5);
+    assert_eq!(
+        result
+            .clients
+            .iter()
+            .find(|client| client.client == "unknown")
+            .map(|client| client.proposals),
+        Some(1)
+    );
+    assert_eq!(result.graffiti.len(), 3);
+    assert_eq!(result.graffiti[0].graffiti, "Lighthouse/v0.2.9");
+    assert_eq!(result.graffiti[0].proposals, 2);
+}
+
+#[test]
+fn node_config() {
     let mut env = build_env();
 
-    let node = build_node(&mut env, testing_client_config());
+    let mut config = testing_client_config();
+    config.rest_api.mode = NodeMode::Minimal;
+    config.store.slots_per_restore_point = NodeMode::Minimal.slots_per_restore_point::<E>();
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
 
-    let version = env
+    let node_config = env
         .runtime()
-        .block_on(remote_node.http.node().get_version())
-        .expect("should fetch version from http api");
+        .block_on(remote_node.http.lighthouse().get_node_config())
+        .expect("should get node config");
 
+    assert_eq!(node_config.mode, "minimal");
     assert_eq!(
-        lighthouse_version::version_with_platform(),
-        version,
-        "result should be as expected"
+        node_config.slots_per_restore_point,
+        E::slots_per_historical_root() as u64
     );
+    assert!(node_config.prune_abandoned_forks);
+    assert!(!node_config.serves_reconstructed_states);
 }
 
 #[test]
-fn get_genesis_state_root() {
+fn shutdown_at_slot() {
     let mut env = build_env();
 
     let node = build_node(&mut env, testing_client_config());
     let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
 
-    let slot = Slot::new(0);
+    let lighthouse = remote_node.http.lighthouse();
 
-    let result = env
-        .runtime()
-        .block_on(remote_node.http.beacon().get_state_root(slot))
-        .expect("should fetch from http api");
+    assert_eq!(
+        env.runtime()
+            .block_on(lighthouse.get_shutdown_slot())
+            .expect("should get shutdown slot"),
+        None,
+        "should not schedule a shutdown by default"
+    );
 
-    let expected = node
-        .client
-        .beacon_chain()
-        .expect("should have beacon chain")
-        .rev_iter_state_roots()
-        .expect("should get iter")
-        .map(Result::unwrap)
-        .find(|(_cur_root, cur_slot)| slot == *cur_slot)
-        .map(|(cur_root, _)| cur_root)
-        .expect("chain should have state root at slot");
+    let slot = beacon_chain.slot().expect("should get slot") + 10;
+    assert_eq!(
+        env.runtime()
+            .block_on(lighthouse.shutdown_at_slot(Some(slot)))
+            .expect("should schedule shutdown"),
+        Some(slot)
+    );
+    assert_eq!(
+        env.runtime()
+            .block_on(lighthouse.get_shutdown_slot())
+            .expect("should get shutdown slot"),
+        Some(slot)
+    );
+    assert_eq!(*beacon_chain.shutdown_slot.read(), Some(slot));
+    assert_eq!(
+        beacon_chain.ended_shutdown_slot(),
+        None,
+        "should not shut down before the slot has ended"
+    );
 
-    assert_eq!(result, expected, "result should be as expected");
+    assert_eq!(
+        env.runtime()
+            .block_on(lighthouse.shutdown_at_slot(None))
+            .expect("should cancel shutdown"),
+        None
+    );
+    assert_eq!(*beacon_chain.shutdown_slot.read(), None);
 }
 
 #[test]
-fn get_genesis_block_root() {
+fn update_trusted_peers() {
     let mut env = build_env();
 
-    let node = build_node(&mut env, testing_client_config());
+    let mut client_config = testing_client_config();
+    client_config.rest_api.allow_trusted_peer_updates = true;
+
+    let node = build_node(&mut env, client_config);
     let remote_node = node.remote_node().expect("should produce remote node");
+    let lighthouse = remote_node.http.lighthouse();
 
-    let slot = Slot::new(0);
+    let peer_id = PeerId::random();
+    env.runtime()
+        .block_on(lighthouse.update_trusted_peers(
+            vec![format!("/ip4/10.0.0.2/tcp/9000/p2p/{}", peer_id)],
+            vec![],
+        ))
+        .expect("should add trusted peer");
+
+    for (add, remove) in &[
+        (vec!["/ip4/10.0.0.2/tcp/9000".to_string()], vec![]),
+        (vec![], vec!["not a peer id".to_string()]),
+    ] {
+        let error = env
+            .runtime()
+            .block_on(lighthouse.update_trusted_peers(add.clone(), remove.clone()))
+            .expect_err("should reject invalid peers");
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+    }
+}
 
-    let result = env
-        .runtime()
-        .block_on(remote_node.http.beacon().get_block_root(slot))
-        .expect("should fetch from http api");
+#[test]
+fn update_trusted_peers_disabled_by_default() {
+    let mut env = build_env();
 
-    let expected = node
-        .client
-        .beacon_chain()
-        .expect("should have beacon chain")
-        .rev_iter_block_roots()
-        .expect("should get iter")
-        .map(Result::unwrap)
-        .find(|(_cur_root, cur_slot)| slot == *cur_slot)
-        .map(|(cur_root, _)| cur_root)
-        .expect("chain should have state root at slot");
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let lighthouse = remote_node.http.lighthouse();
 
-    assert_eq!(result, expected, "result should be as expected");
+    let error = env
+        .runtime()
+        .block_on(lighthouse.update_trusted_peers(
+            vec![format!("/ip4/10.0.0.2/tcp/9000/p2p/{}", PeerId::random())],
+            vec![],
+        ))
+        .expect_err("should not update trusted peers by default");
+    assert_eq!(error.status(), Some(StatusCode::FORBIDDEN));
+
+    assert!(
+        env.runtime()
+            .block_on(lighthouse.get_trusted_peers())
+            .expect("should get trusted peers")
+            .is_empty(),
+        "should not trust any peers"
+    );
 }
 
 #[test]
-fn get_validators() {
+fn ws_period() {
     let mut env = build_env();
 
     let node = build_node(&mut env, testing_client_config());
     let remote_node = node.remote_node().expect("should produce remote node");
-    let chain = node
+    let beacon_chain = node
         .client
         .beacon_chain()
-        .expect("node should have beacon chain");
-    let state = &chain.head().expect("should get head").beacon_state;
+        .expect("client should have beacon chain");
 
-    let validators = state.validators.iter().take(2).collect::<Vec<_>>();
-    let pubkeys = validators
-        .iter()
-        .map(|v| (&v.pubkey).try_into().expect("should decode pubkey bytes"))
-        .collect();
+    let ws_period = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_ws_period())
+        .expect("should get ws period");
 
-    let result = env
+    assert!(
+        ws_period.ws_period
+            >= beacon_chain
+                .spec
+                .min_validator_withdrawability_delay
+                .as_u64()
+    );
+    assert_eq!(
+        ws_period.current_epoch,
+        beacon_chain.epoch().expect("should get epoch")
+    );
+    assert_eq!(ws_period.checkpoint.epoch, Epoch::new(0));
+    assert_eq!(
+        ws_period.checkpoint.root, beacon_chain.genesis_block_root,
+        "should serve the genesis block root rather than the zero root"
+    );
+    assert_eq!(ws_period.safe_until_epoch, Epoch::new(ws_period.ws_period));
+    assert!(ws_period.is_safe);
+}
+
+#[test]
+fn rejects_oversized_body() {
+    let mut env = build_env();
+
+    let mut config = testing_client_config();
+    config.rest_api.max_body_size = 64;
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let error = env
         .runtime()
-        .block_on(remote_node.http.beacon().get_validators(pubkeys, None))
-        .expect("should fetch from http api");
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .drop_caches(vec![DroppableCache::SnapshotCache; 16]),
+        )
+        .expect_err("should reject a body larger than the maximum");
 
-    result
-        .iter()
-        .zip(validators.iter())
-        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+    assert_eq!(error.status(), Some(StatusCode::PAYLOAD_TOO_LARGE));
+    assert!(
+        error
+            .server_message()
+            .expect("should have a server message")
+            .contains("maximum of 64 bytes"),
+        "should name the limit"
+    );
 }
 
 #[test]
-fn get_all_validators() {
+fn rejects_oversized_decompressed_body() {
     let mut env = build_env();
+    let spec = &E::default_spec();
 
-    let node = build_node(&mut env, testing_client_config());
+    let block = SignedBeaconBlock {
+        message: BeaconBlock::<E>::empty(spec),
+        signature: Signature::empty(),
+    };
+    let ssz_len = block.as_ssz_bytes().len();
+    let compressed_len = snap::raw::Encoder::new()
+        .compress_vec(&block.as_ssz_bytes())
+        .expect("should compress block")
+        .len();
+
+    // Permit the compressed block, but not the decompressed block.
+    let mut config = testing_client_config();
+    config.rest_api.max_block_body_size = ssz_len - 1;
+    assert!(compressed_len <= config.rest_api.max_block_body_size);
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
-    let chain = node
-        .client
-        .beacon_chain()
-        .expect("node should have beacon chain");
-    let state = &chain.head().expect("should get head").beacon_state;
 
-    let result = env
+    let publish_status = env
         .runtime()
-        .block_on(remote_node.http.beacon().get_all_validators(None))
-        .expect("should fetch from http api");
+        .block_on(remote_node.http.validator().publish_block_ssz(&block))
+        .expect("should publish block");
 
-    result
-        .iter()
-        .zip(state.validators.iter())
-        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+    match publish_status {
+        BlockPublishStatus::Invalid(message) => assert!(
+            message.contains(&format!("maximum of {} bytes", ssz_len - 1)),
+            "should name the limit: {}",
+            message
+        ),
+        other => panic!(
+            "should reject a body larger than the maximum once decompressed: {:?}",
+            other
+        ),
+    }
 }
 
 #[test]
-fn get_active_validators() {
+fn rejects_heavy_requests_over_limit() {
     let mut env = build_env();
 
-    let node = build_node(&mut env, testing_client_config());
+    let mut config = testing_client_config();
+    config.rest_api.max_concurrent_heavy_requests = 1;
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
-    let chain = node
+    let request_limiter = node
         .client
-        .beacon_chain()
-        .expect("node should have beacon chain");
-    let state = &chain.head().expect("should get head").beacon_state;
-
-    let result = env
+        .http_request_limiter()
+        .expect("should have a request limiter");
+    let http_addr = node
+        .client
+        .http_listen_addr()
+        .expect("should have an http server");
+    let uri: hyper::Uri = format!("http://{}/beacon/state?slot=0", http_addr)
+        .parse()
+        .expect("should parse uri");
+
+    // Whilst another heavy request is being served, a further one should be rejected.
+    let response = env
         .runtime()
-        .block_on(remote_node.http.beacon().get_active_validators(None))
-        .expect("should fetch from http api");
+        .block_on(async {
+            let _permit = request_limiter
+                .acquire("/beacon/state")
+                .await
+                .expect("should acquire the only permit");
 
-    /*
-     * This test isn't comprehensive because all of the validators in the state are active (i.e.,
-     * there is no one to exclude.
-     *
-     * This should be fixed once we can generate more interesting scenarios with the
-     * `NodeTestRig`.
-     */
+            hyper::Client::new().get(uri).await
+        })
+        .expect("should receive a response");
 
-    let validators = state
-        .validators
-        .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()));
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response
+            .headers()
+            .get("retry-after")
+            .expect("should have a retry-after header"),
+        "1"
+    );
 
-    result
-        .iter()
-        .zip(validators)
-        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+    // Once the other request finishes, heavy requests should be served again.
+    env.runtime()
+        .block_on(remote_node.http.beacon().get_state_by_slot(Slot::new(0)))
+        .expect("should serve a heavy request once the limit allows");
 }
 
 #[test]
-fn get_committees() {
+fn serves_duties_whilst_heavy_requests_are_limited() {
     let mut env = build_env();
 
-    let node = build_node(&mut env, testing_client_config());
+    let mut config = testing_client_config();
+    config.rest_api.max_concurrent_heavy_requests = 1;
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
-    let chain = node
+    let request_limiter = node
+        .client
+        .http_request_limiter()
+        .expect("should have a request limiter");
+    let beacon_chain = node
         .client
         .beacon_chain()
-        .expect("node should have beacon chain");
-
-    let epoch = Epoch::new(0);
-
-    let result = env
-        .runtime()
-        .block_on(remote_node.http.beacon().get_committees(epoch))
-        .expect("should fetch from http api");
+        .expect("client should have beacon chain");
 
-    let expected = chain
+    let validators = beacon_chain
         .head()
         .expect("should get head")
         .beacon_state
-        .get_beacon_committees_at_epoch(RelativeEpoch::Current)
-        .expect("should get committees")
+        .validators
         .iter()
-        .map(|c| Committee {
-            slot: c.slot,
-            index: c.index,
-            committee: c.committee.to_vec(),
-        })
+        .map(|v| (&v.pubkey).try_into().expect("pubkey should be valid"))
         .collect::<Vec<_>>();
 
-    assert_eq!(result, expected, "result should be as expected");
+    let indices = (0..validators.len() as u64).collect::<Vec<_>>();
+
+    // Whilst a state is being downloaded, validator clients should still get their duties.
+    let (duties, batched_duties) = env.runtime().block_on(async {
+        let _permit = request_limiter
+            .acquire("/beacon/state")
+            .await
+            .expect("should acquire the only permit");
+
+        let duties = remote_node
+            .http
+            .validator()
+            .get_duties(Epoch::new(0), &validators)
+            .await;
+        let batched_duties = remote_node
+            .http
+            .lighthouse()
+            .get_validator_duties(vec![Epoch::new(0)], indices)
+            .await;
+        (duties, batched_duties)
+    });
+
+    let duties = duties.expect("should serve duties whilst the heavy request limit is reached");
+    assert_eq!(duties.len(), validators.len());
+
+    let batched_duties = batched_duties
+        .expect("should serve batched duties whilst the heavy request limit is reached");
+    assert_eq!(batched_duties.len(), 1);
 }
 
 #[test]
-fn get_fork_choice() {
+fn rejects_disabled_routes() {
     let mut env = build_env();
 
-    let node = build_node(&mut env, testing_client_config());
+    let mut config = testing_client_config();
+    config.rest_api.disabled_routes = vec!["lighthouse/debug/*".to_string()];
+
+    let node = build_node(&mut env, config);
     let remote_node = node.remote_node().expect("should produce remote node");
+    let lighthouse = remote_node.http.lighthouse();
 
-    let fork_choice = env
+    let error = env
         .runtime()
-        .block_on(remote_node.http.advanced().get_fork_choice())
-        .expect("should not error when getting fork choice");
+        .block_on(lighthouse.get_caches())
+        .expect_err("should reject a disabled route");
 
-    assert_eq!(
-        fork_choice,
-        *node
-            .client
-            .beacon_chain()
-            .expect("node should have beacon chain")
-            .fork_choice
-            .read()
-            .proto_array()
-            .core_proto_array(),
-        "result should be as expected"
+    assert_eq!(error.status(), Some(StatusCode::FORBIDDEN));
+    assert!(
+        error
+            .server_message()
+            .expect("should have a server message")
+            .contains("lighthouse/debug/*"),
+        "should name the matching pattern"
     );
+
+    env.runtime()
+        .block_on(lighthouse.get_node_config())
+        .expect("should serve routes which are not disabled");
 }
 
 #[test]
@@ -1105,13 +2891,28 @@ fn proposer_slashing() {
                 .proposer_slashing(proposer_slashing.clone()),
         )
         .expect("should fetch from http api");
-    assert!(result, true);
+    assert_eq!(result, PublishStatus::Valid);
 
     // Length should be just one as we've inserted only one proposer slashing
     let (proposer_slashings, _attester_slashings) = chain.op_pool.get_slashings(&state);
     assert_eq!(proposer_slashings.len(), 1);
     assert_eq!(proposer_slashing.clone(), proposer_slashings[0]);
 
+    // Re-submitting the same slashing should succeed without inserting it again.
+    let result = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .proposer_slashing(proposer_slashing.clone()),
+        )
+        .expect("should fetch from http api");
+    assert_eq!(result, PublishStatus::AlreadyKnown);
+
+    let (proposer_slashings, _attester_slashings) = chain.op_pool.get_slashings(&state);
+    assert_eq!(proposer_slashings.len(), 1);
+
     let mut invalid_proposer_slashing = build_proposer_slashing::<E>(
         ProposerSlashingTestTask::Valid,
         proposer_index as u64,
@@ -1185,13 +2986,28 @@ fn attester_slashing() {
                 .attester_slashing(attester_slashing.clone()),
         )
         .expect("should fetch from http api");
-    assert!(result, true);
+    assert_eq!(result, PublishStatus::Valid);
 
     // Length should be just one as we've inserted only one attester slashing
     let (_proposer_slashings, attester_slashings) = chain.op_pool.get_slashings(&state);
     assert_eq!(attester_slashings.len(), 1);
     assert_eq!(attester_slashing, attester_slashings[0]);
 
+    // Re-submitting the same slashing should succeed without inserting it again.
+    let result = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .attester_slashing(attester_slashing.clone()),
+        )
+        .expect("should fetch from http api");
+    assert_eq!(result, PublishStatus::AlreadyKnown);
+
+    let (_proposer_slashings, attester_slashings) = chain.op_pool.get_slashings(&state);
+    assert_eq!(attester_slashings.len(), 1);
+
     // Building an invalid attester slashing
     let mut invalid_attester_slashing = build_double_vote_attester_slashing(
         AttesterSlashingTestTask::Valid,
@@ -1281,9 +3097,9 @@ mod validator_attestation {
 
         assert_matches!(
             attestation.expect_err("should not succeed"),
-            DidNotSucceed { status, body } => {
+            DidNotSucceed { status, body, .. } => {
                 assert_eq!(status, StatusCode::BAD_REQUEST);
-                assert_eq!(body, "URL query must be valid and contain at least one of the following keys: [\"slot\"]".to_owned());
+                assert_eq!(body, "Invalid query parameters: missing field `slot`".to_owned());
             }
         );
     }
@@ -1302,9 +3118,9 @@ mod validator_attestation {
 
         assert_matches!(
             attestation.expect_err("should not succeed"),
-            DidNotSucceed { status, body } => {
+            DidNotSucceed { status, body, .. } => {
                 assert_eq!(status, StatusCode::BAD_REQUEST);
-                assert_eq!(body, "URL query must be valid and contain at least one of the following keys: [\"slot\"]".to_owned());
+                assert_eq!(body, "Invalid query parameters: missing field `slot`".to_owned());
             }
         );
     }
@@ -1323,9 +3139,9 @@ mod validator_attestation {
 
         assert_matches!(
             attestation.expect_err("should not succeed"),
-            DidNotSucceed { status, body } => {
+            DidNotSucceed { status, body, .. } => {
                 assert_eq!(status, StatusCode::BAD_REQUEST);
-                assert_eq!(body, "URL query must be valid and contain at least one of the following keys: [\"committee_index\"]".to_owned());
+                assert_eq!(body, "Invalid query parameters: missing field `committee_index`".to_owned());
             }
         );
     }
@@ -41,10 +41,28 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("listen-address")
                 .long("listen-address")
                 .value_name("ADDRESS")
-                .help("The address lighthouse will listen for UDP and TCP connections.")
+                .help("The address lighthouse will listen for UDP and TCP connections. May be \
+                       IPv4 or IPv6, e.g. \"::\" to listen on all IPv6 interfaces.")
                 .default_value("0.0.0.0")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("listen-address6")
+                .long("listen-address6")
+                .value_name("ADDRESS")
+                .help("An IPv6 address on which lighthouse also listens for TCP connections, when \
+                       --listen-address is IPv4. This allows dual-stack operation. Discovery only \
+                       listens on --listen-address.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("port6")
+                .long("port6")
+                .value_name("PORT")
+                .help("The TCP port to listen on at --listen-address6. Defaults to --port.")
+                .requires("listen-address6")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("port")
                 .long("port")
@@ -67,6 +85,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("50")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("max-persisted-peers")
+                .long("max-persisted-peers")
+                .value_name("COUNT")
+                .help("The maximum number of previously connected peers to persist across \
+                       restarts. These peers are dialed on startup, before discovery has found \
+                       any others. Set to 0 to disable.")
+                .default_value("100")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("persisted-peer-expiry")
+                .long("persisted-peer-expiry")
+                .value_name("HOURS")
+                .help("Persisted peers which have not been connected for this many hours are \
+                       forgotten.")
+                .default_value("72")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("boot-nodes")
                 .long("boot-nodes")
@@ -102,12 +139,32 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("enr-udp-port")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("enr-address6")
+                .long("enr-address6")
+                .value_name("ADDRESS")
+                .help("The IPv6 address to broadcast to other peers on how to reach this node, \
+                       when dual-stack. Set this only if you are sure other nodes can connect to \
+                       your local node on this address.")
+                .requires("listen-address6")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("enr-tcp6-port")
+                .long("enr-tcp6-port")
+                .value_name("PORT")
+                .help("The IPv6 TCP port of the local ENR, when dual-stack. The --port6 flag is \
+                       used if this is not set.")
+                .requires("listen-address6")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enr-match")
                 .short("e")
                 .long("enr-match")
                 .help("Sets the local ENR IP address and port to match those set for lighthouse. \
-                Specifically, the IP address will be the value of --listen-address and the UDP port will be --discovery-port.")
+                Specifically, the IP address will be the value of --listen-address and the UDP port will be --discovery-port. \
+                If dual-stack, the IPv6 address will be the value of --listen-address6.")
         )
         .arg(
             Arg::with_name("disable-enr-auto-update")
@@ -125,12 +182,49 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        without an ENR.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("trusted-peers")
+                .long("trusted-peers")
+                .allow_hyphen_values(true)
+                .value_name("ENR/MULTIADDR LIST")
+                .help("One or more comma-delimited ENRs or multiaddrs (ending in /p2p/<peer-id>) \
+                       of peers to which a connection is always maintained. Trusted peers are \
+                       exempt from score-based disconnects and are preferred when syncing.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("disable-discovery")
                 .long("disable-discovery")
                 .help("Disables the discv5 discovery protocol. The node will not search for new peers or participate in the discovery protocol.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("disable-inbound")
+                .long("disable-inbound")
+                .help("Does not listen for inbound connections, relying solely on connecting to \
+                       peers. No address is advertised in the ENR, and discovery queries are \
+                       sent from an ephemeral port. Intended for nodes behind a strict firewall \
+                       or NAT.")
+                .conflicts_with("enable-nat")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("enable-nat")
+                .long("enable-nat")
+                .help("Attempts to map the TCP and UDP ports on the local gateway using UPnP, or \
+                       NAT-PMP if UPnP is unavailable. The external address and ports are \
+                       advertised in the ENR, unless set by --enr-address or --enr-tcp-port.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("network-scoring-config")
+                .long("network-scoring-config")
+                .value_name("FILE")
+                .help("Path to a TOML file which overrides the peer scoring parameters (e.g., \
+                       penalties, thresholds and decay). Omitted parameters keep their default \
+                       values. Intended for research; the defaults are recommended.")
+                .takes_value(true),
+        )
 
         /* REST API related arguments */
         .arg(
@@ -163,6 +257,90 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-duties-max-lookback")
+                .long("http-duties-max-lookback")
+                .value_name("EPOCHS")
+                .help("The maximum number of epochs into the past for which the RESTful HTTP API \
+                    will compute validator duties from historical states.")
+                .default_value("1024")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-max-concurrent-heavy-requests")
+                .long("http-max-concurrent-heavy-requests")
+                .value_name("COUNT")
+                .help("The maximum number of requests to expensive RESTful HTTP API endpoints \
+                    (e.g., state downloads) which are served concurrently. Further requests are \
+                    rejected with a 503 if they cannot be served within a second. Must be at \
+                    least 1.")
+                .default_value("4")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-max-body-size")
+                .long("http-max-body-size")
+                .value_name("BYTES")
+                .help("The maximum size of a request body accepted by the RESTful HTTP API, \
+                    also applied to compressed bodies once decompressed. Larger requests are \
+                    rejected with a 413.")
+                .default_value("4194304")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-disabled-routes")
+                .long("http-disabled-routes")
+                .value_name("PATTERNS")
+                .help("A comma-separated list of RESTful HTTP API routes to disable, e.g. \
+                    \"beacon/state,lighthouse/debug/*\". A * matches any single path segment, \
+                    or all remaining segments at the end of a pattern. Requests to disabled \
+                    routes are rejected with a 403.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-max-block-body-size")
+                .long("http-max-block-body-size")
+                .value_name("BYTES")
+                .help("The maximum size of a request body containing a block (i.e., a published \
+                    block) accepted by the RESTful HTTP API. Larger requests are rejected with \
+                    a 413.")
+                .default_value("16777216")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("database-backup-dir")
+                .long("database-backup-dir")
+                .value_name("DIR")
+                .help("Enables the /advanced/database/backup RESTful HTTP API endpoint, which \
+                    writes backups of the database to named directories within DIR. The endpoint \
+                    is disabled unless this flag is provided.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-allow-skip-randao-verification")
+                .long("http-allow-skip-randao-verification")
+                .help("Permit blocks to be produced via the RESTful HTTP API with an empty \
+                    randao reveal which is not verified, using the `skip_randao_verification` \
+                    query parameter. The produced blocks are invalid, so this is only useful for \
+                    testing.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("http-allow-trusted-peer-updates")
+                .long("http-allow-trusted-peer-updates")
+                .help("Permit trusted peers to be added and removed via the \
+                    /lighthouse/admin/trusted_peers RESTful HTTP API endpoint. The endpoint is \
+                    unauthenticated, so only enable this if the API is not publicly reachable.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("allow-unsynced")
+                .long("allow-unsynced")
+                .help("Serve validator duties and produce blocks and attestations via the RESTful \
+                    HTTP API even whilst the node is syncing far behind the current slot. The \
+                    results are likely to be wrong, so this is only recommended for testnets.")
+                .takes_value(false),
+        )
         /* Websocket related arguments */
         .arg(
             Arg::with_name("ws")
@@ -187,6 +365,63 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true),
         )
 
+        /*
+         * Webhooks
+         */
+        .arg(
+            Arg::with_name("webhook-url")
+                .long("webhook-url")
+                .value_name("URL")
+                .help("POST selected events (see --webhook-events) as JSON to this URL. May be \
+                    supplied multiple times.")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("webhook-secret-file")
+                .long("webhook-secret-file")
+                .value_name("FILE")
+                .help("A file containing a secret with which webhook payloads are signed. The \
+                    hex-encoded HMAC-SHA256 of each payload is given in the X-Lighthouse-Signature \
+                    header.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("webhook-events")
+                .long("webhook-events")
+                .value_name("EVENTS")
+                .help("A comma-separated list of the events to send to webhooks. Possible values \
+                    are finality_stall, long_reorg, slashing and balance_drop.")
+                .default_value("finality_stall,long_reorg,slashing,balance_drop")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("webhook-finality-stall-epochs")
+                .long("webhook-finality-stall-epochs")
+                .value_name("EPOCHS")
+                .help("Report a finality stall after this many epochs without finalization.")
+                .default_value("4")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("webhook-long-reorg-distance")
+                .long("webhook-long-reorg-distance")
+                .value_name("SLOTS")
+                .help("Report re-orgs of at least this many slots.")
+                .default_value("2")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("webhook-balance-drop-threshold")
+                .long("webhook-balance-drop-threshold")
+                .value_name("GWEI")
+                .help("Report validators whose balance decreases by at least this many Gwei over \
+                    an epoch.")
+                .default_value("100000000")
+                .takes_value(true),
+        )
+
         /*
          * Eth1 Integration
          */
@@ -197,6 +432,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        block production, you must use this flag if you wish to serve a validator.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("genesis-state")
+                .long("genesis-state")
+                .value_name("FILE")
+                .help("Path to an SSZ-encoded genesis BeaconState from which to start a custom \
+                       chain, instead of the genesis state of the testnet. The state is \
+                       validated against the spec, which may be given by --chain-config. \
+                       Only effective if there is no existing database.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("chain-config")
+                .long("chain-config")
+                .value_name("FILE")
+                .help("Path to a config.yaml of spec constants for a custom chain, in the format \
+                       of the Eth2 specs repository. Overrides the config of the testnet.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("dummy-eth1")
                 .long("dummy-eth1")
@@ -211,11 +464,44 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies the server for a web3 connection to the Eth1 chain. Also enables the --eth1 flag. Defaults to http://127.0.0.1:8545.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("execution-endpoint")
+                .long("execution-endpoint")
+                .value_name("HTTP-ENDPOINT")
+                .help("Specifies the engine API endpoint of an execution engine. The execution \
+                       engine is not contacted until blocks contain execution payloads.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("execution-jwt")
+                .long("execution-jwt")
+                .value_name("PATH")
+                .requires("execution-endpoint")
+                .help("Path to a file containing the hex-encoded 32 byte secret used to \
+                       authenticate with the execution engine. Requests are unauthenticated if \
+                       this flag is not supplied.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("mode")
+                .long("mode")
+                .value_name("MODE")
+                .help("Configures the database and the historical states served by the HTTP API. \
+                       \"standard\" stores a restore point every 2048 slots and serves all \
+                       states. \"archive\" stores a restore point every epoch and keeps \
+                       abandoned forks, serving historical states quickly at the cost of disk \
+                       space. \"minimal\" stores the fewest restore points and refuses requests \
+                       for finalized states between them. DO NOT CHANGE AFTER INITIALIZATION.")
+                .possible_values(&["standard", "archive", "minimal"])
+                .default_value("standard")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slots-per-restore-point")
                 .long("slots-per-restore-point")
                 .value_name("SLOT_COUNT")
-                .help("Specifies how often a freezer DB restore point should be stored. \
+                .help("Specifies how often a freezer DB restore point should be stored, \
+                       overriding the value set by --mode. \
                        DO NOT DECREASE AFTER INITIALIZATION. [default: 2048 (mainnet) or 64 (minimal)]")
                 .takes_value(true)
         )
@@ -226,6 +512,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies how many blocks the database should cache in memory [default: 5]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("import-era")
+                .long("import-era")
+                .value_name("DIR")
+                .help("Imports the blocks of each era file in a directory (as written by \
+                       `lighthouse db export-era`) before joining the network, rather than \
+                       downloading them from peers. Each block is fully verified. Blocks which \
+                       are already known are skipped.")
+                .takes_value(true)
+        )
 
         /*
          * Purge.
@@ -262,4 +558,54 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("700")
         )
+        .arg(
+            Arg::with_name("fork-choice-max-nodes")
+                .long("fork-choice-max-nodes")
+                .help(
+                    "Prune all blocks which do not descend from the finalized block from fork \
+                    choice once it holds more than this many blocks. Blocks which descend from \
+                    the finalized block are always kept, so this does not bound memory usage \
+                    whilst finality is not advancing. Set to 'none' to disable."
+                )
+                .value_name("NUM_BLOCKS")
+                .takes_value(true)
+                .default_value("16384")
+        )
+        .arg(
+            Arg::with_name("safe-slots-to-import-optimistically")
+                .long("safe-slots-to-import-optimistically")
+                .help(
+                    "Import a block whose execution payload has not been validated by the \
+                    execution engine only if it is at least this many slots old, or its parent \
+                    contains an execution payload. Such blocks are optimistic and no duties are \
+                    performed on them until their payloads are validated."
+                )
+                .value_name("NUM_SLOTS")
+                .takes_value(true)
+                .default_value("128")
+        )
+        .arg(
+            Arg::with_name("shutdown-after-slot")
+                .long("shutdown-after-slot")
+                .help(
+                    "Shut down gracefully once the given slot has ended, after any duties of \
+                    that slot have been completed. Useful for coordinating maintenance. The slot \
+                    may be changed while running via the HTTP API."
+                )
+                .value_name("SLOT")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("attestation-packing-deadline")
+                .long("attestation-packing-deadline")
+                .help(
+                    "The time allowed for selecting the attestations of a block during block \
+                    production. Once exceeded, the block is produced with the best attestations \
+                    found so far, rather than risking a missed proposal. Set to 'none' to \
+                    disable."
+                )
+                .value_name("MILLISECONDS")
+                .takes_value(true)
+                .default_value("500")
+        )
 }
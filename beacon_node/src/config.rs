@@ -2,16 +2,19 @@ use beacon_chain::builder::PUBKEY_CACHE_FILENAME;
 use clap::ArgMatches;
 use clap_utils::BAD_TESTNET_DIR_MESSAGE;
 use client::{config::DEFAULT_DATADIR, ClientConfig, ClientGenesis};
-use eth2_libp2p::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig};
+use eth2_libp2p::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, ScoreParams};
 use eth2_testnet_config::Eth2TestnetConfig;
 use slog::{crit, info, Logger};
-use ssz::Encode;
+use ssz::{Decode, Encode};
+use state_processing::is_valid_genesis_state;
 use std::cmp;
 use std::fs;
-use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
 use std::net::{TcpListener, UdpSocket};
-use std::path::PathBuf;
-use types::{ChainSpec, EthSpec, GRAFFITI_BYTES_LEN};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use store::NodeMode;
+use types::{BeaconState, ChainSpec, EthSpec, Graffiti, Slot, YamlConfig, GRAFFITI_BYTES_LEN};
 
 pub const BEACON_NODE_DIR: &str = "beacon";
 pub const NETWORK_DIR: &str = "network";
@@ -112,6 +115,52 @@ pub fn get_config<E: EthSpec>(
         client_config.rest_api.allow_origin = allow_origin.to_string();
     }
 
+    if let Some(lookback) = cli_args.value_of("http-duties-max-lookback") {
+        client_config.rest_api.duties_max_lookback_epochs = lookback
+            .parse::<u64>()
+            .map_err(|_| "http-duties-max-lookback is not a valid u64.")?;
+    }
+
+    client_config.rest_api.max_concurrent_heavy_requests =
+        get_max_concurrent_heavy_requests(cli_args)?;
+
+    if let Some(size) = cli_args.value_of("http-max-body-size") {
+        client_config.rest_api.max_body_size = size
+            .parse::<usize>()
+            .map_err(|_| "http-max-body-size is not a valid usize.")?;
+    }
+
+    if let Some(routes) = cli_args.value_of("http-disabled-routes") {
+        client_config.rest_api.disabled_routes = routes
+            .split(',')
+            .map(|route| route.trim().trim_matches('/'))
+            .filter(|route| !route.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+
+    if let Some(size) = cli_args.value_of("http-max-block-body-size") {
+        client_config.rest_api.max_block_body_size = size
+            .parse::<usize>()
+            .map_err(|_| "http-max-block-body-size is not a valid usize.")?;
+    }
+
+    if let Some(dir) = cli_args.value_of("database-backup-dir") {
+        client_config.rest_api.database_backup_dir = Some(PathBuf::from(dir));
+    }
+
+    if cli_args.is_present("http-allow-skip-randao-verification") {
+        client_config.rest_api.allow_skip_randao_verification = true;
+    }
+
+    if cli_args.is_present("http-allow-trusted-peer-updates") {
+        client_config.rest_api.allow_trusted_peer_updates = true;
+    }
+
+    if cli_args.is_present("allow-unsynced") {
+        client_config.rest_api.allow_unsynced = true;
+    }
+
     /*
      * Websocket server
      */
@@ -132,6 +181,43 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "ws-port is not a valid u16.")?;
     }
 
+    /*
+     * Webhooks
+     */
+    if let Some(urls) = cli_args.values_of("webhook-url") {
+        client_config.webhook.enabled = true;
+        client_config.webhook.urls = urls.map(String::from).collect();
+    }
+
+    if let Some(secret_file) = cli_args.value_of("webhook-secret-file") {
+        client_config.webhook.secret_file = Some(PathBuf::from(secret_file));
+    }
+
+    if let Some(topics) = cli_args.value_of("webhook-events") {
+        client_config.webhook.topics = topics
+            .split(',')
+            .map(|topic| topic.trim().parse())
+            .collect::<Result<_, _>>()?;
+    }
+
+    if let Some(epochs) = cli_args.value_of("webhook-finality-stall-epochs") {
+        client_config.webhook.finality_stall_epochs = epochs
+            .parse::<u64>()
+            .map_err(|_| "webhook-finality-stall-epochs is not a valid u64.")?;
+    }
+
+    if let Some(distance) = cli_args.value_of("webhook-long-reorg-distance") {
+        client_config.webhook.long_reorg_distance = distance
+            .parse::<u64>()
+            .map_err(|_| "webhook-long-reorg-distance is not a valid u64.")?;
+    }
+
+    if let Some(threshold) = cli_args.value_of("webhook-balance-drop-threshold") {
+        client_config.webhook.balance_drop_threshold = threshold
+            .parse::<u64>()
+            .map_err(|_| "webhook-balance-drop-threshold is not a valid u64.")?;
+    }
+
     /*
      * Eth1
      */
@@ -156,19 +242,31 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.endpoint = val.to_string();
     }
 
+    /*
+     * Execution engine
+     */
+
+    if let Some(endpoint) = cli_args.value_of("execution-endpoint") {
+        client_config.execution_layer = Some(execution_layer::Config {
+            endpoint: endpoint.to_string(),
+            jwt_secret: cli_args.value_of("execution-jwt").map(PathBuf::from),
+        });
+    }
+
     if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
         client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
     }
 
+    let mode: NodeMode = clap_utils::parse_required(cli_args, "mode")?;
+    client_config.rest_api.mode = mode;
+    client_config.store.prune_abandoned_forks = mode.prune_abandoned_forks();
+
     if let Some(slots_per_restore_point) = cli_args.value_of("slots-per-restore-point") {
         client_config.store.slots_per_restore_point = slots_per_restore_point
             .parse()
             .map_err(|_| "slots-per-restore-point is not a valid integer".to_string())?;
     } else {
-        client_config.store.slots_per_restore_point = std::cmp::min(
-            E::slots_per_historical_root() as u64,
-            store::config::DEFAULT_SLOTS_PER_RESTORE_POINT,
-        );
+        client_config.store.slots_per_restore_point = mode.slots_per_restore_point::<E>();
     }
 
     if let Some(block_cache_size) = cli_args.value_of("block-cache-size") {
@@ -177,6 +275,10 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    if let Some(import_era_dir) = cli_args.value_of("import-era") {
+        client_config.import_era_dir = Some(PathBuf::from(import_era_dir));
+    }
+
     if spec_constants != client_config.spec_constants {
         crit!(log, "Specification constants do not match.";
               "client_config" => client_config.spec_constants,
@@ -219,11 +321,20 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.deposit_contract_deploy_block;
     client_config.eth1.follow_distance = spec.eth1_follow_distance;
 
-    if let Some(mut boot_nodes) = eth2_testnet_config.boot_enr {
+    // A custom chain started from `--genesis-state` does not use the boot nodes of the default
+    // testnet, only those of an explicitly chosen testnet.
+    let is_default_testnet = !cli_args.is_present("testnet") && !cli_args.is_present("testnet-dir");
+    let is_custom_chain = cli_args.is_present("genesis-state") && is_default_testnet;
+
+    if let Some(mut boot_nodes) = eth2_testnet_config.boot_enr.filter(|_| !is_custom_chain) {
         client_config.network.boot_nodes_enr.append(&mut boot_nodes)
     }
 
-    if let Some(genesis_state) = eth2_testnet_config.genesis_state {
+    if let Some(path) = cli_args.value_of("genesis-state") {
+        client_config.genesis = ClientGenesis::SszBytes {
+            genesis_state_bytes: load_genesis_state_bytes::<E>(Path::new(path), spec)?,
+        };
+    } else if let Some(genesis_state) = eth2_testnet_config.genesis_state {
         // Note: re-serializing the genesis state is not so efficient, however it avoids adding
         // trait bounds to the `ClientGenesis` enum. This would have significant flow-on
         // effects.
@@ -234,6 +345,59 @@ pub fn get_config<E: EthSpec>(
         client_config.genesis = ClientGenesis::DepositContract;
     }
 
+    client_config.graffiti = get_graffiti(cli_args)?;
+
+    if let Some(max_skip_slots) = cli_args.value_of("max-skip-slots") {
+        client_config.chain.import_max_skip_slots = match max_skip_slots {
+            "none" => None,
+            n => Some(
+                n.parse()
+                    .map_err(|_| "Invalid max-skip-slots".to_string())?,
+            ),
+        };
+    }
+
+    if let Some(max_nodes) = cli_args.value_of("fork-choice-max-nodes") {
+        client_config.chain.fork_choice_max_nodes = match max_nodes {
+            "none" => None,
+            n => Some(
+                n.parse()
+                    .map_err(|_| "Invalid fork-choice-max-nodes".to_string())?,
+            ),
+        };
+    }
+
+    if let Some(safe_slots) = cli_args.value_of("safe-slots-to-import-optimistically") {
+        client_config.chain.safe_slots_to_import_optimistically = safe_slots
+            .parse()
+            .map_err(|_| "Invalid safe-slots-to-import-optimistically".to_string())?;
+    }
+
+    if let Some(slot) = cli_args.value_of("shutdown-after-slot") {
+        client_config.chain.shutdown_after_slot = Some(Slot::new(
+            slot.parse()
+                .map_err(|_| "Invalid shutdown-after-slot".to_string())?,
+        ));
+    }
+
+    if let Some(deadline) = cli_args.value_of("attestation-packing-deadline") {
+        client_config.chain.attestation_packing_deadline = match deadline {
+            "none" => None,
+            millis => {
+                let millis = millis
+                    .parse()
+                    .map_err(|_| "Invalid attestation-packing-deadline".to_string())?;
+                Some(Duration::from_millis(millis))
+            }
+        };
+    }
+
+    Ok(client_config)
+}
+
+/// Returns the graffiti included in blocks for which the validator provides none, given by the
+/// `--graffiti` flag or defaulting to the Lighthouse version.
+pub fn get_graffiti(cli_args: &ArgMatches) -> Result<Graffiti, String> {
     let raw_graffiti = if let Some(graffiti) = cli_args.value_of("graffiti") {
         if graffiti.len() > GRAFFITI_BYTES_LEN {
             return Err(format!(
@@ -247,21 +411,26 @@ pub fn get_config<E: EthSpec>(
         lighthouse_version::VERSION.as_bytes()
     };
 
+    let mut graffiti = Graffiti::default();
     let trimmed_graffiti_len = cmp::min(raw_graffiti.len(), GRAFFITI_BYTES_LEN);
-    client_config.graffiti[..trimmed_graffiti_len]
-        .copy_from_slice(&raw_graffiti[..trimmed_graffiti_len]);
+    graffiti[..trimmed_graffiti_len].copy_from_slice(&raw_graffiti[..trimmed_graffiti_len]);
 
-    if let Some(max_skip_slots) = cli_args.value_of("max-skip-slots") {
-        client_config.chain.import_max_skip_slots = match max_skip_slots {
-            "none" => None,
-            n => Some(
-                n.parse()
-                    .map_err(|_| "Invalid max-skip-slots".to_string())?,
-            ),
-        };
+    Ok(graffiti)
+}
+
+/// Returns the maximum number of requests to expensive HTTP API endpoints which are served
+/// concurrently, given by the `--http-max-concurrent-heavy-requests` flag.
+///
+/// Zero is refused, since it would reject every request to those endpoints.
+pub fn get_max_concurrent_heavy_requests(cli_args: &ArgMatches) -> Result<usize, String> {
+    let count =
+        clap_utils::parse_required::<usize>(cli_args, "http-max-concurrent-heavy-requests")?;
+
+    if count == 0 {
+        return Err("http-max-concurrent-heavy-requests must be at least 1.".to_string());
     }
 
-    Ok(client_config)
+    Ok(count)
 }
 
 /// Sets the network config from the command line arguments
@@ -286,12 +455,35 @@ pub fn set_network_config(
         config.listen_address = listen_address;
     }
 
+    if let Some(listen_address_str) = cli_args.value_of("listen-address6") {
+        let listen_address = listen_address_str
+            .parse::<Ipv6Addr>()
+            .map_err(|_| format!("Invalid IPv6 listen address: {:?}", listen_address_str))?;
+        if !config.listen_address.is_ipv4() {
+            return Err("listen-address6 requires listen-address to be IPv4".into());
+        }
+        config.listen_address6 = Some(listen_address);
+    }
+
     if let Some(target_peers_str) = cli_args.value_of("target-peers") {
         config.target_peers = target_peers_str
             .parse::<usize>()
             .map_err(|_| format!("Invalid number of target peers: {}", target_peers_str))?;
     }
 
+    if let Some(max_peers_str) = cli_args.value_of("max-persisted-peers") {
+        config.max_persisted_peers = max_peers_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid number of persisted peers: {}", max_peers_str))?;
+    }
+
+    if let Some(expiry_str) = cli_args.value_of("persisted-peer-expiry") {
+        let hours = expiry_str
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid persisted peer expiry: {}", expiry_str))?;
+        config.persisted_peer_expiry = Duration::from_secs(hours * 60 * 60);
+    }
+
     if let Some(port_str) = cli_args.value_of("port") {
         let port = port_str
             .parse::<u16>()
@@ -307,6 +499,13 @@ pub fn set_network_config(
         config.discovery_port = port;
     }
 
+    if let Some(port_str) = cli_args.value_of("port6") {
+        let port = port_str
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid port: {}", port_str))?;
+        config.libp2p_port6 = Some(port);
+    }
+
     if let Some(boot_enr_str) = cli_args.value_of("boot-nodes") {
         let mut enrs: Vec<Enr> = vec![];
         let mut multiaddrs: Vec<Multiaddr> = vec![];
@@ -332,6 +531,13 @@ pub fn set_network_config(
         config.boot_nodes_multiaddr = multiaddrs;
     }
 
+    if let Some(trusted_peers_str) = cli_args.value_of("trusted-peers") {
+        config.trusted_peers = trusted_peers_str
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+    }
+
     if let Some(libp2p_addresses_str) = cli_args.value_of("libp2p-addresses") {
         config.libp2p_nodes = libp2p_addresses_str
             .split(',')
@@ -359,14 +565,37 @@ pub fn set_network_config(
         );
     }
 
+    if let Some(enr_tcp6_port_str) = cli_args.value_of("enr-tcp6-port") {
+        config.enr_tcp6_port = Some(
+            enr_tcp6_port_str
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid ENR TCP6 port: {}", enr_tcp6_port_str))?,
+        );
+    }
+
     if cli_args.is_present("enr-match") {
-        // set the enr address to localhost if the address is 0.0.0.0
-        if config.listen_address == "0.0.0.0".parse::<IpAddr>().expect("valid ip addr") {
-            config.enr_address = Some("127.0.0.1".parse::<IpAddr>().expect("valid ip addr"));
-        } else {
-            config.enr_address = Some(config.listen_address);
-        }
+        // set the enr address to localhost if the address is 0.0.0.0 (or ::)
+        config.enr_address = Some(match config.listen_address {
+            IpAddr::V4(address) if address.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(address) if address.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            address => address,
+        });
         config.enr_udp_port = Some(config.discovery_port);
+        config.enr_address6 = config.listen_address6.map(|address| {
+            if address.is_unspecified() {
+                Ipv6Addr::LOCALHOST
+            } else {
+                address
+            }
+        });
+    }
+
+    if let Some(enr_address6_str) = cli_args.value_of("enr-address6") {
+        config.enr_address6 = Some(
+            enr_address6_str
+                .parse::<Ipv6Addr>()
+                .map_err(|_| format!("Invalid IPv6 ENR address: {}", enr_address6_str))?,
+        );
     }
 
     if let Some(enr_address) = cli_args.value_of("enr-address") {
@@ -418,9 +647,45 @@ pub fn set_network_config(
         slog::warn!(log, "Discovery is disabled. New peers will not be found");
     }
 
+    if cli_args.is_present("enable-nat") {
+        config.nat_enabled = true;
+    }
+
+    if cli_args.is_present("disable-inbound") {
+        config.disable_inbound = true;
+        // discovery would otherwise advertise the socket that peers observe
+        config.discv5_config.enr_update = false;
+        slog::warn!(
+            log,
+            "Inbound connections are disabled. Peers can only be dialed"
+        );
+    }
+
+    if let Some(path) = cli_args.value_of("network-scoring-config") {
+        config.score_params = load_score_params(Path::new(path))?;
+        slog::warn!(
+            log,
+            "Using custom peer scoring parameters";
+            "params" => format!("{:?}", config.score_params),
+            "path" => path
+        );
+    }
+
     Ok(())
 }
 
+/// Reads peer scoring parameters from a TOML file, using the default for any omitted parameter.
+fn load_score_params(path: &Path) -> Result<ScoreParams, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read scoring config {:?}: {}", path, e))?;
+    let params: ScoreParams = toml::from_str(&contents)
+        .map_err(|e| format!("Unable to parse scoring config {:?}: {}", path, e))?;
+    params
+        .validate()
+        .map_err(|e| format!("Invalid scoring config {:?}: {}", path, e))?;
+    Ok(params)
+}
+
 /// Gets the datadir which should be used.
 pub fn get_data_dir(cli_args: &ArgMatches) -> PathBuf {
     // Read the `--datadir` flag.
@@ -449,6 +714,56 @@ pub fn get_eth2_testnet_config<E: EthSpec>(
     optional_testnet_config.ok_or_else(|| BAD_TESTNET_DIR_MESSAGE.to_string())
 }
 
+/// Returns the spec of a custom chain, given by applying the `--chain-config` YAML file to `spec`.
+///
+/// Returns `None` if no chain config is given.
+pub fn get_chain_config_spec<E: EthSpec>(
+    cli_args: &ArgMatches,
+    spec: &ChainSpec,
+) -> Result<Option<ChainSpec>, String> {
+    let path = match cli_args.value_of("chain-config") {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    YamlConfig::from_file(Path::new(path))?
+        .try_apply_to_chain_spec::<E>(spec)
+        .map(Some)
+        .map_err(|e| format!("Invalid chain config {}: {}", path, e))
+}
+
+/// Reads the SSZ-encoded genesis state at `path`, returning its bytes if it is a valid genesis
+/// state under `spec`.
+pub fn load_genesis_state_bytes<E: EthSpec>(
+    path: &Path,
+    spec: &ChainSpec,
+) -> Result<Vec<u8>, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Unable to read genesis state {}: {}", path.display(), e))?;
+    let state = BeaconState::<E>::from_ssz_bytes(&bytes)
+        .map_err(|e| format!("Unable to decode genesis state {}: {:?}", path.display(), e))?;
+
+    if state.slot != spec.genesis_slot {
+        return Err(format!(
+            "The genesis state is at slot {}, not the genesis slot {}",
+            state.slot, spec.genesis_slot
+        ));
+    }
+    if state.fork.current_version != spec.genesis_fork_version {
+        return Err(format!(
+            "The genesis state fork version {:?} does not match GENESIS_FORK_VERSION {:?}",
+            state.fork.current_version, spec.genesis_fork_version
+        ));
+    }
+    if !is_valid_genesis_state(&state, spec) {
+        return Err(format!(
+            "The genesis state has fewer than MIN_GENESIS_ACTIVE_VALIDATOR_COUNT ({}) active \
+             validators, or is earlier than MIN_GENESIS_TIME ({})",
+            spec.min_genesis_active_validator_count, spec.min_genesis_time
+        ));
+    }
+    Ok(bytes)
+}
+
 /// A bit of hack to find an unused port.
 ///
 /// Does not guarantee that the given port is unused after the function exists, just that it was
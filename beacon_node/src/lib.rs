@@ -7,7 +7,10 @@ mod config;
 pub use beacon_chain;
 pub use cli::cli_app;
 pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis};
-pub use config::{get_data_dir, get_eth2_testnet_config, set_network_config};
+pub use config::{
+    get_chain_config_spec, get_data_dir, get_eth2_testnet_config, get_graffiti,
+    get_max_concurrent_heavy_requests, load_genesis_state_bytes, set_network_config,
+};
 pub use eth2_config::Eth2Config;
 
 use beacon_chain::events::TeeEventHandler;
@@ -19,10 +22,19 @@ use beacon_chain::{
 use clap::ArgMatches;
 use config::get_config;
 use environment::RuntimeContext;
+use network::NetworkMessage;
 use slog::{info, warn};
 use std::ops::{Deref, DerefMut};
 use types::EthSpec;
 
+/// The flags of the beacon node which `ProductionBeaconNode::reload_config` applies to a running
+/// node. Changes to any other flag require a restart.
+pub const HOT_RELOADABLE_FLAGS: &[&str] = &[
+    "graffiti",
+    "target-peers",
+    "http-max-concurrent-heavy-requests",
+];
+
 /// A type-alias to the tighten the definition of a production-intended `Client`.
 pub type ProductionClient<E> = Client<
     Witness<
@@ -53,9 +65,15 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
     /// given `matches` and potentially configuration files on the local filesystem or other
     /// configurations hosted remotely.
     pub async fn new_from_cli(
-        context: RuntimeContext<E>,
+        mut context: RuntimeContext<E>,
         matches: &ArgMatches<'_>,
     ) -> Result<Self, String> {
+        // A custom chain's config replaces that of the testnet.
+        if let Some(spec) = get_chain_config_spec::<E>(&matches, &context.eth2_config.spec)? {
+            info!(context.log(), "Using custom chain config");
+            context.eth2_config.spec = spec;
+        }
+
         let client_config = get_config::<E>(
             &matches,
             &context.eth2_config.spec_constants,
@@ -119,21 +137,26 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             builder.no_eth1_backend()?
         };
 
-        let (builder, events) = builder
-            .system_time_slot_clock()?
-            .tee_event_handler(client_config.websocket_server.clone())?;
+        let builder = builder.system_time_slot_clock()?.tee_event_handler(
+            client_config.websocket_server.clone(),
+            client_config.webhook.clone(),
+        )?;
 
         // Inject the executor into the discv5 network config.
         client_config.network.discv5_config.executor = Some(Box::new(executor));
 
-        let builder = builder
-            .build_beacon_chain()?
-            .network(&client_config.network)
-            .await?
-            .notifier()?;
+        let builder = builder.build_beacon_chain()?;
+
+        let builder = if let Some(import_era_dir) = &client_config.import_era_dir {
+            builder.import_era_files(import_era_dir)?
+        } else {
+            builder
+        };
+
+        let builder = builder.network(&client_config.network).await?.notifier()?;
 
         let builder = if client_config.rest_api.enabled {
-            builder.http_server(&client_config, &http_eth2_config, events)?
+            builder.http_server(&client_config, &http_eth2_config)?
         } else {
             builder
         };
@@ -141,6 +164,31 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         Ok(Self(builder.build()))
     }
 
+    /// Applies the `HOT_RELOADABLE_FLAGS` given in `matches` to the running node.
+    ///
+    /// All of the flags are parsed before any is applied, so an invalid value changes nothing.
+    pub fn reload_config(&self, matches: &ArgMatches) -> Result<(), String> {
+        let graffiti = get_graffiti(matches)?;
+        let target_peers = clap_utils::parse_required::<usize>(matches, "target-peers")?;
+        let max_concurrent_heavy_requests = get_max_concurrent_heavy_requests(matches)?;
+
+        if let Some(beacon_chain) = self.beacon_chain() {
+            beacon_chain.set_graffiti(graffiti);
+        }
+
+        if let Some(network_send) = self.network_send() {
+            network_send
+                .send(NetworkMessage::SetTargetPeers { target_peers })
+                .map_err(|e| format!("Unable to update target peers: {}", e))?;
+        }
+
+        if let Some(request_limiter) = self.http_request_limiter() {
+            request_limiter.set_max_concurrent_heavy_requests(max_concurrent_heavy_requests);
+        }
+
+        Ok(())
+    }
+
     pub fn into_inner(self) -> ProductionClient<E> {
         self.0
     }
@@ -1,4 +1,6 @@
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use types::{EthSpec, MinimalEthSpec};
 
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
@@ -11,6 +13,9 @@ pub struct StoreConfig {
     pub slots_per_restore_point: u64,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
+    /// If true, delete the blocks and states of forks which can no longer become canonical after
+    /// each finalization.
+    pub prune_abandoned_forks: bool,
 }
 
 impl Default for StoreConfig {
@@ -19,6 +24,111 @@ impl Default for StoreConfig {
             // Safe default for tests, shouldn't ever be read by a CLI node.
             slots_per_restore_point: MinimalEthSpec::slots_per_historical_root() as u64,
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            prune_abandoned_forks: true,
         }
     }
 }
+
+/// A preset which configures the database and the serving of historical states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeMode {
+    /// Stores a restore point every 2048 slots and serves all historical states, which may be
+    /// slow to reconstruct.
+    Standard,
+    /// Stores a restore point every epoch and keeps abandoned forks, so historical states are
+    /// fast to reconstruct.
+    Archive,
+    /// Stores as few restore points as possible and only serves finalized states which are
+    /// restore points.
+    Minimal,
+}
+
+impl Default for NodeMode {
+    fn default() -> Self {
+        NodeMode::Standard
+    }
+}
+
+impl NodeMode {
+    /// Returns the number of slots between restore points in this mode.
+    pub fn slots_per_restore_point<E: EthSpec>(self) -> u64 {
+        let slots_per_historical_root = E::slots_per_historical_root() as u64;
+        match self {
+            NodeMode::Standard => {
+                std::cmp::min(slots_per_historical_root, DEFAULT_SLOTS_PER_RESTORE_POINT)
+            }
+            NodeMode::Archive => E::slots_per_epoch(),
+            NodeMode::Minimal => slots_per_historical_root,
+        }
+    }
+
+    /// Returns `true` if abandoned forks are deleted in this mode.
+    pub fn prune_abandoned_forks(self) -> bool {
+        self != NodeMode::Archive
+    }
+
+    /// Returns `true` if finalized states which must be reconstructed from a restore point are
+    /// served in this mode.
+    pub fn serves_reconstructed_states(self) -> bool {
+        self != NodeMode::Minimal
+    }
+}
+
+impl FromStr for NodeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(NodeMode::Standard),
+            "archive" => Ok(NodeMode::Archive),
+            "minimal" => Ok(NodeMode::Minimal),
+            other => Err(format!("Unknown mode: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for NodeMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            NodeMode::Standard => "standard",
+            NodeMode::Archive => "archive",
+            NodeMode::Minimal => "minimal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    #[test]
+    fn mode_restore_points() {
+        assert_eq!(
+            NodeMode::Standard.slots_per_restore_point::<MainnetEthSpec>(),
+            2048
+        );
+        assert_eq!(
+            NodeMode::Archive.slots_per_restore_point::<MainnetEthSpec>(),
+            32
+        );
+        assert_eq!(
+            NodeMode::Minimal.slots_per_restore_point::<MainnetEthSpec>(),
+            8192
+        );
+        assert_eq!(
+            NodeMode::Standard.slots_per_restore_point::<MinimalEthSpec>(),
+            64
+        );
+    }
+
+    #[test]
+    fn mode_round_trip() {
+        for mode in &[NodeMode::Standard, NodeMode::Archive, NodeMode::Minimal] {
+            assert_eq!(mode.to_string().parse::<NodeMode>(), Ok(*mode));
+        }
+        assert!("full".parse::<NodeMode>().is_err());
+    }
+}
@@ -9,8 +9,8 @@ use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metrics;
 use crate::{
-    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
-    StoreOp,
+    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp,
+    PartialBeaconState, RawEntryIter, StoreItem, StoreOp,
 };
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
@@ -30,6 +30,13 @@ use types::*;
 /// 32-byte key for accessing the `split` of the freezer DB.
 pub const SPLIT_DB_KEY: &str = "FREEZERDBSPLITFREEZERDBSPLITFREE";
 
+/// The sub-directory of a backup containing the hot DB.
+pub const BACKUP_HOT_DB_DIR: &str = "chain_db";
+/// The sub-directory of a backup containing the cold DB.
+pub const BACKUP_COLD_DB_DIR: &str = "freezer_db";
+/// The number of entries written to a backup in each batch.
+const BACKUP_BATCH_SIZE: usize = 1024;
+
 /// Defines how blocks should be replayed on states.
 #[derive(PartialEq)]
 pub enum BlockReplay {
@@ -130,13 +137,49 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         config: StoreConfig,
         spec: ChainSpec,
         log: Logger,
+    ) -> Result<HotColdDB<E, LevelDB<E>, LevelDB<E>>, Error> {
+        Self::open_leveldbs(
+            LevelDB::open(hot_path)?,
+            LevelDB::open(cold_path)?,
+            config,
+            spec,
+            log,
+        )
+    }
+
+    /// Open an existing database, with the given paths to the hot and cold DBs.
+    ///
+    /// Unlike `Self::open`, returns an error rather than creating an empty database if either DB
+    /// does not exist.
+    pub fn open_existing(
+        hot_path: &Path,
+        cold_path: &Path,
+        config: StoreConfig,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<HotColdDB<E, LevelDB<E>, LevelDB<E>>, Error> {
+        Self::open_leveldbs(
+            LevelDB::open_existing(hot_path)?,
+            LevelDB::open_existing(cold_path)?,
+            config,
+            spec,
+            log,
+        )
+    }
+
+    fn open_leveldbs(
+        hot_db: LevelDB<E>,
+        cold_db: LevelDB<E>,
+        config: StoreConfig,
+        spec: ChainSpec,
+        log: Logger,
     ) -> Result<HotColdDB<E, LevelDB<E>, LevelDB<E>>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            cold_db,
+            hot_db,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
             config,
             spec,
@@ -733,11 +776,83 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(state)
     }
 
+    /// Iterate through every raw key and value in the hot DB. See `KeyValueStore::iter_raw`.
+    pub fn iter_hot_raw(&self) -> RawEntryIter<'_> {
+        self.hot_db.iter_raw()
+    }
+
+    /// Iterate through every raw key and value in the cold DB. See `KeyValueStore::iter_raw`.
+    pub fn iter_cold_raw(&self) -> RawEntryIter<'_> {
+        self.cold_db.iter_raw()
+    }
+
+    /// Writes a consistent copy of the database to new on-disk databases in `dir`, in the
+    /// `BACKUP_HOT_DB_DIR` and `BACKUP_COLD_DB_DIR` sub-directories. Returns the number of
+    /// entries copied from the hot and cold DBs, respectively.
+    ///
+    /// The copy is made from iterators, which read from an implicit snapshot of each DB, so the
+    /// database can continue to be used during the backup. The split is held constant whilst the
+    /// iterators are created, so the copies of the hot and cold DBs are consistent with each
+    /// other.
+    pub fn backup(&self, dir: &Path) -> Result<(usize, usize), Error> {
+        let hot_path = dir.join(BACKUP_HOT_DB_DIR);
+        let cold_path = dir.join(BACKUP_COLD_DB_DIR);
+
+        for path in &[&hot_path, &cold_path] {
+            if path.exists() {
+                return Err(Error::BackupPathExists(path.to_path_buf()));
+            }
+        }
+
+        std::fs::create_dir_all(dir).map_err(|e| Error::DBError {
+            message: format!("Unable to create {:?}: {:?}", dir, e),
+        })?;
+
+        let split = self.split.read();
+        let cold_entries = self.cold_db.iter_raw();
+        let hot_entries = self.hot_db.iter_raw();
+        drop(split);
+
+        let num_cold = copy_entries(cold_entries, &LevelDB::<E>::open(&cold_path)?)?;
+        let num_hot = copy_entries(hot_entries, &LevelDB::<E>::open(&hot_path)?)?;
+
+        info!(
+            self.log,
+            "Database backup complete";
+            "hot_entries" => num_hot,
+            "cold_entries" => num_cold,
+            "path" => format!("{:?}", dir),
+        );
+
+        Ok((num_hot, num_cold))
+    }
+
+    /// Get a reference to the `StoreConfig` used by the database.
+    pub fn get_config(&self) -> &StoreConfig {
+        &self.config
+    }
+
     /// Fetch a copy of the current split slot from memory.
     pub fn get_split_slot(&self) -> Slot {
         self.split.read().slot
     }
 
+    /// Load the finalized state at `slot`, from either the freezer or the split state.
+    ///
+    /// Return `None` if `slot` is later than the split slot, i.e. it may not be finalized.
+    pub fn get_finalized_state_by_slot(&self, slot: Slot) -> Result<Option<BeaconState<E>>, Error> {
+        // Copy the split so that the lock isn't held while loading the state.
+        let split = *self.split.read();
+
+        if slot < split.slot {
+            self.load_cold_state_by_slot(slot).map(Some)
+        } else if slot == split.slot {
+            self.get_state(&split.state_root, Some(slot))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Fetch the slot of the most recently stored restore point.
     pub fn get_latest_restore_point_slot(&self) -> Slot {
         (self.get_split_slot() - 1) / self.config.slots_per_restore_point
@@ -778,7 +893,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load a frozen state's slot, given its root.
-    fn load_cold_state_slot(&self, state_root: &Hash256) -> Result<Option<Slot>, Error> {
+    pub fn load_cold_state_slot(&self, state_root: &Hash256) -> Result<Option<Slot>, Error> {
         Ok(self
             .cold_db
             .get(state_root)?
@@ -949,6 +1064,29 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     Ok(())
 }
 
+/// Writes all `entries` to `db`, returning the number of entries written.
+fn copy_entries<E: EthSpec>(entries: RawEntryIter, db: &LevelDB<E>) -> Result<usize, Error> {
+    let mut num_entries = 0;
+    let mut batch = Vec::with_capacity(BACKUP_BATCH_SIZE);
+
+    for (key, value) in entries {
+        batch.push(KeyValueStoreOp::PutKeyValue(key, value));
+        num_entries += 1;
+
+        if batch.len() >= BACKUP_BATCH_SIZE {
+            db.do_atomically(std::mem::replace(
+                &mut batch,
+                Vec::with_capacity(BACKUP_BATCH_SIZE),
+            ))?;
+        }
+    }
+
+    db.do_atomically(batch)?;
+    db.sync()?;
+
+    Ok(num_entries)
+}
+
 /// Struct for storing the split slot and state root in the database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
 pub struct Split {
@@ -5,6 +5,7 @@ use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
+use leveldb::iterator::Iterable;
 use leveldb::options::{Options, ReadOptions, WriteOptions};
 use std::marker::PhantomData;
 use std::path::Path;
@@ -18,9 +19,18 @@ pub struct LevelDB<E: EthSpec> {
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
+        Self::open_with_options(path, true)
+    }
+
+    /// Open the existing database at `path`, returning an error if it does not exist.
+    pub fn open_existing(path: &Path) -> Result<Self, Error> {
+        Self::open_with_options(path, false)
+    }
+
+    fn open_with_options(path: &Path, create_if_missing: bool) -> Result<Self, Error> {
         let mut options = Options::new();
 
-        options.create_if_missing = true;
+        options.create_if_missing = create_if_missing;
 
         let db = Database::open(path, options)?;
 
@@ -138,6 +148,14 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         self.db.write(self.write_options(), &leveldb_batch)?;
         Ok(())
     }
+
+    fn iter_raw(&self) -> RawEntryIter<'_> {
+        Box::new(
+            self.db
+                .iter(self.read_options())
+                .map(|(key, value)| (key.key, value)),
+        )
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
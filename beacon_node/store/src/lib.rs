@@ -26,8 +26,10 @@ pub mod iter;
 
 use std::borrow::Cow;
 
-pub use self::config::StoreConfig;
-pub use self::hot_cold_store::{BlockReplay, HotColdDB, HotStateSummary, Split};
+pub use self::config::{NodeMode, StoreConfig};
+pub use self::hot_cold_store::{
+    BlockReplay, HotColdDB, HotStateSummary, Split, BACKUP_COLD_DB_DIR, BACKUP_HOT_DB_DIR,
+};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
@@ -59,8 +61,17 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Execute either all of the operations in `batch` or none at all, returning an error.
     fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error>;
+
+    /// Iterate through every key and value in the database, in key order.
+    ///
+    /// Keys include their column prefix (see `DBColumn::from_key`). This is intended for
+    /// inspecting the database offline and may be slow on a large database.
+    fn iter_raw(&self) -> RawEntryIter<'_>;
 }
 
+/// An iterator over the raw keys and values of a `KeyValueStore`.
+pub type RawEntryIter<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
     let mut result = column.as_bytes().to_vec();
     result.extend_from_slice(key);
@@ -150,6 +161,44 @@ pub enum DBColumn {
     BeaconHistoricalRoots,
     BeaconRandaoMixes,
     DhtEnrs,
+    /// For the peers persisted across restarts, which are dialed on startup.
+    PeerDb,
+}
+
+impl DBColumn {
+    /// All columns.
+    pub const ALL: [DBColumn; 15] = [
+        DBColumn::BeaconMeta,
+        DBColumn::BeaconBlock,
+        DBColumn::BeaconState,
+        DBColumn::BeaconChain,
+        DBColumn::OpPool,
+        DBColumn::Eth1Cache,
+        DBColumn::ForkChoice,
+        DBColumn::BeaconRestorePoint,
+        DBColumn::BeaconStateSummary,
+        DBColumn::BeaconBlockRoots,
+        DBColumn::BeaconStateRoots,
+        DBColumn::BeaconHistoricalRoots,
+        DBColumn::BeaconRandaoMixes,
+        DBColumn::DhtEnrs,
+        DBColumn::PeerDb,
+    ];
+
+    /// Splits a raw database key (as returned by `KeyValueStore::iter_raw`) into its column and
+    /// the key within that column.
+    ///
+    /// Returns `None` if the key does not belong to a known column.
+    pub fn from_key(key: &[u8]) -> Option<(DBColumn, &[u8])> {
+        Self::ALL.iter().find_map(|column| {
+            let prefix: &'static str = (*column).into();
+            if key.starts_with(prefix.as_bytes()) {
+                Some((*column, &key[prefix.len()..]))
+            } else {
+                None
+            }
+        })
+    }
 }
 
 impl Into<&'static str> for DBColumn {
@@ -170,6 +219,7 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconHistoricalRoots => "bhr",
             DBColumn::BeaconRandaoMixes => "brm",
             DBColumn::DhtEnrs => "dht",
+            DBColumn::PeerDb => "pdb",
         }
     }
 }
@@ -230,6 +280,22 @@ mod tests {
 
         assert_eq!(store.exists::<StorableThing>(&key).unwrap(), true);
 
+        let entries = store
+            .iter_raw()
+            .filter_map(|(raw_key, value)| {
+                let (column, key) = DBColumn::from_key(&raw_key)?;
+                Some((column, key.to_vec(), value))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![(
+                DBColumn::BeaconBlock,
+                key.as_bytes().to_vec(),
+                item.as_ssz_bytes()
+            )]
+        );
+
         let retrieved = store.get(&key).unwrap().unwrap();
         assert_eq!(item, retrieved);
 
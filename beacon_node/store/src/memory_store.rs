@@ -1,6 +1,6 @@
-use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp, RawEntryIter};
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 use types::*;
 
@@ -87,6 +87,11 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
         }
         Ok(())
     }
+
+    fn iter_raw(&self) -> RawEntryIter<'_> {
+        let entries: BTreeMap<_, _> = self.db.read().clone().into_iter().collect();
+        Box::new(entries.into_iter())
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for MemoryStore<E> {}
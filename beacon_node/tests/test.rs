@@ -1,11 +1,19 @@
 #![cfg(test)]
 
 use beacon_chain::StateSkipConfig;
+use beacon_node::{
+    cli_app, get_chain_config_spec, get_max_concurrent_heavy_requests, load_genesis_state_bytes,
+};
+use genesis::{generate_deterministic_keypairs, interop_genesis_state};
 use node_test_rig::{
     environment::{Environment, EnvironmentBuilder},
     testing_client_config, LocalBeaconNode,
 };
-use types::{EthSpec, MinimalEthSpec, Slot};
+use ssz::Encode;
+use std::fs::{self, File};
+use std::path::Path;
+use tempfile::tempdir;
+use types::{ChainSpec, EthSpec, MainnetEthSpec, MinimalEthSpec, Slot, YamlConfig};
 
 fn env_builder() -> EnvironmentBuilder<MinimalEthSpec> {
     EnvironmentBuilder::minimal()
@@ -53,3 +61,116 @@ fn http_server_genesis_state() {
     );
     env.fire_signal();
 }
+
+#[test]
+fn max_concurrent_heavy_requests_flag() {
+    let parse = |count: &str| {
+        let matches = cli_app()
+            .get_matches_from_safe(vec![
+                "beacon_node",
+                "--http-max-concurrent-heavy-requests",
+                count,
+            ])
+            .expect("should parse flags");
+        get_max_concurrent_heavy_requests(&matches)
+    };
+
+    assert_eq!(parse("1"), Ok(1));
+    assert_eq!(parse("8"), Ok(8));
+    assert!(parse("0").is_err(), "zero would reject every heavy request");
+    assert!(parse("many").is_err());
+}
+
+fn chain_config_spec(path: &Path, spec: &ChainSpec) -> Result<Option<ChainSpec>, String> {
+    let matches = cli_app()
+        .get_matches_from_safe(vec![
+            "beacon_node",
+            "--chain-config",
+            path.to_str().expect("path should be utf-8"),
+        ])
+        .expect("should parse flags");
+    get_chain_config_spec::<MinimalEthSpec>(&matches, spec)
+}
+
+#[test]
+fn chain_config_flag() {
+    let dir = tempdir().expect("should create temp dir");
+    let path = dir.path().join("config.yaml");
+
+    let matches = cli_app()
+        .get_matches_from_safe(vec!["beacon_node"])
+        .expect("should parse flags");
+    assert_eq!(
+        get_chain_config_spec::<MinimalEthSpec>(&matches, &ChainSpec::minimal()),
+        Ok(None),
+        "should not change the spec without a chain config"
+    );
+
+    assert!(
+        chain_config_spec(&path, &ChainSpec::minimal()).is_err(),
+        "should reject a missing chain config"
+    );
+
+    let mut custom_spec = ChainSpec::minimal();
+    custom_spec.min_genesis_time = 42;
+    custom_spec.genesis_fork_version = [1, 2, 3, 4];
+    let config = YamlConfig::from_spec::<MinimalEthSpec>(&custom_spec);
+    serde_yaml::to_writer(File::create(&path).expect("should create file"), &config)
+        .expect("should write chain config");
+    assert_eq!(
+        chain_config_spec(&path, &ChainSpec::minimal()),
+        Ok(Some(custom_spec))
+    );
+
+    let config = YamlConfig::from_spec::<MainnetEthSpec>(&ChainSpec::mainnet());
+    serde_yaml::to_writer(File::create(&path).expect("should create file"), &config)
+        .expect("should write chain config");
+    assert!(
+        chain_config_spec(&path, &ChainSpec::minimal()).is_err(),
+        "should reject a chain config with other preset values"
+    );
+}
+
+#[test]
+fn genesis_state_file() {
+    let spec = ChainSpec::minimal();
+    let dir = tempdir().expect("should create temp dir");
+    let path = dir.path().join("genesis.ssz");
+
+    assert!(
+        load_genesis_state_bytes::<MinimalEthSpec>(&path, &spec).is_err(),
+        "should reject a missing genesis state"
+    );
+
+    let keypairs =
+        generate_deterministic_keypairs(spec.min_genesis_active_validator_count as usize);
+    let state = interop_genesis_state::<MinimalEthSpec>(&keypairs, spec.min_genesis_time, &spec)
+        .expect("should build genesis state");
+    fs::write(&path, state.as_ssz_bytes()).expect("should write genesis state");
+
+    assert_eq!(
+        load_genesis_state_bytes::<MinimalEthSpec>(&path, &spec),
+        Ok(state.as_ssz_bytes()),
+        "should accept a genesis state matching the spec"
+    );
+
+    let mut other_fork = spec.clone();
+    other_fork.genesis_fork_version = [1, 2, 3, 4];
+    assert!(
+        load_genesis_state_bytes::<MinimalEthSpec>(&path, &other_fork).is_err(),
+        "should reject a genesis state with another fork version"
+    );
+
+    let mut more_validators = spec.clone();
+    more_validators.min_genesis_active_validator_count += 1;
+    assert!(
+        load_genesis_state_bytes::<MinimalEthSpec>(&path, &more_validators).is_err(),
+        "should reject a genesis state with too few validators"
+    );
+
+    fs::write(&path, &[0; 8]).expect("should write invalid genesis state");
+    assert!(
+        load_genesis_state_bytes::<MinimalEthSpec>(&path, &spec).is_err(),
+        "should reject an invalid genesis state"
+    );
+}
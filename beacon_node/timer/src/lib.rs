@@ -3,20 +3,24 @@
 //! This service allows task execution on the beacon node for various functionality.
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use futures::sink::SinkExt;
 use futures::stream::StreamExt;
-use slog::info;
+use slog::{info, warn};
 use slot_clock::SlotClock;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::{interval_at, Instant};
 
 /// Spawns a timer service which periodically executes tasks for the beacon chain
+///
+/// Requests that the node shuts down once the slot set by `BeaconChain::shutdown_slot` has ended.
 pub fn spawn_timer<T: BeaconChainTypes>(
     executor: environment::TaskExecutor,
     beacon_chain: Arc<BeaconChain<T>>,
     milliseconds_per_slot: u64,
 ) -> Result<(), &'static str> {
     let log = executor.log();
+    let mut shutdown_sender = executor.shutdown_sender();
     let start_instant = Instant::now()
         + beacon_chain
             .slot_clock
@@ -25,9 +29,25 @@ pub fn spawn_timer<T: BeaconChainTypes>(
 
     // Warning: `interval_at` panics if `milliseconds_per_slot` = 0.
     let mut interval = interval_at(start_instant, Duration::from_millis(milliseconds_per_slot));
+    let timer_log = log.clone();
     let timer_future = async move {
         while interval.next().await.is_some() {
             beacon_chain.per_slot_task();
+
+            if let Some(shutdown_slot) = beacon_chain.ended_shutdown_slot() {
+                info!(
+                    timer_log,
+                    "Shutting down after scheduled slot";
+                    "slot" => shutdown_slot,
+                );
+                if let Err(e) = shutdown_sender
+                    .send("Reached the scheduled shutdown slot")
+                    .await
+                {
+                    warn!(timer_log, "Failed to send a shutdown signal"; "error" => e.to_string());
+                }
+                break;
+            }
         }
     };
 
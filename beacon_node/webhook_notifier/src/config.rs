@@ -0,0 +1,82 @@
+use serde_derive::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The kinds of event which may be sent to webhooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    /// The chain has not finalized for `Config::finality_stall_epochs`.
+    FinalityStall,
+    /// The head has been re-orged by at least `Config::long_reorg_distance` slots.
+    LongReorg,
+    /// A proposer or attester slashing has been received.
+    Slashing,
+    /// The balances of validators have decreased by at least `Config::balance_drop_threshold` over
+    /// an epoch.
+    BalanceDrop,
+}
+
+impl Topic {
+    pub fn all() -> Vec<Topic> {
+        vec![
+            Topic::FinalityStall,
+            Topic::LongReorg,
+            Topic::Slashing,
+            Topic::BalanceDrop,
+        ]
+    }
+}
+
+impl FromStr for Topic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "finality_stall" => Ok(Topic::FinalityStall),
+            "long_reorg" => Ok(Topic::LongReorg),
+            "slashing" => Ok(Topic::Slashing),
+            "balance_drop" => Ok(Topic::BalanceDrop),
+            other => Err(format!(
+                "Unknown webhook event {:?}, expected one of finality_stall, long_reorg, \
+                 slashing or balance_drop",
+                other
+            )),
+        }
+    }
+}
+
+/// Configuration of the webhook notifier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub enabled: bool,
+    /// The URLs to which events are POSTed as JSON.
+    pub urls: Vec<String>,
+    /// A file containing the secret with which payloads are signed (HMAC-SHA256), if any.
+    pub secret_file: Option<PathBuf>,
+    /// The kinds of event which are sent.
+    pub topics: Vec<Topic>,
+    /// The number of epochs without finalization after which a finality stall is reported.
+    pub finality_stall_epochs: u64,
+    /// The minimum distance (in slots) of a re-org for it to be reported.
+    pub long_reorg_distance: u64,
+    /// The minimum decrease (in Gwei) of a validator's balance over an epoch for it to be reported.
+    pub balance_drop_threshold: u64,
+    /// The number of times delivery of an event to a URL is retried after the first attempt.
+    pub max_retries: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enabled: false,
+            urls: vec![],
+            secret_file: None,
+            topics: Topic::all(),
+            finality_stall_epochs: 4,
+            long_reorg_distance: 2,
+            balance_drop_threshold: 100_000_000,
+            max_retries: 3,
+        }
+    }
+}
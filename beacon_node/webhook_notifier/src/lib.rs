@@ -0,0 +1,430 @@
+//! POSTs selected beacon chain events (e.g., finality stalls) as JSON to operator-defined URLs.
+//!
+//! Payloads have the form `{"event": ..., "data": ...}`. If a secret is configured, the
+//! hex-encoded HMAC-SHA256 of the payload is supplied in the `SIGNATURE_HEADER` header.
+
+use environment::TaskExecutor;
+use hmac::{Hmac, Mac, NewMac};
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use sha2::Sha256;
+use slog::{debug, error, info, warn, Logger};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use types::{Epoch, Hash256};
+
+mod config;
+
+pub use config::{Config, Topic};
+
+/// The header which contains the signature of the payload, as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "X-Lighthouse-Signature";
+
+/// The timeout for each attempt to deliver an event.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The delay before the first retry of a failed delivery, doubled for each subsequent retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// An event which is sent to webhooks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "event", content = "data")]
+pub enum WebhookEvent {
+    FinalityStall {
+        /// The latest epoch finalized since the node started, if any.
+        finalized_epoch: Option<Epoch>,
+        epochs_without_finality: u64,
+    },
+    LongReorg {
+        distance: u64,
+        previous_head_beacon_block_root: Hash256,
+        current_head_beacon_block_root: Hash256,
+    },
+    ProposerSlashing {
+        proposer_index: u64,
+    },
+    AttesterSlashing {
+        attester_indices: Vec<u64>,
+    },
+    BalanceDrop {
+        /// The epoch at which the decreased balances were observed.
+        epoch: Epoch,
+        validators: Vec<BalanceDrop>,
+    },
+}
+
+/// A decrease in the balance of a single validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceDrop {
+    pub validator_index: u64,
+    pub previous_balance: u64,
+    pub balance: u64,
+}
+
+/// Returns the validators whose balance is at least `threshold` lower in `current` than in
+/// `previous`, where both are lists of balances indexed by validator index.
+///
+/// Validators which are not present in `previous` (i.e., new deposits) are ignored.
+pub fn balance_drops(previous: &[u64], current: &[u64], threshold: u64) -> Vec<BalanceDrop> {
+    previous
+        .iter()
+        .zip(current.iter())
+        .enumerate()
+        .filter(|(_, (previous_balance, balance))| {
+            previous_balance.saturating_sub(**balance) >= threshold
+        })
+        .map(|(i, (previous_balance, balance))| BalanceDrop {
+            validator_index: i as u64,
+            previous_balance: *previous_balance,
+            balance: *balance,
+        })
+        .collect()
+}
+
+impl WebhookEvent {
+    pub fn topic(&self) -> Topic {
+        match self {
+            WebhookEvent::FinalityStall { .. } => Topic::FinalityStall,
+            WebhookEvent::LongReorg { .. } => Topic::LongReorg,
+            WebhookEvent::ProposerSlashing { .. } | WebhookEvent::AttesterSlashing { .. } => {
+                Topic::Slashing
+            }
+            WebhookEvent::BalanceDrop { .. } => Topic::BalanceDrop,
+        }
+    }
+}
+
+/// The time at which the chain last finalized, used to detect finality stalls.
+struct FinalityStatus {
+    epoch: Option<Epoch>,
+    since: Instant,
+    stall_reported: bool,
+}
+
+impl FinalityStatus {
+    fn new(epoch: Option<Epoch>) -> Self {
+        Self {
+            epoch,
+            since: Instant::now(),
+            stall_reported: false,
+        }
+    }
+}
+
+struct Inner {
+    tx: mpsc::UnboundedSender<WebhookEvent>,
+    topics: HashSet<Topic>,
+    long_reorg_distance: u64,
+    balance_drop_threshold: u64,
+    finality: Arc<Mutex<FinalityStatus>>,
+}
+
+pub struct WebhookSender {
+    inner: Option<Inner>,
+}
+
+impl WebhookSender {
+    /// Creates a dummy notifier that never starts and where all future calls are no-ops.
+    pub fn dummy() -> Self {
+        Self { inner: None }
+    }
+
+    /// Queues `event` for delivery, if its topic is enabled.
+    pub fn send(&self, event: WebhookEvent) -> Result<(), String> {
+        if let Some(inner) = &self.inner {
+            if inner.topics.contains(&event.topic()) {
+                inner
+                    .tx
+                    .send(event)
+                    .map_err(|e| format!("Unable to queue webhook event: {:?}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports a re-org of `distance` slots, if it is at least the configured
+    /// `long_reorg_distance`.
+    pub fn reorg(
+        &self,
+        distance: u64,
+        previous_head_beacon_block_root: Hash256,
+        current_head_beacon_block_root: Hash256,
+    ) -> Result<(), String> {
+        match &self.inner {
+            Some(inner) if distance >= inner.long_reorg_distance => {
+                self.send(WebhookEvent::LongReorg {
+                    distance,
+                    previous_head_beacon_block_root,
+                    current_head_beacon_block_root,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reports the validators whose balances are at least the configured `balance_drop_threshold`
+    /// lower in `current` than in `previous`, the balances of the head states before and after
+    /// the head advanced to `epoch`.
+    pub fn balances(&self, epoch: Epoch, previous: &[u64], current: &[u64]) -> Result<(), String> {
+        match &self.inner {
+            Some(inner) if inner.topics.contains(&Topic::BalanceDrop) => {
+                let validators = balance_drops(previous, current, inner.balance_drop_threshold);
+
+                if validators.is_empty() {
+                    Ok(())
+                } else {
+                    self.send(WebhookEvent::BalanceDrop { epoch, validators })
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records that the chain has finalized `epoch`, ending any finality stall.
+    pub fn finalized(&self, epoch: Epoch) {
+        if let Some(inner) = &self.inner {
+            *inner.finality.lock() = FinalityStatus::new(Some(epoch));
+        }
+    }
+}
+
+/// Returns the hex-encoded HMAC-SHA256 of `payload` with `secret`.
+pub fn sign(secret: &[u8], payload: &[u8]) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_varkey(secret)
+        .map_err(|e| format!("Invalid webhook secret: {:?}", e))?;
+    mac.update(payload);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Starts the tasks which deliver events to webhooks and detect finality stalls.
+///
+/// `seconds_per_epoch` is used to measure the time without finality.
+pub fn start_notifier(
+    executor: TaskExecutor,
+    config: &Config,
+    seconds_per_epoch: u64,
+) -> Result<WebhookSender, String> {
+    let log = executor.log().clone();
+
+    let secret = config
+        .secret_file
+        .as_ref()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .map(|secret| secret.trim().as_bytes().to_vec())
+                .map_err(|e| format!("Unable to read webhook secret file {:?}: {:?}", path, e))
+        })
+        .transpose()?;
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Unable to build webhook HTTP client: {:?}", e))?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<WebhookEvent>();
+    let finality = Arc::new(Mutex::new(FinalityStatus::new(None)));
+
+    let urls = config.urls.clone();
+    let max_retries = config.max_retries;
+    let delivery_executor = executor.clone();
+    let delivery_log = log.clone();
+    let delivery_future = async move {
+        while let Some(event) = rx.recv().await {
+            let body = match serde_json::to_vec(&event) {
+                Ok(body) => body,
+                Err(e) => {
+                    error!(
+                        delivery_log,
+                        "Unable to serialize webhook event";
+                        "error" => format!("{:?}", e)
+                    );
+                    continue;
+                }
+            };
+
+            let signature = match secret
+                .as_ref()
+                .map(|secret| sign(secret, &body))
+                .transpose()
+            {
+                Ok(signature) => signature,
+                Err(e) => {
+                    error!(delivery_log, "Unable to sign webhook event"; "error" => e);
+                    continue;
+                }
+            };
+
+            for url in &urls {
+                delivery_executor.spawn(
+                    deliver(
+                        client.clone(),
+                        url.clone(),
+                        body.clone(),
+                        signature.clone(),
+                        max_retries,
+                        delivery_log.clone(),
+                    ),
+                    "webhook_delivery",
+                );
+            }
+        }
+    };
+    executor.spawn(delivery_future, "webhook_notifier");
+
+    let stall_tx = tx.clone();
+    let stall_finality = finality.clone();
+    let stall_epochs = config.finality_stall_epochs;
+    let stall_topic_enabled = config.topics.contains(&Topic::FinalityStall);
+    let epoch_duration = Duration::from_secs(seconds_per_epoch);
+    let stall_future = async move {
+        let mut interval = tokio::time::interval(epoch_duration);
+        loop {
+            interval.tick().await;
+
+            let mut status = stall_finality.lock();
+            let epochs_without_finality =
+                status.since.elapsed().as_secs() / std::cmp::max(seconds_per_epoch, 1);
+
+            if stall_topic_enabled
+                && epochs_without_finality >= stall_epochs
+                && !status.stall_reported
+            {
+                status.stall_reported = true;
+                let _ = stall_tx.send(WebhookEvent::FinalityStall {
+                    finalized_epoch: status.epoch,
+                    epochs_without_finality,
+                });
+            }
+        }
+    };
+    executor.spawn(stall_future, "webhook_finality_stall");
+
+    info!(
+        log,
+        "Webhook notifier started";
+        "urls" => config.urls.len(),
+        "topics" => format!("{:?}", config.topics),
+        "signed" => config.secret_file.is_some(),
+    );
+
+    Ok(WebhookSender {
+        inner: Some(Inner {
+            tx,
+            topics: config.topics.iter().copied().collect(),
+            long_reorg_distance: config.long_reorg_distance,
+            balance_drop_threshold: config.balance_drop_threshold,
+            finality,
+        }),
+    })
+}
+
+/// POSTs `body` to `url`, retrying up to `max_retries` times with exponential backoff.
+async fn deliver(
+    client: reqwest::Client,
+    url: String,
+    body: Vec<u8>,
+    signature: Option<String>,
+    max_retries: u32,
+    log: Logger,
+) {
+    let mut delay = INITIAL_RETRY_DELAY;
+
+    for attempt in 0..=max_retries {
+        let mut request = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(body.clone());
+
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", signature));
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!(log, "Delivered webhook event"; "url" => &url);
+                return;
+            }
+            Ok(response) => format!("status {}", response.status()),
+            Err(e) => format!("{:?}", e),
+        };
+
+        if attempt < max_retries {
+            debug!(
+                log,
+                "Failed to deliver webhook event, retrying";
+                "url" => &url,
+                "error" => error,
+                "retry_in_secs" => delay.as_secs()
+            );
+            tokio::time::delay_for(delay).await;
+            delay *= 2;
+        } else {
+            warn!(
+                log,
+                "Failed to deliver webhook event";
+                "url" => &url,
+                "error" => error,
+                "attempts" => max_retries + 1
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_payload() {
+        assert_eq!(
+            sign(b"key", b"The quick brown fox jumps over the lazy dog"),
+            Ok("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8".to_string())
+        );
+    }
+
+    #[test]
+    fn event_json() {
+        let event = WebhookEvent::AttesterSlashing {
+            attester_indices: vec![1, 42],
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"attester_slashing","data":{"attester_indices":[1,42]}}"#
+        );
+        assert_eq!(event.topic(), Topic::Slashing);
+    }
+
+    #[test]
+    fn parse_topics() {
+        assert_eq!("finality_stall".parse(), Ok(Topic::FinalityStall));
+        assert_eq!("long_reorg".parse(), Ok(Topic::LongReorg));
+        assert_eq!("slashing".parse(), Ok(Topic::Slashing));
+        assert_eq!("balance_drop".parse(), Ok(Topic::BalanceDrop));
+        assert!("balance".parse::<Topic>().is_err());
+    }
+
+    #[test]
+    fn find_balance_drops() {
+        let previous = [32, 32, 32, 32];
+        let current = [31, 29, 33, 28, 32];
+
+        assert_eq!(
+            balance_drops(&previous, &current, 3),
+            vec![
+                BalanceDrop {
+                    validator_index: 1,
+                    previous_balance: 32,
+                    balance: 29,
+                },
+                BalanceDrop {
+                    validator_index: 3,
+                    previous_balance: 32,
+                    balance: 28,
+                },
+            ]
+        );
+        assert!(balance_drops(&previous, &current, 5).is_empty());
+    }
+}
@@ -282,3 +282,10 @@ pub fn observe(histogram: &Result<Histogram>, value: f64) {
         histogram.observe(value);
     }
 }
+
+/// Sets the value of the histogram in `histogram_vec` with the given `name` manually.
+pub fn observe_vec(histogram_vec: &Result<HistogramVec>, name: &[&str], value: f64) {
+    if let Some(histogram) = get_histogram(histogram_vec, name) {
+        histogram.observe(value);
+    }
+}
@@ -4,8 +4,11 @@ extern crate lazy_static;
 use lighthouse_metrics::{
     inc_counter, try_create_int_counter, IntCounter, Result as MetricsResult,
 };
+use slog::{Drain, Level, OwnedKVList, Record};
 use slog_term::Decorator;
 use std::io::{Result, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 pub const MAX_MESSAGE_WIDTH: usize = 40;
 
@@ -20,6 +23,58 @@ lazy_static! {
         try_create_int_counter("crit_total", "Count of crits logged");
 }
 
+/// A handle to the minimum level of the logs passed on by a `LevelFilter`, which may be changed
+/// whilst it is in use.
+#[derive(Clone)]
+pub struct LogLevel(Arc<AtomicUsize>);
+
+impl LogLevel {
+    pub fn new(level: Level) -> Self {
+        Self(Arc::new(AtomicUsize::new(level.as_usize())))
+    }
+
+    pub fn get(&self) -> Level {
+        Level::from_usize(self.0.load(Ordering::Relaxed)).unwrap_or(Level::Info)
+    }
+
+    pub fn set(&self, level: Level) {
+        self.0.store(level.as_usize(), Ordering::Relaxed);
+    }
+}
+
+/// Like `slog::LevelFilter`, except that the level is read from a `LogLevel` for each record.
+pub struct LevelFilter<D: Drain> {
+    drain: D,
+    level: LogLevel,
+}
+
+impl<D: Drain> LevelFilter<D> {
+    pub fn new(drain: D, level: LogLevel) -> Self {
+        Self { drain, level }
+    }
+}
+
+impl<D: Drain> Drain for LevelFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(
+        &self,
+        record: &Record,
+        values: &OwnedKVList,
+    ) -> std::result::Result<Self::Ok, Self::Err> {
+        if record.level().is_at_least(self.level.get()) {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_enabled(&self, level: Level) -> bool {
+        level.is_at_least(self.level.get()) && self.drain.is_enabled(level)
+    }
+}
+
 pub struct AlignedTermDecorator<D: Decorator> {
     wrapped: D,
     message_width: usize,
@@ -3,26 +3,49 @@
 //!
 //! Presently, this is only used for testing but it _could_ become a user-facing library.
 
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "record")]
+pub mod record;
+
 use eth2_config::Eth2Config;
-use reqwest::{Client, ClientBuilder, Response, StatusCode};
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{Client, ClientBuilder, Response};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use ssz::Encode;
+use std::fmt;
 use std::marker::PhantomData;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::{fs, io::AsyncWriteExt};
 use types::{
     Attestation, AttestationData, AttesterSlashing, BeaconBlock, BeaconState, CommitteeIndex,
     Epoch, EthSpec, Fork, Graffiti, Hash256, ProposerSlashing, PublicKey, PublicKeyBytes,
     Signature, SignedAggregateAndProof, SignedBeaconBlock, Slot, SubnetId,
 };
-use url::Url;
 
 pub use operation_pool::PersistedOperationPool;
 pub use proto_array::core::ProtoArray;
+pub use reqwest::{Method, RequestBuilder, StatusCode};
 pub use rest_types::{
-    CanonicalHeadResponse, Committee, HeadBeaconBlock, Health, IndividualVotesRequest,
-    IndividualVotesResponse, SyncingResponse, ValidatorDutiesRequest, ValidatorDutyBytes,
-    ValidatorRequest, ValidatorResponse, ValidatorSubscription,
+    AggregateAttestationQuery, AttestationPoolsResponse, AttestationQuery, AttesterDuty,
+    BatchedDutiesQuery, BlockEvent, BlockHeaderResponse, BlockProductionQuery, BlockTimings,
+    BlockTimingsQuery, CachesResponse, CanonicalHeadResponse, ClientDiversityResponse,
+    ClientProposals, Committee, CommitteeAggregation, CommitteesQuery, ConfigReloadResponse,
+    DatabaseBackupRequest, DatabaseBackupResponse, DepositContract, DropCachesRequest,
+    DroppableCache, EpochAttesterDuties, EpochQuery, ForkChoicePruneResponse, GraffitiProposals,
+    HeadBeaconBlock, HeadersQuery, Health, IndividualVotesRequest, IndividualVotesResponse,
+    NaiveAggregationPoolInfo, NodeConfigResponse, ObservedAttestationsInfo,
+    ObservedGossipAggregate, ObservedGossipBlock, ObservedGossipQuery, ObservedGossipResponse,
+    OpPoolAttestationsInfo, OpPoolSlotAttestations, PoolAttestationsQuery, PoolSubmissionResponse,
+    RandaoMix, RandaoQuery, ShutdownAtSlot, SlotOrRootQuery, SlotQuery, SpecConfig,
+    SyncingResponse, TrustedPeerInfo, TrustedPeersRequest, ValidatorDutiesRequest,
+    ValidatorDutyBytes, ValidatorRequest, ValidatorResponse, ValidatorStatus,
+    ValidatorSubscription, ValidatorsQuery, WeakSubjectivityPeriodResponse,
+    SNAPPY_CONTENT_ENCODING, SSZ_CONTENT_TYPE,
 };
+pub use url::Url;
 
 // Setting a long timeout for debug ensures that crypto-heavy operations can still succeed.
 #[cfg(debug_assertions)]
@@ -39,14 +62,13 @@ pub struct RemoteBeaconNode<E: EthSpec> {
 
 impl<E: EthSpec> RemoteBeaconNode<E> {
     /// Uses the default HTTP timeout.
-    pub fn new(http_endpoint: String) -> Result<Self, String> {
+    pub fn new(http_endpoint: String) -> Result<Self, Error> {
         Self::new_with_timeout(http_endpoint, Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
     }
 
-    pub fn new_with_timeout(http_endpoint: String, timeout: Duration) -> Result<Self, String> {
+    pub fn new_with_timeout(http_endpoint: String, timeout: Duration) -> Result<Self, Error> {
         Ok(Self {
-            http: HttpClient::new(http_endpoint, timeout)
-                .map_err(|e| format!("Unable to create http client: {:?}", e))?,
+            http: HttpClient::new(http_endpoint, timeout)?,
         })
     }
 }
@@ -60,33 +82,178 @@ pub enum Error {
     /// There was an error when encoding/decoding an object using serde.
     SerdeJsonError(serde_json::Error),
     /// The server responded to the request, however it did not return a 200-type success code.
-    DidNotSucceed { status: StatusCode, body: String },
+    ///
+    /// The `body` is the error message from the server.
+    DidNotSucceed {
+        method: Method,
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
     /// The request input was invalid.
     InvalidInput,
+    /// The server URL cannot be used as a base for API paths (e.g., `localhost:5052` parses as an
+    /// opaque URL with the `localhost` scheme).
+    InvalidUrl(Url),
+    /// Unable to write a response to disk.
+    IoError(std::io::Error),
+    /// Unable to compress a request body with snappy.
+    SnappyError(snap::Error),
+}
+
+impl Error {
+    /// Returns `true` if the request was aborted because it exceeded its timeout.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::ReqwestError(e) => e.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Returns the HTTP status returned by the server, if it responded with an error.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::DidNotSucceed { status, .. } => Some(*status),
+            Error::ReqwestError(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// Returns the error message returned by the server, if it responded with an error.
+    pub fn server_message(&self) -> Option<&str> {
+        match self {
+            Error::DidNotSucceed { body, .. } => Some(body.trim()),
+            _ => None,
+        }
+    }
+
+    /// Returns each of the failures described by the server, if it responded with an error.
+    ///
+    /// Endpoints which accept a list of objects (e.g., `/validator/attestations`) describe the
+    /// failure of each object which could not be processed on a separate line.
+    pub fn failures(&self) -> Vec<&str> {
+        self.server_message()
+            .map(|message| {
+                message
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UrlParseError(e) => write!(f, "unable to parse url: {}", e),
+            Error::ReqwestError(e) => write!(f, "http request failed: {}", e),
+            Error::SerdeJsonError(e) => write!(f, "json error: {}", e),
+            Error::DidNotSucceed {
+                method,
+                url,
+                status,
+                body,
+            } => write!(f, "{} {} returned {}: {}", method, url, status, body),
+            Error::InvalidInput => write!(f, "invalid request input"),
+            Error::InvalidUrl(url) => write!(f, "url cannot be used as an api base: {}", url),
+            Error::IoError(e) => write!(f, "io error: {}", e),
+            Error::SnappyError(e) => write!(f, "snappy error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UrlParseError(e) => Some(e),
+            Error::ReqwestError(e) => Some(e),
+            Error::SerdeJsonError(e) => Some(e),
+            Error::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Observes the requests made by a `HttpClient`, allowing applications to log them, add headers
+/// (e.g., for tracing) and capture the bodies of error responses.
+///
+/// All methods default to doing nothing.
+pub trait RequestObserver: Send + Sync {
+    /// Called before each request is sent. The returned builder is used to send the request.
+    fn on_request(&self, _method: &Method, _url: &Url, request: RequestBuilder) -> RequestBuilder {
+        request
+    }
+
+    /// Called once each request completes, with the status of the response (or `None` if no
+    /// response was received) and the time taken to receive it.
+    fn on_response(
+        &self,
+        _method: &Method,
+        _url: &Url,
+        _status: Option<StatusCode>,
+        _duration: Duration,
+    ) {
+    }
+
+    /// Called with the raw body of a response which did not succeed.
+    fn on_error_body(&self, _url: &Url, _status: StatusCode, _body: &str) {}
 }
 
 #[derive(Clone)]
 pub struct HttpClient<E> {
     client: Client,
+    /// Used for downloads which may take longer than the timeout of `client` (e.g., states).
+    ///
+    /// Only connecting is subject to a timeout.
+    download_client: Client,
     url: Url,
-    timeout: Duration,
+    /// Overrides the client-wide timeout for each request, if set.
+    request_timeout: Option<Duration>,
+    observer: Option<Arc<dyn RequestObserver>>,
     _phantom: PhantomData<E>,
 }
 
 impl<E: EthSpec> HttpClient<E> {
     /// Creates a new instance (without connecting to the node).
+    ///
+    /// Returns an error if `server_url` cannot be parsed or cannot be used as a base for API
+    /// paths.
     pub fn new(server_url: String, timeout: Duration) -> Result<Self, Error> {
+        let url = Url::parse(&server_url)?;
+
+        if url.cannot_be_a_base() {
+            return Err(Error::InvalidUrl(url));
+        }
+
         Ok(Self {
-            client: ClientBuilder::new()
-                .timeout(timeout)
-                .build()
-                .expect("should build from static configuration"),
-            url: Url::parse(&server_url)?,
-            timeout: Duration::from_secs(15),
+            client: ClientBuilder::new().timeout(timeout).build()?,
+            download_client: ClientBuilder::new().connect_timeout(timeout).build()?,
+            url,
+            request_timeout: None,
+            observer: None,
             _phantom: PhantomData,
         })
     }
 
+    /// Informs `observer` of every request made by this client (and its clones).
+    pub fn with_observer(mut self, observer: Arc<dyn RequestObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Returns a client whose requests time out after `timeout`, instead of the timeout given to
+    /// `Self::new`.
+    ///
+    /// Useful for requests which are worthless after a deadline (e.g., producing an attestation
+    /// after the end of its slot).
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let mut client = self.clone();
+        client.request_timeout = Some(timeout);
+        client
+    }
+
     pub fn beacon(&self) -> Beacon<E> {
         Beacon(self.clone())
     }
@@ -111,54 +278,204 @@ impl<E: EthSpec> HttpClient<E> {
         Consensus(self.clone())
     }
 
+    pub fn lighthouse(&self) -> Lighthouse<E> {
+        Lighthouse(self.clone())
+    }
+
+    /// Performs a GET request to `path` (e.g., `lighthouse/syncing`) with the given query pairs,
+    /// decoding the JSON response as a `T`.
+    ///
+    /// Allows calling endpoints which do not have a typed method on this client. Use
+    /// `serde_json::Value` as `T` if the response does not have a known type.
+    pub async fn get_path<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, Error> {
+        let url = self.url(path)?;
+        self.json_get_query(url, query).await
+    }
+
+    /// Performs a POST request to `path` with `body` encoded as JSON, decoding the JSON response
+    /// as a `T`.
+    ///
+    /// See `Self::get_path`.
+    pub async fn post_path<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, Error> {
+        let url = self.url(path)?;
+        let response = self.json_post(url, body).await?;
+        let success = self.error_for_status(Method::POST, response).await?;
+        success.json::<T>().await.map_err(Error::from)
+    }
+
+    /// Returns the server URL with the `/`-separated segments of `path` appended to it.
+    ///
+    /// Unlike `Url::join`, any path on the server URL is retained (e.g., `http://host/api` with
+    /// `node/version` becomes `http://host/api/node/version`).
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.url.join(path).map_err(|e| e.into())
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.url.clone()))?
+            .pop_if_empty()
+            .extend(path.split('/').filter(|segment| !segment.is_empty()));
+        Ok(url)
     }
 
     pub async fn json_post<T: Serialize>(&self, url: Url, body: T) -> Result<Response, Error> {
-        self.client
+        let request = self.client.post(&url.to_string()).json(&body);
+        self.send(Method::POST, &url, request).await
+    }
+
+    /// Perform a POST request with `body` encoded as SSZ and compressed with snappy, as on gossip.
+    pub async fn ssz_snappy_post<T: Encode>(&self, url: Url, body: &T) -> Result<Response, Error> {
+        let compressed = snap::raw::Encoder::new()
+            .compress_vec(&body.as_ssz_bytes())
+            .map_err(Error::SnappyError)?;
+        let request = self
+            .client
             .post(&url.to_string())
-            .json(&body)
-            .send()
-            .await
-            .map_err(Error::from)
+            .header(CONTENT_TYPE, SSZ_CONTENT_TYPE)
+            .header(CONTENT_ENCODING, SNAPPY_CONTENT_ENCODING)
+            .body(compressed);
+        self.send(Method::POST, &url, request).await
     }
 
     pub async fn json_get<T: DeserializeOwned>(
         &self,
-        mut url: Url,
+        url: Url,
         query_pairs: Vec<(String, String)>,
     ) -> Result<T, Error> {
-        query_pairs.into_iter().for_each(|(key, param)| {
-            url.query_pairs_mut().append_pair(&key, &param);
-        });
+        self.json_get_query(url, &query_pairs).await
+    }
 
-        let response = self
-            .client
-            .get(&url.to_string())
-            .send()
-            .await
-            .map_err(Error::from)?;
+    /// Perform a GET request with the query parameters serialized from `query` (e.g., a
+    /// `rest_types::EpochQuery`).
+    pub async fn json_get_query<T: DeserializeOwned, Q: Serialize + ?Sized>(
+        &self,
+        url: Url,
+        query: &Q,
+    ) -> Result<T, Error> {
+        let request = self.client.get(&url.to_string()).query(query);
+        let response = self.send(Method::GET, &url, request).await?;
 
-        let success = error_for_status(response).await.map_err(Error::from)?;
+        let success = self.error_for_status(Method::GET, response).await?;
         success.json::<T>().await.map_err(Error::from)
     }
-}
 
-/// Returns an `Error` (with a description) if the `response` was not a 200-type success response.
-///
-/// Distinct from `Response::error_for_status` because it includes the body of the response as
-/// text. This ensures the error message from the server is not discarded.
-async fn error_for_status(response: Response) -> Result<Response, Error> {
-    let status = response.status();
+    /// Perform a GET request for the SSZ encoding of a resource, writing the response body to
+    /// `path` as it is received instead of buffering it in memory. Returns the number of bytes
+    /// written.
+    ///
+    /// Any existing file at `path` is overwritten. The file is removed if the download fails.
+    ///
+    /// The download is not subject to the request timeout of this client, since large responses
+    /// may take much longer to receive.
+    pub async fn ssz_get_to_file<Q: Serialize + ?Sized>(
+        &self,
+        url: Url,
+        query: &Q,
+        path: &Path,
+    ) -> Result<u64, Error> {
+        let request = self
+            .download_client
+            .get(&url.to_string())
+            .query(query)
+            .header("accept", "application/ssz");
+        let response = self
+            .send_without_timeout(Method::GET, &url, request)
+            .await?;
+        let mut success = self.error_for_status(Method::GET, response).await?;
+
+        let mut file = fs::File::create(path).await.map_err(Error::IoError)?;
+        let mut written = 0;
+        let result = async {
+            while let Some(chunk) = success.chunk().await? {
+                file.write_all(&chunk).await.map_err(Error::IoError)?;
+                written += chunk.len() as u64;
+            }
+            file.sync_all().await.map_err(Error::IoError)
+        }
+        .await;
+
+        match result {
+            Ok(()) => Ok(written),
+            Err(e) => {
+                drop(file);
+                let _ = fs::remove_file(path).await;
+                Err(e)
+            }
+        }
+    }
 
-    if status.is_success() {
-        Ok(response)
-    } else {
-        let text_result = response.text().await;
-        match text_result {
-            Err(e) => Err(Error::ReqwestError(e)),
-            Ok(body) => Err(Error::DidNotSucceed { status, body }),
+    /// Sends `request`, informing the observer (if any).
+    async fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        request: RequestBuilder,
+    ) -> Result<Response, Error> {
+        let request = match self.request_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+        self.send_without_timeout(method, url, request).await
+    }
+
+    /// Sends `request` without applying the timeout set by `Self::with_timeout`, informing the
+    /// observer (if any).
+    async fn send_without_timeout(
+        &self,
+        method: Method,
+        url: &Url,
+        request: RequestBuilder,
+    ) -> Result<Response, Error> {
+        let request = match &self.observer {
+            Some(observer) => observer.on_request(&method, url, request),
+            None => request,
+        };
+
+        let start = Instant::now();
+        let result = request.send().await;
+
+        if let Some(observer) = &self.observer {
+            let status = result.as_ref().ok().map(Response::status);
+            observer.on_response(&method, url, status, start.elapsed());
+        }
+
+        result.map_err(Error::from)
+    }
+
+    /// Returns an `Error` (with a description) if the `response` was not a 200-type success
+    /// response.
+    ///
+    /// Distinct from `Response::error_for_status` because it includes the body of the response as
+    /// text. This ensures the error message from the server is not discarded.
+    async fn error_for_status(
+        &self,
+        method: Method,
+        response: Response,
+    ) -> Result<Response, Error> {
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let url = response.url().clone();
+            let body = response.text().await.map_err(Error::ReqwestError)?;
+
+            if let Some(observer) = &self.observer {
+                observer.on_error_body(&url, status, &body);
+            }
+
+            Err(Error::DidNotSucceed {
+                method,
+                url,
+                status,
+                body,
+            })
         }
     }
 }
@@ -167,6 +484,9 @@ async fn error_for_status(response: Response) -> Result<Response, Error> {
 pub enum PublishStatus {
     /// The object was valid and has been published to the network.
     Valid,
+    /// The object (or an equivalent one) was already known to the server, so it has not been
+    /// published again. Retrying a submission may produce this status.
+    AlreadyKnown,
     /// The object was not valid and may or may not have been published to the network.
     Invalid(String),
     /// The server responded with an unknown status code. The object may or may not have been
@@ -181,16 +501,91 @@ impl PublishStatus {
     }
 }
 
+/// The outcome of publishing a block with `Validator::publish_block`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BlockPublishStatus {
+    /// The block was valid, has been published to the network and imported by the node.
+    Imported,
+    /// The block has been published to the network, however the node failed to import it.
+    BroadcastOnly(String),
+    /// The block was not valid and has not been published to the network.
+    Invalid(String),
+    /// The server responded with an unknown status code. The block may or may not have been
+    /// published to the network.
+    Unknown,
+}
+
+impl BlockPublishStatus {
+    /// Returns `true` if the block was imported by the node.
+    pub fn is_imported(&self) -> bool {
+        *self == BlockPublishStatus::Imported
+    }
+
+    /// Returns `true` if the block is known to have been published to the network.
+    pub fn is_published(&self) -> bool {
+        match self {
+            BlockPublishStatus::Imported | BlockPublishStatus::BroadcastOnly(_) => true,
+            BlockPublishStatus::Invalid(_) | BlockPublishStatus::Unknown => false,
+        }
+    }
+}
+
+/// Returns the status of an object submitted for the operation pool, given the 200 `response` from
+/// the server.
+async fn pool_submission_status(response: Response) -> Result<PublishStatus, Error> {
+    let submission: PoolSubmissionResponse = response.json().await.map_err(Error::from)?;
+
+    if submission.already_known {
+        Ok(PublishStatus::AlreadyKnown)
+    } else {
+        Ok(PublishStatus::Valid)
+    }
+}
+
+/// Returns the status of published attestations, given the `response` from the server.
+async fn attestations_publish_status<E: EthSpec>(
+    client: &HttpClient<E>,
+    response: Response,
+) -> Result<PublishStatus, Error> {
+    match response.status() {
+        StatusCode::OK => pool_submission_status(response).await,
+        StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
+            response.text().await.map_err(Error::from)?,
+        )),
+        _ => client
+            .error_for_status(Method::POST, response)
+            .await
+            .map(|_| PublishStatus::Unknown),
+    }
+}
+
+/// Returns the status of a published block, given the `response` from the server.
+async fn block_publish_status<E: EthSpec>(
+    client: &HttpClient<E>,
+    response: Response,
+) -> Result<BlockPublishStatus, Error> {
+    match response.status() {
+        StatusCode::OK => Ok(BlockPublishStatus::Imported),
+        StatusCode::ACCEPTED => Ok(BlockPublishStatus::BroadcastOnly(
+            response.text().await.map_err(Error::from)?,
+        )),
+        StatusCode::BAD_REQUEST => Ok(BlockPublishStatus::Invalid(
+            response.text().await.map_err(Error::from)?,
+        )),
+        _ => client
+            .error_for_status(Method::POST, response)
+            .await
+            .map(|_| BlockPublishStatus::Unknown),
+    }
+}
+
 /// Provides the functions on the `/validator` endpoint of the node.
 #[derive(Clone)]
 pub struct Validator<E>(HttpClient<E>);
 
 impl<E: EthSpec> Validator<E> {
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.0
-            .url("validator/")
-            .and_then(move |url| url.join(path).map_err(Error::from))
-            .map_err(Into::into)
+        self.0.url(&format!("validator/{}", path))
     }
 
     /// Produces an unsigned attestation.
@@ -199,14 +594,14 @@ impl<E: EthSpec> Validator<E> {
         slot: Slot,
         committee_index: CommitteeIndex,
     ) -> Result<Attestation<E>, Error> {
-        let query_params = vec![
-            ("slot".into(), format!("{}", slot)),
-            ("committee_index".into(), format!("{}", committee_index)),
-        ];
+        let query = AttestationQuery {
+            slot,
+            committee_index,
+        };
 
         let client = self.0.clone();
         let url = self.url("attestation")?;
-        client.json_get(url, query_params).await
+        client.json_get_query(url, &query).await
     }
 
     /// Produces an aggregate attestation.
@@ -214,14 +609,13 @@ impl<E: EthSpec> Validator<E> {
         &self,
         attestation_data: &AttestationData,
     ) -> Result<Attestation<E>, Error> {
-        let query_params = vec![(
-            "attestation_data".into(),
-            as_ssz_hex_string(attestation_data),
-        )];
+        let query = AggregateAttestationQuery {
+            attestation_data: attestation_data.clone(),
+        };
 
         let client = self.0.clone();
         let url = self.url("aggregate_attestation")?;
-        client.json_get(url, query_params).await
+        client.json_get_query(url, &query).await
     }
 
     /// Posts a list of attestations to the beacon node, expecting it to verify it and publish it to the network.
@@ -232,17 +626,19 @@ impl<E: EthSpec> Validator<E> {
         let client = self.0.clone();
         let url = self.url("attestations")?;
         let response = client.json_post::<_>(url, attestation).await?;
+        attestations_publish_status(&client, response).await
+    }
 
-        match response.status() {
-            StatusCode::OK => Ok(PublishStatus::Valid),
-            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
-                response.text().await.map_err(Error::from)?,
-            )),
-            _ => response
-                .error_for_status()
-                .map_err(Error::from)
-                .map(|_| PublishStatus::Unknown),
-        }
+    /// As per `publish_attestations`, but sends the attestations as snappy-compressed SSZ, which is
+    /// faster to encode and decode than JSON.
+    pub async fn publish_attestations_ssz(
+        &self,
+        attestations: Vec<(Attestation<E>, SubnetId)>,
+    ) -> Result<PublishStatus, Error> {
+        let client = self.0.clone();
+        let url = self.url("attestations")?;
+        let response = client.ssz_snappy_post(url, &attestations).await?;
+        attestations_publish_status(&client, response).await
     }
 
     /// Posts a list of signed aggregates and proofs to the beacon node, expecting it to verify it and publish it to the network.
@@ -257,13 +653,13 @@ impl<E: EthSpec> Validator<E> {
             .await?;
 
         match response.status() {
-            StatusCode::OK => Ok(PublishStatus::Valid),
+            StatusCode::OK => pool_submission_status(response).await,
             StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
                 response.text().await.map_err(Error::from)?,
             )),
-            _ => response
-                .error_for_status()
-                .map_err(Error::from)
+            _ => client
+                .error_for_status(Method::POST, response)
+                .await
                 .map(|_| PublishStatus::Unknown),
         }
     }
@@ -286,26 +682,34 @@ impl<E: EthSpec> Validator<E> {
 
         let url = self.url("duties")?;
         let response = client.json_post::<_>(url, bulk_request).await?;
-        let success = error_for_status(response).await.map_err(Error::from)?;
+        let success = client.error_for_status(Method::POST, response).await?;
         success.json().await.map_err(Error::from)
     }
 
     /// Posts a block to the beacon node, expecting it to verify it and publish it to the network.
-    pub async fn publish_block(&self, block: SignedBeaconBlock<E>) -> Result<PublishStatus, Error> {
+    ///
+    /// A block which is published but not imported by the node (e.g., due to an internal error
+    /// during import) returns `BlockPublishStatus::BroadcastOnly`.
+    pub async fn publish_block(
+        &self,
+        block: SignedBeaconBlock<E>,
+    ) -> Result<BlockPublishStatus, Error> {
         let client = self.0.clone();
         let url = self.url("block")?;
         let response = client.json_post::<_>(url, block).await?;
+        block_publish_status(&client, response).await
+    }
 
-        match response.status() {
-            StatusCode::OK => Ok(PublishStatus::Valid),
-            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
-                response.text().await.map_err(Error::from)?,
-            )),
-            _ => response
-                .error_for_status()
-                .map_err(Error::from)
-                .map(|_| PublishStatus::Unknown),
-        }
+    /// As per `publish_block`, but sends the block as snappy-compressed SSZ, which is faster to
+    /// encode and decode than JSON.
+    pub async fn publish_block_ssz(
+        &self,
+        block: &SignedBeaconBlock<E>,
+    ) -> Result<BlockPublishStatus, Error> {
+        let client = self.0.clone();
+        let url = self.url("block")?;
+        let response = client.ssz_snappy_post(url, block).await?;
+        block_publish_status(&client, response).await
     }
 
     /// Requests a new (unsigned) block from the beacon node.
@@ -318,16 +722,36 @@ impl<E: EthSpec> Validator<E> {
         let client = self.0.clone();
         let url = self.url("block")?;
 
-        let mut query_pairs = vec![
-            ("slot".into(), format!("{}", slot.as_u64())),
-            ("randao_reveal".into(), as_ssz_hex_string(&randao_reveal)),
-        ];
+        let query = BlockProductionQuery {
+            slot,
+            randao_reveal,
+            graffiti,
+            skip_randao_verification: false,
+        };
 
-        if let Some(graffiti_bytes) = graffiti {
-            query_pairs.push(("graffiti".into(), as_ssz_hex_string(&graffiti_bytes)));
-        }
+        client.json_get_query(url, &query).await
+    }
 
-        client.json_get::<BeaconBlock<E>>(url, query_pairs).await
+    /// Requests a new (unsigned) block from the beacon node with an empty randao reveal, which is
+    /// not verified.
+    ///
+    /// The beacon node must have been started with `--http-allow-skip-randao-verification`.
+    pub async fn produce_block_skip_randao_verification(
+        &self,
+        slot: Slot,
+        graffiti: Option<Graffiti>,
+    ) -> Result<BeaconBlock<E>, Error> {
+        let client = self.0.clone();
+        let url = self.url("block")?;
+
+        let query = BlockProductionQuery {
+            slot,
+            randao_reveal: Signature::empty(),
+            graffiti,
+            skip_randao_verification: true,
+        };
+
+        client.json_get_query(url, &query).await
     }
 
     /// Subscribes a list of validators to particular slots for attestation production/publication.
@@ -344,9 +768,9 @@ impl<E: EthSpec> Validator<E> {
             StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
                 response.text().await.map_err(Error::from)?,
             )),
-            _ => response
-                .error_for_status()
-                .map_err(Error::from)
+            _ => client
+                .error_for_status(Method::POST, response)
+                .await
                 .map(|_| PublishStatus::Unknown),
         }
     }
@@ -358,10 +782,7 @@ pub struct Beacon<E>(HttpClient<E>);
 
 impl<E: EthSpec> Beacon<E> {
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.0
-            .url("beacon/")
-            .and_then(move |url| url.join(path).map_err(Error::from))
-            .map_err(Into::into)
+        self.0.url(&format!("beacon/{}", path))
     }
 
     /// Returns the genesis time.
@@ -404,8 +825,7 @@ impl<E: EthSpec> Beacon<E> {
         &self,
         slot: Slot,
     ) -> Result<(SignedBeaconBlock<E>, Hash256), Error> {
-        self.get_block("slot".to_string(), format!("{}", slot.as_u64()))
-            .await
+        self.get_block(SlotOrRootQuery::slot(slot)).await
     }
 
     /// Returns the block and block root at the given root.
@@ -413,28 +833,25 @@ impl<E: EthSpec> Beacon<E> {
         &self,
         root: Hash256,
     ) -> Result<(SignedBeaconBlock<E>, Hash256), Error> {
-        self.get_block("root".to_string(), root_as_string(root))
-            .await
+        self.get_block(SlotOrRootQuery::root(root)).await
     }
 
-    /// Returns the block and block root at the given slot.
+    /// Returns the block and block root selected by `query`.
     async fn get_block(
         &self,
-        query_key: String,
-        query_param: String,
+        query: SlotOrRootQuery,
     ) -> Result<(SignedBeaconBlock<E>, Hash256), Error> {
         let client = self.0.clone();
         let url = self.url("block")?;
         client
-            .json_get::<BlockResponse<E>>(url, vec![(query_key, query_param)])
+            .json_get_query::<BlockResponse<E>, _>(url, &query)
             .await
             .map(|response| (response.beacon_block, response.root))
     }
 
     /// Returns the state and state root at the given slot.
     pub async fn get_state_by_slot(&self, slot: Slot) -> Result<(BeaconState<E>, Hash256), Error> {
-        self.get_state("slot".to_string(), format!("{}", slot.as_u64()))
-            .await
+        self.get_state(SlotOrRootQuery::slot(slot)).await
     }
 
     /// Returns the state and state root at the given root.
@@ -442,38 +859,72 @@ impl<E: EthSpec> Beacon<E> {
         &self,
         root: Hash256,
     ) -> Result<(BeaconState<E>, Hash256), Error> {
-        self.get_state("root".to_string(), root_as_string(root))
+        self.get_state(SlotOrRootQuery::root(root)).await
+    }
+
+    /// Writes the SSZ-encoded `rest_types::StateResponse` (the state root and state) at the given
+    /// slot to `path`, without holding the state in memory. Returns the number of bytes written.
+    pub async fn get_state_by_slot_to_file(&self, slot: Slot, path: &Path) -> Result<u64, Error> {
+        self.get_state_to_file(SlotOrRootQuery::slot(slot), path)
+            .await
+    }
+
+    /// Writes the SSZ-encoded `rest_types::StateResponse` (the state root and state) at the given
+    /// root to `path`, without holding the state in memory. Returns the number of bytes written.
+    pub async fn get_state_by_root_to_file(
+        &self,
+        root: Hash256,
+        path: &Path,
+    ) -> Result<u64, Error> {
+        self.get_state_to_file(SlotOrRootQuery::root(root), path)
             .await
     }
 
+    /// Writes the SSZ-encoded `rest_types::StateResponse` selected by `query` to `path`.
+    async fn get_state_to_file(&self, query: SlotOrRootQuery, path: &Path) -> Result<u64, Error> {
+        let client = self.0.clone();
+        let url = self.url("state")?;
+        client.ssz_get_to_file(url, &query, path).await
+    }
+
+    /// Writes the SSZ-encoded genesis `BeaconState` to `path`, without holding the state in
+    /// memory. Returns the number of bytes written.
+    pub async fn get_genesis_state_to_file(&self, path: &Path) -> Result<u64, Error> {
+        let client = self.0.clone();
+        let url = self.url("state/genesis")?;
+        client.ssz_get_to_file(url, &(), path).await
+    }
+
     /// Returns the root of the state at the given slot.
     pub async fn get_state_root(&self, slot: Slot) -> Result<Hash256, Error> {
         let client = self.0.clone();
         let url = self.url("state_root")?;
-        client
-            .json_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
-            .await
+        client.json_get_query(url, &SlotQuery { slot }).await
     }
 
     /// Returns the root of the block at the given slot.
     pub async fn get_block_root(&self, slot: Slot) -> Result<Hash256, Error> {
         let client = self.0.clone();
         let url = self.url("block_root")?;
-        client
-            .json_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
-            .await
+        client.json_get_query(url, &SlotQuery { slot }).await
     }
 
-    /// Returns the state and state root at the given slot.
-    async fn get_state(
+    /// Returns the headers of the canonical blocks matching `query`.
+    pub async fn get_headers(
         &self,
-        query_key: String,
-        query_param: String,
-    ) -> Result<(BeaconState<E>, Hash256), Error> {
+        query: HeadersQuery,
+    ) -> Result<Vec<BlockHeaderResponse>, Error> {
+        let client = self.0.clone();
+        let url = self.url("headers")?;
+        client.json_get_query(url, &query).await
+    }
+
+    /// Returns the state and state root selected by `query`.
+    async fn get_state(&self, query: SlotOrRootQuery) -> Result<(BeaconState<E>, Hash256), Error> {
         let client = self.0.clone();
         let url = self.url("state")?;
         client
-            .json_get::<StateResponse<E>>(url, vec![(query_key, query_param)])
+            .json_get_query::<StateResponse<E>, _>(url, &query)
             .await
             .map(|response| (response.beacon_state, response.root))
     }
@@ -499,7 +950,7 @@ impl<E: EthSpec> Beacon<E> {
 
         let url = self.url("validators")?;
         let response = client.json_post::<_>(url, bulk_request).await?;
-        let success = error_for_status(response).await.map_err(Error::from)?;
+        let success = client.error_for_status(Method::POST, response).await?;
         success.json().await.map_err(Error::from)
     }
 
@@ -512,15 +963,13 @@ impl<E: EthSpec> Beacon<E> {
         state_root: Option<Hash256>,
     ) -> Result<Vec<ValidatorResponse>, Error> {
         let client = self.0.clone();
-
-        let query_params = if let Some(state_root) = state_root {
-            vec![("state_root".into(), root_as_string(state_root))]
-        } else {
-            vec![]
+        let query = ValidatorsQuery {
+            state_root,
+            ..ValidatorsQuery::default()
         };
 
         let url = self.url("validators/all")?;
-        client.json_get(url, query_params).await
+        client.json_get_query(url, &query).await
     }
 
     /// Returns the active validators.
@@ -532,49 +981,116 @@ impl<E: EthSpec> Beacon<E> {
         state_root: Option<Hash256>,
     ) -> Result<Vec<ValidatorResponse>, Error> {
         let client = self.0.clone();
-
-        let query_params = if let Some(state_root) = state_root {
-            vec![("state_root".into(), root_as_string(state_root))]
-        } else {
-            vec![]
+        let query = ValidatorsQuery {
+            state_root,
+            ..ValidatorsQuery::default()
         };
 
         let url = self.url("validators/active")?;
-        client.json_get(url, query_params).await
+        client.json_get_query(url, &query).await
+    }
+
+    /// Returns the validators which match any of `statuses` at the current epoch of the state.
+    ///
+    /// If `state_root` is `Some`, the query will use the given state instead of the default
+    /// canonical head state.
+    pub async fn get_validators_by_status(
+        &self,
+        state_root: Option<Hash256>,
+        statuses: Vec<ValidatorStatus>,
+    ) -> Result<Vec<ValidatorResponse>, Error> {
+        let client = self.0.clone();
+        let query = ValidatorsQuery {
+            state_root,
+            status: statuses,
+            ..ValidatorsQuery::default()
+        };
+
+        let url = self.url("validators/all")?;
+        client.json_get_query(url, &query).await
+    }
+
+    /// Returns the RANDAO mix of the given `epoch`, or the current epoch if `None`.
+    ///
+    /// If `state_root` is `Some`, the query will use the given state instead of the default
+    /// canonical head state.
+    pub async fn get_randao_mix(
+        &self,
+        state_root: Option<Hash256>,
+        epoch: Option<Epoch>,
+    ) -> Result<Hash256, Error> {
+        let client = self.0.clone();
+        let query = RandaoQuery { state_root, epoch };
+
+        let url = self.url("randao")?;
+        client
+            .json_get_query::<RandaoMix, _>(url, &query)
+            .await
+            .map(|response| response.randao)
     }
 
     /// Returns committees at the given epoch.
     pub async fn get_committees(&self, epoch: Epoch) -> Result<Vec<Committee>, Error> {
+        self.get_committees_by_query(CommitteesQuery::epoch(epoch))
+            .await
+    }
+
+    /// Returns the committees matching `query`.
+    pub async fn get_committees_by_query(
+        &self,
+        query: CommitteesQuery,
+    ) -> Result<Vec<Committee>, Error> {
         let client = self.0.clone();
 
         let url = self.url("committees")?;
-        client
-            .json_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
-            .await
+        client.json_get_query(url, &query).await
+    }
+
+    /// Returns the attestations in the beacon node's pools, optionally only those for the given
+    /// `slot` and/or `committee_index`.
+    pub async fn get_pool_attestations(
+        &self,
+        slot: Option<Slot>,
+        committee_index: Option<CommitteeIndex>,
+    ) -> Result<Vec<Attestation<E>>, Error> {
+        let client = self.0.clone();
+        let query = PoolAttestationsQuery {
+            slot,
+            committee_index,
+        };
+
+        let url = self.url("pool/attestations")?;
+        client.json_get_query(url, &query).await
     }
 
+    /// Posts a proposer slashing to the beacon node, for inclusion in its operation pool.
+    ///
+    /// Returns `PublishStatus::AlreadyKnown` if a slashing of the same proposer is known.
     pub async fn proposer_slashing(
         &self,
         proposer_slashing: ProposerSlashing,
-    ) -> Result<bool, Error> {
+    ) -> Result<PublishStatus, Error> {
         let client = self.0.clone();
 
         let url = self.url("proposer_slashing")?;
         let response = client.json_post::<_>(url, proposer_slashing).await?;
-        let success = error_for_status(response).await.map_err(Error::from)?;
-        success.json().await.map_err(Error::from)
+        let success = client.error_for_status(Method::POST, response).await?;
+        pool_submission_status(success).await
     }
 
+    /// Posts an attester slashing to the beacon node, for inclusion in its operation pool.
+    ///
+    /// Returns `PublishStatus::AlreadyKnown` if slashings of all the attesters are known.
     pub async fn attester_slashing(
         &self,
         attester_slashing: AttesterSlashing<E>,
-    ) -> Result<bool, Error> {
+    ) -> Result<PublishStatus, Error> {
         let client = self.0.clone();
 
         let url = self.url("attester_slashing")?;
         let response = client.json_post::<_>(url, attester_slashing).await?;
-        let success = error_for_status(response).await.map_err(Error::from)?;
-        success.json().await.map_err(Error::from)
+        let success = client.error_for_status(Method::POST, response).await?;
+        pool_submission_status(success).await
     }
 }
 
@@ -584,10 +1100,7 @@ pub struct Spec<E>(HttpClient<E>);
 
 impl<E: EthSpec> Spec<E> {
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.0
-            .url("spec/")
-            .and_then(move |url| url.join(path).map_err(Error::from))
-            .map_err(Into::into)
+        self.0.url(&format!("spec/{}", path))
     }
 
     pub async fn get_eth2_config(&self) -> Result<Eth2Config, Error> {
@@ -595,6 +1108,25 @@ impl<E: EthSpec> Spec<E> {
         let url = self.url("eth2_config")?;
         client.json_get(url, vec![]).await
     }
+
+    /// Returns every spec constant, including the preset values of the `EthSpec`.
+    pub async fn get_config(&self) -> Result<SpecConfig, Error> {
+        let client = self.0.clone();
+        let url = self.url("config")?;
+        client.json_get(url, vec![]).await
+    }
+
+    pub async fn get_deposit_contract(&self) -> Result<DepositContract, Error> {
+        let client = self.0.clone();
+        let url = self.url("deposit_contract")?;
+        client.json_get(url, vec![]).await
+    }
+
+    pub async fn get_fork_schedule(&self) -> Result<Vec<Fork>, Error> {
+        let client = self.0.clone();
+        let url = self.url("fork_schedule")?;
+        client.json_get(url, vec![]).await
+    }
 }
 
 /// Provides the functions on the `/node` endpoint of the node.
@@ -603,10 +1135,7 @@ pub struct Node<E>(HttpClient<E>);
 
 impl<E: EthSpec> Node<E> {
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.0
-            .url("node/")
-            .and_then(move |url| url.join(path).map_err(Error::from))
-            .map_err(Into::into)
+        self.0.url(&format!("node/{}", path))
     }
 
     pub async fn get_version(&self) -> Result<String, Error> {
@@ -634,10 +1163,7 @@ pub struct Advanced<E>(HttpClient<E>);
 
 impl<E: EthSpec> Advanced<E> {
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.0
-            .url("advanced/")
-            .and_then(move |url| url.join(path).map_err(Error::from))
-            .map_err(Into::into)
+        self.0.url(&format!("advanced/{}", path))
     }
 
     /// Gets the core `ProtoArray` struct from the node.
@@ -647,6 +1173,32 @@ impl<E: EthSpec> Advanced<E> {
         client.json_get(url, vec![]).await
     }
 
+    /// Removes every block which does not descend from the finalized block from the node's fork
+    /// choice.
+    pub async fn prune_fork_choice(&self) -> Result<ForkChoicePruneResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("fork_choice/prune")?;
+        let request = client.client.post(&url.to_string());
+        let response = client.send(Method::POST, &url, request).await?;
+        let success = client.error_for_status(Method::POST, response).await?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Writes a backup of the node's database to a new directory called `name`, within the
+    /// directory given by the node's `--database-backup-dir` flag.
+    ///
+    /// `name` must not contain any path separators or already exist. The node refuses the request
+    /// unless it was started with `--database-backup-dir`.
+    pub async fn backup_database(&self, name: String) -> Result<DatabaseBackupResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("database/backup")?;
+        let response = client
+            .json_post(url, DatabaseBackupRequest { name })
+            .await?;
+        let success = client.error_for_status(Method::POST, response).await?;
+        success.json().await.map_err(Error::from)
+    }
+
     /// Gets the core `PersistedOperationPool` struct from the node.
     pub async fn get_operation_pool(&self) -> Result<PersistedOperationPool<E>, Error> {
         let client = self.0.clone();
@@ -661,10 +1213,7 @@ pub struct Consensus<E>(HttpClient<E>);
 
 impl<E: EthSpec> Consensus<E> {
     fn url(&self, path: &str) -> Result<Url, Error> {
-        self.0
-            .url("consensus/")
-            .and_then(move |url| url.join(path).map_err(Error::from))
-            .map_err(Into::into)
+        self.0.url(&format!("consensus/{}", path))
     }
 
     /// Gets a `IndividualVote` for each of the given `pubkeys`.
@@ -678,16 +1227,168 @@ impl<E: EthSpec> Consensus<E> {
 
         let url = self.url("individual_votes")?;
         let response = client.json_post::<_>(url, req_body).await?;
-        let success = error_for_status(response).await.map_err(Error::from)?;
+        let success = client.error_for_status(Method::POST, response).await?;
         success.json().await.map_err(Error::from)
     }
 
     /// Gets a `VoteCount` for the given `epoch`.
     pub async fn get_vote_count(&self, epoch: Epoch) -> Result<IndividualVotesResponse, Error> {
         let client = self.0.clone();
-        let query_params = vec![("epoch".into(), format!("{}", epoch.as_u64()))];
         let url = self.url("vote_count")?;
-        client.json_get(url, query_params).await
+        client.json_get_query(url, &EpochQuery { epoch }).await
+    }
+}
+
+/// Provides the functions on the `/lighthouse` endpoint of the node.
+#[derive(Clone)]
+pub struct Lighthouse<E>(HttpClient<E>);
+
+impl<E: EthSpec> Lighthouse<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0.url(&format!("lighthouse/{}", path))
+    }
+
+    /// Gets the attester duties of the validators with the given `indices` for each of the given
+    /// `epochs`, in a single request.
+    pub async fn get_validator_duties(
+        &self,
+        epochs: Vec<Epoch>,
+        indices: Vec<u64>,
+    ) -> Result<Vec<EpochAttesterDuties>, Error> {
+        let client = self.0.clone();
+        let url = self.url("validator/duties")?;
+        client
+            .json_get_query(url, &BatchedDutiesQuery { epochs, indices })
+            .await
+    }
+
+    /// Gets the times at which recently imported blocks reached each stage of import. If `root`
+    /// is supplied, only the timings of that block are returned.
+    pub async fn get_block_timings(
+        &self,
+        root: Option<Hash256>,
+    ) -> Result<Vec<BlockTimings>, Error> {
+        let client = self.0.clone();
+        let url = self.url("debug/block_timings")?;
+        client
+            .json_get_query(url, &BlockTimingsQuery { root })
+            .await
+    }
+
+    /// Gets the times at which recent blocks and aggregates were first received on gossip. If
+    /// `slot` is supplied, only the messages from that slot are returned.
+    pub async fn get_observed_gossip(
+        &self,
+        slot: Option<Slot>,
+    ) -> Result<ObservedGossipResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("debug/observed_gossip")?;
+        client
+            .json_get_query(url, &ObservedGossipQuery { slot })
+            .await
+    }
+
+    /// Gets an estimate of the proportion of recent blocks proposed by each client, as identified
+    /// by the graffiti of the blocks.
+    pub async fn get_client_diversity(&self) -> Result<ClientDiversityResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("analysis/client_diversity")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Gets the mode of the node and the database and API settings it determines.
+    pub async fn get_node_config(&self) -> Result<NodeConfigResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("config")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Gets the weak subjectivity period of the head state and the latest finalized checkpoint.
+    pub async fn get_ws_period(&self) -> Result<WeakSubjectivityPeriodResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("ws_period")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Gets the contents of the naive aggregation pool and the attestations of the operation pool.
+    pub async fn get_attestation_pools(&self) -> Result<AttestationPoolsResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("debug/attestation_pools")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Gets the size and hit rate of each of the in-memory caches of the node.
+    pub async fn get_caches(&self) -> Result<CachesResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("debug/caches")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Gets the slot after which the node will shut down, if any.
+    pub async fn get_shutdown_slot(&self) -> Result<Option<Slot>, Error> {
+        let client = self.0.clone();
+        let url = self.url("admin/shutdown_at_slot")?;
+        client
+            .json_get::<ShutdownAtSlot>(url, vec![])
+            .await
+            .map(|response| response.slot)
+    }
+
+    /// Schedules the node to shut down gracefully once `slot` has ended, or cancels the scheduled
+    /// shutdown if `slot` is `None`.
+    pub async fn shutdown_at_slot(&self, slot: Option<Slot>) -> Result<Option<Slot>, Error> {
+        let client = self.0.clone();
+        let url = self.url("admin/shutdown_at_slot")?;
+        let response = client.json_post(url, ShutdownAtSlot { slot }).await?;
+        let success = client.error_for_status(Method::POST, response).await?;
+        success
+            .json::<ShutdownAtSlot>()
+            .await
+            .map(|response| response.slot)
+            .map_err(Error::from)
+    }
+
+    /// Gets the trusted peers of the node.
+    pub async fn get_trusted_peers(&self) -> Result<Vec<TrustedPeerInfo>, Error> {
+        let client = self.0.clone();
+        let url = self.url("admin/trusted_peers")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Trusts the peers in `add`, given as ENRs or multiaddrs, and stops trusting the peer IDs in
+    /// `remove`.
+    pub async fn update_trusted_peers(
+        &self,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<(), Error> {
+        let client = self.0.clone();
+        let url = self.url("admin/trusted_peers")?;
+        let response = client
+            .json_post(url, TrustedPeersRequest { add, remove })
+            .await?;
+        client.error_for_status(Method::POST, response).await?;
+        Ok(())
+    }
+
+    /// Reloads the configuration of the node, returning which changed flags were applied and which
+    /// require a restart.
+    pub async fn reload_config(&self) -> Result<ConfigReloadResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("admin/reload-config")?;
+        let request = client.client.post(&url.to_string());
+        let response = client.send(Method::POST, &url, request).await?;
+        let success = client.error_for_status(Method::POST, response).await?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Clears each of the given `caches`, returning the state of the caches afterwards.
+    pub async fn drop_caches(&self, caches: Vec<DroppableCache>) -> Result<CachesResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("debug/caches/drop")?;
+        let response = client.json_post(url, DropCachesRequest { caches }).await?;
+        let success = client.error_for_status(Method::POST, response).await?;
+        success.json().await.map_err(Error::from)
     }
 }
 
@@ -705,14 +1406,6 @@ pub struct StateResponse<T: EthSpec> {
     pub root: Hash256,
 }
 
-fn root_as_string(root: Hash256) -> String {
-    format!("0x{:?}", root)
-}
-
-fn as_ssz_hex_string<T: Encode>(item: &T) -> String {
-    format!("0x{}", hex::encode(item.as_ssz_bytes()))
-}
-
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Error {
         Error::ReqwestError(e)
@@ -730,3 +1423,39 @@ impl From<serde_json::Error> for Error {
         Error::SerdeJsonError(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    fn client(server_url: &str) -> Result<HttpClient<MainnetEthSpec>, Error> {
+        HttpClient::new(server_url.to_string(), Duration::from_secs(1))
+    }
+
+    #[test]
+    fn url_retains_base_path() {
+        let get_url = |base: &str, path: &str| client(base).unwrap().url(path).unwrap().to_string();
+
+        assert_eq!(
+            get_url("http://localhost:5052", "node/version"),
+            "http://localhost:5052/node/version"
+        );
+        assert_eq!(
+            get_url("http://localhost:5052/", "beacon/validators/all"),
+            "http://localhost:5052/beacon/validators/all"
+        );
+        assert_eq!(
+            get_url("http://localhost:5052/api", "spec/eth2_config"),
+            "http://localhost:5052/api/spec/eth2_config"
+        );
+    }
+
+    #[test]
+    fn opaque_url_is_rejected() {
+        match client("localhost:5052") {
+            Err(Error::InvalidUrl(_)) => {}
+            _ => panic!("opaque url should be rejected"),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! A mock beacon node HTTP server with programmable responses.
+//!
+//! Allows applications which use a `RemoteBeaconNode` (e.g., validator clients) to be tested
+//! without running a beacon chain. Responses are set per method and path, and all requests are
+//! recorded so they can be inspected.
+
+use crate::{Error, RemoteBeaconNode};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use types::EthSpec;
+
+/// A response to be returned by the mock server.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: StatusCode,
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+/// A request received by the mock server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Option<String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Default)]
+struct State {
+    responses: HashMap<(Method, String), MockResponse>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// A HTTP server on a local port which returns programmed responses.
+///
+/// Requests without a programmed response receive a 404. The server stops when dropped.
+pub struct MockBeaconNode<E> {
+    listen_addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    exit: Option<oneshot::Sender<()>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> MockBeaconNode<E> {
+    /// Starts the server on a free local port. Must be called within a tokio runtime.
+    pub fn start() -> Result<Self, String> {
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle_request(req, state.clone()))) }
+        });
+
+        let server = Server::try_bind(&([127, 0, 0, 1], 0).into())
+            .map_err(|e| format!("Unable to bind mock beacon node: {:?}", e))?
+            .serve(make_service);
+        let listen_addr = server.local_addr();
+
+        let (exit, exit_rx) = oneshot::channel();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = exit_rx.await;
+        }));
+
+        Ok(Self {
+            listen_addr,
+            state,
+            exit: Some(exit),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// The URL of the server, e.g. `http://127.0.0.1:5052`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.listen_addr)
+    }
+
+    /// Returns a client connected to the server.
+    pub fn remote_node(&self) -> Result<RemoteBeaconNode<E>, Error> {
+        RemoteBeaconNode::new(self.url())
+    }
+
+    /// Respond to requests for `method` and `path` (e.g., `/node/version`) with `response`,
+    /// replacing any previous response.
+    ///
+    /// If `path` includes a query (e.g., `/beacon/block?slot=1`), only requests with exactly that
+    /// query match. Such responses take precedence over a response set for the path alone.
+    pub fn set_response(&self, method: Method, path: &str, response: MockResponse) {
+        self.state
+            .lock()
+            .responses
+            .insert((method, path.to_string()), response);
+    }
+
+    /// Respond to requests for `method` and `path` with `value` as JSON, with a 200 status.
+    pub fn set_json_response<T: Serialize>(&self, method: Method, path: &str, value: &T) {
+        let body = serde_json::to_vec(value).expect("value should serialize as JSON");
+        self.set_response(
+            method,
+            path,
+            MockResponse {
+                status: StatusCode::OK,
+                content_type: "application/json".to_string(),
+                body,
+            },
+        )
+    }
+
+    /// Respond to requests for `method` and `path` with `status` and a text `message`, as the
+    /// beacon node does for errors.
+    pub fn set_error_response(
+        &self,
+        method: Method,
+        path: &str,
+        status: StatusCode,
+        message: &str,
+    ) {
+        self.set_response(
+            method,
+            path,
+            MockResponse {
+                status,
+                content_type: "text/plain; charset=utf-8".to_string(),
+                body: message.as_bytes().to_vec(),
+            },
+        )
+    }
+
+    /// Returns all requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().requests.clone()
+    }
+}
+
+impl<E> Drop for MockBeaconNode<E> {
+    fn drop(&mut self) {
+        if let Some(exit) = self.exit.take() {
+            let _ = exit.send(());
+        }
+    }
+}
+
+async fn handle_request(
+    req: Request<Body>,
+    state: Arc<Mutex<State>>,
+) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body)
+        .await
+        .map(|bytes| bytes.to_vec())
+        .unwrap_or_default();
+
+    let path = parts.uri.path().to_string();
+    let mut state = state.lock();
+    state.requests.push(RecordedRequest {
+        method: parts.method.clone(),
+        path: path.clone(),
+        query: parts.uri.query().map(String::from),
+        body,
+    });
+
+    let with_query = parts
+        .uri
+        .query()
+        .map(|query| (parts.method.clone(), format!("{}?{}", path, query)));
+    let response = match with_query
+        .and_then(|key| state.responses.get(&key))
+        .or_else(|| state.responses.get(&(parts.method, path.clone())))
+    {
+        Some(response) => Response::builder()
+            .status(response.status)
+            .header("content-type", response.content_type.as_str())
+            .body(Body::from(response.body.clone())),
+        None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from(format!("No mock response for {}", path))),
+    };
+
+    Ok(response.expect("mock response should be valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Hash256, MinimalEthSpec};
+
+    #[tokio::test]
+    async fn programmed_responses() {
+        let mock = MockBeaconNode::<MinimalEthSpec>::start().unwrap();
+        let remote_node = mock.remote_node().unwrap();
+
+        mock.set_json_response(Method::GET, "/node/version", &"Lighthouse/mock");
+        mock.set_error_response(
+            Method::GET,
+            "/beacon/block",
+            StatusCode::NOT_FOUND,
+            "Unable to find SignedBeaconBlock",
+        );
+
+        assert_eq!(
+            remote_node.http.node().get_version().await.unwrap(),
+            "Lighthouse/mock"
+        );
+
+        match remote_node
+            .http
+            .beacon()
+            .get_block_by_root(Hash256::zero())
+            .await
+        {
+            Err(e @ Error::DidNotSucceed { .. }) => {
+                assert_eq!(e.status(), Some(StatusCode::NOT_FOUND));
+                assert_eq!(e.server_message(), Some("Unable to find SignedBeaconBlock"));
+                assert!(e
+                    .to_string()
+                    .starts_with(&format!("GET {}/beacon/block?root=", mock.url())));
+            }
+            other => panic!("unexpected result {:?}", other.map(|_| ())),
+        }
+
+        // Requests without a programmed response receive a 404.
+        assert!(remote_node.http.beacon().get_genesis_time().await.is_err());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].path, "/node/version");
+        assert_eq!(requests[1].path, "/beacon/block");
+        assert!(requests[1].query.as_ref().unwrap().starts_with("root="));
+        assert_eq!(requests[2].path, "/beacon/genesis_time");
+    }
+
+    #[tokio::test]
+    async fn generic_requests() {
+        let mock = MockBeaconNode::<MinimalEthSpec>::start().unwrap();
+        let remote_node = mock.remote_node().unwrap();
+
+        let syncing = serde_json::json!({ "is_synced": true });
+        mock.set_json_response(Method::GET, "/lighthouse/future?epoch=3", &syncing);
+        mock.set_json_response(Method::POST, "/lighthouse/future", &42_u64);
+
+        let response: serde_json::Value = remote_node
+            .http
+            .get_path("lighthouse/future", &[("epoch", "3")])
+            .await
+            .unwrap();
+        assert_eq!(response, syncing);
+
+        let response: u64 = remote_node
+            .http
+            .post_path("lighthouse/future", &vec![1_u64, 2])
+            .await
+            .unwrap();
+        assert_eq!(response, 42);
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].query.as_deref(), Some("epoch=3"));
+        assert_eq!(requests[1].body, b"[1,2]".to_vec());
+    }
+
+    #[tokio::test]
+    async fn download_to_file() {
+        let mock = MockBeaconNode::<MinimalEthSpec>::start().unwrap();
+        let remote_node = mock.remote_node().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        // Large enough to be received in many chunks.
+        let body = (0..4 * 1024 * 1024)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        mock.set_response(
+            Method::GET,
+            "/beacon/state/genesis",
+            MockResponse {
+                status: StatusCode::OK,
+                content_type: "application/ssz".to_string(),
+                body: body.clone(),
+            },
+        );
+
+        let path = dir.path().join("genesis.ssz");
+        let written = remote_node
+            .http
+            .beacon()
+            .get_genesis_state_to_file(&path)
+            .await
+            .unwrap();
+
+        assert_eq!(written, body.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn error_failures() {
+        let mock = MockBeaconNode::<MinimalEthSpec>::start().unwrap();
+        let remote_node = mock.remote_node().unwrap();
+
+        mock.set_error_response(
+            Method::POST,
+            "/validator/attestations",
+            StatusCode::BAD_REQUEST,
+            "attestation 0 is invalid\nattestation 2 is invalid\n",
+        );
+
+        let error = remote_node
+            .http
+            .post_path::<_, ()>("validator/attestations", &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.failures(),
+            vec!["attestation 0 is invalid", "attestation 2 is invalid"]
+        );
+        match error {
+            Error::DidNotSucceed { method, url, .. } => {
+                assert_eq!(method, Method::POST);
+                assert_eq!(url.path(), "/validator/attestations");
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Records the responses of a live beacon node as fixtures, and replays them from a
+//! `MockBeaconNode`.
+//!
+//! A `RecordingProxy` forwards every request to an upstream beacon node (of any implementation)
+//! and writes each response to a fixture file. Tests can then replay the fixtures, checking that
+//! the `HttpClient` parses real-world responses without needing a live node.
+//!
+//! Each fixture is a JSON file containing the request method, path and query, and the response
+//! status, content type and body. Bodies which are not UTF-8 (e.g., SSZ) are stored as hex.
+
+use crate::mock::{MockBeaconNode, MockResponse};
+use crate::Url;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use types::EthSpec;
+
+/// A request and the response returned to it by a beacon node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub status: u16,
+    pub content_type: String,
+    pub body: String,
+    /// `true` if `body` is the hex encoding of a non-UTF-8 body.
+    #[serde(default)]
+    pub hex_encoded: bool,
+}
+
+impl Fixture {
+    /// Reads a fixture from the JSON file at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes =
+            fs::read(path).map_err(|e| format!("Unable to read fixture {:?}: {:?}", path, e))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Unable to parse fixture {:?}: {:?}", path, e))
+    }
+
+    /// Reads every `.json` fixture in `dir`, in file name (i.e., recording) order.
+    pub fn load_dir(dir: &Path) -> Result<Vec<Self>, String> {
+        let mut paths = fs::read_dir(dir)
+            .map_err(|e| format!("Unable to read fixture dir {:?}: {:?}", dir, e))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Unable to read fixture dir {:?}: {:?}", dir, e))?;
+        paths.retain(|path| path.extension().map_or(false, |ext| ext == "json"));
+        paths.sort();
+
+        paths.iter().map(|path| Self::load(path)).collect()
+    }
+
+    /// The path, including the query if any, as used to program a `MockBeaconNode`.
+    pub fn path_and_query(&self) -> String {
+        match &self.query {
+            Some(query) => format!("{}?{}", self.path, query),
+            None => self.path.clone(),
+        }
+    }
+
+    /// The response body as bytes.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, String> {
+        if self.hex_encoded {
+            hex::decode(&self.body).map_err(|e| format!("Invalid hex body: {:?}", e))
+        } else {
+            Ok(self.body.as_bytes().to_vec())
+        }
+    }
+
+    /// The file name of the `index`th fixture recorded, e.g. `0003_GET_node_syncing.json`.
+    fn file_name(&self, index: usize) -> String {
+        let path = self
+            .path
+            .trim_matches('/')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("{:04}_{}_{}.json", index, self.method, path)
+    }
+}
+
+/// Starts a `MockBeaconNode` which responds to requests with the fixtures in `dir`.
+///
+/// If several fixtures share a method, path and query, the last one recorded is used. Must be
+/// called within a tokio runtime.
+pub fn replay<E: EthSpec>(dir: &Path) -> Result<MockBeaconNode<E>, String> {
+    let mock = MockBeaconNode::start()?;
+
+    for fixture in Fixture::load_dir(dir)? {
+        let method = Method::from_bytes(fixture.method.as_bytes())
+            .map_err(|e| format!("Invalid method {}: {:?}", fixture.method, e))?;
+        let status = StatusCode::from_u16(fixture.status)
+            .map_err(|e| format!("Invalid status {}: {:?}", fixture.status, e))?;
+
+        mock.set_response(
+            method,
+            &fixture.path_and_query(),
+            MockResponse {
+                status,
+                content_type: fixture.content_type.clone(),
+                body: fixture.body_bytes()?,
+            },
+        );
+    }
+
+    Ok(mock)
+}
+
+struct ProxyState {
+    client: Client,
+    upstream: Url,
+    fixture_dir: PathBuf,
+    count: AtomicUsize,
+}
+
+/// A HTTP server on a local port which forwards requests to an upstream beacon node and records
+/// each response as a fixture.
+///
+/// The server stops when dropped.
+pub struct RecordingProxy {
+    listen_addr: SocketAddr,
+    fixture_dir: PathBuf,
+    exit: Option<oneshot::Sender<()>>,
+}
+
+impl RecordingProxy {
+    /// Starts the proxy on a free local port, writing fixtures to `fixture_dir` (which is created
+    /// if it does not exist). Must be called within a tokio runtime.
+    pub fn start(upstream: Url, fixture_dir: PathBuf) -> Result<Self, String> {
+        fs::create_dir_all(&fixture_dir)
+            .map_err(|e| format!("Unable to create fixture dir {:?}: {:?}", fixture_dir, e))?;
+
+        let state = Arc::new(ProxyState {
+            client: Client::new(),
+            upstream,
+            fixture_dir: fixture_dir.clone(),
+            count: AtomicUsize::new(0),
+        });
+
+        let make_service = make_service_fn(move |_| {
+            let state = state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| proxy_request(req, state.clone()))) }
+        });
+
+        let server = Server::try_bind(&([127, 0, 0, 1], 0).into())
+            .map_err(|e| format!("Unable to bind recording proxy: {:?}", e))?
+            .serve(make_service);
+        let listen_addr = server.local_addr();
+
+        let (exit, exit_rx) = oneshot::channel();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = exit_rx.await;
+        }));
+
+        Ok(Self {
+            listen_addr,
+            fixture_dir,
+            exit: Some(exit),
+        })
+    }
+
+    /// The URL of the proxy, e.g. `http://127.0.0.1:5052`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.listen_addr)
+    }
+
+    pub fn fixture_dir(&self) -> &Path {
+        &self.fixture_dir
+    }
+}
+
+impl Drop for RecordingProxy {
+    fn drop(&mut self) {
+        if let Some(exit) = self.exit.take() {
+            let _ = exit.send(());
+        }
+    }
+}
+
+async fn proxy_request(
+    req: Request<Body>,
+    state: Arc<ProxyState>,
+) -> Result<Response<Body>, Infallible> {
+    let response = forward_and_record(req, &state).await.and_then(|fixture| {
+        Response::builder()
+            .status(fixture.status)
+            .header("content-type", fixture.content_type.as_str())
+            .body(Body::from(fixture.body_bytes()?))
+            .map_err(|e| format!("Invalid response: {:?}", e))
+    });
+
+    Ok(response.unwrap_or_else(|e| {
+        Response::builder()
+            .status(StatusCode::BAD_GATEWAY)
+            .body(Body::from(e))
+            .expect("error response should be valid")
+    }))
+}
+
+/// Forwards `req` to the upstream beacon node and writes its response to a fixture file.
+async fn forward_and_record(req: Request<Body>, state: &ProxyState) -> Result<Fixture, String> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| format!("Unable to read request body: {:?}", e))?;
+
+    let mut url = state
+        .upstream
+        .join(parts.uri.path().trim_start_matches('/'))
+        .map_err(|e| format!("Invalid path {}: {:?}", parts.uri.path(), e))?;
+    url.set_query(parts.uri.query());
+
+    let mut request = state.client.request(parts.method.clone(), url).body(body);
+    if let Some(content_type) = parts.headers.get(CONTENT_TYPE) {
+        request = request.header(CONTENT_TYPE, content_type.clone());
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Upstream request failed: {:?}", e))?;
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Unable to read upstream response: {:?}", e))?;
+
+    let (body, hex_encoded) = match String::from_utf8(bytes.to_vec()) {
+        Ok(body) => (body, false),
+        Err(_) => (hex::encode(&bytes), true),
+    };
+
+    let fixture = Fixture {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().map(String::from),
+        status,
+        content_type,
+        body,
+        hex_encoded,
+    };
+
+    let index = state.count.fetch_add(1, Ordering::SeqCst);
+    let path = state.fixture_dir.join(fixture.file_name(index));
+    let json = serde_json::to_vec_pretty(&fixture)
+        .map_err(|e| format!("Unable to encode fixture: {:?}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Unable to write fixture {:?}: {:?}", path, e))?;
+
+    Ok(fixture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, RemoteBeaconNode, SyncingResponse};
+    use rest_types::SyncingStatus;
+    use types::{Hash256, MinimalEthSpec, Slot};
+
+    type E = MinimalEthSpec;
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let syncing = SyncingResponse {
+            is_syncing: true,
+            is_pre_genesis: false,
+            is_optimistic: false,
+            sync_status: SyncingStatus {
+                starting_slot: Slot::new(0),
+                current_slot: Slot::new(100),
+                highest_slot: Slot::new(200),
+            },
+        };
+
+        let upstream = MockBeaconNode::<E>::start().unwrap();
+        upstream.set_json_response(Method::GET, "/node/syncing", &syncing);
+        upstream.set_error_response(
+            Method::GET,
+            "/beacon/block",
+            StatusCode::NOT_FOUND,
+            "Unable to find SignedBeaconBlock",
+        );
+
+        let proxy = RecordingProxy::start(
+            Url::parse(&upstream.url()).unwrap(),
+            dir.path().join("fixtures"),
+        )
+        .unwrap();
+        let remote_node = RemoteBeaconNode::<E>::new(proxy.url()).unwrap();
+
+        assert_eq!(
+            remote_node.http.node().syncing_status().await.unwrap(),
+            syncing
+        );
+        assert!(remote_node
+            .http
+            .beacon()
+            .get_block_by_root(Hash256::zero())
+            .await
+            .is_err());
+
+        let fixtures = Fixture::load_dir(proxy.fixture_dir()).unwrap();
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(fixtures[0].path, "/node/syncing");
+        assert_eq!(fixtures[1].status, 404);
+        assert!(fixtures[1].query.as_ref().unwrap().starts_with("root="));
+
+        // Stop the upstream node to ensure responses come from the fixtures.
+        drop(upstream);
+
+        let replayed = replay::<E>(proxy.fixture_dir()).unwrap();
+        let remote_node = replayed.remote_node().unwrap();
+
+        assert_eq!(
+            remote_node.http.node().syncing_status().await.unwrap(),
+            syncing
+        );
+        match remote_node
+            .http
+            .beacon()
+            .get_block_by_root(Hash256::zero())
+            .await
+        {
+            Err(Error::DidNotSucceed { status, body, .. }) => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(body, "Unable to find SignedBeaconBlock");
+            }
+            other => panic!("unexpected result {:?}", other.map(|_| ())),
+        }
+        assert_eq!(replayed.requests().len(), 2);
+    }
+}
@@ -0,0 +1,29 @@
+//! Collection of types for the /advanced HTTP
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+
+/// The response for the /advanced/fork_choice/prune HTTP POST.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct ForkChoicePruneResponse {
+    /// The number of blocks removed from fork choice.
+    pub removed_nodes: u64,
+    /// The number of blocks remaining in fork choice.
+    pub remaining_nodes: u64,
+}
+
+/// The request for the /advanced/database/backup HTTP POST.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseBackupRequest {
+    /// The name of the directory in which to write the backup, within the directory given by the
+    /// beacon node's `--database-backup-dir`. It must not already exist.
+    pub name: String,
+}
+
+/// The response for the /advanced/database/backup HTTP POST.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct DatabaseBackupResponse {
+    /// The number of entries copied from the hot database.
+    pub hot_entries: u64,
+    /// The number of entries copied from the freezer database.
+    pub cold_entries: u64,
+}
@@ -12,6 +12,10 @@ pub enum ApiError {
     ImATeapot(String),       // Just in case.
     ProcessingError(String), // A 202 error, for when a block/attestation cannot be processed, but still transmitted.
     InvalidHeaderValue(String),
+    ServiceUnavailable(String, u64), // A 503 error, with the number of seconds after which the request may be retried.
+    RangeNotSatisfiable(String, usize), // A 416 error, with the length of the full response.
+    PayloadTooLarge(String),
+    Forbidden(String),
 }
 
 pub type ApiResult = Result<Response<Body>, ApiError>;
@@ -28,16 +32,60 @@ impl ApiError {
             ApiError::ImATeapot(desc) => (StatusCode::IM_A_TEAPOT, desc),
             ApiError::ProcessingError(desc) => (StatusCode::ACCEPTED, desc),
             ApiError::InvalidHeaderValue(desc) => (StatusCode::INTERNAL_SERVER_ERROR, desc),
+            ApiError::ServiceUnavailable(desc, _) => (StatusCode::SERVICE_UNAVAILABLE, desc),
+            ApiError::RangeNotSatisfiable(desc, _) => (StatusCode::RANGE_NOT_SATISFIABLE, desc),
+            ApiError::PayloadTooLarge(desc) => (StatusCode::PAYLOAD_TOO_LARGE, desc),
+            ApiError::Forbidden(desc) => (StatusCode::FORBIDDEN, desc),
+        }
+    }
+
+    /// Returns `self` with its description replaced by `f(description)`.
+    pub fn map_description<F: FnOnce(String) -> String>(self, f: F) -> Self {
+        match self {
+            ApiError::MethodNotAllowed(desc) => ApiError::MethodNotAllowed(f(desc)),
+            ApiError::ServerError(desc) => ApiError::ServerError(f(desc)),
+            ApiError::NotImplemented(desc) => ApiError::NotImplemented(f(desc)),
+            ApiError::BadRequest(desc) => ApiError::BadRequest(f(desc)),
+            ApiError::NotFound(desc) => ApiError::NotFound(f(desc)),
+            ApiError::UnsupportedType(desc) => ApiError::UnsupportedType(f(desc)),
+            ApiError::ImATeapot(desc) => ApiError::ImATeapot(f(desc)),
+            ApiError::ProcessingError(desc) => ApiError::ProcessingError(f(desc)),
+            ApiError::InvalidHeaderValue(desc) => ApiError::InvalidHeaderValue(f(desc)),
+            ApiError::ServiceUnavailable(desc, retry_after) => {
+                ApiError::ServiceUnavailable(f(desc), retry_after)
+            }
+            ApiError::RangeNotSatisfiable(desc, len) => ApiError::RangeNotSatisfiable(f(desc), len),
+            ApiError::PayloadTooLarge(desc) => ApiError::PayloadTooLarge(f(desc)),
+            ApiError::Forbidden(desc) => ApiError::Forbidden(f(desc)),
         }
     }
 }
 
 impl Into<Response<Body>> for ApiError {
     fn into(self) -> Response<Body> {
+        let retry_after = match &self {
+            ApiError::ServiceUnavailable(_, retry_after) => Some(*retry_after),
+            _ => None,
+        };
+        let content_range = match &self {
+            ApiError::RangeNotSatisfiable(_, len) => Some(format!("bytes */{}", len)),
+            _ => None,
+        };
         let (status_code, desc) = self.status_code();
-        Response::builder()
+
+        let mut builder = Response::builder()
             .status(status_code)
-            .header("content-type", "text/plain; charset=utf-8")
+            .header("content-type", "text/plain; charset=utf-8");
+
+        if let Some(retry_after) = retry_after {
+            builder = builder.header("retry-after", retry_after);
+        }
+
+        if let Some(content_range) = content_range {
+            builder = builder.header("content-range", content_range);
+        }
+
+        builder
             .body(Body::from(desc))
             .expect("Response should always be created.")
     }
@@ -1,10 +1,14 @@
 //! A collection of REST API types for interaction with the beacon node.
 
+use crate::{ExtraFields, Paginated, ValidatorStatus};
 use bls::PublicKeyBytes;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use types::beacon_state::EthSpec;
-use types::{BeaconState, CommitteeIndex, Hash256, SignedBeaconBlock, Slot, Validator};
+use types::{
+    BeaconState, CommitteeIndex, Epoch, Hash256, SignedBeaconBlock, SignedBeaconBlockHeader, Slot,
+    Validator,
+};
 
 /// Information about a block that is at the head of a chain. May or may not represent the
 /// canonical head.
@@ -41,6 +45,11 @@ pub struct ValidatorResponse {
     pub validator_index: Option<usize>,
     pub balance: Option<u64>,
     pub validator: Option<Validator>,
+    /// Fields which are unknown to this version of Lighthouse. Not included in the SSZ encoding.
+    #[serde(flatten, default, skip_serializing_if = "ExtraFields::is_empty")]
+    #[ssz(skip_serializing)]
+    #[ssz(skip_deserializing)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
@@ -63,3 +72,209 @@ pub struct StateResponse<T: EthSpec> {
     pub root: Hash256,
     pub beacon_state: BeaconState<T>,
 }
+
+/// The response to a successful submission of objects for the operation pool and publication on
+/// the network (e.g., `/beacon/proposer_slashing` or `/validator/attestations`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct PoolSubmissionResponse {
+    /// `true` if all of the objects were already known to the beacon node, in which case they have
+    /// not been imported or published again.
+    pub already_known: bool,
+}
+
+/// The header of a block, as returned by `/beacon/headers`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct BlockHeaderResponse {
+    pub root: Hash256,
+    pub canonical: bool,
+    pub header: SignedBeaconBlockHeader,
+}
+
+/// Query parameters for endpoints which return a set of validators (e.g.,
+/// `/beacon/validators/all`).
+///
+/// The response may be paginated with `offset` and `limit`, see `Paginated`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorsQuery {
+    /// If set to `None`, uses the canonical head state.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::root_option"
+    )]
+    pub state_root: Option<Hash256>,
+    /// The number of validators to skip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    /// The maximum number of validators to return.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// If non-empty, only validators which match one of these statuses at the current epoch of
+    /// the state are returned (see `ValidatorStatus::matches`).
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "crate::query_value::str_list"
+    )]
+    pub status: Vec<ValidatorStatus>,
+}
+
+impl ValidatorsQuery {
+    /// Returns `true` if `validator` should be included in the response.
+    pub fn includes(&self, validator: &Validator, epoch: Epoch, far_future_epoch: Epoch) -> bool {
+        if self.status.is_empty() {
+            return true;
+        }
+
+        let status = ValidatorStatus::from_validator(validator, epoch, far_future_epoch);
+        self.status.iter().any(|filter| status.matches(*filter))
+    }
+}
+
+impl Paginated for ValidatorsQuery {
+    fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+/// Query parameters for `/beacon/randao`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RandaoQuery {
+    /// If set to `None`, uses the canonical head state.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::root_option"
+    )]
+    pub state_root: Option<Hash256>,
+    /// If set to `None`, uses the current epoch of the state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+}
+
+/// The RANDAO mix of an epoch, as returned by `/beacon/randao`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct RandaoMix {
+    pub randao: Hash256,
+}
+
+/// Query parameters for endpoints which take only an epoch (e.g., `/consensus/vote_count`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpochQuery {
+    pub epoch: Epoch,
+}
+
+/// Query parameters for endpoints which take only a slot (e.g., `/beacon/block_root`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SlotQuery {
+    #[serde(with = "crate::query_value::slot")]
+    pub slot: Slot,
+}
+
+/// Query parameters for endpoints which return a block or state by either its `slot` or its
+/// `root` (e.g., `/beacon/block`). At most one of them may be given.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlotOrRootQuery {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::slot_option"
+    )]
+    pub slot: Option<Slot>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::root_option"
+    )]
+    pub root: Option<Hash256>,
+}
+
+impl SlotOrRootQuery {
+    pub fn slot(slot: Slot) -> Self {
+        Self {
+            slot: Some(slot),
+            root: None,
+        }
+    }
+
+    pub fn root(root: Hash256) -> Self {
+        Self {
+            slot: None,
+            root: Some(root),
+        }
+    }
+}
+
+/// Query parameters for `/beacon/headers`.
+///
+/// Without any parameters, the header of the canonical head block is returned.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeadersQuery {
+    /// Only return the header of the canonical block at this slot.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::slot_option"
+    )]
+    pub slot: Option<Slot>,
+    /// Only return headers whose parent has this root.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::root_option"
+    )]
+    pub parent_root: Option<Hash256>,
+}
+
+/// Query parameters for `/beacon/pool/attestations`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PoolAttestationsQuery {
+    /// Only return attestations for this slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<Slot>,
+    /// Only return attestations for this committee index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committee_index: Option<CommitteeIndex>,
+}
+
+/// Query parameters for `/beacon/committees`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommitteesQuery {
+    pub epoch: Epoch,
+    /// Only return the committees at this slot, which must be in `epoch`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<Slot>,
+    /// Only return the committees with this index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<CommitteeIndex>,
+}
+
+impl CommitteesQuery {
+    /// Returns a query for all the committees in `epoch`.
+    pub fn epoch(epoch: Epoch) -> Self {
+        Self {
+            epoch,
+            slot: None,
+            index: None,
+        }
+    }
+}
+
+/// The data of each `block` event streamed by `/beacon/block/stream`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockEvent {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: u64,
+    /// The graffiti as UTF-8, with invalid bytes replaced and trailing null bytes removed.
+    pub graffiti: String,
+    /// The time at which the block was received on gossip, in milliseconds after the start of its
+    /// slot. `None` if the block was not received on gossip.
+    pub observed_delay_ms: Option<u64>,
+    /// The time at which the block was imported, in milliseconds after the start of its slot.
+    pub imported_delay_ms: Option<u64>,
+}
@@ -0,0 +1,156 @@
+//! Support for requesting a single range of the bytes of a large response (e.g., a `BeaconState`)
+//! with the `Range` header, as per RFC 7233.
+
+use crate::ApiError;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Bytes to be returned in a response, of which a single range may have been requested.
+pub struct RangedBytes {
+    pub(crate) bytes: Arc<Vec<u8>>,
+    pub(crate) range: Option<Range<usize>>,
+}
+
+impl RangedBytes {
+    /// Returns the bytes in the range specified by `range_header` (the value of the `Range`
+    /// header of the request), or all of the bytes if there is no header.
+    pub fn new(bytes: Arc<Vec<u8>>, range_header: Option<&str>) -> Result<Self, ApiError> {
+        let range = match range_header {
+            Some(header) => parse_range(header, bytes.len())?,
+            None => None,
+        };
+
+        Ok(Self { bytes, range })
+    }
+}
+
+/// Parses the value of a `Range` header, returning the requested range of a response of `len`
+/// bytes.
+///
+/// Returns `None` if the header should be ignored and the full response returned, which is the
+/// case for units other than `bytes` and for requests of multiple ranges.
+pub fn parse_range(header: &str, len: usize) -> Result<Option<Range<usize>>, ApiError> {
+    let spec = if header.starts_with("bytes=") {
+        header["bytes=".len()..].trim()
+    } else {
+        return Ok(None);
+    };
+
+    if spec.contains(',') {
+        return Ok(None);
+    }
+
+    let mut parts = spec.splitn(2, '-');
+    let first = parts.next().unwrap_or("").trim();
+    let last = parts
+        .next()
+        .ok_or_else(|| invalid_range(header, "missing '-'"))?
+        .trim();
+
+    let parse = |value: &str| {
+        value
+            .parse::<usize>()
+            .map_err(|_| invalid_range(header, &format!("invalid position {:?}", value)))
+    };
+
+    let unsatisfiable = || {
+        ApiError::RangeNotSatisfiable(
+            format!("Range {:?} is not satisfiable for {} bytes", header, len),
+            len,
+        )
+    };
+
+    let range = match (first.is_empty(), last.is_empty()) {
+        // A suffix range: the last `n` bytes.
+        (true, false) => {
+            let suffix_len = parse(last)?;
+            if suffix_len == 0 || len == 0 {
+                return Err(unsatisfiable());
+            }
+            len.saturating_sub(suffix_len)..len
+        }
+        // From `first` until the end.
+        (false, true) => {
+            let start = parse(first)?;
+            if start >= len {
+                return Err(unsatisfiable());
+            }
+            start..len
+        }
+        // From `first` until `last`, inclusive.
+        (false, false) => {
+            let start = parse(first)?;
+            let end = parse(last)?;
+            if end < start {
+                return Err(invalid_range(
+                    header,
+                    "last position is before first position",
+                ));
+            }
+            if start >= len {
+                return Err(unsatisfiable());
+            }
+            start..std::cmp::min(end, len - 1) + 1
+        }
+        (true, true) => return Err(invalid_range(header, "missing positions")),
+    };
+
+    Ok(Some(range))
+}
+
+fn invalid_range(header: &str, reason: &str) -> ApiError {
+    ApiError::BadRequest(format!("Invalid Range header {:?}: {}", header, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_ranges() {
+        assert_eq!(parse_range("bytes=0-9", 100), Ok(Some(0..10)));
+        assert_eq!(parse_range("bytes=10-", 100), Ok(Some(10..100)));
+        assert_eq!(parse_range("bytes=-10", 100), Ok(Some(90..100)));
+        assert_eq!(parse_range("bytes=-1000", 100), Ok(Some(0..100)));
+        assert_eq!(parse_range("bytes=90-1000", 100), Ok(Some(90..100)));
+        assert_eq!(parse_range("bytes=99-99", 100), Ok(Some(99..100)));
+    }
+
+    #[test]
+    fn ignored_ranges() {
+        assert_eq!(parse_range("items=0-9", 100), Ok(None));
+        assert_eq!(parse_range("bytes=0-9,20-29", 100), Ok(None));
+    }
+
+    #[test]
+    fn unsatisfiable_ranges() {
+        for header in &["bytes=100-", "bytes=100-200", "bytes=-0"] {
+            match parse_range(header, 100) {
+                Err(ApiError::RangeNotSatisfiable(_, 100)) => {}
+                other => panic!("unexpected result for {:?}: {:?}", header, other),
+            }
+        }
+
+        match parse_range("bytes=0-", 0) {
+            Err(ApiError::RangeNotSatisfiable(_, 0)) => {}
+            other => panic!("unexpected result for empty response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        for header in &[
+            "bytes=",
+            "bytes=-",
+            "bytes=5",
+            "bytes=a-9",
+            "bytes=9-5",
+            "bytes=0-x",
+        ] {
+            match parse_range(header, 100) {
+                Err(ApiError::BadRequest(_)) => {}
+                other => panic!("unexpected result for {:?}: {:?}", header, other),
+            }
+        }
+    }
+}
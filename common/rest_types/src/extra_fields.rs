@@ -0,0 +1,11 @@
+//! Preserves JSON fields which are unknown to this version of Lighthouse.
+
+use std::collections::BTreeMap;
+
+/// The fields of a JSON object which do not correspond to any field of the type it was decoded
+/// into, by name.
+///
+/// Fields added to an API response by a newer beacon node are retained here rather than rejected,
+/// and are included again if the response is re-encoded (e.g., when it is proxied or persisted).
+/// It is always empty for responses produced by this version of Lighthouse.
+pub type ExtraFields = BTreeMap<String, serde_json::Value>;
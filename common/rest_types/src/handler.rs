@@ -1,11 +1,21 @@
-use crate::{ApiError, ApiResult};
+use crate::{ApiError, ApiResult, RangedBytes};
 use environment::TaskExecutor;
+use hyper::body::HttpBody;
 use hyper::header;
 use hyper::{Body, Request, Response, StatusCode};
 use serde::Deserialize;
 use serde::Serialize;
 use ssz::Encode;
 
+/// The `Content-Type` of a request body encoded as SSZ.
+///
+/// `application/ssz` is also accepted.
+pub const SSZ_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// The `Content-Encoding` of an SSZ request body compressed with snappy, using the raw (unframed)
+/// format used for gossip messages.
+pub const SNAPPY_CONTENT_ENCODING: &str = "snappy";
+
 /// Defines the encoding for the API.
 #[derive(Clone, Serialize, Deserialize, Copy)]
 pub enum ApiEncodingFormat {
@@ -42,6 +52,7 @@ pub struct Handler<T> {
     ctx: T,
     encoding: ApiEncodingFormat,
     allow_body: bool,
+    max_body_size: usize,
 }
 
 impl<T: Clone + Send + Sync + 'static> Handler<T> {
@@ -68,6 +79,7 @@ impl<T: Clone + Send + Sync + 'static> Handler<T> {
             body,
             ctx,
             allow_body: false,
+            max_body_size: usize::max_value(),
             encoding: ApiEncodingFormat::from(accept_header.as_str()),
         })
     }
@@ -79,12 +91,19 @@ impl<T: Clone + Send + Sync + 'static> Handler<T> {
         self
     }
 
+    /// Rejects a request body larger than `max_body_size` bytes with a 413 error. Unlimited by
+    /// default.
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
     /// Return a simple static value.
     ///
     /// Does not use the blocking executor.
     pub async fn static_value<V>(self, value: V) -> Result<HandledRequest<V>, ApiError> {
         // Always check and disallow a body for a static value.
-        let _ = Self::get_body(self.body, false).await?;
+        let _ = Self::get_body(self.body, false, 0).await?;
 
         Ok(HandledRequest {
             value,
@@ -100,7 +119,7 @@ impl<T: Clone + Send + Sync + 'static> Handler<T> {
         V: Send + Sync + 'static,
         F: Fn(Request<Vec<u8>>, T) -> Result<V, ApiError> + Send + Sync + 'static,
     {
-        let body = Self::get_body(self.body, self.allow_body).await?;
+        let body = Self::get_body(self.body, self.allow_body, self.max_body_size).await?;
         let (req_parts, _) = self.req.into_parts();
         let req = Request::from_parts(req_parts, body);
 
@@ -121,7 +140,7 @@ impl<T: Clone + Send + Sync + 'static> Handler<T> {
         F: Fn(Request<Vec<u8>>, T) -> Result<V, ApiError> + Send + Sync + 'static,
     {
         let ctx = self.ctx;
-        let body = Self::get_body(self.body, self.allow_body).await?;
+        let body = Self::get_body(self.body, self.allow_body, self.max_body_size).await?;
         let (req_parts, _) = self.req.into_parts();
         let req = Request::from_parts(req_parts, body);
 
@@ -161,19 +180,36 @@ impl<T: Clone + Send + Sync + 'static> Handler<T> {
             .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
     }
 
-    /// Downloads the bytes for `body`.
-    async fn get_body(body: Body, allow_body: bool) -> Result<Vec<u8>, ApiError> {
-        let bytes = hyper::body::to_bytes(body)
-            .await
-            .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
-
-        if !allow_body && !bytes[..].is_empty() {
-            Err(ApiError::BadRequest(
-                "The request body must be empty".to_string(),
-            ))
-        } else {
-            Ok(bytes.into_iter().collect())
+    /// Downloads the bytes for `body`, returning an error if it is larger than `max_body_size`.
+    async fn get_body(
+        mut body: Body,
+        allow_body: bool,
+        max_body_size: usize,
+    ) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = vec![];
+
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk.map_err(|e| {
+                ApiError::ServerError(format!("Unable to get request body: {:?}", e))
+            })?;
+
+            if !allow_body && !chunk.is_empty() {
+                return Err(ApiError::BadRequest(
+                    "The request body must be empty".to_string(),
+                ));
+            }
+
+            if bytes.len() + chunk.len() > max_body_size {
+                return Err(ApiError::PayloadTooLarge(format!(
+                    "The request body exceeds the maximum of {} bytes for this endpoint",
+                    max_body_size
+                )));
+            }
+
+            bytes.extend_from_slice(&chunk);
         }
+
+        Ok(bytes)
     }
 }
 
@@ -195,6 +231,36 @@ impl HandledRequest<String> {
     }
 }
 
+impl HandledRequest<RangedBytes> {
+    /// Return SSZ bytes, or only the requested range of them with a 206 status.
+    pub fn ranged_ssz_encoding(self) -> ApiResult {
+        let RangedBytes { bytes, range } = self.value;
+        let builder = Response::builder()
+            .header("content-type", "application/ssz")
+            .header(header::ACCEPT_RANGES, "bytes");
+
+        let response = match range {
+            Some(range) => builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    header::CONTENT_RANGE,
+                    format!(
+                        "bytes {}-{}/{}",
+                        range.start,
+                        range.end.saturating_sub(1),
+                        bytes.len()
+                    ),
+                )
+                .body(Body::from(bytes[range].to_vec())),
+            None => builder
+                .status(StatusCode::OK)
+                .body(Body::from(bytes.as_ref().clone())),
+        };
+
+        response.map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
+    }
+}
+
 impl<V: Serialize + Encode> HandledRequest<V> {
     /// Suitable for all items which implement `serde` and `ssz`.
     pub fn all_encodings(self) -> ApiResult {
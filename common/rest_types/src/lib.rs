@@ -2,21 +2,48 @@
 //!
 //! This is primarily used by the validator client and the beacon node rest API.
 
+mod advanced;
 mod api_error;
 mod beacon;
+mod byte_range;
 mod consensus;
+mod extra_fields;
 mod handler;
+mod lighthouse;
 mod node;
+mod pagination;
+mod query_value;
+mod spec;
+mod ssz_hex;
 mod validator;
+mod validator_status;
 
+pub use advanced::{DatabaseBackupRequest, DatabaseBackupResponse, ForkChoicePruneResponse};
 pub use api_error::{ApiError, ApiResult};
 pub use beacon::{
-    BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, StateResponse,
-    ValidatorRequest, ValidatorResponse,
+    BlockEvent, BlockHeaderResponse, BlockResponse, CanonicalHeadResponse, Committee,
+    CommitteesQuery, EpochQuery, HeadBeaconBlock, HeadersQuery, PoolAttestationsQuery,
+    PoolSubmissionResponse, RandaoMix, RandaoQuery, SlotOrRootQuery, SlotQuery, StateResponse,
+    ValidatorRequest, ValidatorResponse, ValidatorsQuery,
 };
+pub use byte_range::{parse_range, RangedBytes};
 pub use consensus::{IndividualVote, IndividualVotesRequest, IndividualVotesResponse};
-pub use handler::{ApiEncodingFormat, Handler};
+pub use extra_fields::ExtraFields;
+pub use handler::{ApiEncodingFormat, Handler, SNAPPY_CONTENT_ENCODING, SSZ_CONTENT_TYPE};
+pub use lighthouse::{
+    AttestationPoolsResponse, BlockTimings, BlockTimingsQuery, CacheInfo, CachesResponse,
+    ClientDiversityResponse, ClientProposals, CommitteeAggregation, ConfigReloadResponse,
+    DropCachesRequest, DroppableCache, GraffitiProposals, NaiveAggregationPoolInfo,
+    NodeConfigResponse, ObservedAttestationsInfo, ObservedGossipAggregate, ObservedGossipBlock,
+    ObservedGossipQuery, ObservedGossipResponse, OpPoolAttestationsInfo, OpPoolSlotAttestations,
+    ShutdownAtSlot, TrustedPeerInfo, TrustedPeersRequest, WeakSubjectivityPeriodResponse,
+};
 pub use node::{Health, SyncingResponse, SyncingStatus};
+pub use pagination::Paginated;
+pub use spec::{DepositContract, SpecConfig, TestnetInfo};
 pub use validator::{
-    ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription,
+    AggregateAttestationQuery, AttestationQuery, AttesterDuty, BatchedDutiesQuery,
+    BlockProductionQuery, EpochAttesterDuties, ValidatorDutiesRequest, ValidatorDuty,
+    ValidatorDutyBytes, ValidatorSubscription,
 };
+pub use validator_status::ValidatorStatus;
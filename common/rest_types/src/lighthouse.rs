@@ -0,0 +1,294 @@
+//! Collection of types for the /lighthouse HTTP
+use serde::{Deserialize, Serialize};
+use types::{Checkpoint, Epoch, Hash256, Slot};
+
+/// Query parameters for `/lighthouse/debug/block_timings`.
+///
+/// Without any parameters, the timings of all recently imported blocks are returned.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockTimingsQuery {
+    /// Only return the timings of the block with this root.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::root_option"
+    )]
+    pub root: Option<Hash256>,
+}
+
+/// The times at which a block reached each stage of import, in milliseconds after the start of
+/// the block's slot.
+///
+/// A stage is `None` if the block has not reached it, or if it was skipped (e.g., blocks from sync
+/// are not received on gossip).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockTimings {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    /// The block was received from the network.
+    pub observed_delay_ms: Option<i64>,
+    /// All of the signatures in the block were verified.
+    pub signature_verified_delay_ms: Option<i64>,
+    /// The block was applied to its parent state.
+    pub state_transition_delay_ms: Option<i64>,
+    /// The block was added to fork choice and written to the database.
+    pub imported_delay_ms: Option<i64>,
+    /// The block became the head of the chain.
+    pub head_delay_ms: Option<i64>,
+}
+
+/// The size and lookup statistics of an in-memory cache.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    /// The number of entries in the cache.
+    pub len: u64,
+    /// The maximum number of entries in the cache, or `None` if it is unbounded.
+    pub capacity: Option<u64>,
+    /// The number of lookups which found an entry since the node started.
+    pub hits: u64,
+    /// The number of lookups which did not find an entry since the node started.
+    pub misses: u64,
+    /// `hits / (hits + misses)`, or `None` if there have been no lookups.
+    pub hit_rate: Option<f64>,
+}
+
+/// The response for the /lighthouse/debug/caches HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CachesResponse {
+    pub snapshot_cache: CacheInfo,
+    pub shuffling_cache: CacheInfo,
+    pub validator_pubkey_cache: CacheInfo,
+}
+
+/// A cache which may be cleared via the /lighthouse/debug/caches/drop HTTP POST.
+///
+/// The validator pubkey cache may not be cleared, since the node cannot verify signatures
+/// without it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DroppableCache {
+    SnapshotCache,
+    ShufflingCache,
+}
+
+/// The request for the /lighthouse/debug/caches/drop HTTP POST.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DropCachesRequest {
+    pub caches: Vec<DroppableCache>,
+}
+
+/// Query parameters for `/lighthouse/debug/observed_gossip`.
+///
+/// Without any parameters, all recent observations are returned.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObservedGossipQuery {
+    /// Only return the observations of messages from this slot.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::query_value::slot_option"
+    )]
+    pub slot: Option<Slot>,
+}
+
+/// A block received on gossip.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObservedGossipBlock {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: u64,
+    /// The time at which the block was first received, in milliseconds after the start of its
+    /// slot.
+    pub seen_delay_ms: Option<i64>,
+}
+
+/// An aggregate received on gossip.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObservedGossipAggregate {
+    pub slot: Slot,
+    /// The root of the aggregate's `AttestationData`.
+    pub attestation_data_root: Hash256,
+    pub aggregator_index: u64,
+    /// The time at which the aggregate was first received, in milliseconds after the start of its
+    /// slot.
+    pub seen_delay_ms: Option<i64>,
+}
+
+/// The response for the /lighthouse/debug/observed_gossip HTTP GET.
+///
+/// Each list is ordered by the time at which the messages were received, earliest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObservedGossipResponse {
+    pub blocks: Vec<ObservedGossipBlock>,
+    pub aggregates: Vec<ObservedGossipAggregate>,
+}
+
+/// The number of recent blocks which were proposed by a client.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientProposals {
+    /// The client identified by the graffiti of the blocks, or `unknown`.
+    pub client: String,
+    pub proposals: u64,
+    /// The fraction of all recent blocks which were proposed by the client.
+    pub proportion: f64,
+}
+
+/// The number of recent blocks with the same graffiti.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraffitiProposals {
+    pub graffiti: String,
+    pub proposals: u64,
+}
+
+/// The response for the /lighthouse/analysis/client_diversity HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientDiversityResponse {
+    /// The slot of the earliest recent block, or `None` if no blocks have been imported.
+    pub start_slot: Option<Slot>,
+    /// The slot of the latest recent block, or `None` if no blocks have been imported.
+    pub end_slot: Option<Slot>,
+    /// The number of recent blocks.
+    pub proposals: u64,
+    /// The proposals of each client, in descending order of proposals.
+    pub clients: Vec<ClientProposals>,
+    /// The most common graffiti, in descending order of proposals.
+    pub graffiti: Vec<GraffitiProposals>,
+}
+
+/// The response for the /lighthouse/config HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeConfigResponse {
+    /// The `--mode` of the node: `standard`, `archive` or `minimal`.
+    pub mode: String,
+    /// The number of slots between restore points in the freezer database.
+    pub slots_per_restore_point: u64,
+    /// If true, the blocks and states of abandoned forks are deleted after finalization.
+    pub prune_abandoned_forks: bool,
+    /// If false, finalized states which aren't restore points are not served.
+    pub serves_reconstructed_states: bool,
+    /// The maximum number of epochs prior to the current epoch for which duties are served.
+    pub duties_max_lookback_epochs: u64,
+}
+
+/// The response for the /lighthouse/ws_period HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeakSubjectivityPeriodResponse {
+    /// The current epoch, according to the node's clock.
+    pub current_epoch: Epoch,
+    /// The weak subjectivity period of the head state, in epochs.
+    pub ws_period: u64,
+    /// The latest finalized checkpoint, from which other nodes may sync.
+    pub checkpoint: Checkpoint,
+    /// The last epoch at which it is safe to sync from `checkpoint`.
+    pub safe_until_epoch: Epoch,
+    /// True if it is safe to sync from `checkpoint` at the current epoch. False if the chain has
+    /// not finalized for longer than the weak subjectivity period.
+    pub is_safe: bool,
+}
+
+/// The request for the /lighthouse/admin/shutdown_at_slot HTTP POST, and the response for its GET
+/// and POST.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShutdownAtSlot {
+    /// The node shuts down once this slot has ended. `None` if no shutdown is scheduled, or to
+    /// cancel the scheduled shutdown.
+    pub slot: Option<Slot>,
+}
+
+/// The response for the /lighthouse/admin/reload-config HTTP POST.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigReloadResponse {
+    /// The flags whose new values were applied to the running node.
+    pub applied: Vec<String>,
+    /// The flags whose values changed, but which only take effect once the node is restarted.
+    pub requires_restart: Vec<String>,
+}
+
+/// The request for the /lighthouse/admin/trusted_peers HTTP POST.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustedPeersRequest {
+    /// The ENRs or multiaddrs (ending in `/p2p/<peer-id>`) of peers to trust.
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// The peer IDs of peers to stop trusting. They remain connected, subject to scoring.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// A trusted peer, in the response for the /lighthouse/admin/trusted_peers HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrustedPeerInfo {
+    pub peer_id: String,
+    pub connected: bool,
+}
+
+/// The aggregation of the attestations of a committee in the naive aggregation pool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommitteeAggregation {
+    pub slot: Slot,
+    pub index: u64,
+    /// The number of distinct `AttestationData` attested to by members of the committee.
+    pub attestation_data: u64,
+    /// The number of attesters in the aggregate with the most attesters.
+    pub aggregated_attesters: u64,
+    pub committee_size: u64,
+    /// `aggregated_attesters` as a fraction of `committee_size`.
+    pub completeness: f64,
+    /// `true` if an aggregator has been declared for the committee.
+    pub aggregator_registered: bool,
+}
+
+/// The contents of the naive aggregation pool, which aggregates unaggregated attestations so that
+/// aggregators can obtain them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NaiveAggregationPoolInfo {
+    /// Attestations from earlier slots are rejected by the pool.
+    pub lowest_permissible_slot: Slot,
+    /// The slots with attestations in the pool, in ascending order.
+    pub slots: Vec<Slot>,
+    /// The number of distinct `AttestationData` in the pool.
+    pub num_attestations: u64,
+    /// Ordered by slot, then committee index.
+    pub committees: Vec<CommitteeAggregation>,
+}
+
+/// The attestations in the operation pool from a single slot.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OpPoolSlotAttestations {
+    pub slot: Slot,
+    /// The number of distinct `AttestationData`.
+    pub attestation_data: u64,
+    /// The number of attestations, which may not be aggregated if their attesters overlap.
+    pub attestations: u64,
+}
+
+/// The attestations in the operation pool, which are available for inclusion in blocks.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OpPoolAttestationsInfo {
+    pub num_attestations: u64,
+    /// Ordered by slot.
+    pub slots: Vec<OpPoolSlotAttestations>,
+}
+
+/// The sizes of the caches used to reject attestations which have already been seen on gossip.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObservedAttestationsInfo {
+    /// The number of aggregate attestations observed in recent slots.
+    pub aggregates: u64,
+    /// The number of validators observed attesting in the current epoch.
+    pub attesters_current_epoch: u64,
+    /// The number of validators observed attesting in the previous epoch.
+    pub attesters_previous_epoch: u64,
+    /// The number of validators observed aggregating in the current epoch.
+    pub aggregators_current_epoch: u64,
+    /// The number of validators observed aggregating in the previous epoch.
+    pub aggregators_previous_epoch: u64,
+}
+
+/// The response for the /lighthouse/debug/attestation_pools HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttestationPoolsResponse {
+    pub naive_aggregation_pool: NaiveAggregationPoolInfo,
+    pub op_pool: OpPoolAttestationsInfo,
+    pub observed: ObservedAttestationsInfo,
+}
@@ -30,6 +30,14 @@ pub struct SyncingStatus {
 pub struct SyncingResponse {
     /// Is the node syncing.
     pub is_syncing: bool,
+    /// Is the present time prior to genesis. If so, the node is not syncing and every slot in the
+    /// sync status is the genesis slot.
+    #[serde(default)]
+    pub is_pre_genesis: bool,
+    /// Was the head block imported without its execution payload being validated. If so, the node
+    /// does not serve duties.
+    #[serde(default)]
+    pub is_optimistic: bool,
     /// The current sync status.
     pub sync_status: SyncingStatus,
 }
@@ -0,0 +1,54 @@
+//! Typed pagination for endpoints which return long lists.
+
+/// Implemented by the query parameters of endpoints which return a page of a list, selected by
+/// the `offset` and `limit` parameters.
+pub trait Paginated {
+    /// The number of items to skip, or `None` to start from the first.
+    fn offset(&self) -> Option<usize>;
+
+    /// The maximum number of items to return, or `None` to return every remaining item.
+    fn limit(&self) -> Option<usize>;
+
+    /// Returns the page of `items` selected by `self`.
+    fn paginate<I: IntoIterator>(&self, items: I) -> Vec<I::Item> {
+        items
+            .into_iter()
+            .skip(self.offset().unwrap_or(0))
+            .take(self.limit().unwrap_or(usize::max_value()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Page(Option<usize>, Option<usize>);
+
+    impl Paginated for Page {
+        fn offset(&self) -> Option<usize> {
+            self.0
+        }
+
+        fn limit(&self) -> Option<usize> {
+            self.1
+        }
+    }
+
+    #[test]
+    fn paginate() {
+        let items = 0..10;
+
+        assert_eq!(
+            Page(None, None).paginate(items.clone()),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Page(Some(3), None).paginate(items.clone()),
+            (3..10).collect::<Vec<_>>()
+        );
+        assert_eq!(Page(None, Some(2)).paginate(items.clone()), vec![0, 1]);
+        assert_eq!(Page(Some(8), Some(5)).paginate(items.clone()), vec![8, 9]);
+        assert!(Page(Some(10), Some(5)).paginate(items).is_empty());
+    }
+}
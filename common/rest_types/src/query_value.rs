@@ -0,0 +1,321 @@
+//! Strict parsing of the slots and roots which identify blocks and states in query parameters,
+//! with errors which describe exactly what is wrong with a malformed value.
+//!
+//! E.g., `#[serde(default, with = "crate::query_value::root_option")]`.
+
+use types::{Hash256, Slot};
+
+/// The number of hex characters in a `0x`-prefixed root.
+const ROOT_HEX_LEN: usize = 64;
+
+/// Parses a slot, given as a decimal number.
+pub fn parse_slot(value: &str) -> Result<Slot, String> {
+    if value.is_empty() {
+        return Err("empty slot, expected a decimal number".to_string());
+    }
+
+    if value.starts_with("0x") {
+        return Err("invalid slot: expected a decimal number, not hex".to_string());
+    }
+
+    if value.chars().all(char::is_alphabetic) {
+        return Err(format!(
+            "unknown slot keyword {:?}, expected a decimal number",
+            value
+        ));
+    }
+
+    if let Some((position, c)) = value.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(format!(
+            "invalid slot: unexpected character {:?} at position {}, expected a decimal number",
+            c, position
+        ));
+    }
+
+    value
+        .parse::<u64>()
+        .map(Slot::new)
+        .map_err(|_| format!("invalid slot: {} is larger than the maximum slot", value))
+}
+
+/// Parses a root, given as `0x` followed by 64 hex characters.
+pub fn parse_root(value: &str) -> Result<Hash256, String> {
+    if !value.starts_with("0x") {
+        return Err("invalid root: missing 0x prefix".to_string());
+    }
+
+    let hex_chars = &value[2..];
+    let len = hex_chars.chars().count();
+    if len != ROOT_HEX_LEN {
+        return Err(format!(
+            "invalid root: expected {} hex characters after the 0x prefix, found {}",
+            ROOT_HEX_LEN, len
+        ));
+    }
+
+    if let Some((position, c)) = hex_chars
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_hexdigit())
+    {
+        return Err(format!(
+            "invalid root: invalid hex character {:?} at position {}",
+            c,
+            position + 2
+        ));
+    }
+
+    hex::decode(hex_chars)
+        .map(|bytes| Hash256::from_slice(&bytes))
+        .map_err(|e| format!("invalid root: {:?}", e))
+}
+
+/// Serializes a `Slot` with `parse_slot`.
+pub mod slot {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use types::Slot;
+
+    pub fn serialize<S: Serializer>(slot: &Slot, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(slot.as_u64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Slot, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        super::parse_slot(&string).map_err(D::Error::custom)
+    }
+}
+
+/// As `slot`, for optional values. The field must also have `#[serde(default)]` and skip
+/// serializing `None`.
+pub mod slot_option {
+    use serde::{Deserializer, Serializer};
+    use types::Slot;
+
+    pub fn serialize<S: Serializer>(slot: &Option<Slot>, serializer: S) -> Result<S::Ok, S::Error> {
+        match slot {
+            Some(slot) => super::slot::serialize(slot, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Slot>, D::Error> {
+        super::slot::deserialize(deserializer).map(Some)
+    }
+}
+
+/// Serializes an optional `Hash256` with `parse_root`. The field must also have
+/// `#[serde(default)]` and skip serializing `None`.
+pub mod root_option {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use types::Hash256;
+
+    pub fn serialize<S: Serializer>(
+        root: &Option<Hash256>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match root {
+            Some(root) => serializer.serialize_str(&format!("0x{}", hex::encode(root.as_bytes()))),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Hash256>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        super::parse_root(&string)
+            .map(Some)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serializes a list of `u64`-like values (e.g., epochs or validator indices) as comma-separated
+/// decimal numbers, e.g., `5,6,7`. An empty string is an empty list.
+pub mod u64_list {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: Copy + Into<u64>>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let strings: Vec<String> = values
+            .iter()
+            .map(|value| Into::<u64>::into(*value).to_string())
+            .collect();
+        serializer.serialize_str(&strings.join(","))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: From<u64>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+
+        if string.is_empty() {
+            return Ok(vec![]);
+        }
+
+        string
+            .split(',')
+            .map(|value| {
+                value.parse::<u64>().map(T::from).map_err(|_| {
+                    D::Error::custom(format!(
+                        "invalid list item {:?}, expected a decimal number",
+                        value
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Serializes a list of values as comma-separated strings, using `Display` and `FromStr`, e.g.,
+/// `active,exited_slashed`. An empty string is an empty list.
+pub mod str_list {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer, T: Display>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let strings: Vec<String> = values.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&strings.join(","))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: FromStr<Err = String>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+
+        if string.is_empty() {
+            return Ok(vec![]);
+        }
+
+        string
+            .split(',')
+            .map(|value| T::from_str(value).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_root() -> String {
+        format!("0x{}{}", "00".repeat(31), "2a")
+    }
+
+    #[test]
+    fn parse_valid() {
+        assert_eq!(parse_slot("0"), Ok(Slot::new(0)));
+        assert_eq!(parse_slot("10000000"), Ok(Slot::new(10_000_000)));
+        assert_eq!(
+            parse_slot(&u64::max_value().to_string()),
+            Ok(Slot::max_value())
+        );
+        assert_eq!(parse_root(&valid_root()), Ok(Hash256::from_low_u64_be(42)));
+        assert_eq!(
+            parse_root(&format!("0x{}", "AB".repeat(32))),
+            Ok(Hash256::repeat_byte(0xab))
+        );
+    }
+
+    #[test]
+    fn slot_errors() {
+        let cases = vec![
+            ("", "empty slot"),
+            ("head", "unknown slot keyword \"head\""),
+            ("genesis", "unknown slot keyword \"genesis\""),
+            ("0x2a", "not hex"),
+            ("-1", "unexpected character '-' at position 0"),
+            ("4 2", "unexpected character ' ' at position 1"),
+            ("42a", "unexpected character 'a' at position 2"),
+            ("1.5", "unexpected character '.' at position 1"),
+            ("18446744073709551616", "larger than the maximum slot"),
+        ];
+
+        for (value, expected) in cases {
+            let error = parse_slot(value).expect_err(value);
+            assert!(
+                error.contains(expected),
+                "error for {:?} should contain {:?}, got {:?}",
+                value,
+                expected,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn root_errors() {
+        let cases = vec![
+            ("".to_string(), "missing 0x prefix"),
+            ("head".to_string(), "missing 0x prefix"),
+            ("00".repeat(32), "missing 0x prefix"),
+            ("0x".to_string(), "found 0"),
+            ("0x2a".to_string(), "found 2"),
+            (format!("0x{}", "00".repeat(33)), "found 66"),
+            (
+                format!("0x{}zz", "00".repeat(31)),
+                "invalid hex character 'z' at position 64",
+            ),
+            (
+                format!("0x{}ü", "0".repeat(63)),
+                "invalid hex character 'ü' at position 65",
+            ),
+        ];
+
+        for (value, expected) in cases {
+            let error = parse_root(&value).expect_err(&value);
+            assert!(
+                error.contains(expected),
+                "error for {:?} should contain {:?}, got {:?}",
+                value,
+                expected,
+                error
+            );
+        }
+    }
+
+    /// Mutates a valid root and slot in many ways, checking that parsing never panics and only
+    /// accepts well-formed values.
+    #[test]
+    fn malformed_inputs() {
+        let root = valid_root();
+        let junk = ['g', 'Z', ' ', '-', '%', '\0', 'ü', '∞'];
+
+        for len in 0..root.len() {
+            assert!(parse_root(&root[..len]).is_err(), "truncated at {}", len);
+        }
+
+        for position in 0..root.len() {
+            for c in junk.iter() {
+                let mut mutated: Vec<char> = root.chars().collect();
+                mutated[position] = *c;
+                let mutated: String = mutated.into_iter().collect();
+                assert!(parse_root(&mutated).is_err(), "{:?}", mutated);
+                assert!(parse_slot(&mutated).is_err(), "{:?}", mutated);
+            }
+
+            let mut extended = root.clone();
+            extended.insert(position, '0');
+            assert!(parse_root(&extended).is_err(), "{:?}", extended);
+        }
+
+        for c in junk.iter() {
+            for position in 0..3 {
+                let mut mutated: Vec<char> = "123".chars().collect();
+                mutated[position] = *c;
+                let mutated: String = mutated.into_iter().collect();
+                assert!(parse_slot(&mutated).is_err(), "{:?}", mutated);
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Collection of types for the /spec and /lighthouse/testnet HTTP endpoints.
+use serde::{Deserialize, Serialize};
+use types::{Hash256, YamlConfig};
+
+/// Every constant of the spec (including the preset values of the `EthSpec`), in the format of
+/// the YAML configs of the specs repo.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpecConfig {
+    /// The name of the spec constants in use, e.g. `mainnet`.
+    #[serde(rename = "CONFIG_NAME")]
+    pub config_name: String,
+    #[serde(flatten)]
+    pub yaml_config: YamlConfig,
+}
+
+/// The eth1 deposit contract of the network.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositContract {
+    /// The id of the eth1 network on which the contract is deployed.
+    pub chain_id: u64,
+    pub address: String,
+    /// The block in which the contract was deployed.
+    pub deploy_block: u64,
+}
+
+/// The information required to bootstrap a node onto the same network as this one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestnetInfo {
+    /// The name of the spec constants in use, e.g. `mainnet`.
+    pub config_name: String,
+    /// The ENRs of the boot nodes the node was configured with, in base64.
+    pub boot_enrs: Vec<String>,
+    pub genesis_time: u64,
+    pub genesis_validators_root: Hash256,
+    pub genesis_state_root: Hash256,
+    pub deposit_contract: DepositContract,
+}
@@ -0,0 +1,44 @@
+//! Serializes SSZ-encodable values as a `0x`-prefixed hex string, for use as query parameters.
+//!
+//! E.g., `#[serde(with = "crate::ssz_hex")]`.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+use ssz::{Decode, Encode};
+
+pub fn serialize<T: Encode, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(value.as_ssz_bytes())))
+}
+
+pub fn deserialize<'de, T: Decode, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    if !string.starts_with("0x") {
+        return Err(D::Error::custom("SSZ hex must have a 0x prefix"));
+    }
+    let bytes = hex::decode(&string[2..])
+        .map_err(|e| D::Error::custom(format!("Invalid SSZ hex: {:?}", e)))?;
+    T::from_ssz_bytes(&bytes).map_err(|e| D::Error::custom(format!("Invalid SSZ bytes: {:?}", e)))
+}
+
+/// As the parent module, for optional values. The field must also have `#[serde(default)]` and
+/// skip serializing `None`.
+pub mod option {
+    use serde::{Deserializer, Serializer};
+    use ssz::{Decode, Encode};
+
+    pub fn serialize<T: Encode, S: Serializer>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T: Decode, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        super::deserialize(deserializer).map(Some)
+    }
+}
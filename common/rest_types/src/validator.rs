@@ -1,7 +1,8 @@
+use crate::ExtraFields;
 use bls::{PublicKey, PublicKeyBytes};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use types::{CommitteeIndex, Epoch, Slot};
+use types::{AttestationData, CommitteeIndex, Epoch, Graffiti, Hash256, Signature, Slot};
 
 /// A Validator duty with the validator public key represented a `PublicKeyBytes`.
 pub type ValidatorDutyBytes = ValidatorDutyBase<PublicKeyBytes>;
@@ -27,14 +28,25 @@ pub struct ValidatorDutyBase<T> {
     ///
     /// Should be set to `None` when duties are not yet known (before the current epoch).
     pub block_proposal_slots: Option<Vec<Slot>>,
+    /// The root of the head block from which `block_proposal_slots` were computed, when they were
+    /// computed ahead of time for the next epoch.
+    ///
+    /// Such proposal slots may change if the head changes before the epoch begins. Set to `None`
+    /// when the proposal slots are final (or unknown).
+    #[serde(default)]
+    pub block_proposal_dependent_root: Option<Hash256>,
     /// This provides the modulo: `max(1, len(committee) // TARGET_AGGREGATORS_PER_COMMITTEE)`
     /// which allows the validator client to determine if this duty requires the validator to be
     /// aggregate attestations.
     pub aggregator_modulo: Option<u64>,
+    /// Fields which are unknown to this version of Lighthouse.
+    #[serde(flatten, default, skip_serializing_if = "ExtraFields::is_empty")]
+    pub extra: ExtraFields,
 }
 
 impl<T> ValidatorDutyBase<T> {
-    /// Return `true` if these validator duties are equal, ignoring their `block_proposal_slots`.
+    /// Return `true` if these validator duties are equal, ignoring their `block_proposal_slots`
+    /// and `block_proposal_dependent_root`.
     pub fn eq_ignoring_proposal_slots(&self, other: &Self) -> bool
     where
         T: PartialEq,
@@ -73,6 +85,79 @@ pub struct ValidatorSubscription {
     pub is_aggregator: bool,
 }
 
+/// Query parameters for `/validator/attestation`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct AttestationQuery {
+    pub slot: Slot,
+    pub committee_index: CommitteeIndex,
+}
+
+/// Query parameters for `/validator/block`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct BlockProductionQuery {
+    pub slot: Slot,
+    pub randao_reveal: Signature,
+    /// If set to `None`, the beacon node's graffiti is used.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::ssz_hex::option"
+    )]
+    pub graffiti: Option<Graffiti>,
+    /// Produce the block without verifying the `randao_reveal`, which must be the empty
+    /// (all-zero) signature. Only permitted if the beacon node was started with
+    /// `--http-allow-skip-randao-verification`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_randao_verification: bool,
+}
+
+/// Query parameters for `/validator/aggregate_attestation`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct AggregateAttestationQuery {
+    /// The data of the attestations to aggregate, as SSZ hex.
+    #[serde(with = "crate::ssz_hex")]
+    pub attestation_data: AttestationData,
+}
+
+/// Query parameters for `/lighthouse/validator/duties`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct BatchedDutiesQuery {
+    /// The epochs for which to return duties, e.g., `epochs=5,6,7`.
+    #[serde(with = "crate::query_value::u64_list")]
+    pub epochs: Vec<Epoch>,
+    /// The indices of the validators for which to return duties, e.g., `indices=1,2,3`.
+    #[serde(with = "crate::query_value::u64_list")]
+    pub indices: Vec<u64>,
+}
+
+/// The attester duties of a set of validators during a single epoch, as returned by
+/// `/lighthouse/validator/duties`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct EpochAttesterDuties {
+    pub epoch: Epoch,
+    /// The duties of each requested validator which is active in `epoch`, in the order in which
+    /// they were requested. Unknown and inactive validators are omitted.
+    pub duties: Vec<AttesterDuty>,
+}
+
+/// The attester duty of a single validator during some epoch.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct AttesterDuty {
+    /// The validator's index in `state.validators`.
+    pub validator_index: u64,
+    /// The slot at which the validator must attest.
+    pub attestation_slot: Slot,
+    /// The index of the committee within `slot` of which the validator is a member.
+    pub attestation_committee_index: CommitteeIndex,
+    /// The position of the validator in the committee.
+    pub attestation_committee_position: usize,
+    /// The committee count at `attestation_slot`.
+    pub committee_count_at_slot: u64,
+    /// The modulo used to determine if the validator is an aggregator, see
+    /// `ValidatorDutyBase::aggregator_modulo`.
+    pub aggregator_modulo: u64,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -90,14 +175,59 @@ mod test {
             attestation_committee_position: Some(6),
             committee_count_at_slot: Some(4),
             block_proposal_slots: None,
+            block_proposal_dependent_root: None,
             aggregator_modulo: Some(99),
+            extra: ExtraFields::default(),
         };
         let duty2 = ValidatorDuty {
             block_proposal_slots: Some(vec![Slot::new(42), Slot::new(45)]),
+            block_proposal_dependent_root: Some(Hash256::repeat_byte(7)),
             ..duty1.clone()
         };
         assert_ne!(duty1, duty2);
         assert!(duty1.eq_ignoring_proposal_slots(&duty2));
         assert!(duty2.eq_ignoring_proposal_slots(&duty1));
     }
+
+    #[test]
+    fn unknown_fields_are_preserved() {
+        let json = r#"{
+            "validator_pubkey": "0x8000",
+            "validator_index": 10,
+            "attestation_slot": 50,
+            "attestation_committee_index": 2,
+            "attestation_committee_position": 6,
+            "committee_count_at_slot": 4,
+            "block_proposal_slots": [],
+            "aggregator_modulo": 99,
+            "new_field": {"nested": [1, 2]}
+        }"#;
+
+        let duty: ValidatorDutyBase<String> = serde_json::from_str(json).unwrap();
+        assert_eq!(duty.validator_index, Some(10));
+        assert_eq!(duty.block_proposal_dependent_root, None);
+        assert_eq!(
+            duty.extra.get("new_field"),
+            Some(&serde_json::json!({"nested": [1, 2]}))
+        );
+
+        let reencoded: serde_json::Value = serde_json::to_value(&duty).unwrap();
+        assert_eq!(
+            reencoded["new_field"],
+            serde_json::json!({"nested": [1, 2]})
+        );
+
+        // Known fields are not duplicated in the extra fields.
+        let duty = ValidatorDutyBase {
+            extra: ExtraFields::default(),
+            ..duty
+        };
+        let encoded = serde_json::to_value(&duty).unwrap();
+        assert!(encoded.get("extra").is_none());
+        assert!(encoded.get("new_field").is_none());
+        assert_eq!(
+            serde_json::from_value::<ValidatorDutyBase<String>>(encoded).unwrap(),
+            duty
+        );
+    }
 }
@@ -0,0 +1,254 @@
+//! The status of a validator, using the taxonomy of the standard Eth2 API.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use types::{Epoch, Validator};
+
+/// The status of a validator at some epoch.
+///
+/// A validator's status is always one of the nine specific statuses (e.g., `ActiveExiting`),
+/// each of which belongs to one of the four general statuses (e.g., `Active`). General statuses
+/// are only used to filter validators, see `Self::matches`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    /// The validator's deposit has been processed, but it is not yet eligible for activation.
+    PendingInitialized,
+    /// The validator is eligible for activation and is waiting in the activation queue.
+    PendingQueued,
+    /// The validator is active and has not initiated an exit.
+    ActiveOngoing,
+    /// The validator is active and has voluntarily initiated an exit.
+    ActiveExiting,
+    /// The validator is active but has been slashed, so will be exited.
+    ActiveSlashed,
+    /// The validator has exited without being slashed and is not yet withdrawable.
+    ExitedUnslashed,
+    /// The validator has exited after being slashed and is not yet withdrawable.
+    ExitedSlashed,
+    /// The validator is withdrawable and has a non-zero balance.
+    WithdrawalPossible,
+    /// The validator is withdrawable and its balance has been withdrawn.
+    WithdrawalDone,
+    /// Any of `PendingInitialized` or `PendingQueued`.
+    Pending,
+    /// Any of `ActiveOngoing`, `ActiveExiting` or `ActiveSlashed`.
+    Active,
+    /// Any of `ExitedUnslashed` or `ExitedSlashed`.
+    Exited,
+    /// Any of `WithdrawalPossible` or `WithdrawalDone`.
+    Withdrawal,
+}
+
+impl ValidatorStatus {
+    /// Every status, specific statuses first.
+    pub const ALL: [ValidatorStatus; 13] = [
+        ValidatorStatus::PendingInitialized,
+        ValidatorStatus::PendingQueued,
+        ValidatorStatus::ActiveOngoing,
+        ValidatorStatus::ActiveExiting,
+        ValidatorStatus::ActiveSlashed,
+        ValidatorStatus::ExitedUnslashed,
+        ValidatorStatus::ExitedSlashed,
+        ValidatorStatus::WithdrawalPossible,
+        ValidatorStatus::WithdrawalDone,
+        ValidatorStatus::Pending,
+        ValidatorStatus::Active,
+        ValidatorStatus::Exited,
+        ValidatorStatus::Withdrawal,
+    ];
+
+    /// Returns the specific status of `validator` at `epoch`.
+    pub fn from_validator(validator: &Validator, epoch: Epoch, far_future_epoch: Epoch) -> Self {
+        if validator.is_withdrawable_at(epoch) {
+            if validator.effective_balance == 0 {
+                ValidatorStatus::WithdrawalDone
+            } else {
+                ValidatorStatus::WithdrawalPossible
+            }
+        } else if validator.is_exited_at(epoch) {
+            if validator.slashed {
+                ValidatorStatus::ExitedSlashed
+            } else {
+                ValidatorStatus::ExitedUnslashed
+            }
+        } else if validator.is_active_at(epoch) {
+            if validator.slashed {
+                ValidatorStatus::ActiveSlashed
+            } else if validator.exit_epoch != far_future_epoch {
+                ValidatorStatus::ActiveExiting
+            } else {
+                ValidatorStatus::ActiveOngoing
+            }
+        } else if validator.activation_eligibility_epoch == far_future_epoch {
+            ValidatorStatus::PendingInitialized
+        } else {
+            ValidatorStatus::PendingQueued
+        }
+    }
+
+    /// Returns the general status to which `self` belongs. General statuses belong to themselves.
+    pub fn superstatus(self) -> Self {
+        match self {
+            ValidatorStatus::PendingInitialized
+            | ValidatorStatus::PendingQueued
+            | ValidatorStatus::Pending => ValidatorStatus::Pending,
+            ValidatorStatus::ActiveOngoing
+            | ValidatorStatus::ActiveExiting
+            | ValidatorStatus::ActiveSlashed
+            | ValidatorStatus::Active => ValidatorStatus::Active,
+            ValidatorStatus::ExitedUnslashed
+            | ValidatorStatus::ExitedSlashed
+            | ValidatorStatus::Exited => ValidatorStatus::Exited,
+            ValidatorStatus::WithdrawalPossible
+            | ValidatorStatus::WithdrawalDone
+            | ValidatorStatus::Withdrawal => ValidatorStatus::Withdrawal,
+        }
+    }
+
+    /// Returns `true` if `self` is one of the four general statuses.
+    pub fn is_general(self) -> bool {
+        self.superstatus() == self
+    }
+
+    /// Returns `true` if `self` is `filter`, or belongs to the general status `filter`.
+    pub fn matches(self, filter: ValidatorStatus) -> bool {
+        self == filter || self.superstatus() == filter
+    }
+
+    pub fn is_pending(self) -> bool {
+        self.superstatus() == ValidatorStatus::Pending
+    }
+
+    pub fn is_active(self) -> bool {
+        self.superstatus() == ValidatorStatus::Active
+    }
+
+    pub fn is_exited(self) -> bool {
+        self.superstatus() == ValidatorStatus::Exited
+    }
+
+    pub fn is_withdrawal(self) -> bool {
+        self.superstatus() == ValidatorStatus::Withdrawal
+    }
+
+    /// Returns the name of the status used by the API, e.g. `active_exiting`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ValidatorStatus::PendingInitialized => "pending_initialized",
+            ValidatorStatus::PendingQueued => "pending_queued",
+            ValidatorStatus::ActiveOngoing => "active_ongoing",
+            ValidatorStatus::ActiveExiting => "active_exiting",
+            ValidatorStatus::ActiveSlashed => "active_slashed",
+            ValidatorStatus::ExitedUnslashed => "exited_unslashed",
+            ValidatorStatus::ExitedSlashed => "exited_slashed",
+            ValidatorStatus::WithdrawalPossible => "withdrawal_possible",
+            ValidatorStatus::WithdrawalDone => "withdrawal_done",
+            ValidatorStatus::Pending => "pending",
+            ValidatorStatus::Active => "active",
+            ValidatorStatus::Exited => "exited",
+            ValidatorStatus::Withdrawal => "withdrawal",
+        }
+    }
+}
+
+impl fmt::Display for ValidatorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ValidatorStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| format!("unknown validator status {:?}", s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FAR_FUTURE_EPOCH: Epoch = Epoch::new(u64::max_value());
+
+    fn validator() -> Validator {
+        Validator {
+            effective_balance: 32_000_000_000,
+            ..Validator::default()
+        }
+    }
+
+    fn status(validator: &Validator, epoch: u64) -> ValidatorStatus {
+        ValidatorStatus::from_validator(validator, Epoch::new(epoch), FAR_FUTURE_EPOCH)
+    }
+
+    #[test]
+    fn from_validator() {
+        let mut v = validator();
+        assert_eq!(status(&v, 0), ValidatorStatus::PendingInitialized);
+
+        v.activation_eligibility_epoch = Epoch::new(1);
+        assert_eq!(status(&v, 2), ValidatorStatus::PendingQueued);
+
+        v.activation_epoch = Epoch::new(5);
+        assert_eq!(status(&v, 4), ValidatorStatus::PendingQueued);
+        assert_eq!(status(&v, 5), ValidatorStatus::ActiveOngoing);
+
+        v.exit_epoch = Epoch::new(10);
+        v.withdrawable_epoch = Epoch::new(20);
+        assert_eq!(status(&v, 9), ValidatorStatus::ActiveExiting);
+        assert_eq!(status(&v, 10), ValidatorStatus::ExitedUnslashed);
+        assert_eq!(status(&v, 20), ValidatorStatus::WithdrawalPossible);
+
+        v.slashed = true;
+        assert_eq!(status(&v, 9), ValidatorStatus::ActiveSlashed);
+        assert_eq!(status(&v, 10), ValidatorStatus::ExitedSlashed);
+
+        v.effective_balance = 0;
+        assert_eq!(status(&v, 20), ValidatorStatus::WithdrawalDone);
+    }
+
+    #[test]
+    fn matches() {
+        for status in ValidatorStatus::ALL.iter().copied() {
+            assert!(status.matches(status));
+            assert!(status.matches(status.superstatus()));
+            assert!(status.superstatus().is_general());
+            assert_eq!(
+                [
+                    status.is_pending(),
+                    status.is_active(),
+                    status.is_exited(),
+                    status.is_withdrawal()
+                ]
+                .iter()
+                .filter(|is| **is)
+                .count(),
+                1
+            );
+        }
+
+        assert!(!ValidatorStatus::Active.matches(ValidatorStatus::ActiveOngoing));
+        assert!(!ValidatorStatus::ActiveOngoing.matches(ValidatorStatus::ActiveExiting));
+        assert!(!ValidatorStatus::ExitedSlashed.matches(ValidatorStatus::Active));
+    }
+
+    #[test]
+    fn string_round_trip() {
+        for status in ValidatorStatus::ALL.iter().copied() {
+            assert_eq!(status.to_string().parse(), Ok(status));
+            assert_eq!(
+                serde_json::to_string(&status).unwrap(),
+                format!("\"{}\"", status)
+            );
+        }
+
+        assert!("active-ongoing".parse::<ValidatorStatus>().is_err());
+    }
+}
@@ -37,6 +37,11 @@ impl ManualSlotClock {
             self.genesis_duration + self.slot_duration * slots_since_genesis;
     }
 
+    /// Sets the present time to `duration` since the UNIX epoch.
+    pub fn set_current_time(&self, duration: Duration) {
+        *self.current_time.write() = duration;
+    }
+
     pub fn advance_slot(&self) {
         self.set_slot(self.now().unwrap().as_u64() + 1)
     }
@@ -147,6 +152,10 @@ impl SlotClock for ManualSlotClock {
         self.duration_to_slot(slot, *self.current_time.read())
     }
 
+    fn start_of(&self, slot: Slot) -> Option<Duration> {
+        self.start_of(slot)
+    }
+
     fn genesis_slot(&self) -> Slot {
         self.genesis_slot
     }
@@ -62,6 +62,18 @@ pub enum InvalidBlock {
     },
 }
 
+/// The result of verifying the execution payload of a block with an execution engine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PayloadVerificationStatus {
+    /// The execution engine determined the payload to be valid.
+    Verified,
+    /// The block was imported without the execution engine having validated its payload (e.g.,
+    /// because the execution engine is syncing).
+    NotVerified,
+    /// The block does not contain an execution payload.
+    Irrelevant,
+}
+
 #[derive(Debug)]
 pub enum InvalidAttestation {
     /// The attestations aggregation bits were empty when they shouldn't be.
@@ -400,6 +412,7 @@ where
     ///
     /// - `block_root` is the root of `block.
     /// - The root of `state` matches `block.state_root`.
+    /// - `payload_verification_status` is the result of verifying the block's execution payload.
     ///
     /// ## Specification
     ///
@@ -420,6 +433,7 @@ where
         block: &BeaconBlock<E>,
         block_root: Hash256,
         state: &BeaconState<E>,
+        payload_verification_status: PayloadVerificationStatus,
     ) -> Result<(), Error<T::Error>> {
         let current_slot = self.update_time(current_slot)?;
 
@@ -536,6 +550,7 @@ where
             state_root: block.state_root,
             justified_epoch: state.current_justified_checkpoint.epoch,
             finalized_epoch: state.finalized_checkpoint.epoch,
+            is_optimistic: payload_verification_status == PayloadVerificationStatus::NotVerified,
         })?;
 
         Ok(())
@@ -753,6 +768,12 @@ where
         })
     }
 
+    /// Returns `true` if the block was imported without its execution payload being verified, and
+    /// the payload has not been verified since. Returns `None` if the block is unknown.
+    pub fn is_optimistic_block(&self, block_root: &Hash256) -> Option<bool> {
+        self.proto_array.is_optimistic(block_root)
+    }
+
     /// Return `true` if `block_root` is equal to the finalized root, or a known descendant of it.
     pub fn is_descendant_of_finalized(&self, block_root: Hash256) -> bool {
         self.proto_array
@@ -795,6 +816,17 @@ where
             .map_err(Into::into)
     }
 
+    /// Removes every block from the underlying fork choice DAG which does not descend from the
+    /// finalized block, regardless of the usual pruning threshold. Returns the number of blocks
+    /// removed.
+    pub fn prune_all(&mut self) -> Result<usize, Error<T::Error>> {
+        let finalized_root = self.fc_store.finalized_checkpoint().root;
+
+        self.proto_array
+            .prune_all(finalized_root)
+            .map_err(Into::into)
+    }
+
     /// Instantiate `Self` from some `PersistedForkChoice` generated by a earlier call to
     /// `Self::to_persisted`.
     pub fn from_persisted(
@@ -827,7 +859,7 @@ where
 /// This is used when persisting the state of the fork choice to disk.
 #[derive(Encode, Decode, Clone)]
 pub struct PersistedForkChoice {
-    proto_array_bytes: Vec<u8>,
+    pub proto_array_bytes: Vec<u8>,
     queued_attestations: Vec<QueuedAttestation>,
 }
 
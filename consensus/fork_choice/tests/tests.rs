@@ -7,8 +7,8 @@ use beacon_chain::{
     BeaconChain, BeaconChainError, BeaconForkChoiceStore, ForkChoiceError, StateSkipConfig,
 };
 use fork_choice::{
-    ForkChoiceStore, InvalidAttestation, InvalidBlock, QueuedAttestation,
-    SAFE_SLOTS_TO_UPDATE_JUSTIFIED,
+    ForkChoiceStore, InvalidAttestation, InvalidBlock, PayloadVerificationStatus,
+    QueuedAttestation, SAFE_SLOTS_TO_UPDATE_JUSTIFIED,
 };
 use std::sync::Mutex;
 use store::{MemoryStore, StoreConfig};
@@ -212,7 +212,13 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                &state,
+                PayloadVerificationStatus::Irrelevant,
+            )
             .unwrap();
         self
     }
@@ -246,7 +252,13 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                &state,
+                PayloadVerificationStatus::Irrelevant,
+            )
             .err()
             .expect("on_block did not return an error");
         comparison_func(err);
@@ -554,12 +566,7 @@ fn invalid_block_future_slot() {
             |block, _| {
                 block.slot = block.slot + 1;
             },
-            |err| {
-                assert_invalid_block!(
-                    err,
-                    InvalidBlock::FutureSlot { .. }
-                )
-            },
+            |err| assert_invalid_block!(err, InvalidBlock::FutureSlot { .. }),
         );
 }
 
@@ -127,6 +127,7 @@ impl ForkChoiceTestDefinition {
                         target_root: Hash256::zero(),
                         justified_epoch,
                         finalized_epoch,
+                        is_optimistic: false,
                     };
                     fork_choice.process_block(block).unwrap_or_else(|e| {
                         panic!(
@@ -22,11 +22,65 @@ pub struct ProtoNode {
     pub parent: Option<usize>,
     pub justified_epoch: Epoch,
     pub finalized_epoch: Epoch,
+    /// `true` if the block was imported optimistically, i.e., without its execution payload
+    /// having been verified by an execution engine.
+    pub is_optimistic: bool,
     weight: u64,
     best_child: Option<usize>,
     best_descendant: Option<usize>,
 }
 
+/// The encoding of `ProtoNode` before `is_optimistic` was added.
+#[derive(Encode, Decode)]
+pub struct ProtoNodeV1 {
+    slot: Slot,
+    state_root: Hash256,
+    target_root: Hash256,
+    root: Hash256,
+    parent: Option<usize>,
+    justified_epoch: Epoch,
+    finalized_epoch: Epoch,
+    weight: u64,
+    best_child: Option<usize>,
+    best_descendant: Option<usize>,
+}
+
+impl From<ProtoNodeV1> for ProtoNode {
+    /// Blocks were not imported optimistically prior to `is_optimistic` being added.
+    fn from(node: ProtoNodeV1) -> Self {
+        Self {
+            slot: node.slot,
+            state_root: node.state_root,
+            target_root: node.target_root,
+            root: node.root,
+            parent: node.parent,
+            justified_epoch: node.justified_epoch,
+            finalized_epoch: node.finalized_epoch,
+            is_optimistic: false,
+            weight: node.weight,
+            best_child: node.best_child,
+            best_descendant: node.best_descendant,
+        }
+    }
+}
+
+impl From<ProtoNode> for ProtoNodeV1 {
+    fn from(node: ProtoNode) -> Self {
+        Self {
+            slot: node.slot,
+            state_root: node.state_root,
+            target_root: node.target_root,
+            root: node.root,
+            parent: node.parent,
+            justified_epoch: node.justified_epoch,
+            finalized_epoch: node.finalized_epoch,
+            weight: node.weight,
+            best_child: node.best_child,
+            best_descendant: node.best_descendant,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct ProtoArray {
     /// Do not attempt to prune the tree unless it has at least this many nodes. Small prunes
@@ -148,6 +202,7 @@ impl ProtoArray {
                 .and_then(|parent| self.indices.get(&parent).copied()),
             justified_epoch: block.justified_epoch,
             finalized_epoch: block.finalized_epoch,
+            is_optimistic: block.is_optimistic,
             weight: 0,
             best_child: None,
             best_descendant: None,
@@ -158,6 +213,34 @@ impl ProtoArray {
 
         if let Some(parent_index) = node.parent {
             self.maybe_update_best_child_and_descendant(parent_index, node_index)?;
+
+            // A verified execution payload implies that the payloads of all ancestors are valid.
+            if !node.is_optimistic {
+                self.propagate_execution_payload_validation(parent_index)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Marks the node at `index` and all of its ancestors as no longer optimistic, since their
+    /// execution payloads are known to be valid.
+    pub fn propagate_execution_payload_validation(&mut self, index: usize) -> Result<(), Error> {
+        let mut index = Some(index);
+
+        while let Some(i) = index {
+            let node = self
+                .nodes
+                .get_mut(i)
+                .ok_or_else(|| Error::InvalidNodeIndex(i))?;
+
+            // The ancestors of a non-optimistic node are never optimistic.
+            if !node.is_optimistic {
+                break;
+            }
+
+            node.is_optimistic = false;
+            index = node.parent;
         }
 
         Ok(())
@@ -277,6 +360,69 @@ impl ProtoArray {
         Ok(())
     }
 
+    /// Remove every node which is neither the finalized node nor one of its descendants,
+    /// regardless of `self.prune_threshold`.
+    ///
+    /// Unlike `Self::maybe_prune`, this also removes branches which conflict with finality (i.e.,
+    /// which fork from an ancestor of the finalized node). Returns the number of nodes removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the finalized node is unknown.
+    pub fn prune_all(&mut self, finalized_root: Hash256) -> Result<usize, Error> {
+        let finalized_index = *self
+            .indices
+            .get(&finalized_root)
+            .ok_or_else(|| Error::FinalizedNodeUnknown(finalized_root))?;
+
+        // Map the index of each node to its index after pruning, or `None` if it is to be removed.
+        //
+        // A parent always precedes its children in `self.nodes`, so the fate of each node's parent
+        // is known by the time the node is reached.
+        let mut new_indices: Vec<Option<usize>> = Vec::with_capacity(self.nodes.len());
+        let mut retained = 0;
+        for (index, node) in self.nodes.iter().enumerate() {
+            let keep = index == finalized_index
+                || (index > finalized_index
+                    && node
+                        .parent
+                        .and_then(|parent| new_indices.get(parent).copied().flatten())
+                        .is_some());
+
+            if keep {
+                new_indices.push(Some(retained));
+                retained += 1;
+            } else {
+                new_indices.push(None);
+            }
+        }
+
+        let removed = self.nodes.len() - retained;
+        let remap =
+            |index: Option<usize>| index.and_then(|i| new_indices.get(i).copied().flatten());
+
+        self.nodes = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .zip(new_indices.iter())
+            .filter(|(_node, new_index)| new_index.is_some())
+            .map(|(mut node, _new_index)| {
+                node.parent = remap(node.parent);
+                node.best_child = remap(node.best_child);
+                node.best_descendant = remap(node.best_descendant);
+                node
+            })
+            .collect();
+
+        self.indices = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.root, index))
+            .collect();
+
+        Ok(removed)
+    }
+
     /// Observe the parent at `parent_index` with respect to the child at `child_index` and
     /// potentially modify the `parent.best_child` and `parent.best_descendant` values.
     ///
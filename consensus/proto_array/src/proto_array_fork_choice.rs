@@ -1,9 +1,10 @@
 use crate::error::Error;
-use crate::proto_array::ProtoArray;
-use crate::ssz_container::SszContainer;
+use crate::proto_array::{ProtoArray, ProtoNode};
+use crate::ssz_container::{SszContainer, SszContainerV1};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::collections::HashMap;
+use std::mem;
 use types::{Epoch, Hash256, Slot};
 
 pub const DEFAULT_PRUNE_THRESHOLD: usize = 256;
@@ -26,6 +27,8 @@ pub struct Block {
     pub target_root: Hash256,
     pub justified_epoch: Epoch,
     pub finalized_epoch: Epoch,
+    /// `true` if the block's execution payload has not been verified by an execution engine.
+    pub is_optimistic: bool,
 }
 
 /// A Vec-wrapper which will grow to match any request.
@@ -88,6 +91,8 @@ impl ProtoArrayForkChoice {
             target_root: finalized_root,
             justified_epoch,
             finalized_epoch,
+            // The finalized block is the trusted starting point of fork choice.
+            is_optimistic: false,
         };
 
         proto_array
@@ -163,6 +168,14 @@ impl ProtoArrayForkChoice {
             .map_err(|e| format!("find_head maybe_prune failed: {:?}", e))
     }
 
+    /// Removes every node which does not descend from the finalized block, see
+    /// `ProtoArray::prune_all`. Returns the number of nodes removed.
+    pub fn prune_all(&mut self, finalized_root: Hash256) -> Result<usize, String> {
+        self.proto_array
+            .prune_all(finalized_root)
+            .map_err(|e| format!("prune_all failed: {:?}", e))
+    }
+
     pub fn set_prune_threshold(&mut self, prune_threshold: usize) {
         self.proto_array.prune_threshold = prune_threshold;
     }
@@ -175,6 +188,16 @@ impl ProtoArrayForkChoice {
         self.proto_array.nodes.is_empty()
     }
 
+    /// Returns an estimate of the number of bytes of memory used by `self`.
+    pub fn approximate_size_bytes(&self) -> usize {
+        let proto_array = &self.proto_array;
+
+        proto_array.nodes.capacity() * mem::size_of::<ProtoNode>()
+            + proto_array.indices.capacity() * mem::size_of::<(Hash256, usize)>()
+            + self.votes.0.capacity() * mem::size_of::<VoteTracker>()
+            + self.balances.capacity() * mem::size_of::<u64>()
+    }
+
     pub fn contains_block(&self, block_root: &Hash256) -> bool {
         self.proto_array.indices.contains_key(block_root)
     }
@@ -195,9 +218,38 @@ impl ProtoArrayForkChoice {
             target_root: block.target_root,
             justified_epoch: block.justified_epoch,
             finalized_epoch: block.finalized_epoch,
+            is_optimistic: block.is_optimistic,
         })
     }
 
+    /// Returns `true` if the block with `block_root` was imported optimistically and its
+    /// execution payload has not since been verified, or `None` if the block is unknown.
+    pub fn is_optimistic(&self, block_root: &Hash256) -> Option<bool> {
+        let block_index = self.proto_array.indices.get(block_root)?;
+        self.proto_array
+            .nodes
+            .get(*block_index)
+            .map(|node| node.is_optimistic)
+    }
+
+    /// Records that the execution payload of the block with `block_root` is valid, which implies
+    /// the payloads of all of its ancestors are valid.
+    pub fn process_execution_payload_validation(
+        &mut self,
+        block_root: &Hash256,
+    ) -> Result<(), String> {
+        let block_index = self
+            .proto_array
+            .indices
+            .get(block_root)
+            .copied()
+            .ok_or_else(|| format!("Unknown block {:?}", block_root))?;
+
+        self.proto_array
+            .propagate_execution_payload_validation(block_index)
+            .map_err(|e| format!("process_execution_payload_validation failed: {:?}", e))
+    }
+
     /// Returns `true` if the `descendant_root` has an ancestor with `ancestor_root`. Always
     /// returns `false` if either input roots are unknown.
     ///
@@ -243,6 +295,21 @@ impl ProtoArrayForkChoice {
             .map_err(|e| format!("Failed to decode ProtoArrayForkChoice: {:?}", e))
     }
 
+    /// Encodes `self` as it was before `ProtoNode::is_optimistic` was added, discarding it.
+    ///
+    /// Only intended for testing the decoding of previously persisted fork choice.
+    pub fn as_bytes_v1(&self) -> Vec<u8> {
+        SszContainerV1::from(SszContainer::from(self)).as_ssz_bytes()
+    }
+
+    /// Decodes `bytes` encoded before `ProtoNode::is_optimistic` was added. No block is considered
+    /// to have been imported optimistically.
+    pub fn from_bytes_v1(bytes: &[u8]) -> Result<Self, String> {
+        SszContainerV1::from_ssz_bytes(bytes)
+            .map(|container| SszContainer::from(container).into())
+            .map_err(|e| format!("Failed to decode ProtoArrayForkChoice (v1): {:?}", e))
+    }
+
     /// Returns a read-lock to core `ProtoArray` struct.
     ///
     /// Should only be used when encoding/decoding during troubleshooting.
@@ -360,6 +427,7 @@ mod test_compute_deltas {
                 target_root: finalized_root,
                 justified_epoch: genesis_epoch,
                 finalized_epoch: genesis_epoch,
+                is_optimistic: false,
             })
             .unwrap();
 
@@ -373,6 +441,7 @@ mod test_compute_deltas {
                 target_root: finalized_root,
                 justified_epoch: genesis_epoch,
                 finalized_epoch: genesis_epoch,
+                is_optimistic: false,
             })
             .unwrap();
 
@@ -397,6 +466,190 @@ mod test_compute_deltas {
         assert!(!fc.is_descendant(not_finalized_desc, unknown));
     }
 
+    #[test]
+    fn prune_all() {
+        let genesis_slot = Slot::new(0);
+        let genesis_epoch = Epoch::new(0);
+
+        let state_root = Hash256::from_low_u64_be(0);
+        let genesis_root = hash_from_index(0);
+        let ancestor = hash_from_index(1);
+        let finalized_root = hash_from_index(2);
+        let conflicting = hash_from_index(3);
+        let descendant_a = hash_from_index(4);
+        let descendant_b = hash_from_index(5);
+
+        let mut fc = ProtoArrayForkChoice::new(
+            genesis_slot,
+            state_root,
+            genesis_epoch,
+            genesis_epoch,
+            genesis_root,
+        )
+        .unwrap();
+
+        // genesis <- ancestor <- finalized <- descendant_a <- descendant_b
+        //                  ^
+        //                  \---- conflicting
+        let blocks = vec![
+            (1, ancestor, genesis_root),
+            (2, finalized_root, ancestor),
+            (3, conflicting, ancestor),
+            (4, descendant_a, finalized_root),
+            (5, descendant_b, descendant_a),
+        ];
+        for (slot, root, parent_root) in blocks {
+            fc.process_block(Block {
+                slot: Slot::new(slot),
+                root,
+                parent_root: Some(parent_root),
+                state_root,
+                target_root: genesis_root,
+                justified_epoch: genesis_epoch,
+                finalized_epoch: genesis_epoch,
+                is_optimistic: false,
+            })
+            .unwrap();
+        }
+
+        assert_eq!(fc.len(), 6);
+        assert_eq!(fc.prune_all(finalized_root), Ok(3));
+        assert_eq!(fc.len(), 3);
+
+        for root in &[genesis_root, ancestor, conflicting] {
+            assert!(!fc.contains_block(root), "should remove {:?}", root);
+        }
+
+        assert_eq!(fc.get_block(&finalized_root).unwrap().parent_root, None);
+        assert_eq!(
+            fc.get_block(&descendant_a).unwrap().parent_root,
+            Some(finalized_root)
+        );
+        assert_eq!(
+            fc.get_block(&descendant_b).unwrap().parent_root,
+            Some(descendant_a)
+        );
+        assert!(fc.is_descendant(finalized_root, descendant_b));
+
+        // Pruning again should not remove anything.
+        assert_eq!(fc.prune_all(finalized_root), Ok(0));
+        assert!(fc.prune_all(conflicting).is_err());
+    }
+
+    #[test]
+    fn optimistic_blocks() {
+        let genesis_slot = Slot::new(0);
+        let genesis_epoch = Epoch::new(0);
+
+        let state_root = Hash256::from_low_u64_be(0);
+        let genesis_root = hash_from_index(0);
+        let block_a = hash_from_index(1);
+        let block_b = hash_from_index(2);
+        let block_c = hash_from_index(3);
+        let block_d = hash_from_index(4);
+
+        let mut fc = ProtoArrayForkChoice::new(
+            genesis_slot,
+            state_root,
+            genesis_epoch,
+            genesis_epoch,
+            genesis_root,
+        )
+        .unwrap();
+
+        // genesis <- a <- b <- c
+        //             ^
+        //             \---- d
+        let blocks = vec![
+            (1, block_a, genesis_root, true),
+            (2, block_b, block_a, true),
+            (3, block_c, block_b, true),
+            (2, block_d, block_a, true),
+        ];
+        for (slot, root, parent_root, is_optimistic) in blocks {
+            fc.process_block(Block {
+                slot: Slot::new(slot),
+                root,
+                parent_root: Some(parent_root),
+                state_root,
+                target_root: genesis_root,
+                justified_epoch: genesis_epoch,
+                finalized_epoch: genesis_epoch,
+                is_optimistic,
+            })
+            .unwrap();
+        }
+
+        assert_eq!(fc.is_optimistic(&genesis_root), Some(false));
+        assert_eq!(fc.is_optimistic(&block_c), Some(true));
+        assert_eq!(fc.is_optimistic(&hash_from_index(5)), None);
+
+        // Validating `b` validates its ancestors, but not its descendants or other forks.
+        fc.process_execution_payload_validation(&block_b).unwrap();
+        assert_eq!(fc.is_optimistic(&block_a), Some(false));
+        assert_eq!(fc.is_optimistic(&block_b), Some(false));
+        assert_eq!(fc.is_optimistic(&block_c), Some(true));
+        assert_eq!(fc.is_optimistic(&block_d), Some(true));
+
+        // A verified block validates its ancestors when it is imported.
+        let block_e = hash_from_index(5);
+        fc.process_block(Block {
+            slot: Slot::new(3),
+            root: block_e,
+            parent_root: Some(block_d),
+            state_root,
+            target_root: genesis_root,
+            justified_epoch: genesis_epoch,
+            finalized_epoch: genesis_epoch,
+            is_optimistic: false,
+        })
+        .unwrap();
+        assert_eq!(fc.is_optimistic(&block_d), Some(false));
+        assert_eq!(fc.is_optimistic(&block_e), Some(false));
+        assert_eq!(fc.is_optimistic(&block_c), Some(true));
+    }
+
+    #[test]
+    fn decodes_v1_encoding() {
+        let genesis_epoch = Epoch::new(0);
+        let state_root = Hash256::from_low_u64_be(0);
+        let genesis_root = hash_from_index(0);
+        let optimistic_root = hash_from_index(1);
+
+        let mut fc = ProtoArrayForkChoice::new(
+            Slot::new(0),
+            state_root,
+            genesis_epoch,
+            genesis_epoch,
+            genesis_root,
+        )
+        .unwrap();
+
+        fc.process_block(Block {
+            slot: Slot::new(1),
+            root: optimistic_root,
+            parent_root: Some(genesis_root),
+            state_root,
+            target_root: genesis_root,
+            justified_epoch: genesis_epoch,
+            finalized_epoch: genesis_epoch,
+            is_optimistic: true,
+        })
+        .unwrap();
+
+        let decoded = ProtoArrayForkChoice::from_bytes_v1(&fc.as_bytes_v1()).unwrap();
+        assert_eq!(decoded.is_optimistic(&optimistic_root), Some(false));
+
+        // Other than `is_optimistic`, the v1 encoding should be lossless.
+        fc.process_execution_payload_validation(&optimistic_root)
+            .unwrap();
+        assert!(decoded == fc, "should round-trip through the v1 encoding");
+        assert!(
+            ProtoArrayForkChoice::from_bytes(&decoded.as_bytes()).unwrap() == fc,
+            "should re-encode with the current encoding"
+        );
+    }
+
     #[test]
     fn zero_hash() {
         let validator_count: usize = 16;
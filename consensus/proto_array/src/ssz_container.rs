@@ -1,5 +1,5 @@
 use crate::{
-    proto_array::{ProtoArray, ProtoNode},
+    proto_array::{ProtoArray, ProtoNode, ProtoNodeV1},
     proto_array_fork_choice::{ElasticList, ProtoArrayForkChoice, VoteTracker},
 };
 use ssz_derive::{Decode, Encode};
@@ -51,3 +51,43 @@ impl From<SszContainer> for ProtoArrayForkChoice {
         }
     }
 }
+
+/// The encoding of `SszContainer` before `ProtoNode::is_optimistic` was added.
+#[derive(Encode, Decode)]
+pub struct SszContainerV1 {
+    votes: Vec<VoteTracker>,
+    balances: Vec<u64>,
+    prune_threshold: usize,
+    justified_epoch: Epoch,
+    finalized_epoch: Epoch,
+    nodes: Vec<ProtoNodeV1>,
+    indices: Vec<(Hash256, usize)>,
+}
+
+impl From<SszContainerV1> for SszContainer {
+    fn from(from: SszContainerV1) -> Self {
+        Self {
+            votes: from.votes,
+            balances: from.balances,
+            prune_threshold: from.prune_threshold,
+            justified_epoch: from.justified_epoch,
+            finalized_epoch: from.finalized_epoch,
+            nodes: from.nodes.into_iter().map(Into::into).collect(),
+            indices: from.indices,
+        }
+    }
+}
+
+impl From<SszContainer> for SszContainerV1 {
+    fn from(from: SszContainer) -> Self {
+        Self {
+            votes: from.votes,
+            balances: from.balances,
+            prune_threshold: from.prune_threshold,
+            justified_epoch: from.justified_epoch,
+            finalized_epoch: from.finalized_epoch,
+            nodes: from.nodes.into_iter().map(Into::into).collect(),
+            indices: from.indices,
+        }
+    }
+}
@@ -4,6 +4,7 @@ mod get_base_reward;
 mod get_indexed_attestation;
 mod initiate_validator_exit;
 mod slash_validator;
+mod weak_subjectivity;
 
 pub use deposit_data_tree::DepositDataTree;
 pub use get_attesting_indices::get_attesting_indices;
@@ -11,6 +12,7 @@ pub use get_base_reward::get_base_reward;
 pub use get_indexed_attestation::get_indexed_attestation;
 pub use initiate_validator_exit::initiate_validator_exit;
 pub use slash_validator::slash_validator;
+pub use weak_subjectivity::{compute_weak_subjectivity_period, SAFETY_DECAY};
 
 use safe_arith::{ArithError, SafeArith};
 use types::{BeaconState, EthSpec};
@@ -0,0 +1,128 @@
+use safe_arith::{ArithError, SafeArith};
+use std::cmp;
+use types::*;
+
+/// The fraction of the validator set, in percent, which is assumed to be lost to an attacker over
+/// the weak subjectivity period.
+pub const SAFETY_DECAY: u64 = 10;
+
+const ETH_TO_GWEI: u64 = 1_000_000_000;
+
+/// Returns the weak subjectivity period of `state`, the number of epochs after its current epoch
+/// for which it is safe to sync from, as defined by `compute_weak_subjectivity_period` in the
+/// weak subjectivity guide of the spec.
+pub fn compute_weak_subjectivity_period<T: EthSpec>(
+    state: &BeaconState<T>,
+    spec: &ChainSpec,
+) -> Result<Epoch, BeaconStateError> {
+    let active_validator_indices =
+        state.get_active_validator_indices(state.current_epoch(), spec)?;
+    let total_active_balance = state.get_total_balance(&active_validator_indices, spec)?;
+
+    let epochs = weak_subjectivity_epochs(
+        active_validator_indices.len() as u64,
+        total_active_balance,
+        T::MaxDeposits::to_u64().safe_mul(T::slots_per_epoch())?,
+        spec,
+    )?;
+
+    Ok(Epoch::new(
+        spec.min_validator_withdrawability_delay
+            .as_u64()
+            .safe_add(epochs)?,
+    ))
+}
+
+/// Returns the number of epochs which the weak subjectivity period exceeds
+/// `MIN_VALIDATOR_WITHDRAWABILITY_DELAY` by, for `validator_count` active validators with
+/// `total_active_balance` and at most `max_deposits_per_epoch` deposits per epoch.
+fn weak_subjectivity_epochs(
+    validator_count: u64,
+    total_active_balance: u64,
+    max_deposits_per_epoch: u64,
+    spec: &ChainSpec,
+) -> Result<u64, ArithError> {
+    let n = validator_count;
+    let t = total_active_balance.safe_div(n)?.safe_div(ETH_TO_GWEI)?;
+    let max_t = spec.max_effective_balance.safe_div(ETH_TO_GWEI)?;
+    let churn_limit = cmp::max(
+        spec.min_per_epoch_churn_limit,
+        n.safe_div(spec.churn_limit_quotient)?,
+    );
+    let d = SAFETY_DECAY;
+
+    let max_t_factor = max_t.safe_mul(d.safe_mul(3)?.safe_add(200)?)?;
+    let t_factor = t.safe_mul(d.safe_mul(12)?.safe_add(200)?)?;
+
+    if max_t_factor < t_factor {
+        let epochs_for_validator_set_churn =
+            n.safe_mul(t_factor.safe_sub(max_t_factor)?)?.safe_div(
+                churn_limit
+                    .safe_mul(600)?
+                    .safe_mul(t.safe_mul(2)?.safe_add(max_t)?)?,
+            )?;
+        let epochs_for_balance_top_ups = n
+            .safe_mul(d.safe_mul(3)?.safe_add(200)?)?
+            .safe_div(max_deposits_per_epoch.safe_mul(600)?)?;
+
+        Ok(cmp::max(
+            epochs_for_validator_set_churn,
+            epochs_for_balance_top_ups,
+        ))
+    } else {
+        n.safe_mul(3)?.safe_mul(d)?.safe_mul(t)?.safe_div(
+            max_deposits_per_epoch
+                .safe_mul(200)?
+                .safe_mul(max_t.safe_sub(t)?)?,
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::integer_arithmetic)]
+mod tests {
+    use super::*;
+
+    /// The weak subjectivity periods tabulated in the spec, as `(t, N, period)`.
+    const SPEC_PERIODS: &[(u64, u64, u64)] = &[
+        (28, 32_768, 504),
+        (28, 65_536, 752),
+        (28, 131_072, 1_248),
+        (28, 262_144, 2_241),
+        (28, 524_288, 2_241),
+        (28, 1_048_576, 2_241),
+        (32, 32_768, 665),
+        (32, 65_536, 1_075),
+        (32, 131_072, 1_894),
+        (32, 262_144, 3_532),
+        (32, 524_288, 3_532),
+        (32, 1_048_576, 3_532),
+    ];
+
+    #[test]
+    fn matches_spec_table() {
+        let spec = MainnetEthSpec::default_spec();
+        let max_deposits_per_epoch =
+            <MainnetEthSpec as EthSpec>::MaxDeposits::to_u64() * MainnetEthSpec::slots_per_epoch();
+
+        for &(t, n, period) in SPEC_PERIODS {
+            let epochs =
+                weak_subjectivity_epochs(n, n * t * ETH_TO_GWEI, max_deposits_per_epoch, &spec)
+                    .expect("should compute period");
+
+            assert_eq!(
+                spec.min_validator_withdrawability_delay + epochs,
+                Epoch::new(period),
+                "t = {}, N = {}",
+                t,
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn no_validators() {
+        let spec = MainnetEthSpec::default_spec();
+        assert!(weak_subjectivity_epochs(0, 0, 512, &spec).is_err());
+    }
+}
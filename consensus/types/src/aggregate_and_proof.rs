@@ -78,3 +78,12 @@ impl<T: EthSpec> AggregateAndProof<T> {
 }
 
 impl<T: EthSpec> SignedRoot for AggregateAndProof<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainnetEthSpec;
+
+    ssz_and_tree_hash_tests!(AggregateAndProof<MainnetEthSpec>);
+    json_tests!(AggregateAndProof<MainnetEthSpec>);
+}
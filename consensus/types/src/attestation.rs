@@ -90,4 +90,6 @@ mod tests {
     use crate::*;
 
     ssz_and_tree_hash_tests!(Attestation<MainnetEthSpec>);
+
+    json_tests!(Attestation<MainnetEthSpec>);
 }
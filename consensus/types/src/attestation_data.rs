@@ -43,4 +43,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(AttestationData);
+
+    json_tests!(AttestationData);
 }
@@ -213,4 +213,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(BeaconBlock<MainnetEthSpec>);
+
+    json_tests!(BeaconBlock<MainnetEthSpec>);
 }
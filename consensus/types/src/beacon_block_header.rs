@@ -67,4 +67,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(BeaconBlockHeader);
+
+    json_tests!(BeaconBlockHeader);
 }
@@ -4,6 +4,8 @@ use crate::test_utils::*;
 
 ssz_and_tree_hash_tests!(FoundationBeaconState);
 
+json_tests!(FoundationBeaconState);
+
 fn test_beacon_proposer_index<T: EthSpec>() {
     let spec = T::default_spec();
     let relative_epoch = RelativeEpoch::Current;
@@ -1,6 +1,7 @@
 use crate::*;
 use int_to_bytes::int_to_bytes4;
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 use std::fs::File;
 use std::path::Path;
 use tree_hash::TreeHash;
@@ -437,6 +438,25 @@ mod tests {
     }
 }
 
+/// A preset value of a `YamlConfig` which differs from that of an `EthSpec`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PresetMismatch {
+    /// The name of the value in the YAML config, e.g. `SLOTS_PER_EPOCH`.
+    pub name: &'static str,
+    pub config_value: u64,
+    pub preset_value: u64,
+}
+
+impl fmt::Display for PresetMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (config: {}, preset: {})",
+            self.name, self.config_value, self.preset_value
+        )
+    }
+}
+
 /// Union of a ChainSpec struct and an EthSpec struct that holds constants used for the configs
 /// from the Ethereum 2 specs repo (https://github.com/ethereum/eth2.0-specs/tree/dev/configs)
 ///
@@ -641,24 +661,138 @@ impl YamlConfig {
             .map_err(|e| format!("Error parsing spec at {}: {:?}", filename.display(), e))
     }
 
+    /// Returns the preset values of this config which differ from those of `T`.
+    ///
+    /// The preset values are type-level constants of the `EthSpec`, so can't be changed at
+    /// runtime. All other values are applied by `apply_to_chain_spec`.
+    pub fn preset_mismatches<T: EthSpec>(&self) -> Vec<PresetMismatch> {
+        let presets = [
+            (
+                "JUSTIFICATION_BITS_LENGTH",
+                u64::from(self.justification_bits_length),
+                T::JustificationBitsLength::to_u64(),
+            ),
+            (
+                "MAX_VALIDATORS_PER_COMMITTEE",
+                u64::from(self.max_validators_per_committee),
+                T::MaxValidatorsPerCommittee::to_u64(),
+            ),
+            (
+                "GENESIS_EPOCH",
+                self.genesis_epoch.as_u64(),
+                T::genesis_epoch().as_u64(),
+            ),
+            (
+                "SLOTS_PER_EPOCH",
+                self.slots_per_epoch,
+                T::slots_per_epoch(),
+            ),
+            (
+                "EPOCHS_PER_ETH1_VOTING_PERIOD",
+                self.epochs_per_eth1_voting_period,
+                T::EpochsPerEth1VotingPeriod::to_u64(),
+            ),
+            (
+                "SLOTS_PER_HISTORICAL_ROOT",
+                self.slots_per_historical_root as u64,
+                T::SlotsPerHistoricalRoot::to_u64(),
+            ),
+            (
+                "EPOCHS_PER_HISTORICAL_VECTOR",
+                self.epochs_per_historical_vector as u64,
+                T::EpochsPerHistoricalVector::to_u64(),
+            ),
+            (
+                "EPOCHS_PER_SLASHINGS_VECTOR",
+                self.epochs_per_slashings_vector as u64,
+                T::EpochsPerSlashingsVector::to_u64(),
+            ),
+            (
+                "HISTORICAL_ROOTS_LIMIT",
+                self.historical_roots_limit,
+                T::HistoricalRootsLimit::to_u64(),
+            ),
+            (
+                "VALIDATOR_REGISTRY_LIMIT",
+                self.validator_registry_limit,
+                T::ValidatorRegistryLimit::to_u64(),
+            ),
+            (
+                "MAX_PROPOSER_SLASHINGS",
+                u64::from(self.max_proposer_slashings),
+                T::MaxProposerSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTER_SLASHINGS",
+                u64::from(self.max_attester_slashings),
+                T::MaxAttesterSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTATIONS",
+                u64::from(self.max_attestations),
+                T::MaxAttestations::to_u64(),
+            ),
+            (
+                "MAX_DEPOSITS",
+                u64::from(self.max_deposits),
+                T::MaxDeposits::to_u64(),
+            ),
+            (
+                "MAX_VOLUNTARY_EXITS",
+                u64::from(self.max_voluntary_exits),
+                T::MaxVoluntaryExits::to_u64(),
+            ),
+        ];
+
+        presets
+            .iter()
+            .filter(|(_, config_value, preset_value)| config_value != preset_value)
+            .map(|&(name, config_value, preset_value)| PresetMismatch {
+                name,
+                config_value,
+                preset_value,
+            })
+            .collect()
+    }
+
+    /// Returns the name of the first of the `mainnet`, `minimal` and `interop` presets which all
+    /// the preset values of this config match, if any.
+    pub fn compatible_preset(&self) -> Option<&'static str> {
+        if self.preset_mismatches::<MainnetEthSpec>().is_empty() {
+            Some(MainnetEthSpec::spec_name())
+        } else if self.preset_mismatches::<MinimalEthSpec>().is_empty() {
+            Some(MinimalEthSpec::spec_name())
+        } else if self.preset_mismatches::<InteropEthSpec>().is_empty() {
+            Some(InteropEthSpec::spec_name())
+        } else {
+            None
+        }
+    }
+
+    /// Applies this config to `chain_spec`, or returns an error listing the preset values which
+    /// differ from those of `T`.
+    pub fn try_apply_to_chain_spec<T: EthSpec>(
+        &self,
+        chain_spec: &ChainSpec,
+    ) -> Result<ChainSpec, String> {
+        self.apply_to_chain_spec::<T>(chain_spec).ok_or_else(|| {
+            let mismatches = self
+                .preset_mismatches::<T>()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            format!(
+                "The config is not compatible with the {} preset. These parameters are fixed by \
+                 the preset and can't be changed at runtime: {}",
+                T::spec_name(),
+                mismatches.join(", ")
+            )
+        })
+    }
+
     pub fn apply_to_chain_spec<T: EthSpec>(&self, chain_spec: &ChainSpec) -> Option<ChainSpec> {
         // Checking for EthSpec constants
-        if self.justification_bits_length != T::JustificationBitsLength::to_u32()
-            || self.max_validators_per_committee != T::MaxValidatorsPerCommittee::to_u32()
-            || self.genesis_epoch != T::genesis_epoch()
-            || self.slots_per_epoch != T::slots_per_epoch()
-            || self.epochs_per_eth1_voting_period != T::EpochsPerEth1VotingPeriod::to_u64()
-            || self.slots_per_historical_root != T::slots_per_historical_root()
-            || self.epochs_per_historical_vector != T::epochs_per_historical_vector()
-            || self.epochs_per_slashings_vector != T::EpochsPerSlashingsVector::to_usize()
-            || self.historical_roots_limit != T::HistoricalRootsLimit::to_u64()
-            || self.validator_registry_limit != T::ValidatorRegistryLimit::to_u64()
-            || self.max_proposer_slashings != T::MaxProposerSlashings::to_u32()
-            || self.max_attester_slashings != T::MaxAttesterSlashings::to_u32()
-            || self.max_attestations != T::MaxAttestations::to_u32()
-            || self.max_deposits != T::MaxDeposits::to_u32()
-            || self.max_voluntary_exits != T::MaxVoluntaryExits::to_u32()
-        {
+        if !self.preset_mismatches::<T>().is_empty() {
             return None;
         }
 
@@ -779,4 +913,26 @@ mod yaml_tests {
             .expect("should have applied spec");
         assert_eq!(new_spec, ChainSpec::minimal());
     }
+
+    #[test]
+    fn preset_mismatches() {
+        let yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&ChainSpec::minimal());
+        assert!(yamlconfig.preset_mismatches::<MinimalEthSpec>().is_empty());
+        assert_eq!(yamlconfig.compatible_preset(), Some("minimal"));
+
+        let mismatches = yamlconfig.preset_mismatches::<MainnetEthSpec>();
+        assert!(mismatches.contains(&PresetMismatch {
+            name: "SLOTS_PER_EPOCH",
+            config_value: 8,
+            preset_value: 32,
+        }));
+        let error = yamlconfig
+            .try_apply_to_chain_spec::<MainnetEthSpec>(&ChainSpec::mainnet())
+            .unwrap_err();
+        assert!(error.contains("SLOTS_PER_EPOCH (config: 8, preset: 32)"));
+
+        let mut custom = yamlconfig;
+        custom.slots_per_epoch = 4;
+        assert_eq!(custom.compatible_preset(), None);
+    }
 }
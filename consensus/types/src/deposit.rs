@@ -23,4 +23,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(Deposit);
+
+    json_tests!(Deposit);
 }
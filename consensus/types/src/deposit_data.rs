@@ -47,4 +47,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(DepositData);
+
+    json_tests!(DepositData);
 }
@@ -25,4 +25,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(DepositMessage);
+
+    json_tests!(DepositMessage);
 }
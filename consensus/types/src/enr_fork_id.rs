@@ -34,4 +34,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(EnrForkId);
+
+    json_tests!(EnrForkId);
 }
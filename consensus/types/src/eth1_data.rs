@@ -35,4 +35,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(Eth1Data);
+
+    json_tests!(Eth1Data);
 }
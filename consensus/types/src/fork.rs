@@ -56,6 +56,8 @@ mod tests {
 
     ssz_and_tree_hash_tests!(Fork);
 
+    json_tests!(Fork);
+
     #[test]
     fn get_fork_version() {
         let previous_version = [1; 4];
@@ -30,4 +30,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(ForkData);
+
+    json_tests!(ForkData);
 }
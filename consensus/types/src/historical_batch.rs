@@ -24,4 +24,6 @@ mod tests {
     pub type FoundationHistoricalBatch = HistoricalBatch<MainnetEthSpec>;
 
     ssz_and_tree_hash_tests!(FoundationHistoricalBatch);
+
+    json_tests!(FoundationHistoricalBatch);
 }
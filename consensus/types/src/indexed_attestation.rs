@@ -13,7 +13,9 @@ use tree_hash_derive::TreeHash;
 ///
 /// Spec v0.12.1
 #[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
-#[derive(Derivative, Debug, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[derive(
+    Derivative, Debug, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
 #[derivative(PartialEq, Eq)] // to satisfy Clippy's lint about `Hash`
 #[serde(bound = "T: EthSpec")]
 pub struct IndexedAttestation<T: EthSpec> {
@@ -128,6 +130,8 @@ mod tests {
 
     ssz_and_tree_hash_tests!(IndexedAttestation<MainnetEthSpec>);
 
+    json_tests!(IndexedAttestation<MainnetEthSpec>);
+
     fn create_indexed_attestation(
         target_epoch: u64,
         source_epoch: u64,
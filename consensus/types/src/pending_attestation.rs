@@ -35,4 +35,6 @@ mod tests {
     use crate::*;
 
     ssz_and_tree_hash_tests!(PendingAttestation<MainnetEthSpec>);
+
+    json_tests!(PendingAttestation<MainnetEthSpec>);
 }
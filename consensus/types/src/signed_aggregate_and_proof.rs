@@ -98,3 +98,12 @@ impl<T: EthSpec> SignedAggregateAndProof<T> {
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainnetEthSpec;
+
+    ssz_and_tree_hash_tests!(SignedAggregateAndProof<MainnetEthSpec>);
+    json_tests!(SignedAggregateAndProof<MainnetEthSpec>);
+}
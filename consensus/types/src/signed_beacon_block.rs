@@ -110,4 +110,6 @@ mod tests {
     use crate::MainnetEthSpec;
 
     ssz_tests!(SignedBeaconBlock<MainnetEthSpec>);
+
+    json_tests!(SignedBeaconBlock<MainnetEthSpec>);
 }
@@ -21,4 +21,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(SignedBeaconBlockHeader);
+
+    json_tests!(SignedBeaconBlockHeader);
 }
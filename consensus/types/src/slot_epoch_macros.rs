@@ -579,6 +579,7 @@ macro_rules! all_tests {
         math_between_tests!($type, $type);
         math_tests!($type);
         ssz_and_tree_hash_tests!($type);
+        json_tests!($type);
 
         mod u64_tests {
             use super::*;
@@ -44,3 +44,35 @@ macro_rules! tree_hash_tests {
         }
     };
 }
+
+/// Checks that randomly generated instances of `$type` survive a round trip through JSON, that the
+/// JSON encoding is canonical (i.e., re-encoding the decoded value produces identical JSON, with
+/// fields in the same order) and that the value decoded from JSON has the same SSZ encoding as the
+/// original.
+#[macro_export]
+macro_rules! json_tests {
+    ($type: ty) => {
+        #[test]
+        pub fn test_json_round_trip() {
+            use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+            use ssz::ssz_encode;
+
+            for seed in 1..=8 {
+                let mut rng = XorShiftRng::from_seed([seed; 16]);
+                let original = <$type>::random_for_test(&mut rng);
+
+                let json = serde_json::to_string(&original).unwrap();
+                let decoded: $type = serde_json::from_str(&json).unwrap();
+
+                assert_eq!(original, decoded, "seed {}", seed);
+                assert_eq!(
+                    serde_json::to_string(&decoded).unwrap(),
+                    json,
+                    "seed {}",
+                    seed
+                );
+                assert_eq!(ssz_encode(&decoded), ssz_encode(&original), "seed {}", seed);
+            }
+        }
+    };
+}
@@ -141,4 +141,6 @@ mod tests {
     }
 
     ssz_and_tree_hash_tests!(Validator);
+
+    json_tests!(Validator);
 }
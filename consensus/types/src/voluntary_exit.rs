@@ -48,4 +48,6 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(VoluntaryExit);
+
+    json_tests!(VoluntaryExit);
 }
@@ -0,0 +1,752 @@
+//! Utilities for inspecting and exporting data from a beacon node database.
+//!
+//! The beacon node must not be running, since the database can only be opened by one process.
+//! The database of a running beacon node can be backed up using its HTTP API.
+
+pub mod replay;
+
+use beacon_chain::era::Era;
+use beacon_chain::DatabaseView;
+use beacon_node::{get_data_dir, ClientConfig};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use environment::Environment;
+use replay::{parse_signature_strategy, replay_block, ReplayResult};
+use slog::info;
+use ssz::{Decode, Encode};
+use state_processing::BlockSignatureStrategy;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use store::{DBColumn, LevelDB, RawEntryIter, StoreConfig, BACKUP_COLD_DB_DIR, BACKUP_HOT_DB_DIR};
+use types::{BeaconState, ChainSpec, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+pub const CMD: &str = "database_manager";
+pub const EXPORT_CHECKPOINT_CMD: &str = "export-checkpoint";
+pub const INSPECT_CMD: &str = "inspect";
+pub const BACKUP_CMD: &str = "backup";
+pub const RESTORE_CMD: &str = "restore";
+pub const REPLAY_BLOCK_CMD: &str = "replay-block";
+pub const EXPORT_ERA_CMD: &str = "export-era";
+
+pub const BLOCK_FILENAME: &str = "block.ssz";
+pub const STATE_FILENAME: &str = "state.ssz";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .visible_aliases(&["db", CMD])
+        .about("Utilities for managing a stopped beacon node's database.")
+        .arg(
+            Arg::with_name("freezer-dir")
+                .long("freezer-dir")
+                .value_name("DIR")
+                .help("Data directory for the freezer database.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slots-per-restore-point")
+                .long("slots-per-restore-point")
+                .value_name("SLOT_COUNT")
+                .help(
+                    "Specifies how often a freezer DB restore point is stored. Must match the \
+                    value used by the beacon node. [default: 2048 (mainnet) or 64 (minimal)]",
+                )
+                .takes_value(true),
+        )
+        .subcommand(
+            SubCommand::with_name(EXPORT_CHECKPOINT_CMD)
+                .about(
+                    "Writes the finalized state at an epoch boundary, along with the latest \
+                    block applied to it, to a directory as SSZ files. The pair may be used to \
+                    bootstrap another node out-of-band.",
+                )
+                .arg(
+                    Arg::with_name("slot")
+                        .long("slot")
+                        .value_name("SLOT")
+                        .help(
+                            "The slot of the state to export. Must be the first slot of a \
+                            finalized epoch.",
+                        )
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("DIR")
+                        .help("The directory in which to write the block and state.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(EXPORT_ERA_CMD)
+                .about(
+                    "Writes the finalized blocks of a range of epochs to a directory as era \
+                    files. Each era file contains the blocks of SLOTS_PER_HISTORICAL_ROOT slots \
+                    and the state at the end of them. A new node may import the files with \
+                    `lighthouse bn --import-era` rather than downloading the blocks.",
+                )
+                .arg(
+                    Arg::with_name("start-epoch")
+                        .long("start-epoch")
+                        .value_name("EPOCH")
+                        .help("The first epoch to export. Defaults to genesis.")
+                        .default_value("0")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("end-epoch")
+                        .long("end-epoch")
+                        .value_name("EPOCH")
+                        .help(
+                            "The last epoch to export. Every era containing an epoch in the range \
+                            is exported, so the era containing this epoch must be finalized. \
+                            Defaults to the last finalized era.",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("DIR")
+                        .help("The directory in which to write the era files.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(INSPECT_CMD)
+                .about(
+                    "Prints the number and size of the entries in each column of the hot and \
+                    freezer databases, along with the largest entries. Reads the whole database, \
+                    so may take some time.",
+                )
+                .arg(
+                    Arg::with_name("largest")
+                        .long("largest")
+                        .value_name("COUNT")
+                        .help("The number of the largest entries to print for each database.")
+                        .default_value("5")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(BACKUP_CMD)
+                .about(
+                    "Copies the hot and freezer databases into a new directory. To back up the \
+                    database of a running beacon node, use its HTTP API instead.",
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("DIR")
+                        .help("The directory in which to write the backup.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(RESTORE_CMD)
+                .about(
+                    "Restores a backup made with the backup command or the HTTP API. Refuses to \
+                    overwrite an existing database.",
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .value_name("DIR")
+                        .help("The directory containing the backup.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(REPLAY_BLOCK_CMD)
+                .about(
+                    "Applies one or more blocks to a pre-state, printing the resulting state \
+                    roots and the time taken by each stage of the state transition. The blocks \
+                    and pre-state are read from the database, or from SSZ files if --pre-state \
+                    is supplied.",
+                )
+                .arg(
+                    Arg::with_name("block-root")
+                        .long("block-root")
+                        .value_name("ROOT")
+                        .help(
+                            "The 0x-prefixed root of the last block to replay from the database. \
+                            The pre-state is the post-state of the parent of the first block.",
+                        )
+                        .takes_value(true)
+                        .required_unless("pre-state")
+                        .conflicts_with("pre-state"),
+                )
+                .arg(
+                    Arg::with_name("count")
+                        .long("count")
+                        .value_name("COUNT")
+                        .help(
+                            "The number of blocks to replay from the database, ending with the \
+                            block at --block-root.",
+                        )
+                        .default_value("1")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("pre-state")
+                        .long("pre-state")
+                        .value_name("FILE")
+                        .help("An SSZ file containing the pre-state.")
+                        .takes_value(true)
+                        .requires("block"),
+                )
+                .arg(
+                    Arg::with_name("block")
+                        .long("block")
+                        .value_name("FILE")
+                        .help(
+                            "An SSZ file containing a signed block to apply to the pre-state. \
+                            May be supplied multiple times, in order of increasing slot.",
+                        )
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .requires("pre-state"),
+                )
+                .arg(
+                    Arg::with_name("signature-verification")
+                        .long("signature-verification")
+                        .value_name("STRATEGY")
+                        .help("How the signatures in each block are verified.")
+                        .possible_values(&["bulk", "individual", "none"])
+                        .default_value("bulk")
+                        .takes_value(true),
+                ),
+        )
+}
+
+/// Run the database manager, returning an error if the operation did not succeed.
+pub fn run<E: EthSpec>(matches: &ArgMatches<'_>, mut env: Environment<E>) -> Result<(), String> {
+    let client_config = parse_client_config::<E>(matches)?;
+    let context = env.core_context();
+    let log = context.log().clone();
+    let spec = context.eth2_config().spec.clone();
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    if let (RESTORE_CMD, Some(matches)) = matches.subcommand() {
+        let input_dir: PathBuf = clap_utils::parse_required(matches, "input")?;
+        let db = DatabaseView::<E, LevelDB<E>, LevelDB<E>>::open(
+            &input_dir.join(BACKUP_HOT_DB_DIR),
+            &input_dir.join(BACKUP_COLD_DB_DIR),
+            client_config.store,
+            spec,
+            log.clone(),
+        )?;
+        let head_block_root = db.head_block_root();
+        let split_slot = db.split_slot();
+        // Close the backup before copying it.
+        drop(db);
+
+        restore(&input_dir, &hot_path, &cold_path)?;
+
+        info!(
+            log,
+            "Restored database";
+            "input_dir" => format!("{:?}", input_dir),
+            "head_block_root" => format!("{:?}", head_block_root),
+            "split_slot" => split_slot,
+        );
+
+        return Ok(());
+    }
+
+    if let (REPLAY_BLOCK_CMD, Some(matches)) = matches.subcommand() {
+        if matches.is_present("pre-state") {
+            let pre_state_path: PathBuf = clap_utils::parse_required(matches, "pre-state")?;
+            let pre_state: BeaconState<E> = load_ssz(&pre_state_path)?;
+            let blocks = matches
+                .values_of("block")
+                .into_iter()
+                .flatten()
+                .map(|path| load_ssz(&PathBuf::from(path)))
+                .collect::<Result<Vec<SignedBeaconBlock<E>>, _>>()?;
+
+            return replay_blocks(pre_state, &blocks, signature_strategy(matches)?, &spec);
+        }
+    }
+
+    if !hot_path.exists() {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    let db = DatabaseView::open(
+        &hot_path,
+        &cold_path,
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    match matches.subcommand() {
+        (EXPORT_CHECKPOINT_CMD, Some(matches)) => {
+            let slot = Slot::new(clap_utils::parse_required(matches, "slot")?);
+            let output_dir: PathBuf = clap_utils::parse_required(matches, "output")?;
+            let (block_root, state_root) = export_checkpoint(&db, slot, &output_dir)?;
+
+            info!(
+                log,
+                "Exported checkpoint";
+                "output_dir" => format!("{:?}", output_dir),
+                "state_root" => format!("{:?}", state_root),
+                "block_root" => format!("{:?}", block_root),
+                "slot" => slot,
+            );
+        }
+        (EXPORT_ERA_CMD, Some(matches)) => {
+            let start_epoch = Epoch::new(clap_utils::parse_required(matches, "start-epoch")?);
+            let end_epoch = clap_utils::parse_optional(matches, "end-epoch")?.map(Epoch::new);
+            let output_dir: PathBuf = clap_utils::parse_required(matches, "output")?;
+
+            for era in era_range(&db, start_epoch, end_epoch)? {
+                let (path, num_blocks) = export_era(&db, era, &output_dir)?;
+
+                info!(
+                    log,
+                    "Exported era";
+                    "path" => format!("{:?}", path),
+                    "blocks" => num_blocks,
+                    "era" => era,
+                );
+            }
+        }
+        (INSPECT_CMD, Some(matches)) => {
+            let num_largest = clap_utils::parse_required(matches, "largest")?;
+
+            println!("Split slot: {}", db.split_slot());
+            println!();
+            print_stats(
+                "Hot database",
+                &DatabaseStats::from_entries(db.iter_hot_raw(), num_largest),
+            );
+            println!();
+            print_stats(
+                "Freezer database",
+                &DatabaseStats::from_entries(db.iter_cold_raw(), num_largest),
+            );
+        }
+        (BACKUP_CMD, Some(matches)) => {
+            let output_dir: PathBuf = clap_utils::parse_required(matches, "output")?;
+            db.backup(&output_dir)?;
+        }
+        (REPLAY_BLOCK_CMD, Some(matches)) => {
+            let block_root = clap_utils::parse_ssz_required(matches, "block-root")?;
+            let count = clap_utils::parse_required(matches, "count")?;
+            let (pre_state, blocks) = load_blocks_for_replay(&db, block_root, count)?;
+
+            replay_blocks(pre_state, &blocks, signature_strategy(matches)?, &spec)?;
+        }
+        (unknown, _) => {
+            return Err(format!(
+                "{} is not a valid {} command. See --help.",
+                unknown, CMD
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Determine the database locations in the same way as the beacon node.
+fn parse_client_config<E: EthSpec>(matches: &ArgMatches<'_>) -> Result<ClientConfig, String> {
+    let mut client_config = ClientConfig::default();
+
+    client_config.data_dir = get_data_dir(matches);
+
+    if let Some(freezer_dir) = matches.value_of("freezer-dir") {
+        client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
+    }
+
+    client_config.store = StoreConfig::default();
+    client_config.store.slots_per_restore_point =
+        clap_utils::parse_optional(matches, "slots-per-restore-point")?.unwrap_or_else(|| {
+            std::cmp::min(
+                E::slots_per_historical_root() as u64,
+                store::config::DEFAULT_SLOTS_PER_RESTORE_POINT,
+            )
+        });
+
+    Ok(client_config)
+}
+
+/// Write the finalized state at `slot` and the latest block applied to it to `output_dir`,
+/// returning the roots of the block and state.
+///
+/// Existing files are never overwritten.
+pub fn export_checkpoint<E: EthSpec>(
+    db: &DatabaseView<E, LevelDB<E>, LevelDB<E>>,
+    slot: Slot,
+    output_dir: &Path,
+) -> Result<(Hash256, Hash256), String> {
+    if slot % E::slots_per_epoch() != 0 {
+        return Err(format!("Slot {} is not the first slot of an epoch", slot));
+    }
+
+    let state = db.get_finalized_state_by_slot(slot)?.ok_or_else(|| {
+        format!(
+            "Slot {} is not finalized, the latest finalized slot is {}",
+            slot,
+            db.split_slot()
+        )
+    })?;
+    let state_root = state.canonical_root();
+
+    // The state root of the latest block header is only filled in at the next slot.
+    let mut latest_block_header = state.latest_block_header.clone();
+    if latest_block_header.state_root == Hash256::zero() {
+        latest_block_header.state_root = state_root;
+    }
+    let block_root = latest_block_header.canonical_root();
+
+    let block = db
+        .get_block(&block_root)?
+        .ok_or_else(|| format!("Block {:?} is missing from the database", block_root))?;
+
+    let block_path = output_dir.join(BLOCK_FILENAME);
+    let state_path = output_dir.join(STATE_FILENAME);
+    for path in &[&block_path, &state_path] {
+        if path.exists() {
+            return Err(format!("Refusing to overwrite {:?}", path));
+        }
+    }
+
+    fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", output_dir, e))?;
+    fs::write(&block_path, block.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", block_path, e))?;
+    fs::write(&state_path, state.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", state_path, e))?;
+
+    Ok((block_root, state_root))
+}
+
+/// Returns the numbers of the eras containing the epochs from `start_epoch` to `end_epoch`
+/// (inclusive), or to the last finalized era if `end_epoch` is `None`.
+///
+/// Era 0, which contains only the genesis state, is never included.
+pub fn era_range<E: EthSpec>(
+    db: &DatabaseView<E, LevelDB<E>, LevelDB<E>>,
+    start_epoch: Epoch,
+    end_epoch: Option<Epoch>,
+) -> Result<std::ops::RangeInclusive<u64>, String> {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let era_of = |epoch: Epoch| epoch.start_slot(E::slots_per_epoch()).as_u64() / slots_per_era + 1;
+    let last_finalized_era = db.split_slot().as_u64() / slots_per_era;
+
+    let start_era = era_of(start_epoch);
+    let end_era = end_epoch.map_or(last_finalized_era, era_of);
+
+    if end_era > last_finalized_era {
+        return Err(format!(
+            "Era {} is not finalized, the latest finalized era is {}",
+            end_era, last_finalized_era
+        ));
+    }
+    if start_era > end_era {
+        return Err(format!(
+            "No eras to export, start era {} is after end era {}",
+            start_era, end_era
+        ));
+    }
+
+    Ok(start_era..=end_era)
+}
+
+/// Write era `number` to a new file in `output_dir`, returning its path and the number of blocks
+/// it contains.
+pub fn export_era<E: EthSpec>(
+    db: &DatabaseView<E, LevelDB<E>, LevelDB<E>>,
+    number: u64,
+    output_dir: &Path,
+) -> Result<(PathBuf, usize), String> {
+    let state_slot = Slot::new(number * E::slots_per_historical_root() as u64);
+    let state = db.get_finalized_state_by_slot(state_slot)?.ok_or_else(|| {
+        format!(
+            "Era {} is not finalized, the latest finalized slot is {}",
+            number,
+            db.split_slot()
+        )
+    })?;
+
+    let mut blocks: Vec<SignedBeaconBlock<E>> = vec![];
+    let mut prev_root = None;
+    for slot in Era::<E>::start_slot(number).as_u64()..state_slot.as_u64() {
+        let block_root = *state
+            .get_block_root(Slot::new(slot))
+            .map_err(|e| format!("Unable to get block root at slot {}: {:?}", slot, e))?;
+
+        // Skipped slots repeat the root of the previous block.
+        if prev_root == Some(block_root) {
+            continue;
+        }
+        prev_root = Some(block_root);
+
+        let block = db
+            .get_block(&block_root)?
+            .ok_or_else(|| format!("Block {:?} is missing from the database", block_root))?;
+
+        // The first slots of the era may be skipped, repeating a block from the previous era.
+        if block.slot() == slot {
+            blocks.push(block);
+        }
+    }
+
+    let era = Era { blocks, state };
+    let num_blocks = era.blocks.len();
+    let path = era.write(output_dir)?;
+
+    Ok((path, num_blocks))
+}
+
+/// Load the `count` blocks ending with the block at `block_root`, along with the post-state of the
+/// parent of the first block.
+pub fn load_blocks_for_replay<E: EthSpec>(
+    db: &DatabaseView<E, LevelDB<E>, LevelDB<E>>,
+    block_root: Hash256,
+    count: usize,
+) -> Result<(BeaconState<E>, Vec<SignedBeaconBlock<E>>), String> {
+    if count == 0 {
+        return Err("At least one block must be replayed".to_string());
+    }
+
+    let get_block = |block_root: &Hash256| {
+        db.get_block(block_root)?
+            .ok_or_else(|| format!("Block {:?} is missing from the database", block_root))
+    };
+
+    let mut blocks = vec![get_block(&block_root)?];
+    while blocks.len() < count {
+        let parent_root = blocks[blocks.len() - 1].parent_root();
+        blocks.push(get_block(&parent_root)?);
+    }
+    blocks.reverse();
+
+    let parent = get_block(&blocks[0].parent_root())?;
+    let pre_state = db
+        .get_state(&parent.state_root(), Some(parent.slot()))?
+        .ok_or_else(|| {
+            format!(
+                "State {:?} at slot {} is missing from the database",
+                parent.state_root(),
+                parent.slot()
+            )
+        })?;
+
+    Ok((pre_state, blocks))
+}
+
+/// Apply `blocks` to `pre_state` in order, printing the resulting state root and stage timings for
+/// each block.
+///
+/// Returns an error if a block is invalid or its state root does not match the computed state
+/// root.
+pub fn replay_blocks<E: EthSpec>(
+    mut state: BeaconState<E>,
+    blocks: &[SignedBeaconBlock<E>],
+    signature_strategy: BlockSignatureStrategy,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    for block in blocks {
+        let result = replay_block(&mut state, block, signature_strategy, spec)
+            .map_err(|e| format!("Unable to replay block at slot {}: {}", block.slot(), e))?;
+
+        print_replay_result(&result);
+
+        if result.state_root != block.state_root() {
+            return Err(format!(
+                "State root mismatch at slot {}: block has {:?}, computed {:?}",
+                block.slot(),
+                block.state_root(),
+                result.state_root
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn signature_strategy(matches: &ArgMatches<'_>) -> Result<BlockSignatureStrategy, String> {
+    let strategy = matches
+        .value_of("signature-verification")
+        .ok_or_else(|| "signature-verification not specified".to_string())?;
+    parse_signature_strategy(strategy)
+}
+
+fn load_ssz<T: Decode>(path: &Path) -> Result<T, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+    T::from_ssz_bytes(&bytes).map_err(|e| format!("Unable to decode {:?}: {:?}", path, e))
+}
+
+/// Copy the hot and cold DBs of the backup in `input_dir` to `hot_path` and `cold_path`.
+///
+/// Refuses to overwrite an existing database.
+pub fn restore(input_dir: &Path, hot_path: &Path, cold_path: &Path) -> Result<(), String> {
+    for path in &[hot_path, cold_path] {
+        if path.exists() {
+            return Err(format!("Refusing to overwrite {:?}", path));
+        }
+    }
+
+    copy_dir(&input_dir.join(BACKUP_HOT_DB_DIR), hot_path)?;
+    copy_dir(&input_dir.join(BACKUP_COLD_DB_DIR), cold_path)
+}
+
+/// Copy every file in `from` to `to`, which is created if it does not exist.
+///
+/// LevelDB does not use sub-directories, so they are not copied.
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Unable to create {:?}: {:?}", to, e))?;
+
+    for entry in fs::read_dir(from).map_err(|e| format!("Unable to read {:?}: {:?}", from, e))? {
+        let path = entry
+            .map_err(|e| format!("Unable to read {:?}: {:?}", from, e))?
+            .path();
+
+        if path.is_file() {
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| format!("Invalid file path {:?}", path))?;
+            fs::copy(&path, to.join(file_name))
+                .map_err(|e| format!("Unable to copy {:?}: {:?}", path, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The number and size of the entries in a single database column.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ColumnStats {
+    pub entries: usize,
+    /// The total size of the keys, excluding the column prefix.
+    pub key_bytes: usize,
+    pub value_bytes: usize,
+}
+
+/// A single database entry, identified by its column and key.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EntrySize {
+    pub value_bytes: usize,
+    pub column: String,
+    pub key: Vec<u8>,
+}
+
+/// Statistics about the entries in a database.
+#[derive(Debug, Default)]
+pub struct DatabaseStats {
+    /// Statistics for each column, by name. Keys which do not belong to a known column are
+    /// counted under `UNKNOWN_COLUMN`.
+    pub columns: BTreeMap<String, ColumnStats>,
+    /// The largest entries, largest first.
+    pub largest: Vec<EntrySize>,
+}
+
+/// The column name for keys which do not belong to a known column.
+pub const UNKNOWN_COLUMN: &str = "Unknown";
+
+impl DatabaseStats {
+    /// Computes the statistics for all `entries`, retaining the `num_largest` largest entries.
+    pub fn from_entries(entries: RawEntryIter, num_largest: usize) -> Self {
+        let mut columns = BTreeMap::<String, ColumnStats>::new();
+        // A min-heap, so the smallest of the largest entries can be evicted.
+        let mut largest = BinaryHeap::new();
+
+        for (raw_key, value) in entries {
+            let (column, key) = match DBColumn::from_key(&raw_key) {
+                Some((column, key)) => (format!("{:?}", column), key),
+                None => (UNKNOWN_COLUMN.to_string(), &raw_key[..]),
+            };
+
+            let stats = columns.entry(column.clone()).or_default();
+            stats.entries += 1;
+            stats.key_bytes += key.len();
+            stats.value_bytes += value.len();
+
+            largest.push(Reverse(EntrySize {
+                value_bytes: value.len(),
+                column,
+                key: key.to_vec(),
+            }));
+            if largest.len() > num_largest {
+                largest.pop();
+            }
+        }
+
+        let mut largest = largest
+            .into_iter()
+            .map(|Reverse(entry)| entry)
+            .collect::<Vec<_>>();
+        largest.sort_by(|a, b| b.cmp(a));
+
+        Self { columns, largest }
+    }
+}
+
+fn print_replay_result(result: &ReplayResult) {
+    println!("Slot {}:", result.slot);
+    println!("  Block root: {:?}", result.block_root);
+    println!("  State root: {:?}", result.state_root);
+
+    let mut total = Duration::default();
+    for (stage, duration) in &result.timings {
+        println!("  {:<28} {:>16}", stage, format!("{:?}", duration));
+        total += *duration;
+    }
+    println!("  {:<28} {:>16}", "Total", format!("{:?}", total));
+}
+
+fn print_stats(name: &str, stats: &DatabaseStats) {
+    println!("{}:", name);
+    println!(
+        "  {:<24} {:>12} {:>16} {:>16}",
+        "Column", "Entries", "Key bytes", "Value bytes"
+    );
+
+    let mut total = ColumnStats::default();
+    for (column, column_stats) in &stats.columns {
+        println!(
+            "  {:<24} {:>12} {:>16} {:>16}",
+            column, column_stats.entries, column_stats.key_bytes, column_stats.value_bytes
+        );
+        total.entries += column_stats.entries;
+        total.key_bytes += column_stats.key_bytes;
+        total.value_bytes += column_stats.value_bytes;
+    }
+    println!(
+        "  {:<24} {:>12} {:>16} {:>16}",
+        "Total", total.entries, total.key_bytes, total.value_bytes
+    );
+
+    if !stats.largest.is_empty() {
+        println!("  Largest entries:");
+        for entry in &stats.largest {
+            println!(
+                "    {:<24} 0x{} {} bytes",
+                entry.column,
+                entry
+                    .key
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>(),
+                entry.value_bytes
+            );
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Replays blocks upon a pre-state, timing each stage of the state transition.
+//!
+//! The stages mirror those of `per_block_processing`, so that a slow or failing operation can be
+//! identified when debugging a consensus failure offline.
+
+use state_processing::per_block_processing::{
+    process_attestations, process_attester_slashings, process_block_header, process_deposits,
+    process_eth1_data, process_exits, process_proposer_slashings, process_randao,
+    signature_sets::get_pubkey_from_state, verify_block_signature,
+};
+use state_processing::{
+    per_slot_processing, BlockSignatureStrategy, BlockSignatureVerifier, SlotProcessingError,
+    VerifySignatures,
+};
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// The outcome of replaying a single block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayResult {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    /// The state root computed by the replay.
+    pub state_root: Hash256,
+    /// The duration of each stage of the state transition, in the order they were run.
+    pub timings: Vec<(&'static str, Duration)>,
+}
+
+/// Parses a signature verification strategy, as accepted on the command line.
+pub fn parse_signature_strategy(strategy: &str) -> Result<BlockSignatureStrategy, String> {
+    match strategy {
+        "bulk" => Ok(BlockSignatureStrategy::VerifyBulk),
+        "individual" => Ok(BlockSignatureStrategy::VerifyIndividual),
+        "none" => Ok(BlockSignatureStrategy::NoVerification),
+        other => Err(format!(
+            "Unknown signature verification strategy: {}",
+            other
+        )),
+    }
+}
+
+/// Advances `state` to the slot of `block`, then applies `block` to it.
+///
+/// Returns an error naming the stage which failed if the block is invalid. The state root of the
+/// block is not checked, this is left to the caller.
+pub fn replay_block<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    block: &SignedBeaconBlock<E>,
+    signature_strategy: BlockSignatureStrategy,
+    spec: &ChainSpec,
+) -> Result<ReplayResult, String> {
+    let mut timings = vec![];
+    let block_root = block.canonical_root();
+    let verify_signatures = if signature_strategy == BlockSignatureStrategy::VerifyIndividual {
+        VerifySignatures::True
+    } else {
+        VerifySignatures::False
+    };
+
+    if block.slot() < state.slot {
+        return Err(format!(
+            "Block at slot {} cannot be applied to a state at slot {}",
+            block.slot(),
+            state.slot
+        ));
+    }
+
+    time_stage(&mut timings, "slot_processing", || {
+        while state.slot < block.slot() {
+            per_slot_processing(state, None, spec)?;
+        }
+        Ok::<_, SlotProcessingError>(())
+    })?;
+    time_stage(&mut timings, "build_caches", || {
+        state.build_all_caches(spec)
+    })?;
+
+    if signature_strategy == BlockSignatureStrategy::VerifyBulk {
+        time_stage(&mut timings, "bulk_signature_verification", || {
+            BlockSignatureVerifier::verify_entire_block(
+                state,
+                |i| get_pubkey_from_state(state, i),
+                block,
+                Some(block_root),
+                spec,
+            )
+        })?;
+    }
+
+    time_stage(&mut timings, "block_header", || {
+        process_block_header(state, &block.message, spec)
+    })?;
+    if verify_signatures.is_true() {
+        time_stage(&mut timings, "block_signature", || {
+            verify_block_signature(state, block, Some(block_root), spec)
+        })?;
+    }
+    time_stage(&mut timings, "randao", || {
+        process_randao(state, &block.message, verify_signatures, spec)
+    })?;
+    time_stage(&mut timings, "eth1_data", || {
+        process_eth1_data(state, &block.message.body.eth1_data)
+    })?;
+    time_stage(&mut timings, "proposer_slashings", || {
+        process_proposer_slashings(
+            state,
+            &block.message.body.proposer_slashings,
+            verify_signatures,
+            spec,
+        )
+    })?;
+    time_stage(&mut timings, "attester_slashings", || {
+        process_attester_slashings(
+            state,
+            &block.message.body.attester_slashings,
+            verify_signatures,
+            spec,
+        )
+    })?;
+    time_stage(&mut timings, "attestations", || {
+        process_attestations(
+            state,
+            &block.message.body.attestations,
+            verify_signatures,
+            spec,
+        )
+    })?;
+    time_stage(&mut timings, "deposits", || {
+        process_deposits(state, &block.message.body.deposits, spec)
+    })?;
+    time_stage(&mut timings, "voluntary_exits", || {
+        process_exits(
+            state,
+            &block.message.body.voluntary_exits,
+            verify_signatures,
+            spec,
+        )
+    })?;
+
+    let state_root = time_stage(&mut timings, "state_root", || {
+        state.update_tree_hash_cache()
+    })?;
+
+    Ok(ReplayResult {
+        slot: block.slot(),
+        block_root,
+        state_root,
+        timings,
+    })
+}
+
+/// Runs `f`, recording its duration under `name`.
+fn time_stage<T, U: Debug>(
+    timings: &mut Vec<(&'static str, Duration)>,
+    name: &'static str,
+    f: impl FnOnce() -> Result<T, U>,
+) -> Result<T, String> {
+    let start = Instant::now();
+    let result = f().map_err(|e| format!("Failed during {}: {:?}", name, e));
+    timings.push((name, start.elapsed()));
+    result
+}
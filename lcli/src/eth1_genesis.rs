@@ -31,13 +31,7 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches<'_>) -> Res
         .yaml_config
         .as_ref()
         .ok_or_else(|| "The testnet directory must contain a spec config".to_string())?
-        .apply_to_chain_spec::<T>(&env.core_context().eth2_config.spec)
-        .ok_or_else(|| {
-            format!(
-                "The loaded config is not compatible with the {} spec",
-                &env.core_context().eth2_config.spec_constants
-            )
-        })?;
+        .try_apply_to_chain_spec::<T>(&env.core_context().eth2_config.spec)?;
 
     let mut config = Eth1Config::default();
     config.endpoint = endpoint.to_string();
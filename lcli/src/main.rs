@@ -12,6 +12,7 @@ mod parse_hex;
 mod refund_deposit_contract;
 mod skip_slots;
 mod transition_blocks;
+mod transition_state;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 use environment::EnvironmentBuilder;
@@ -108,6 +109,45 @@ fn main() {
                         .help("Path to output a SSZ file."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("transition-state")
+                .about(
+                    "Advances a state across some number of slots and, optionally, upgrades it \
+                    to a fork. Useful for comparing post-states with other clients.",
+                )
+                .arg(
+                    Arg::with_name("pre")
+                        .long("pre")
+                        .value_name("BEACON_STATE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a SSZ file of the pre-state."),
+                )
+                .arg(
+                    Arg::with_name("slots")
+                        .long("slots")
+                        .value_name("SLOT_COUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Number of slots to process."),
+                )
+                .arg(
+                    Arg::with_name("fork")
+                        .long("fork")
+                        .value_name("FORK")
+                        .takes_value(true)
+                        .possible_values(transition_state::FORKS)
+                        .help("The fork to upgrade the state to, after slot processing."),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("SSZ_FILE")
+                        .takes_value(true)
+                        .default_value("./output.ssz")
+                        .help("Path to output a SSZ file."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("transition-blocks")
                 .about("Performs a state transition given a pre-state and block")
@@ -558,6 +598,8 @@ fn run<T: EthSpec>(
         }
         ("transition-blocks", Some(matches)) => run_transition_blocks::<T>(matches)
             .map_err(|e| format!("Failed to transition blocks: {}", e)),
+        ("transition-state", Some(matches)) => transition_state::run::<T>(matches)
+            .map_err(|e| format!("Failed to transition state: {}", e)),
         ("skip-slots", Some(matches)) => {
             skip_slots::run::<T>(matches).map_err(|e| format!("Failed to skip slots: {}", e))
         }
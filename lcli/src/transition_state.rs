@@ -0,0 +1,75 @@
+use crate::transition_blocks::load_from_ssz;
+use clap::ArgMatches;
+use ssz::Encode;
+use state_processing::per_slot_processing;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::PathBuf;
+use types::{BeaconState, EthSpec};
+
+/// The forks to which a state may be upgraded. Only phase 0 is currently implemented, and every
+/// state is already a phase 0 state, so no upgrade is ever performed.
+pub const FORKS: &[&str] = &["phase0"];
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let pre_state_path = matches
+        .value_of("pre")
+        .ok_or_else(|| "No pre-state file supplied".to_string())?
+        .parse::<PathBuf>()
+        .map_err(|e| format!("Failed to parse pre-state path: {}", e))?;
+
+    let slots = matches
+        .value_of("slots")
+        .ok_or_else(|| "No slots supplied".to_string())?
+        .parse::<u64>()
+        .map_err(|e| format!("Failed to parse slots: {}", e))?;
+
+    let fork = matches.value_of("fork");
+    if let Some(fork) = fork {
+        if !FORKS.contains(&fork) {
+            return Err(format!("Unsupported fork: {}", fork));
+        }
+    }
+
+    let output_path = matches
+        .value_of("output")
+        .ok_or_else(|| "No output file supplied".to_string())?
+        .parse::<PathBuf>()
+        .map_err(|e| format!("Failed to parse output path: {}", e))?;
+
+    info!("Using {} spec", T::spec_name());
+    info!("Pre-state path: {:?}", pre_state_path);
+    info!("Slots: {}", slots);
+
+    let mut state: BeaconState<T> = load_from_ssz(pre_state_path)?;
+
+    let spec = &T::default_spec();
+
+    state
+        .build_all_caches(spec)
+        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+
+    let target_slot = state.slot + slots;
+    while state.slot < target_slot {
+        per_slot_processing(&mut state, None, spec)
+            .map_err(|e| format!("Failed to advance to slot {}: {:?}", state.slot + 1, e))?;
+    }
+
+    if let Some(fork) = fork {
+        info!("State is already at the {} fork, no upgrade required", fork);
+    }
+
+    let state_root = state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("Unable to compute state root: {:?}", e))?;
+    info!("Post-state slot: {}, root: {:?}", state.slot, state_root);
+
+    let mut output_file =
+        File::create(output_path).map_err(|e| format!("Unable to create output file: {:?}", e))?;
+
+    output_file
+        .write_all(&state.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write to output file: {:?}", e))?;
+
+    Ok(())
+}
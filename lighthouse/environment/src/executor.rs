@@ -1,5 +1,6 @@
-use crate::metrics;
+use crate::{metrics, ConfigReload, ReloadRequest};
 use futures::channel::mpsc::Sender;
+use futures::channel::oneshot;
 use futures::prelude::*;
 use slog::{debug, trace};
 use tokio::runtime::Handle;
@@ -16,6 +17,8 @@ pub struct TaskExecutor {
     ///
     /// The task must provide a reason for shutting down.
     pub(crate) signal_tx: Sender<&'static str>,
+    /// Sender for requests to reload the configuration, if it may be reloaded.
+    pub(crate) reload_tx: Option<Sender<ReloadRequest>>,
 
     pub(crate) log: slog::Logger,
 }
@@ -35,10 +38,30 @@ impl TaskExecutor {
             handle,
             exit,
             signal_tx,
+            reload_tx: None,
             log,
         }
     }
 
+    /// Requests that the configuration is reloaded, as upon `SIGHUP`, returning which flags were
+    /// applied and which require a restart.
+    pub async fn reload_config(&self) -> Result<ConfigReload, String> {
+        let mut reload_tx = self
+            .reload_tx
+            .clone()
+            .ok_or("Reloading the configuration is not supported")?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+
+        reload_tx
+            .send(reply_tx)
+            .await
+            .map_err(|e| format!("Unable to request a reload: {}", e))?;
+
+        reply_rx
+            .await
+            .map_err(|_| "The reload request was dropped".to_string())?
+    }
+
     /// Spawn a future on the tokio runtime wrapped in an `exit_future::Exit`. The task is canceled
     /// when the corresponding exit_future `Signal` is fired/dropped.
     ///
@@ -13,28 +13,59 @@ use futures::channel::{
     mpsc::{channel, Receiver, Sender},
     oneshot,
 };
-use futures::{future, StreamExt};
+use futures::StreamExt;
 
 pub use executor::TaskExecutor;
-use slog::{info, o, Drain, Level, Logger};
+use logging::{LevelFilter, LogLevel};
+use slog::{info, o, warn, Drain, Level, Logger};
 use sloggers::{null::NullLoggerBuilder, Build};
-use std::cell::RefCell;
 use std::ffi::OsStr;
 use std::fs::{rename as FsRename, OpenOptions};
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
 use types::{EthSpec, InteropEthSpec, MainnetEthSpec, MinimalEthSpec};
 mod executor;
 mod metrics;
 
 pub const ETH2_CONFIG_FILENAME: &str = "eth2-spec.toml";
 const LOG_CHANNEL_SIZE: usize = 2048;
+/// The maximum number of requests to reload the configuration which may be queued.
+const RELOAD_CHANNEL_SIZE: usize = 4;
+
+/// A request to reload the configuration, answered with its outcome.
+pub(crate) type ReloadRequest = oneshot::Sender<Result<ConfigReload, String>>;
+
+/// The outcome of reloading the configuration of running services.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigReload {
+    /// The flags whose new values were applied.
+    pub applied: Vec<String>,
+    /// The flags whose values changed, but which were not applied because they only take effect
+    /// on restart.
+    pub requires_restart: Vec<String>,
+}
+
+/// Returns the level of the `--debug-level` flag value `debug_level`.
+pub fn parse_debug_level(debug_level: &str) -> Result<Level, String> {
+    match debug_level {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown debug-level: {}", unknown)),
+    }
+}
 
 /// Builds an `Environment`.
 pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Runtime>,
     log: Option<Logger>,
+    log_level: Option<LogLevel>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     testnet: Option<Eth2TestnetConfig<E>>,
@@ -46,6 +77,7 @@ impl EnvironmentBuilder<MinimalEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_level: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             testnet: None,
@@ -59,6 +91,7 @@ impl EnvironmentBuilder<MainnetEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_level: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             testnet: None,
@@ -72,6 +105,7 @@ impl EnvironmentBuilder<InteropEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_level: None,
             eth_spec_instance: InteropEthSpec,
             eth2_config: Eth2Config::interop(),
             testnet: None,
@@ -146,17 +180,11 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let log_level = LogLevel::new(parse_debug_level(debug_level)?);
+        let drain = LevelFilter::new(drain, log_level.clone());
 
         self.log = Some(Logger::root(drain.fuse(), o!()));
+        self.log_level = Some(log_level);
         Ok(self)
     }
 
@@ -213,15 +241,8 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let log_level = LogLevel::new(parse_debug_level(debug_level)?);
+        let drain = LevelFilter::new(drain, log_level.clone());
 
         let log = Logger::root(drain.fuse(), o!());
         info!(
@@ -231,6 +252,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         );
 
         self.log = Some(log);
+        self.log_level = Some(log_level);
 
         Ok(self)
     }
@@ -241,16 +263,18 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         eth2_testnet_config: Eth2TestnetConfig<E>,
     ) -> Result<Self, String> {
         // Create a new chain spec from the default configuration.
-        self.eth2_config.spec = eth2_testnet_config
+        let yaml_config = eth2_testnet_config
             .yaml_config
             .as_ref()
-            .ok_or_else(|| "The testnet directory must contain a spec config".to_string())?
-            .apply_to_chain_spec::<E>(&self.eth2_config.spec)
-            .ok_or_else(|| {
-                format!(
-                    "The loaded config is not compatible with the {} spec",
-                    &self.eth2_config.spec_constants
-                )
+            .ok_or_else(|| "The testnet directory must contain a spec config".to_string())?;
+        self.eth2_config.spec = yaml_config
+            .try_apply_to_chain_spec::<E>(&self.eth2_config.spec)
+            .map_err(|e| match yaml_config.compatible_preset() {
+                Some(preset) => format!(
+                    "{}. The config matches the {} preset, try --spec {}",
+                    e, preset, preset
+                ),
+                None => e,
             })?;
 
         self.testnet = Some(eth2_testnet_config);
@@ -274,17 +298,21 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
     pub fn build(self) -> Result<Environment<E>, String> {
         let (signal, exit) = exit_future::signal();
         let (signal_tx, signal_rx) = channel(1);
+        let (reload_tx, reload_rx) = channel(RELOAD_CHANNEL_SIZE);
         Ok(Environment {
             runtime: self
                 .runtime
                 .ok_or_else(|| "Cannot build environment without runtime".to_string())?,
             signal_tx,
             signal_rx: Some(signal_rx),
+            reload_tx,
+            reload_rx: Some(reload_rx),
             signal: Some(signal),
             exit,
             log: self
                 .log
                 .ok_or_else(|| "Cannot build environment without log".to_string())?,
+            log_level: self.log_level,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             testnet: self.testnet,
@@ -312,6 +340,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             executor: TaskExecutor {
                 handle: self.executor.handle.clone(),
                 signal_tx: self.executor.signal_tx.clone(),
+                reload_tx: self.executor.reload_tx.clone(),
                 exit: self.executor.exit.clone(),
                 log: self.executor.log.new(o!("service" => service_name)),
             },
@@ -339,9 +368,15 @@ pub struct Environment<E: EthSpec> {
     signal_rx: Option<Receiver<&'static str>>,
     /// Sender to request shutting down.
     signal_tx: Sender<&'static str>,
+    /// Sender to request reloading the configuration.
+    reload_tx: Sender<ReloadRequest>,
+    /// Receiver side of the requests to reload the configuration.
+    reload_rx: Option<Receiver<ReloadRequest>>,
     signal: Option<exit_future::Signal>,
     exit: exit_future::Exit,
     log: Logger,
+    /// The level of `log`, if it may be changed whilst running.
+    log_level: Option<LogLevel>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub testnet: Option<Eth2TestnetConfig<E>>,
@@ -362,6 +397,7 @@ impl<E: EthSpec> Environment<E> {
             executor: TaskExecutor {
                 exit: self.exit.clone(),
                 signal_tx: self.signal_tx.clone(),
+                reload_tx: Some(self.reload_tx.clone()),
                 handle: self.runtime().handle().clone(),
                 log: self.log.clone(),
             },
@@ -376,6 +412,7 @@ impl<E: EthSpec> Environment<E> {
             executor: TaskExecutor {
                 exit: self.exit.clone(),
                 signal_tx: self.signal_tx.clone(),
+                reload_tx: Some(self.reload_tx.clone()),
                 handle: self.runtime().handle().clone(),
                 log: self.log.new(o!("service" => service_name)),
             },
@@ -384,41 +421,54 @@ impl<E: EthSpec> Environment<E> {
         }
     }
 
+    /// Returns a handle to the level of the logger, if it may be changed whilst running.
+    pub fn log_level(&self) -> Option<LogLevel> {
+        self.log_level.clone()
+    }
+
     /// Block the current thread until a shutdown signal is received.
     ///
-    /// This can be either the user Ctrl-C'ing or a task requesting to shutdown.
-    pub fn block_until_shutdown_requested(&mut self) -> Result<(), String> {
-        // future of a task requesting to shutdown
-        let mut rx = self
+    /// This can be either the user Ctrl-C'ing or a task requesting to shutdown. Until then,
+    /// `reload` is called to reload the configuration upon each `SIGHUP` and each request made
+    /// with `TaskExecutor::reload_config`.
+    pub fn block_until_shutdown_requested(
+        &mut self,
+        mut reload: impl FnMut() -> Result<ConfigReload, String>,
+    ) -> Result<(), String> {
+        // requests from tasks to shutdown or to reload the configuration
+        let mut signal_rx = self
             .signal_rx
             .take()
             .ok_or("Inner shutdown already received")?;
-        let inner_shutdown =
-            async move { rx.next().await.ok_or("Internal shutdown channel exhausted") };
-        futures::pin_mut!(inner_shutdown);
-
-        // setup for handling a Ctrl-C
-        let (ctrlc_send, ctrlc_oneshot) = oneshot::channel();
-        let ctrlc_send_c = RefCell::new(Some(ctrlc_send));
-        ctrlc::set_handler(move || {
-            if let Some(ctrlc_send) = ctrlc_send_c.try_borrow_mut().unwrap().take() {
-                ctrlc_send.send(()).expect("Error sending ctrl-c message");
-            }
-        })
-        .map_err(|e| format!("Could not set ctrlc handler: {:?}", e))?;
+        let mut reload_rx = self
+            .reload_rx
+            .take()
+            .ok_or("Reload requests already received")?;
+        let log = self.log.clone();
 
         // Block this thread until a shutdown signal is received.
-        match self
-            .runtime()
-            .block_on(future::select(inner_shutdown, ctrlc_oneshot))
-        {
-            future::Either::Left((Ok(reason), _)) => {
-                info!(self.log, "Internal shutdown received"; "reason" => reason);
-                Ok(())
+        self.runtime().block_on(async move {
+            let shutdown_signal = shutdown_signal();
+            futures::pin_mut!(shutdown_signal);
+            let mut hangup_signal = HangupSignal::new()?;
+
+            loop {
+                tokio::select! {
+                    reason = signal_rx.next() => {
+                        let reason = reason.ok_or("Internal shutdown channel exhausted")?;
+                        info!(log, "Internal shutdown received"; "reason" => reason);
+                        return Ok(());
+                    }
+                    result = &mut shutdown_signal => return result,
+                    _ = hangup_signal.recv() => {
+                        reload_and_log(&mut reload, &log, "SIGHUP");
+                    }
+                    Some(reply) = reload_rx.next() => {
+                        let _ = reply.send(reload_and_log(&mut reload, &log, "request"));
+                    }
+                }
             }
-            future::Either::Left((Err(e), _)) => Err(e.into()),
-            future::Either::Right((x, _)) => x.map_err(|e| format!("Ctrlc oneshot failed: {}", e)),
-        }
+        })
     }
 
     /// Shutdown the `tokio` runtime when all tasks are idle.
@@ -443,6 +493,103 @@ impl<E: EthSpec> Environment<E> {
     }
 }
 
+/// Calls `reload`, logging which flags were applied and which require a restart.
+fn reload_and_log(
+    reload: &mut impl FnMut() -> Result<ConfigReload, String>,
+    log: &Logger,
+    trigger: &str,
+) -> Result<ConfigReload, String> {
+    info!(log, "Reloading configuration"; "trigger" => trigger);
+
+    let result = reload();
+    match &result {
+        Ok(reload) => {
+            info!(
+                log,
+                "Reloaded configuration";
+                "applied" => reload.applied.join(", ")
+            );
+            if !reload.requires_restart.is_empty() {
+                warn!(
+                    log,
+                    "Configuration changes require a restart";
+                    "flags" => reload.requires_restart.join(", ")
+                );
+            }
+        }
+        Err(e) => warn!(log, "Unable to reload configuration"; "error" => e),
+    }
+
+    result
+}
+
+/// Resolves once the process is asked to shut down with `SIGINT` or `SIGTERM`.
+///
+/// `SIGHUP` is left to `HangupSignal`, since `ctrlc` would treat it as a request to shut down.
+#[cfg(unix)]
+async fn shutdown_signal() -> Result<(), String> {
+    let mut interrupt = signal(SignalKind::interrupt())
+        .map_err(|e| format!("Could not set SIGINT handler: {:?}", e))?;
+    let mut terminate = signal(SignalKind::terminate())
+        .map_err(|e| format!("Could not set SIGTERM handler: {:?}", e))?;
+
+    tokio::select! {
+        _ = interrupt.recv() => (),
+        _ = terminate.recv() => (),
+    }
+
+    Ok(())
+}
+
+/// Resolves once the user Ctrl-C's.
+#[cfg(not(unix))]
+async fn shutdown_signal() -> Result<(), String> {
+    let (ctrlc_send, ctrlc_oneshot) = oneshot::channel();
+    let ctrlc_send_c = std::cell::RefCell::new(Some(ctrlc_send));
+    ctrlc::set_handler(move || {
+        if let Some(ctrlc_send) = ctrlc_send_c.try_borrow_mut().unwrap().take() {
+            ctrlc_send.send(()).expect("Error sending ctrl-c message");
+        }
+    })
+    .map_err(|e| format!("Could not set ctrlc handler: {:?}", e))?;
+
+    ctrlc_oneshot
+        .await
+        .map_err(|e| format!("Ctrlc oneshot failed: {}", e))
+}
+
+/// Receives the `SIGHUP` signals which request that the configuration is reloaded. Never receives
+/// anything on platforms without `SIGHUP`.
+struct HangupSignal {
+    #[cfg(unix)]
+    signal: Signal,
+}
+
+impl HangupSignal {
+    #[cfg(unix)]
+    fn new() -> Result<Self, String> {
+        Ok(Self {
+            signal: signal(SignalKind::hangup())
+                .map_err(|e| format!("Could not set SIGHUP handler: {:?}", e))?,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> Result<Self, String> {
+        Ok(Self {})
+    }
+
+    #[cfg(unix)]
+    async fn recv(&mut self) {
+        self.signal.recv().await;
+    }
+
+    #[cfg(not(unix))]
+    async fn recv(&mut self) {
+        futures::future::pending::<()>().await
+    }
+}
+
 pub fn null_logger() -> Result<Logger, String> {
     let log_builder = NullLoggerBuilder;
     log_builder
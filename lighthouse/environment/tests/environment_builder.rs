@@ -1,8 +1,10 @@
 #![cfg(test)]
 
-use environment::EnvironmentBuilder;
+use environment::{ConfigReload, EnvironmentBuilder};
 use eth2_testnet_config::Eth2TestnetConfig;
+use futures::SinkExt;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use types::{Epoch, MainnetEthSpec, YamlConfig};
 
 fn builder() -> EnvironmentBuilder<MainnetEthSpec> {
@@ -42,3 +44,46 @@ mod setup_eth2_config {
         }
     }
 }
+
+mod reload_config {
+    use super::*;
+
+    #[test]
+    fn requests_are_served_until_shutdown() {
+        let mut environment = builder().build().expect("should build environment");
+        let executor = environment.core_context().executor;
+
+        let reload = ConfigReload {
+            applied: vec!["graffiti".to_string()],
+            requires_restart: vec!["http-port".to_string()],
+        };
+
+        let results = Arc::new(Mutex::new(vec![]));
+        let task_results = results.clone();
+        executor.clone().spawn(
+            async move {
+                for _ in 0..2 {
+                    let result = executor.reload_config().await;
+                    task_results.lock().unwrap().push(result);
+                }
+
+                let _ = executor.shutdown_sender().send("reloaded").await;
+            },
+            "reload_config_test",
+        );
+
+        let mut reloads = 0;
+        environment
+            .block_until_shutdown_requested(|| {
+                reloads += 1;
+                Ok(reload.clone())
+            })
+            .expect("should shut down");
+
+        assert_eq!(reloads, 2);
+        assert_eq!(
+            *results.lock().unwrap(),
+            vec![Ok(reload.clone()), Ok(reload)]
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Support for `--config-file`, a TOML or YAML file of flags named as on the command line.
+//!
+//! Top-level keys are flags of the `lighthouse` command itself (e.g., `debug-level` or `testnet`),
+//! whilst the flags of the beacon node and validator client are given in the `beacon_node` and
+//! `validator_client` tables. Flags given on the command line take precedence over the file.
+
+use clap::{App, AppSettings, Arg, ArgMatches};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const CMD: &str = "config";
+pub const DUMP_CMD: &str = "dump";
+pub const CONFIG_FILE_FLAG: &str = "config-file";
+pub const FORMAT_FLAG: &str = "format";
+pub const COMMAND_FLAG: &str = "command";
+
+/// The subcommands whose flags may be given in a table of the config file.
+pub const SUBCOMMANDS: &[&str] = &["beacon_node", "validator_client"];
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about("Inspects the configuration given by --config-file.")
+        .subcommand(
+            App::new(DUMP_CMD)
+                .about(
+                    "Prints the effective configuration of a command, merging --config-file \
+                     with its command line, in the format of a config file.",
+                )
+                .setting(AppSettings::TrailingVarArg)
+                .arg(
+                    Arg::with_name(FORMAT_FLAG)
+                        .long(FORMAT_FLAG)
+                        .value_name("FORMAT")
+                        .help("The format to print the configuration in.")
+                        .possible_values(&["toml", "yaml"])
+                        .default_value("toml")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_FLAG)
+                        .value_name("COMMAND")
+                        .help(
+                            "The beacon_node or validator_client command line to merge with \
+                             the config file, e.g., `bn --http --http-port 5053`. If omitted, \
+                             the tables of the config file are printed as they are.",
+                        )
+                        .multiple(true)
+                        .allow_hyphen_values(true),
+                ),
+        )
+}
+
+/// Run the `config` subcommand, using `parse` to parse a `lighthouse` command line and merge it
+/// with the `--config-file`.
+pub fn run<'a>(
+    matches: &ArgMatches,
+    config_matches: &ArgMatches,
+    parse: impl FnOnce(Vec<OsString>) -> Result<ArgMatches<'a>, String>,
+) -> Result<(), String> {
+    match config_matches.subcommand() {
+        (DUMP_CMD, Some(dump_matches)) => dump(matches, dump_matches, parse),
+        _ => Err("No config subcommand supplied. See --help.".to_string()),
+    }
+}
+
+/// Prints the flags of the top-level command and the `COMMAND` of `lighthouse config dump`,
+/// including those from the `--config-file`.
+fn dump<'a>(
+    matches: &ArgMatches,
+    dump_matches: &ArgMatches,
+    parse: impl FnOnce(Vec<OsString>) -> Result<ArgMatches<'a>, String>,
+) -> Result<(), String> {
+    let format: Format = clap_utils::parse_required(dump_matches, FORMAT_FLAG)?;
+    let path = config_file_path(matches);
+
+    let mut args = vec![OsString::from("lighthouse")];
+    if let Some(path) = &path {
+        args.push(format!("--{}={}", CONFIG_FILE_FLAG, path.display()).into());
+    }
+    for (name, value) in &ConfigFile::from_matches(matches).global {
+        value.push_args(name, &mut args)?;
+    }
+    args.extend(
+        dump_matches
+            .values_of_os(COMMAND_FLAG)
+            .into_iter()
+            .flatten()
+            .map(OsString::from),
+    );
+
+    let mut effective = ConfigFile::from_matches(&parse(args)?);
+
+    // Keep the tables of the subcommands which weren't given, so the output is a complete config
+    // file.
+    if let Some(path) = path {
+        for (name, flags) in ConfigFile::from_file(&path)?.subcommands {
+            effective.subcommands.entry(name).or_insert(flags);
+        }
+    }
+
+    print!("{}", effective.serialize(format)?);
+    Ok(())
+}
+
+/// Returns the `--config-file` given to `lighthouse` or its subcommand.
+pub fn config_file_path(matches: &ArgMatches) -> Option<PathBuf> {
+    matches
+        .value_of(CONFIG_FILE_FLAG)
+        .or_else(|| {
+            matches
+                .subcommand()
+                .1
+                .and_then(|sub_matches| sub_matches.value_of(CONFIG_FILE_FLAG))
+        })
+        .map(PathBuf::from)
+}
+
+/// The format of a config file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    /// Returns the format of the config file at `path`, given by its extension.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(Format::Toml),
+            Some("yaml") | Some("yml") => Ok(Format::Yaml),
+            _ => Err(format!(
+                "Unknown format of config file {:?}, expected a .toml, .yaml or .yml file",
+                path
+            )),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "toml" => Ok(Format::Toml),
+            "yaml" => Ok(Format::Yaml),
+            other => Err(format!("Unknown config file format: {}", other)),
+        }
+    }
+}
+
+/// The value of a flag in a config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FlagValue {
+    /// A flag which doesn't take a value, given if `true`.
+    Switch(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    /// A flag which is given once for each value.
+    List(Vec<FlagValue>),
+}
+
+impl FlagValue {
+    /// Appends the command line arguments giving this value for the flag `name` to `args`.
+    fn push_args(&self, name: &str, args: &mut Vec<OsString>) -> Result<(), String> {
+        match self {
+            FlagValue::Switch(true) => args.push(format!("--{}", name).into()),
+            FlagValue::Switch(false) => (),
+            FlagValue::Integer(value) => args.push(format!("--{}={}", name, value).into()),
+            FlagValue::Float(value) => args.push(format!("--{}={}", name, value).into()),
+            FlagValue::String(value) => args.push(format!("--{}={}", name, value).into()),
+            FlagValue::List(values) => {
+                for value in values {
+                    match value {
+                        FlagValue::Switch(_) | FlagValue::List(_) => {
+                            return Err(format!(
+                                "Invalid value for {}: lists may only contain strings and numbers",
+                                name
+                            ))
+                        }
+                        value => value.push_args(name, args)?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A flag of a config file, and the subcommand it belongs to. The subcommand is `None` for the
+/// flags of the `lighthouse` command.
+pub type FlagName = (Option<String>, String);
+
+/// A top-level entry of a config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Flag(FlagValue),
+    Table(BTreeMap<String, FlagValue>),
+}
+
+/// The flags given in a config file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConfigFile {
+    /// Flags of the `lighthouse` command.
+    pub global: BTreeMap<String, FlagValue>,
+    /// Flags of each of the `SUBCOMMANDS`, by subcommand name.
+    pub subcommands: BTreeMap<String, BTreeMap<String, FlagValue>>,
+}
+
+impl ConfigFile {
+    /// Reads the config file at `path`, in the format given by its extension.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read config file {:?}: {}", path, e))?;
+
+        Self::parse(&contents, Format::from_path(path)?)
+            .map_err(|e| format!("Unable to parse config file {:?}: {}", path, e))
+    }
+
+    /// Parses the `contents` of a config file.
+    pub fn parse(contents: &str, format: Format) -> Result<Self, String> {
+        let entries: BTreeMap<String, Entry> = match format {
+            Format::Toml => toml::from_str(contents).map_err(|e| e.to_string())?,
+            Format::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string())?,
+        };
+
+        let mut config_file = Self::default();
+
+        for (name, entry) in entries {
+            match entry {
+                Entry::Flag(_) if name == CONFIG_FILE_FLAG => {
+                    return Err(format!("{} cannot be given in a config file", name))
+                }
+                Entry::Flag(value) => {
+                    config_file.global.insert(name, value);
+                }
+                Entry::Table(flags) if SUBCOMMANDS.contains(&name.as_str()) => {
+                    config_file.subcommands.insert(name, flags);
+                }
+                Entry::Table(_) => {
+                    return Err(format!(
+                        "Unknown table {}, expected one of: {}",
+                        name,
+                        SUBCOMMANDS.join(", ")
+                    ))
+                }
+            }
+        }
+
+        Ok(config_file)
+    }
+
+    /// Returns the config file in the given `format`.
+    pub fn serialize(&self, format: Format) -> Result<String, String> {
+        let entries = self
+            .global
+            .iter()
+            .map(|(name, value)| (name.clone(), Entry::Flag(value.clone())))
+            .chain(
+                self.subcommands
+                    .iter()
+                    .map(|(name, flags)| (name.clone(), Entry::Table(flags.clone()))),
+            )
+            .collect::<BTreeMap<_, _>>();
+
+        match format {
+            // Converting to a `toml::Value` first places the tables after the other values, as
+            // TOML requires.
+            Format::Toml => toml::Value::try_from(&entries)
+                .and_then(|value| toml::to_string(&value))
+                .map_err(|e| format!("Unable to serialize config as TOML: {}", e)),
+            Format::Yaml => serde_yaml::to_string(&entries)
+                .map_err(|e| format!("Unable to serialize config as YAML: {}", e)),
+        }
+    }
+
+    /// Returns the command line `args` with the flags of the config file which aren't given in
+    /// `matches`, the result of parsing `args`, added.
+    ///
+    /// Top-level flags are inserted before the subcommand, and the flags of the subcommand given
+    /// in `args` are appended.
+    pub fn merge_into_args(
+        &self,
+        args: Vec<OsString>,
+        matches: &ArgMatches,
+    ) -> Result<Vec<OsString>, String> {
+        let (subcommand, sub_matches) = matches.subcommand();
+
+        let mut global_args = vec![];
+        for (name, value) in &self.global {
+            // Global flags may also be given after the subcommand.
+            let given = matches.occurrences_of(name) > 0
+                || sub_matches.map_or(false, |sub_matches| sub_matches.occurrences_of(name) > 0);
+
+            if !given {
+                value.push_args(name, &mut global_args)?;
+            }
+        }
+
+        let mut sub_args = vec![];
+        if let (Some(sub_matches), Some(flags)) = (sub_matches, self.subcommands.get(subcommand)) {
+            for (name, value) in flags {
+                if sub_matches.occurrences_of(name) == 0 {
+                    value.push_args(name, &mut sub_args)?;
+                }
+            }
+        }
+
+        let mut args = args.into_iter();
+        Ok(args
+            .next()
+            .into_iter()
+            .chain(global_args)
+            .chain(args)
+            .chain(sub_args)
+            .collect())
+    }
+
+    /// Returns the flags given in `matches`, and in those of its subcommand if it is one of the
+    /// `SUBCOMMANDS`.
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        let global = given_flags(matches, |name| name != CONFIG_FILE_FLAG);
+
+        let mut subcommands = BTreeMap::new();
+        if let (name, Some(sub_matches)) = matches.subcommand() {
+            if SUBCOMMANDS.contains(&name) {
+                // Global flags are propagated to the subcommand, skip those given at the top
+                // level.
+                let flags = given_flags(sub_matches, |name| {
+                    name != CONFIG_FILE_FLAG && !global.contains_key(name)
+                });
+                subcommands.insert(name.to_string(), flags);
+            }
+        }
+
+        Self {
+            global,
+            subcommands,
+        }
+    }
+
+    /// Returns the flags whose values differ in `other`, including those given in only one of the
+    /// two.
+    pub fn changed_flags(&self, other: &Self) -> Vec<FlagName> {
+        let empty = BTreeMap::new();
+
+        let mut changed = changed_keys(&self.global, &other.global)
+            .map(|name| (None, name))
+            .collect::<Vec<_>>();
+
+        let subcommands = self
+            .subcommands
+            .keys()
+            .chain(other.subcommands.keys())
+            .collect::<BTreeSet<_>>();
+
+        for subcommand in subcommands {
+            let flags = self.subcommands.get(subcommand).unwrap_or(&empty);
+            let other_flags = other.subcommands.get(subcommand).unwrap_or(&empty);
+            changed.extend(
+                changed_keys(flags, other_flags).map(|name| (Some(subcommand.clone()), name)),
+            );
+        }
+
+        changed
+    }
+
+    /// Sets the flag `name` of `subcommand` to its value in `other`, removing it if it isn't given
+    /// there.
+    pub fn copy_flag(&mut self, other: &Self, (subcommand, name): &FlagName) {
+        let (flags, other_flags) = match subcommand {
+            None => (&mut self.global, Some(&other.global)),
+            Some(subcommand) => (
+                self.subcommands.entry(subcommand.clone()).or_default(),
+                other.subcommands.get(subcommand),
+            ),
+        };
+
+        match other_flags.and_then(|other_flags| other_flags.get(name)) {
+            Some(value) => flags.insert(name.clone(), value.clone()),
+            None => flags.remove(name),
+        };
+    }
+}
+
+/// Returns the keys whose values differ between `a` and `b`.
+fn changed_keys<'a>(
+    a: &'a BTreeMap<String, FlagValue>,
+    b: &'a BTreeMap<String, FlagValue>,
+) -> impl Iterator<Item = String> + 'a {
+    a.keys()
+        .chain(b.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(move |name| a.get(*name) != b.get(*name))
+        .cloned()
+}
+
+/// Returns the flags accepted by `filter` which are given in `matches`, excluding defaults.
+fn given_flags(matches: &ArgMatches, filter: impl Fn(&str) -> bool) -> BTreeMap<String, FlagValue> {
+    // `clap` has no public method to list the matched arguments, so its `args` map is used.
+    matches
+        .args
+        .keys()
+        .filter(|name| filter(name) && matches.occurrences_of(name) > 0)
+        .map(|name| {
+            let value = match matches.values_of(name) {
+                None => FlagValue::Switch(true),
+                Some(values) => {
+                    let mut values = values
+                        .map(|value| FlagValue::String(value.to_string()))
+                        .collect::<Vec<_>>();
+
+                    if values.len() == 1 {
+                        values.remove(0)
+                    } else {
+                        FlagValue::List(values)
+                    }
+                }
+            };
+
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+debug-level = "debug"
+
+[beacon_node]
+http = true
+http-port = 5053
+boot-nodes = ["enr:a", "enr:b"]
+"#;
+
+    const YAML: &str = r#"
+debug-level: debug
+beacon_node:
+  http: true
+  http-port: 5053
+  boot-nodes:
+    - "enr:a"
+    - "enr:b"
+"#;
+
+    fn app<'a, 'b>() -> App<'a, 'b> {
+        App::new("lighthouse")
+            .arg(
+                Arg::with_name("debug-level")
+                    .long("debug-level")
+                    .takes_value(true)
+                    .global(true)
+                    .default_value("info"),
+            )
+            .arg(
+                Arg::with_name(CONFIG_FILE_FLAG)
+                    .long(CONFIG_FILE_FLAG)
+                    .takes_value(true)
+                    .global(true),
+            )
+            .subcommand(
+                App::new("beacon_node")
+                    .arg(Arg::with_name("http").long("http"))
+                    .arg(
+                        Arg::with_name("http-port")
+                            .long("http-port")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        Arg::with_name("boot-nodes")
+                            .long("boot-nodes")
+                            .takes_value(true)
+                            .multiple(true)
+                            .number_of_values(1),
+                    ),
+            )
+    }
+
+    fn merged_matches<'a>(config_file: &ConfigFile, args: &[&str]) -> ArgMatches<'a> {
+        let args = args.iter().map(OsString::from).collect::<Vec<_>>();
+        let matches = app().get_matches_from(args.clone());
+        let args = config_file
+            .merge_into_args(args, &matches)
+            .expect("should merge args");
+
+        app()
+            .get_matches_from_safe(args)
+            .expect("merged args should be valid")
+    }
+
+    #[test]
+    fn toml_and_yaml_match() {
+        let toml = ConfigFile::parse(TOML, Format::Toml).expect("should parse TOML");
+        let yaml = ConfigFile::parse(YAML, Format::Yaml).expect("should parse YAML");
+
+        assert_eq!(toml, yaml);
+        assert_eq!(
+            toml.global.get("debug-level"),
+            Some(&FlagValue::String("debug".to_string()))
+        );
+        assert_eq!(
+            toml.subcommands["beacon_node"].get("http-port"),
+            Some(&FlagValue::Integer(5053))
+        );
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert!(ConfigFile::parse("[account_manager]\nforce = true", Format::Toml).is_err());
+        assert!(ConfigFile::parse("config-file = \"a.toml\"", Format::Toml).is_err());
+        assert!(ConfigFile::parse("a: {b: {c: 1}}", Format::Yaml).is_err());
+    }
+
+    #[test]
+    fn command_line_takes_precedence() {
+        let config_file = ConfigFile::parse(TOML, Format::Toml).unwrap();
+        let matches = merged_matches(
+            &config_file,
+            &["lighthouse", "beacon_node", "--http-port", "6000"],
+        );
+        let bn_matches = matches.subcommand_matches("beacon_node").unwrap();
+
+        assert_eq!(matches.value_of("debug-level"), Some("debug"));
+        assert!(bn_matches.is_present("http"));
+        assert_eq!(bn_matches.value_of("http-port"), Some("6000"));
+        assert_eq!(
+            bn_matches
+                .values_of("boot-nodes")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec!["enr:a", "enr:b"]
+        );
+
+        let matches = merged_matches(
+            &config_file,
+            &["lighthouse", "--debug-level", "warn", "beacon_node"],
+        );
+        assert_eq!(matches.value_of("debug-level"), Some("warn"));
+    }
+
+    #[test]
+    fn only_given_subcommand_is_merged() {
+        let config_file = ConfigFile::parse(TOML, Format::Toml).unwrap();
+        let matches = merged_matches(&config_file, &["lighthouse"]);
+
+        assert_eq!(matches.value_of("debug-level"), Some("debug"));
+        assert!(matches.subcommand_matches("beacon_node").is_none());
+    }
+
+    #[test]
+    fn changed_flags() {
+        let old = ConfigFile::parse(TOML, Format::Toml).unwrap();
+        let new = ConfigFile::parse(
+            "debug-level = \"debug\"\n[beacon_node]\nhttp = true\nhttp-port = 6000\ngraffiti = \"a\"",
+            Format::Toml,
+        )
+        .unwrap();
+
+        let changed = old.changed_flags(&new);
+        let beacon_node = |name: &str| (Some("beacon_node".to_string()), name.to_string());
+        assert_eq!(
+            changed,
+            vec![
+                beacon_node("boot-nodes"),
+                beacon_node("graffiti"),
+                beacon_node("http-port")
+            ]
+        );
+
+        let mut running = old.clone();
+        for flag in &changed {
+            running.copy_flag(&new, flag);
+        }
+        assert_eq!(running, new);
+        assert!(running.changed_flags(&new).is_empty());
+
+        let mut without_global = old.clone();
+        without_global.global.clear();
+        assert_eq!(
+            old.changed_flags(&without_global),
+            vec![(None, "debug-level".to_string())]
+        );
+    }
+
+    #[test]
+    fn dump_round_trip() {
+        let config_file = ConfigFile::parse(TOML, Format::Toml).unwrap();
+        let matches = merged_matches(
+            &config_file,
+            &["lighthouse", "beacon_node", "--http-port", "6000"],
+        );
+        let effective = ConfigFile::from_matches(&matches);
+
+        assert_eq!(
+            effective.subcommands["beacon_node"].get("http-port"),
+            Some(&FlagValue::String("6000".to_string()))
+        );
+
+        for format in &[Format::Toml, Format::Yaml] {
+            let serialized = effective.serialize(*format).expect("should serialize");
+            assert_eq!(
+                ConfigFile::parse(&serialized, *format).expect("should parse dump"),
+                effective
+            );
+        }
+    }
+}
@@ -1,13 +1,19 @@
+mod config_file;
+
 use beacon_node::ProductionBeaconNode;
 use clap::{App, Arg, ArgMatches};
+use config_file::{ConfigFile, FlagName, CONFIG_FILE_FLAG};
 use env_logger::{Builder, Env};
-use environment::EnvironmentBuilder;
-use eth2_testnet_config::{Eth2TestnetConfig, DEFAULT_HARDCODED_TESTNET};
+use environment::{parse_debug_level, ConfigReload, EnvironmentBuilder};
+use eth2_testnet_config::{Eth2TestnetConfig, DEFAULT_HARDCODED_TESTNET, YAML_CONFIG_FILE};
 use lighthouse_version::VERSION;
+use logging::LogLevel;
 use slog::{crit, info, warn};
+use std::env;
+use std::ffi::OsString;
 use std::path::PathBuf;
 use std::process::exit;
-use types::EthSpec;
+use types::{EthSpec, MainnetEthSpec, YamlConfig};
 use validator_client::ProductionValidatorClient;
 
 pub const DEFAULT_DATA_DIR: &str = ".lighthouse";
@@ -23,29 +29,25 @@ fn bls_library_name() -> &'static str {
     }
 }
 
-fn main() {
-    // Parse the CLI parameters.
-    let matches = App::new("Lighthouse")
-        .version(VERSION.replace("Lighthouse/", "").as_str())
+fn cli_app<'a>(version: &'a str, long_version: &'a str) -> App<'a, 'a> {
+    App::new("Lighthouse")
+        .version(version)
         .author("Sigma Prime <contact@sigmaprime.io>")
         .setting(clap::AppSettings::ColoredHelp)
         .about(
             "Ethereum 2.0 client by Sigma Prime. Provides a full-featured beacon \
              node, a validator client and utilities for managing validator accounts.",
         )
-        .long_version(
-            format!(
-                "{}\n\
-                 BLS Library: {}",
-                 VERSION.replace("Lighthouse/", ""), bls_library_name()
-            ).as_str()
-        )
+        .long_version(long_version)
         .arg(
             Arg::with_name("spec")
                 .short("s")
                 .long("spec")
                 .value_name("TITLE")
-                .help("Specifies the default eth2 spec type.")
+                .help(
+                    "Specifies the default eth2 spec type. If not provided, the preset of the \
+                     --testnet-dir or --chain-config spec config is used, if any.",
+                )
                 .takes_value(true)
                 .possible_values(&["mainnet", "minimal", "interop"])
                 .global(true)
@@ -118,11 +120,62 @@ fn main() {
                 .global(true)
 
         )
+        .arg(
+            Arg::with_name(CONFIG_FILE_FLAG)
+                .long(CONFIG_FILE_FLAG)
+                .value_name("FILE")
+                .help(
+                    "Path to a TOML or YAML file of flags, named as on the command line. \
+                     Top-level keys are flags of this command, whilst the flags of the \
+                     beacon_node and validator_client are given in tables of those names. \
+                     Flags given on the command line take precedence over the file. Some \
+                     flags may be changed whilst running by editing the file and sending \
+                     SIGHUP.",
+                )
+                .takes_value(true)
+                .global(true),
+        )
         .subcommand(beacon_node::cli_app())
         .subcommand(boot_node::cli_app())
         .subcommand(validator_client::cli_app())
         .subcommand(account_manager::cli_app())
-        .get_matches();
+        .subcommand(database_manager::cli_app())
+        .subcommand(tools::cli_app())
+        .subcommand(config_file::cli_app())
+}
+
+/// Parses the command line `args`, adding the flags of the `--config-file`, if any, which aren't
+/// given on the command line.
+fn parse_args<'a>(
+    app: impl Fn() -> App<'a, 'a>,
+    args: Vec<OsString>,
+) -> Result<ArgMatches<'a>, String> {
+    let matches = app()
+        .get_matches_from_safe(&args)
+        .unwrap_or_else(|e| e.exit());
+
+    let path = match config_file::config_file_path(&matches) {
+        Some(path) => path,
+        None => return Ok(matches),
+    };
+
+    let args = ConfigFile::from_file(&path)?.merge_into_args(args, &matches)?;
+
+    app()
+        .get_matches_from_safe(args)
+        .map_err(|e| format!("Invalid config file {:?}: {}", path, e.message))
+}
+
+fn main() {
+    let version = VERSION.replace("Lighthouse/", "");
+    let long_version = format!("{}\nBLS Library: {}", version, bls_library_name());
+    let parse = |args: Vec<OsString>| parse_args(|| cli_app(&version, &long_version), args);
+
+    // Parse the CLI parameters.
+    let matches = parse(env::args_os().collect()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1)
+    });
 
     // boot node subcommand circumvents the environment
     if let Some(bootnode_matches) = matches.subcommand_matches("boot_node") {
@@ -135,6 +188,17 @@ fn main() {
         return;
     }
 
+    // The config subcommand also circumvents the environment.
+    if let Some(config_matches) = matches.subcommand_matches(config_file::CMD) {
+        match config_file::run(&matches, config_matches, parse) {
+            Ok(()) => exit(0),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1)
+            }
+        }
+    }
+
     // Debugging output for libp2p and external crates.
     if matches.is_present("env_log") {
         Builder::from_env(Env::default()).init();
@@ -142,11 +206,24 @@ fn main() {
 
     macro_rules! run_with_spec {
         ($env_builder: expr) => {
-            run($env_builder, &matches)
+            run($env_builder, &matches, parse)
         };
     }
 
-    let result = match matches.value_of("spec") {
+    // Select the preset matching the spec config when `--spec` isn't given explicitly.
+    let spec = if matches.occurrences_of("spec") == 0 {
+        match config_preset(&matches) {
+            Ok(preset) => preset.or_else(|| matches.value_of("spec")),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1)
+            }
+        }
+    } else {
+        matches.value_of("spec")
+    };
+
+    let result = match spec {
         Some("minimal") => run_with_spec!(EnvironmentBuilder::minimal()),
         Some("mainnet") => run_with_spec!(EnvironmentBuilder::mainnet()),
         Some("interop") => run_with_spec!(EnvironmentBuilder::interop()),
@@ -170,9 +247,47 @@ fn main() {
     }
 }
 
-fn run<E: EthSpec>(
+/// Returns the name of the preset matching the spec config given by `--testnet-dir` or the
+/// beacon node's `--chain-config`, if any.
+///
+/// Returns an error if the spec config doesn't match any of the presets, listing the preset
+/// values which can't be changed at runtime.
+fn config_preset(matches: &ArgMatches) -> Result<Option<&'static str>, String> {
+    let path = if let Some(path) = matches
+        .subcommand_matches("beacon_node")
+        .and_then(|m| m.value_of("chain-config"))
+    {
+        PathBuf::from(path)
+    } else if let Some(dir) = matches.value_of("testnet-dir") {
+        PathBuf::from(dir).join(YAML_CONFIG_FILE)
+    } else {
+        return Ok(None);
+    };
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let yaml_config = YamlConfig::from_file(&path)?;
+    yaml_config.compatible_preset().map(Some).ok_or_else(|| {
+        let mismatches = yaml_config
+            .preset_mismatches::<MainnetEthSpec>()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        format!(
+            "The spec config {} doesn't match any preset. These parameters can't be changed \
+             at runtime and differ from the mainnet preset: {}",
+            path.display(),
+            mismatches.join(", ")
+        )
+    })
+}
+
+fn run<'a, E: EthSpec>(
     environment_builder: EnvironmentBuilder<E>,
     matches: &ArgMatches,
+    parse: impl Fn(Vec<OsString>) -> Result<ArgMatches<'a>, String>,
 ) -> Result<(), String> {
     if std::mem::size_of::<usize>() != 8 {
         return Err(format!(
@@ -244,6 +359,22 @@ fn run<E: EthSpec>(
         return Ok(());
     };
 
+    if let Some(sub_matches) = matches.subcommand_matches("database_manager") {
+        eprintln!("Running database manager for {} testnet", testnet_name);
+        database_manager::run(sub_matches, environment)?;
+
+        // Exit as soon as database manager returns control.
+        return Ok(());
+    };
+
+    if let Some(sub_matches) = matches.subcommand_matches(tools::CMD) {
+        eprintln!("Running tools for {} testnet", testnet_name);
+        tools::run(sub_matches, environment)?;
+
+        // Exit as soon as the tools return control.
+        return Ok(());
+    };
+
     warn!(
         log,
         "Ethereum 2.0 is pre-release. This software is experimental."
@@ -303,8 +434,21 @@ fn run<E: EthSpec>(
         return Err("No subcommand supplied.".into());
     }
 
+    // The flags in effect, updated as changes are applied by reloading the configuration.
+    let mut running_flags = ConfigFile::from_matches(matches);
+    let log_level = environment.log_level();
+    let reload = || {
+        reload_config(
+            &mut running_flags,
+            &parse,
+            log_level.as_ref(),
+            beacon_node.as_ref(),
+            validator_client.as_ref(),
+        )
+    };
+
     // Block this thread until we get a ctrl-c or a task sends a shutdown signal.
-    environment.block_until_shutdown_requested()?;
+    environment.block_until_shutdown_requested(reload)?;
     info!(log, "Shutting down..");
 
     environment.fire_signal();
@@ -315,3 +459,67 @@ fn run<E: EthSpec>(
     environment.shutdown_on_idle();
     Ok(())
 }
+
+/// Re-parses the command line and `--config-file`, applying the changes to the flags of the
+/// running `beacon_node` or `validator_client` which can be changed without a restart.
+///
+/// `running_flags` are the flags in effect, which are updated with the changes applied. Other
+/// changes are reported as requiring a restart, and are reported again by later reloads.
+fn reload_config<'a, E: EthSpec>(
+    running_flags: &mut ConfigFile,
+    parse: impl Fn(Vec<OsString>) -> Result<ArgMatches<'a>, String>,
+    log_level: Option<&LogLevel>,
+    beacon_node: Option<&ProductionBeaconNode<E>>,
+    validator_client: Option<&ProductionValidatorClient<E>>,
+) -> Result<ConfigReload, String> {
+    let matches = parse(env::args_os().collect())?;
+    let reloaded_flags = ConfigFile::from_matches(&matches);
+
+    let (hot, cold): (Vec<FlagName>, Vec<FlagName>) = running_flags
+        .changed_flags(&reloaded_flags)
+        .into_iter()
+        .partition(|(subcommand, name)| match subcommand.as_deref() {
+            None => name == "debug-level",
+            Some("beacon_node") => beacon_node::HOT_RELOADABLE_FLAGS.contains(&name.as_str()),
+            Some("validator_client") => {
+                validator_client::HOT_RELOADABLE_FLAGS.contains(&name.as_str())
+            }
+            Some(_) => false,
+        });
+
+    let changed = |subcommand: &str| {
+        hot.iter()
+            .any(|(changed_subcommand, _)| changed_subcommand.as_deref() == Some(subcommand))
+    };
+
+    // Each node parses all of its flags before applying any, so an invalid value changes nothing.
+    if let Some(beacon_node) = beacon_node.filter(|_| changed("beacon_node")) {
+        let sub_matches = matches
+            .subcommand_matches("beacon_node")
+            .ok_or("The beacon_node subcommand is missing")?;
+        beacon_node.reload_config(sub_matches)?;
+    }
+
+    if let Some(validator_client) = validator_client.filter(|_| changed("validator_client")) {
+        let sub_matches = matches
+            .subcommand_matches("validator_client")
+            .ok_or("The validator_client subcommand is missing")?;
+        validator_client.reload_config(sub_matches)?;
+    }
+
+    if let Some(log_level) = log_level {
+        let debug_level = matches
+            .value_of("debug-level")
+            .ok_or_else(|| "Expected --debug-level flag".to_string())?;
+        log_level.set(parse_debug_level(debug_level)?);
+    }
+
+    for flag in &hot {
+        running_flags.copy_flag(&reloaded_flags, flag);
+    }
+
+    Ok(ConfigReload {
+        applied: hot.into_iter().map(|(_, name)| name).collect(),
+        requires_restart: cold.into_iter().map(|(_, name)| name).collect(),
+    })
+}
@@ -1,6 +1,11 @@
 #![cfg(not(debug_assertions))]
 
 use account_manager::{
+    engine_jwt::{
+        generate::{CMD as GENERATE_CMD, FORCE_FLAG},
+        show::CMD as SHOW_CMD,
+        CMD as ENGINE_JWT_CMD, SECRET_FILE_FLAG,
+    },
     validator::{
         create::*,
         import::{self, CMD as IMPORT_CMD},
@@ -18,9 +23,11 @@ use account_utils::{
     validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
     ZeroizeString,
 };
+use slashing_protection::SLASHING_PROTECTION_FILENAME;
 use std::env;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 use std::str::from_utf8;
@@ -467,4 +474,61 @@ fn validator_import_launchpad() {
         defs.as_slice() == &[expected_def],
         "validator defs file should be accurate"
     );
+
+    assert!(
+        dst_dir.path().join(SLASHING_PROTECTION_FILENAME).exists(),
+        "validator should be registered for slashing protection"
+    );
+}
+
+/// Returns the `lighthouse account engine-jwt` command, using the secret file at `path`.
+fn engine_jwt_cmd<P: AsRef<Path>>(path: P) -> Command {
+    let mut cmd = account_cmd();
+    cmd.arg(ENGINE_JWT_CMD)
+        .arg(format!("--{}", SECRET_FILE_FLAG))
+        .arg(path.as_ref().as_os_str());
+    cmd
+}
+
+/// Uses `lighthouse account engine-jwt show` to read the secret at `path`.
+fn show_engine_jwt<P: AsRef<Path>>(path: P) -> Result<String, String> {
+    output_result(engine_jwt_cmd(path).arg(SHOW_CMD)).map(|output| {
+        from_utf8(&output.stdout)
+            .expect("stdout is not utf8")
+            .trim()
+            .to_string()
+    })
+}
+
+#[test]
+fn engine_jwt_generate_show_and_rotate() {
+    let dir = tempdir().unwrap();
+    let secret_file = dir.path().join("engine").join("jwt.hex");
+
+    output_result(engine_jwt_cmd(&secret_file).arg(GENERATE_CMD)).unwrap();
+
+    assert_eq!(
+        fs::metadata(&secret_file).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+
+    let secret = show_engine_jwt(&secret_file).unwrap();
+    assert_eq!(secret.len(), 66, "should be 32 bytes of 0x-prefixed hex");
+
+    // Should not replace the secret without --force.
+    output_result(engine_jwt_cmd(&secret_file).arg(GENERATE_CMD)).unwrap_err();
+    assert_eq!(show_engine_jwt(&secret_file).unwrap(), secret);
+
+    output_result(
+        engine_jwt_cmd(&secret_file)
+            .arg(GENERATE_CMD)
+            .arg(format!("--{}", FORCE_FLAG)),
+    )
+    .unwrap();
+    assert_ne!(show_engine_jwt(&secret_file).unwrap(), secret);
+
+    // Should not show a secret which other users can read.
+    fs::set_permissions(&secret_file, fs::Permissions::from_mode(0o644)).unwrap();
+    let err = show_engine_jwt(&secret_file).unwrap_err();
+    assert!(err.contains("only be accessible by its owner"));
 }
@@ -57,11 +57,12 @@ impl<E: EthSpec> LocalBeaconNode<E> {
             .client
             .http_listen_addr()
             .ok_or_else(|| "A remote beacon node must have a http server".to_string())?;
-        Ok(RemoteBeaconNode::new(format!(
+        RemoteBeaconNode::new(format!(
             "http://{}:{}",
             socket_addr.ip(),
             socket_addr.port()
-        ))?)
+        ))
+        .map_err(|e| format!("Unable to create remote node: {}", e))
     }
 }
 
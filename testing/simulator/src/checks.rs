@@ -1,5 +1,6 @@
 use crate::local_network::LocalNetwork;
-use std::time::Duration;
+use node_test_rig::RemoteBeaconNode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use types::{Epoch, EthSpec, Slot, Unsigned};
 
 /// Checks that all of the validators have on-boarded by the start of the second eth1 voting
@@ -151,3 +152,127 @@ pub async fn verify_full_block_production_up_to<E: EthSpec>(
     }
     Ok(())
 }
+
+/// Waits until all of the given `nodes` report via their HTTP API that the chain has started,
+/// returning the genesis time they agree upon.
+///
+/// Returns an error if the nodes disagree on the genesis time, or the chain has not started
+/// within `timeout` of the genesis time.
+pub async fn wait_for_genesis<E: EthSpec>(
+    nodes: &[RemoteBeaconNode<E>],
+    timeout: Duration,
+) -> Result<u64, String> {
+    let mut genesis_times = Vec::new();
+    for remote_node in nodes {
+        genesis_times.push(
+            remote_node
+                .http
+                .beacon()
+                .get_genesis_time()
+                .await
+                .map_err(|e| format!("Get genesis time via http failed: {:?}", e))?,
+        );
+    }
+
+    let genesis_time = *genesis_times
+        .first()
+        .ok_or_else(|| "No nodes to wait for".to_string())?;
+    if genesis_times.iter().any(|time| *time != genesis_time) {
+        return Err(format!(
+            "Nodes disagree on the genesis time. Genesis times: {:?}",
+            genesis_times
+        ));
+    }
+
+    let deadline = Duration::from_secs(genesis_time) + timeout;
+    loop {
+        let mut is_pre_genesis = false;
+        for remote_node in nodes {
+            is_pre_genesis |= remote_node
+                .http
+                .node()
+                .syncing_status()
+                .await
+                .map_err(|e| format!("Get syncing status via http failed: {:?}", e))?
+                .is_pre_genesis;
+        }
+
+        if !is_pre_genesis {
+            return Ok(genesis_time);
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| "Unable to read system time")?;
+        if now > deadline {
+            return Err(format!(
+                "Nodes had not started the chain {:?} after genesis",
+                timeout
+            ));
+        }
+
+        tokio::time::delay_for(Duration::from_secs(1)).await;
+    }
+}
+
+/// Waits until all of the given `nodes` report via their HTTP API a head state that has a
+/// finalized epoch of at least `epoch`.
+///
+/// Returns an error if this has not happened after `timeout_epochs` epochs.
+pub async fn wait_for_finalization<E: EthSpec>(
+    nodes: &[RemoteBeaconNode<E>],
+    epoch: Epoch,
+    slot_duration: Duration,
+    timeout_epochs: u64,
+) -> Result<(), String> {
+    let mut epochs = Vec::new();
+
+    for _ in 0..=timeout_epochs * E::slots_per_epoch() {
+        epochs = finalized_epochs(nodes).await?;
+
+        if epochs.iter().all(|node_epoch| *node_epoch >= epoch) {
+            return Ok(());
+        }
+
+        tokio::time::delay_for(slot_duration).await;
+    }
+
+    Err(format!(
+        "Nodes have not finalized epoch {} after {} epochs. Finalized epochs: {:?}",
+        epoch, timeout_epochs, epochs
+    ))
+}
+
+/// Verifies that none of the given `nodes` report via their HTTP API a head state that has
+/// finalized an epoch after genesis.
+pub async fn verify_none_finalized<E: EthSpec>(
+    nodes: &[RemoteBeaconNode<E>],
+) -> Result<(), String> {
+    let epochs = finalized_epochs(nodes).await?;
+
+    if epochs.iter().any(|node_epoch| *node_epoch != Epoch::new(0)) {
+        Err(format!(
+            "Nodes have finalized despite not having a supermajority. Finalized epochs: {:?}",
+            epochs
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the finalized epoch of the head state of each of the given `nodes`.
+async fn finalized_epochs<E: EthSpec>(nodes: &[RemoteBeaconNode<E>]) -> Result<Vec<Epoch>, String> {
+    let mut epochs = Vec::new();
+    for remote_node in nodes {
+        epochs.push(
+            remote_node
+                .http
+                .beacon()
+                .get_head()
+                .await
+                .map(|head| head.finalized_slot.epoch(E::slots_per_epoch()))
+                .map_err(|e| format!("Get head via http failed: {:?}", e))?,
+        );
+    }
+    Ok(epochs)
+}
@@ -105,4 +105,58 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .help("Sync verification strategy to run."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Runs a simulation which bypasses the eth1 chain and is driven and checked \
+                    solely through the HTTP API of each beacon node. Optionally, some of the nodes \
+                    may be partitioned from the rest of the network, in which case they must not \
+                    finalize whilst the others do.")
+                .arg(
+                    Arg::with_name("nodes")
+                        .short("n")
+                        .long("nodes")
+                        .takes_value(true)
+                        .default_value("4")
+                        .help("Number of beacon nodes"),
+                )
+                .arg(
+                    Arg::with_name("validators")
+                        .short("v")
+                        .long("validators")
+                        .takes_value(true)
+                        .default_value("64")
+                        .help("Total number of validators, divided evenly between the nodes"),
+                )
+                .arg(
+                    Arg::with_name("speed_up_factor")
+                        .short("s")
+                        .long("speed_up_factor")
+                        .takes_value(true)
+                        .default_value("4")
+                        .help("Speed up factor"),
+                )
+                .arg(
+                    Arg::with_name("partition")
+                        .short("p")
+                        .long("partition")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Number of beacon nodes which are never connected to the others. \
+                            Must be less than a third of the nodes."),
+                )
+                .arg(
+                    Arg::with_name("finalization_timeout")
+                        .long("finalization_timeout")
+                        .takes_value(true)
+                        .default_value("6")
+                        .help("Number of epochs after genesis by which the nodes must finalize"),
+                )
+                .arg(
+                    Arg::with_name("continue_after_checks")
+                        .short("c")
+                        .long("continue_after_checks")
+                        .takes_value(false)
+                        .help("Continue after checks (default false)"),
+                ),
+        )
 }
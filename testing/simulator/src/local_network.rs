@@ -78,10 +78,29 @@ impl<E: EthSpec> LocalNetwork<E> {
     }
 
     /// Adds a beacon node to the network, connecting to the 0'th beacon node via ENR.
-    pub async fn add_beacon_node(&self, mut beacon_config: ClientConfig) -> Result<(), String> {
+    pub async fn add_beacon_node(&self, beacon_config: ClientConfig) -> Result<(), String> {
+        self.add_beacon_node_with_boot_node(beacon_config, true)
+            .await
+    }
+
+    /// Adds a beacon node to the network which is not given the ENR of any other node, so that it
+    /// is partitioned from the rest of the network.
+    pub async fn add_isolated_beacon_node(
+        &self,
+        beacon_config: ClientConfig,
+    ) -> Result<(), String> {
+        self.add_beacon_node_with_boot_node(beacon_config, false)
+            .await
+    }
+
+    async fn add_beacon_node_with_boot_node(
+        &self,
+        mut beacon_config: ClientConfig,
+        connect_to_boot_node: bool,
+    ) -> Result<(), String> {
         let self_1 = self.clone();
         println!("Adding beacon node..");
-        {
+        if connect_to_boot_node {
             let read_lock = self.beacon_nodes.read();
 
             let boot_node = read_lock.first().expect("should have at least one node");
@@ -21,6 +21,7 @@ mod cli;
 mod eth1_sim;
 mod local_network;
 mod no_eth1_sim;
+mod rest_sim;
 mod sync_sim;
 
 use cli::cli_app;
@@ -57,6 +58,13 @@ fn main() {
                 std::process::exit(1)
             }
         },
+        ("run", Some(matches)) => match rest_sim::run_rest_sim(matches) {
+            Ok(()) => println!("Simulation exited successfully"),
+            Err(e) => {
+                eprintln!("Simulation exited with error: {}", e);
+                std::process::exit(1)
+            }
+        },
         _ => {
             eprintln!("Invalid subcommand. Use --help to see available options");
             std::process::exit(1)
@@ -0,0 +1,180 @@
+use crate::{checks, LocalNetwork};
+use clap::ArgMatches;
+use futures::prelude::*;
+use node_test_rig::{
+    environment::EnvironmentBuilder, testing_client_config, ClientGenesis, ValidatorConfig,
+    ValidatorFiles,
+};
+use rayon::prelude::*;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::Epoch;
+
+/// The delay before genesis, long enough for all of the nodes to start beforehand.
+const GENESIS_DELAY: Duration = Duration::from_secs(30);
+
+/// Runs a simulation which is orchestrated and checked solely through the HTTP API of each beacon
+/// node, as an external client would observe it.
+///
+/// The last `partition` beacon nodes (and their validator clients) are never connected to the
+/// others, so that they form a minority partition which must not finalize whilst the majority
+/// does.
+pub fn run_rest_sim(matches: &ArgMatches) -> Result<(), String> {
+    let node_count = value_t!(matches, "nodes", usize).expect("missing nodes default");
+    let validator_count =
+        value_t!(matches, "validators", usize).expect("missing validators default");
+    let speed_up_factor =
+        value_t!(matches, "speed_up_factor", u64).expect("missing speed_up_factor default");
+    let partition_count = value_t!(matches, "partition", usize).expect("missing partition default");
+    let finalization_timeout = value_t!(matches, "finalization_timeout", u64)
+        .expect("missing finalization_timeout default");
+    let continue_after_checks = matches.is_present("continue_after_checks");
+
+    if node_count == 0 || validator_count % node_count != 0 {
+        return Err(format!(
+            "The {} validators must be divided evenly between the {} nodes",
+            validator_count, node_count
+        ));
+    }
+
+    // Each node has the same number of validators, so the partition holds less than a third of
+    // the stake if it has less than a third of the nodes.
+    if partition_count * 3 >= node_count {
+        return Err(format!(
+            "A partition of {} of the {} nodes would prevent the others from finalizing",
+            partition_count, node_count
+        ));
+    }
+
+    let validators_per_node = validator_count / node_count;
+
+    println!("Beacon Chain Simulator (HTTP API):");
+    println!(" nodes:{}", node_count);
+    println!(" validators:{}", validator_count);
+    println!(" partition:{}", partition_count);
+    println!(" finalization_timeout:{}", finalization_timeout);
+    println!(" continue_after_checks:{}", continue_after_checks);
+
+    // Generate the directories and keystores required for the validator clients.
+    let validator_files = (0..node_count)
+        .into_par_iter()
+        .map(|i| {
+            println!(
+                "Generating keystores for validator {} of {}",
+                i + 1,
+                node_count
+            );
+
+            let indices =
+                (i * validators_per_node..(i + 1) * validators_per_node).collect::<Vec<_>>();
+            ValidatorFiles::with_keystores(&indices).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let log_level = "debug";
+    let log_format = None;
+
+    let mut env = EnvironmentBuilder::mainnet()
+        .async_logger(log_level, log_format)?
+        .multi_threaded_tokio_runtime()?
+        .build()?;
+
+    let spec = &mut env.eth2_config.spec;
+
+    spec.milliseconds_per_slot /= speed_up_factor;
+
+    let genesis_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| "should get system time")?
+        + GENESIS_DELAY;
+
+    let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
+
+    let context = env.core_context();
+
+    let mut beacon_config = testing_client_config();
+
+    beacon_config.genesis = ClientGenesis::Interop {
+        validator_count,
+        genesis_time: genesis_time.as_secs(),
+    };
+    beacon_config.dummy_eth1_backend = true;
+    beacon_config.sync_eth1_chain = true;
+
+    beacon_config.network.enr_address = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+
+    let main_future = async {
+        let network = LocalNetwork::new(context, beacon_config.clone()).await?;
+
+        /*
+         * One by one, add beacon nodes to the network, leaving the last `partition_count` nodes
+         * without a boot node.
+         */
+        for i in 1..node_count {
+            if i < node_count - partition_count {
+                network.add_beacon_node(beacon_config.clone()).await?;
+            } else {
+                network
+                    .add_isolated_beacon_node(beacon_config.clone())
+                    .await?;
+            }
+        }
+
+        /*
+         * Attach each validator client to a single corresponding beacon node.
+         */
+        for (i, files) in validator_files.into_iter().enumerate() {
+            network
+                .add_validator_client(
+                    ValidatorConfig {
+                        disable_auto_discover: false,
+                        ..ValidatorConfig::default()
+                    },
+                    i,
+                    files,
+                )
+                .await?;
+        }
+
+        let remote_nodes = network.remote_nodes()?;
+        let (majority, minority) = remote_nodes.split_at(node_count - partition_count);
+
+        /*
+         * The checks, which only use the HTTP API of the beacon nodes.
+         */
+        let genesis_time = checks::wait_for_genesis(&remote_nodes, slot_duration).await?;
+        println!("Genesis reached at {}", genesis_time);
+
+        checks::wait_for_finalization(majority, Epoch::new(2), slot_duration, finalization_timeout)
+            .await?;
+        println!("{} nodes have finalized", majority.len());
+
+        checks::verify_none_finalized(minority).await?;
+        if !minority.is_empty() {
+            println!("{} partitioned nodes have not finalized", minority.len());
+        }
+
+        // The `final_future` either completes immediately or never completes, depending on the value
+        // of `continue_after_checks`.
+
+        if continue_after_checks {
+            future::pending::<()>().await;
+        }
+        /*
+         * End the simulation by dropping the network. This will kill all running beacon nodes and
+         * validator clients.
+         */
+        println!(
+            "Simulation complete. Finished with {} beacon nodes and {} validator clients",
+            network.beacon_node_count(),
+            network.validator_client_count()
+        );
+
+        // Be explicit about dropping the network, as this kills all the nodes. This ensures
+        // all the checks have adequate time to pass.
+        drop(network);
+        Ok::<(), String>(())
+    };
+
+    env.runtime().block_on(main_future)
+}
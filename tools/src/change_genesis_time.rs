@@ -0,0 +1,55 @@
+use crate::{load_ssz, write_ssz};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use slog::{info, Logger};
+use std::path::PathBuf;
+use types::{BeaconState, EthSpec};
+
+pub const CMD: &str = "change-genesis-time";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD)
+        .about("Modifies the genesis time of a state.")
+        .arg(
+            Arg::with_name("ssz-state")
+                .long("ssz-state")
+                .value_name("SSZ_FILE")
+                .help("Path to a SSZ file of the state.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("genesis-time")
+                .long("genesis-time")
+                .value_name("UNIX_EPOCH")
+                .help("The value for state.genesis_time.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .value_name("SSZ_FILE")
+                .help("Path to which the modified state is written. Defaults to --ssz-state.")
+                .takes_value(true),
+        )
+}
+
+pub fn run<E: EthSpec>(matches: &ArgMatches, log: &Logger) -> Result<(), String> {
+    let state_path: PathBuf = clap_utils::parse_required(matches, "ssz-state")?;
+    let genesis_time: u64 = clap_utils::parse_required(matches, "genesis-time")?;
+    let output_path: PathBuf =
+        clap_utils::parse_optional(matches, "output")?.unwrap_or_else(|| state_path.clone());
+
+    let mut state: BeaconState<E> = load_ssz(&state_path)?;
+    state.genesis_time = genesis_time;
+    write_ssz(&output_path, &state)?;
+
+    info!(
+        log,
+        "Changed genesis time";
+        "genesis_time" => genesis_time,
+        "output" => format!("{:?}", output_path),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::write_ssz;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use clap_utils::parse_ssz_optional;
+use genesis::interop_genesis_state;
+use slog::{info, Logger};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec};
+
+pub const CMD: &str = "interop-genesis";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD)
+        .about("Produces an interop-compatible genesis state using deterministic keypairs.")
+        .arg(
+            Arg::with_name("validator-count")
+                .long("validator-count")
+                .value_name("INTEGER")
+                .help("The number of validators in the genesis state.")
+                .takes_value(true)
+                .default_value("1024"),
+        )
+        .arg(
+            Arg::with_name("genesis-time")
+                .long("genesis-time")
+                .value_name("UNIX_EPOCH")
+                .help("The value for state.genesis_time. Defaults to now.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("genesis-fork-version")
+                .long("genesis-fork-version")
+                .value_name("HEX")
+                .help(
+                    "Overrides the genesis fork version of the spec. Used to avoid replay \
+                    attacks between testnets.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .value_name("SSZ_FILE")
+                .help("Path to which the genesis state is written as SSZ.")
+                .takes_value(true)
+                .required(true),
+        )
+}
+
+pub fn run<E: EthSpec>(matches: &ArgMatches, spec: &ChainSpec, log: &Logger) -> Result<(), String> {
+    let validator_count: usize = clap_utils::parse_required(matches, "validator-count")?;
+    let output_path: PathBuf = clap_utils::parse_required(matches, "output")?;
+
+    let genesis_time =
+        if let Some(genesis_time) = clap_utils::parse_optional(matches, "genesis-time")? {
+            genesis_time
+        } else {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| format!("Unable to get time: {:?}", e))?
+                .as_secs()
+        };
+
+    let mut spec = spec.clone();
+    if let Some(v) = parse_ssz_optional(matches, "genesis-fork-version")? {
+        spec.genesis_fork_version = v;
+    }
+
+    let keypairs = generate_deterministic_keypairs(validator_count);
+    let genesis_state: BeaconState<E> = interop_genesis_state(&keypairs, genesis_time, &spec)?;
+    write_ssz(&output_path, &genesis_state)?;
+
+    info!(
+        log,
+        "Created interop genesis state";
+        "validator_count" => validator_count,
+        "genesis_time" => genesis_time,
+        "output" => format!("{:?}", output_path),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Utilities for manipulating beacon states offline, reading and writing SSZ files.
+//!
+//! The spec is that of the main binary, i.e., it is determined by the `--spec` flag and the
+//! configuration of the `--testnet` or `--testnet-dir` in use.
+
+pub mod change_genesis_time;
+pub mod interop_genesis;
+pub mod skip_slots;
+
+use clap::{App, ArgMatches};
+use environment::Environment;
+use ssz::{Decode, Encode};
+use std::fs;
+use std::path::Path;
+use types::EthSpec;
+
+pub const CMD: &str = "tools";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about("Utilities for creating and manipulating beacon states for testing.")
+        .subcommand(skip_slots::cli_app())
+        .subcommand(change_genesis_time::cli_app())
+        .subcommand(interop_genesis::cli_app())
+}
+
+/// Run the tools, returning an error if the operation did not succeed.
+pub fn run<E: EthSpec>(matches: &ArgMatches<'_>, mut env: Environment<E>) -> Result<(), String> {
+    let context = env.core_context();
+    let log = context.log().clone();
+    let spec = context.eth2_config().spec.clone();
+
+    match matches.subcommand() {
+        (skip_slots::CMD, Some(matches)) => skip_slots::run::<E>(matches, &spec, &log),
+        (change_genesis_time::CMD, Some(matches)) => change_genesis_time::run::<E>(matches, &log),
+        (interop_genesis::CMD, Some(matches)) => interop_genesis::run::<E>(matches, &spec, &log),
+        _ => Err("No tools subcommand supplied. See --help.".to_string()),
+    }
+}
+
+fn load_ssz<T: Decode>(path: &Path) -> Result<T, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+    T::from_ssz_bytes(&bytes).map_err(|e| format!("Unable to decode {:?}: {:?}", path, e))
+}
+
+fn write_ssz<T: Encode>(path: &Path, item: &T) -> Result<(), String> {
+    fs::write(path, item.as_ssz_bytes()).map_err(|e| format!("Unable to write {:?}: {:?}", path, e))
+}
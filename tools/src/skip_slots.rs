@@ -0,0 +1,97 @@
+use crate::{load_ssz, write_ssz};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use slog::{info, Logger};
+use state_processing::per_slot_processing;
+use std::path::PathBuf;
+use types::{BeaconState, ChainSpec, EthSpec};
+
+pub const CMD: &str = "skip-slots";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD)
+        .about("Advances a state across some number of empty slots.")
+        .arg(
+            Arg::with_name("pre-state")
+                .long("pre-state")
+                .value_name("SSZ_FILE")
+                .help("Path to a SSZ file of the pre-state.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("slots")
+                .long("slots")
+                .value_name("SLOT_COUNT")
+                .help("Number of slots to skip.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .value_name("SSZ_FILE")
+                .help("Path to which the post-state is written as SSZ.")
+                .takes_value(true)
+                .required(true),
+        )
+}
+
+pub fn run<E: EthSpec>(matches: &ArgMatches, spec: &ChainSpec, log: &Logger) -> Result<(), String> {
+    let pre_state_path: PathBuf = clap_utils::parse_required(matches, "pre-state")?;
+    let slots: u64 = clap_utils::parse_required(matches, "slots")?;
+    let output_path: PathBuf = clap_utils::parse_required(matches, "output")?;
+
+    let pre_state: BeaconState<E> = load_ssz(&pre_state_path)?;
+    let pre_slot = pre_state.slot;
+    let post_state = skip_slots(pre_state, slots, spec)?;
+    write_ssz(&output_path, &post_state)?;
+
+    info!(
+        log,
+        "Skipped slots";
+        "pre_slot" => pre_slot,
+        "post_slot" => post_state.slot,
+        "output" => format!("{:?}", output_path),
+    );
+
+    Ok(())
+}
+
+/// Advances `state` across `slots` empty slots.
+pub fn skip_slots<E: EthSpec>(
+    mut state: BeaconState<E>,
+    slots: u64,
+    spec: &ChainSpec,
+) -> Result<BeaconState<E>, String> {
+    state
+        .build_all_caches(spec)
+        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+
+    for i in 0..slots {
+        per_slot_processing(&mut state, None, spec)
+            .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
+    }
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genesis::interop_genesis_state;
+    use types::{test_utils::generate_deterministic_keypairs, Epoch, MinimalEthSpec, Slot};
+
+    #[test]
+    fn skips_across_epoch_boundary() {
+        let spec = MinimalEthSpec::default_spec();
+        let keypairs = generate_deterministic_keypairs(8);
+        let genesis_state: BeaconState<MinimalEthSpec> =
+            interop_genesis_state(&keypairs, 0, &spec).unwrap();
+
+        let slots = MinimalEthSpec::slots_per_epoch() + 1;
+        let state = skip_slots(genesis_state, slots, &spec).unwrap();
+
+        assert_eq!(state.slot, Slot::new(slots));
+        assert_eq!(state.current_epoch(), Epoch::new(1));
+    }
+}
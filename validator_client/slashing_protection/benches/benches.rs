@@ -0,0 +1,111 @@
+//! Compares the throughput of the SQLite and LMDB slashing protection databases when many
+//! validators sign at the same time, as they do at slot boundaries.
+
+use criterion::Criterion;
+use criterion::{criterion_group, criterion_main, Benchmark};
+use rayon::prelude::*;
+use slashing_protection::{DatabaseBackend, SlashingProtection};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+use types::{
+    test_utils::generate_deterministic_keypair, AttestationData, BeaconBlockHeader, Checkpoint,
+    Epoch, Hash256, Slot,
+};
+
+const VALIDATOR_COUNTS: [usize; 2] = [64, 1_024];
+
+fn attestation(source: u64, target: u64) -> AttestationData {
+    AttestationData {
+        slot: Slot::new(0),
+        index: 0,
+        beacon_block_root: Hash256::zero(),
+        source: Checkpoint {
+            epoch: Epoch::new(source),
+            root: Hash256::zero(),
+        },
+        target: Checkpoint {
+            epoch: Epoch::new(target),
+            root: Hash256::zero(),
+        },
+    }
+}
+
+fn block(slot: u64) -> BeaconBlockHeader {
+    BeaconBlockHeader {
+        slot: Slot::new(slot),
+        proposer_index: 0,
+        parent_root: Hash256::zero(),
+        state_root: Hash256::zero(),
+        body_root: Hash256::zero(),
+    }
+}
+
+fn all_benches(c: &mut Criterion) {
+    for &validator_count in &VALIDATOR_COUNTS {
+        let pubkeys = Arc::new(
+            (0..validator_count)
+                .into_par_iter()
+                .map(|i| generate_deterministic_keypair(i).pk)
+                .collect::<Vec<_>>(),
+        );
+
+        for &backend in &DatabaseBackend::ALL {
+            let dir = Arc::new(tempdir().expect("should create tempdir"));
+            let db = SlashingProtection::open_or_create(backend, dir.path())
+                .expect("should create database");
+            db.register_validators(pubkeys.iter())
+                .expect("should register validators");
+
+            // Every validator attests to the next epoch, in parallel.
+            let inner_db = db.clone();
+            let inner_pubkeys = pubkeys.clone();
+            let inner_dir = dir.clone();
+            let epoch = AtomicU64::new(0);
+            c.bench(
+                &format!("{}_validators", validator_count),
+                Benchmark::new(format!("{}/attest_in_parallel", backend), move |b| {
+                    let _dir = &inner_dir;
+                    b.iter(|| {
+                        let target = epoch.fetch_add(1, Ordering::Relaxed) + 1;
+                        inner_pubkeys.par_iter().for_each(|pubkey| {
+                            inner_db
+                                .check_and_insert_attestation(
+                                    pubkey,
+                                    &attestation(target - 1, target),
+                                    Hash256::zero(),
+                                )
+                                .expect("attestation should be safe");
+                        })
+                    })
+                })
+                .sample_size(10),
+            );
+
+            // Every validator proposes a block at the next slot, in parallel.
+            let inner_pubkeys = pubkeys.clone();
+            let slot = AtomicU64::new(0);
+            c.bench(
+                &format!("{}_validators", validator_count),
+                Benchmark::new(format!("{}/propose_in_parallel", backend), move |b| {
+                    let _dir = &dir;
+                    b.iter(|| {
+                        let slot = slot.fetch_add(1, Ordering::Relaxed) + 1;
+                        inner_pubkeys.par_iter().for_each(|pubkey| {
+                            db.check_and_insert_block_proposal(
+                                pubkey,
+                                &block(slot),
+                                Hash256::zero(),
+                            )
+                            .expect("block should be safe");
+                        })
+                    })
+                })
+                .sample_size(10),
+            );
+        }
+    }
+}
+
+criterion_group!(benches, all_benches,);
+criterion_main!(benches);
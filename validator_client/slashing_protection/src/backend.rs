@@ -0,0 +1,319 @@
+//! Selects between the SQLite and LMDB implementations of the slashing protection database.
+
+use crate::instance_lock::Heartbeat;
+use crate::interchange::{Interchange, InterchangeError};
+use crate::{
+    LmdbSlashingDatabase, NotSafe, Safe, SlashingDatabase, ValidatorHistory,
+    SLASHING_PROTECTION_FILENAME, SLASHING_PROTECTION_LMDB_FILENAME,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKey, Slot};
+
+/// The implementation of a slashing protection database.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    Sqlite,
+    Lmdb,
+}
+
+impl Default for DatabaseBackend {
+    fn default() -> Self {
+        DatabaseBackend::Sqlite
+    }
+}
+
+impl DatabaseBackend {
+    pub const ALL: [DatabaseBackend; 2] = [DatabaseBackend::Sqlite, DatabaseBackend::Lmdb];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DatabaseBackend::Sqlite => "sqlite",
+            DatabaseBackend::Lmdb => "lmdb",
+        }
+    }
+
+    /// The name of the database file within the validator datadir.
+    pub fn filename(self) -> &'static str {
+        match self {
+            DatabaseBackend::Sqlite => SLASHING_PROTECTION_FILENAME,
+            DatabaseBackend::Lmdb => SLASHING_PROTECTION_LMDB_FILENAME,
+        }
+    }
+
+    /// The path of the database within the validator datadir `dir`.
+    pub fn path(self, dir: &Path) -> PathBuf {
+        dir.join(self.filename())
+    }
+
+    /// Returns the backend of the database in `dir`, or `None` if there is none.
+    ///
+    /// Errors if `dir` contains a database of each backend, as it is unclear which is in use.
+    pub fn detect(dir: &Path) -> Result<Option<Self>, String> {
+        let existing = Self::ALL
+            .iter()
+            .copied()
+            .filter(|backend| backend.path(dir).exists())
+            .collect::<Vec<_>>();
+
+        match existing.as_slice() {
+            [] => Ok(None),
+            [backend] => Ok(Some(*backend)),
+            _ => Err(format!(
+                "{:?} contains both {} and {} slashing protection databases, remove the one \
+                which is not in use",
+                dir, SLASHING_PROTECTION_FILENAME, SLASHING_PROTECTION_LMDB_FILENAME
+            )),
+        }
+    }
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|backend| backend.name() == s)
+            .ok_or_else(|| format!("Unknown slashing protection backend: {}", s))
+    }
+}
+
+impl fmt::Display for DatabaseBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A slashing protection database of either backend.
+///
+/// Each method behaves identically to that of `SlashingDatabase`.
+#[derive(Debug, Clone)]
+pub enum SlashingProtection {
+    Sqlite(SlashingDatabase),
+    Lmdb(LmdbSlashingDatabase),
+}
+
+impl SlashingProtection {
+    /// Open the `backend` database in the validator datadir `dir`, or create one if none exists.
+    ///
+    /// Errors if `dir` contains a database of the other backend but not of `backend`, because
+    /// signing with a new, empty database would not protect against slashing.
+    pub fn open_or_create(backend: DatabaseBackend, dir: &Path) -> Result<Self, NotSafe> {
+        let path = backend.path(dir);
+
+        if !path.exists() {
+            if let Some(existing) = DatabaseBackend::ALL
+                .iter()
+                .copied()
+                .find(|other| *other != backend && other.path(dir).exists())
+            {
+                return Err(NotSafe::BackendMismatch {
+                    selected: backend,
+                    existing,
+                });
+            }
+        }
+
+        match backend {
+            DatabaseBackend::Sqlite => SlashingDatabase::open_or_create(&path).map(Self::Sqlite),
+            DatabaseBackend::Lmdb => LmdbSlashingDatabase::open_or_create(&path).map(Self::Lmdb),
+        }
+    }
+
+    /// Open the existing `backend` database in the validator datadir `dir`.
+    pub fn open(backend: DatabaseBackend, dir: &Path) -> Result<Self, NotSafe> {
+        let path = backend.path(dir);
+        match backend {
+            DatabaseBackend::Sqlite => SlashingDatabase::open(&path).map(Self::Sqlite),
+            DatabaseBackend::Lmdb => LmdbSlashingDatabase::open(&path).map(Self::Lmdb),
+        }
+    }
+
+    pub fn backend(&self) -> DatabaseBackend {
+        match self {
+            SlashingProtection::Sqlite(_) => DatabaseBackend::Sqlite,
+            SlashingProtection::Lmdb(_) => DatabaseBackend::Lmdb,
+        }
+    }
+
+    pub fn register_validator(&self, validator_pk: &PublicKey) -> Result<(), NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.register_validator(validator_pk),
+            SlashingProtection::Lmdb(db) => db.register_validator(validator_pk),
+        }
+    }
+
+    pub fn register_validators<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKey>,
+    ) -> Result<(), NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.register_validators(public_keys),
+            SlashingProtection::Lmdb(db) => db.register_validators(public_keys),
+        }
+    }
+
+    pub fn register_validators_batch(
+        &self,
+        public_keys: &[&PublicKey],
+        progress: impl FnMut(usize, usize),
+    ) -> Result<usize, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.register_validators_batch(public_keys, progress),
+            SlashingProtection::Lmdb(db) => db.register_validators_batch(public_keys, progress),
+        }
+    }
+
+    pub fn check_and_insert_block_proposal(
+        &self,
+        validator_pubkey: &PublicKey,
+        block_header: &BeaconBlockHeader,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => {
+                db.check_and_insert_block_proposal(validator_pubkey, block_header, domain)
+            }
+            SlashingProtection::Lmdb(db) => {
+                db.check_and_insert_block_proposal(validator_pubkey, block_header, domain)
+            }
+        }
+    }
+
+    pub fn check_and_insert_attestation(
+        &self,
+        validator_pubkey: &PublicKey,
+        attestation: &AttestationData,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => {
+                db.check_and_insert_attestation(validator_pubkey, attestation, domain)
+            }
+            SlashingProtection::Lmdb(db) => {
+                db.check_and_insert_attestation(validator_pubkey, attestation, domain)
+            }
+        }
+    }
+
+    pub fn check_and_insert_block_signing_root(
+        &self,
+        validator_pubkey: &PublicKey,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => {
+                db.check_and_insert_block_signing_root(validator_pubkey, slot, signing_root)
+            }
+            SlashingProtection::Lmdb(db) => {
+                db.check_and_insert_block_signing_root(validator_pubkey, slot, signing_root)
+            }
+        }
+    }
+
+    pub fn check_and_insert_attestation_signing_root(
+        &self,
+        validator_pubkey: &PublicKey,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.check_and_insert_attestation_signing_root(
+                validator_pubkey,
+                att_source_epoch,
+                att_target_epoch,
+                signing_root,
+            ),
+            SlashingProtection::Lmdb(db) => db.check_and_insert_attestation_signing_root(
+                validator_pubkey,
+                att_source_epoch,
+                att_target_epoch,
+                signing_root,
+            ),
+        }
+    }
+
+    pub fn export_minimal_interchange(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<Interchange, InterchangeError> {
+        match self {
+            SlashingProtection::Sqlite(db) => {
+                db.export_minimal_interchange(genesis_validators_root)
+            }
+            SlashingProtection::Lmdb(db) => db.export_minimal_interchange(genesis_validators_root),
+        }
+    }
+
+    pub fn import_minimal_interchange(
+        &self,
+        interchange: &Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<usize, InterchangeError> {
+        match self {
+            SlashingProtection::Sqlite(db) => {
+                db.import_minimal_interchange(interchange, genesis_validators_root)
+            }
+            SlashingProtection::Lmdb(db) => {
+                db.import_minimal_interchange(interchange, genesis_validators_root)
+            }
+        }
+    }
+
+    pub fn export_history(&self) -> Result<Vec<ValidatorHistory>, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.export_history(),
+            SlashingProtection::Lmdb(db) => db.export_history(),
+        }
+    }
+
+    pub fn read_heartbeat(&self) -> Result<Option<Heartbeat>, NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.read_heartbeat(),
+            SlashingProtection::Lmdb(db) => db.read_heartbeat(),
+        }
+    }
+
+    pub fn write_heartbeat(&self, heartbeat: Option<Heartbeat>) -> Result<(), NotSafe> {
+        match self {
+            SlashingProtection::Sqlite(db) => db.write_heartbeat(heartbeat),
+            SlashingProtection::Lmdb(db) => db.write_heartbeat(heartbeat),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn detect_backend() {
+        let dir = tempdir().unwrap();
+        assert_eq!(DatabaseBackend::detect(dir.path()), Ok(None));
+
+        drop(SlashingProtection::open_or_create(DatabaseBackend::Lmdb, dir.path()).unwrap());
+        assert_eq!(
+            DatabaseBackend::detect(dir.path()),
+            Ok(Some(DatabaseBackend::Lmdb))
+        );
+
+        drop(SlashingDatabase::create(&DatabaseBackend::Sqlite.path(dir.path())).unwrap());
+        assert!(DatabaseBackend::detect(dir.path()).is_err());
+    }
+
+    #[test]
+    fn backend_from_str() {
+        for backend in &DatabaseBackend::ALL {
+            assert_eq!(backend.to_string().parse(), Ok(*backend));
+        }
+        assert!("leveldb".parse::<DatabaseBackend>().is_err());
+    }
+}
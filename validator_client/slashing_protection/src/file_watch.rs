@@ -0,0 +1,107 @@
+use crate::NotSafe;
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The identity, size and modification time of the database file.
+///
+/// Recorded after each of our writes, so that a change caused by another process (e.g. the file
+/// being replaced, or restored from a backup) can be detected before signing.
+///
+/// Hashing the file contents instead would require reading the entire (ever-growing) file for
+/// every signature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileFingerprint {
+    id: Option<(u64, u64)>,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileFingerprint {
+    /// Read the fingerprint of the file at `path`, or `None` if it no longer exists.
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            id: Self::file_id(&metadata),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    /// The device and inode of the file, which change if it is replaced.
+    #[cfg(unix)]
+    fn file_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+        use std::os::unix::fs::MetadataExt;
+
+        Some((metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(windows)]
+    fn file_id(_: &std::fs::Metadata) -> Option<(u64, u64)> {
+        None
+    }
+}
+
+#[derive(Debug)]
+struct FileWatchState {
+    expected: Option<FileFingerprint>,
+    /// Set once an external modification is detected. Never unset, because the database may
+    /// continue to serve stale pages (or write to a file which has been unlinked), so only a
+    /// restart can safely resume signing.
+    modified: Option<String>,
+}
+
+/// Tracks whether a database file has been modified by another process.
+#[derive(Debug)]
+pub struct FileWatch {
+    path: PathBuf,
+    state: Mutex<FileWatchState>,
+}
+
+impl FileWatch {
+    /// Starts watching the file at `path`, expecting its current state.
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(FileWatchState {
+                expected: FileFingerprint::read(path),
+                modified: None,
+            }),
+        }
+    }
+
+    /// Run `f`, which may write to the database, only if the database file has not been modified
+    /// by another process since our last write, e.g. by replacing it or restoring an older copy
+    /// (which would roll back the signing history).
+    ///
+    /// Writes are serialised so that those made by this process are never mistaken for external
+    /// modifications.
+    pub fn guard<T>(&self, f: impl FnOnce() -> Result<T, NotSafe>) -> Result<T, NotSafe> {
+        let mut state = self.state.lock();
+
+        if state.modified.is_none() {
+            let current = FileFingerprint::read(&self.path);
+            if current != state.expected {
+                state.modified = Some(match (state.expected, current) {
+                    (_, None) => "database file was removed".to_string(),
+                    (Some(expected), Some(current)) if expected.id != current.id => {
+                        "database file was replaced".to_string()
+                    }
+                    _ => "database file was modified by another process".to_string(),
+                });
+            }
+        }
+
+        if let Some(reason) = &state.modified {
+            return Err(NotSafe::ExternallyModified(format!(
+                "{}, restart to resume signing",
+                reason
+            )));
+        }
+
+        // Record the state of the file even if `f` fails, as the database may have touched it.
+        let result = f();
+        state.expected = FileFingerprint::read(&self.path);
+        result
+    }
+}
@@ -0,0 +1,223 @@
+//! Detects other processes using the same slashing protection database, such as a duplicate
+//! validator client, which would sign conflicting messages with the same keys.
+//!
+//! Two mechanisms are used:
+//!
+//! - An exclusive OS-level lock on a file next to the database, which the OS releases when the
+//!   process exits, even if it crashes.
+//! - A heartbeat recorded in the database, which is refreshed every `HEARTBEAT_INTERVAL`. This
+//!   detects processes for which the file lock is ineffective, e.g. those on another host sharing
+//!   the datadir over a network file system.
+
+use crate::{NotSafe, SlashingProtection};
+use fs2::FileExt;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Path to the lock file within the validator datadir.
+pub const SLASHING_PROTECTION_LOCK_FILENAME: &str = "slashing_protection.lock";
+
+/// The interval at which a process using the database must refresh its heartbeat.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(12);
+
+/// A heartbeat refreshed more recently than this is assumed to belong to a live process.
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(3 * 12);
+
+/// The most recent heartbeat of the process using the database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heartbeat {
+    /// Chosen at random by each process, since process IDs are not unique across hosts (or
+    /// containers).
+    pub instance_id: u64,
+    pub pid: u32,
+    /// Seconds since the UNIX epoch.
+    pub last_seen: u64,
+}
+
+/// Exclusive use of the slashing protection database by this process.
+///
+/// The heartbeat is removed when dropped, so that the next process need not wait for it to
+/// expire.
+#[derive(Debug)]
+pub struct InstanceLock {
+    db: SlashingProtection,
+    instance_id: u64,
+    /// Held for the lifetime of the lock. `None` if the lock file was held by another process.
+    _lock_file: Option<File>,
+    overridden: Option<String>,
+}
+
+impl InstanceLock {
+    /// Lock `db`, which is stored in the validator datadir `dir`, for use by this process.
+    ///
+    /// Errors if another live process holds the lock file or has a recent heartbeat, unless
+    /// `force` is true, in which case the lock is taken regardless (see `Self::overridden`).
+    pub fn acquire(db: SlashingProtection, dir: &Path, force: bool) -> Result<Self, NotSafe> {
+        let lock_path = dir.join(SLASHING_PROTECTION_LOCK_FILENAME);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(&lock_path)?;
+
+        let mut conflicts = vec![];
+
+        let lock_file = match file.try_lock_exclusive() {
+            Ok(()) => Some(file),
+            Err(e) if e.raw_os_error() == fs2::lock_contended_error().raw_os_error() => {
+                conflicts.push(format!("{:?} is locked by another process", lock_path));
+                None
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Check the lock file first, since the database itself may be locked by the other process.
+        if !conflicts.is_empty() && !force {
+            return Err(NotSafe::InstanceLocked(conflicts.join(", ")));
+        }
+
+        if let Some(heartbeat) = db.read_heartbeat()? {
+            let age = unix_now().saturating_sub(heartbeat.last_seen);
+            if age < HEARTBEAT_TIMEOUT.as_secs() {
+                conflicts.push(format!(
+                    "process {} refreshed its heartbeat {} seconds ago, it is assumed to have \
+                    stopped after {} seconds",
+                    heartbeat.pid,
+                    age,
+                    HEARTBEAT_TIMEOUT.as_secs()
+                ));
+            }
+        }
+
+        if !conflicts.is_empty() && !force {
+            return Err(NotSafe::InstanceLocked(conflicts.join(", ")));
+        }
+
+        let lock = Self {
+            db,
+            instance_id: rand::random(),
+            _lock_file: lock_file,
+            overridden: if conflicts.is_empty() {
+                None
+            } else {
+                Some(conflicts.join(", "))
+            },
+        };
+        lock.heartbeat()?;
+
+        Ok(lock)
+    }
+
+    /// The reason that another process may be using the database, if the lock was forced.
+    pub fn overridden(&self) -> Option<&str> {
+        self.overridden.as_deref()
+    }
+
+    /// Refresh the heartbeat of this process, which must be done every `HEARTBEAT_INTERVAL`.
+    ///
+    /// Errors if another process has since recorded a heartbeat.
+    pub fn heartbeat(&self) -> Result<(), NotSafe> {
+        if let Some(current) = self.db.read_heartbeat()? {
+            if current.instance_id != self.instance_id {
+                return Err(NotSafe::InstanceLocked(format!(
+                    "process {} has since started using the database",
+                    current.pid
+                )));
+            }
+        }
+
+        self.db.write_heartbeat(Some(Heartbeat {
+            instance_id: self.instance_id,
+            pid: std::process::id(),
+            last_seen: unix_now(),
+        }))
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Ok(Some(current)) = self.db.read_heartbeat() {
+            if current.instance_id == self.instance_id {
+                let _ = self.db.write_heartbeat(None);
+            }
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DatabaseBackend;
+    use tempfile::tempdir;
+
+    #[test]
+    fn second_instance_refused() {
+        for backend in &DatabaseBackend::ALL {
+            let dir = tempdir().unwrap();
+            let db = SlashingProtection::open_or_create(*backend, dir.path()).unwrap();
+
+            let first = InstanceLock::acquire(db.clone(), dir.path(), false).unwrap();
+            assert_eq!(first.overridden(), None);
+            assert!(matches!(
+                InstanceLock::acquire(db.clone(), dir.path(), false),
+                Err(NotSafe::InstanceLocked(_))
+            ));
+
+            let forced = InstanceLock::acquire(db.clone(), dir.path(), true).unwrap();
+            assert!(forced.overridden().is_some());
+            assert!(matches!(first.heartbeat(), Err(NotSafe::InstanceLocked(_))));
+            forced.heartbeat().unwrap();
+        }
+    }
+
+    #[test]
+    fn recent_heartbeat_refused() {
+        for backend in &DatabaseBackend::ALL {
+            let dir = tempdir().unwrap();
+            let db = SlashingProtection::open_or_create(*backend, dir.path()).unwrap();
+
+            // A process which holds no lock file, e.g. one on another host.
+            let mut heartbeat = Heartbeat {
+                instance_id: 1,
+                pid: 1,
+                last_seen: unix_now(),
+            };
+            db.write_heartbeat(Some(heartbeat)).unwrap();
+            assert!(matches!(
+                InstanceLock::acquire(db.clone(), dir.path(), false),
+                Err(NotSafe::InstanceLocked(_))
+            ));
+
+            heartbeat.last_seen -= HEARTBEAT_TIMEOUT.as_secs();
+            db.write_heartbeat(Some(heartbeat)).unwrap();
+            let lock = InstanceLock::acquire(db.clone(), dir.path(), false).unwrap();
+            assert_eq!(lock.overridden(), None);
+        }
+    }
+
+    #[test]
+    fn heartbeat_removed_on_drop() {
+        for backend in &DatabaseBackend::ALL {
+            let dir = tempdir().unwrap();
+            let db = SlashingProtection::open_or_create(*backend, dir.path()).unwrap();
+
+            let lock = InstanceLock::acquire(db.clone(), dir.path(), false).unwrap();
+            assert_eq!(
+                db.read_heartbeat().unwrap().map(|heartbeat| heartbeat.pid),
+                Some(std::process::id())
+            );
+            drop(lock);
+            assert_eq!(db.read_heartbeat().unwrap(), None);
+
+            InstanceLock::acquire(db, dir.path(), false).unwrap();
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! The "minimal" slashing protection interchange format, which contains only the latest block slot
+//! and attestation epochs signed by each validator.
+//!
+//! Importing a minimal record establishes a *watermark*: blocks at or below the slot, and
+//! attestations with an earlier source or an earlier or equal target, are refused. Watermarks are
+//! stored as ordinary rows with a signing root of `WATERMARK_SIGNING_ROOT`, which cannot be the
+//! signing root of any real message.
+
+use crate::NotSafe;
+use serde_derive::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use types::{Epoch, Hash256, PublicKey, Slot};
+
+/// The value of `interchange_format` for minimal interchange files.
+pub const MINIMAL_INTERCHANGE_FORMAT: &str = "minimal";
+/// The supported value of `interchange_format_version`.
+pub const INTERCHANGE_FORMAT_VERSION: u64 = 3;
+/// The signing root used for the rows which record imported watermarks.
+pub const WATERMARK_SIGNING_ROOT: Hash256 = Hash256::zero();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterchangeMetadata {
+    pub interchange_format: String,
+    pub interchange_format_version: u64,
+    pub genesis_validators_root: Hash256,
+}
+
+/// The latest messages signed by a single validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MinimalInterchangeData {
+    pub pubkey: PublicKey,
+    pub last_signed_block_slot: Option<Slot>,
+    pub last_signed_attestation_source_epoch: Option<Epoch>,
+    pub last_signed_attestation_target_epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Interchange {
+    pub metadata: InterchangeMetadata,
+    pub data: Vec<MinimalInterchangeData>,
+}
+
+#[derive(Debug)]
+pub enum InterchangeError {
+    UnsupportedFormat(String),
+    UnsupportedVersion(u64),
+    GenesisValidatorsRootMismatch {
+        interchange_file: Hash256,
+        client: Hash256,
+    },
+    /// Only one of the source and target epochs was supplied for the validator.
+    IncompleteAttestation(PublicKey),
+    /// The source epoch exceeds the target epoch for the validator.
+    SourceExceedsTarget(PublicKey),
+    JsonError(String),
+    NotSafe(NotSafe),
+}
+
+impl Interchange {
+    /// Creates a minimal interchange for the network with `genesis_validators_root`.
+    pub fn minimal(genesis_validators_root: Hash256, data: Vec<MinimalInterchangeData>) -> Self {
+        Self {
+            metadata: InterchangeMetadata {
+                interchange_format: MINIMAL_INTERCHANGE_FORMAT.to_string(),
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root,
+            },
+            data,
+        }
+    }
+
+    pub fn from_json_reader(reader: impl Read) -> Result<Self, InterchangeError> {
+        serde_json::from_reader(reader).map_err(|e| InterchangeError::JsonError(format!("{:?}", e)))
+    }
+
+    pub fn write_to(&self, writer: impl Write) -> Result<(), InterchangeError> {
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(|e| InterchangeError::JsonError(format!("{:?}", e)))
+    }
+
+    /// Checks that `self` is a minimal interchange for the network with `genesis_validators_root`,
+    /// and that each record is well-formed.
+    pub fn verify(&self, genesis_validators_root: Hash256) -> Result<(), InterchangeError> {
+        if self.metadata.interchange_format != MINIMAL_INTERCHANGE_FORMAT {
+            return Err(InterchangeError::UnsupportedFormat(
+                self.metadata.interchange_format.clone(),
+            ));
+        }
+
+        if self.metadata.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedVersion(
+                self.metadata.interchange_format_version,
+            ));
+        }
+
+        if self.metadata.genesis_validators_root != genesis_validators_root {
+            return Err(InterchangeError::GenesisValidatorsRootMismatch {
+                interchange_file: self.metadata.genesis_validators_root,
+                client: genesis_validators_root,
+            });
+        }
+
+        for record in &self.data {
+            record.attestation_watermark()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MinimalInterchangeData {
+    /// Returns the source and target epochs of the latest attestation, if any.
+    pub fn attestation_watermark(&self) -> Result<Option<(Epoch, Epoch)>, InterchangeError> {
+        match (
+            self.last_signed_attestation_source_epoch,
+            self.last_signed_attestation_target_epoch,
+        ) {
+            (Some(source), Some(target)) if source > target => {
+                Err(InterchangeError::SourceExceedsTarget(self.pubkey.clone()))
+            }
+            (Some(source), Some(target)) => Ok(Some((source, target))),
+            (None, None) => Ok(None),
+            _ => Err(InterchangeError::IncompleteAttestation(self.pubkey.clone())),
+        }
+    }
+}
+
+impl From<NotSafe> for InterchangeError {
+    fn from(e: NotSafe) -> Self {
+        InterchangeError::NotSafe(e)
+    }
+}
+
+impl From<rusqlite::Error> for InterchangeError {
+    fn from(e: rusqlite::Error) -> Self {
+        NotSafe::from(e).into()
+    }
+}
+
+impl From<r2d2::Error> for InterchangeError {
+    fn from(e: r2d2::Error) -> Self {
+        NotSafe::from(e).into()
+    }
+}
+
+impl From<lmdb::Error> for InterchangeError {
+    fn from(e: lmdb::Error) -> Self {
+        NotSafe::from(e).into()
+    }
+}
@@ -0,0 +1,372 @@
+#![cfg(test)]
+
+use crate::attestation_tests::attestation_data_builder;
+use crate::block_tests::block;
+use crate::interchange::*;
+use crate::test_utils::*;
+use crate::*;
+use tempfile::{tempdir, TempDir};
+use types::{Epoch, Hash256, Slot};
+
+const GENESIS_VALIDATORS_ROOT: Hash256 = Hash256::repeat_byte(0x42);
+
+fn create_db(backend: DatabaseBackend) -> (TempDir, SlashingProtection) {
+    let dir = tempdir().unwrap();
+    let db = SlashingProtection::open_or_create(backend, dir.path()).unwrap();
+    (dir, db)
+}
+
+fn record(
+    index: usize,
+    slot: Option<u64>,
+    attestation: Option<(u64, u64)>,
+) -> MinimalInterchangeData {
+    MinimalInterchangeData {
+        pubkey: pubkey(index),
+        last_signed_block_slot: slot.map(Slot::new),
+        last_signed_attestation_source_epoch: attestation.map(|(source, _)| Epoch::new(source)),
+        last_signed_attestation_target_epoch: attestation.map(|(_, target)| Epoch::new(target)),
+    }
+}
+
+fn import(db: &SlashingProtection, data: Vec<MinimalInterchangeData>) {
+    let interchange = Interchange::minimal(GENESIS_VALIDATORS_ROOT, data);
+    let num_imported = db
+        .import_minimal_interchange(&interchange, GENESIS_VALIDATORS_ROOT)
+        .unwrap();
+    assert_eq!(num_imported, interchange.data.len());
+}
+
+#[test]
+fn export_import_round_trip() {
+    for backend in &DatabaseBackend::ALL {
+        let (_dir, db) = create_db(*backend);
+        db.register_validators(vec![pubkey(0), pubkey(1), pubkey(2)].iter())
+            .unwrap();
+
+        for slot in &[3, 7, 5] {
+            db.check_and_insert_block_proposal(&pubkey(0), &block(*slot), DEFAULT_DOMAIN)
+                .unwrap();
+        }
+        for (source, target) in &[(0, 1), (1, 2), (2, 4)] {
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(*source, *target),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+        }
+        db.check_and_insert_attestation(
+            &pubkey(1),
+            &attestation_data_builder(3, 5),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap();
+
+        let exported = db
+            .export_minimal_interchange(GENESIS_VALIDATORS_ROOT)
+            .unwrap();
+        assert_eq!(
+            exported.data,
+            vec![
+                record(0, Some(7), Some((2, 4))),
+                record(1, None, Some((3, 5))),
+                record(2, None, None),
+            ]
+        );
+
+        // Importing into an empty database registers the validators and round-trips.
+        let (_dir, other_db) = create_db(*backend);
+        let mut buffer = vec![];
+        exported.write_to(&mut buffer).unwrap();
+        let interchange = Interchange::from_json_reader(&buffer[..]).unwrap();
+        assert_eq!(interchange, exported);
+        other_db
+            .import_minimal_interchange(&interchange, GENESIS_VALIDATORS_ROOT)
+            .unwrap();
+        assert_eq!(
+            other_db
+                .export_minimal_interchange(GENESIS_VALIDATORS_ROOT)
+                .unwrap(),
+            exported
+        );
+    }
+}
+
+#[test]
+fn block_watermark() {
+    for backend in &DatabaseBackend::ALL {
+        let (_dir, db) = create_db(*backend);
+        import(&db, vec![record(0, Some(10), None)]);
+
+        for slot in &[5, 10] {
+            assert_eq!(
+                db.check_and_insert_block_proposal(&pubkey(0), &block(*slot), DEFAULT_DOMAIN),
+                Err(NotSafe::InvalidBlock(
+                    InvalidBlock::SlotViolatesLowerBound {
+                        block_slot: Slot::new(*slot),
+                        bound_slot: Slot::new(10),
+                    }
+                ))
+            );
+        }
+
+        assert_eq!(
+            db.check_and_insert_block_proposal(&pubkey(0), &block(11), DEFAULT_DOMAIN),
+            Ok(Safe::Valid)
+        );
+    }
+}
+
+#[test]
+fn attestation_watermark() {
+    for backend in &DatabaseBackend::ALL {
+        let (_dir, db) = create_db(*backend);
+        import(&db, vec![record(0, None, Some((5, 8)))]);
+
+        let check = |source, target| {
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(source, target),
+                DEFAULT_DOMAIN,
+            )
+        };
+
+        assert_eq!(
+            check(4, 9),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::SourceLessThanLowerBound {
+                    source_epoch: Epoch::new(4),
+                    bound_epoch: Epoch::new(5),
+                }
+            ))
+        );
+        assert_eq!(
+            check(5, 8),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::TargetLessThanOrEqLowerBound {
+                    target_epoch: Epoch::new(8),
+                    bound_epoch: Epoch::new(8),
+                }
+            ))
+        );
+        assert_eq!(check(5, 9), Ok(Safe::Valid));
+        assert_eq!(check(9, 10), Ok(Safe::Valid));
+    }
+}
+
+// A watermark at the same target as an existing attestation must not allow it to be surrounded.
+#[test]
+fn attestation_watermark_same_target() {
+    for backend in &DatabaseBackend::ALL {
+        let (_dir, db) = create_db(*backend);
+        db.register_validator(&pubkey(0)).unwrap();
+        db.check_and_insert_attestation(
+            &pubkey(0),
+            &attestation_data_builder(6, 8),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap();
+
+        import(&db, vec![record(0, None, Some((5, 8)))]);
+
+        assert_eq!(
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(5, 9),
+                DEFAULT_DOMAIN
+            ),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::SourceLessThanLowerBound {
+                    source_epoch: Epoch::new(5),
+                    bound_epoch: Epoch::new(6),
+                }
+            ))
+        );
+    }
+}
+
+#[test]
+fn invalid_interchange() {
+    for backend in &DatabaseBackend::ALL {
+        let (_dir, db) = create_db(*backend);
+
+        let interchange = Interchange::minimal(GENESIS_VALIDATORS_ROOT, vec![]);
+        assert!(matches!(
+            db.import_minimal_interchange(&interchange, Hash256::zero()),
+            Err(InterchangeError::GenesisValidatorsRootMismatch { .. })
+        ));
+
+        let mut interchange = Interchange::minimal(GENESIS_VALIDATORS_ROOT, vec![]);
+        interchange.metadata.interchange_format = "complete".to_string();
+        assert!(matches!(
+            db.import_minimal_interchange(&interchange, GENESIS_VALIDATORS_ROOT),
+            Err(InterchangeError::UnsupportedFormat(_))
+        ));
+
+        let mut incomplete = record(0, None, Some((1, 2)));
+        incomplete.last_signed_attestation_source_epoch = None;
+        let interchange = Interchange::minimal(GENESIS_VALIDATORS_ROOT, vec![incomplete]);
+        assert!(matches!(
+            db.import_minimal_interchange(&interchange, GENESIS_VALIDATORS_ROOT),
+            Err(InterchangeError::IncompleteAttestation(_))
+        ));
+
+        let interchange =
+            Interchange::minimal(GENESIS_VALIDATORS_ROOT, vec![record(0, None, Some((3, 2)))]);
+        assert!(matches!(
+            db.import_minimal_interchange(&interchange, GENESIS_VALIDATORS_ROOT),
+            Err(InterchangeError::SourceExceedsTarget(_))
+        ));
+
+        // Nothing was imported.
+        assert_eq!(
+            db.export_minimal_interchange(GENESIS_VALIDATORS_ROOT)
+                .unwrap()
+                .data,
+            vec![]
+        );
+    }
+}
+
+/// Parses a test vector with a single step, importing a complete interchange for `pubkey(0)`.
+fn test_vector(
+    signed_blocks: &str,
+    signed_attestations: &str,
+    blocks: &str,
+    attestations: &str,
+) -> crate::interchange_vectors::TestCase {
+    let pubkey_json = serde_json::to_string(&pubkey(0)).unwrap();
+    let root_json = serde_json::to_string(&GENESIS_VALIDATORS_ROOT).unwrap();
+    let json = format!(
+        r#"{{
+            "name": "test_vector",
+            "genesis_validators_root": {root},
+            "steps": [{{
+                "should_succeed": true,
+                "contains_slashable_data": false,
+                "interchange": {{
+                    "metadata": {{
+                        "interchange_format_version": "5",
+                        "genesis_validators_root": {root}
+                    }},
+                    "data": [{{
+                        "pubkey": {pubkey},
+                        "signed_blocks": [{signed_blocks}],
+                        "signed_attestations": [{signed_attestations}]
+                    }}]
+                }},
+                "blocks": [{blocks}],
+                "attestations": [{attestations}]
+            }}]
+        }}"#,
+        root = root_json,
+        pubkey = pubkey_json,
+        signed_blocks = signed_blocks,
+        signed_attestations = signed_attestations,
+        blocks = blocks.replace("PUBKEY", &pubkey_json),
+        attestations = attestations.replace("PUBKEY", &pubkey_json),
+    );
+    serde_json::from_str(&json).unwrap()
+}
+
+const ROOT_1: &str = r#""0x1111111111111111111111111111111111111111111111111111111111111111""#;
+const ROOT_2: &str = r#""0x2222222222222222222222222222222222222222222222222222222222222222""#;
+
+#[test]
+fn interchange_vectors_without_signing_roots() {
+    let mut test_case = test_vector(
+        r#"{"slot": "10"}, {"slot": "8"}"#,
+        r#"{"source_epoch": "1", "target_epoch": "3"}"#,
+        r#"{"pubkey": PUBKEY, "slot": "10", "should_succeed": false},
+           {"pubkey": PUBKEY, "slot": "11", "should_succeed": true}"#,
+        r#"{"pubkey": PUBKEY, "source_epoch": "0", "target_epoch": "4", "should_succeed": false},
+           {"pubkey": PUBKEY, "source_epoch": "1", "target_epoch": "3", "should_succeed": false},
+           {"pubkey": PUBKEY, "source_epoch": "1", "target_epoch": "4", "should_succeed": true}"#,
+    );
+
+    for backend in &DatabaseBackend::ALL {
+        test_case.run(*backend).unwrap();
+    }
+
+    // Expecting the wrong outcome is reported as a failure.
+    test_case.steps[0].blocks[0].should_succeed = true;
+    for backend in &DatabaseBackend::ALL {
+        assert_eq!(test_case.run(*backend).unwrap_err().len(), 1);
+    }
+}
+
+#[test]
+fn interchange_vectors_with_signing_roots() {
+    let block = |slot, root: &str, should_succeed| {
+        format!(
+            r#"{{"pubkey": PUBKEY, "slot": "{}", "signing_root": {}, "should_succeed": {}}}"#,
+            slot, root, should_succeed
+        )
+    };
+    let attestation = |source, target, root: &str, should_succeed| {
+        format!(
+            r#"{{"pubkey": PUBKEY, "source_epoch": "{}", "target_epoch": "{}",
+                "signing_root": {}, "should_succeed": {}}}"#,
+            source, target, root, should_succeed
+        )
+    };
+
+    let test_case = test_vector(
+        &format!(
+            r#"{{"slot": "5", "signing_root": {root1}}}, {{"slot": "8", "signing_root": {root1}}}"#,
+            root1 = ROOT_1
+        ),
+        &format!(
+            r#"{{"source_epoch": "2", "target_epoch": "3", "signing_root": {root1}}},
+               {{"source_epoch": "3", "target_epoch": "6", "signing_root": {root1}}}"#,
+            root1 = ROOT_1
+        ),
+        &[
+            // Imported blocks may be signed again, but not replaced.
+            block(5, ROOT_1, true),
+            block(8, ROOT_1, true),
+            block(8, ROOT_2, false),
+            // Blocks below the earliest imported block are refused, others are not.
+            block(4, ROOT_2, false),
+            block(6, ROOT_2, true),
+            block(9, ROOT_2, true),
+        ]
+        .join(","),
+        &[
+            // Imported attestations may be signed again, but not replaced.
+            attestation(2, 3, ROOT_1, true),
+            attestation(3, 6, ROOT_1, true),
+            attestation(3, 6, ROOT_2, false),
+            // Attestations with a source or target below the earliest imported ones are refused.
+            attestation(1, 7, ROOT_2, false),
+            attestation(2, 2, ROOT_2, false),
+            // Surrounding and surrounded attestations are refused, others are not.
+            attestation(2, 7, ROOT_2, false),
+            attestation(4, 5, ROOT_2, false),
+            attestation(6, 7, ROOT_2, true),
+        ]
+        .join(","),
+    );
+
+    for backend in &DatabaseBackend::ALL {
+        test_case.run(*backend).unwrap();
+    }
+}
+
+#[test]
+fn interchange_vectors_wrong_genesis_validators_root() {
+    let mut test_case = test_vector(
+        r#"{"slot": "10"}"#,
+        "",
+        r#"{"pubkey": PUBKEY, "slot": "10", "should_succeed": false}"#,
+        "",
+    );
+    test_case.genesis_validators_root = Hash256::repeat_byte(0x43);
+    test_case.steps[0].should_succeed = false;
+
+    for backend in &DatabaseBackend::ALL {
+        // The validator was never registered, so signing fails.
+        test_case.run(*backend).unwrap();
+    }
+}
@@ -0,0 +1,317 @@
+//! Runs the standard slashing protection interchange test vectors against a slashing protection
+//! database of either backend.
+//!
+//! The vectors are published at:
+//!
+//! https://github.com/eth2-clients/slashing-protection-interchange-tests
+//!
+//! Each test case imports one or more interchange files and then attempts to sign blocks and
+//! attestations, checking whether each operation succeeds.
+//!
+//! The vectors use the "complete" interchange format, which lists every signed message, whereas
+//! the database only imports the minimal format. A complete interchange is therefore imported
+//! message by message, following the conditions of EIP-3076:
+//!
+//! - Messages with a signing root are recorded as signed, so that signing them again succeeds.
+//! - Watermarks refuse blocks below the earliest imported block, and attestations with a source
+//!   below the earliest imported source, or a target below the earliest imported target.
+//! - Messages without a signing root can never be signed again, and are imported as watermarks.
+//!   This is stricter than EIP-3076 requires, because it also refuses the earlier slots and
+//!   epochs.
+
+use crate::interchange::{Interchange, MinimalInterchangeData};
+use crate::{DatabaseBackend, InvalidAttestation, InvalidBlock, NotSafe, Safe, SlashingProtection};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use tempfile::tempdir;
+use types::{Epoch, Hash256, PublicKey, Slot};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteInterchangeMetadata {
+    pub genesis_validators_root: Hash256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteSignedBlock {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: Slot,
+    pub signing_root: Option<Hash256>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteSignedAttestation {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub source_epoch: Epoch,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub target_epoch: Epoch,
+    pub signing_root: Option<Hash256>,
+}
+
+/// Every message signed by a single validator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteInterchangeData {
+    pub pubkey: PublicKey,
+    pub signed_blocks: Vec<CompleteSignedBlock>,
+    pub signed_attestations: Vec<CompleteSignedAttestation>,
+}
+
+/// An interchange in the "complete" format used by the test vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteInterchange {
+    pub metadata: CompleteInterchangeMetadata,
+    pub data: Vec<CompleteInterchangeData>,
+}
+
+impl CompleteInterchange {
+    /// Imports every message of the interchange into `db`, registering any unknown validators.
+    ///
+    /// Messages which are below a watermark of `db` are already protected and are ignored. Errors
+    /// if the interchange is for another network, or if a message conflicts with one in `db`.
+    pub fn import(
+        &self,
+        db: &SlashingProtection,
+        genesis_validators_root: Hash256,
+    ) -> Result<(), String> {
+        if self.metadata.genesis_validators_root != genesis_validators_root {
+            return Err(format!(
+                "Interchange is for genesis validators root {:?}, not {:?}",
+                self.metadata.genesis_validators_root, genesis_validators_root
+            ));
+        }
+
+        // Register the validators before importing their messages.
+        let registrations = self
+            .data
+            .iter()
+            .map(|record| watermark(record.pubkey.clone(), None, None))
+            .collect();
+        import_minimal(db, registrations, genesis_validators_root)?;
+
+        let mut watermarks = vec![];
+
+        for record in &self.data {
+            for block in &record.signed_blocks {
+                if let Some(signing_root) = block.signing_root {
+                    let result = db.check_and_insert_block_signing_root(
+                        &record.pubkey,
+                        block.slot,
+                        signing_root,
+                    );
+                    check_imported(result, block)?;
+                }
+            }
+
+            for attestation in &record.signed_attestations {
+                if let Some(signing_root) = attestation.signing_root {
+                    let result = db.check_and_insert_attestation_signing_root(
+                        &record.pubkey,
+                        attestation.source_epoch,
+                        attestation.target_epoch,
+                        signing_root,
+                    );
+                    check_imported(result, attestation)?;
+                }
+            }
+
+            // Refuse blocks below the earliest block, and at or below any block without a
+            // signing root.
+            let block_watermark = std::cmp::max(
+                record
+                    .signed_blocks
+                    .iter()
+                    .map(|block| block.slot)
+                    .min()
+                    .and_then(|slot| slot.as_u64().checked_sub(1))
+                    .map(Slot::new),
+                record
+                    .signed_blocks
+                    .iter()
+                    .filter(|block| block.signing_root.is_none())
+                    .map(|block| block.slot)
+                    .max(),
+            );
+
+            // Refuse attestations with a source below the earliest source, or a target below the
+            // earliest target or at or below that of any attestation without a signing root.
+            let min_source = record
+                .signed_attestations
+                .iter()
+                .map(|attestation| attestation.source_epoch)
+                .min();
+            let target_watermark = std::cmp::max(
+                record
+                    .signed_attestations
+                    .iter()
+                    .map(|attestation| attestation.target_epoch)
+                    .min()
+                    .and_then(|epoch| epoch.as_u64().checked_sub(1))
+                    .map(Epoch::new),
+                record
+                    .signed_attestations
+                    .iter()
+                    .filter(|attestation| attestation.signing_root.is_none())
+                    .map(|attestation| attestation.target_epoch)
+                    .max(),
+            );
+            // The source watermark may only exceed the target watermark if the earliest source
+            // and target are those of the same attestation, which was imported above and can't
+            // be surrounded. Lowering it to the target watermark is therefore still safe.
+            let attestation_watermark = match (min_source, target_watermark) {
+                (Some(source), Some(target)) => Some((std::cmp::min(source, target), target)),
+                _ => None,
+            };
+
+            watermarks.push(watermark(
+                record.pubkey.clone(),
+                block_watermark,
+                attestation_watermark,
+            ));
+        }
+
+        import_minimal(db, watermarks, genesis_validators_root)
+    }
+}
+
+fn watermark(
+    pubkey: PublicKey,
+    slot: Option<Slot>,
+    attestation: Option<(Epoch, Epoch)>,
+) -> MinimalInterchangeData {
+    MinimalInterchangeData {
+        pubkey,
+        last_signed_block_slot: slot,
+        last_signed_attestation_source_epoch: attestation.map(|(source, _)| source),
+        last_signed_attestation_target_epoch: attestation.map(|(_, target)| target),
+    }
+}
+
+fn import_minimal(
+    db: &SlashingProtection,
+    data: Vec<MinimalInterchangeData>,
+    genesis_validators_root: Hash256,
+) -> Result<(), String> {
+    db.import_minimal_interchange(
+        &Interchange::minimal(genesis_validators_root, data),
+        genesis_validators_root,
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Unable to import watermarks: {:?}", e))
+}
+
+/// Returns an error if importing `message` failed for any reason other than it being below a
+/// watermark.
+fn check_imported(result: Result<Safe, NotSafe>, message: &impl fmt::Debug) -> Result<(), String> {
+    match result {
+        Ok(_)
+        | Err(NotSafe::InvalidBlock(InvalidBlock::SlotViolatesLowerBound { .. }))
+        | Err(NotSafe::InvalidAttestation(InvalidAttestation::SourceLessThanLowerBound {
+            ..
+        }))
+        | Err(NotSafe::InvalidAttestation(InvalidAttestation::TargetLessThanOrEqLowerBound {
+            ..
+        })) => Ok(()),
+        Err(e) => Err(format!("Unable to import {:?}: {:?}", message, e)),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestBlock {
+    pub pubkey: PublicKey,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: Slot,
+    #[serde(default)]
+    pub signing_root: Hash256,
+    pub should_succeed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestAttestation {
+    pub pubkey: PublicKey,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub source_epoch: Epoch,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub target_epoch: Epoch,
+    #[serde(default)]
+    pub signing_root: Hash256,
+    pub should_succeed: bool,
+}
+
+/// An interchange to import, followed by messages to sign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestStep {
+    pub should_succeed: bool,
+    pub interchange: CompleteInterchange,
+    pub blocks: Vec<TestBlock>,
+    pub attestations: Vec<TestAttestation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+    pub genesis_validators_root: Hash256,
+    pub steps: Vec<TestStep>,
+}
+
+impl TestCase {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        serde_json::from_reader(file).map_err(|e| format!("Unable to parse {:?}: {:?}", path, e))
+    }
+
+    /// Runs the test case against a new database of `backend`, returning a description of each
+    /// operation whose outcome differed from the expected outcome.
+    pub fn run(&self, backend: DatabaseBackend) -> Result<(), Vec<String>> {
+        let dir = tempdir().map_err(|e| vec![format!("Unable to create tempdir: {:?}", e)])?;
+        let db = SlashingProtection::open_or_create(backend, dir.path())
+            .map_err(|e| vec![format!("Unable to create database: {:?}", e)])?;
+
+        let mut failures = vec![];
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let result = step.interchange.import(&db, self.genesis_validators_root);
+            if result.is_ok() != step.should_succeed {
+                failures.push(format!(
+                    "step {}: import should_succeed: {}, got: {:?}",
+                    i, step.should_succeed, result
+                ));
+            }
+
+            for (j, block) in step.blocks.iter().enumerate() {
+                let result = db.check_and_insert_block_signing_root(
+                    &block.pubkey,
+                    block.slot,
+                    block.signing_root,
+                );
+                if result.is_ok() != block.should_succeed {
+                    failures.push(format!(
+                        "step {}, block {}: should_succeed: {}, got: {:?}",
+                        i, j, block.should_succeed, result
+                    ));
+                }
+            }
+
+            for (j, att) in step.attestations.iter().enumerate() {
+                let result = db.check_and_insert_attestation_signing_root(
+                    &att.pubkey,
+                    att.source_epoch,
+                    att.target_epoch,
+                    att.signing_root,
+                );
+                if result.is_ok() != att.should_succeed {
+                    failures.push(format!(
+                        "step {}, attestation {}: should_succeed: {}, got: {:?}",
+                        i, j, att.should_succeed, result
+                    ));
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
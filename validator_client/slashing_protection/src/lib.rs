@@ -1,11 +1,27 @@
 mod attestation_tests;
+mod backend;
 mod block_tests;
+mod file_watch;
+mod instance_lock;
+pub mod interchange;
+mod interchange_tests;
+pub mod interchange_vectors;
+mod lmdb_database;
+pub mod migration;
 mod parallel_tests;
 mod signed_attestation;
 mod signed_block;
 mod slashing_database;
 mod test_utils;
 
+pub use crate::backend::{DatabaseBackend, SlashingProtection};
+pub use crate::instance_lock::{
+    Heartbeat, InstanceLock, HEARTBEAT_INTERVAL, HEARTBEAT_TIMEOUT,
+    SLASHING_PROTECTION_LOCK_FILENAME,
+};
+pub use crate::interchange::{Interchange, InterchangeError, MinimalInterchangeData};
+pub use crate::lmdb_database::LmdbSlashingDatabase;
+pub use crate::migration::ValidatorHistory;
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::SlashingDatabase;
@@ -14,6 +30,11 @@ use std::io::{Error as IOError, ErrorKind};
 use std::string::ToString;
 use types::{Hash256, PublicKey};
 
+/// Path to the slashing protection database within the validator datadir.
+pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
+/// Path to the LMDB slashing protection database within the validator datadir.
+pub const SLASHING_PROTECTION_LMDB_FILENAME: &str = "slashing_protection.lmdb";
+
 /// The attestation or block is not safe to sign.
 ///
 /// This could be because it's slashable, or because an error occurred.
@@ -25,6 +46,16 @@ pub enum NotSafe {
     IOError(ErrorKind),
     SQLError(String),
     SQLPoolError(String),
+    LmdbError(String),
+    /// The database file was replaced or modified by another process while it was open.
+    ExternallyModified(String),
+    /// Another process is using the database, or has taken it over.
+    InstanceLocked(String),
+    /// The validator datadir contains a database of another backend, but not of the selected one.
+    BackendMismatch {
+        selected: DatabaseBackend,
+        existing: DatabaseBackend,
+    },
 }
 
 /// The attestation or block is safe to sign, and will not cause the signer to be slashed.
@@ -70,6 +101,12 @@ impl From<r2d2::Error> for NotSafe {
     }
 }
 
+impl From<lmdb::Error> for NotSafe {
+    fn from(error: lmdb::Error) -> Self {
+        NotSafe::LmdbError(format!("{:?}", error))
+    }
+}
+
 impl ToString for NotSafe {
     fn to_string(&self) -> String {
         format!("{:?}", self)
@@ -0,0 +1,975 @@
+//! An LMDB implementation of the slashing protection database, with the same interface and
+//! semantics as the SQLite `SlashingDatabase`.
+//!
+//! The SQLite database serialises all access through a single exclusive connection, which becomes
+//! a bottleneck at slot boundaries for validator clients with very many validators. LMDB still
+//! allows only one write transaction at a time (so that checking and inserting remain atomic), but
+//! its transactions are much cheaper.
+//!
+//! The database contains the following tables, in which integers are big-endian so that keys
+//! sort numerically:
+//!
+//! - `validators`: public key => validator ID
+//! - `metadata`: `next_validator_id` => validator ID, and `heartbeat` => instance ID ++ process ID
+//!    ++ time last seen
+//! - `signed_blocks`: validator ID ++ slot => signing root
+//! - `signed_attestations`: validator ID ++ target epoch => source epoch ++ signing root
+//! - `block_watermarks`: validator ID => greatest imported slot
+//! - `attestation_watermarks`: validator ID => greatest imported source epoch ++ greatest
+//!    imported target epoch
+//!
+//! As in the SQLite database, imported watermarks are also stored as signed blocks and
+//! attestations with a signing root of `WATERMARK_SIGNING_ROOT`. The watermark tables allow the
+//! lower bounds to be checked without scanning the history of the validator.
+
+use crate::file_watch::FileWatch;
+use crate::instance_lock::Heartbeat;
+use crate::interchange::{
+    Interchange, InterchangeError, MinimalInterchangeData, WATERMARK_SIGNING_ROOT,
+};
+use crate::signed_attestation::InvalidAttestation;
+use crate::signed_block::InvalidBlock;
+use crate::slashing_database::REGISTRATION_PROGRESS_INTERVAL;
+use crate::{NotSafe, Safe, SignedAttestation, SignedBlock, ValidatorHistory};
+use lmdb::{
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
+};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Arc;
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKey, SignedRoot, Slot};
+
+/// The maximum size of the database.
+///
+/// Space is only allocated as the database grows (except on Windows, where the file is
+/// pre-allocated), so this need only be large enough for many years of history.
+pub const LMDB_MAP_SIZE: usize = 16 * (1 << 30);
+
+const MAX_DBS: u32 = 6;
+
+const VALIDATORS_DB: &str = "validators";
+const METADATA_DB: &str = "metadata";
+const SIGNED_BLOCKS_DB: &str = "signed_blocks";
+const SIGNED_ATTESTATIONS_DB: &str = "signed_attestations";
+const BLOCK_WATERMARKS_DB: &str = "block_watermarks";
+const ATTESTATION_WATERMARKS_DB: &str = "attestation_watermarks";
+
+const NEXT_VALIDATOR_ID_KEY: &[u8] = b"next_validator_id";
+const HEARTBEAT_KEY: &[u8] = b"heartbeat";
+
+#[derive(Debug, Clone, Copy)]
+struct Databases {
+    validators: Database,
+    metadata: Database,
+    signed_blocks: Database,
+    signed_attestations: Database,
+    block_watermarks: Database,
+    attestation_watermarks: Database,
+}
+
+/// A slashing protection database stored in a single LMDB file.
+///
+/// The database must be opened at most once per process, as required by LMDB.
+#[derive(Debug, Clone)]
+pub struct LmdbSlashingDatabase {
+    env: Arc<Environment>,
+    dbs: Databases,
+    file_watch: Arc<FileWatch>,
+}
+
+impl LmdbSlashingDatabase {
+    /// Open an existing database at the given `path`, or create one if none exists.
+    pub fn open_or_create(path: &Path) -> Result<Self, NotSafe> {
+        if path.exists() {
+            Self::open(path)
+        } else {
+            Self::create(path)
+        }
+    }
+
+    /// Create a slashing database at the given path.
+    ///
+    /// Error if a database (or any file) already exists at `path`.
+    pub fn create(path: &Path) -> Result<Self, NotSafe> {
+        if path.exists() {
+            return Err(NotSafe::IOError(ErrorKind::AlreadyExists));
+        }
+        Self::open_env(path)
+    }
+
+    /// Open an existing `LmdbSlashingDatabase` from disk.
+    pub fn open(path: &Path) -> Result<Self, NotSafe> {
+        if !path.exists() {
+            return Err(NotSafe::IOError(ErrorKind::NotFound));
+        }
+        Self::open_env(path)
+    }
+
+    /// Open the environment at `path` (which is created, readable and writable only by its owner,
+    /// if it does not exist), and each of its tables.
+    fn open_env(path: &Path) -> Result<Self, NotSafe> {
+        let env = Environment::new()
+            .set_flags(EnvironmentFlags::NO_SUB_DIR)
+            .set_max_dbs(MAX_DBS)
+            .set_map_size(LMDB_MAP_SIZE)
+            .open_with_permissions(path, 0o600)?;
+
+        let create_db = |name| env.create_db(Some(name), DatabaseFlags::empty());
+        let dbs = Databases {
+            validators: create_db(VALIDATORS_DB)?,
+            metadata: create_db(METADATA_DB)?,
+            signed_blocks: create_db(SIGNED_BLOCKS_DB)?,
+            signed_attestations: create_db(SIGNED_ATTESTATIONS_DB)?,
+            block_watermarks: create_db(BLOCK_WATERMARKS_DB)?,
+            attestation_watermarks: create_db(ATTESTATION_WATERMARKS_DB)?,
+        };
+
+        Ok(Self {
+            env: Arc::new(env),
+            dbs,
+            file_watch: Arc::new(FileWatch::new(path)),
+        })
+    }
+
+    /// Register a validator with the slashing protection database.
+    ///
+    /// This allows the validator to record their signatures in the database, and check
+    /// for slashings.
+    pub fn register_validator(&self, validator_pk: &PublicKey) -> Result<(), NotSafe> {
+        self.register_validators(std::iter::once(validator_pk))
+    }
+
+    /// Register multiple validators with the slashing protection database.
+    pub fn register_validators<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKey>,
+    ) -> Result<(), NotSafe> {
+        let public_keys = public_keys.collect::<Vec<_>>();
+        self.register_validators_batch(&public_keys, |_, _| ())
+            .map(|_| ())
+    }
+
+    /// Register many validators with the slashing protection database in a single transaction,
+    /// returning the number of validators which were not already registered.
+    ///
+    /// Progress is reported as for `SlashingDatabase::register_validators_batch`.
+    pub fn register_validators_batch(
+        &self,
+        public_keys: &[&PublicKey],
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize, NotSafe> {
+        self.file_watch.guard(|| {
+            let mut txn = self.env.begin_rw_txn()?;
+            let mut num_registered = 0;
+
+            for (i, pubkey) in public_keys.iter().enumerate() {
+                let (_, is_new) = self.get_or_register_validator(&mut txn, pubkey)?;
+                if is_new {
+                    num_registered += 1;
+                }
+
+                if (i + 1) % REGISTRATION_PROGRESS_INTERVAL == 0 {
+                    progress(i + 1, public_keys.len());
+                }
+            }
+            txn.commit()?;
+
+            progress(public_keys.len(), public_keys.len());
+
+            Ok(num_registered)
+        })
+    }
+
+    /// Get the database-internal ID for a validator.
+    ///
+    /// This is NOT the same as a validator index, and depends on the ordering that validators
+    /// are registered with the slashing protection database (and may vary between machines).
+    fn get_validator_id(
+        &self,
+        txn: &impl Transaction,
+        public_key: &PublicKey,
+    ) -> Result<u64, NotSafe> {
+        get_optional(txn, self.dbs.validators, &public_key.serialize())?
+            .map(u64_from_bytes)
+            .transpose()?
+            .ok_or_else(|| NotSafe::UnregisteredValidator(public_key.clone()))
+    }
+
+    /// Get the database-internal ID for a validator, registering it if necessary. Returns `true`
+    /// alongside the ID if the validator was registered.
+    fn get_or_register_validator(
+        &self,
+        txn: &mut RwTransaction,
+        public_key: &PublicKey,
+    ) -> Result<(u64, bool), NotSafe> {
+        match self.get_validator_id(txn, public_key) {
+            Ok(validator_id) => Ok((validator_id, false)),
+            Err(NotSafe::UnregisteredValidator(_)) => {
+                let validator_id = get_optional(txn, self.dbs.metadata, &NEXT_VALIDATOR_ID_KEY)?
+                    .map(u64_from_bytes)
+                    .transpose()?
+                    .unwrap_or(0);
+
+                txn.put(
+                    self.dbs.validators,
+                    &public_key.serialize(),
+                    &validator_id.to_be_bytes(),
+                    WriteFlags::empty(),
+                )?;
+                txn.put(
+                    self.dbs.metadata,
+                    &NEXT_VALIDATOR_ID_KEY,
+                    &(validator_id + 1).to_be_bytes(),
+                    WriteFlags::empty(),
+                )?;
+
+                Ok((validator_id, true))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns every block signed by the validator with `validator_id`, in order of slot.
+    fn signed_blocks(
+        &self,
+        txn: &impl Transaction,
+        validator_id: u64,
+    ) -> Result<Vec<SignedBlock>, NotSafe> {
+        validator_entries(txn, self.dbs.signed_blocks, validator_id)?
+            .into_iter()
+            .map(|(slot, value)| {
+                Ok(SignedBlock::new(
+                    Slot::new(slot),
+                    hash256_from_bytes(value)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns every attestation signed by the validator with `validator_id`, in order of target
+    /// epoch.
+    fn signed_attestations(
+        &self,
+        txn: &impl Transaction,
+        validator_id: u64,
+    ) -> Result<Vec<SignedAttestation>, NotSafe> {
+        validator_entries(txn, self.dbs.signed_attestations, validator_id)?
+            .into_iter()
+            .map(|(target, value)| attestation_from_bytes(Epoch::new(target), value))
+            .collect()
+    }
+
+    /// Check a block proposal from the validator with `validator_id` for slash safety.
+    fn check_block_proposal(
+        &self,
+        txn: &impl Transaction,
+        validator_id: u64,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        let bound_slot = get_optional(txn, self.dbs.block_watermarks, &validator_id.to_be_bytes())?
+            .map(u64_from_bytes)
+            .transpose()?
+            .map(Slot::new);
+
+        if let Some(bound_slot) = bound_slot {
+            if slot <= bound_slot {
+                return Err(NotSafe::InvalidBlock(
+                    InvalidBlock::SlotViolatesLowerBound {
+                        block_slot: slot,
+                        bound_slot,
+                    },
+                ));
+            }
+        }
+
+        let existing_root = get_optional(
+            txn,
+            self.dbs.signed_blocks,
+            &validator_key(validator_id, slot.as_u64()),
+        )?
+        .map(hash256_from_bytes)
+        .transpose()?;
+
+        match existing_root {
+            // Same slot and same hash -> we're re-broadcasting a previously signed block
+            Some(existing_root) if existing_root == signing_root => Ok(Safe::SameData),
+            // Same slot but not the same hash -> it's a DoubleBlockProposal
+            Some(existing_root) => Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
+                SignedBlock::new(slot, existing_root),
+            ))),
+            None => Ok(Safe::Valid),
+        }
+    }
+
+    /// Check an attestation from the validator with `validator_id` for slash safety.
+    fn check_attestation(
+        &self,
+        txn: &impl Transaction,
+        validator_id: u64,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        // 0. Check that the attestation is above any imported watermark.
+        let watermark = get_optional(
+            txn,
+            self.dbs.attestation_watermarks,
+            &validator_id.to_be_bytes(),
+        )?
+        .map(epoch_pair_from_bytes)
+        .transpose()?;
+
+        if let Some((bound_source, bound_target)) = watermark {
+            if att_source_epoch < bound_source {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::SourceLessThanLowerBound {
+                        source_epoch: att_source_epoch,
+                        bound_epoch: bound_source,
+                    },
+                ));
+            }
+
+            if att_target_epoch <= bound_target {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::TargetLessThanOrEqLowerBound {
+                        target_epoch: att_target_epoch,
+                        bound_epoch: bound_target,
+                    },
+                ));
+            }
+        }
+
+        // 1. Check for a double vote. Namely, an existing attestation with the same target epoch,
+        //    and a different signing root.
+        let same_target_att = get_optional(
+            txn,
+            self.dbs.signed_attestations,
+            &validator_key(validator_id, att_target_epoch.as_u64()),
+        )?
+        .map(|value| attestation_from_bytes(att_target_epoch, value))
+        .transpose()?;
+
+        if let Some(existing_attestation) = same_target_att {
+            if existing_attestation.signing_root == signing_root {
+                return Ok(Safe::SameData);
+            } else {
+                return Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
+                    existing_attestation,
+                )));
+            }
+        }
+
+        let attestations = self.signed_attestations(txn, validator_id)?;
+
+        // 2. Check that no previous vote is surrounding `attestation`.
+        // If there is a surrounding attestation, we only return the most recent one.
+        if let Some(prev) = attestations.iter().rev().find(|prev| {
+            prev.source_epoch < att_source_epoch && prev.target_epoch > att_target_epoch
+        }) {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::PrevSurroundsNew { prev: prev.clone() },
+            ));
+        }
+
+        // 3. Check that no previous vote is surrounded by `attestation`.
+        // If there is a surrounded attestation, we only return the most recent one.
+        if let Some(prev) = attestations.iter().rev().find(|prev| {
+            prev.source_epoch > att_source_epoch && prev.target_epoch < att_target_epoch
+        }) {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::NewSurroundsPrev { prev: prev.clone() },
+            ));
+        }
+
+        // Everything has been checked, return Valid
+        Ok(Safe::Valid)
+    }
+
+    /// Insert a block, which should only be done in the same transaction as its check.
+    fn insert_block_proposal(
+        &self,
+        txn: &mut RwTransaction,
+        validator_id: u64,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Result<(), NotSafe> {
+        txn.put(
+            self.dbs.signed_blocks,
+            &validator_key(validator_id, slot.as_u64()),
+            &signing_root.as_bytes(),
+            WriteFlags::empty(),
+        )?;
+        Ok(())
+    }
+
+    /// Insert an attestation, which should only be done in the same transaction as its check.
+    fn insert_attestation(
+        &self,
+        txn: &mut RwTransaction,
+        validator_id: u64,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Result<(), NotSafe> {
+        let mut value = att_source_epoch.as_u64().to_be_bytes().to_vec();
+        value.extend_from_slice(signing_root.as_bytes());
+
+        txn.put(
+            self.dbs.signed_attestations,
+            &validator_key(validator_id, att_target_epoch.as_u64()),
+            &value,
+            WriteFlags::empty(),
+        )?;
+        Ok(())
+    }
+
+    /// Raise the block watermark of the validator with `validator_id` to `slot`, if it is lower.
+    fn raise_block_watermark(
+        &self,
+        txn: &mut RwTransaction,
+        validator_id: u64,
+        slot: Slot,
+    ) -> Result<(), NotSafe> {
+        let key = validator_id.to_be_bytes();
+        let existing = get_optional(txn, self.dbs.block_watermarks, &key)?
+            .map(u64_from_bytes)
+            .transpose()?;
+        let slot = existing.map_or(slot.as_u64(), |existing| existing.max(slot.as_u64()));
+
+        txn.put(
+            self.dbs.block_watermarks,
+            &key,
+            &slot.to_be_bytes(),
+            WriteFlags::empty(),
+        )?;
+        Ok(())
+    }
+
+    /// Raise the attestation watermarks of the validator with `validator_id` to `source` and
+    /// `target`, where they are lower.
+    fn raise_attestation_watermark(
+        &self,
+        txn: &mut RwTransaction,
+        validator_id: u64,
+        source: Epoch,
+        target: Epoch,
+    ) -> Result<(), NotSafe> {
+        let key = validator_id.to_be_bytes();
+        let (source, target) = match get_optional(txn, self.dbs.attestation_watermarks, &key)?
+            .map(epoch_pair_from_bytes)
+            .transpose()?
+        {
+            Some((existing_source, existing_target)) => {
+                (existing_source.max(source), existing_target.max(target))
+            }
+            None => (source, target),
+        };
+
+        let mut value = source.as_u64().to_be_bytes().to_vec();
+        value.extend_from_slice(&target.as_u64().to_be_bytes());
+        txn.put(
+            self.dbs.attestation_watermarks,
+            &key,
+            &value,
+            WriteFlags::empty(),
+        )?;
+        Ok(())
+    }
+
+    /// Check a block proposal for slash safety, and if it is safe, record it in the database.
+    ///
+    /// The checking and inserting happen atomically and exclusively, within a single write
+    /// transaction.
+    pub fn check_and_insert_block_proposal(
+        &self,
+        validator_pubkey: &PublicKey,
+        block_header: &BeaconBlockHeader,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.check_and_insert_block_signing_root(
+            validator_pubkey,
+            block_header.slot,
+            block_header.signing_root(domain),
+        )
+    }
+
+    /// As for `check_and_insert_block_proposal`, but for a block at `slot` with a precomputed
+    /// `signing_root`.
+    pub fn check_and_insert_block_signing_root(
+        &self,
+        validator_pubkey: &PublicKey,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.file_watch.guard(|| {
+            let mut txn = self.env.begin_rw_txn()?;
+            let validator_id = self.get_validator_id(&txn, validator_pubkey)?;
+
+            let safe = self.check_block_proposal(&txn, validator_id, slot, signing_root)?;
+
+            if safe != Safe::SameData {
+                self.insert_block_proposal(&mut txn, validator_id, slot, signing_root)?;
+            }
+
+            txn.commit()?;
+            Ok(safe)
+        })
+    }
+
+    /// Check an attestation for slash safety, and if it is safe, record it in the database.
+    ///
+    /// The checking and inserting happen atomically and exclusively, within a single write
+    /// transaction.
+    pub fn check_and_insert_attestation(
+        &self,
+        validator_pubkey: &PublicKey,
+        attestation: &AttestationData,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.check_and_insert_attestation_signing_root(
+            validator_pubkey,
+            attestation.source.epoch,
+            attestation.target.epoch,
+            attestation.signing_root(domain),
+        )
+    }
+
+    /// As for `check_and_insert_attestation`, but for an attestation with the given source and
+    /// target epochs and a precomputed `signing_root`.
+    pub fn check_and_insert_attestation_signing_root(
+        &self,
+        validator_pubkey: &PublicKey,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        // Although it's not required to avoid slashing, we disallow attestations
+        // which are obviously invalid by virtue of their source epoch exceeding their target.
+        if att_source_epoch > att_target_epoch {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::SourceExceedsTarget,
+            ));
+        }
+
+        self.file_watch.guard(|| {
+            let mut txn = self.env.begin_rw_txn()?;
+            let validator_id = self.get_validator_id(&txn, validator_pubkey)?;
+
+            let safe = self.check_attestation(
+                &txn,
+                validator_id,
+                att_source_epoch,
+                att_target_epoch,
+                signing_root,
+            )?;
+
+            if safe != Safe::SameData {
+                self.insert_attestation(
+                    &mut txn,
+                    validator_id,
+                    att_source_epoch,
+                    att_target_epoch,
+                    signing_root,
+                )?;
+            }
+
+            txn.commit()?;
+            Ok(safe)
+        })
+    }
+
+    /// Returns the public key and ID of each registered validator, in order of registration.
+    fn validators(&self, txn: &impl Transaction) -> Result<Vec<(PublicKey, u64)>, NotSafe> {
+        let mut cursor = txn.open_ro_cursor(self.dbs.validators)?;
+        let mut validators = cursor
+            .iter_start()
+            .map(|entry| {
+                let (key, value) = entry?;
+                let pubkey = PublicKey::deserialize(key).map_err(|e| {
+                    NotSafe::LmdbError(format!("Invalid public key in database: {:?}", e))
+                })?;
+                Ok((pubkey, u64_from_bytes(value)?))
+            })
+            .collect::<Result<Vec<_>, NotSafe>>()?;
+        validators.sort_by_key(|(_, validator_id)| *validator_id);
+        Ok(validators)
+    }
+
+    /// Export the latest block slot and attestation epochs signed by each registered validator,
+    /// as a minimal interchange.
+    ///
+    /// The source and target epochs are the greatest of any attestation, and need not be from the
+    /// same attestation.
+    pub fn export_minimal_interchange(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<Interchange, InterchangeError> {
+        let txn = self.env.begin_ro_txn().map_err(NotSafe::from)?;
+
+        let data = self
+            .validators(&txn)?
+            .into_iter()
+            .map(|(pubkey, validator_id)| {
+                let last_signed_block_slot = self
+                    .signed_blocks(&txn, validator_id)?
+                    .last()
+                    .map(|block| block.slot);
+                let attestations = self.signed_attestations(&txn, validator_id)?;
+
+                Ok(MinimalInterchangeData {
+                    pubkey,
+                    last_signed_block_slot,
+                    last_signed_attestation_source_epoch: attestations
+                        .iter()
+                        .map(|attestation| attestation.source_epoch)
+                        .max(),
+                    last_signed_attestation_target_epoch: attestations
+                        .last()
+                        .map(|attestation| attestation.target_epoch),
+                })
+            })
+            .collect::<Result<_, InterchangeError>>()?;
+
+        Ok(Interchange::minimal(genesis_validators_root, data))
+    }
+
+    /// Import each record of a minimal interchange as a watermark, registering any unknown
+    /// validators. Returns the number of records imported.
+    ///
+    /// Signing is subsequently refused for blocks at or below the last signed slot, and for
+    /// attestations with an earlier source epoch, or an earlier or equal target epoch, than the
+    /// last signed attestation.
+    pub fn import_minimal_interchange(
+        &self,
+        interchange: &Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<usize, InterchangeError> {
+        interchange.verify(genesis_validators_root)?;
+
+        self.file_watch
+            .guard(|| {
+                let mut txn = self.env.begin_rw_txn()?;
+
+                for record in &interchange.data {
+                    let (validator_id, _) =
+                        self.get_or_register_validator(&mut txn, &record.pubkey)?;
+
+                    if let Some(slot) = record.last_signed_block_slot {
+                        // Replacing a block at the same slot is safe, because no block at that
+                        // slot may be signed after the import.
+                        self.insert_block_proposal(
+                            &mut txn,
+                            validator_id,
+                            slot,
+                            WATERMARK_SIGNING_ROOT,
+                        )?;
+                        self.raise_block_watermark(&mut txn, validator_id, slot)?;
+                    }
+
+                    // Verified by `Interchange::verify`.
+                    if let Ok(Some((source, target))) = record.attestation_watermark() {
+                        // If an attestation with the same target exists, keep the greater source
+                        // epoch so that the attestation can't be surrounded after the import.
+                        let existing_source = get_optional(
+                            &txn,
+                            self.dbs.signed_attestations,
+                            &validator_key(validator_id, target.as_u64()),
+                        )?
+                        .map(|value| attestation_from_bytes(target, value))
+                        .transpose()?
+                        .map(|existing| existing.source_epoch);
+                        let source =
+                            existing_source.map_or(source, |existing| existing.max(source));
+
+                        self.insert_attestation(
+                            &mut txn,
+                            validator_id,
+                            source,
+                            target,
+                            WATERMARK_SIGNING_ROOT,
+                        )?;
+                        self.raise_attestation_watermark(&mut txn, validator_id, source, target)?;
+                    }
+                }
+
+                txn.commit()?;
+                Ok(interchange.data.len())
+            })
+            .map_err(Into::into)
+    }
+
+    /// Export every block and attestation signed by each registered validator, in order of
+    /// registration.
+    pub fn export_history(&self) -> Result<Vec<ValidatorHistory>, NotSafe> {
+        let txn = self.env.begin_ro_txn()?;
+
+        self.validators(&txn)?
+            .into_iter()
+            .map(|(pubkey, validator_id)| {
+                Ok(ValidatorHistory {
+                    pubkey,
+                    signed_blocks: self.signed_blocks(&txn, validator_id)?,
+                    signed_attestations: self.signed_attestations(&txn, validator_id)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Import the complete history of each validator, as exported by `export_history`,
+    /// registering any unknown validators.
+    ///
+    /// Blocks and attestations are inserted without being checked, replacing any with the same
+    /// slot or target epoch, so this should only be used to populate a new database.
+    pub fn import_history(&self, history: &[ValidatorHistory]) -> Result<(), NotSafe> {
+        self.file_watch.guard(|| {
+            let mut txn = self.env.begin_rw_txn()?;
+
+            for validator in history {
+                let (validator_id, _) =
+                    self.get_or_register_validator(&mut txn, &validator.pubkey)?;
+
+                for block in &validator.signed_blocks {
+                    self.insert_block_proposal(
+                        &mut txn,
+                        validator_id,
+                        block.slot,
+                        block.signing_root,
+                    )?;
+                    if block.signing_root == WATERMARK_SIGNING_ROOT {
+                        self.raise_block_watermark(&mut txn, validator_id, block.slot)?;
+                    }
+                }
+
+                for attestation in &validator.signed_attestations {
+                    self.insert_attestation(
+                        &mut txn,
+                        validator_id,
+                        attestation.source_epoch,
+                        attestation.target_epoch,
+                        attestation.signing_root,
+                    )?;
+                    if attestation.signing_root == WATERMARK_SIGNING_ROOT {
+                        self.raise_attestation_watermark(
+                            &mut txn,
+                            validator_id,
+                            attestation.source_epoch,
+                            attestation.target_epoch,
+                        )?;
+                    }
+                }
+            }
+
+            txn.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Read the heartbeat of the process using the database, if any.
+    pub fn read_heartbeat(&self) -> Result<Option<Heartbeat>, NotSafe> {
+        let txn = self.env.begin_ro_txn()?;
+        let heartbeat = get_optional(&txn, self.dbs.metadata, &HEARTBEAT_KEY)?
+            .map(heartbeat_from_bytes)
+            .transpose()?;
+        Ok(heartbeat)
+    }
+
+    /// Replace the heartbeat of the process using the database, or remove it if `None`.
+    pub fn write_heartbeat(&self, heartbeat: Option<Heartbeat>) -> Result<(), NotSafe> {
+        self.file_watch.guard(|| {
+            let mut txn = self.env.begin_rw_txn()?;
+
+            if let Some(heartbeat) = heartbeat {
+                let mut value = [0; 24];
+                value[..8].copy_from_slice(&heartbeat.instance_id.to_be_bytes());
+                value[8..16].copy_from_slice(&u64::from(heartbeat.pid).to_be_bytes());
+                value[16..].copy_from_slice(&heartbeat.last_seen.to_be_bytes());
+                txn.put(
+                    self.dbs.metadata,
+                    &HEARTBEAT_KEY,
+                    &value,
+                    WriteFlags::empty(),
+                )?;
+            } else {
+                match txn.del(self.dbs.metadata, &HEARTBEAT_KEY, None) {
+                    Ok(()) | Err(lmdb::Error::NotFound) => (),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            txn.commit()?;
+            Ok(())
+        })
+    }
+}
+
+/// Returns the value of `key` in `db`, or `None` if there is none.
+fn get_optional<'txn, K: AsRef<[u8]>>(
+    txn: &'txn impl Transaction,
+    db: Database,
+    key: &K,
+) -> Result<Option<&'txn [u8]>, NotSafe> {
+    match txn.get(db, key) {
+        Ok(value) => Ok(Some(value)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The key of the entry for `n` (e.g., a slot) of the validator with `validator_id`.
+fn validator_key(validator_id: u64, n: u64) -> [u8; 16] {
+    let mut key = [0; 16];
+    key[..8].copy_from_slice(&validator_id.to_be_bytes());
+    key[8..].copy_from_slice(&n.to_be_bytes());
+    key
+}
+
+/// Returns each entry of `db` belonging to the validator with `validator_id`, as `n` (e.g., a
+/// slot) and the value, in order of increasing `n`.
+fn validator_entries<'txn>(
+    txn: &'txn impl Transaction,
+    db: Database,
+    validator_id: u64,
+) -> Result<Vec<(u64, &'txn [u8])>, NotSafe> {
+    let mut cursor = txn.open_ro_cursor(db)?;
+    let mut entries = vec![];
+
+    for entry in cursor.iter_from(validator_key(validator_id, 0)) {
+        let (key, value) = entry?;
+        if key.len() != 16 || u64_from_bytes(&key[..8])? != validator_id {
+            break;
+        }
+        entries.push((u64_from_bytes(&key[8..])?, value));
+    }
+
+    Ok(entries)
+}
+
+fn u64_from_bytes(bytes: &[u8]) -> Result<u64, NotSafe> {
+    let mut array = [0; 8];
+    if bytes.len() != array.len() {
+        return Err(NotSafe::LmdbError(format!(
+            "Invalid length for u64: {}",
+            bytes.len()
+        )));
+    }
+    array.copy_from_slice(bytes);
+    Ok(u64::from_be_bytes(array))
+}
+
+fn hash256_from_bytes(bytes: &[u8]) -> Result<Hash256, NotSafe> {
+    if bytes.len() == 32 {
+        Ok(Hash256::from_slice(bytes))
+    } else {
+        Err(NotSafe::LmdbError(format!(
+            "Invalid length for Hash256: {}",
+            bytes.len()
+        )))
+    }
+}
+
+/// Parse a pair of epochs, e.g. the source and target of a watermark.
+fn epoch_pair_from_bytes(bytes: &[u8]) -> Result<(Epoch, Epoch), NotSafe> {
+    if bytes.len() != 16 {
+        return Err(NotSafe::LmdbError(format!(
+            "Invalid length for epoch pair: {}",
+            bytes.len()
+        )));
+    }
+    Ok((
+        Epoch::new(u64_from_bytes(&bytes[..8])?),
+        Epoch::new(u64_from_bytes(&bytes[8..])?),
+    ))
+}
+
+/// Parse the value of an attestation with `target_epoch` of `(source_epoch, signing_root)`.
+fn attestation_from_bytes(target_epoch: Epoch, bytes: &[u8]) -> Result<SignedAttestation, NotSafe> {
+    if bytes.len() != 40 {
+        return Err(NotSafe::LmdbError(format!(
+            "Invalid length for attestation: {}",
+            bytes.len()
+        )));
+    }
+    Ok(SignedAttestation::new(
+        Epoch::new(u64_from_bytes(&bytes[..8])?),
+        target_epoch,
+        hash256_from_bytes(&bytes[8..])?,
+    ))
+}
+
+fn heartbeat_from_bytes(bytes: &[u8]) -> Result<Heartbeat, NotSafe> {
+    if bytes.len() != 24 {
+        return Err(NotSafe::LmdbError(format!(
+            "Invalid length for heartbeat: {}",
+            bytes.len()
+        )));
+    }
+    let pid = u64_from_bytes(&bytes[8..16])?;
+    Ok(Heartbeat {
+        instance_id: u64_from_bytes(&bytes[..8])?,
+        pid: pid as u32,
+        last_seen: u64_from_bytes(&bytes[16..])?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_tests::block;
+    use crate::test_utils::{pubkey, DEFAULT_DOMAIN};
+    use tempfile::tempdir;
+
+    #[test]
+    fn open_non_existent_error() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.lmdb");
+        assert!(LmdbSlashingDatabase::open(&file).is_err());
+        assert!(!file.exists(), "should not create a database");
+    }
+
+    #[test]
+    fn double_create_error() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.lmdb");
+        drop(LmdbSlashingDatabase::create(&file).unwrap());
+        LmdbSlashingDatabase::create(&file).unwrap_err();
+    }
+
+    // Validators should keep their IDs, and the history should persist, after re-opening.
+    #[test]
+    fn reopen_and_register() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.lmdb");
+
+        let db = LmdbSlashingDatabase::create(&file).unwrap();
+        db.register_validators(vec![pubkey(0), pubkey(1)].iter())
+            .unwrap();
+        db.check_and_insert_block_proposal(&pubkey(1), &block(1), DEFAULT_DOMAIN)
+            .unwrap();
+        drop(db);
+
+        let db = LmdbSlashingDatabase::open(&file).unwrap();
+        let pubkeys = (0..3).map(pubkey).collect::<Vec<_>>();
+        assert_eq!(
+            db.register_validators_batch(&pubkeys.iter().collect::<Vec<_>>(), |_, _| ())
+                .unwrap(),
+            1
+        );
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&pubkey(1), &block(1), DEFAULT_DOMAIN),
+            Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+        ));
+
+        let history = db.export_history().unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|validator| validator.pubkey.clone())
+                .collect::<Vec<_>>(),
+            pubkeys,
+            "should export in order of registration"
+        );
+        assert_eq!(history[1].signed_blocks.len(), 1);
+    }
+}
@@ -0,0 +1,238 @@
+//! Migrates the signing history of the SQLite slashing protection database of a validator client
+//! to a new LMDB database.
+
+use crate::{
+    DatabaseBackend, LmdbSlashingDatabase, SignedAttestation, SignedBlock, SlashingDatabase,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use types::PublicKey;
+
+/// The suffix appended to the file name of the SQLite database once it has been migrated, so that
+/// it can't be used (and diverge from the LMDB database) by accident.
+pub const MIGRATED_SUFFIX: &str = "migrated";
+
+/// Every block and attestation signed by a validator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorHistory {
+    pub pubkey: PublicKey,
+    /// In order of slot.
+    pub signed_blocks: Vec<SignedBlock>,
+    /// In order of target epoch.
+    pub signed_attestations: Vec<SignedAttestation>,
+}
+
+/// The result of a successful migration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationSummary {
+    pub validators: usize,
+    pub signed_blocks: usize,
+    pub signed_attestations: usize,
+    /// The path to which the SQLite database was moved.
+    pub migrated_sqlite_path: PathBuf,
+}
+
+/// Copies the complete signing history of the SQLite database in `dir` to a new LMDB database in
+/// the same directory, then moves the SQLite database aside.
+///
+/// The validator client must not be running. The copy is verified by exporting the history of
+/// the LMDB database and comparing it to that of the SQLite database. If the migration fails, the
+/// partially-written LMDB database is removed and the SQLite database is left untouched.
+pub fn migrate_sqlite_to_lmdb(dir: &Path) -> Result<MigrationSummary, String> {
+    let sqlite_path = DatabaseBackend::Sqlite.path(dir);
+    let lmdb_path = DatabaseBackend::Lmdb.path(dir);
+
+    if lmdb_path.exists() {
+        return Err(format!(
+            "An LMDB database already exists at {:?}",
+            lmdb_path
+        ));
+    }
+
+    let sqlite_db = SlashingDatabase::open(&sqlite_path).map_err(|e| {
+        format!(
+            "Unable to open slashing protection database at {:?}: {:?}",
+            sqlite_path, e
+        )
+    })?;
+    let history = sqlite_db
+        .export_history()
+        .map_err(|e| format!("Unable to read slashing protection data: {:?}", e))?;
+
+    let copy = || {
+        let lmdb_db = LmdbSlashingDatabase::create(&lmdb_path)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", lmdb_path, e))?;
+        lmdb_db
+            .import_history(&history)
+            .map_err(|e| format!("Unable to write slashing protection data: {:?}", e))?;
+
+        let copied = lmdb_db
+            .export_history()
+            .map_err(|e| format!("Unable to read migrated slashing protection data: {:?}", e))?;
+        if copied != history {
+            return Err("Migrated slashing protection data does not match the original".into());
+        }
+        Ok(())
+    };
+
+    if let Err(e) = copy() {
+        let _ = fs::remove_file(&lmdb_path);
+        let _ = fs::remove_file(lmdb_lock_path(&lmdb_path));
+        return Err(e);
+    }
+
+    // Release the exclusive lock on the SQLite database before moving it.
+    drop(sqlite_db);
+
+    let mut migrated_sqlite_path = sqlite_path.clone().into_os_string();
+    migrated_sqlite_path.push(format!(".{}", MIGRATED_SUFFIX));
+    let migrated_sqlite_path = PathBuf::from(migrated_sqlite_path);
+    fs::rename(&sqlite_path, &migrated_sqlite_path).map_err(|e| {
+        format!(
+            "Migrated to {:?}, but unable to move {:?} aside, remove it before starting the \
+            validator client: {:?}",
+            lmdb_path, sqlite_path, e
+        )
+    })?;
+
+    Ok(MigrationSummary {
+        validators: history.len(),
+        signed_blocks: history
+            .iter()
+            .map(|validator| validator.signed_blocks.len())
+            .sum(),
+        signed_attestations: history
+            .iter()
+            .map(|validator| validator.signed_attestations.len())
+            .sum(),
+        migrated_sqlite_path,
+    })
+}
+
+/// The path of the lock file which LMDB creates alongside the database at `lmdb_path`.
+fn lmdb_lock_path(lmdb_path: &Path) -> PathBuf {
+    let mut path = lmdb_path.to_path_buf().into_os_string();
+    path.push("-lock");
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attestation_tests::attestation_data_builder;
+    use crate::block_tests::block;
+    use crate::interchange::{Interchange, MinimalInterchangeData};
+    use crate::test_utils::{pubkey, DEFAULT_DOMAIN};
+    use crate::{InvalidAttestation, InvalidBlock, NotSafe, SlashingProtection};
+    use tempfile::tempdir;
+    use types::{Epoch, Hash256, Slot};
+
+    #[test]
+    fn migrate_and_continue_signing() {
+        let dir = tempdir().unwrap();
+        let genesis_validators_root = Hash256::repeat_byte(1);
+
+        let sqlite_db =
+            SlashingDatabase::create(&DatabaseBackend::Sqlite.path(dir.path())).unwrap();
+        sqlite_db
+            .register_validators(vec![pubkey(0), pubkey(1)].iter())
+            .unwrap();
+        for slot in &[1, 3] {
+            sqlite_db
+                .check_and_insert_block_proposal(&pubkey(0), &block(*slot), DEFAULT_DOMAIN)
+                .unwrap();
+        }
+        sqlite_db
+            .check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(1, 5),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+        let interchange = Interchange::minimal(
+            genesis_validators_root,
+            vec![MinimalInterchangeData {
+                pubkey: pubkey(1),
+                last_signed_block_slot: Some(Slot::new(10)),
+                last_signed_attestation_source_epoch: Some(Epoch::new(2)),
+                last_signed_attestation_target_epoch: Some(Epoch::new(3)),
+            }],
+        );
+        sqlite_db
+            .import_minimal_interchange(&interchange, genesis_validators_root)
+            .unwrap();
+        let history = sqlite_db.export_history().unwrap();
+        drop(sqlite_db);
+
+        let summary = migrate_sqlite_to_lmdb(dir.path()).unwrap();
+        assert_eq!(summary.validators, 2);
+        assert_eq!(summary.signed_blocks, 3);
+        assert_eq!(summary.signed_attestations, 2);
+        assert!(!DatabaseBackend::Sqlite.path(dir.path()).exists());
+        assert!(summary.migrated_sqlite_path.exists());
+
+        assert!(
+            migrate_sqlite_to_lmdb(dir.path()).is_err(),
+            "should not migrate twice"
+        );
+
+        let db = SlashingProtection::open_or_create(DatabaseBackend::Lmdb, dir.path()).unwrap();
+        assert_eq!(db.export_history().unwrap(), history);
+
+        // Slashable messages and those below the imported watermarks should be refused.
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&pubkey(0), &block(3), DEFAULT_DOMAIN),
+            Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(2, 4),
+                DEFAULT_DOMAIN
+            ),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::PrevSurroundsNew { .. }
+            ))
+        ));
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&pubkey(1), &block(10), DEFAULT_DOMAIN),
+            Err(NotSafe::InvalidBlock(
+                InvalidBlock::SlotViolatesLowerBound { .. }
+            ))
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(
+                &pubkey(1),
+                &attestation_data_builder(2, 3),
+                DEFAULT_DOMAIN
+            ),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::TargetLessThanOrEqLowerBound { .. }
+            ))
+        ));
+
+        db.check_and_insert_block_proposal(&pubkey(0), &block(4), DEFAULT_DOMAIN)
+            .unwrap();
+        db.check_and_insert_attestation(
+            &pubkey(1),
+            &attestation_data_builder(2, 4),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn refuse_other_backend() {
+        let dir = tempdir().unwrap();
+        drop(SlashingProtection::open_or_create(DatabaseBackend::Sqlite, dir.path()).unwrap());
+
+        assert_eq!(
+            SlashingProtection::open_or_create(DatabaseBackend::Lmdb, dir.path()).unwrap_err(),
+            NotSafe::BackendMismatch {
+                selected: DatabaseBackend::Lmdb,
+                existing: DatabaseBackend::Sqlite,
+            }
+        );
+        assert!(!DatabaseBackend::Lmdb.path(dir.path()).exists());
+    }
+}
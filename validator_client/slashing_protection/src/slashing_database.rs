@@ -1,12 +1,19 @@
+use crate::file_watch::FileWatch;
+use crate::instance_lock::Heartbeat;
+use crate::interchange::{
+    Interchange, InterchangeError, MinimalInterchangeData, WATERMARK_SIGNING_ROOT,
+};
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
-use crate::{NotSafe, Safe, SignedAttestation, SignedBlock};
+use crate::{NotSafe, Safe, SignedAttestation, SignedBlock, ValidatorHistory};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
+use std::collections::HashSet;
 use std::fs::{File, OpenOptions};
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
-use types::{AttestationData, BeaconBlockHeader, Hash256, PublicKey, SignedRoot};
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKey, SignedRoot, Slot};
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
 
@@ -20,9 +27,17 @@ pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 #[cfg(test)]
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// The number of public keys processed by `SlashingDatabase::register_validators_batch` between
+/// each report of progress.
+#[cfg(not(test))]
+pub const REGISTRATION_PROGRESS_INTERVAL: usize = 1_000;
+#[cfg(test)]
+pub const REGISTRATION_PROGRESS_INTERVAL: usize = 4;
+
 #[derive(Debug, Clone)]
 pub struct SlashingDatabase {
     conn_pool: Pool,
+    file_watch: Arc<FileWatch>,
 }
 
 impl SlashingDatabase {
@@ -80,13 +95,20 @@ impl SlashingDatabase {
             params![],
         )?;
 
-        Ok(Self { conn_pool })
+        Ok(Self::from_conn_pool(conn_pool, path))
     }
 
     /// Open an existing `SlashingDatabase` from disk.
     pub fn open(path: &Path) -> Result<Self, NotSafe> {
         let conn_pool = Self::open_conn_pool(&path)?;
-        Ok(Self { conn_pool })
+        Ok(Self::from_conn_pool(conn_pool, path))
+    }
+
+    fn from_conn_pool(conn_pool: Pool, path: &Path) -> Self {
+        Self {
+            conn_pool,
+            file_watch: Arc::new(FileWatch::new(path)),
+        }
     }
 
     /// Open a new connection pool with all of the necessary settings and tweaks.
@@ -142,18 +164,52 @@ impl SlashingDatabase {
         &self,
         public_keys: impl Iterator<Item = &'a PublicKey>,
     ) -> Result<(), NotSafe> {
-        let mut conn = self.conn_pool.get()?;
-        let txn = conn.transaction()?;
-        {
-            let mut stmt = txn.prepare("INSERT INTO validators (public_key) VALUES (?1)")?;
+        let public_keys = public_keys.collect::<Vec<_>>();
+        self.register_validators_batch(&public_keys, |_, _| ())
+            .map(|_| ())
+    }
 
-            for pubkey in public_keys {
-                stmt.execute(&[pubkey.to_hex_string()])?;
+    /// Register many validators with the slashing protection database in a single transaction,
+    /// returning the number of validators which were not already registered.
+    ///
+    /// Validators which are already registered are skipped. After every
+    /// `REGISTRATION_PROGRESS_INTERVAL` public keys, and once all have been processed, `progress`
+    /// is called with the number of public keys processed and the total number.
+    pub fn register_validators_batch(
+        &self,
+        public_keys: &[&PublicKey],
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize, NotSafe> {
+        self.file_watch.guard(|| {
+            let mut conn = self.conn_pool.get()?;
+            let txn = conn.transaction()?;
+            let mut num_registered = 0;
+            {
+                let mut registered = txn
+                    .prepare("SELECT public_key FROM validators")?
+                    .query_map(params![], |row| row.get(0))?
+                    .collect::<Result<HashSet<String>, _>>()?;
+                let mut stmt = txn.prepare("INSERT INTO validators (public_key) VALUES (?1)")?;
+
+                for (i, pubkey) in public_keys.iter().enumerate() {
+                    let pubkey_hex = pubkey.to_hex_string();
+                    if !registered.contains(&pubkey_hex) {
+                        stmt.execute(params![pubkey_hex])?;
+                        registered.insert(pubkey_hex);
+                        num_registered += 1;
+                    }
+
+                    if (i + 1) % REGISTRATION_PROGRESS_INTERVAL == 0 {
+                        progress(i + 1, public_keys.len());
+                    }
+                }
             }
-        }
-        txn.commit()?;
+            txn.commit()?;
 
-        Ok(())
+            progress(public_keys.len(), public_keys.len());
+
+            Ok(num_registered)
+        })
     }
 
     /// Get the database-internal ID for a validator.
@@ -175,25 +231,44 @@ impl SlashingDatabase {
         &self,
         txn: &Transaction,
         validator_pubkey: &PublicKey,
-        block_header: &BeaconBlockHeader,
-        domain: Hash256,
+        slot: Slot,
+        signing_root: Hash256,
     ) -> Result<Safe, NotSafe> {
         let validator_id = Self::get_validator_id(txn, validator_pubkey)?;
 
+        let bound_slot: Option<Slot> = txn
+            .prepare(
+                "SELECT MAX(slot)
+                 FROM signed_blocks
+                 WHERE validator_id = ?1 AND signing_root = ?2",
+            )?
+            .query_row(
+                params![validator_id, WATERMARK_SIGNING_ROOT.as_bytes()],
+                |row| row.get(0),
+            )?;
+
+        if let Some(bound_slot) = bound_slot {
+            if slot <= bound_slot {
+                return Err(NotSafe::InvalidBlock(
+                    InvalidBlock::SlotViolatesLowerBound {
+                        block_slot: slot,
+                        bound_slot,
+                    },
+                ));
+            }
+        }
+
         let existing_block = txn
             .prepare(
                 "SELECT slot, signing_root
                  FROM signed_blocks
                  WHERE validator_id = ?1 AND slot = ?2",
             )?
-            .query_row(
-                params![validator_id, block_header.slot],
-                SignedBlock::from_row,
-            )
+            .query_row(params![validator_id, slot], SignedBlock::from_row)
             .optional()?;
 
         if let Some(existing_block) = existing_block {
-            if existing_block.signing_root == block_header.signing_root(domain) {
+            if existing_block.signing_root == signing_root {
                 // Same slot and same hash -> we're re-broadcasting a previously signed block
                 Ok(Safe::SameData)
             } else {
@@ -212,12 +287,10 @@ impl SlashingDatabase {
         &self,
         txn: &Transaction,
         validator_pubkey: &PublicKey,
-        attestation: &AttestationData,
-        domain: Hash256,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
     ) -> Result<Safe, NotSafe> {
-        let att_source_epoch = attestation.source.epoch;
-        let att_target_epoch = attestation.target.epoch;
-
         // Although it's not required to avoid slashing, we disallow attestations
         // which are obviously invalid by virtue of their source epoch exceeding their target.
         if att_source_epoch > att_target_epoch {
@@ -228,6 +301,40 @@ impl SlashingDatabase {
 
         let validator_id = Self::get_validator_id(txn, validator_pubkey)?;
 
+        // 0. Check that the attestation is above any imported watermark.
+        let (bound_source, bound_target): (Option<Epoch>, Option<Epoch>) = txn
+            .prepare(
+                "SELECT MAX(source_epoch), MAX(target_epoch)
+                 FROM signed_attestations
+                 WHERE validator_id = ?1 AND signing_root = ?2",
+            )?
+            .query_row(
+                params![validator_id, WATERMARK_SIGNING_ROOT.as_bytes()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+
+        if let Some(bound_epoch) = bound_source {
+            if att_source_epoch < bound_epoch {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::SourceLessThanLowerBound {
+                        source_epoch: att_source_epoch,
+                        bound_epoch,
+                    },
+                ));
+            }
+        }
+
+        if let Some(bound_epoch) = bound_target {
+            if att_target_epoch <= bound_epoch {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::TargetLessThanOrEqLowerBound {
+                        target_epoch: att_target_epoch,
+                        bound_epoch,
+                    },
+                ));
+            }
+        }
+
         // 1. Check for a double vote. Namely, an existing attestation with the same target epoch,
         //    and a different signing root.
         let same_target_att = txn
@@ -245,7 +352,7 @@ impl SlashingDatabase {
         if let Some(existing_attestation) = same_target_att {
             // If the new attestation is identical to the existing attestation, then we already
             // know that it is safe, and can return immediately.
-            if existing_attestation.signing_root == attestation.signing_root(domain) {
+            if existing_attestation.signing_root == signing_root {
                 return Ok(Safe::SameData);
             // Otherwise if the hashes are different, this is a double vote.
             } else {
@@ -311,19 +418,15 @@ impl SlashingDatabase {
         &self,
         txn: &Transaction,
         validator_pubkey: &PublicKey,
-        block_header: &BeaconBlockHeader,
-        domain: Hash256,
+        slot: Slot,
+        signing_root: Hash256,
     ) -> Result<(), NotSafe> {
         let validator_id = Self::get_validator_id(txn, validator_pubkey)?;
 
         txn.execute(
             "INSERT INTO signed_blocks (validator_id, slot, signing_root)
              VALUES (?1, ?2, ?3)",
-            params![
-                validator_id,
-                block_header.slot,
-                block_header.signing_root(domain).as_bytes()
-            ],
+            params![validator_id, slot, signing_root.as_bytes()],
         )?;
         Ok(())
     }
@@ -336,8 +439,9 @@ impl SlashingDatabase {
         &self,
         txn: &Transaction,
         validator_pubkey: &PublicKey,
-        attestation: &AttestationData,
-        domain: Hash256,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
     ) -> Result<(), NotSafe> {
         let validator_id = Self::get_validator_id(txn, validator_pubkey)?;
 
@@ -346,9 +450,9 @@ impl SlashingDatabase {
              VALUES (?1, ?2, ?3, ?4)",
             params![
                 validator_id,
-                attestation.source.epoch,
-                attestation.target.epoch,
-                attestation.signing_root(domain).as_bytes()
+                att_source_epoch,
+                att_target_epoch,
+                signing_root.as_bytes()
             ],
         )?;
         Ok(())
@@ -366,17 +470,34 @@ impl SlashingDatabase {
         block_header: &BeaconBlockHeader,
         domain: Hash256,
     ) -> Result<Safe, NotSafe> {
-        let mut conn = self.conn_pool.get()?;
-        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        self.check_and_insert_block_signing_root(
+            validator_pubkey,
+            block_header.slot,
+            block_header.signing_root(domain),
+        )
+    }
 
-        let safe = self.check_block_proposal(&txn, validator_pubkey, block_header, domain)?;
+    /// As for `check_and_insert_block_proposal`, but for a block at `slot` with a precomputed
+    /// `signing_root`.
+    pub fn check_and_insert_block_signing_root(
+        &self,
+        validator_pubkey: &PublicKey,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.file_watch.guard(|| {
+            let mut conn = self.conn_pool.get()?;
+            let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
 
-        if safe != Safe::SameData {
-            self.insert_block_proposal(&txn, validator_pubkey, block_header, domain)?;
-        }
+            let safe = self.check_block_proposal(&txn, validator_pubkey, slot, signing_root)?;
 
-        txn.commit()?;
-        Ok(safe)
+            if safe != Safe::SameData {
+                self.insert_block_proposal(&txn, validator_pubkey, slot, signing_root)?;
+            }
+
+            txn.commit()?;
+            Ok(safe)
+        })
     }
 
     /// Check an attestation for slash safety, and if it is safe, record it in the database.
@@ -391,24 +512,294 @@ impl SlashingDatabase {
         attestation: &AttestationData,
         domain: Hash256,
     ) -> Result<Safe, NotSafe> {
-        let mut conn = self.conn_pool.get()?;
-        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        self.check_and_insert_attestation_signing_root(
+            validator_pubkey,
+            attestation.source.epoch,
+            attestation.target.epoch,
+            attestation.signing_root(domain),
+        )
+    }
+
+    /// As for `check_and_insert_attestation`, but for an attestation with the given source and
+    /// target epochs and a precomputed `signing_root`.
+    pub fn check_and_insert_attestation_signing_root(
+        &self,
+        validator_pubkey: &PublicKey,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.file_watch.guard(|| {
+            let mut conn = self.conn_pool.get()?;
+            let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+            let safe = self.check_attestation(
+                &txn,
+                validator_pubkey,
+                att_source_epoch,
+                att_target_epoch,
+                signing_root,
+            )?;
+
+            if safe != Safe::SameData {
+                self.insert_attestation(
+                    &txn,
+                    validator_pubkey,
+                    att_source_epoch,
+                    att_target_epoch,
+                    signing_root,
+                )?;
+            }
+
+            txn.commit()?;
+            Ok(safe)
+        })
+    }
+
+    /// Export the latest block slot and attestation epochs signed by each registered validator,
+    /// as a minimal interchange.
+    ///
+    /// The source and target epochs are the greatest of any attestation, and need not be from the
+    /// same attestation.
+    pub fn export_minimal_interchange(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<Interchange, InterchangeError> {
+        let conn = self.conn_pool.get()?;
+
+        let data = conn
+            .prepare(
+                "SELECT public_key,
+                    (SELECT MAX(slot) FROM signed_blocks WHERE validator_id = validators.id),
+                    (SELECT MAX(source_epoch) FROM signed_attestations
+                     WHERE validator_id = validators.id),
+                    (SELECT MAX(target_epoch) FROM signed_attestations
+                     WHERE validator_id = validators.id)
+                 FROM validators
+                 ORDER BY id ASC",
+            )?
+            .query_map(params![], |row| {
+                let pubkey: String = row.get(0)?;
+                Ok((
+                    pubkey,
+                    row.get::<_, Option<Slot>>(1)?,
+                    row.get::<_, Option<Epoch>>(2)?,
+                    row.get::<_, Option<Epoch>>(3)?,
+                ))
+            })?
+            .map(|row| {
+                let (pubkey, last_signed_block_slot, source, target) = row?;
+
+                Ok(MinimalInterchangeData {
+                    pubkey: pubkey_from_hex(&pubkey)?,
+                    last_signed_block_slot,
+                    last_signed_attestation_source_epoch: source,
+                    last_signed_attestation_target_epoch: target,
+                })
+            })
+            .collect::<Result<_, InterchangeError>>()?;
+
+        Ok(Interchange::minimal(genesis_validators_root, data))
+    }
+
+    /// Export every block and attestation signed by each registered validator, in order of
+    /// registration.
+    pub fn export_history(&self) -> Result<Vec<ValidatorHistory>, NotSafe> {
+        let conn = self.conn_pool.get()?;
+
+        let validators = conn
+            .prepare("SELECT id, public_key FROM validators ORDER BY id ASC")?
+            .query_map(params![], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, String)>, _>>()?;
+
+        let mut blocks_stmt = conn.prepare(
+            "SELECT slot, signing_root
+             FROM signed_blocks
+             WHERE validator_id = ?1
+             ORDER BY slot ASC",
+        )?;
+        let mut attestations_stmt = conn.prepare(
+            "SELECT source_epoch, target_epoch, signing_root
+             FROM signed_attestations
+             WHERE validator_id = ?1
+             ORDER BY target_epoch ASC",
+        )?;
 
-        let safe = self.check_attestation(&txn, validator_pubkey, attestation, domain)?;
+        validators
+            .into_iter()
+            .map(|(validator_id, pubkey)| {
+                Ok(ValidatorHistory {
+                    pubkey: pubkey_from_hex(&pubkey)?,
+                    signed_blocks: blocks_stmt
+                        .query_map(params![validator_id], SignedBlock::from_row)?
+                        .collect::<Result<_, _>>()?,
+                    signed_attestations: attestations_stmt
+                        .query_map(params![validator_id], SignedAttestation::from_row)?
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect()
+    }
 
-        if safe != Safe::SameData {
-            self.insert_attestation(&txn, validator_pubkey, attestation, domain)?;
+    /// Import each record of a minimal interchange as a watermark, registering any unknown
+    /// validators. Returns the number of records imported.
+    ///
+    /// Signing is subsequently refused for blocks at or below the last signed slot, and for
+    /// attestations with an earlier source epoch, or an earlier or equal target epoch, than the
+    /// last signed attestation.
+    pub fn import_minimal_interchange(
+        &self,
+        interchange: &Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<usize, InterchangeError> {
+        interchange.verify(genesis_validators_root)?;
+
+        self.file_watch
+            .guard(|| {
+                let mut conn = self.conn_pool.get()?;
+                let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+                for record in &interchange.data {
+                    let validator_id = match Self::get_validator_id(&txn, &record.pubkey) {
+                        Ok(id) => id,
+                        Err(NotSafe::UnregisteredValidator(_)) => {
+                            txn.execute(
+                                "INSERT INTO validators (public_key) VALUES (?1)",
+                                params![record.pubkey.to_hex_string()],
+                            )?;
+                            txn.last_insert_rowid()
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    if let Some(slot) = record.last_signed_block_slot {
+                        // Replacing a block at the same slot is safe, because no block at that
+                        // slot may be signed after the import.
+                        txn.execute(
+                        "INSERT OR REPLACE INTO signed_blocks (validator_id, slot, signing_root)
+                         VALUES (?1, ?2, ?3)",
+                        params![validator_id, slot, WATERMARK_SIGNING_ROOT.as_bytes()],
+                    )?;
+                    }
+
+                    // Verified by `Interchange::verify`.
+                    if let Ok(Some((source, target))) = record.attestation_watermark() {
+                        // If an attestation with the same target exists, keep the greater source
+                        // epoch so that the attestation can't be surrounded after the import.
+                        let existing_source: Option<Epoch> = txn
+                            .query_row(
+                                "SELECT source_epoch FROM signed_attestations
+                             WHERE validator_id = ?1 AND target_epoch = ?2",
+                                params![validator_id, target],
+                                |row| row.get(0),
+                            )
+                            .optional()?;
+                        let source =
+                            existing_source.map_or(source, |existing| existing.max(source));
+
+                        txn.execute(
+                            "INSERT OR REPLACE INTO signed_attestations
+                            (validator_id, source_epoch, target_epoch, signing_root)
+                         VALUES (?1, ?2, ?3, ?4)",
+                            params![
+                                validator_id,
+                                source,
+                                target,
+                                WATERMARK_SIGNING_ROOT.as_bytes()
+                            ],
+                        )?;
+                    }
+                }
+
+                txn.commit()?;
+                Ok(interchange.data.len())
+            })
+            .map_err(Into::into)
+    }
+
+    /// Read the heartbeat of the process using the database, if any.
+    pub fn read_heartbeat(&self) -> Result<Option<Heartbeat>, NotSafe> {
+        let conn = self.conn_pool.get()?;
+
+        // Databases created before the heartbeat was introduced have no heartbeat table.
+        let table_exists = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'heartbeat'",
+                params![],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !table_exists {
+            return Ok(None);
         }
 
-        txn.commit()?;
-        Ok(safe)
+        let heartbeat = conn
+            .query_row(
+                "SELECT instance_id, pid, last_seen FROM heartbeat WHERE id = 0",
+                params![],
+                |row| {
+                    Ok(Heartbeat {
+                        instance_id: row.get::<_, i64>(0)? as u64,
+                        pid: row.get(1)?,
+                        last_seen: row.get::<_, i64>(2)? as u64,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(heartbeat)
+    }
+
+    /// Replace the heartbeat of the process using the database, or remove it if `None`.
+    pub fn write_heartbeat(&self, heartbeat: Option<Heartbeat>) -> Result<(), NotSafe> {
+        self.file_watch.guard(|| {
+            let mut conn = self.conn_pool.get()?;
+            let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+            txn.execute(
+                "CREATE TABLE IF NOT EXISTS heartbeat (
+                    id INTEGER PRIMARY KEY CHECK (id = 0),
+                    instance_id INTEGER NOT NULL,
+                    pid INTEGER NOT NULL,
+                    last_seen INTEGER NOT NULL
+                )",
+                params![],
+            )?;
+
+            if let Some(heartbeat) = heartbeat {
+                // Stored as signed integers, which are the only integers supported by SQLite.
+                txn.execute(
+                    "INSERT OR REPLACE INTO heartbeat (id, instance_id, pid, last_seen)
+                     VALUES (0, ?1, ?2, ?3)",
+                    params![
+                        heartbeat.instance_id as i64,
+                        heartbeat.pid,
+                        heartbeat.last_seen as i64
+                    ],
+                )?;
+            } else {
+                txn.execute("DELETE FROM heartbeat", params![])?;
+            }
+
+            txn.commit()?;
+            Ok(())
+        })
     }
 }
 
+/// Parse a public key stored as a 0x-prefixed hex string.
+fn pubkey_from_hex(pubkey: &str) -> Result<PublicKey, NotSafe> {
+    hex::decode(pubkey.trim_start_matches("0x"))
+        .map_err(|e| format!("{:?}", e))
+        .and_then(|bytes| PublicKey::deserialize(&bytes).map_err(|e| format!("{:?}", e)))
+        .map_err(|e| NotSafe::SQLError(format!("Invalid public key in database: {}", e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::pubkey;
+    use crate::block_tests::block;
+    use crate::test_utils::{pubkey, DEFAULT_DOMAIN};
     use tempfile::tempdir;
 
     #[test]
@@ -468,4 +859,98 @@ mod tests {
         let db2 = SlashingDatabase::open(&file).unwrap();
         check(&db2);
     }
+
+    // Replacing the database file while it is open should halt signing.
+    #[test]
+    fn replaced_file_error() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let other_file = dir.path().join("other.sqlite");
+
+        let db = SlashingDatabase::create(&file).unwrap();
+        db.register_validator(&pubkey(0)).unwrap();
+        db.check_and_insert_block_proposal(&pubkey(0), &block(1), DEFAULT_DOMAIN)
+            .unwrap();
+
+        drop(SlashingDatabase::create(&other_file).unwrap());
+        std::fs::rename(&other_file, &file).unwrap();
+
+        for slot in 2..4 {
+            match db.check_and_insert_block_proposal(&pubkey(0), &block(slot), DEFAULT_DOMAIN) {
+                Err(NotSafe::ExternallyModified(reason)) => assert!(reason.contains("replaced")),
+                other => panic!("unexpected result {:?}", other),
+            }
+        }
+    }
+
+    // Registering validators in a batch should skip those already registered.
+    #[test]
+    fn register_validators_batch() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        db.register_validator(&pubkey(0)).unwrap();
+
+        let num_keys = REGISTRATION_PROGRESS_INTERVAL + 1;
+        let pubkeys = (0..num_keys).map(pubkey).collect::<Vec<_>>();
+        let pubkey_refs = pubkeys.iter().collect::<Vec<_>>();
+        let mut reports = vec![];
+
+        assert_eq!(
+            db.register_validators_batch(&pubkey_refs, |done, total| reports.push((done, total)))
+                .unwrap(),
+            num_keys - 1,
+            "should only register new validators"
+        );
+        assert_eq!(
+            reports,
+            vec![
+                (REGISTRATION_PROGRESS_INTERVAL, num_keys),
+                (num_keys, num_keys)
+            ]
+        );
+
+        let conn = db.conn_pool.get().unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM validators", params![], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count as usize, num_keys, "should not duplicate validators");
+        drop(conn);
+
+        assert_eq!(
+            db.register_validators_batch(&pubkey_refs, |_, _| ())
+                .unwrap(),
+            0
+        );
+        db.check_and_insert_block_proposal(&pubkey(num_keys - 1), &block(1), DEFAULT_DOMAIN)
+            .unwrap();
+    }
+
+    // Restoring an old copy of the database file while it is open should halt signing.
+    #[test]
+    fn rolled_back_file_error() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let backup_file = dir.path().join("backup.sqlite");
+
+        let db = SlashingDatabase::create(&file).unwrap();
+        db.register_validator(&pubkey(0)).unwrap();
+        std::fs::copy(&file, &backup_file).unwrap();
+
+        db.check_and_insert_block_proposal(&pubkey(0), &block(1), DEFAULT_DOMAIN)
+            .unwrap();
+        std::fs::copy(&backup_file, &file).unwrap();
+
+        match db.check_and_insert_block_proposal(&pubkey(0), &block(2), DEFAULT_DOMAIN) {
+            Err(NotSafe::ExternallyModified(reason)) => assert!(reason.contains("another process")),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(matches!(
+            db.register_validator(&pubkey(1)),
+            Err(NotSafe::ExternallyModified(_))
+        ));
+    }
 }
@@ -74,43 +74,53 @@ impl<T> Default for StreamTest<T> {
 }
 
 impl StreamTest<AttestationData> {
+    /// Runs the test against a new database of each backend.
     pub fn run(&self) {
-        let dir = tempdir().unwrap();
-        let slashing_db_file = dir.path().join("slashing_protection.sqlite");
-        let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
-
-        for pubkey in &self.registered_validators {
-            slashing_db.register_validator(pubkey).unwrap();
-        }
-
-        for (i, test) in self.cases.iter().enumerate() {
-            assert_eq!(
-                slashing_db.check_and_insert_attestation(&test.pubkey, &test.data, test.domain),
-                test.expected,
-                "attestation {} not processed as expected",
-                i
-            );
+        for backend in &DatabaseBackend::ALL {
+            let dir = tempdir().unwrap();
+            let slashing_db = SlashingProtection::open_or_create(*backend, dir.path()).unwrap();
+
+            for pubkey in &self.registered_validators {
+                slashing_db.register_validator(pubkey).unwrap();
+            }
+
+            for (i, test) in self.cases.iter().enumerate() {
+                assert_eq!(
+                    slashing_db.check_and_insert_attestation(&test.pubkey, &test.data, test.domain),
+                    test.expected,
+                    "attestation {} not processed as expected by {}",
+                    i,
+                    backend
+                );
+            }
         }
     }
 }
 
 impl StreamTest<BeaconBlockHeader> {
+    /// Runs the test against a new database of each backend.
     pub fn run(&self) {
-        let dir = tempdir().unwrap();
-        let slashing_db_file = dir.path().join("slashing_protection.sqlite");
-        let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
-
-        for pubkey in &self.registered_validators {
-            slashing_db.register_validator(pubkey).unwrap();
-        }
-
-        for (i, test) in self.cases.iter().enumerate() {
-            assert_eq!(
-                slashing_db.check_and_insert_block_proposal(&test.pubkey, &test.data, test.domain),
-                test.expected,
-                "attestation {} not processed as expected",
-                i
-            );
+        for backend in &DatabaseBackend::ALL {
+            let dir = tempdir().unwrap();
+            let slashing_db = SlashingProtection::open_or_create(*backend, dir.path()).unwrap();
+
+            for pubkey in &self.registered_validators {
+                slashing_db.register_validator(pubkey).unwrap();
+            }
+
+            for (i, test) in self.cases.iter().enumerate() {
+                assert_eq!(
+                    slashing_db.check_and_insert_block_proposal(
+                        &test.pubkey,
+                        &test.data,
+                        test.domain
+                    ),
+                    test.expected,
+                    "block {} not processed as expected by {}",
+                    i,
+                    backend
+                );
+            }
         }
     }
 }
@@ -0,0 +1,50 @@
+#![cfg(feature = "interchange-tests")]
+
+use slashing_protection::interchange_vectors::TestCase;
+use slashing_protection::DatabaseBackend;
+use std::fs;
+use std::path::PathBuf;
+
+fn test_root_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("interchange-tests")
+        .join("tests")
+}
+
+#[test]
+fn generated() {
+    let dir = test_root_dir().join("generated");
+    let mut paths = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("unable to read {:?}, run `make` first: {:?}", dir, e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty(), "no test vectors in {:?}", dir);
+
+    let test_cases = paths
+        .iter()
+        .map(|path| TestCase::load(path).unwrap())
+        .collect::<Vec<_>>();
+
+    for backend in &DatabaseBackend::ALL {
+        let failures = test_cases
+            .iter()
+            .filter_map(|test_case| {
+                test_case
+                    .run(*backend)
+                    .err()
+                    .map(|failures| format!("{}: {:#?}", test_case.name, failures))
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            failures.is_empty(),
+            "{} of {} test cases failed with the {} backend:\n{}",
+            failures.len(),
+            test_cases.len(),
+            backend,
+            failures.join("\n")
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Tracks the outcome of aggregation duties, to help diagnose failures to produce aggregates.
+
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use types::Epoch;
+
+/// The number of epochs for which statistics are retained.
+const RETAINED_EPOCHS: u64 = 4;
+
+/// Aggregation statistics for a single epoch.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EpochAggregationStats {
+    /// The number of `SignedAggregateAndProof`s which were published to the beacon node.
+    pub published: usize,
+    /// The number of aggregation duties for which no aggregate was published, e.g. because the
+    /// beacon node failed to produce an aggregate or the published aggregate was invalid.
+    pub missed: usize,
+    /// The number of selection proofs computed for duties in this epoch.
+    pub selection_proofs: u32,
+    /// The total time spent computing selection proofs.
+    pub selection_proof_time: Duration,
+    /// The longest time spent computing a single selection proof.
+    pub max_selection_proof_time: Duration,
+}
+
+impl EpochAggregationStats {
+    /// The number of aggregation duties, whether or not they were successful.
+    pub fn duties(&self) -> usize {
+        self.published + self.missed
+    }
+
+    /// The mean time spent computing a selection proof, if any were computed.
+    pub fn mean_selection_proof_time(&self) -> Option<Duration> {
+        if self.selection_proofs > 0 {
+            Some(self.selection_proof_time / self.selection_proofs)
+        } else {
+            None
+        }
+    }
+}
+
+/// Aggregation statistics for the last `RETAINED_EPOCHS` epochs.
+#[derive(Default)]
+pub struct AggregationStats {
+    epochs: RwLock<BTreeMap<Epoch, EpochAggregationStats>>,
+}
+
+impl AggregationStats {
+    /// Records the outcome of the aggregation duties of a single committee.
+    pub fn record_aggregation(&self, epoch: Epoch, published: usize, missed: usize) {
+        self.update(epoch, |stats| {
+            stats.published += published;
+            stats.missed += missed;
+        })
+    }
+
+    /// Records that computing a selection proof for a duty in `epoch` took `duration`.
+    pub fn record_selection_proof(&self, epoch: Epoch, duration: Duration) {
+        self.update(epoch, |stats| {
+            stats.selection_proofs += 1;
+            stats.selection_proof_time += duration;
+            stats.max_selection_proof_time =
+                std::cmp::max(stats.max_selection_proof_time, duration);
+        })
+    }
+
+    /// Returns the statistics for `epoch`, if any were recorded.
+    pub fn get(&self, epoch: Epoch) -> Option<EpochAggregationStats> {
+        self.epochs.read().get(&epoch).copied()
+    }
+
+    fn update<F: FnOnce(&mut EpochAggregationStats)>(&self, epoch: Epoch, f: F) {
+        let mut epochs = self.epochs.write();
+
+        f(epochs.entry(epoch).or_default());
+
+        if let Some(latest) = epochs.keys().next_back().copied() {
+            let prior_to = latest.saturating_sub(RETAINED_EPOCHS);
+            *epochs = epochs.split_off(&prior_to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_prune() {
+        let stats = AggregationStats::default();
+
+        stats.record_selection_proof(Epoch::new(1), Duration::from_millis(2));
+        stats.record_selection_proof(Epoch::new(1), Duration::from_millis(4));
+        stats.record_aggregation(Epoch::new(1), 2, 0);
+        stats.record_aggregation(Epoch::new(1), 1, 3);
+
+        let epoch_stats = stats.get(Epoch::new(1)).unwrap();
+        assert_eq!(epoch_stats.duties(), 6);
+        assert_eq!(epoch_stats.published, 3);
+        assert_eq!(epoch_stats.missed, 3);
+        assert_eq!(
+            epoch_stats.mean_selection_proof_time(),
+            Some(Duration::from_millis(3))
+        );
+        assert_eq!(
+            epoch_stats.max_selection_proof_time,
+            Duration::from_millis(4)
+        );
+
+        stats.record_aggregation(Epoch::new(1 + RETAINED_EPOCHS), 1, 0);
+        assert!(stats.get(Epoch::new(1)).is_some());
+
+        stats.record_aggregation(Epoch::new(2 + RETAINED_EPOCHS), 1, 0);
+        assert!(stats.get(Epoch::new(1)).is_none());
+        assert_eq!(
+            stats.get(Epoch::new(2 + RETAINED_EPOCHS)).unwrap().duties(),
+            1
+        );
+    }
+}
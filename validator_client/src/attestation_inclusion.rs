@@ -0,0 +1,279 @@
+//! Tracks whether the attestations published by our validators are included in the canonical
+//! chain, so that operators do not need to rely upon third-party block explorers.
+
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use types::{Attestation, CommitteeIndex, Epoch, EthSpec, Slot};
+
+/// The number of epochs for which statistics are retained.
+const RETAINED_EPOCHS: u64 = 4;
+
+/// An attestation published by one of our validators which has not yet been seen in a block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingAttestation {
+    pub validator_index: u64,
+    pub slot: Slot,
+    pub committee_index: CommitteeIndex,
+    pub committee_position: usize,
+}
+
+impl PendingAttestation {
+    /// Returns `true` if `attestation` is for the same committee as `self` and includes the
+    /// signature of our validator.
+    fn is_included_in<E: EthSpec>(&self, attestation: &Attestation<E>) -> bool {
+        attestation.data.slot == self.slot
+            && attestation.data.index == self.committee_index
+            && attestation
+                .aggregation_bits
+                .get(self.committee_position)
+                .unwrap_or(false)
+    }
+}
+
+/// An attestation from one of our validators which was found in a block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inclusion {
+    pub attestation: PendingAttestation,
+    /// The slot of the block which included the attestation.
+    pub inclusion_slot: Slot,
+}
+
+impl Inclusion {
+    /// The number of slots between the attestation and the block which included it.
+    pub fn distance(&self) -> u64 {
+        self.inclusion_slot
+            .as_u64()
+            .saturating_sub(self.attestation.slot.as_u64())
+    }
+}
+
+/// Attestation inclusion statistics for a single epoch, grouped by the epoch of the attestation.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EpochInclusionStats {
+    /// The number of attestations which were included in a block.
+    pub included: usize,
+    /// The number of attestations which were not included in any block before the end of their
+    /// inclusion window.
+    pub missed: usize,
+    /// The sum of the inclusion distances of all included attestations.
+    pub total_inclusion_distance: u64,
+    /// The greatest inclusion distance of any included attestation.
+    pub max_inclusion_distance: u64,
+}
+
+impl EpochInclusionStats {
+    /// The number of attestations whose inclusion is known, whether or not they were included.
+    pub fn attestations(&self) -> usize {
+        self.included + self.missed
+    }
+
+    /// The mean inclusion distance of the included attestations, if any were included.
+    pub fn mean_inclusion_distance(&self) -> Option<f64> {
+        if self.included > 0 {
+            Some(self.total_inclusion_distance as f64 / self.included as f64)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    pending: Vec<PendingAttestation>,
+    last_checked_slot: Option<Slot>,
+    epochs: BTreeMap<Epoch, EpochInclusionStats>,
+}
+
+/// Records the attestations published by our validators and finds them in subsequent blocks.
+#[derive(Default)]
+pub struct AttestationInclusionTracker {
+    inner: RwLock<Inner>,
+}
+
+impl AttestationInclusionTracker {
+    /// Starts tracking the inclusion of an attestation published by one of our validators.
+    pub fn register(&self, attestation: PendingAttestation) {
+        self.inner.write().pending.push(attestation);
+    }
+
+    /// Returns `true` if there are any attestations awaiting inclusion.
+    pub fn has_pending(&self) -> bool {
+        !self.inner.read().pending.is_empty()
+    }
+
+    /// Returns the last slot for which `process_block` or `process_skipped_slot` was called.
+    pub fn last_checked_slot(&self) -> Option<Slot> {
+        self.inner.read().last_checked_slot
+    }
+
+    /// Finds any pending attestations which are included in `attestations`, the attestations of
+    /// the canonical block at `block_slot`. The included attestations are returned and are no
+    /// longer tracked.
+    ///
+    /// Any pending attestations which can no longer be included after `block_slot` are returned
+    /// as missed.
+    pub fn process_block<E: EthSpec>(
+        &self,
+        block_slot: Slot,
+        attestations: &[Attestation<E>],
+    ) -> (Vec<Inclusion>, Vec<PendingAttestation>) {
+        let mut inner = self.inner.write();
+
+        let mut inclusions = vec![];
+        inner.pending.retain(|pending| {
+            if pending.slot < block_slot
+                && attestations
+                    .iter()
+                    .any(|attestation| pending.is_included_in(attestation))
+            {
+                inclusions.push(Inclusion {
+                    attestation: *pending,
+                    inclusion_slot: block_slot,
+                });
+                false
+            } else {
+                true
+            }
+        });
+
+        for inclusion in &inclusions {
+            let stats = inner
+                .epochs
+                .entry(inclusion.attestation.slot.epoch(E::slots_per_epoch()))
+                .or_default();
+            let distance = inclusion.distance();
+            stats.included += 1;
+            stats.total_inclusion_distance += distance;
+            stats.max_inclusion_distance = std::cmp::max(stats.max_inclusion_distance, distance);
+        }
+
+        let missed = Self::checked_slot::<E>(&mut inner, block_slot);
+
+        (inclusions, missed)
+    }
+
+    /// Records that there is no canonical block at `slot`.
+    ///
+    /// Any pending attestations which can no longer be included after `slot` are returned as
+    /// missed.
+    pub fn process_skipped_slot<E: EthSpec>(&self, slot: Slot) -> Vec<PendingAttestation> {
+        Self::checked_slot::<E>(&mut self.inner.write(), slot)
+    }
+
+    /// Returns the statistics for attestations from `epoch`, if any were recorded.
+    pub fn get(&self, epoch: Epoch) -> Option<EpochInclusionStats> {
+        self.inner.read().epochs.get(&epoch).copied()
+    }
+
+    /// Marks `slot` as checked, removing and returning the pending attestations whose inclusion
+    /// window ends at or before `slot`.
+    fn checked_slot<E: EthSpec>(inner: &mut Inner, slot: Slot) -> Vec<PendingAttestation> {
+        inner.last_checked_slot = Some(slot);
+
+        let mut missed = vec![];
+        inner.pending.retain(|pending| {
+            if pending.slot + E::slots_per_epoch() <= slot {
+                missed.push(*pending);
+                false
+            } else {
+                true
+            }
+        });
+
+        for pending in &missed {
+            inner
+                .epochs
+                .entry(pending.slot.epoch(E::slots_per_epoch()))
+                .or_default()
+                .missed += 1;
+        }
+
+        if let Some(latest) = inner.epochs.keys().next_back().copied() {
+            let prior_to = latest.saturating_sub(RETAINED_EPOCHS);
+            inner.epochs = inner.epochs.split_off(&prior_to);
+        }
+
+        missed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{
+        AggregateSignature, AttestationData, BitList, Checkpoint, Hash256, MainnetEthSpec,
+    };
+
+    type E = MainnetEthSpec;
+
+    fn pending(validator_index: u64, slot: u64, committee_position: usize) -> PendingAttestation {
+        PendingAttestation {
+            validator_index,
+            slot: Slot::new(slot),
+            committee_index: 0,
+            committee_position,
+        }
+    }
+
+    fn attestation(slot: u64, committee_positions: &[usize]) -> Attestation<E> {
+        let mut aggregation_bits = BitList::with_capacity(8).unwrap();
+        for position in committee_positions {
+            aggregation_bits.set(*position, true).unwrap();
+        }
+
+        Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                slot: Slot::new(slot),
+                index: 0,
+                beacon_block_root: Hash256::zero(),
+                source: Checkpoint::default(),
+                target: Checkpoint::default(),
+            },
+            signature: AggregateSignature::empty(),
+        }
+    }
+
+    #[test]
+    fn includes_and_misses() {
+        let tracker = AttestationInclusionTracker::default();
+        let slots_per_epoch = E::slots_per_epoch();
+
+        tracker.register(pending(0, 1, 0));
+        tracker.register(pending(1, 1, 1));
+        tracker.register(pending(2, 2, 2));
+        assert!(tracker.has_pending());
+
+        // Attestations cannot be included in a block at their own slot.
+        let (inclusions, missed) = tracker.process_block(Slot::new(1), &[attestation(1, &[0])]);
+        assert!(inclusions.is_empty());
+        assert!(missed.is_empty());
+
+        let (inclusions, missed) = tracker.process_block(Slot::new(3), &[attestation(1, &[0])]);
+        assert_eq!(inclusions.len(), 1);
+        assert_eq!(inclusions[0].attestation.validator_index, 0);
+        assert_eq!(inclusions[0].distance(), 2);
+        assert!(missed.is_empty());
+        assert_eq!(tracker.last_checked_slot(), Some(Slot::new(3)));
+
+        // The attestation from slot 1 cannot be included after `1 + SLOTS_PER_EPOCH`.
+        let missed = tracker.process_skipped_slot::<E>(Slot::new(1 + slots_per_epoch));
+        assert_eq!(missed, vec![pending(1, 1, 1)]);
+        assert!(tracker.has_pending());
+
+        let (inclusions, _) =
+            tracker.process_block(Slot::new(2 + slots_per_epoch), &[attestation(2, &[2])]);
+        assert_eq!(inclusions[0].distance(), slots_per_epoch);
+        assert!(!tracker.has_pending());
+
+        let stats = tracker.get(Epoch::new(0)).unwrap();
+        assert_eq!(stats.attestations(), 3);
+        assert_eq!(stats.included, 2);
+        assert_eq!(stats.missed, 1);
+        assert_eq!(stats.max_inclusion_distance, slots_per_epoch);
+        assert_eq!(
+            stats.mean_inclusion_distance(),
+            Some((2 + slots_per_epoch) as f64 / 2.0)
+        );
+    }
+}
@@ -1,17 +1,19 @@
 use crate::{
+    attestation_inclusion::PendingAttestation,
     duties_service::{DutiesService, DutyAndProof},
+    metrics,
+    slot_pipeline::{SlotPhase, SlotPipeline},
     validator_store::ValidatorStore,
 };
 use environment::RuntimeContext;
-use futures::StreamExt;
 use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
-use slog::{crit, debug, error, info, trace};
+use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
-use tokio::time::{delay_until, interval_at, Duration, Instant};
-use types::{Attestation, ChainSpec, CommitteeIndex, EthSpec, Slot, SubnetId};
+use tokio::time::{delay_for, Duration};
+use types::{Attestation, CommitteeIndex, EthSpec, Slot, SubnetId};
 
 /// Builds an `AttestationService`.
 pub struct AttestationServiceBuilder<T, E: EthSpec> {
@@ -117,73 +119,100 @@ impl<T, E: EthSpec> Deref for AttestationService<T, E> {
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     /// Starts the service which periodically produces attestations.
-    pub fn start_update_service(self, spec: &ChainSpec) -> Result<(), String> {
+    pub fn start_update_service(self) -> Result<(), String> {
         let log = self.context.log().clone();
 
-        let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
-        let duration_to_next_slot = self
-            .slot_clock
-            .duration_to_next_slot()
+        let mut pipeline = SlotPipeline::new(SlotPhase::Attestation);
+        let duration_to_next_tick = pipeline
+            .duration_to_next_tick(&self.slot_clock)
             .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
 
         info!(
             log,
             "Attestation production service started";
-            "next_update_millis" => duration_to_next_slot.as_millis()
+            "next_update_millis" => duration_to_next_tick.as_millis()
         );
 
-        let mut interval = {
-            // Note: `interval_at` panics if `slot_duration` is 0
-            interval_at(
-                Instant::now() + duration_to_next_slot + slot_duration / 3,
-                slot_duration,
-            )
-        };
-
         let executor = self.context.executor.clone();
 
-        let interval_fut = async move {
-            while interval.next().await.is_some() {
+        let pipeline_fut = async move {
+            loop {
+                let tick = pipeline.next(&self.slot_clock).await;
                 let log = self.context.log();
 
-                if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
-                    crit!(
-                        log,
-                        "Failed to spawn attestation tasks";
-                        "error" => e
-                    )
-                } else {
+                for slot in tick.skipped {
+                    self.skip_attestation_tasks(slot);
+                }
+
+                if let Some(slot) = tick.slot {
+                    self.spawn_attestation_tasks(slot);
                     trace!(
                         log,
                         "Spawned attestation tasks";
+                        "slot" => slot.as_u64(),
                     )
                 }
+
+                self.inner
+                    .context
+                    .executor
+                    .runtime_handle()
+                    .spawn(self.clone().check_attestation_inclusion());
             }
         };
 
-        executor.spawn(interval_fut, "attestation_service");
+        executor.spawn(pipeline_fut, "attestation_service");
         Ok(())
     }
 
-    /// For each each required attestation, spawn a new task that downloads, signs and uploads the
-    /// attestation to the beacon node.
-    fn spawn_attestation_tasks(&self, slot_duration: Duration) -> Result<(), String> {
-        let slot = self
-            .slot_clock
-            .now()
-            .ok_or_else(|| "Failed to read slot clock".to_string())?;
-        let duration_to_next_slot = self
-            .slot_clock
-            .duration_to_next_slot()
-            .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
+    /// Records that the attestations (and any aggregates) due in `slot` will not be produced,
+    /// because the service was unable to run during the slot (e.g., due to a long pause or a jump
+    /// of the system clock).
+    fn skip_attestation_tasks(&self, slot: Slot) {
+        let duties = self.duties_service.attesters(slot);
 
-        // If a validator needs to publish an aggregate attestation, they must do so at 2/3
-        // through the slot. This delay triggers at this time
-        let aggregate_production_instant = Instant::now()
-            + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
-                .unwrap_or_else(|| Duration::from_secs(0));
+        if duties.is_empty() {
+            return;
+        }
 
+        let num_aggregators = duties
+            .iter()
+            .filter(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+            .count();
+
+        metrics::record_failed_duty(
+            &metrics::MISSED_ATTESTATIONS_TOTAL,
+            metrics::ATTESTATION,
+            "stale_slot",
+            slot,
+            duties.len(),
+        );
+        metrics::record_failed_duty(
+            &metrics::FAILED_AGGREGATIONS_TOTAL,
+            metrics::AGGREGATE,
+            "stale_slot",
+            slot,
+            num_aggregators,
+        );
+        self.duties_service.aggregation_stats.record_aggregation(
+            slot.epoch(E::slots_per_epoch()),
+            0,
+            num_aggregators,
+        );
+
+        warn!(
+            self.context.log(),
+            "Skipped stale attestation duties";
+            "info" => "Your machine could be overloaded or its clock could have jumped",
+            "aggregators" => num_aggregators,
+            "attesters" => duties.len(),
+            "slot" => slot.as_u64(),
+        );
+    }
+
+    /// For each each required attestation, spawn a new task that downloads, signs and uploads the
+    /// attestation to the beacon node.
+    fn spawn_attestation_tasks(&self, slot: Slot) {
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
             .duties_service
             .attesters(slot)
@@ -211,12 +240,85 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         slot,
                         committee_index,
                         validator_duties,
-                        aggregate_production_instant,
                     ),
                 );
             });
+    }
 
-        Ok(())
+    /// Returns the time remaining until the end of `slot`, or `None` if it has already ended.
+    fn duration_to_end_of(&self, slot: Slot) -> Option<Duration> {
+        self.slot_clock.duration_to_slot(slot + 1)
+    }
+
+    /// Searches the canonical blocks since the last check for the attestations published by our
+    /// validators, logging the inclusion distance of any which are found and warning about any
+    /// which can no longer be included.
+    ///
+    /// Only blocks prior to the current slot are checked, since the block for the current slot
+    /// may not have arrived yet.
+    async fn check_attestation_inclusion(self) {
+        let log = self.context.log();
+        let tracker = &self.duties_service.attestation_inclusion;
+
+        let latest_slot = match self.slot_clock.now() {
+            Some(slot) if slot > 0 => slot - 1,
+            _ => return,
+        };
+
+        // Never look back further than the inclusion window, since any older attestations must
+        // already have been included or missed.
+        let start_slot = std::cmp::max(
+            tracker
+                .last_checked_slot()
+                .map_or(latest_slot, |slot| slot + 1),
+            latest_slot.saturating_sub(E::slots_per_epoch()),
+        );
+
+        for slot in (start_slot.as_u64()..=latest_slot.as_u64()).map(Slot::new) {
+            if !tracker.has_pending() {
+                break;
+            }
+
+            let (inclusions, missed) =
+                match self.beacon_node.http.beacon().get_block_by_slot(slot).await {
+                    // A skipped slot returns the prior block.
+                    Ok((block, _)) if block.message.slot == slot => {
+                        tracker.process_block(slot, &block.message.body.attestations)
+                    }
+                    Ok(_) => (vec![], tracker.process_skipped_slot::<E>(slot)),
+                    Err(e) => {
+                        // Try again next slot, e.g. if the BN has not yet imported the block.
+                        debug!(
+                            log,
+                            "Unable to check attestation inclusion";
+                            "error" => format!("{:?}", e),
+                            "slot" => slot.as_u64(),
+                        );
+                        break;
+                    }
+                };
+
+            for inclusion in inclusions {
+                info!(
+                    log,
+                    "Attestation included";
+                    "inclusion_distance" => inclusion.distance(),
+                    "inclusion_slot" => inclusion.inclusion_slot.as_u64(),
+                    "validator_index" => inclusion.attestation.validator_index,
+                    "slot" => inclusion.attestation.slot.as_u64(),
+                );
+            }
+
+            for pending in missed {
+                warn!(
+                    log,
+                    "Attestation not included";
+                    "validator_index" => pending.validator_index,
+                    "committee_index" => pending.committee_index,
+                    "slot" => pending.slot.as_u64(),
+                );
+            }
+        }
     }
 
     /// Performs the first step of the attesting process: downloading `Attestation` objects,
@@ -233,7 +335,6 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         slot: Slot,
         committee_index: CommitteeIndex,
         validator_duties: Vec<DutyAndProof>,
-        aggregate_production_instant: Instant,
     ) -> Result<(), ()> {
         let log = self.context.log();
 
@@ -243,49 +344,87 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             return Ok(());
         }
 
+        let num_aggregators = validator_duties
+            .iter()
+            .filter(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+            .count();
+
         // Step 1.
         //
         // Download, sign and publish an `Attestation` for each validator.
-        let attestation_opt = self
+        let result = self
             .produce_and_publish_attestations(slot, committee_index, &validator_duties)
-            .await
-            .map_err(move |e| {
-                crit!(
-                    log,
-                    "Error during attestation routine";
-                    "error" => format!("{:?}", e),
-                    "committee_index" => committee_index,
-                    "slot" => slot.as_u64(),
-                )
-            })?;
+            .await;
+
+        // Aggregates cannot be produced without an attestation.
+        if !matches!(result, Ok(Some(_))) {
+            metrics::record_failed_duty(
+                &metrics::FAILED_AGGREGATIONS_TOTAL,
+                metrics::AGGREGATE,
+                "no_attestation",
+                slot,
+                num_aggregators,
+            );
+        }
 
         // Step 2.
         //
         // If an attestation was produced, make an aggregate.
-        if let Some(attestation) = attestation_opt {
-            // First, wait until the `aggregation_production_instant` (2/3rds
-            // of the way though the slot). As verified in the
-            // `delay_triggers_when_in_the_past` test, this code will still run
-            // even if the instant has already elapsed.
-            delay_until(aggregate_production_instant).await;
-
-            // Then download, sign and publish a `SignedAggregateAndProof` for each
-            // validator that is elected to aggregate for this `slot` and
-            // `committee_index`.
-            self.produce_and_publish_aggregates(attestation, &validator_duties)
-                .await
-                .map_err(move |e| {
-                    crit!(
+        let result = match result {
+            Ok(Some(attestation)) => {
+                // First, wait until the aggregation phase of the slot (2/3rds of the way
+                // through the slot).
+                if let Some(delay) = SlotPhase::Aggregation.duration_until(&self.slot_clock, slot) {
+                    delay_for(delay).await;
+                }
+
+                if num_aggregators > 0 && SlotPhase::Aggregation.is_stale(&self.slot_clock, slot) {
+                    // The task was delayed beyond the end of the slot, so any aggregate would be
+                    // too late to be useful.
+                    metrics::record_failed_duty(
+                        &metrics::FAILED_AGGREGATIONS_TOTAL,
+                        metrics::AGGREGATE,
+                        "stale_slot",
+                        slot,
+                        num_aggregators,
+                    );
+                    warn!(
                         log,
-                        "Error during attestation routine";
-                        "error" => format!("{:?}", e),
+                        "Skipped stale aggregation duties";
+                        "info" => "Your machine could be overloaded",
+                        "aggregators" => num_aggregators,
                         "committee_index" => committee_index,
                         "slot" => slot.as_u64(),
-                    )
-                })?;
-        }
+                    );
+                    Ok(0)
+                } else {
+                    // Then download, sign and publish a `SignedAggregateAndProof` for each
+                    // validator that is elected to aggregate for this `slot` and
+                    // `committee_index`.
+                    self.produce_and_publish_aggregates(attestation, &validator_duties)
+                        .await
+                }
+            }
+            Ok(None) => Ok(0),
+            Err(e) => Err(e),
+        };
 
-        Ok(())
+        let num_published = result.as_ref().map_or(0, |num| *num);
+        self.duties_service.aggregation_stats.record_aggregation(
+            slot.epoch(E::slots_per_epoch()),
+            num_published,
+            num_aggregators.saturating_sub(num_published),
+        );
+
+        result.map(|_| ()).map_err(move |e| {
+            crit!(
+                log,
+                "Error during attestation routine";
+                "error" => format!("{:?}", e),
+                "committee_index" => committee_index,
+                "slot" => slot.as_u64(),
+            )
+        })
     }
 
     /// Performs the first step of the attesting process: downloading `Attestation` objects,
@@ -318,19 +457,48 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .ok_or_else(|| "Unable to determine current slot from clock".to_string())?
             .epoch(E::slots_per_epoch());
 
-        let attestation = self
-            .beacon_node
-            .http
-            .validator()
-            .produce_attestation(slot, committee_index)
-            .await
-            .map_err(|e| format!("Failed to produce attestation: {:?}", e))?;
+        let missed = |reason, count| {
+            metrics::record_failed_duty(
+                &metrics::MISSED_ATTESTATIONS_TOTAL,
+                metrics::ATTESTATION,
+                reason,
+                slot,
+                count,
+            )
+        };
+
+        // An attestation produced after the end of its slot would be late, so do not wait for
+        // the BN beyond then.
+        let deadline = self.duration_to_end_of(slot).ok_or_else(|| {
+            missed("deadline", validator_duties.len());
+            format!("Slot {} ended before attestation production", slot)
+        })?;
+
+        let attestation = {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_REQUEST_TIMES,
+                &[metrics::PRODUCE_ATTESTATION],
+            );
+            self.beacon_node
+                .http
+                .with_timeout(deadline)
+                .validator()
+                .produce_attestation(slot, committee_index)
+                .await
+        }
+        .map_err(|e| {
+            missed(
+                metrics::beacon_node_error_reason(&e),
+                validator_duties.len(),
+            );
+            format!("Failed to produce attestation: {:?}", e)
+        })?;
 
         // For each validator in `validator_duties`, clone the `attestation` and add
         // their signature.
         //
         // If any validator is unable to sign, they are simply skipped.
-        let signed_attestations = validator_duties
+        let (signed_attestations, pending_attestations): (Vec<_>, Vec<_>) = validator_duties
             .iter()
             .filter_map(|duty| {
                 // Ensure that all required fields are present in the validator duty.
@@ -338,7 +506,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     duty_slot,
                     duty_committee_index,
                     validator_committee_position,
-                    _,
+                    validator_index,
                     committee_count_at_slot,
                 ) = if let Some(tuple) = duty.attestation_duties() {
                     tuple
@@ -387,9 +555,22 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         &mut attestation,
                         current_epoch,
                     )
-                    .map(|_| (attestation, subnet_id))
+                    .map(|_| {
+                        let pending = PendingAttestation {
+                            validator_index,
+                            slot: duty_slot,
+                            committee_index: duty_committee_index,
+                            committee_position: validator_committee_position,
+                        };
+                        ((attestation, subnet_id), pending)
+                    })
             })
-            .collect::<Vec<_>>();
+            .unzip();
+
+        missed(
+            "signing",
+            validator_duties.len() - signed_attestations.len(),
+        );
 
         // If there are any signed attestations, publish them to the BN. Otherwise,
         // just return early.
@@ -397,35 +578,67 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             let num_attestations = signed_attestations.len();
             let beacon_block_root = attestation.0.data.beacon_block_root;
 
-            self.beacon_node
-                .http
-                .validator()
-                .publish_attestations(signed_attestations)
-                .await
-                .map_err(|e| format!("Failed to publish attestation: {:?}", e))
-                .map(move |publish_status| match publish_status {
-                    PublishStatus::Valid => info!(
-                        log,
-                        "Successfully published attestations";
-                        "count" => num_attestations,
-                        "head_block" => format!("{:?}", beacon_block_root),
-                        "committee_index" => committee_index,
-                        "slot" => slot.as_u64(),
-                        "type" => "unaggregated",
-                    ),
-                    PublishStatus::Invalid(msg) => crit!(
+            let publish_status = {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::BEACON_NODE_REQUEST_TIMES,
+                    &[metrics::PUBLISH_ATTESTATIONS],
+                );
+                self.beacon_node
+                    .http
+                    .validator()
+                    .publish_attestations(signed_attestations)
+                    .await
+            }
+            .map_err(|e| {
+                missed(metrics::beacon_node_error_reason(&e), num_attestations);
+                format!("Failed to publish attestation: {:?}", e)
+            })?;
+
+            // Watch for the attestations in subsequent blocks, as long as the BN accepted them.
+            if let PublishStatus::Valid | PublishStatus::AlreadyKnown = publish_status {
+                metrics::record_successful_duty(metrics::ATTESTATION, num_attestations);
+                for pending in pending_attestations {
+                    self.duties_service.attestation_inclusion.register(pending);
+                }
+            }
+
+            match publish_status {
+                PublishStatus::Valid => info!(
+                    log,
+                    "Successfully published attestations";
+                    "count" => num_attestations,
+                    "head_block" => format!("{:?}", beacon_block_root),
+                    "committee_index" => committee_index,
+                    "slot" => slot.as_u64(),
+                    "type" => "unaggregated",
+                ),
+                PublishStatus::AlreadyKnown => info!(
+                    log,
+                    "Attestations already known to beacon node";
+                    "count" => num_attestations,
+                    "head_block" => format!("{:?}", beacon_block_root),
+                    "committee_index" => committee_index,
+                    "slot" => slot.as_u64(),
+                    "type" => "unaggregated",
+                ),
+                PublishStatus::Invalid(msg) => {
+                    missed("invalid", num_attestations);
+                    crit!(
                         log,
                         "Published attestation was invalid";
                         "message" => msg,
                         "committee_index" => committee_index,
                         "slot" => slot.as_u64(),
                         "type" => "unaggregated",
-                    ),
-                    PublishStatus::Unknown => {
-                        crit!(log, "Unknown condition when publishing unagg. attestation")
-                    }
-                })
-                .map(|()| Some(attestation.0))
+                    )
+                }
+                PublishStatus::Unknown => {
+                    missed("unknown", num_attestations);
+                    crit!(log, "Unknown condition when publishing unagg. attestation")
+                }
+            }
+
+            Ok(Some(attestation.0))
         } else {
             debug!(
                 log,
@@ -451,20 +664,54 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     /// Only one aggregated `Attestation` is downloaded from the BN. It is then cloned and signed
     /// by each validator and the list of individually-signed `SignedAggregateAndProof` objects is
     /// returned to the BN.
+    ///
+    /// Returns the number of `SignedAggregateAndProof` objects which were accepted by the BN.
     async fn produce_and_publish_aggregates(
         &self,
         attestation: Attestation<E>,
         validator_duties: &[DutyAndProof],
-    ) -> Result<(), String> {
+    ) -> Result<usize, String> {
         let log = self.context.log();
+        let failed = |reason, count| {
+            metrics::record_failed_duty(
+                &metrics::FAILED_AGGREGATIONS_TOTAL,
+                metrics::AGGREGATE,
+                reason,
+                attestation.data.slot,
+                count,
+            )
+        };
+        let num_aggregators = validator_duties
+            .iter()
+            .filter(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+            .count();
+
+        let deadline = self
+            .duration_to_end_of(attestation.data.slot)
+            .ok_or_else(|| {
+                failed("deadline", num_aggregators);
+                format!(
+                    "Slot {} ended before aggregate production",
+                    attestation.data.slot
+                )
+            })?;
 
-        let aggregated_attestation = self
-            .beacon_node
-            .http
-            .validator()
-            .produce_aggregate_attestation(&attestation.data)
-            .await
-            .map_err(|e| format!("Failed to produce an aggregate attestation: {:?}", e))?;
+        let aggregated_attestation = {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_REQUEST_TIMES,
+                &[metrics::PRODUCE_AGGREGATE],
+            );
+            self.beacon_node
+                .http
+                .with_timeout(deadline)
+                .validator()
+                .produce_aggregate_attestation(&attestation.data)
+                .await
+        }
+        .map_err(|e| {
+            failed(metrics::beacon_node_error_reason(&e), num_aggregators);
+            format!("Failed to produce an aggregate attestation: {:?}", e)
+        })?;
 
         // For each validator, clone the `aggregated_attestation` and convert it into
         // a `SignedAggregateAndProof`
@@ -506,19 +753,33 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             })
             .collect::<Vec<_>>();
 
+        failed(
+            "signing",
+            num_aggregators.saturating_sub(signed_aggregate_and_proofs.len()),
+        );
+
         // If there any signed aggregates and proofs were produced, publish them to the
         // BN.
         if let Some(first) = signed_aggregate_and_proofs.first().cloned() {
             let attestation = first.message.aggregate;
+            let num_signed = signed_aggregate_and_proofs.len();
 
-            let publish_status = self
-                .beacon_node
-                .http
-                .validator()
-                .publish_aggregate_and_proof(signed_aggregate_and_proofs)
-                .await
-                .map_err(|e| format!("Failed to publish aggregate and proofs: {:?}", e))?;
-            match publish_status {
+            let publish_status = {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::BEACON_NODE_REQUEST_TIMES,
+                    &[metrics::PUBLISH_AGGREGATES],
+                );
+                self.beacon_node
+                    .http
+                    .validator()
+                    .publish_aggregate_and_proof(signed_aggregate_and_proofs)
+                    .await
+            }
+            .map_err(|e| {
+                failed(metrics::beacon_node_error_reason(&e), num_signed);
+                format!("Failed to publish aggregate and proofs: {:?}", e)
+            })?;
+            match &publish_status {
                 PublishStatus::Valid => info!(
                     log,
                     "Successfully published attestations";
@@ -528,19 +789,39 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     "slot" => attestation.data.slot.as_u64(),
                     "type" => "aggregated",
                 ),
-                PublishStatus::Invalid(msg) => crit!(
+                PublishStatus::AlreadyKnown => info!(
                     log,
-                    "Published attestation was invalid";
-                    "message" => msg,
+                    "Attestations already known to beacon node";
+                    "signatures" => attestation.aggregation_bits.num_set_bits(),
+                    "head_block" => format!("{:?}", attestation.data.beacon_block_root),
                     "committee_index" => attestation.data.index,
                     "slot" => attestation.data.slot.as_u64(),
                     "type" => "aggregated",
                 ),
+                PublishStatus::Invalid(msg) => {
+                    failed("invalid", num_signed);
+                    crit!(
+                        log,
+                        "Published attestation was invalid";
+                        "message" => msg,
+                        "committee_index" => attestation.data.index,
+                        "slot" => attestation.data.slot.as_u64(),
+                        "type" => "aggregated",
+                    )
+                }
                 PublishStatus::Unknown => {
+                    failed("unknown", num_signed);
                     crit!(log, "Unknown condition when publishing agg. attestation")
                 }
             };
-            Ok(())
+
+            match publish_status {
+                PublishStatus::Valid | PublishStatus::AlreadyKnown => {
+                    metrics::record_successful_duty(metrics::AGGREGATE, num_signed);
+                    Ok(num_signed)
+                }
+                PublishStatus::Invalid(_) | PublishStatus::Unknown => Ok(0),
+            }
         } else {
             debug!(
                 log,
@@ -548,7 +829,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 "committee_index" => attestation.data.index,
                 "slot" => attestation.data.slot.as_u64(),
             );
-            Ok(())
+            Ok(0)
         }
     }
 }
@@ -558,6 +839,7 @@ mod tests {
     use super::*;
     use futures::future::FutureExt;
     use parking_lot::RwLock;
+    use tokio::time::{delay_until, Instant};
 
     /// This test is to ensure that a `tokio_timer::Delay` with an instant in the past will still
     /// trigger.
@@ -1,8 +1,9 @@
-use crate::validator_store::ValidatorStore;
+use crate::{metrics, validator_store::ValidatorStore};
 use environment::RuntimeContext;
 use futures::channel::mpsc::Receiver;
 use futures::{StreamExt, TryFutureExt};
-use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
+use parking_lot::RwLock;
+use remote_beacon_node::{BlockPublishStatus, RemoteBeaconNode};
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::ops::Deref;
@@ -69,7 +70,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or_else(|| "Cannot build BlockService without runtime_context")?,
-                graffiti: self.graffiti,
+                graffiti: RwLock::new(self.graffiti),
             }),
         })
     }
@@ -81,7 +82,7 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: Arc<T>,
     beacon_node: RemoteBeaconNode<E>,
     context: RuntimeContext<E>,
-    graffiti: Option<Graffiti>,
+    graffiti: RwLock<Option<Graffiti>>,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -112,6 +113,12 @@ pub struct BlockServiceNotification {
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
+    /// Sets the graffiti included in the blocks produced, or lets the beacon node choose it if
+    /// `None`.
+    pub fn set_graffiti(&self, graffiti: Option<Graffiti>) {
+        *self.graffiti.write() = graffiti;
+    }
+
     pub fn start_update_service(
         self,
         notification_rx: Receiver<BlockServiceNotification>,
@@ -143,6 +150,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         })?;
 
         if notification.slot != slot {
+            metrics::record_failed_duty(
+                &metrics::FAILED_BLOCK_PROPOSALS_TOTAL,
+                metrics::BLOCK,
+                "expired_slot",
+                notification.slot,
+                notification.block_proposers.len(),
+            );
             warn!(
                 log,
                 "Skipping block production for expired slot";
@@ -208,53 +222,105 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
     /// Produce a block at the given slot for validator_pubkey
     async fn publish_block(self, slot: Slot, validator_pubkey: PublicKey) -> Result<(), String> {
         let log = self.context.log();
+        let failed = |reason| {
+            metrics::record_failed_duty(
+                &metrics::FAILED_BLOCK_PROPOSALS_TOTAL,
+                metrics::BLOCK,
+                reason,
+                slot,
+                1,
+            )
+        };
 
-        let current_slot = self
-            .slot_clock
-            .now()
-            .ok_or_else(|| "Unable to determine current slot from clock".to_string())?;
+        let current_slot = self.slot_clock.now().ok_or_else(|| {
+            failed("slot_clock");
+            "Unable to determine current slot from clock".to_string()
+        })?;
 
         let randao_reveal = self
             .validator_store
             .randao_reveal(&validator_pubkey, slot.epoch(E::slots_per_epoch()))
-            .ok_or_else(|| "Unable to produce randao reveal".to_string())?;
+            .ok_or_else(|| {
+                failed("randao_reveal");
+                "Unable to produce randao reveal".to_string()
+            })?;
+
+        let graffiti = *self.graffiti.read();
 
-        let block = self
-            .beacon_node
-            .http
-            .validator()
-            .produce_block(slot, randao_reveal, self.graffiti)
-            .await
-            .map_err(|e| format!("Error from beacon node when producing block: {:?}", e))?;
+        let block = {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_REQUEST_TIMES,
+                &[metrics::PRODUCE_BLOCK],
+            );
+            self.beacon_node
+                .http
+                .validator()
+                .produce_block(slot, randao_reveal, graffiti)
+                .await
+        }
+        .map_err(|e| {
+            failed(metrics::beacon_node_error_reason(&e));
+            format!("Error from beacon node when producing block: {:?}", e)
+        })?;
 
         let signed_block = self
             .validator_store
             .sign_block(&validator_pubkey, block, current_slot)
-            .ok_or_else(|| "Unable to sign block".to_string())?;
-
-        let publish_status = self
-            .beacon_node
-            .http
-            .validator()
-            .publish_block(signed_block.clone())
-            .await
-            .map_err(|e| format!("Error from beacon node when publishing block: {:?}", e))?;
+            .ok_or_else(|| {
+                failed("signing");
+                "Unable to sign block".to_string()
+            })?;
+
+        let publish_status = {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_REQUEST_TIMES,
+                &[metrics::PUBLISH_BLOCK],
+            );
+            self.beacon_node
+                .http
+                .validator()
+                .publish_block(signed_block.clone())
+                .await
+        }
+        .map_err(|e| {
+            failed(metrics::beacon_node_error_reason(&e));
+            format!("Error from beacon node when publishing block: {:?}", e)
+        })?;
 
         match publish_status {
-            PublishStatus::Valid => info!(
-                log,
-                "Successfully published block";
-                "deposits" => signed_block.message.body.deposits.len(),
-                "attestations" => signed_block.message.body.attestations.len(),
-                "slot" => signed_block.slot().as_u64(),
-            ),
-            PublishStatus::Invalid(msg) => crit!(
-                log,
-                "Published block was invalid";
-                "message" => msg,
-                "slot" => signed_block.slot().as_u64(),
-            ),
-            PublishStatus::Unknown => crit!(log, "Unknown condition when publishing block"),
+            BlockPublishStatus::Imported => {
+                metrics::record_successful_duty(metrics::BLOCK, 1);
+                info!(
+                    log,
+                    "Successfully published block";
+                    "deposits" => signed_block.message.body.deposits.len(),
+                    "attestations" => signed_block.message.body.attestations.len(),
+                    "slot" => signed_block.slot().as_u64(),
+                )
+            }
+            BlockPublishStatus::BroadcastOnly(msg) => {
+                metrics::record_successful_duty(metrics::BLOCK, 1);
+                warn!(
+                    log,
+                    "Published block was not imported by beacon node";
+                    "info" => "the block has been broadcast to the network",
+                    "message" => msg,
+                    "slot" => signed_block.slot().as_u64(),
+                )
+            }
+            BlockPublishStatus::Invalid(msg) => {
+                failed("invalid");
+                crit!(
+                    log,
+                    "Published block was invalid";
+                    "message" => msg,
+                    "slot" => signed_block.slot().as_u64(),
+                )
+            }
+            BlockPublishStatus::Unknown => {
+                failed("unknown");
+                crit!(log, "Unknown condition when publishing block")
+            }
         }
 
         Ok(())
@@ -55,11 +55,31 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("allow-unsynced")
                 .long("allow-unsynced")
+                .alias("allow-unsynced-beacon-node")
                 .help(
-                    "If present, the validator client will still poll for duties if the beacon
-                      node is not synced.",
+                    "If present, the validator client will not wait for the beacon node to sync \
+                      at startup and will still poll for duties if the beacon node is not synced. \
+                      Only recommended for testnets.",
                 ),
         )
+        .arg(
+            Arg::with_name("genesis-poll-interval")
+                .long("genesis-poll-interval")
+                .value_name("SECONDS")
+                .help("The interval between checks of the genesis time whilst waiting for \
+                    genesis at startup.")
+                .default_value("12")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("sync-poll-interval")
+                .long("sync-poll-interval")
+                .value_name("SECONDS")
+                .help("The interval between checks of the beacon node sync status whilst \
+                    waiting for it to sync at startup.")
+                .default_value("12")
+                .takes_value(true),
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::with_name("graffiti")
@@ -68,4 +88,75 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("GRAFFITI")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("metrics")
+                .long("metrics")
+                .help("Enable the HTTP server which serves Prometheus metrics at /metrics.")
+        )
+        .arg(
+            Arg::with_name("metrics-address")
+                .long("metrics-address")
+                .value_name("ADDRESS")
+                .help("Set the listen address for the metrics HTTP server.")
+                .default_value("127.0.0.1")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-port")
+                .long("metrics-port")
+                .value_name("PORT")
+                .help("Set the listen TCP port for the metrics HTTP server.")
+                .default_value("5064")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-allow-origin")
+                .long("metrics-allow-origin")
+                .value_name("ORIGIN")
+                .help("Set the value of the Access-Control-Allow-Origin response HTTP header for the metrics server.  Use * to allow any origin (not recommended in production)")
+                .default_value("")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("signing-audit-log")
+                .long("signing-audit-log")
+                .help(
+                    "Record every signed block and attestation in an append-only log in the \
+                    datadir. When the metrics server is enabled, the log can be read at \
+                    /signing_audit_log.",
+                )
+        )
+        .arg(
+            Arg::with_name("signing-audit-log-max-size")
+                .long("signing-audit-log-max-size")
+                .value_name("MEGABYTES")
+                .help(
+                    "The size at which the signing audit log is rotated. Up to four rotated \
+                    files are retained.",
+                )
+                .default_value("64")
+                .takes_value(true),
+        )        .arg(
+            Arg::with_name("slashing-protection-backend")
+                .long("slashing-protection-backend")
+                .value_name("BACKEND")
+                .help(
+                    "The database used for slashing protection. LMDB avoids the write lock \
+                    contention of SQLite when running very large numbers of validators. An \
+                    existing SQLite database must first be converted with `lighthouse account \
+                    validator slashing-protection migrate-to-lmdb`.",
+                )
+                .possible_values(&["sqlite", "lmdb"])
+                .default_value("sqlite")
+                .takes_value(true),
+        )        .arg(
+            Arg::with_name("force")
+                .long("force")
+                .help(
+                    "Start even if another process appears to be using the slashing protection \
+                    database, i.e. it holds the lock file or has recently refreshed its heartbeat \
+                    in the database. WARNING: running two validator clients with the same keys \
+                    WILL lead to those validators getting slashed.",
+                ),
+        )
 }
@@ -1,14 +1,22 @@
+use crate::http_metrics;
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_path_with_default_in_home_dir};
 use serde_derive::{Deserialize, Serialize};
+use slashing_protection::DatabaseBackend;
 use std::path::PathBuf;
+use std::time::Duration;
 use types::{Graffiti, GRAFFITI_BYTES_LEN};
 
 pub const DEFAULT_HTTP_SERVER: &str = "http://localhost:5052/";
 pub const DEFAULT_DATA_DIR: &str = ".lighthouse/validators";
 pub const DEFAULT_SECRETS_DIR: &str = ".lighthouse/secrets";
-/// Path to the slashing protection database within the datadir.
-pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
+/// The default interval between checks of the genesis time at startup, in seconds.
+pub const DEFAULT_GENESIS_POLL_INTERVAL_SECS: u64 = 12;
+/// The default interval between checks of the beacon node sync status at startup, in seconds.
+pub const DEFAULT_SYNC_POLL_INTERVAL_SECS: u64 = 12;
+pub use slashing_protection::SLASHING_PROTECTION_FILENAME;
+/// The default size at which the signing audit log is rotated, in megabytes.
+pub const DEFAULT_SIGNING_AUDIT_LOG_MAX_SIZE_MB: u64 = 64;
 
 /// Stores the core configuration for this validator instance.
 #[derive(Clone, Serialize, Deserialize)]
@@ -24,12 +32,28 @@ pub struct Config {
     /// If true, the validator client will still poll for duties and produce blocks even if the
     /// beacon node is not synced at startup.
     pub allow_unsynced_beacon_node: bool,
+    /// The interval between checks of the genesis time whilst waiting for genesis at startup.
+    pub genesis_poll_interval: Duration,
+    /// The interval between checks of the beacon node sync status whilst waiting for it to sync
+    /// at startup.
+    pub sync_poll_interval: Duration,
     /// If true, refuse to unlock a keypair that is guarded by a lockfile.
     pub strict_lockfiles: bool,
     /// If true, don't scan the validators dir for new keystores.
     pub disable_auto_discover: bool,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Option<Graffiti>,
+    /// Configuration for the HTTP server which serves Prometheus metrics.
+    pub http_metrics: http_metrics::Config,
+    /// If true, record every signed block and attestation in the signing audit log.
+    pub signing_audit_log: bool,
+    /// The size at which the signing audit log is rotated, in bytes.
+    pub signing_audit_log_max_size: u64,
+    /// The database used for slashing protection.
+    pub slashing_protection_backend: DatabaseBackend,
+    /// If true, start even if another process appears to be using the slashing protection
+    /// database.
+    pub force_slashing_protection_lock: bool,
 }
 
 impl Default for Config {
@@ -46,9 +70,16 @@ impl Default for Config {
             secrets_dir,
             http_server: DEFAULT_HTTP_SERVER.to_string(),
             allow_unsynced_beacon_node: false,
+            genesis_poll_interval: Duration::from_secs(DEFAULT_GENESIS_POLL_INTERVAL_SECS),
+            sync_poll_interval: Duration::from_secs(DEFAULT_SYNC_POLL_INTERVAL_SECS),
             strict_lockfiles: false,
             disable_auto_discover: false,
             graffiti: None,
+            http_metrics: http_metrics::Config::default(),
+            signing_audit_log: false,
+            signing_audit_log_max_size: DEFAULT_SIGNING_AUDIT_LOG_MAX_SIZE_MB * 1024 * 1024,
+            slashing_protection_backend: DatabaseBackend::default(),
+            force_slashing_protection_lock: false,
         }
     }
 }
@@ -77,6 +108,15 @@ impl Config {
         }
 
         config.allow_unsynced_beacon_node = cli_args.is_present("allow-unsynced");
+
+        if let Some(secs) = parse_optional(cli_args, "genesis-poll-interval")? {
+            config.genesis_poll_interval = Duration::from_secs(secs);
+        }
+
+        if let Some(secs) = parse_optional(cli_args, "sync-poll-interval")? {
+            config.sync_poll_interval = Duration::from_secs(secs);
+        }
+
         config.strict_lockfiles = cli_args.is_present("strict-lockfiles");
         config.disable_auto_discover = cli_args.is_present("disable-auto-discover");
 
@@ -84,26 +124,64 @@ impl Config {
             config.secrets_dir = secrets_dir;
         }
 
-        if let Some(input_graffiti) = cli_args.value_of("graffiti") {
-            let graffiti_bytes = input_graffiti.as_bytes();
-            if graffiti_bytes.len() > GRAFFITI_BYTES_LEN {
-                return Err(format!(
-                    "Your graffiti is too long! {} bytes maximum!",
-                    GRAFFITI_BYTES_LEN
-                ));
-            } else {
-                // Default graffiti to all 0 bytes.
-                let mut graffiti = Graffiti::default();
-
-                // Copy the provided bytes over.
-                //
-                // Panic-free because `graffiti_bytes.len()` <= `GRAFFITI_BYTES_LEN`.
-                graffiti[..graffiti_bytes.len()].copy_from_slice(&graffiti_bytes);
-
-                config.graffiti = Some(graffiti);
-            }
+        config.graffiti = get_graffiti(cli_args)?;
+
+        config.http_metrics.enabled = cli_args.is_present("metrics");
+
+        if let Some(address) = parse_optional(cli_args, "metrics-address")? {
+            config.http_metrics.listen_address = address;
+        }
+
+        if let Some(port) = parse_optional(cli_args, "metrics-port")? {
+            config.http_metrics.port = port;
+        }
+
+        if let Some(allow_origin) = cli_args.value_of("metrics-allow-origin") {
+            // Pre-validate the config value to give feedback to the user on startup, instead of
+            // when the metrics server is started.
+            hyper::header::HeaderValue::from_str(allow_origin)
+                .map_err(|_| "Invalid metrics-allow-origin value")?;
+
+            config.http_metrics.allow_origin = allow_origin.to_string();
+        }
+
+        config.signing_audit_log = cli_args.is_present("signing-audit-log");
+
+        if let Some(megabytes) = parse_optional::<u64>(cli_args, "signing-audit-log-max-size")? {
+            config.signing_audit_log_max_size = megabytes * 1024 * 1024;
+        }
+
+        if let Some(backend) = parse_optional(cli_args, "slashing-protection-backend")? {
+            config.slashing_protection_backend = backend;
         }
 
+        config.force_slashing_protection_lock = cli_args.is_present("force");
+
         Ok(config)
     }
 }
+
+/// Returns the graffiti given by the `--graffiti` flag, if any.
+pub fn get_graffiti(cli_args: &ArgMatches) -> Result<Option<Graffiti>, String> {
+    if let Some(input_graffiti) = cli_args.value_of("graffiti") {
+        let graffiti_bytes = input_graffiti.as_bytes();
+        if graffiti_bytes.len() > GRAFFITI_BYTES_LEN {
+            Err(format!(
+                "Your graffiti is too long! {} bytes maximum!",
+                GRAFFITI_BYTES_LEN
+            ))
+        } else {
+            // Default graffiti to all 0 bytes.
+            let mut graffiti = Graffiti::default();
+
+            // Copy the provided bytes over.
+            //
+            // Panic-free because `graffiti_bytes.len()` <= `GRAFFITI_BYTES_LEN`.
+            graffiti[..graffiti_bytes.len()].copy_from_slice(&graffiti_bytes);
+
+            Ok(Some(graffiti))
+        }
+    } else {
+        Ok(None)
+    }
+}
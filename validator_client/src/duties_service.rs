@@ -1,20 +1,27 @@
 use crate::{
-    block_service::BlockServiceNotification, is_synced::is_synced, validator_store::ValidatorStore,
+    aggregation_stats::AggregationStats,
+    attestation_inclusion::AttestationInclusionTracker,
+    block_service::BlockServiceNotification,
+    is_synced::is_synced,
+    metrics,
+    slot_pipeline::{SlotPhase, SlotPipeline},
+    validator_store::ValidatorStore,
 };
 use environment::RuntimeContext;
 use futures::channel::mpsc::Sender;
-use futures::{SinkExt, StreamExt};
+use futures::SinkExt;
 use parking_lot::RwLock;
 use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
 use rest_types::{ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription};
-use slog::{debug, error, trace, warn};
+use slog::{debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, CommitteeIndex, Epoch, EthSpec, PublicKey, SelectionProof, Slot};
+use tokio::time::{delay_for, Duration, Instant};
+use types::{CommitteeIndex, Epoch, EthSpec, PublicKey, SelectionProof, Slot};
 
 /// Delay this period of time after the slot starts. This allows the node to process the new slot.
 const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(100);
@@ -106,6 +113,21 @@ impl DutyAndProof {
     pub fn validator_pubkey(&self) -> &PublicKey {
         &self.duty.validator_pubkey
     }
+
+    /// Returns the beacon committee subscription required to perform the attestation duty, if
+    /// any.
+    pub fn subscription(&self) -> Option<ValidatorSubscription> {
+        let (slot, attestation_committee_index, _, validator_index, committee_count_at_slot) =
+            self.attestation_duties()?;
+
+        Some(ValidatorSubscription {
+            validator_index,
+            attestation_committee_index,
+            slot,
+            committee_count_at_slot,
+            is_aggregator: self.selection_proof.is_some(),
+        })
+    }
 }
 
 impl TryInto<DutyAndProof> for ValidatorDutyBytes {
@@ -122,7 +144,9 @@ impl TryInto<DutyAndProof> for ValidatorDutyBytes {
             attestation_committee_position: self.attestation_committee_position,
             committee_count_at_slot: self.committee_count_at_slot,
             block_proposal_slots: self.block_proposal_slots,
+            block_proposal_dependent_root: self.block_proposal_dependent_root,
             aggregator_modulo: self.aggregator_modulo,
+            extra: self.extra,
         };
         Ok(DutyAndProof {
             duty,
@@ -150,6 +174,18 @@ enum InsertOutcome {
 }
 
 impl InsertOutcome {
+    /// Returns `true` if the outcome required a selection proof to be computed.
+    pub fn computes_selection_proof(&self) -> bool {
+        match self {
+            InsertOutcome::NewValidator
+            | InsertOutcome::NewEpoch
+            | InsertOutcome::Replaced { .. } => true,
+            InsertOutcome::NewProposalSlots | InsertOutcome::Identical | InsertOutcome::Invalid => {
+                false
+            }
+        }
+    }
+
     /// Returns `true` if the outcome indicates that the validator _might_ require a subscription.
     pub fn is_subscription_candidate(&self) -> bool {
         match self {
@@ -243,6 +279,17 @@ impl DutiesStore {
             .collect()
     }
 
+    /// Returns the subscriptions for all known attestation duties at or after `slot`.
+    fn subscriptions_from(&self, slot: Slot) -> Vec<ValidatorSubscription> {
+        self.store
+            .read()
+            .values()
+            .flat_map(|validator_map| validator_map.values())
+            .filter_map(DutyAndProof::subscription)
+            .filter(|subscription| subscription.slot >= slot)
+            .collect()
+    }
+
     fn is_aggregator(&self, validator_pubkey: &PublicKey, epoch: Epoch) -> Option<bool> {
         Some(
             self.store
@@ -273,9 +320,15 @@ impl DutiesStore {
             if let Some(known_duties) = validator_map.get_mut(&epoch) {
                 if known_duties.duty.eq_ignoring_proposal_slots(&duties.duty) {
                     if known_duties.duty.block_proposal_slots == duties.duty.block_proposal_slots {
+                        // Proposal slots computed ahead of time may be confirmed once the epoch
+                        // begins.
+                        known_duties.duty.block_proposal_dependent_root =
+                            duties.duty.block_proposal_dependent_root;
                         Ok(InsertOutcome::Identical)
                     } else if duties.duty.block_proposal_slots.is_some() {
                         known_duties.duty.block_proposal_slots = duties.duty.block_proposal_slots;
+                        known_duties.duty.block_proposal_dependent_root =
+                            duties.duty.block_proposal_dependent_root;
                         Ok(InsertOutcome::NewProposalSlots)
                     } else {
                         Ok(InsertOutcome::Invalid)
@@ -374,6 +427,9 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
         Ok(DutiesService {
             inner: Arc::new(Inner {
                 store: Arc::new(DutiesStore::default()),
+                resubscription_required: AtomicBool::new(false),
+                aggregation_stats: AggregationStats::default(),
+                attestation_inclusion: AttestationInclusionTracker::default(),
                 validator_store: self
                     .validator_store
                     .ok_or_else(|| "Cannot build DutiesService without validator_store")?,
@@ -395,6 +451,14 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
 /// Helper to minimise `Arc` usage.
 pub struct Inner<T, E: EthSpec> {
     store: Arc<DutiesStore>,
+    /// Set when the beacon node may have lost our beacon committee subscriptions (e.g., because
+    /// it was unreachable, restarted or replaced), so that they are all sent again.
+    resubscription_required: AtomicBool,
+    /// Statistics about aggregation duties, reported by the notifier.
+    pub(crate) aggregation_stats: AggregationStats,
+    /// Tracks the inclusion of published attestations, reported by the attestation service and
+    /// the notifier.
+    pub(crate) attestation_inclusion: AttestationInclusionTracker,
     validator_store: ValidatorStore<T, E>,
     pub(crate) slot_clock: T,
     pub(crate) beacon_node: RemoteBeaconNode<E>,
@@ -462,61 +526,108 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
     pub fn start_update_service(
         self,
         mut block_service_tx: Sender<BlockServiceNotification>,
-        spec: &ChainSpec,
     ) -> Result<(), String> {
-        let duration_to_next_slot = self
-            .slot_clock
+        // Ensure the slot clock can be read before starting the service.
+        self.slot_clock
             .duration_to_next_slot()
             .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
 
-        let mut interval = {
-            let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
-            // Note: `interval_at` panics if `slot_duration` is 0
-            interval_at(
-                Instant::now() + duration_to_next_slot + TIME_DELAY_FROM_SLOT,
-                slot_duration,
-            )
-        };
-
-        // Run an immediate update before starting the updater service.
-        let duties_service = self.clone();
-        let mut block_service_tx_clone = block_service_tx.clone();
-        self.inner
-            .context
-            .executor
-            .runtime_handle()
-            .spawn(async move { duties_service.do_update(&mut block_service_tx_clone).await });
+        let mut pipeline = SlotPipeline::new(SlotPhase::BlockProposal);
+
+        // Run an immediate update before starting the updater service, notifying the block
+        // service if it is not too late to propose in the current slot.
+        if let Some(slot) = self.slot_clock.now() {
+            let notify_block_service = !SlotPhase::BlockProposal.is_stale(&self.slot_clock, slot);
+            pipeline.mark_run(slot);
+
+            let duties_service = self.clone();
+            let mut block_service_tx_clone = block_service_tx.clone();
+            self.inner
+                .context
+                .executor
+                .runtime_handle()
+                .spawn(async move {
+                    duties_service
+                        .do_update(&mut block_service_tx_clone, slot, notify_block_service)
+                        .await
+                });
+        }
 
         let executor = self.inner.context.executor.clone();
 
-        let interval_fut = async move {
-            while interval.next().await.is_some() {
-                self.clone().do_update(&mut block_service_tx).await;
+        let pipeline_fut = async move {
+            loop {
+                let tick = pipeline.next(&self.slot_clock).await;
+                let slot = match tick.present_slot() {
+                    Some(slot) => slot,
+                    None => continue,
+                };
+
+                for slot in tick.skipped {
+                    self.skip_block_proposals(slot);
+                }
+
+                delay_for(TIME_DELAY_FROM_SLOT).await;
+
+                self.clone()
+                    .do_update(&mut block_service_tx, slot, tick.slot.is_some())
+                    .await;
             }
         };
 
-        executor.spawn(interval_fut, "duties_service");
+        executor.spawn(pipeline_fut, "duties_service");
 
         Ok(())
     }
 
-    /// Attempt to download the duties of all managed validators for this epoch and the next.
-    async fn do_update(self, block_service_tx: &mut Sender<BlockServiceNotification>) {
+    /// Records that the blocks due to be proposed in `slot` will not be produced, because the
+    /// service was unable to run at the start of the slot (e.g., due to a long pause or a jump of
+    /// the system clock).
+    fn skip_block_proposals(&self, slot: Slot) {
+        let proposers = self.block_proposers(slot);
+
+        if proposers.is_empty() {
+            return;
+        }
+
+        metrics::record_failed_duty(
+            &metrics::FAILED_BLOCK_PROPOSALS_TOTAL,
+            metrics::BLOCK,
+            "stale_slot",
+            slot,
+            proposers.len(),
+        );
+        warn!(
+            self.context.log(),
+            "Skipped stale block proposal duties";
+            "info" => "Your machine could be overloaded or its clock could have jumped",
+            "proposers" => proposers.len(),
+            "slot" => slot.as_u64(),
+        );
+    }
+
+    /// Attempt to download the duties of all managed validators for the epoch of `slot` and the
+    /// next.
+    ///
+    /// If `notify_block_service` is `true`, the block service is notified of any block proposals
+    /// due in `slot`.
+    async fn do_update(
+        self,
+        block_service_tx: &mut Sender<BlockServiceNotification>,
+        slot: Slot,
+        notify_block_service: bool,
+    ) {
         let log = self.context.log();
 
         if !is_synced(&self.beacon_node, &self.slot_clock, None).await
             && !self.allow_unsynced_beacon_node
         {
+            // The beacon node may be restarting or may have been replaced, in which case it will
+            // have forgotten our subscriptions.
+            self.require_resubscription();
             return;
         }
 
-        let slot = if let Some(slot) = self.slot_clock.now() {
-            slot
-        } else {
-            error!(log, "Duties manager failed to read slot clock");
-            return;
-        };
-
         let current_epoch = slot.epoch(E::slots_per_epoch());
 
         if slot % E::slots_per_epoch() == 0 {
@@ -532,9 +643,22 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             self.store.prune(prune_below);
         }
 
+        // Replay the subscriptions for the duties we already know before fetching new duties, so
+        // that the subscriptions for new duties are not sent twice.
+        if self.resubscription_required.load(Ordering::Relaxed) {
+            if let Err(e) = self.resubscribe_all(slot).await {
+                error!(
+                    log,
+                    "Failed to resubscribe validators";
+                    "error" => e
+                );
+            }
+        }
+
         // Update duties for the current epoch, but keep running if there's an error:
         // block production or the next epoch update could still succeed.
         if let Err(e) = self.clone().update_epoch(current_epoch).await {
+            self.require_resubscription();
             error!(
                 log,
                 "Failed to get current epoch duties";
@@ -543,22 +667,25 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
         }
 
         // Notify the block service to produce a block.
-        if let Err(e) = block_service_tx
-            .send(BlockServiceNotification {
-                slot,
-                block_proposers: self.block_proposers(slot),
-            })
-            .await
-        {
-            error!(
-                log,
-                "Failed to notify block service";
-                "error" => format!("{:?}", e)
-            );
-        };
+        if notify_block_service {
+            if let Err(e) = block_service_tx
+                .send(BlockServiceNotification {
+                    slot,
+                    block_proposers: self.block_proposers(slot),
+                })
+                .await
+            {
+                error!(
+                    log,
+                    "Failed to notify block service";
+                    "error" => format!("{:?}", e)
+                );
+            };
+        }
 
         // Update duties for the next epoch.
         if let Err(e) = self.clone().update_epoch(current_epoch + 1).await {
+            self.require_resubscription();
             error!(
                 log,
                 "Failed to get next epoch duties";
@@ -567,16 +694,79 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
         }
     }
 
+    /// Causes the beacon committee subscriptions of all known duties to be sent to the beacon
+    /// node during the next update.
+    ///
+    /// This should be called whenever the beacon node may have lost the subscriptions, e.g.
+    /// because it was unreachable or is a different node to the one previously used.
+    pub fn require_resubscription(&self) {
+        self.resubscription_required.store(true, Ordering::Relaxed);
+    }
+
+    /// Sends the subscriptions for all known attestation duties at or after `slot` to the beacon
+    /// node.
+    async fn resubscribe_all(&self, slot: Slot) -> Result<(), String> {
+        let log = self.context.log();
+        let subscriptions = self.store.subscriptions_from(slot);
+        let count = subscriptions.len();
+
+        // Clear the flag before subscribing so that a request for resubscription made while the
+        // subscriptions are in flight is not lost.
+        self.resubscription_required.store(false, Ordering::Relaxed);
+
+        if count == 0 {
+            return Ok(());
+        }
+
+        let result = {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_REQUEST_TIMES,
+                &[metrics::SUBSCRIBE],
+            );
+            self.beacon_node
+                .http
+                .validator()
+                .subscribe(subscriptions)
+                .await
+        }
+        .map_err(|e| format!("{:?}", e))
+        .and_then(|status| match status {
+            PublishStatus::Valid | PublishStatus::AlreadyKnown => Ok(()),
+            PublishStatus::Unknown => Err("Unknown response from subscription".to_string()),
+            PublishStatus::Invalid(e) => Err(e),
+        });
+
+        match result {
+            Ok(()) => {
+                info!(
+                    log,
+                    "Resubscribed validators to beacon node";
+                    "count" => count,
+                );
+                Ok(())
+            }
+            Err(e) => {
+                self.require_resubscription();
+                Err(e)
+            }
+        }
+    }
+
     /// Attempt to download the duties of all managed validators for the given `epoch`.
     async fn update_epoch(self, epoch: Epoch) -> Result<(), String> {
         let pubkeys = self.validator_store.voting_pubkeys();
-        let all_duties = self
-            .beacon_node
-            .http
-            .validator()
-            .get_duties(epoch, pubkeys.as_slice())
-            .await
-            .map_err(move |e| format!("Failed to get duties for epoch {}: {:?}", epoch, e))?;
+        let all_duties = {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_REQUEST_TIMES,
+                &[metrics::GET_DUTIES],
+            );
+            self.beacon_node
+                .http
+                .validator()
+                .get_duties(epoch, pubkeys.as_slice())
+                .await
+        }
+        .map_err(move |e| format!("Failed to get duties for epoch {}: {:?}", epoch, e))?;
 
         let log = self.context.log().clone();
 
@@ -608,6 +798,7 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                 let validator_pubkey = duties.duty.validator_pubkey.clone();
 
                 // Attempt to update our local store.
+                let insert_instant = Instant::now();
                 let outcome = self
                     .store
                     .insert(epoch, duties, E::slots_per_epoch(), &self.validator_store)
@@ -620,6 +811,13 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                     })
                     .ok()?;
 
+                // Inserting duties is dominated by the time taken to compute the selection proof,
+                // if one was required.
+                if outcome.computes_selection_proof() && remote_duties.aggregator_modulo.is_some() {
+                    self.aggregation_stats
+                        .record_selection_proof(epoch, insert_instant.elapsed());
+                }
+
                 match &outcome {
                     InsertOutcome::NewValidator => {
                         debug!(
@@ -693,30 +891,36 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
 
             Ok(())
         } else {
-            self.beacon_node
-                .http
-                .validator()
-                .subscribe(validator_subscriptions)
-                .await
-                .map_err(|e| format!("Failed to subscribe validators: {:?}", e))
-                .map(move |status| {
-                    match status {
-                        PublishStatus::Valid => debug!(
-                            log,
-                            "Successfully subscribed validators";
-                            "count" => count
-                        ),
-                        PublishStatus::Unknown => error!(
-                            log,
-                            "Unknown response from subscription";
-                        ),
-                        PublishStatus::Invalid(e) => error!(
-                            log,
-                            "Failed to subscribe validator";
-                            "error" => e
-                        ),
-                    };
-                })
+            {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::BEACON_NODE_REQUEST_TIMES,
+                    &[metrics::SUBSCRIBE],
+                );
+                self.beacon_node
+                    .http
+                    .validator()
+                    .subscribe(validator_subscriptions)
+                    .await
+            }
+            .map_err(|e| format!("Failed to subscribe validators: {:?}", e))
+            .map(move |status| {
+                match status {
+                    PublishStatus::Valid | PublishStatus::AlreadyKnown => debug!(
+                        log,
+                        "Successfully subscribed validators";
+                        "count" => count
+                    ),
+                    PublishStatus::Unknown => error!(
+                        log,
+                        "Unknown response from subscription";
+                    ),
+                    PublishStatus::Invalid(e) => error!(
+                        log,
+                        "Failed to subscribe validator";
+                        "error" => e
+                    ),
+                };
+            })
         }
     }
 }
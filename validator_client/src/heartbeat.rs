@@ -0,0 +1,38 @@
+use crate::ProductionValidatorClient;
+use futures::StreamExt;
+use slashing_protection::{NotSafe, HEARTBEAT_INTERVAL};
+use slog::{crit, error};
+use tokio::time::interval;
+use types::EthSpec;
+
+/// Spawns a service which refreshes the heartbeat of this process in the slashing protection
+/// database, so that other validator clients refuse to start whilst it is running.
+pub fn spawn_heartbeat<T: EthSpec>(client: &ProductionValidatorClient<T>) {
+    let context = client.context.service_context("heartbeat".into());
+    let executor = context.executor.clone();
+    let instance_lock = client.instance_lock.clone();
+
+    let interval_fut = async move {
+        let log = context.log();
+        let mut interval = interval(HEARTBEAT_INTERVAL);
+
+        while interval.next().await.is_some() {
+            match instance_lock.heartbeat() {
+                Ok(()) => (),
+                Err(NotSafe::InstanceLocked(reason)) => crit!(
+                    log,
+                    "Another validator client is using the slashing protection database";
+                    "msg" => "it must be stopped, running both WILL lead to slashing",
+                    "reason" => reason,
+                ),
+                Err(e) => error!(
+                    log,
+                    "Unable to refresh slashing protection heartbeat";
+                    "error" => format!("{:?}", e),
+                ),
+            }
+        }
+    };
+
+    executor.spawn(interval_fut, "validator_heartbeat");
+}
@@ -0,0 +1,299 @@
+//! Serves the validator client's Prometheus metrics and signing audit log over HTTP.
+
+use crate::signing_audit_log::SigningAuditLog;
+use environment::TaskExecutor;
+use futures::future::TryFutureExt;
+use hyper::header::HeaderValue;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use lighthouse_metrics::{Encoder, TextEncoder};
+use serde_derive::{Deserialize, Serialize};
+use slog::{info, warn};
+use std::net::{Ipv4Addr, SocketAddr};
+
+/// The default port of the metrics HTTP server.
+pub const DEFAULT_PORT: u16 = 5064;
+
+/// The number of signing audit log entries returned when no `limit` is given.
+pub const DEFAULT_AUDIT_LOG_LIMIT: usize = 100;
+/// The maximum number of signing audit log entries returned in a single response.
+pub const MAX_AUDIT_LOG_LIMIT: usize = 1_000;
+
+/// Metrics HTTP server configuration, with the same shape as that of the beacon node's HTTP
+/// server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Enable the metrics HTTP server.
+    pub enabled: bool,
+    /// The IPv4 address the metrics HTTP server will listen on.
+    pub listen_address: Ipv4Addr,
+    /// The port the metrics HTTP server will listen on.
+    pub port: u16,
+    /// If something else than "", a 'Access-Control-Allow-Origin' header will be present in
+    /// responses.  Put *, to allow any origin.
+    pub allow_origin: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enabled: false,
+            listen_address: Ipv4Addr::new(127, 0, 0, 1),
+            port: DEFAULT_PORT,
+            allow_origin: "".to_string(),
+        }
+    }
+}
+
+/// Starts an HTTP server which serves the metrics at `GET /metrics`, returning the address it is
+/// listening on.
+///
+/// If `signing_audit_log` is supplied, it is served at `GET /signing_audit_log`, paged using the
+/// `offset` and `limit` query parameters.
+pub fn start_server(
+    config: &Config,
+    executor: TaskExecutor,
+    signing_audit_log: Option<SigningAuditLog>,
+) -> Result<SocketAddr, String> {
+    let log = executor.log().clone();
+
+    let allow_origin = if config.allow_origin.is_empty() {
+        None
+    } else {
+        Some(
+            HeaderValue::from_str(&config.allow_origin)
+                .map_err(|_| "Invalid allow-origin value".to_string())?,
+        )
+    };
+
+    let make_service = make_service_fn(move |_socket: &AddrStream| {
+        let signing_audit_log = signing_audit_log.clone();
+        let allow_origin = allow_origin.clone();
+        async move {
+            Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
+                let signing_audit_log = signing_audit_log.clone();
+                let allow_origin = allow_origin.clone();
+                async move {
+                    let mut response = on_http_request(req, signing_audit_log);
+                    if let Some(allow_origin) = allow_origin {
+                        let headers = response.headers_mut();
+                        headers.insert(hyper::header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+                        headers.insert(hyper::header::VARY, HeaderValue::from_static("Origin"));
+                    }
+                    Ok::<_, hyper::Error>(response)
+                }
+            }))
+        }
+    });
+
+    let bind_addr = (config.listen_address, config.port).into();
+    let server = Server::try_bind(&bind_addr)
+        .map_err(|e| format!("Unable to bind metrics server to {}: {}", bind_addr, e))?
+        .serve(make_service);
+
+    let actual_listen_addr = server.local_addr();
+
+    let exit = executor.exit();
+    let inner_log = log.clone();
+    let server_future = server
+        .with_graceful_shutdown(async {
+            let _ = exit.await;
+        })
+        .map_err(move |e| {
+            warn!(
+                inner_log,
+                "Metrics server failed";
+                "error" => format!("{:?}", e)
+            )
+        })
+        .unwrap_or_else(|_| ());
+
+    info!(
+        log,
+        "Metrics server started";
+        "address" => format!("{}", actual_listen_addr.ip()),
+        "port" => actual_listen_addr.port(),
+    );
+
+    executor.spawn_without_exit(server_future, "http_metrics");
+
+    Ok(actual_listen_addr)
+}
+
+fn on_http_request(
+    req: Request<Body>,
+    signing_audit_log: Option<SigningAuditLog>,
+) -> Response<Body> {
+    match (req.method(), req.uri().path(), signing_audit_log) {
+        (&Method::GET, "/metrics", _) => metrics_response(),
+        (&Method::GET, "/signing_audit_log", Some(signing_audit_log)) => {
+            signing_audit_log_response(req.uri().query(), &signing_audit_log)
+        }
+        _ => response(
+            StatusCode::NOT_FOUND,
+            "Request path and/or method not found.",
+        ),
+    }
+}
+
+fn metrics_response() -> Response<Body> {
+    let mut buffer = vec![];
+    let encoder = TextEncoder::new();
+
+    match encoder.encode(&lighthouse_metrics::gather(), &mut buffer) {
+        Ok(()) => match String::from_utf8(buffer) {
+            Ok(body) => response(StatusCode::OK, body),
+            Err(e) => response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Unable to encode metrics as utf8: {:?}", e),
+            ),
+        },
+        Err(e) => response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Unable to encode metrics: {:?}", e),
+        ),
+    }
+}
+
+fn signing_audit_log_response(
+    query: Option<&str>,
+    signing_audit_log: &SigningAuditLog,
+) -> Response<Body> {
+    let mut offset = 0;
+    let mut limit = DEFAULT_AUDIT_LOG_LIMIT;
+
+    for pair in query
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+    {
+        let mut split = pair.splitn(2, '=');
+        let key = split.next().unwrap_or("");
+        let value = split.next().unwrap_or("");
+
+        let target = match key {
+            "offset" => &mut offset,
+            "limit" => &mut limit,
+            _ => {
+                return response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown query parameter: {}", key),
+                )
+            }
+        };
+
+        match value.parse() {
+            Ok(value) => *target = value,
+            Err(e) => {
+                return response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid value for {}: {:?}", key, e),
+                )
+            }
+        }
+    }
+
+    if limit > MAX_AUDIT_LOG_LIMIT {
+        return response(
+            StatusCode::BAD_REQUEST,
+            format!("The limit must not exceed {}", MAX_AUDIT_LOG_LIMIT),
+        );
+    }
+
+    match signing_audit_log
+        .read(offset, limit)
+        .and_then(|entries| serde_json::to_string(&entries).map_err(|e| format!("{:?}", e)))
+    {
+        Ok(body) => {
+            let mut response = response(StatusCode::OK, body);
+            response.headers_mut().insert(
+                hyper::header::CONTENT_TYPE,
+                hyper::header::HeaderValue::from_static("application/json"),
+            );
+            response
+        }
+        Err(e) => response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Unable to read signing audit log: {}", e),
+        ),
+    }
+}
+
+fn response<T: Into<Body>>(status: StatusCode, body: T) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics;
+    use hyper::header::{HeaderMap, ACCESS_CONTROL_ALLOW_ORIGIN, VARY};
+    use hyper::Client;
+    use tokio::runtime::Runtime;
+
+    /// Starts a metrics server with the given `allow_origin`, returning the headers and body of
+    /// the response to `GET /metrics`.
+    fn get_metrics(allow_origin: &str) -> Result<(HeaderMap, String), String> {
+        let mut runtime = Runtime::new().expect("should start runtime");
+        let (_signal, exit) = exit_future::signal();
+        let (signal_tx, _signal_rx) = futures::channel::mpsc::channel(1);
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        let executor = TaskExecutor::new(runtime.handle().clone(), exit, log, signal_tx);
+
+        let config = Config {
+            enabled: true,
+            port: 0,
+            allow_origin: allow_origin.to_string(),
+            ..Config::default()
+        };
+        let addr = runtime.enter(|| start_server(&config, executor, None))?;
+
+        runtime.block_on(async move {
+            let url = format!("http://{}/metrics", addr)
+                .parse()
+                .expect("should parse url");
+            let response = Client::new().get(url).await.expect("should get metrics");
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let headers = response.headers().clone();
+            let body = hyper::body::to_bytes(response.into_body())
+                .await
+                .expect("should read body");
+            Ok((
+                headers,
+                String::from_utf8(body.to_vec()).expect("should be utf8"),
+            ))
+        })
+    }
+
+    #[test]
+    fn serves_successful_duties() {
+        metrics::record_successful_duty(metrics::BLOCK, 1);
+
+        let (headers, body) = get_metrics("").expect("should start server");
+        assert!(
+            body.contains("vc_successful_duties_total{duty=\"block\"}"),
+            "{}",
+            body
+        );
+        assert!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[test]
+    fn allow_origin() {
+        let (headers, _) = get_metrics("*").expect("should start server");
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(&HeaderValue::from_static("*"))
+        );
+        assert_eq!(headers.get(VARY), Some(&HeaderValue::from_static("Origin")));
+
+        assert!(
+            get_metrics("\n").is_err(),
+            "should not start with an invalid allow-origin"
+        );
+    }
+}
@@ -12,6 +12,8 @@ const SYNC_TOLERANCE: u64 = 4;
 /// Returns `false` if:
 ///
 ///  - The beacon node is unreachable.
+///  - The beacon node's head was imported optimistically (its execution payload has not been
+///  validated).
 ///  - The beacon node indicates that it is syncing **AND** it is more than `SYNC_TOLERANCE` behind
 ///  the highest known slot.
 ///
@@ -38,12 +40,27 @@ pub async fn is_synced<T: SlotClock, E: EthSpec>(
     };
 
     match &resp {
+        SyncingResponse {
+            is_optimistic: true,
+            ..
+        } => {
+            if let Some(log) = log_opt {
+                error!(
+                    log,
+                    "Beacon node head is optimistic";
+                    "msg" => "not receiving new duties until the execution engine has validated \
+                    the head",
+                );
+            }
+            false
+        }
         SyncingResponse {
             is_syncing: false, ..
         } => true,
         SyncingResponse {
             is_syncing: true,
             sync_status,
+            ..
         } => {
             if let Some(log) = log_opt {
                 debug!(
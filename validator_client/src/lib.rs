@@ -1,12 +1,22 @@
+#[macro_use]
+extern crate lazy_static;
+
+mod aggregation_stats;
+mod attestation_inclusion;
 mod attestation_service;
 mod block_service;
 mod cli;
 mod config;
 mod duties_service;
 mod fork_service;
+mod heartbeat;
+pub mod http_metrics;
 mod initialized_validators;
 mod is_synced;
+mod metrics;
 mod notifier;
+mod signing_audit_log;
+mod slot_pipeline;
 mod validator_store;
 
 pub use cli::cli_app;
@@ -18,19 +28,29 @@ use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
 use duties_service::{DutiesService, DutiesServiceBuilder};
 use environment::RuntimeContext;
+use eth2_config::Eth2Config;
 use fork_service::{ForkService, ForkServiceBuilder};
 use futures::channel::mpsc;
+use heartbeat::spawn_heartbeat;
 use initialized_validators::InitializedValidators;
+use is_synced::is_synced;
 use notifier::spawn_notifier;
-use remote_beacon_node::RemoteBeaconNode;
-use slog::{error, info, Logger};
+use remote_beacon_node::{RemoteBeaconNode, SpecConfig};
+use signing_audit_log::SigningAuditLog;
+use slashing_protection::{InstanceLock, NotSafe, SlashingProtection};
+use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{delay_for, Duration};
-use types::EthSpec;
+use types::{ChainSpec, EthSpec};
 use validator_store::ValidatorStore;
 
+/// The flags of the validator client which `ProductionValidatorClient::reload_config` applies to a
+/// running client. Changes to any other flag require a restart.
+pub const HOT_RELOADABLE_FLAGS: &[&str] = &["graffiti"];
+
 /// The interval between attempts to contact the beacon node during startup.
 const RETRY_DELAY: Duration = Duration::from_secs(2);
 
@@ -43,6 +63,8 @@ pub struct ProductionValidatorClient<T: EthSpec> {
     fork_service: ForkService<SystemTimeSlotClock, T>,
     block_service: BlockService<SystemTimeSlotClock, T>,
     attestation_service: AttestationService<SystemTimeSlotClock, T>,
+    signing_audit_log: Option<SigningAuditLog>,
+    instance_lock: Arc<InstanceLock>,
     config: Config,
 }
 
@@ -70,6 +92,43 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             "datadir" => format!("{:?}", config.data_dir),
         );
 
+        // Lock the slashing protection database before anything else (e.g., the lockfiles of the
+        // validator keystores) is touched, so that a duplicate validator client exits without
+        // disturbing the one which is running.
+        let slashing_protection = SlashingProtection::open_or_create(
+            config.slashing_protection_backend,
+            &config.data_dir,
+        )
+        .map_err(|e| {
+            format!(
+                "Failed to open or create slashing protection database: {:?}",
+                e
+            )
+        })?;
+        let instance_lock = InstanceLock::acquire(
+            slashing_protection.clone(),
+            &config.data_dir,
+            config.force_slashing_protection_lock,
+        )
+        .map_err(|e| match e {
+            NotSafe::InstanceLocked(reason) => format!(
+                "Another validator client appears to be using the slashing protection database \
+                ({}). Running both WILL lead to slashing. If you are certain that it has \
+                stopped, use --force",
+                reason
+            ),
+            e => format!("Failed to lock slashing protection database: {:?}", e),
+        })?;
+
+        if let Some(reason) = instance_lock.overridden() {
+            warn!(
+                log,
+                "Forced the slashing protection lock";
+                "msg" => "ensure no other validator client is using these keys",
+                "reason" => reason,
+            );
+        }
+
         let mut validator_defs = ValidatorDefinitions::open_or_create(&config.data_dir)
             .map_err(|e| format!("Unable to open or create validator definitions: {:?}", e))?;
 
@@ -108,43 +167,14 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         // TODO: check if all logs in wait_for_node are produed while awaiting
         let beacon_node = wait_for_node(beacon_node, &log).await?;
-        let eth2_config = beacon_node
+        let spec_config = beacon_node
             .http
             .spec()
-            .get_eth2_config()
-            .await
-            .map_err(|e| format!("Unable to read eth2 config from beacon node: {:?}", e))?;
-        let genesis_time = beacon_node
-            .http
-            .beacon()
-            .get_genesis_time()
+            .get_config()
             .await
-            .map_err(|e| format!("Unable to read genesis time from beacon node: {:?}", e))?;
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| format!("Unable to read system time: {:?}", e))?;
-        let genesis = Duration::from_secs(genesis_time);
-
-        // If the time now is less than (prior to) genesis, then delay until the
-        // genesis instant.
-        //
-        // If the validator client starts before genesis, it will get errors from
-        // the slot clock.
-        if now < genesis {
-            info!(
-                log,
-                "Starting node prior to genesis";
-                "seconds_to_wait" => (genesis - now).as_secs()
-            );
-
-            delay_for(genesis - now).await
-        } else {
-            info!(
-                log,
-                "Genesis has already occurred";
-                "seconds_ago" => (now - genesis).as_secs()
-            );
-        }
+            .map_err(|e| format!("Unable to read spec config from beacon node: {:?}", e))?;
+        let genesis_time =
+            wait_for_genesis(&beacon_node, config.genesis_poll_interval, &log).await?;
         let genesis_validators_root = beacon_node
             .http
             .beacon()
@@ -157,22 +187,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 )
             })?;
 
-        // Do not permit a connection to a beacon node using different spec constants.
-        if context.eth2_config.spec_constants != eth2_config.spec_constants {
-            return Err(format!(
-                "Beacon node is using an incompatible spec. Got {}, expected {}",
-                eth2_config.spec_constants, context.eth2_config.spec_constants
-            ));
-        }
-
-        // Note: here we just assume the spec variables of the remote node. This is very useful
-        // for testnets, but perhaps a security issue when it comes to mainnet.
-        //
-        // A damaging attack would be for a beacon node to convince the validator client of a
-        // different `SLOTS_PER_EPOCH` variable. This could result in slashable messages being
-        // produced. We are safe from this because `SLOTS_PER_EPOCH` is a type-level constant
-        // for Lighthouse.
-        context.eth2_config = eth2_config;
+        context.eth2_config.spec = beacon_node_spec::<T>(&context.eth2_config, &spec_config)?;
 
         let slot_clock = SystemTimeSlotClock::new(
             context.eth2_config.spec.genesis_slot,
@@ -180,6 +195,16 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             Duration::from_millis(context.eth2_config.spec.milliseconds_per_slot),
         );
 
+        if config.allow_unsynced_beacon_node {
+            info!(
+                log,
+                "Not waiting for beacon node to sync";
+                "msg" => "duties may be incorrect until the beacon node is synced",
+            );
+        } else {
+            wait_for_sync(&beacon_node, &slot_clock, config.sync_poll_interval, &log).await;
+        }
+
         let fork_service = ForkServiceBuilder::new()
             .slot_clock(slot_clock.clone())
             .beacon_node(beacon_node.clone())
@@ -188,6 +213,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let validator_store: ValidatorStore<SystemTimeSlotClock, T> = ValidatorStore::new(
             validators,
+            slashing_protection,
             &config,
             genesis_validators_root,
             context.eth2_config.spec.clone(),
@@ -203,6 +229,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         validator_store.register_all_validators_for_slashing_protection()?;
 
+        let signing_audit_log = validator_store.signing_audit_log();
+
         let duties_service = DutiesServiceBuilder::new()
             .slot_clock(slot_clock.clone())
             .validator_store(validator_store.clone())
@@ -233,10 +261,20 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             fork_service,
             block_service,
             attestation_service,
+            signing_audit_log,
+            instance_lock: Arc::new(instance_lock),
             config,
         })
     }
 
+    /// Applies the `HOT_RELOADABLE_FLAGS` given in `matches` to the running client.
+    pub fn reload_config(&self, matches: &ArgMatches) -> Result<(), String> {
+        let graffiti = config::get_graffiti(matches)?;
+        self.block_service.set_graffiti(graffiti);
+
+        Ok(())
+    }
+
     pub fn start_service(&mut self) -> Result<(), String> {
         // We use `SLOTS_PER_EPOCH` as the capacity of the block notification channel, because
         // we don't except notifications to be delayed by more than a single slot, let alone a
@@ -246,7 +284,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         self.duties_service
             .clone()
-            .start_update_service(block_service_tx, &self.context.eth2_config.spec)
+            .start_update_service(block_service_tx)
             .map_err(|e| format!("Unable to start duties service: {}", e))?;
 
         self.fork_service
@@ -261,15 +299,57 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         self.attestation_service
             .clone()
-            .start_update_service(&self.context.eth2_config.spec)
+            .start_update_service()
             .map_err(|e| format!("Unable to start attestation service: {}", e))?;
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
+        spawn_heartbeat(self);
+
+        if self.config.http_metrics.enabled {
+            http_metrics::start_server(
+                &self.config.http_metrics,
+                self.context.executor.clone(),
+                self.signing_audit_log.clone(),
+            )
+            .map_err(|e| format!("Unable to start metrics server: {}", e))?;
+        }
+
         Ok(())
     }
 }
 
+/// Returns the spec of the beacon node, given by applying its `spec_config` to the spec of
+/// `eth2_config`.
+///
+/// Errors if the beacon node uses different spec constants, or preset values which differ from
+/// those of `E`.
+fn beacon_node_spec<E: EthSpec>(
+    eth2_config: &Eth2Config,
+    spec_config: &SpecConfig,
+) -> Result<ChainSpec, String> {
+    // Do not permit a connection to a beacon node using different spec constants.
+    if eth2_config.spec_constants != spec_config.config_name {
+        return Err(format!(
+            "Beacon node is using an incompatible spec. Got {}, expected {}",
+            spec_config.config_name, eth2_config.spec_constants
+        ));
+    }
+
+    // Note: here we just assume the spec variables of the remote node. This is very useful
+    // for testnets and custom chains, which are configured automatically, but perhaps a
+    // security issue when it comes to mainnet.
+    //
+    // A damaging attack would be for a beacon node to convince the validator client of a
+    // different `SLOTS_PER_EPOCH` variable. This could result in slashable messages being
+    // produced. We are safe from this because `SLOTS_PER_EPOCH` is a type-level constant
+    // for Lighthouse, and `apply_to_chain_spec` rejects configs with other preset values.
+    spec_config
+        .yaml_config
+        .try_apply_to_chain_spec::<E>(&eth2_config.spec)
+        .map_err(|e| format!("Invalid beacon node config: {}", e))
+}
+
 /// Request the version from the node, looping back and trying again on failure. Exit once the node
 /// has been contacted.
 async fn wait_for_node<E: EthSpec>(
@@ -308,3 +388,154 @@ async fn wait_for_node<E: EthSpec>(
         }
     }
 }
+
+/// Request the genesis time from the node, waiting until genesis has occurred before returning
+/// it.
+///
+/// The beacon node cannot serve the genesis time until it knows the genesis state, so failed
+/// requests are retried every `poll_interval`. Whilst waiting for the genesis instant, the time
+/// remaining is logged every `poll_interval`.
+///
+/// If the validator client starts before genesis, it will get errors from the slot clock.
+async fn wait_for_genesis<E: EthSpec>(
+    beacon_node: &RemoteBeaconNode<E>,
+    poll_interval: Duration,
+    log: &Logger,
+) -> Result<u64, String> {
+    let genesis_time = loop {
+        match beacon_node.http.beacon().get_genesis_time().await {
+            Ok(genesis_time) => break genesis_time,
+            Err(e) => {
+                warn!(
+                    log,
+                    "Waiting for beacon node genesis";
+                    "msg" => "the beacon node may still be waiting for the genesis state",
+                    "error" => format!("{:?}", e),
+                );
+                delay_for(poll_interval).await;
+            }
+        }
+    };
+    let genesis = Duration::from_secs(genesis_time);
+
+    let now = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))
+    };
+
+    let now_at_start = now()?;
+    if now_at_start >= genesis {
+        info!(
+            log,
+            "Genesis has already occurred";
+            "seconds_ago" => (now_at_start - genesis).as_secs()
+        );
+        return Ok(genesis_time);
+    }
+
+    loop {
+        let current_time = now()?;
+        if current_time >= genesis {
+            break;
+        }
+
+        let time_to_genesis = genesis - current_time;
+        info!(
+            log,
+            "Waiting for genesis";
+            "seconds_to_wait" => time_to_genesis.as_secs(),
+        );
+
+        delay_for(std::cmp::min(time_to_genesis, poll_interval)).await;
+    }
+
+    info!(log, "Genesis has occurred");
+
+    Ok(genesis_time)
+}
+
+/// Poll the sync status of the node every `poll_interval`, returning once it is synced (or close
+/// enough to the head to be used).
+///
+/// Waiting can be skipped with `--allow-unsynced`, which is only recommended for testnets.
+async fn wait_for_sync<T: SlotClock, E: EthSpec>(
+    beacon_node: &RemoteBeaconNode<E>,
+    slot_clock: &T,
+    poll_interval: Duration,
+    log: &Logger,
+) {
+    while !is_synced(beacon_node, slot_clock, None).await {
+        let status = beacon_node
+            .http
+            .node()
+            .syncing_status()
+            .await
+            .map(|resp| resp.sync_status);
+
+        match status {
+            Ok(status) => info!(
+                log,
+                "Waiting for beacon node to sync";
+                "current_slot" => status.current_slot.as_u64(),
+                "target_slot" => status.highest_slot.as_u64(),
+                "slots_remaining" => status.highest_slot.as_u64().saturating_sub(status.current_slot.as_u64()),
+            ),
+            Err(e) => warn!(
+                log,
+                "Waiting for beacon node to sync";
+                "error" => format!("{:?}", e),
+            ),
+        }
+
+        delay_for(poll_interval).await;
+    }
+
+    info!(log, "Beacon node is synced");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{MainnetEthSpec, MinimalEthSpec, YamlConfig};
+
+    fn spec_config<E: EthSpec>(config_name: &str, spec: &ChainSpec) -> SpecConfig {
+        SpecConfig {
+            config_name: config_name.to_string(),
+            yaml_config: YamlConfig::from_spec::<E>(spec),
+        }
+    }
+
+    #[test]
+    fn applies_matching_spec() {
+        let eth2_config = Eth2Config::minimal();
+        let mut spec = eth2_config.spec.clone();
+        spec.min_genesis_time = 42;
+        spec.genesis_fork_version = [1, 2, 3, 4];
+
+        let config = spec_config::<MinimalEthSpec>("minimal", &spec);
+        assert_eq!(
+            beacon_node_spec::<MinimalEthSpec>(&eth2_config, &config),
+            Ok(spec)
+        );
+    }
+
+    #[test]
+    fn rejects_other_spec_constants() {
+        let eth2_config = Eth2Config::minimal();
+
+        let config = spec_config::<MinimalEthSpec>("mainnet", &eth2_config.spec);
+        assert!(beacon_node_spec::<MinimalEthSpec>(&eth2_config, &config).is_err());
+    }
+
+    #[test]
+    fn rejects_other_preset_values() {
+        let eth2_config = Eth2Config::minimal();
+
+        // A config named `minimal`, but with the preset values of mainnet.
+        let config = spec_config::<MainnetEthSpec>("minimal", &ChainSpec::mainnet());
+        let error = beacon_node_spec::<MinimalEthSpec>(&eth2_config, &config)
+            .expect_err("should reject mainnet preset values");
+        assert!(error.contains("SLOTS_PER_EPOCH"), "{}", error);
+    }
+}
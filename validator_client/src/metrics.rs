@@ -0,0 +1,118 @@
+use remote_beacon_node::Error as BeaconNodeError;
+use types::Slot;
+
+pub use lighthouse_metrics::*;
+
+/// The `duty` label for attestations.
+pub const ATTESTATION: &str = "attestation";
+/// The `duty` label for aggregates.
+pub const AGGREGATE: &str = "aggregate";
+/// The `duty` label for block proposals.
+pub const BLOCK: &str = "block";
+
+/// The `type` label for selection proof signatures.
+pub const SELECTION_PROOF: &str = "selection_proof";
+/// The `type` label for randao reveal signatures.
+pub const RANDAO_REVEAL: &str = "randao_reveal";
+
+/*
+ * The `endpoint` labels for beacon node requests.
+ */
+pub const PRODUCE_ATTESTATION: &str = "produce_attestation";
+pub const PUBLISH_ATTESTATIONS: &str = "publish_attestations";
+pub const PRODUCE_AGGREGATE: &str = "produce_aggregate";
+pub const PUBLISH_AGGREGATES: &str = "publish_aggregates";
+pub const PRODUCE_BLOCK: &str = "produce_block";
+pub const PUBLISH_BLOCK: &str = "publish_block";
+pub const GET_DUTIES: &str = "get_duties";
+pub const SUBSCRIBE: &str = "subscribe";
+
+lazy_static! {
+    pub static ref MISSED_ATTESTATIONS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_missed_attestations_total",
+        "Count of attestation duties for which no valid attestation was published",
+        &["reason"]
+    );
+    pub static ref FAILED_AGGREGATIONS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_failed_aggregations_total",
+        "Count of aggregation duties for which no valid aggregate was published",
+        &["reason"]
+    );
+    pub static ref FAILED_BLOCK_PROPOSALS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_failed_block_proposals_total",
+        "Count of block proposal duties for which no valid block was published",
+        &["reason"]
+    );
+    pub static ref LAST_FAILED_DUTY_SLOT: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "vc_last_failed_duty_slot",
+        "The slot of the most recent duty of each type which failed",
+        &["duty"]
+    );
+    pub static ref SUCCESSFUL_DUTIES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_successful_duties_total",
+        "Count of duties of each type which were published and accepted by the beacon node",
+        &["duty"]
+    );
+
+    /*
+     * Beacon node requests
+     */
+    pub static ref BEACON_NODE_REQUEST_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_beacon_node_request_seconds",
+        "Time taken by the beacon node to respond to each type of request, including failures",
+        &["endpoint"]
+    );
+
+    /*
+     * Signing
+     */
+    pub static ref SIGNING_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_signing_seconds",
+        "Time taken to sign each type of message, excluding slashing protection checks",
+        &["type"]
+    );
+    pub static ref SLASHING_PROTECTION_CHECK_TIMES: Result<HistogramVec> =
+        try_create_histogram_vec(
+            "vc_slashing_protection_check_seconds",
+            "Time taken to check and record each type of message in the slashing protection \
+             database",
+            &["type"]
+        );
+}
+
+/// Records that `count` duties of type `duty` were accepted by the beacon node.
+pub fn record_successful_duty(duty: &str, count: usize) {
+    if let Some(counter) = get_int_counter(&SUCCESSFUL_DUTIES_TOTAL, &[duty]) {
+        counter.inc_by(count as i64);
+    }
+}
+
+/// Records that `count` duties of type `duty` for `slot` failed for `reason`.
+///
+/// The slot is recorded in a gauge rather than as a label, to avoid creating a new time series
+/// for every slot.
+pub fn record_failed_duty(
+    counter: &Result<IntCounterVec>,
+    duty: &str,
+    reason: &str,
+    slot: Slot,
+    count: usize,
+) {
+    if count == 0 {
+        return;
+    }
+
+    if let Some(counter) = get_int_counter(counter, &[reason]) {
+        counter.inc_by(count as i64);
+    }
+    set_int_gauge(&LAST_FAILED_DUTY_SLOT, &[duty], slot.as_u64() as i64);
+}
+
+/// Returns the `reason` label for a duty which failed due to an error from the beacon node.
+pub fn beacon_node_error_reason(e: &BeaconNodeError) -> &'static str {
+    if e.is_timeout() {
+        "beacon_node_timeout"
+    } else {
+        "beacon_node_error"
+    }
+}
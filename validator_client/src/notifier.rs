@@ -1,6 +1,6 @@
 use crate::{is_synced::is_synced, ProductionValidatorClient};
 use futures::StreamExt;
-use slog::{error, info};
+use slog::{error, info, warn};
 use slot_clock::SlotClock;
 use tokio::time::{interval_at, Duration, Instant};
 use types::EthSpec;
@@ -75,6 +75,72 @@ pub fn spawn_notifier<T: EthSpec>(client: &ProductionValidatorClient<T>) -> Resu
                         "slot" => format!("{}", slot),
                     );
                 }
+
+                // Summarise the aggregation duties of the previous epoch at the start of each
+                // epoch.
+                if slot % T::slots_per_epoch() == 0 && epoch > 0 {
+                    let previous_epoch = epoch - 1;
+                    if let Some(stats) = duties_service.aggregation_stats.get(previous_epoch) {
+                        let mean_selection_proof_ms = stats
+                            .mean_selection_proof_time()
+                            .map_or(0, |time| time.as_millis());
+                        let max_selection_proof_ms = stats.max_selection_proof_time.as_millis();
+
+                        if stats.missed > 0 {
+                            warn!(
+                                log,
+                                "Missed aggregation duties";
+                                "missed" => stats.missed,
+                                "published" => stats.published,
+                                "duties" => stats.duties(),
+                                "mean_selection_proof_ms" => mean_selection_proof_ms,
+                                "max_selection_proof_ms" => max_selection_proof_ms,
+                                "epoch" => format!("{}", previous_epoch),
+                            );
+                        } else if stats.duties() > 0 {
+                            info!(
+                                log,
+                                "Aggregation duties complete";
+                                "published" => stats.published,
+                                "mean_selection_proof_ms" => mean_selection_proof_ms,
+                                "max_selection_proof_ms" => max_selection_proof_ms,
+                                "epoch" => format!("{}", previous_epoch),
+                            );
+                        }
+                    }
+                }
+
+                // Summarise the inclusion of the attestations from two epochs ago, since those
+                // can no longer be included once this epoch has started.
+                if slot % T::slots_per_epoch() == 0 && epoch > 1 {
+                    let prior_epoch = epoch - 2;
+                    if let Some(stats) = duties_service.attestation_inclusion.get(prior_epoch) {
+                        let mean_inclusion_distance = stats
+                            .mean_inclusion_distance()
+                            .map_or("n/a".to_string(), |distance| format!("{:.2}", distance));
+
+                        if stats.missed > 0 {
+                            warn!(
+                                log,
+                                "Attestations not included";
+                                "missed" => stats.missed,
+                                "included" => stats.included,
+                                "mean_inclusion_distance" => mean_inclusion_distance,
+                                "max_inclusion_distance" => stats.max_inclusion_distance,
+                                "epoch" => format!("{}", prior_epoch),
+                            );
+                        } else if stats.attestations() > 0 {
+                            info!(
+                                log,
+                                "All attestations included";
+                                "included" => stats.included,
+                                "mean_inclusion_distance" => mean_inclusion_distance,
+                                "max_inclusion_distance" => stats.max_inclusion_distance,
+                                "epoch" => format!("{}", prior_epoch),
+                            );
+                        }
+                    }
+                }
             } else {
                 error!(log, "Unable to read slot clock");
             }
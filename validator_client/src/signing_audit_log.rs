@@ -0,0 +1,254 @@
+//! An append-only log of the blocks and attestations signed by the validator client.
+//!
+//! Each entry is written as a single line of JSON. When the log reaches its maximum size it is
+//! rotated, retaining at most `ROTATED_FILES` previous files (e.g., `signing_audit_log.jsonl.1`
+//! is the most recently rotated).
+
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use types::{Epoch, Hash256, PublicKey, Slot};
+
+/// Path to the signing audit log within the datadir.
+pub const SIGNING_AUDIT_LOG_FILENAME: &str = "signing_audit_log.jsonl";
+/// The number of rotated log files which are retained.
+pub const ROTATED_FILES: usize = 4;
+
+/// The kind of message which was signed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignedMessageKind {
+    Block,
+    Attestation,
+    AggregateAndProof,
+}
+
+/// A single entry in the signing audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningAuditEntry {
+    /// Seconds since the UNIX epoch at which the message was signed.
+    pub timestamp: u64,
+    pub kind: SignedMessageKind,
+    pub pubkey: PublicKey,
+    pub slot: Slot,
+    /// The epoch of the message. For attestations this is the target epoch.
+    pub epoch: Epoch,
+    pub signing_root: Hash256,
+}
+
+struct Inner {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+}
+
+/// A handle to the signing audit log, which may be cloned and shared between threads.
+#[derive(Clone)]
+pub struct SigningAuditLog {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl SigningAuditLog {
+    /// Opens the log at `path`, creating it if it does not exist. The log is rotated before it
+    /// would exceed `max_size` bytes.
+    pub fn open(path: PathBuf, max_size: u64) -> Result<Self, String> {
+        let file = open_append(&path)?;
+        let size = file
+            .metadata()
+            .map_err(|e| format!("Unable to read metadata of {:?}: {:?}", path, e))?
+            .len();
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Inner {
+                path,
+                file,
+                size,
+                max_size,
+            })),
+        })
+    }
+
+    /// Appends `entry` to the log, rotating it first if required.
+    pub fn record(&self, entry: &SigningAuditEntry) -> Result<(), String> {
+        let mut line = serde_json::to_vec(entry)
+            .map_err(|e| format!("Unable to encode signing audit entry: {:?}", e))?;
+        line.push(b'\n');
+
+        let mut inner = self.inner.lock();
+
+        if inner.size > 0 && inner.size + line.len() as u64 > inner.max_size {
+            inner.rotate()?;
+        }
+
+        inner
+            .file
+            .write_all(&line)
+            .map_err(|e| format!("Unable to write to {:?}: {:?}", inner.path, e))?;
+        inner.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    /// Returns at most `limit` entries, skipping the first `offset` entries. Entries are ordered
+    /// from oldest to newest, starting with the oldest retained rotated file.
+    ///
+    /// Offsets refer to different entries after the log is rotated.
+    pub fn read(&self, offset: usize, limit: usize) -> Result<Vec<SigningAuditEntry>, String> {
+        // Hold the lock so the files are not rotated whilst they are read.
+        let inner = self.inner.lock();
+
+        let paths = (1..=ROTATED_FILES)
+            .rev()
+            .map(|i| rotated_path(&inner.path, i))
+            .chain(std::iter::once(inner.path.clone()))
+            .filter(|path| path.exists());
+
+        let mut entries = vec![];
+        let mut skipped = 0;
+
+        for path in paths {
+            let file =
+                File::open(&path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+
+            for line in BufReader::new(file).lines() {
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+
+                let line = line.map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+
+                if skipped < offset {
+                    skipped += 1;
+                    continue;
+                }
+
+                let entry = serde_json::from_str(&line)
+                    .map_err(|e| format!("Invalid entry in {:?}: {:?}", path, e))?;
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Inner {
+    /// Shifts each rotated file along by one, discarding the oldest, then moves the current log
+    /// to the first rotated file and starts a new one.
+    fn rotate(&mut self) -> Result<(), String> {
+        for i in (1..ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, i);
+            if from.exists() {
+                let to = rotated_path(&self.path, i + 1);
+                fs::rename(&from, &to)
+                    .map_err(|e| format!("Unable to rename {:?}: {:?}", from, e))?;
+            }
+        }
+
+        let to = rotated_path(&self.path, 1);
+        fs::rename(&self.path, &to)
+            .map_err(|e| format!("Unable to rename {:?}: {:?}", self.path, e))?;
+
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))
+}
+
+/// Returns the path of the `i`th rotated file, e.g. `signing_audit_log.jsonl.1`.
+fn rotated_path(path: &Path, i: usize) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{}", i));
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+    use types::Keypair;
+
+    fn entry(slot: u64) -> SigningAuditEntry {
+        SigningAuditEntry {
+            timestamp: 42,
+            kind: SignedMessageKind::Attestation,
+            pubkey: Keypair::random().pk,
+            slot: Slot::new(slot),
+            epoch: Epoch::new(slot / 32),
+            signing_root: Hash256::from_low_u64_be(slot),
+        }
+    }
+
+    /// The length of a line for an entry with a two-digit slot.
+    fn line_len() -> u64 {
+        serde_json::to_vec(&entry(10)).unwrap().len() as u64 + 1
+    }
+
+    #[test]
+    fn record_and_read() {
+        let dir = TempDir::new("signing_audit_log").unwrap();
+        let log =
+            SigningAuditLog::open(dir.path().join(SIGNING_AUDIT_LOG_FILENAME), 1 << 20).unwrap();
+
+        let entries = (0..10).map(entry).collect::<Vec<_>>();
+        for entry in &entries {
+            log.record(entry).unwrap();
+        }
+
+        assert_eq!(log.read(0, 100).unwrap(), entries);
+        assert_eq!(log.read(3, 4).unwrap(), entries[3..7].to_vec());
+        assert_eq!(log.read(10, 4).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn reopen_appends() {
+        let dir = TempDir::new("signing_audit_log").unwrap();
+        let path = dir.path().join(SIGNING_AUDIT_LOG_FILENAME);
+
+        let first = entry(1);
+        let second = entry(2);
+
+        SigningAuditLog::open(path.clone(), 1 << 20)
+            .unwrap()
+            .record(&first)
+            .unwrap();
+        let log = SigningAuditLog::open(path, 1 << 20).unwrap();
+        log.record(&second).unwrap();
+
+        assert_eq!(log.read(0, 100).unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn rotation() {
+        let dir = TempDir::new("signing_audit_log").unwrap();
+        let path = dir.path().join(SIGNING_AUDIT_LOG_FILENAME);
+        // Allow two entries per file.
+        let log = SigningAuditLog::open(path.clone(), line_len() * 2).unwrap();
+
+        let entries = (10..10 + (ROTATED_FILES as u64 + 2) * 2)
+            .map(entry)
+            .collect::<Vec<_>>();
+        for entry in &entries {
+            log.record(entry).unwrap();
+        }
+
+        assert!(rotated_path(&path, ROTATED_FILES).exists());
+        assert!(!rotated_path(&path, ROTATED_FILES + 1).exists());
+
+        // The oldest file has been discarded.
+        assert_eq!(log.read(0, 100).unwrap(), entries[2..].to_vec());
+    }
+}
@@ -0,0 +1,373 @@
+//! Schedules the work performed at a fixed point within each slot (e.g., attesting 1/3 of the way
+//! through the slot) by reading the slot clock, rather than by counting the ticks of an interval.
+//!
+//! After a long pause (e.g., due to GC or the machine being suspended) or a jump of the system
+//! clock, an interval would fire for each missed tick in quick succession. Instead, a
+//! `SlotPipeline` reports the slots whose work was missed, so that they can be logged and
+//! skipped, and only runs the work for the present slot if it is not too late to be useful.
+
+use slot_clock::SlotClock;
+use std::time::Duration;
+use tokio::time::delay_for;
+use types::Slot;
+
+/// The point within a slot at which some duty is performed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotPhase {
+    /// The start of the slot, when blocks are proposed.
+    BlockProposal,
+    /// 1/3 of the way through the slot, when attestations are produced.
+    Attestation,
+    /// 2/3 of the way through the slot, when aggregates are produced.
+    Aggregation,
+}
+
+impl SlotPhase {
+    /// Returns the duration from the start of a slot until this phase begins.
+    pub fn offset(self, slot_duration: Duration) -> Duration {
+        match self {
+            SlotPhase::BlockProposal => Duration::from_secs(0),
+            SlotPhase::Attestation => slot_duration / 3,
+            SlotPhase::Aggregation => slot_duration * 2 / 3,
+        }
+    }
+
+    /// Returns the duration after this phase begins for which its work is still useful. Work which
+    /// would start any later is stale and should be skipped.
+    ///
+    /// A late block is better than a missed one, so blocks may be proposed at any point in the
+    /// slot.
+    pub fn tolerance(self, slot_duration: Duration) -> Duration {
+        match self {
+            SlotPhase::BlockProposal => slot_duration,
+            SlotPhase::Attestation | SlotPhase::Aggregation => slot_duration / 3,
+        }
+    }
+
+    /// Returns the duration from now until this phase of `slot` begins, or zero if it has already
+    /// begun.
+    pub fn duration_until<T: SlotClock>(self, slot_clock: &T, slot: Slot) -> Option<Duration> {
+        let start = slot_clock.start_of(slot)? + self.offset(slot_clock.slot_duration());
+        Some(
+            start
+                .checked_sub(slot_clock.now_duration()?)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns `true` if it is now too late to begin the work of this phase of `slot`.
+    pub fn is_stale<T: SlotClock>(self, slot_clock: &T, slot: Slot) -> bool {
+        let slot_duration = slot_clock.slot_duration();
+
+        match (slot_clock.start_of(slot), slot_clock.now_duration()) {
+            (Some(start), Some(now)) => {
+                now > start + self.offset(slot_duration) + self.tolerance(slot_duration)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// The outcome of a phase of a slot being reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotTick {
+    /// The slot whose work should be performed now, or `None` if the work for the present slot is
+    /// stale.
+    pub slot: Option<Slot>,
+    /// The slots whose work was missed (in ascending order), including the present slot if its
+    /// work is stale.
+    pub skipped: Vec<Slot>,
+}
+
+impl SlotTick {
+    /// Returns the present slot, whether its work should be performed or is stale.
+    pub fn present_slot(&self) -> Option<Slot> {
+        self.slot.or_else(|| self.skipped.last().copied())
+    }
+}
+
+/// Produces a `SlotTick` each time a `SlotPhase` of a slot is reached, according to a slot clock.
+pub struct SlotPipeline {
+    phase: SlotPhase,
+    /// The latest slot which has been run or skipped.
+    last_slot: Option<Slot>,
+}
+
+impl SlotPipeline {
+    pub fn new(phase: SlotPhase) -> Self {
+        Self {
+            phase,
+            last_slot: None,
+        }
+    }
+
+    /// Records that the work for `slot` has been performed outside of the pipeline (e.g.,
+    /// immediately at startup), so that no tick is produced for it or any earlier slot.
+    pub fn mark_run(&mut self, slot: Slot) {
+        self.last_slot = Some(
+            self.last_slot
+                .map_or(slot, |last| std::cmp::max(last, slot)),
+        );
+    }
+
+    /// Waits until the phase of the next slot is reached.
+    pub async fn next<T: SlotClock>(&mut self, slot_clock: &T) -> SlotTick {
+        loop {
+            let delay = self
+                .duration_to_next_tick(slot_clock)
+                .unwrap_or_else(|| slot_clock.slot_duration());
+            delay_for(delay).await;
+
+            if let Some(tick) = self.poll(slot_clock) {
+                return tick;
+            }
+        }
+    }
+
+    /// Returns the duration from now until the phase of the next slot to be run is reached, or
+    /// zero if it has already been reached.
+    ///
+    /// Returns `None` if the slot clock cannot be read.
+    pub fn duration_to_next_tick<T: SlotClock>(&self, slot_clock: &T) -> Option<Duration> {
+        let now = slot_clock.now_duration()?;
+        let next_slot = match (slot_clock.slot_of(now), self.last_slot) {
+            (Some(slot), Some(last)) if slot <= last => last + 1,
+            (Some(slot), _) => slot,
+            // Prior to genesis.
+            (None, _) => slot_clock.genesis_slot(),
+        };
+
+        let tick = slot_clock.start_of(next_slot)? + self.phase.offset(slot_clock.slot_duration());
+
+        Some(tick.checked_sub(now).unwrap_or_default())
+    }
+
+    /// Returns a tick if the phase of a slot which has not yet been run or skipped has been
+    /// reached.
+    ///
+    /// Returns `None` if the phase of the present slot has not been reached, or the present slot
+    /// has already been run or skipped (e.g., because the clock went backwards).
+    pub fn poll<T: SlotClock>(&mut self, slot_clock: &T) -> Option<SlotTick> {
+        let now = slot_clock.now_duration()?;
+        let slot = slot_clock.slot_of(now)?;
+
+        if self.last_slot.map_or(false, |last| slot <= last) {
+            return None;
+        }
+
+        let slot_duration = slot_clock.slot_duration();
+        let offset_in_slot = now.checked_sub(slot_clock.start_of(slot)?)?;
+        let phase_offset = self.phase.offset(slot_duration);
+
+        if offset_in_slot < phase_offset {
+            return None;
+        }
+
+        let first_unseen = self.last_slot.map_or(slot, |last| last + 1);
+        let mut skipped: Vec<Slot> = (first_unseen.as_u64()..slot.as_u64())
+            .map(Slot::new)
+            .collect();
+
+        self.last_slot = Some(slot);
+
+        if offset_in_slot > phase_offset + self.phase.tolerance(slot_duration) {
+            skipped.push(slot);
+            Some(SlotTick {
+                slot: None,
+                skipped,
+            })
+        } else {
+            Some(SlotTick {
+                slot: Some(slot),
+                skipped,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::TestingSlotClock;
+
+    const SLOT_MILLIS: u64 = 12_000;
+
+    fn slot_clock() -> TestingSlotClock {
+        TestingSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(100),
+            Duration::from_millis(SLOT_MILLIS),
+        )
+    }
+
+    /// Sets the time to `millis` after the start of `slot`.
+    fn set_time(slot_clock: &TestingSlotClock, slot: u64, millis: u64) {
+        slot_clock.set_current_time(
+            Duration::from_secs(100) + Duration::from_millis(slot * SLOT_MILLIS + millis),
+        );
+    }
+
+    fn run(slot: u64) -> Option<SlotTick> {
+        Some(SlotTick {
+            slot: Some(Slot::new(slot)),
+            skipped: vec![],
+        })
+    }
+
+    #[test]
+    fn runs_each_slot_once() {
+        let clock = slot_clock();
+        let mut pipeline = SlotPipeline::new(SlotPhase::Attestation);
+
+        set_time(&clock, 0, 3_999);
+        assert_eq!(pipeline.poll(&clock), None, "phase not reached");
+        assert_eq!(
+            pipeline.duration_to_next_tick(&clock),
+            Some(Duration::from_millis(1))
+        );
+
+        set_time(&clock, 0, 4_000);
+        assert_eq!(pipeline.poll(&clock), run(0));
+        assert_eq!(pipeline.poll(&clock), None, "slot already run");
+        assert_eq!(
+            pipeline.duration_to_next_tick(&clock),
+            Some(Duration::from_millis(SLOT_MILLIS))
+        );
+
+        set_time(&clock, 1, 5_000);
+        assert_eq!(pipeline.poll(&clock), run(1));
+    }
+
+    #[test]
+    fn does_not_rerun_marked_slots() {
+        let clock = slot_clock();
+        let mut pipeline = SlotPipeline::new(SlotPhase::BlockProposal);
+
+        set_time(&clock, 2, 1_000);
+        pipeline.mark_run(Slot::new(2));
+        assert_eq!(pipeline.poll(&clock), None, "slot already run at startup");
+        assert_eq!(
+            pipeline.duration_to_next_tick(&clock),
+            Some(Duration::from_millis(SLOT_MILLIS - 1_000))
+        );
+
+        pipeline.mark_run(Slot::new(1));
+        set_time(&clock, 3, 0);
+        assert_eq!(pipeline.poll(&clock), run(3), "earlier slots do not rewind");
+    }
+
+    #[test]
+    fn present_slot() {
+        assert_eq!(run(3).unwrap().present_slot(), Some(Slot::new(3)));
+        assert_eq!(
+            SlotTick {
+                slot: None,
+                skipped: vec![Slot::new(3), Slot::new(4)],
+            }
+            .present_slot(),
+            Some(Slot::new(4))
+        );
+    }
+
+    #[test]
+    fn waits_for_genesis() {
+        let clock = slot_clock();
+        let pipeline = SlotPipeline::new(SlotPhase::Attestation);
+
+        clock.set_current_time(Duration::from_secs(90));
+        assert_eq!(
+            pipeline.duration_to_next_tick(&clock),
+            Some(Duration::from_secs(14))
+        );
+    }
+
+    #[test]
+    fn skips_stale_work() {
+        let clock = slot_clock();
+        let mut pipeline = SlotPipeline::new(SlotPhase::Attestation);
+
+        set_time(&clock, 0, 4_000);
+        assert_eq!(pipeline.poll(&clock), run(0));
+
+        // A pause which ends after the attestation phase of slot 1 has passed.
+        set_time(&clock, 1, 8_001);
+        assert_eq!(
+            pipeline.poll(&clock),
+            Some(SlotTick {
+                slot: None,
+                skipped: vec![Slot::new(1)],
+            })
+        );
+
+        set_time(&clock, 2, 8_000);
+        assert_eq!(pipeline.poll(&clock), run(2), "within tolerance");
+    }
+
+    #[test]
+    fn skips_missed_slots_after_clock_jump() {
+        let clock = slot_clock();
+        let mut pipeline = SlotPipeline::new(SlotPhase::BlockProposal);
+
+        set_time(&clock, 0, 0);
+        assert_eq!(pipeline.poll(&clock), run(0));
+
+        // The clock jumps forward to late in slot 4.
+        set_time(&clock, 4, 6_000);
+        assert_eq!(
+            pipeline.poll(&clock),
+            Some(SlotTick {
+                slot: Some(Slot::new(4)),
+                skipped: vec![Slot::new(1), Slot::new(2), Slot::new(3)],
+            })
+        );
+
+        // The clock jumps backwards, the skipped slots are not revisited.
+        set_time(&clock, 2, 0);
+        assert_eq!(pipeline.poll(&clock), None);
+
+        set_time(&clock, 5, 100);
+        assert_eq!(pipeline.poll(&clock), run(5));
+    }
+
+    #[test]
+    fn stale_phases() {
+        let clock = slot_clock();
+        let slot = Slot::new(3);
+
+        set_time(&clock, 3, 4_000);
+        assert!(!SlotPhase::BlockProposal.is_stale(&clock, slot));
+        assert!(!SlotPhase::Aggregation.is_stale(&clock, slot));
+        assert_eq!(
+            SlotPhase::Aggregation.duration_until(&clock, slot),
+            Some(Duration::from_millis(4_000))
+        );
+
+        set_time(&clock, 3, 4_001);
+        assert!(!SlotPhase::BlockProposal.is_stale(&clock, slot));
+        assert_eq!(
+            SlotPhase::Attestation.duration_until(&clock, slot),
+            Some(Duration::from_secs(0))
+        );
+
+        set_time(&clock, 4, 0);
+        assert!(!SlotPhase::BlockProposal.is_stale(&clock, slot));
+        assert!(!SlotPhase::Aggregation.is_stale(&clock, slot));
+        set_time(&clock, 4, 1);
+        assert!(SlotPhase::BlockProposal.is_stale(&clock, slot));
+        assert!(SlotPhase::Aggregation.is_stale(&clock, slot));
+    }
+
+    #[test]
+    fn proposes_late_in_slot() {
+        let clock = slot_clock();
+        let mut pipeline = SlotPipeline::new(SlotPhase::BlockProposal);
+
+        // E.g., the validator client was started part way through the slot.
+        set_time(&clock, 3, 5_000);
+        assert!(!SlotPhase::BlockProposal.is_stale(&clock, Slot::new(3)));
+        assert_eq!(pipeline.poll(&clock), run(3));
+
+        set_time(&clock, 4, SLOT_MILLIS - 1);
+        assert_eq!(pipeline.poll(&clock), run(4));
+    }
+}
@@ -1,14 +1,19 @@
 use crate::{
-    config::{Config, SLASHING_PROTECTION_FILENAME},
+    config::Config,
     fork_service::ForkService,
     initialized_validators::InitializedValidators,
+    metrics,
+    signing_audit_log::{
+        SignedMessageKind, SigningAuditEntry, SigningAuditLog, SIGNING_AUDIT_LOG_FILENAME,
+    },
 };
 use parking_lot::RwLock;
-use slashing_protection::{NotSafe, Safe, SlashingDatabase};
-use slog::{crit, error, warn, Logger};
+use slashing_protection::{NotSafe, Safe, SlashingProtection};
+use slog::{crit, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tempdir::TempDir;
 use types::{
     Attestation, BeaconBlock, ChainSpec, Domain, Epoch, EthSpec, Fork, Hash256, Keypair, PublicKey,
@@ -44,7 +49,8 @@ impl PartialEq for LocalValidator {
 #[derive(Clone)]
 pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<InitializedValidators>>,
-    slashing_protection: SlashingDatabase,
+    slashing_protection: SlashingProtection,
+    signing_audit_log: Option<SigningAuditLog>,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
     log: Logger,
@@ -56,24 +62,26 @@ pub struct ValidatorStore<T, E: EthSpec> {
 impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     pub fn new(
         validators: InitializedValidators,
+        slashing_protection: SlashingProtection,
         config: &Config,
         genesis_validators_root: Hash256,
         spec: ChainSpec,
         fork_service: ForkService<T, E>,
         log: Logger,
     ) -> Result<Self, String> {
-        let slashing_db_path = config.data_dir.join(SLASHING_PROTECTION_FILENAME);
-        let slashing_protection =
-            SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
-                format!(
-                    "Failed to open or create slashing protection database: {:?}",
-                    e
-                )
-            })?;
+        let signing_audit_log = if config.signing_audit_log {
+            let path = config.data_dir.join(SIGNING_AUDIT_LOG_FILENAME);
+            let audit_log = SigningAuditLog::open(path, config.signing_audit_log_max_size)
+                .map_err(|e| format!("Failed to open signing audit log: {}", e))?;
+            Some(audit_log)
+        } else {
+            None
+        };
 
         Ok(Self {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
+            signing_audit_log,
             genesis_validators_root,
             spec: Arc::new(spec),
             log,
@@ -88,9 +96,33 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Registration is required to protect against a lost or missing slashing database,
     /// such as when relocating validator keys to a new machine.
     pub fn register_all_validators_for_slashing_protection(&self) -> Result<(), String> {
-        self.slashing_protection
-            .register_validators(self.validators.read().iter_voting_pubkeys())
-            .map_err(|e| format!("Error while registering validators: {:?}", e))
+        let validators = self.validators.read();
+        let pubkeys = validators.iter_voting_pubkeys().collect::<Vec<_>>();
+
+        let num_registered = self
+            .slashing_protection
+            .register_validators_batch(&pubkeys, |done, total| {
+                if done < total {
+                    info!(
+                        self.log,
+                        "Registering validators for slashing protection";
+                        "done" => done,
+                        "total" => total,
+                    );
+                }
+            })
+            .map_err(|e| format!("Error while registering validators: {:?}", e))?;
+
+        if num_registered > 0 {
+            info!(
+                self.log,
+                "Registered validators for slashing protection";
+                "new_validators" => num_registered,
+                "total" => pubkeys.len(),
+            );
+        }
+
+        Ok(())
     }
 
     pub fn voting_pubkeys(&self) -> Vec<PublicKey> {
@@ -105,6 +137,49 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().num_enabled()
     }
 
+    /// Returns the signing audit log, if it is enabled.
+    pub fn signing_audit_log(&self) -> Option<SigningAuditLog> {
+        self.signing_audit_log.clone()
+    }
+
+    /// Records the signing of `message` in the signing audit log, if it is enabled.
+    ///
+    /// Failing to write to the log is not fatal, since it would cause the validator to miss its
+    /// duties.
+    fn record_signing<M: SignedRoot>(
+        &self,
+        kind: SignedMessageKind,
+        validator_pubkey: &PublicKey,
+        slot: Slot,
+        epoch: Epoch,
+        message: &M,
+        domain: Hash256,
+    ) {
+        if let Some(audit_log) = &self.signing_audit_log {
+            let entry = SigningAuditEntry {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or(0),
+                kind,
+                pubkey: validator_pubkey.clone(),
+                slot,
+                epoch,
+                signing_root: message.signing_root(domain),
+            };
+
+            if let Err(e) = audit_log.record(&entry) {
+                error!(
+                    self.log,
+                    "Unable to write to signing audit log";
+                    "kind" => format!("{:?}", kind),
+                    "slot" => slot.as_u64(),
+                    "error" => e,
+                );
+            }
+        }
+    }
+
     fn fork(&self) -> Option<Fork> {
         if self.fork_service.fork().is_none() {
             error!(
@@ -129,6 +204,8 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 );
                 let message = epoch.signing_root(domain);
 
+                let _timer =
+                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::RANDAO_REVEAL]);
                 Some(voting_keypair.sk.sign(message))
             })
     }
@@ -159,11 +236,14 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             self.genesis_validators_root,
         );
 
+        let timer =
+            metrics::start_timer_vec(&metrics::SLASHING_PROTECTION_CHECK_TIMES, &[metrics::BLOCK]);
         let slashing_status = self.slashing_protection.check_and_insert_block_proposal(
             validator_pubkey,
             &block.block_header(),
             domain,
         );
+        metrics::stop_timer(timer);
 
         match slashing_status {
             // We can safely sign this block.
@@ -171,6 +251,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 let validators = self.validators.read();
                 let voting_keypair = validators.voting_keypair(validator_pubkey)?;
 
+                self.record_signing(
+                    SignedMessageKind::Block,
+                    validator_pubkey,
+                    block.slot,
+                    block.epoch(),
+                    &block,
+                    domain,
+                );
+
+                let _timer = metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::BLOCK]);
                 Some(block.sign(
                     &voting_keypair.sk,
                     &fork,
@@ -194,6 +284,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 );
                 None
             }
+            Err(NotSafe::ExternallyModified(reason)) => {
+                crit!(
+                    self.log,
+                    "Not signing block, slashing protection database modified";
+                    "msg" => "check that the database holds the full signing history and restart",
+                    "reason" => reason
+                );
+                None
+            }
             Err(e) => {
                 crit!(
                     self.log,
@@ -226,11 +325,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             &fork,
             self.genesis_validators_root,
         );
+        let timer = metrics::start_timer_vec(
+            &metrics::SLASHING_PROTECTION_CHECK_TIMES,
+            &[metrics::ATTESTATION],
+        );
         let slashing_status = self.slashing_protection.check_and_insert_attestation(
             validator_pubkey,
             &attestation.data,
             domain,
         );
+        metrics::stop_timer(timer);
 
         match slashing_status {
             // We can safely sign this attestation.
@@ -238,6 +342,8 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 let validators = self.validators.read();
                 let voting_keypair = validators.voting_keypair(validator_pubkey)?;
 
+                let timer =
+                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::ATTESTATION]);
                 attestation
                     .sign(
                         &voting_keypair.sk,
@@ -254,6 +360,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                         )
                     })
                     .ok()?;
+                metrics::stop_timer(timer);
+
+                self.record_signing(
+                    SignedMessageKind::Attestation,
+                    validator_pubkey,
+                    attestation.data.slot,
+                    attestation.data.target.epoch,
+                    &attestation.data,
+                    domain,
+                );
 
                 Some(())
             }
@@ -273,6 +389,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 );
                 None
             }
+            Err(NotSafe::ExternallyModified(reason)) => {
+                crit!(
+                    self.log,
+                    "Not signing attestation, slashing protection database modified";
+                    "msg" => "check that the database holds the full signing history and restart",
+                    "reason" => reason
+                );
+                None
+            }
             Err(e) => {
                 crit!(
                     self.log,
@@ -298,16 +423,41 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     ) -> Option<SignedAggregateAndProof<E>> {
         let validators = self.validators.read();
         let voting_keypair = &validators.voting_keypair(validator_pubkey)?;
+        let fork = self.fork()?;
 
-        Some(SignedAggregateAndProof::from_aggregate(
+        let timer = metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::AGGREGATE]);
+        let signed_aggregate = SignedAggregateAndProof::from_aggregate(
             validator_index,
             aggregate,
             Some(selection_proof),
             &voting_keypair.sk,
-            &self.fork()?,
+            &fork,
             self.genesis_validators_root,
             &self.spec,
-        ))
+        );
+        metrics::stop_timer(timer);
+
+        if self.signing_audit_log.is_some() {
+            let slot = signed_aggregate.message.aggregate.data.slot;
+            let epoch = slot.epoch(E::slots_per_epoch());
+            let domain = self.spec.get_domain(
+                epoch,
+                Domain::AggregateAndProof,
+                &fork,
+                self.genesis_validators_root,
+            );
+
+            self.record_signing(
+                SignedMessageKind::AggregateAndProof,
+                validator_pubkey,
+                slot,
+                epoch,
+                &signed_aggregate.message,
+                domain,
+            );
+        }
+
+        Some(signed_aggregate)
     }
 
     /// Produces a `SelectionProof` for the `slot`, signed by with corresponding secret key to
@@ -319,11 +469,13 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     ) -> Option<SelectionProof> {
         let validators = self.validators.read();
         let voting_keypair = &validators.voting_keypair(validator_pubkey)?;
+        let fork = self.fork()?;
 
+        let _timer = metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::SELECTION_PROOF]);
         Some(SelectionProof::new::<E>(
             slot,
             &voting_keypair.sk,
-            &self.fork()?,
+            &fork,
             self.genesis_validators_root,
             &self.spec,
         ))